@@ -0,0 +1,285 @@
+//! FlowPaste's rules, PII scanner and AI prompts for scripts and pipelines,
+//! without the app running. Installed as `flow-paste-cli`, since `flow-paste`
+//! is the app itself.
+
+use std::env;
+use std::fs;
+use std::io::{self, Read, Write};
+use std::process::ExitCode;
+use thiserror::Error;
+
+use flow_paste_core::ai::{
+    self, AIConfig, AIError, AIProviderType, AiProvider, ChatMessage, OllamaProvider, OpenAIProvider,
+};
+use flow_paste_core::privacy;
+use flow_paste_core::regex::{self, RegexError};
+
+const USAGE: &str = "\
+Usage:
+  flow-paste-cli apply <rule>[,<rule>...] [--stdin | <file>]
+  flow-paste-cli ai <prompt> [--no-shield] [--stdin | <file>]
+  flow-paste-cli scan-pii [--json] [--stdin | <file>]
+  flow-paste-cli rules
+
+Input is read from stdin when no file is given. `ai` uses Ollama unless
+FLOWPASTE_PROVIDER=OpenAI (with OPENAI_API_KEY); FLOWPASTE_MODEL,
+FLOWPASTE_OLLAMA_URL and FLOWPASTE_OPENAI_URL override the defaults.
+PII is masked before text goes to OpenAI unless --no-shield is given.
+`scan-pii` exits with 1 when it finds anything.";
+
+#[derive(Debug, Error)]
+enum CliError {
+    #[error("{0}\n\n{USAGE}")]
+    Usage(String),
+    #[error("failed to read {0}: {1}")]
+    Read(String, io::Error),
+    #[error("failed to write output: {0}")]
+    Write(#[from] io::Error),
+    #[error(transparent)]
+    Rule(#[from] RegexError),
+    #[error(transparent)]
+    Ai(#[from] AIError),
+    #[error("{0}")]
+    Config(String),
+}
+
+#[derive(Debug, PartialEq)]
+enum Input {
+    Stdin,
+    File(String),
+}
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Apply { rule_ids: Vec<String>, input: Input },
+    Ai { prompt: String, shield: bool, input: Input },
+    ScanPii { json: bool, input: Input },
+    Rules,
+    Help,
+}
+
+/// Split `args` into the subcommand's flags and positional arguments.
+fn parse_args(args: &[String]) -> Result<Command, CliError> {
+    let Some((name, rest)) = args.split_first() else {
+        return Ok(Command::Help);
+    };
+    let mut flags: Vec<&str> = Vec::new();
+    let mut positional: Vec<&str> = Vec::new();
+    for arg in rest {
+        if arg.starts_with("--") {
+            flags.push(arg);
+        } else {
+            positional.push(arg);
+        }
+    }
+
+    let allowed: &[&str] = match name.as_str() {
+        "apply" => &["--stdin"],
+        "ai" => &["--stdin", "--no-shield"],
+        "scan-pii" => &["--stdin", "--json"],
+        _ => &[],
+    };
+    if let Some(flag) = flags.iter().find(|f| !allowed.contains(f)) {
+        return Err(CliError::Usage(format!("unknown option '{}' for '{}'", flag, name)));
+    }
+    let stdin = flags.contains(&"--stdin");
+    // What's left after the command's own arguments names the input file
+    let input = |extra: &[&str]| match (extra, stdin) {
+        ([], _) | (["-"], _) => Ok(Input::Stdin),
+        ([file], false) => Ok(Input::File(file.to_string())),
+        ([_], true) => Err(CliError::Usage("give either --stdin or a file, not both".to_string())),
+        _ => Err(CliError::Usage(format!("too many arguments for '{}'", name))),
+    };
+
+    match name.as_str() {
+        "apply" => {
+            let Some((rules, extra)) = positional.split_first() else {
+                return Err(CliError::Usage("apply needs at least one rule id".to_string()));
+            };
+            let rule_ids: Vec<String> = rules
+                .split(',')
+                .map(str::trim)
+                .filter(|id| !id.is_empty())
+                .map(str::to_string)
+                .collect();
+            if rule_ids.is_empty() {
+                return Err(CliError::Usage("apply needs at least one rule id".to_string()));
+            }
+            Ok(Command::Apply { rule_ids, input: input(extra)? })
+        }
+        "ai" => {
+            let Some((prompt, extra)) = positional.split_first().filter(|(p, _)| !p.trim().is_empty()) else {
+                return Err(CliError::Usage("ai needs a prompt".to_string()));
+            };
+            Ok(Command::Ai {
+                prompt: prompt.to_string(),
+                shield: !flags.contains(&"--no-shield"),
+                input: input(extra)?,
+            })
+        }
+        "scan-pii" => Ok(Command::ScanPii {
+            json: flags.contains(&"--json"),
+            input: input(&positional)?,
+        }),
+        "rules" if positional.is_empty() => Ok(Command::Rules),
+        "help" | "-h" => Ok(Command::Help),
+        other => Err(CliError::Usage(format!("unknown command '{}'", other))),
+    }
+}
+
+fn read_input(input: &Input) -> Result<String, CliError> {
+    match input {
+        Input::Stdin => {
+            let mut text = String::new();
+            io::stdin()
+                .read_to_string(&mut text)
+                .map_err(|e| CliError::Read("stdin".to_string(), e))?;
+            Ok(text)
+        }
+        Input::File(path) => fs::read_to_string(path).map_err(|e| CliError::Read(path.clone(), e)),
+    }
+}
+
+/// Provider settings from `FLOWPASTE_*` variables, the same names the app
+/// reads overrides from, with the app's default providers otherwise.
+fn ai_config() -> Result<AIConfig, CliError> {
+    let var = |name: &str| env::var(name).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty());
+    let mut config = AIConfig::default();
+    match var("FLOWPASTE_PROVIDER").as_deref() {
+        None | Some("Ollama") => {
+            if let Some(url) = var("FLOWPASTE_OLLAMA_URL") {
+                config.base_url = url;
+            }
+        }
+        Some("OpenAI") => {
+            config.provider = AIProviderType::OpenAI;
+            config.base_url = var("FLOWPASTE_OPENAI_URL").unwrap_or_else(|| "https://api.openai.com/v1".to_string());
+            config.model = "gpt-4o-mini".to_string();
+            config.api_key = Some(
+                var("OPENAI_API_KEY").ok_or_else(|| CliError::Config("OPENAI_API_KEY is not set".to_string()))?,
+            );
+        }
+        Some(other) => return Err(CliError::Config(format!("unknown provider '{}'", other))),
+    }
+    if let Some(model) = var("FLOWPASTE_MODEL") {
+        config.model = model;
+    }
+    Ok(config)
+}
+
+/// Run `prompt` on `text` like a headless hotkey does, masking PII for cloud
+/// providers unless `shield` is off.
+async fn run_prompt(prompt: &str, text: &str, shield: bool) -> Result<String, CliError> {
+    let config = ai_config()?;
+    let content = format!("{}\n\nContent:\n{}", prompt, text);
+    let masked = (shield && config.provider == AIProviderType::OpenAI).then(|| privacy::mask_pii(&content));
+    if let Some(m) = masked.as_ref().filter(|m| !m.mapping.mappings.is_empty()) {
+        eprintln!("Masked {} PII item(s) before sending", m.mapping.mappings.len());
+    }
+    let message = masked.as_ref().map_or(content, |m| m.masked.clone());
+
+    let provider: Box<dyn AiProvider> = match config.provider {
+        AIProviderType::Ollama => Box::new(OllamaProvider::new()),
+        AIProviderType::OpenAI => Box::new(OpenAIProvider::new()),
+    };
+    let response = ai::complete(provider.as_ref(), vec![ChatMessage::user(message)], &config).await?;
+    Ok(match &masked {
+        Some(m) => privacy::restore_pii(&response, &m.mapping),
+        None => response,
+    })
+}
+
+async fn run(command: Command) -> Result<ExitCode, CliError> {
+    let mut stdout = io::stdout().lock();
+    match command {
+        Command::Apply { rule_ids, input } => {
+            let text = read_input(&input)?;
+            write!(stdout, "{}", regex::apply_pipeline(&text, &rule_ids, &[])?)?;
+        }
+        Command::Ai { prompt, shield, input } => {
+            let text = read_input(&input)?;
+            writeln!(stdout, "{}", run_prompt(&prompt, &text, shield).await?)?;
+        }
+        Command::ScanPii { json, input } => {
+            let result = privacy::scan_pii(&read_input(&input)?);
+            if json {
+                let json = serde_json::to_string_pretty(&result).map_err(|e| CliError::Config(e.to_string()))?;
+                writeln!(stdout, "{}", json)?;
+            } else {
+                for item in &result.items {
+                    writeln!(stdout, "{}-{}\t{:?}\t{}", item.start, item.end, item.pii_type, item.value)?;
+                }
+            }
+            if result.has_pii {
+                return Ok(ExitCode::from(1));
+            }
+        }
+        Command::Rules => {
+            for rule in regex::get_builtin_rules() {
+                writeln!(stdout, "{}\t{}", rule.id, rule.name)?;
+            }
+        }
+        Command::Help => writeln!(stdout, "{}", USAGE)?,
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let command = match parse_args(&args) {
+        Ok(command) => command,
+        Err(e) => {
+            eprintln!("flow-paste-cli: {}", e);
+            return ExitCode::from(2);
+        }
+    };
+    match run(command).await {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("flow-paste-cli: {}", e);
+            ExitCode::from(2)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, CliError> {
+        parse_args(&args.iter().map(|a| a.to_string()).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn test_parse_apply() {
+        assert_eq!(
+            parse(&["apply", "trim_whitespace,remove_empty_lines", "--stdin"]).unwrap(),
+            Command::Apply {
+                rule_ids: vec!["trim_whitespace".to_string(), "remove_empty_lines".to_string()],
+                input: Input::Stdin,
+            }
+        );
+        assert_eq!(
+            parse(&["apply", "trim_whitespace", "notes.txt"]).unwrap(),
+            Command::Apply { rule_ids: vec!["trim_whitespace".to_string()], input: Input::File("notes.txt".to_string()) }
+        );
+        assert!(parse(&["apply"]).is_err());
+        assert!(parse(&["apply", "trim_whitespace", "a.txt", "--stdin"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_ai_and_scan() {
+        assert_eq!(
+            parse(&["ai", "fix grammar", "--no-shield"]).unwrap(),
+            Command::Ai { prompt: "fix grammar".to_string(), shield: false, input: Input::Stdin }
+        );
+        assert_eq!(
+            parse(&["scan-pii", "--json", "file.txt"]).unwrap(),
+            Command::ScanPii { json: true, input: Input::File("file.txt".to_string()) }
+        );
+        assert!(parse(&["scan-pii", "--no-shield"]).is_err());
+        assert!(parse(&["frobnicate"]).is_err());
+        assert_eq!(parse(&[]).unwrap(), Command::Help);
+    }
+}
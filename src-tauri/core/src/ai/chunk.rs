@@ -0,0 +1,167 @@
+//! Prompts for text too long for one request: the prompt is run on each
+//! chunk, then the partial answers are merged (summaries, questions) or
+//! joined in order (translations and other per-part transforms).
+
+use crate::text::text_stats;
+
+/// Context window assumed when a provider doesn't say.
+pub const DEFAULT_CONTEXT_TOKENS: u32 = 8192;
+/// Room for the instructions wrapped around each chunk.
+const PROMPT_OVERHEAD_TOKENS: usize = 200;
+/// Chunks are never made smaller than this, however small the context.
+const MIN_CHUNK_TOKENS: usize = 256;
+
+const SUMMARY_WORDS: &[&str] = &[
+    "summar",
+    "tl;dr",
+    "tldr",
+    "key point",
+    "overview",
+    "outline",
+    "总结",
+    "摘要",
+    "概括",
+    "要点",
+    "归纳",
+    "提炼",
+    "大纲",
+    "要約",
+    "まとめ",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkMode {
+    /// Partial answers are merged into one by another request
+    Merge,
+    /// Partial answers are the result, in order
+    Join,
+}
+
+impl ChunkMode {
+    pub fn for_prompt(prompt: &str) -> Self {
+        let prompt = prompt.to_lowercase();
+        if SUMMARY_WORDS.iter().any(|w| prompt.contains(w)) {
+            Self::Merge
+        } else {
+            Self::Join
+        }
+    }
+}
+
+/// The message for `prompt` about `text` when it fits in one request.
+pub fn with_content(prompt: &str, text: &str) -> String {
+    format!("{}\n\nContent:\n{}", prompt, text)
+}
+
+/// Tokens of input that fit next to `prompt` and a reply of `max_tokens`.
+pub fn input_budget(context_tokens: u32, max_tokens: u32, prompt: &str) -> usize {
+    (context_tokens as usize)
+        .saturating_sub(
+            max_tokens as usize + text_stats(prompt).estimated_tokens + PROMPT_OVERHEAD_TOKENS,
+        )
+        .max(MIN_CHUNK_TOKENS)
+}
+
+/// The message for chunk `index` (from 0) of `total`.
+pub fn part_prompt(
+    prompt: &str,
+    chunk: &str,
+    index: usize,
+    total: usize,
+    mode: ChunkMode,
+) -> String {
+    let note = match mode {
+        ChunkMode::Merge => {
+            "Answer for this part only; the answers for all parts are combined afterwards."
+        }
+        ChunkMode::Join => {
+            "Reply with the result for this part only, without any introduction or comment, \
+             so the parts can be joined."
+        }
+    };
+    format!(
+        "{}\n\nThe content is part {} of {} of a longer text. {}\n\nContent:\n{}",
+        prompt,
+        index + 1,
+        total,
+        note,
+        chunk
+    )
+}
+
+/// The message combining `parts`, the answers for consecutive parts.
+pub fn merge_prompt(prompt: &str, parts: &[String]) -> String {
+    let parts = parts
+        .iter()
+        .enumerate()
+        .map(|(i, part)| format!("--- Part {} ---\n{}", i + 1, part.trim()))
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    format!(
+        "{}\n\nThe text was too long to read at once, so the instruction was answered for each part \
+         separately. Combine these partial answers into a single answer for the whole text:\n\n{}",
+        prompt, parts
+    )
+}
+
+/// Joined answers of a `ChunkMode::Join` run.
+pub fn join_parts(parts: &[String]) -> String {
+    parts
+        .iter()
+        .map(|p| p.trim())
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Consecutive `parts` grouped so each group fits in `max_tokens`; a part
+/// over the limit gets a group of its own.
+pub fn group_parts(parts: Vec<String>, max_tokens: usize) -> Vec<Vec<String>> {
+    let mut groups: Vec<Vec<String>> = Vec::new();
+    let mut group_tokens = 0;
+    for part in parts {
+        let tokens = text_stats(&part).estimated_tokens;
+        match groups.last_mut() {
+            Some(group) if group_tokens + tokens <= max_tokens => {
+                group.push(part);
+                group_tokens += tokens;
+            }
+            _ => {
+                groups.push(vec![part]);
+                group_tokens = tokens;
+            }
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mode_for_prompt() {
+        assert_eq!(
+            ChunkMode::for_prompt("Summarize this article"),
+            ChunkMode::Merge
+        );
+        assert_eq!(ChunkMode::for_prompt("请总结以下内容"), ChunkMode::Merge);
+        assert_eq!(
+            ChunkMode::for_prompt("Translate to English"),
+            ChunkMode::Join
+        );
+    }
+
+    #[test]
+    fn test_budget_and_groups() {
+        assert_eq!(
+            input_budget(8192, 2048, ""),
+            8192 - 2048 - PROMPT_OVERHEAD_TOKENS
+        );
+        assert_eq!(input_budget(2048, 2048, "Summarize"), MIN_CHUNK_TOKENS);
+
+        let parts = vec!["a".repeat(40), "b".repeat(40), "c".repeat(40)];
+        let groups = group_parts(parts, 20);
+        assert_eq!(groups.iter().map(Vec::len).collect::<Vec<_>>(), vec![2, 1]);
+        assert!(merge_prompt("Summarize", &groups[0]).contains("--- Part 2 ---"));
+    }
+}
@@ -0,0 +1,166 @@
+use serde::Deserialize;
+use uuid::Uuid;
+
+use super::intent::{ActionChip, ActionType, ContentCandidate, ContentType, IntentResult};
+
+/// Below this the heuristics are guessing, so the model is asked too.
+const AMBIGUOUS_CONFIDENCE: f32 = 0.6;
+/// Confidence given to the model's content type; it only fills in for Unknown.
+const AI_CONFIDENCE: f32 = 0.5;
+const MAX_SUGGESTIONS: usize = 3;
+/// Only the start of long text is sent; a small model classifies that just as well.
+const MAX_PROMPT_CHARS: usize = 2000;
+
+/// What the model made of the text.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Classification {
+    /// One of the `ContentType` names, or anything else when it has no idea
+    #[serde(default)]
+    pub content_type: Option<String>,
+    #[serde(default)]
+    pub actions: Vec<SuggestedAction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct SuggestedAction {
+    pub label: String,
+    /// Instruction run on the text when the chip is picked
+    pub prompt: String,
+}
+
+/// Unknown content, or a best guess the heuristics aren't sure of.
+pub fn needs_classification(result: &IntentResult) -> bool {
+    result.content_type == ContentType::Unknown
+        || !result.candidates.first().is_some_and(|c| c.confidence >= AMBIGUOUS_CONFIDENCE)
+}
+
+pub fn classification_prompt(text: &str) -> String {
+    let sample: String = text.chars().take(MAX_PROMPT_CHARS).collect();
+    format!(
+        "Classify the text below and suggest up to {} useful actions on it.\n\
+         Reply with JSON only, in this shape:\n\
+         {{\"contentType\": \"json|sql|code|log|table|list|prose|email|url|path|diff|unknown\", \
+         \"actions\": [{{\"label\": \"short button label\", \"prompt\": \"instruction to run on the text\"}}]}}\n\
+         Write labels in the same language as the text.\n\n\
+         Text:\n{}",
+        MAX_SUGGESTIONS, sample
+    )
+}
+
+/// The JSON object in the model's reply, which small models often wrap in
+/// prose or a code fence.
+pub fn parse_classification(response: &str) -> Option<Classification> {
+    let start = response.find('{')?;
+    let end = response.rfind('}')?;
+    if end < start {
+        return None;
+    }
+    serde_json::from_str(&response[start..=end]).ok()
+}
+
+/// Add the model's suggestions to `result`. For Unknown text they replace the
+/// generic chips up front; otherwise the heuristics' best chip stays first.
+pub fn merge_classification(result: &mut IntentResult, classification: Classification, max_chips: usize) {
+    let content_type = classification
+        .content_type
+        .and_then(|name| serde_json::from_value(serde_json::Value::String(name.to_lowercase())).ok())
+        .filter(|t| *t != ContentType::Unknown);
+    let unknown = result.content_type == ContentType::Unknown;
+    if let (true, Some(content_type)) = (unknown, content_type) {
+        result.content_type = content_type;
+        result.candidates = vec![ContentCandidate {
+            content_type,
+            confidence: AI_CONFIDENCE,
+        }];
+    }
+
+    let suggested = classification
+        .actions
+        .into_iter()
+        .filter(|a| !a.label.trim().is_empty() && !a.prompt.trim().is_empty())
+        .take(MAX_SUGGESTIONS)
+        .map(|a| {
+            let mut chip = ActionChip::new(
+                format!("ai:{}", a.label.trim()),
+                a.label.trim(),
+                ActionType::AIPrompt,
+                a.prompt.trim(),
+            );
+            chip.handle = Some(Uuid::new_v4().to_string());
+            chip
+        });
+
+    let heuristic = std::mem::take(&mut result.chips);
+    let keep_first = if unknown { 0 } else { 1 };
+    let mut chips: Vec<ActionChip> = Vec::new();
+    let ordered = heuristic
+        .iter()
+        .take(keep_first)
+        .cloned()
+        .chain(suggested)
+        .chain(heuristic.iter().skip(keep_first).cloned());
+    for chip in ordered {
+        if !chips.iter().any(|c| c.payload == chip.payload) {
+            chips.push(chip);
+        }
+    }
+
+    chips.truncate(max_chips);
+    for (i, chip) in chips.iter_mut().enumerate() {
+        chip.shortcut = Some((i + 1).to_string());
+    }
+    result.chips = chips;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::intent::{detect_intent, ChipLayout};
+
+    #[test]
+    fn test_parse_classification() {
+        let reply = "Sure! Here you go:\n```json\n{\"contentType\": \"prose\", \"actions\": [{\"label\": \"Shorten\", \"prompt\": \"Make it shorter\"}]}\n```";
+        let parsed = parse_classification(reply).unwrap();
+        assert_eq!(parsed.content_type.as_deref(), Some("prose"));
+        assert_eq!(parsed.actions[0].label, "Shorten");
+
+        assert_eq!(parse_classification("no json here"), None);
+        assert_eq!(parse_classification("} backwards {"), None);
+    }
+
+    #[test]
+    fn test_unknown_content_takes_the_model_type_and_chips() {
+        let mut result = detect_intent("!!!", &[], &ChipLayout::default(), &[]);
+        assert!(needs_classification(&result));
+        let classification = Classification {
+            content_type: Some("Code".to_string()),
+            actions: vec![
+                SuggestedAction { label: "Explain".to_string(), prompt: "Explain this".to_string() },
+                SuggestedAction { label: " ".to_string(), prompt: "blank label".to_string() },
+            ],
+        };
+        merge_classification(&mut result, classification, 3);
+
+        assert_eq!(result.content_type, ContentType::Code);
+        assert_eq!(result.chips.len(), 3);
+        assert_eq!(result.chips[0].payload, "Explain this");
+        assert_eq!(result.chips[0].action_id, "ai:Explain");
+        assert_eq!(result.chips[0].shortcut.as_deref(), Some("1"));
+    }
+
+    #[test]
+    fn test_known_content_keeps_its_best_chip() {
+        let mut result = detect_intent("- b\n- a\n- c", &[], &ChipLayout::default(), &[]);
+        let first = result.chips[0].payload.clone();
+        let classification = Classification {
+            content_type: Some("prose".to_string()),
+            actions: vec![SuggestedAction { label: "Number".to_string(), prompt: "Number the items".to_string() }],
+        };
+        merge_classification(&mut result, classification, 3);
+
+        assert_eq!(result.content_type, ContentType::List);
+        assert_eq!(result.chips[0].payload, first);
+        assert_eq!(result.chips[1].payload, "Number the items");
+    }
+}
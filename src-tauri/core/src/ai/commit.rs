@@ -0,0 +1,92 @@
+//! Git commit messages in the Conventional Commits format, written from a
+//! diff by the AI provider.
+
+/// Types a Conventional Commits subject may start with.
+pub const COMMIT_TYPES: &[&str] = &[
+    "feat", "fix", "docs", "style", "refactor", "perf", "test", "build", "ci", "chore", "revert",
+];
+
+/// Asks for the message alone; "summarize" makes a long diff's partial
+/// answers merge into one message.
+pub const COMMIT_PROMPT: &str = "Summarize the changes in this diff as one git commit message in the \
+Conventional Commits format. First line: `<type>(<optional scope>): <subject>`, at most 72 characters, \
+where type is one of feat, fix, docs, style, refactor, perf, test, build, ci, chore or revert and the \
+subject is in the imperative mood without a trailing period. Then a blank line and a short body saying \
+what changed and why, wrapped at 72 characters. Reply with the commit message only.";
+
+/// Whether `subject` starts with a known type, an optional `(scope)` and
+/// optional `!`, then `: `.
+pub fn is_conventional(subject: &str) -> bool {
+    let Some((head, rest)) = subject.split_once(": ") else {
+        return false;
+    };
+    if rest.trim().is_empty() {
+        return false;
+    }
+    let head = head.strip_suffix('!').unwrap_or(head);
+    let kind = match head.split_once('(') {
+        Some((kind, scope)) => match scope.strip_suffix(')') {
+            Some(scope) if !scope.is_empty() && !scope.contains([' ', '(', ')']) => kind,
+            _ => return false,
+        },
+        None => head,
+    };
+    COMMIT_TYPES.contains(&kind)
+}
+
+/// The commit message in a model reply: without code fences, a leading
+/// "Commit message:" label, surrounding quotes or the subject's trailing
+/// period.
+pub fn clean_commit_message(reply: &str) -> String {
+    let mut lines: Vec<&str> = reply.trim().lines().collect();
+    if lines.first().is_some_and(|l| l.trim_start().starts_with("```")) {
+        lines.remove(0);
+        if lines.last().is_some_and(|l| l.trim().starts_with("```")) {
+            lines.pop();
+        }
+    }
+    while lines.first().is_some_and(|l| {
+        let l = l.trim().to_lowercase();
+        l.is_empty() || (l.ends_with(':') && l.contains("commit message"))
+    }) {
+        lines.remove(0);
+    }
+
+    let message = lines.join("\n");
+    let message = message.trim().trim_matches(|c| c == '"' || c == '`').trim();
+    let (subject, body) = match message.split_once('\n') {
+        Some((subject, body)) => (subject, Some(body)),
+        None => (message, None),
+    };
+    let subject = subject.trim().trim_end_matches('.');
+    match body {
+        Some(body) if !body.trim().is_empty() => format!("{}\n\n{}", subject, body.trim()),
+        _ => subject.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai::chunk::ChunkMode;
+
+    #[test]
+    fn test_is_conventional() {
+        assert!(is_conventional("feat(parser): add arrays"));
+        assert!(is_conventional("fix!: drop Node 14 support"));
+        assert!(!is_conventional("Add arrays to the parser"));
+        assert!(!is_conventional("feature: add arrays"));
+        assert!(!is_conventional("feat(): add arrays"));
+        assert_eq!(ChunkMode::for_prompt(COMMIT_PROMPT), ChunkMode::Merge);
+    }
+
+    #[test]
+    fn test_clean_commit_message() {
+        let reply = "Commit message:\n```\nfeat(api): add token refresh.\n\nTokens are renewed\nbefore they expire.\n```";
+        assert_eq!(
+            clean_commit_message(reply),
+            "feat(api): add token refresh\n\nTokens are renewed\nbefore they expire."
+        );
+        assert_eq!(clean_commit_message("\"fix: handle empty input\""), "fix: handle empty input");
+    }
+}
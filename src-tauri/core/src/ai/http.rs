@@ -0,0 +1,142 @@
+use reqwest::{Certificate, Client, ClientBuilder, NoProxy, Proxy};
+use std::time::Duration;
+
+use super::types::AIError;
+
+/// Network settings for provider HTTP clients. Empty values keep reqwest's
+/// defaults (system proxy from the environment, built-in roots). With only
+/// `no_proxy` set, the proxy comes from the environment and `no_proxy` is
+/// added to its bypass list; proxies from OS settings aren't used then.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HttpSettings {
+    pub proxy_url: String,
+    /// Comma-separated hosts, domains or CIDR ranges that bypass the proxy
+    pub no_proxy: String,
+    /// PEM file with extra root certificates, e.g. for TLS-intercepting proxies
+    pub ca_cert_path: String,
+}
+
+pub fn build_client(settings: &HttpSettings) -> Result<Client, AIError> {
+    client_builder(settings)?
+        .timeout(Duration::from_secs(120))
+        .build()
+        .map_err(|e| AIError::ConnectionFailed(format!("failed to create HTTP client: {}", e)))
+}
+
+/// Builder with the proxy and certificates of `settings`, for clients that
+/// need more options than the shared one.
+pub fn client_builder(settings: &HttpSettings) -> Result<ClientBuilder, AIError> {
+    let mut builder = Client::builder();
+
+    let proxy_url = settings.proxy_url.trim();
+    if !proxy_url.is_empty() {
+        let proxy = Proxy::all(proxy_url)
+            .map_err(|e| AIError::ConnectionFailed(format!("invalid proxy URL: {}", e)))?
+            .no_proxy(NoProxy::from_string(&settings.no_proxy));
+        builder = builder.proxy(proxy);
+    } else if !settings.no_proxy.trim().is_empty() {
+        // reqwest's own environment proxy can't take extra exceptions
+        for proxy in env_proxies(&settings.no_proxy, |name| std::env::var(name).ok())? {
+            builder = builder.proxy(proxy);
+        }
+    }
+
+    let ca_path = settings.ca_cert_path.trim();
+    if !ca_path.is_empty() {
+        let pem = std::fs::read(ca_path)
+            .map_err(|e| AIError::ConnectionFailed(format!("can't read CA certificate {}: {}", ca_path, e)))?;
+        let certs = Certificate::from_pem_bundle(&pem)
+            .map_err(|e| AIError::ConnectionFailed(format!("invalid CA certificate {}: {}", ca_path, e)))?;
+        if certs.is_empty() {
+            return Err(AIError::ConnectionFailed(format!("no certificates in {}", ca_path)));
+        }
+        for cert in certs {
+            builder = builder.add_root_certificate(cert);
+        }
+    }
+
+    Ok(builder)
+}
+
+/// The proxies reqwest would take from environment variables read with
+/// `var`, bypassed for `no_proxy` as well as the environment's own list.
+fn env_proxies(no_proxy: &str, var: impl Fn(&str) -> Option<String>) -> Result<Vec<Proxy>, AIError> {
+    let var = |names: &[&str]| names.iter().find_map(|name| var(name)).filter(|v| !v.trim().is_empty());
+    let bypass = match var(&["no_proxy", "NO_PROXY"]) {
+        Some(env) => format!("{},{}", no_proxy, env),
+        None => no_proxy.to_string(),
+    };
+    let mut proxies = Vec::new();
+    let schemes = [
+        ("http", ["http_proxy", "HTTP_PROXY"]),
+        ("https", ["https_proxy", "HTTPS_PROXY"]),
+        ("all", ["all_proxy", "ALL_PROXY"]),
+    ];
+    for (scheme, names) in schemes {
+        let Some(url) = var(&names) else {
+            continue;
+        };
+        let proxy = match scheme {
+            "http" => Proxy::http(url.trim()),
+            "https" => Proxy::https(url.trim()),
+            _ => Proxy::all(url.trim()),
+        }
+        .map_err(|e| AIError::ConnectionFailed(format!("invalid proxy URL in the environment: {}", e)))?;
+        proxies.push(proxy.no_proxy(NoProxy::from_string(&bypass)));
+    }
+    Ok(proxies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// HTTP server on localhost answering every request with `body`.
+    async fn serve(body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        format!("http://{}", addr)
+    }
+
+    async fn fetch(no_proxy: &str, proxy: &str, url: &str) -> String {
+        let proxies = env_proxies(no_proxy, |name| (name == "http_proxy").then(|| proxy.to_string())).unwrap();
+        let builder = proxies.into_iter().fold(Client::builder(), |b, p| b.proxy(p));
+        let client = builder.build().unwrap();
+        client.get(url).send().await.unwrap().text().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_no_proxy_applies_to_env_proxy() {
+        let proxy = serve("proxy").await;
+        let target = serve("direct").await;
+        assert_eq!(fetch("127.0.0.1", &proxy, &target).await, "direct");
+        assert_eq!(fetch("example.com", &proxy, &target).await, "proxy");
+    }
+
+    #[test]
+    fn test_env_proxies() {
+        let none = env_proxies("localhost", |_| None).unwrap();
+        assert!(none.is_empty());
+        let env = |name: &str| match name {
+            "HTTPS_PROXY" => Some("http://proxy:3128".to_string()),
+            "all_proxy" => Some(" ".to_string()),
+            _ => None,
+        };
+        assert_eq!(env_proxies("localhost", env).unwrap().len(), 1);
+        assert!(env_proxies("localhost", |name| (name == "http_proxy").then(|| "::bad".to_string())).is_err());
+    }
+}
@@ -0,0 +1,1616 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use uuid::Uuid;
+
+use super::language::detect_language;
+use crate::calc::calculate;
+use crate::i18n::{self, tr, Locale};
+use crate::text::{
+    audio_file_path, classify_id, decode_base64, file_path, find_colors, fix_mojibake, has_tracking_params, is_color_list,
+    is_email_thread, is_path_list, is_url_list, is_windows_path, parse_table, parse_timestamp, ColorFormat, IdKind, TableFormat, TableInfo,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ContentType {
+    Json,
+    Color,
+    Timestamp,
+    Identifier,
+    Math,
+    Url,
+    Path,
+    Base64,
+    Sql,
+    Diff,
+    Email,
+    Log,
+    Code,
+    Table,
+    List,
+    Prose,
+    Unknown,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionChip {
+    /// The same for every detection: `<content type>.<action id>` for built-in
+    /// chips (e.g. `json.format_json`), the action id otherwise
+    pub id: String,
+    /// Stable name of the action, the same in every UI language: the built-in
+    /// chip's name (e.g. `format_json`) or `custom:<intent id>`
+    pub action_id: String,
+    /// Unique per detection, for telling apart chips from different calls
+    #[serde(default)]
+    pub handle: Option<String>,
+    pub label: String,
+    pub action_type: ActionType,
+    pub payload: String,
+    pub shortcut: Option<String>,
+}
+
+impl ActionChip {
+    /// A chip without its content-type prefix, handle or shortcut yet.
+    pub fn new(
+        action_id: impl Into<String>,
+        label: impl Into<String>,
+        action_type: ActionType,
+        payload: impl Into<String>,
+    ) -> Self {
+        let action_id = action_id.into();
+        Self {
+            id: action_id.clone(),
+            action_id,
+            handle: None,
+            label: label.into(),
+            action_type,
+            payload: payload.into(),
+            shortcut: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub enum ActionType {
+    LocalRule,
+    #[serde(rename = "AIPrompt")]
+    AIPrompt,
+    /// Run a backend command on the text; the payload names one of `COMMANDS`
+    Command,
+    /// Send the text to a stored webhook; the payload is its id
+    Webhook,
+    /// Run a stored workflow on the text; the payload is its id
+    Workflow,
+}
+
+/// Backend commands a `Command` chip can run.
+pub const COMMANDS: &[&str] = &["save_decoded_file", "make_markdown_links", "make_citation", "copy_qr_code", "save_qr_code", "transcribe_audio", "translate_text", "save_to_notes", "hash_md5", "hash_sha1", "hash_sha256", "hash_crc32", "generate_commit_message"];
+
+/// A content type the text may be, and how likely it is (0.0-1.0).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentCandidate {
+    pub content_type: ContentType,
+    pub confidence: f32,
+}
+
+/// What `detect_intent` found out about a piece of text.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntentResult {
+    /// The most likely type, same as `candidates[0]`
+    pub content_type: ContentType,
+    /// Every type that matched, most likely first
+    pub candidates: Vec<ContentCandidate>,
+    /// "zh", "en", ... for prose-like text, for use in prompt templates
+    pub language: Option<String>,
+    /// Format, header and columns when the text is a table
+    pub table: Option<TableInfo>,
+    pub chips: Vec<ActionChip>,
+}
+
+/// Chips offered at once unless the chip layout says otherwise.
+pub const DEFAULT_MAX_CHIPS: usize = 3;
+/// Chips map to the 1-9 shortcuts, so there can't be more.
+pub const MAX_CHIPS_LIMIT: usize = 9;
+
+/// Runners-up close enough to the top type to get chips of their own.
+const MIN_SECONDARY_CONFIDENCE: f32 = 0.5;
+const MAX_CONFIDENCE_GAP: f32 = 0.3;
+
+/// How built-in chips are offered, from settings.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChipLayout {
+    /// Chips offered at once, 1-9
+    #[serde(default = "default_max_chips")]
+    pub max_chips: usize,
+    /// Overrides for single content types
+    #[serde(default)]
+    pub types: Vec<ContentTypeChips>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentTypeChips {
+    pub content_type: ContentType,
+    /// Replaces `ChipLayout::max_chips` when this is the detected type
+    #[serde(default)]
+    pub max_chips: Option<usize>,
+    /// Built-in chip action ids to offer first, in this order; the rest follow as usual
+    #[serde(default)]
+    pub order: Vec<String>,
+    /// Built-in chip action ids never to offer
+    #[serde(default)]
+    pub hidden: Vec<String>,
+}
+
+fn default_max_chips() -> usize {
+    DEFAULT_MAX_CHIPS
+}
+
+impl Default for ChipLayout {
+    fn default() -> Self {
+        Self {
+            max_chips: DEFAULT_MAX_CHIPS,
+            types: Vec::new(),
+        }
+    }
+}
+
+impl ChipLayout {
+    fn for_type(&self, content_type: ContentType) -> Option<&ContentTypeChips> {
+        self.types.iter().find(|t| t.content_type == content_type)
+    }
+
+    /// Chips offered when `content_type` is detected.
+    pub fn max_chips(&self, content_type: ContentType) -> usize {
+        self.for_type(content_type)
+            .and_then(|t| t.max_chips)
+            .unwrap_or(self.max_chips)
+            .clamp(1, MAX_CHIPS_LIMIT)
+    }
+
+    /// Drop hidden chips and move the preferred ones to the front.
+    fn arrange(&self, content_type: ContentType, mut chips: Vec<ActionChip>) -> Vec<ActionChip> {
+        let Some(prefs) = self.for_type(content_type) else {
+            return chips;
+        };
+        chips.retain(|c| !prefs.hidden.contains(&c.action_id));
+        // Stable, so unlisted chips keep their order after the listed ones
+        chips.sort_by_key(|c| prefs.order.iter().position(|id| *id == c.action_id).unwrap_or(usize::MAX));
+        chips
+    }
+}
+
+/// How often and how recently a built-in chip was picked for a content type.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChipUsage {
+    pub content_type: ContentType,
+    pub action_id: String,
+    /// One point per pick, each worth less the longer ago it was
+    pub score: f64,
+    /// Unix timestamp in milliseconds
+    pub last_used: i64,
+}
+
+/// A pick counts half as much after two weeks.
+const USAGE_HALF_LIFE_MS: f64 = 14.0 * 24.0 * 60.0 * 60.0 * 1000.0;
+
+/// `score` as of `now`, so old habits fade as new ones form.
+pub fn decayed_score(score: f64, last_used: i64, now: i64) -> f64 {
+    let age = (now - last_used).max(0) as f64;
+    score * 0.5f64.powf(age / USAGE_HALF_LIFE_MS)
+}
+
+/// Most picked chips first; chips never picked keep their order behind them.
+fn rank_by_usage(
+    content_type: ContentType,
+    mut chips: Vec<ActionChip>,
+    usage: &[ChipUsage],
+    now: i64,
+) -> Vec<ActionChip> {
+    let score = |chip: &ActionChip| {
+        usage
+            .iter()
+            .find(|u| u.content_type == content_type && u.action_id == chip.action_id)
+            .map_or(0.0, |u| decayed_score(u.score, u.last_used, now))
+    };
+    chips.sort_by(|a, b| score(b).total_cmp(&score(a)));
+    chips
+}
+
+/// What a user-defined intent reacts to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "kind")]
+pub enum IntentMatcher {
+    /// The text matches `pattern` anywhere
+    Regex { pattern: String },
+    /// The text is detected as this content type
+    ContentType {
+        #[serde(rename = "contentType")]
+        content_type: ContentType,
+    },
+}
+
+/// A chip the user added, offered ahead of the built-in ones when its
+/// matcher fires.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustomIntent {
+    pub id: String,
+    pub matcher: IntentMatcher,
+    pub label: String,
+    pub action_type: ActionType,
+    /// Rule id for `LocalRule`, prompt for `AIPrompt`, command name for
+    /// `Command`, webhook id for `Webhook`, workflow id for `Workflow`
+    pub payload: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+impl CustomIntent {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.id.trim().is_empty() {
+            return Err("id must not be empty".to_string());
+        }
+        if self.label.trim().is_empty() {
+            return Err("label must not be empty".to_string());
+        }
+        if self.payload.trim().is_empty() {
+            return Err("payload must not be empty".to_string());
+        }
+        if self.action_type == ActionType::Command && !COMMANDS.contains(&self.payload.as_str()) {
+            return Err(format!("unknown command: {}", self.payload));
+        }
+        if let IntentMatcher::Regex { pattern } = &self.matcher {
+            Regex::new(pattern).map_err(|e| format!("invalid pattern: {}", e))?;
+        }
+        Ok(())
+    }
+
+    fn matches(&self, text: &str, content_types: &[ContentType]) -> bool {
+        match &self.matcher {
+            // Stored intents are validated, but a bad pattern must not break detection
+            IntentMatcher::Regex { pattern } => custom_pattern(pattern).is_some_and(|re| re.is_match(text)),
+            IntentMatcher::ContentType { content_type: wanted } => content_types.contains(wanted),
+        }
+    }
+}
+
+/// Past this many patterns the cache starts over, as edited ones pile up
+const MAX_CUSTOM_PATTERNS: usize = 256;
+
+/// Compiled custom intent patterns by source, `None` for invalid ones.
+/// Intents are reloaded for every detection, so they can't hold their own.
+static CUSTOM_PATTERNS: Lazy<Mutex<HashMap<String, Option<Regex>>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+fn custom_pattern(pattern: &str) -> Option<Regex> {
+    let mut cache = CUSTOM_PATTERNS.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(re) = cache.get(pattern) {
+        return re.clone();
+    }
+    if cache.len() >= MAX_CUSTOM_PATTERNS {
+        cache.clear();
+    }
+    let re = Regex::new(pattern).ok();
+    cache.insert(pattern.to_string(), re.clone());
+    re
+}
+
+// Regex patterns for content detection
+static JSON_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*[\{\[]").unwrap());
+static CODE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(function|class|def|pub fn|const|let|var|import|#include|package)\s+\w+").unwrap()
+});
+// A statement at the start of the text (after any `--` comment lines). SELECT
+// needs a column-list-like start so "Select the file from ..." stays prose.
+static SQL_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?is)^(?:\s*--[^\n]*\n)*\s*(?:select\s+(?:distinct\s+)?(?:\*|[\w.]+\s*\(|[\w.]+\s*,|[\w.]+\s+from\s)|insert\s+into\s|update\s+\S+\s+set\s|delete\s+from\s|create\s+(?:or\s+replace\s+)?(?:temporary\s+)?(?:table|view|index|unique\s+index)\s|alter\s+table\s|drop\s+(?:table|view|index)\s|with\s+\w+\s+as\s*\()",
+    )
+    .unwrap()
+});
+static DATA_URI_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^data:[\w.+-]+/[\w.+-]+(?:;[\w.+-]+=[\w.+-]+)*;base64,").unwrap()
+});
+// Whitespace is stripped first; shorter runs are more likely words or ids
+static BASE64_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^[A-Za-z0-9+/_-]{32,}={0,2}$").unwrap()
+});
+// `diff --git` headers, `@@ -1,2 +1,3 @@` hunks or a `---`/`+++` file pair
+static DIFF_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^(?:diff --git |@@ -\d+(?:,\d+)? \+\d+(?:,\d+)? @@|--- \S.*\n\+\+\+ \S)").unwrap()
+});
+// Timestamp or level token at the start of a log line
+static LOG_LINE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^\s*\[?(?:\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}|\d{2}:\d{2}:\d{2}|(?:TRACE|DEBUG|INFO|WARN|WARNING|ERROR|FATAL|CRITICAL)\b)").unwrap()
+});
+// Stack frames: Java/JS `at ...`, Python `File "...", line N`, numbered backtraces
+static STACK_FRAME_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?m)^\s+(?:at\s+\S+|File "[^"]+", line \d+|\d+:\s+\S+)"#).unwrap()
+});
+static CRASH_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^(?:Traceback \(most recent call last\)|thread '[^']*' panicked at|panic: |goroutine \d+ \[|Exception in thread )").unwrap()
+});
+static LIST_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^(\s*[-*+•]\s+|\s*\d+[.)]\s+)").unwrap()
+});
+static URL_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"https?://[^\s]+").unwrap()
+});
+
+/// Chips for `text`: matching user intents first, then built-in chips whose
+/// payload isn't already offered, renumbered 1..=3.
+pub fn detect_intent(
+    text: &str,
+    custom: &[CustomIntent],
+    layout: &ChipLayout,
+    usage: &[ChipUsage],
+) -> IntentResult {
+    if text.is_empty() {
+        return IntentResult {
+            content_type: ContentType::Unknown,
+            candidates: vec![],
+            language: None,
+            table: None,
+            chips: vec![],
+        };
+    }
+
+    let candidates = rank_content_types(text);
+    let top = candidates[0];
+    let max_chips = layout.max_chips(top.content_type);
+    // The top type plus runners-up that are nearly as likely, e.g. code inside prose
+    let types: Vec<ContentType> = candidates
+        .iter()
+        .take(max_chips)
+        .filter(|c| {
+            c.confidence >= MIN_SECONDARY_CONFIDENCE && top.confidence - c.confidence <= MAX_CONFIDENCE_GAP
+        })
+        .map(|c| c.content_type)
+        .collect();
+    let types = if types.is_empty() { vec![top.content_type] } else { types };
+
+    // Structured content is mostly syntax, so its "language" is noise
+    let language = if types.iter().all(|t| is_structured(*t)) {
+        None
+    } else {
+        detect_language(text)
+    };
+    let table = if types.contains(&ContentType::Table) {
+        table_info(text)
+    } else {
+        None
+    };
+    let mut chips: Vec<ActionChip> = custom
+        .iter()
+        .filter(|intent| intent.enabled && intent.matches(text, &types))
+        .map(|intent| {
+            let id = format!("custom:{}", intent.id);
+            ActionChip::new(id, intent.label.clone(), intent.action_type, intent.payload.clone())
+        })
+        .collect();
+    let chip_lists: Vec<Vec<ActionChip>> = types
+        .iter()
+        .map(|t| {
+            let chips = generate_action_chips(*t, text, language, table.as_ref(), max_chips);
+            let chips = rank_by_usage(*t, chips, usage, chrono::Utc::now().timestamp_millis());
+            layout.arrange(*t, chips)
+        })
+        .collect();
+    for chip in merge_chips(chip_lists, max_chips) {
+        if !chips.iter().any(|c| c.payload == chip.payload) {
+            chips.push(chip);
+        }
+    }
+
+    chips.truncate(max_chips);
+    for (i, chip) in chips.iter_mut().enumerate() {
+        chip.shortcut = Some((i + 1).to_string());
+        chip.handle = Some(Uuid::new_v4().to_string());
+    }
+    IntentResult {
+        content_type: top.content_type,
+        candidates,
+        language: language.map(str::to_string),
+        table,
+        chips,
+    }
+}
+
+/// The serde name, e.g. "json", as the frontend and stored settings see it.
+pub fn content_type_key(content_type: ContentType) -> String {
+    serde_json::to_value(content_type)
+        .ok()
+        .and_then(|v| v.as_str().map(str::to_string))
+        .unwrap_or_default()
+}
+
+fn is_structured(content_type: ContentType) -> bool {
+    matches!(
+        content_type,
+        ContentType::Json
+            | ContentType::Color
+            | ContentType::Timestamp
+            | ContentType::Identifier
+            | ContentType::Math
+            | ContentType::Url
+            | ContentType::Path
+            | ContentType::Base64
+            | ContentType::Sql
+            | ContentType::Diff
+            | ContentType::Log
+            | ContentType::Code
+            | ContentType::Table
+    )
+}
+
+/// Chips for the top type first, keeping one slot for the best chip of each runner-up.
+fn merge_chips(lists: Vec<Vec<ActionChip>>, max_chips: usize) -> Vec<ActionChip> {
+    let runners_up = lists.len().saturating_sub(1);
+    let mut lists: Vec<std::vec::IntoIter<ActionChip>> = lists.into_iter().map(Vec::into_iter).collect();
+
+    let primary_slots = max_chips.saturating_sub(runners_up).max(1);
+    let mut ordered: Vec<ActionChip> = lists[0].by_ref().take(primary_slots).collect();
+    ordered.extend(lists.iter_mut().skip(1).filter_map(Iterator::next));
+    ordered.extend(lists.into_iter().flatten());
+
+    let mut merged: Vec<ActionChip> = Vec::new();
+    for chip in ordered {
+        if !merged.iter().any(|c| c.payload == chip.payload) {
+            merged.push(chip);
+        }
+    }
+    merged
+}
+
+/// Content types the text matches, most likely first. Whole-text formats
+/// such as JSON or a URL list and quoting formats come back alone; the rest
+/// are scored together so mixed content gets more than one candidate.
+fn rank_content_types(text: &str) -> Vec<ContentCandidate> {
+    let only = |content_type, confidence| vec![ContentCandidate { content_type, confidence }];
+    let trimmed = text.trim();
+
+    // JSON detection (highest priority for structured data)
+    if JSON_PATTERN.is_match(trimmed) {
+        // Validate it's likely valid JSON
+        if trimmed.ends_with('}') || trimmed.ends_with(']') {
+            return only(ContentType::Json, 0.9);
+        }
+    }
+
+    if is_color_list(trimmed) {
+        return only(ContentType::Color, 0.95);
+    }
+
+    // One timestamp, UUID or hash per line
+    if all_lines(trimmed, |l| parse_timestamp(l).is_some()) {
+        return only(ContentType::Timestamp, 0.95);
+    }
+    if all_lines(trimmed, |l| classify_id(l).is_some()) {
+        return only(ContentType::Identifier, 0.9);
+    }
+
+    // After timestamps, so epoch values and ISO dates are never subtracted
+    if calculate(trimmed).is_some() {
+        return only(ContentType::Math, 0.9);
+    }
+
+    if is_url_list(trimmed) {
+        return only(ContentType::Url, 0.95);
+    }
+
+    if is_path_list(trimmed) {
+        return only(ContentType::Path, 0.9);
+    }
+
+    if is_base64(trimmed) {
+        return only(ContentType::Base64, 0.85);
+    }
+
+    // Diffs and email threads quote any other kind of content, so what they
+    // quote doesn't count
+    if DIFF_PATTERN.is_match(text) {
+        return only(ContentType::Diff, 0.95);
+    }
+    if is_email_thread(text) {
+        return only(ContentType::Email, 0.9);
+    }
+
+    // Scores below keep the old first-match order on ties: SQL statements
+    // often span indented lines, and tracebacks quote source lines.
+    let mut candidates = Vec::new();
+    let mut add = |content_type, confidence| candidates.push(ContentCandidate { content_type, confidence });
+
+    if SQL_PATTERN.is_match(text) {
+        add(ContentType::Sql, 0.85);
+    }
+    if is_log(text) {
+        add(ContentType::Log, 0.85);
+    }
+
+    let lines: Vec<&str> = text.lines().filter(|l| !l.trim().is_empty()).collect();
+    let ratio = |count: usize| count as f32 / lines.len().max(1) as f32;
+
+    let code_lines = lines.iter().filter(|l| is_code_line(l)).count();
+    let indented_lines = lines.iter().filter(|l| l.starts_with("    ") || l.starts_with('\t')).count();
+    let looks_like_code = CODE_PATTERN.is_match(text)
+        || (lines.len() >= 2 && indented_lines >= 2 && indented_lines >= lines.len() / 3);
+    if looks_like_code && code_lines > 0 {
+        add(ContentType::Code, 0.4 + 0.4 * ratio(code_lines));
+    }
+
+    // Table detection (Markdown pipe tables, CSV/TSV)
+    if table_info(text).is_some() {
+        add(ContentType::Table, 0.75);
+    }
+
+    let list_lines = LIST_PATTERN.find_iter(text).count();
+    if list_lines >= 2 {
+        add(ContentType::List, 0.5 + 0.3 * ratio(list_lines));
+    }
+
+    // Prose (multiple sentences, Western or CJK punctuation), less likely
+    // the more of it is code or list items
+    let sentences = text.matches(&['.', '!', '?', '。', '！', '？'][..]).count();
+    if sentences >= 2 && text.len() > 50 {
+        let other = ratio(code_lines) + if list_lines >= 2 { ratio(list_lines) } else { 0.0 };
+        add(ContentType::Prose, 0.4 + 0.4 * (1.0 - other).max(0.0));
+    }
+
+    if candidates.is_empty() {
+        return only(ContentType::Unknown, 0.0);
+    }
+    // Stable, so ties keep the order above
+    candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+    candidates
+}
+
+/// A line that reads as code rather than a sentence mentioning `let` or `import`.
+fn is_code_line(line: &str) -> bool {
+    let line = line.trim_end();
+    line.ends_with([';', '{', '}'])
+        || line.starts_with("    ")
+        || line.starts_with('\t')
+        || (CODE_PATTERN.is_match(line) && !line.ends_with(['.', '!', '?', '。', '！', '？']))
+}
+
+/// Two comma-separated fields per line are as likely to be sentences as CSV.
+fn table_info(text: &str) -> Option<TableInfo> {
+    parse_table(text)
+        .map(|table| table.info)
+        .filter(|info| info.format != TableFormat::Csv || info.column_count >= 3)
+}
+
+/// Whether `text` has non-blank lines and `f` holds for all of them.
+fn all_lines(text: &str, f: impl Fn(&str) -> bool) -> bool {
+    let mut lines = text.lines().filter(|l| !l.trim().is_empty()).peekable();
+    lines.peek().is_some() && lines.all(f)
+}
+
+/// A data URI, or one long run of Base64 that mixes cases and digits (which
+/// rules out words and hex hashes) and actually decodes.
+fn is_base64(trimmed: &str) -> bool {
+    if DATA_URI_PATTERN.is_match(trimmed) {
+        return decode_base64(trimmed).is_some();
+    }
+    let compact: String = trimmed.chars().filter(|c| !c.is_whitespace()).collect();
+    BASE64_PATTERN.is_match(&compact)
+        && compact.chars().any(|c| c.is_ascii_uppercase())
+        && compact.chars().any(|c| c.is_ascii_lowercase())
+        && compact.chars().any(|c| c.is_ascii_digit())
+        && decode_base64(&compact).is_some()
+}
+
+/// A crash report, a stack trace, or lines that mostly start with a
+/// timestamp or level.
+fn is_log(text: &str) -> bool {
+    if CRASH_PATTERN.is_match(text) || STACK_FRAME_PATTERN.find_iter(text).count() >= 2 {
+        return true;
+    }
+    let log_lines = LOG_LINE_PATTERN.find_iter(text).count();
+    let lines = text.lines().filter(|l| !l.trim().is_empty()).count();
+    log_lines >= 2 && log_lines * 2 >= lines
+}
+
+/// Chinese text is translated to English and English text to Chinese;
+/// anything else goes to the UI language. With a Japanese UI, everything
+/// but Japanese goes to Japanese, and Japanese to English.
+fn target_locale(language: Option<&str>) -> Locale {
+    match (i18n::current(), language) {
+        (Locale::Ja, Some("ja")) => Locale::En,
+        (Locale::Ja, _) => Locale::Ja,
+        (_, Some("zh")) => Locale::En,
+        (_, Some("en")) => Locale::ZhCn,
+        (locale, _) => locale,
+    }
+}
+
+/// Where translating `text` goes, the same target its translate chip shows.
+pub fn translation_target(text: &str) -> Locale {
+    target_locale(detect_language(text))
+}
+
+/// AI prompt of the translate chip for `text`, for translating without a
+/// translation service.
+pub fn translation_prompt(text: &str) -> &'static str {
+    translation_chip(detect_language(text)).1
+}
+
+fn translation_chip(language: Option<&str>) -> (&'static str, &'static str) {
+    match target_locale(language) {
+        Locale::En => ("chip.translate_en", "Translate this text to English"),
+        Locale::ZhCn => ("chip.translate_zh", "Translate this text to Chinese"),
+        Locale::Ja => ("chip.translate_ja", "Translate this text to Japanese"),
+    }
+}
+
+/// Command the translate chips run instead of their AI prompt when a
+/// machine translation service is set up.
+pub const TRANSLATE_COMMAND: &str = "translate_text";
+
+/// Point the translate chips at `TRANSLATE_COMMAND`.
+pub fn use_translation_command(result: &mut IntentResult) {
+    let translate_chips = result
+        .chips
+        .iter_mut()
+        .filter(|c| c.action_type == ActionType::AIPrompt && c.action_id.starts_with("translate_"));
+    for chip in translate_chips {
+        chip.action_type = ActionType::Command;
+        chip.payload = TRANSLATE_COMMAND.to_string();
+    }
+}
+
+/// Command of the chip that appends the text to the user's notes.
+pub const NOTES_COMMAND: &str = "save_to_notes";
+
+/// Offer saving to notes as the last chip, in place of the last one when
+/// `max_chips` are already offered.
+pub fn add_notes_chip(result: &mut IntentResult, max_chips: usize) {
+    if result.chips.iter().any(|c| c.payload == NOTES_COMMAND) {
+        return;
+    }
+    result.chips.truncate(max_chips.saturating_sub(1));
+    let mut chip = ActionChip::new("save_to_notes", tr("chip.save_to_notes"), ActionType::Command, NOTES_COMMAND);
+    chip.shortcut = Some((result.chips.len() + 1).to_string());
+    chip.handle = Some(Uuid::new_v4().to_string());
+    result.chips.push(chip);
+}
+
+/// Garbled text is offered the fix first, whatever else it looks like.
+fn fix_encoding_chip() -> ActionChip {
+    ActionChip::new("fix_encoding", tr("chip.fix_encoding"), ActionType::LocalRule, "fix_encoding")
+}
+
+fn generate_action_chips(
+    content_type: ContentType,
+    text: &str,
+    language: Option<&str>,
+    table: Option<&TableInfo>,
+    max_chips: usize,
+) -> Vec<ActionChip> {
+    let mut chips = Vec::new();
+
+    match content_type {
+        ContentType::Json => {
+            chips.push(ActionChip::new(
+                "format_json",
+                tr("chip.format_json"),
+                ActionType::AIPrompt,
+                "Format this JSON with proper indentation",
+            ));
+
+            chips.push(ActionChip::new(
+                "minify_json",
+                tr("chip.minify_json"),
+                ActionType::AIPrompt,
+                "Minify this JSON to a single line",
+            ));
+
+            chips.push(ActionChip::new(
+                "json_to_yaml",
+                tr("chip.json_to_yaml"),
+                ActionType::AIPrompt,
+                "Convert this JSON to YAML format",
+            ));
+        }
+        ContentType::Sql => {
+            chips.push(ActionChip::new(
+                "format_sql",
+                tr("chip.format_sql"),
+                ActionType::LocalRule,
+                "format_sql",
+            ));
+
+            chips.push(ActionChip::new(
+                "explain_sql",
+                tr("chip.explain_sql"),
+                ActionType::AIPrompt,
+                "Explain what this SQL query does, step by step",
+            ));
+
+            chips.push(ActionChip::new(
+                "optimize_sql",
+                tr("chip.optimize_sql"),
+                ActionType::AIPrompt,
+                "Suggest how to make this SQL query faster and explain why",
+            ));
+        }
+        ContentType::Color => {
+            // Offer the notations the colors aren't already in
+            let colors = find_colors(text);
+            let conversions = [
+                (ColorFormat::Hex, "chip.color_to_hex", "color_to_hex"),
+                (ColorFormat::Rgb, "chip.color_to_rgb", "color_to_rgb"),
+                (ColorFormat::Hsl, "chip.color_to_hsl", "color_to_hsl"),
+            ];
+            for (format, label, rule) in conversions {
+                if colors.iter().all(|c| c.format == format) {
+                    continue;
+                }
+                chips.push(ActionChip::new(
+                    label.trim_start_matches("chip."),
+                    tr(label),
+                    ActionType::LocalRule,
+                    rule,
+                ));
+            }
+            // Always shown, and takes the last conversion's place when all don't fit
+            chips.truncate(max_chips.saturating_sub(1));
+
+            chips.push(ActionChip::new(
+                "color_palette",
+                tr("chip.color_palette"),
+                ActionType::LocalRule,
+                "color_palette",
+            ));
+        }
+        ContentType::Timestamp => {
+            chips.push(ActionChip::new(
+                "convert_timestamp",
+                tr("chip.convert_timestamp"),
+                ActionType::LocalRule,
+                "convert_timestamp",
+            ));
+        }
+        ContentType::Identifier => {
+            let has_uuid = text.lines().any(|l| matches!(classify_id(l), Some(IdKind::Uuid(_))));
+            if has_uuid {
+                chips.push(ActionChip::new(
+                    "new_uuid",
+                    tr("chip.new_uuid"),
+                    ActionType::LocalRule,
+                    "new_uuid",
+                ));
+            }
+
+            chips.push(ActionChip::new(
+                "toggle_case",
+                tr("chip.toggle_case"),
+                ActionType::LocalRule,
+                "toggle_case",
+            ));
+
+            chips.push(ActionChip::new(
+                "identify_ids",
+                tr("chip.identify_ids"),
+                ActionType::LocalRule,
+                "identify_ids",
+            ));
+        }
+        ContentType::Math => {
+            // The label is the answer itself
+            if let Some(answer) = calculate(text) {
+                chips.push(ActionChip::new(
+                    "calculate",
+                    format!("= {}", answer),
+                    ActionType::LocalRule,
+                    "calculate",
+                ));
+            }
+        }
+        ContentType::Url => {
+            chips.push(ActionChip::new(
+                "markdown_link",
+                tr("chip.markdown_link"),
+                ActionType::Command,
+                "make_markdown_links",
+            ));
+            chips.push(ActionChip::new(
+                "citation",
+                tr("chip.citation"),
+                ActionType::Command,
+                "make_citation",
+            ));
+
+            if text.split_whitespace().any(has_tracking_params) {
+                chips.push(ActionChip::new(
+                    "strip_tracking",
+                    tr("chip.strip_tracking"),
+                    ActionType::LocalRule,
+                    "strip_tracking_params",
+                ));
+            }
+
+            // A code holding several URLs isn't useful to scan
+            if text.split_whitespace().count() == 1 {
+                chips.push(ActionChip::new(
+                    "qr_code",
+                    tr("chip.qr_code"),
+                    ActionType::LocalRule,
+                    "qr_code",
+                ));
+                chips.push(ActionChip::new(
+                    "qr_image",
+                    tr("chip.qr_image"),
+                    ActionType::Command,
+                    "copy_qr_code",
+                ));
+            }
+        }
+        ContentType::Path => {
+            if audio_file_path(text).is_some() {
+                chips.push(ActionChip::new(
+                    "transcribe_audio",
+                    tr("chip.transcribe_audio"),
+                    ActionType::Command,
+                    "transcribe_audio",
+                ));
+            }
+
+            // A single file is likely a download to check against its published checksum
+            let is_file = file_path(text).is_some();
+            if is_file {
+                chips.push(ActionChip::new(
+                    "hash_sha256",
+                    tr("chip.hash_sha256"),
+                    ActionType::Command,
+                    "hash_sha256",
+                ));
+            }
+
+            let (label, rule) = if text.lines().any(is_windows_path) {
+                ("chip.paths_to_posix", "paths_to_posix")
+            } else {
+                ("chip.paths_to_windows", "paths_to_windows")
+            };
+            chips.push(ActionChip::new(
+                label.trim_start_matches("chip."),
+                tr(label),
+                ActionType::LocalRule,
+                rule,
+            ));
+
+            chips.push(ActionChip::new(
+                "shell_quote_paths",
+                tr("chip.shell_quote_paths"),
+                ActionType::LocalRule,
+                "shell_quote_paths",
+            ));
+
+            chips.push(ActionChip::new(
+                "extract_file_names",
+                tr("chip.extract_file_names"),
+                ActionType::LocalRule,
+                "extract_file_names",
+            ));
+
+            if is_file {
+                let hashes = [("hash_md5", "chip.hash_md5"), ("hash_sha1", "chip.hash_sha1"), ("hash_crc32", "chip.hash_crc32")];
+                for (name, label) in hashes {
+                    chips.push(ActionChip::new(name, tr(label), ActionType::Command, name));
+                }
+            } else if !text.trim().contains('\n') {
+                // A single folder may be a repository with staged changes
+                chips.push(ActionChip::new(
+                    "commit_message",
+                    tr("chip.commit_message"),
+                    ActionType::Command,
+                    "generate_commit_message",
+                ));
+            }
+        }
+        ContentType::Base64 => {
+            // Binary data can't go back on the clipboard as text
+            let is_text = decode_base64(text).is_some_and(|d| d.as_text().is_some());
+            if is_text {
+                chips.push(ActionChip::new(
+                    "decode_base64",
+                    tr("chip.decode_base64"),
+                    ActionType::LocalRule,
+                    "decode_base64",
+                ));
+            } else {
+                chips.push(ActionChip::new(
+                    "save_decoded",
+                    tr("chip.save_decoded"),
+                    ActionType::Command,
+                    "save_decoded_file",
+                ));
+            }
+        }
+        ContentType::Diff => {
+            chips.push(ActionChip::new(
+                "summarize_diff",
+                tr("chip.summarize_diff"),
+                ActionType::AIPrompt,
+                "Summarize the changes in this diff",
+            ));
+
+            chips.push(ActionChip::new(
+                "commit_message",
+                tr("chip.commit_message"),
+                ActionType::Command,
+                "generate_commit_message",
+            ));
+
+            chips.push(ActionChip::new(
+                "strip_diff",
+                tr("chip.strip_diff"),
+                ActionType::LocalRule,
+                "strip_diff_markers",
+            ));
+        }
+        ContentType::Email => {
+            chips.push(ActionChip::new(
+                "summarize_thread",
+                tr("chip.summarize_thread"),
+                ActionType::AIPrompt,
+                "Summarize this email thread: who said what, decisions made and open questions",
+            ));
+
+            chips.push(ActionChip::new(
+                "draft_reply",
+                tr("chip.draft_reply"),
+                ActionType::AIPrompt,
+                "Draft a reply to the latest message in this email thread, in the same language and tone",
+            ));
+
+            chips.push(ActionChip::new(
+                "strip_quoted",
+                tr("chip.strip_quoted"),
+                ActionType::LocalRule,
+                "strip_quoted_text",
+            ));
+        }
+        ContentType::Log => {
+            chips.push(ActionChip::new(
+                "explain_error",
+                tr("chip.explain_error"),
+                ActionType::AIPrompt,
+                "Explain the cause of this error and how to fix it",
+            ));
+
+            chips.push(ActionChip::new(
+                "extract_errors",
+                tr("chip.extract_errors"),
+                ActionType::LocalRule,
+                "extract_errors",
+            ));
+
+            chips.push(ActionChip::new(
+                "summarize_log",
+                tr("chip.summarize_log"),
+                ActionType::AIPrompt,
+                "Summarize what happened in this log, in order, and list the errors",
+            ));
+        }
+        ContentType::Code => {
+            chips.push(ActionChip::new(
+                "add_comments",
+                tr("chip.add_comments"),
+                ActionType::AIPrompt,
+                "Add clear comments to explain this code",
+            ));
+
+            chips.push(ActionChip::new(
+                "refactor",
+                tr("chip.refactor"),
+                ActionType::AIPrompt,
+                "Refactor this code for better readability and performance",
+            ));
+
+            chips.push(ActionChip::new(
+                "explain_code",
+                tr("chip.explain_code"),
+                ActionType::AIPrompt,
+                "Explain what this code does in simple terms",
+            ));
+        }
+        ContentType::Table => {
+            if table.is_some_and(|t| t.format == TableFormat::Markdown) {
+                chips.push(ActionChip::new(
+                    "table_to_tsv",
+                    tr("chip.table_to_tsv"),
+                    ActionType::LocalRule,
+                    "markdown_table_to_tsv",
+                ));
+            } else {
+                chips.push(ActionChip::new(
+                    "to_markdown_table",
+                    tr("chip.to_markdown_table"),
+                    ActionType::LocalRule,
+                    "to_markdown_table",
+                ));
+            }
+
+            chips.push(ActionChip::new(
+                "first_column",
+                tr("chip.first_column"),
+                ActionType::LocalRule,
+                "extract_column:1",
+            ));
+
+            chips.push(ActionChip::new(
+                "sort_table",
+                tr("chip.sort_table"),
+                ActionType::AIPrompt,
+                "Sort this table by the first column",
+            ));
+        }
+        ContentType::List => {
+            chips.push(ActionChip::new(
+                "sort_list",
+                tr("chip.sort_list"),
+                ActionType::LocalRule,
+                "sort_list",
+            ));
+
+            chips.push(ActionChip::new(
+                "dedupe",
+                tr("chip.dedupe"),
+                ActionType::AIPrompt,
+                "Remove duplicate items from this list",
+            ));
+
+            chips.push(ActionChip::new(
+                "comma_separated",
+                tr("chip.comma_separated"),
+                ActionType::AIPrompt,
+                "Convert this list to comma-separated values",
+            ));
+        }
+        ContentType::Prose => {
+            let has_urls = URL_PATTERN.is_match(text);
+            let is_long = text.len() > 500;
+
+            if fix_mojibake(text).is_some() {
+                chips.push(fix_encoding_chip());
+            }
+
+            if is_long {
+                chips.push(ActionChip::new(
+                    "summarize",
+                    tr("chip.summarize"),
+                    ActionType::AIPrompt,
+                    "Summarize the key points of this text in bullet points",
+                ));
+            }
+
+            chips.push(ActionChip::new(
+                "fix_grammar",
+                tr("chip.fix_grammar"),
+                ActionType::AIPrompt,
+                "Fix grammar and spelling errors",
+            ));
+
+            if has_urls {
+                chips.push(ActionChip::new(
+                    "extract_urls",
+                    tr("chip.extract_urls"),
+                    ActionType::LocalRule,
+                    "extract_urls",
+                ));
+            } else {
+                let (label, prompt) = translation_chip(language);
+                chips.push(ActionChip::new(
+                    label.trim_start_matches("chip."),
+                    tr(label),
+                    ActionType::AIPrompt,
+                    prompt,
+                ));
+            }
+        }
+        ContentType::Unknown => {
+            if fix_mojibake(text).is_some() {
+                chips.push(fix_encoding_chip());
+            }
+
+            // Generic actions for unknown content
+            chips.push(ActionChip::new(
+                "remove_empty_lines",
+                tr("chip.remove_empty_lines"),
+                ActionType::LocalRule,
+                "remove_empty_lines",
+            ));
+
+            chips.push(ActionChip::new(
+                "trim",
+                tr("chip.trim"),
+                ActionType::LocalRule,
+                "trim_whitespace",
+            ));
+
+            chips.push(ActionChip::new(
+                "collapse_spaces",
+                tr("chip.collapse_spaces"),
+                ActionType::LocalRule,
+                "collapse_spaces",
+            ));
+        }
+    }
+
+    let prefix = content_type_key(content_type);
+    for chip in &mut chips {
+        chip.id = format!("{}.{}", prefix, chip.action_id);
+    }
+    chips
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn detect_content_type(text: &str) -> ContentType {
+        rank_content_types(text)[0].content_type
+    }
+
+    #[test]
+    fn test_detect_json() {
+        let json = r#"{"name": "test", "value": 123}"#;
+        assert_eq!(detect_content_type(json), ContentType::Json);
+
+        let json_array = r#"[1, 2, 3]"#;
+        assert_eq!(detect_content_type(json_array), ContentType::Json);
+    }
+
+    #[test]
+    fn test_detect_code() {
+        let code = r#"function hello() {
+    console.log("Hello");
+    return true;
+}"#;
+        assert_eq!(detect_content_type(code), ContentType::Code);
+    }
+
+    #[test]
+    fn test_detect_sql() {
+        assert_eq!(detect_content_type("select id, name from users where id = 1"), ContentType::Sql);
+        assert_eq!(
+            detect_content_type("-- cleanup\nDELETE FROM sessions\n    WHERE expires_at < now();"),
+            ContentType::Sql
+        );
+        assert_eq!(detect_content_type("CREATE TABLE t (\n    id INTEGER PRIMARY KEY\n)"), ContentType::Sql);
+        assert_ne!(
+            detect_content_type("Select the file from the menu. Then press save to keep it."),
+            ContentType::Sql
+        );
+
+        let chips = detect_intent("SELECT * FROM orders", &[], &ChipLayout::default(), &[]).chips;
+        assert_eq!(chips[0].payload, "format_sql");
+        assert!(matches!(chips[0].action_type, ActionType::LocalRule));
+    }
+
+    #[test]
+    fn test_detect_color() {
+        let hex = "#1e90ff\n#ff6347";
+        assert_eq!(detect_content_type(hex), ContentType::Color);
+        let payloads: Vec<String> = detect_intent(hex, &[], &ChipLayout::default(), &[]).chips.into_iter().map(|c| c.payload).collect();
+        assert_eq!(payloads, ["color_to_rgb", "color_to_hsl", "color_palette"]);
+
+        let mixed = "#1e90ff\nrgb(255, 99, 71)\nhsl(0, 100%, 50%)";
+        let payloads = |max_chips: usize| -> Vec<String> {
+            let layout = ChipLayout { max_chips, ..ChipLayout::default() };
+            detect_intent(mixed, &[], &layout, &[]).chips.into_iter().map(|c| c.payload).collect()
+        };
+        assert_eq!(payloads(3), ["color_to_hex", "color_to_rgb", "color_palette"]);
+        assert_eq!(payloads(5), ["color_to_hex", "color_to_rgb", "color_to_hsl", "color_palette"]);
+
+        let rgb = "rgb(30, 144, 255)";
+        assert_eq!(detect_intent(rgb, &[], &ChipLayout::default(), &[]).chips[0].payload, "color_to_hex");
+        assert_ne!(detect_content_type("background: #fff;"), ContentType::Color);
+    }
+
+    #[test]
+    fn test_detect_timestamp() {
+        for text in ["1700000000", "1700000000000\n1700000001000", "2023-11-14T22:13:20Z"] {
+            assert_eq!(detect_content_type(text), ContentType::Timestamp, "{}", text);
+        }
+        assert_eq!(detect_intent("1700000000", &[], &ChipLayout::default(), &[]).chips[0].payload, "convert_timestamp");
+        assert_ne!(detect_content_type("4155551234"), ContentType::Timestamp);
+    }
+
+    #[test]
+    fn test_detect_identifier() {
+        let ids = "550e8400-e29b-41d4-a716-446655440000\n6ba7b810-9dad-11d1-80b4-00c04fd430c8";
+        assert_eq!(detect_content_type(ids), ContentType::Identifier);
+        let payloads: Vec<String> = detect_intent(ids, &[], &ChipLayout::default(), &[]).chips.into_iter().map(|c| c.payload).collect();
+        assert_eq!(payloads, ["new_uuid", "toggle_case", "identify_ids"]);
+
+        let sha = "da39a3ee5e6b4b0d3255bfef95601890afd80709";
+        assert_eq!(detect_content_type(sha), ContentType::Identifier);
+        assert_eq!(detect_intent(sha, &[], &ChipLayout::default(), &[]).chips[0].payload, "toggle_case");
+
+        assert_ne!(detect_content_type("1234567"), ContentType::Identifier);
+    }
+
+    #[test]
+    fn test_detect_math() {
+        assert_eq!(detect_content_type("1499*1.13"), ContentType::Math);
+        assert_eq!(detect_content_type("72 inches in cm"), ContentType::Math);
+        let chips = detect_intent("1499*1.13", &[], &ChipLayout::default(), &[]).chips;
+        assert_eq!(chips[0].label, "= 1693.87");
+        assert_eq!(chips[0].payload, "calculate");
+
+        assert_ne!(detect_content_type("2024-06-01"), ContentType::Math);
+    }
+
+    #[test]
+    fn test_detect_url() {
+        let url = "https://example.com/post?id=3&utm_source=newsletter";
+        assert_eq!(detect_content_type(url), ContentType::Url);
+        let layout = ChipLayout { max_chips: MAX_CHIPS_LIMIT, ..ChipLayout::default() };
+        let payloads: Vec<String> = detect_intent(url, &[], &layout, &[]).chips.into_iter().map(|c| c.payload).collect();
+        assert_eq!(payloads, ["make_markdown_links", "make_citation", "strip_tracking_params", "qr_code", "copy_qr_code"]);
+
+        let urls = "https://example.com\nhttps://rust-lang.org";
+        let payloads: Vec<String> = detect_intent(urls, &[], &ChipLayout::default(), &[]).chips.into_iter().map(|c| c.payload).collect();
+        assert_eq!(payloads, ["make_markdown_links", "make_citation"]);
+
+        assert_ne!(detect_content_type("read https://example.com"), ContentType::Url);
+    }
+
+    #[test]
+    fn test_detect_mojibake() {
+        for garbled in ["ÕâÊÇÒ»¶Î´Ó¾ÉÏµÍ³¸´ÖÆ³öÀ´µÄÖÐÎÄÎÄ±¾", "浣犲ソ涓栫晫"] {
+            let chips = detect_intent(garbled, &[], &ChipLayout::default(), &[]).chips;
+            assert_eq!(chips[0].payload, "fix_encoding", "{}", garbled);
+        }
+    }
+
+    #[test]
+    fn test_detect_path() {
+        let windows = "C:\\Users\\me\\report.docx\nD:\\backup";
+        assert_eq!(detect_content_type(windows), ContentType::Path);
+        let payloads: Vec<String> = detect_intent(windows, &[], &ChipLayout::default(), &[]).chips.into_iter().map(|c| c.payload).collect();
+        assert_eq!(payloads, ["paths_to_posix", "shell_quote_paths", "extract_file_names"]);
+
+        assert_eq!(detect_intent("/etc/hosts", &[], &ChipLayout::default(), &[]).chips[0].payload, "paths_to_windows");
+        assert_eq!(detect_intent("~/rec/call.mp3", &[], &ChipLayout::default(), &[]).chips[0].payload, "transcribe_audio");
+        let chips = detect_intent("D:\\Downloads\\setup.iso", &[], &ChipLayout { max_chips: MAX_CHIPS_LIMIT, ..ChipLayout::default() }, &[]).chips;
+        assert_eq!(chips[0].payload, "hash_sha256");
+        assert!(chips.iter().any(|c| c.payload == "hash_crc32"));
+        let chips = detect_intent("~/src/flow-paste", &[], &ChipLayout { max_chips: MAX_CHIPS_LIMIT, ..ChipLayout::default() }, &[]).chips;
+        assert!(chips.iter().any(|c| c.payload == "generate_commit_message"));
+    }
+
+    #[test]
+    fn test_detect_base64() {
+        let text = "VGhlIHF1aWNrIGJyb3duIGZveCBqdW1wcyBvdmVyIHRoZSBsYXp5IGRvZyAxMjM=";
+        assert_eq!(detect_content_type(text), ContentType::Base64);
+        let chips = detect_intent(text, &[], &ChipLayout::default(), &[]).chips;
+        assert_eq!(chips[0].payload, "decode_base64");
+
+        let png = "data:image/png;base64,iVBORw0KGgoAAAANSUhEUgAAAAEAAAABCAYAAAAfFcSJAAAADUlEQVR42mNk";
+        assert_eq!(detect_content_type(png), ContentType::Base64);
+        let chips = detect_intent(png, &[], &ChipLayout::default(), &[]).chips;
+        assert_eq!(chips[0].action_type, ActionType::Command);
+
+        // Long identifiers aren't Base64
+        assert_ne!(detect_content_type("supercalifragilisticexpialidocious_word"), ContentType::Base64);
+    }
+
+    #[test]
+    fn test_detect_diff() {
+        let git = "diff --git a/a.py b/a.py\n--- a/a.py\n+++ b/a.py\n@@ -1 +1 @@\n-import os\n+import sys";
+        assert_eq!(detect_content_type(git), ContentType::Diff);
+        let hunk_only = "@@ -10,2 +10,2 @@ fn main() {\n-    let x = 1;\n+    let x = 2;";
+        assert_eq!(detect_content_type(hunk_only), ContentType::Diff);
+
+        let chips = detect_intent(git, &[], &ChipLayout::default(), &[]).chips;
+        assert_eq!(chips[1].payload, "generate_commit_message");
+        assert_eq!(chips[2].payload, "strip_diff_markers");
+    }
+
+    #[test]
+    fn test_detect_email() {
+        let thread = "Works for me.\n\nOn Mon, 3 Jun 2024 at 10:00, Ann <ann@example.com> wrote:\n> Shall we meet at noon?";
+        assert_eq!(detect_content_type(thread), ContentType::Email);
+        let payloads: Vec<String> = detect_intent(thread, &[], &ChipLayout::default(), &[]).chips.into_iter().map(|c| c.payload).collect();
+        assert_eq!(payloads[2], "strip_quoted_text");
+        assert_eq!(detect_intent(thread, &[], &ChipLayout::default(), &[]).language.as_deref(), Some("en"));
+    }
+
+    #[test]
+    fn test_detect_log() {
+        let java = "java.lang.NullPointerException: name\n\tat com.example.App.run(App.java:12)\n\tat com.example.App.main(App.java:5)";
+        let python = "Traceback (most recent call last):\n  File \"main.py\", line 3, in <module>\n    import missing\nModuleNotFoundError: No module named 'missing'";
+        let rust = "thread 'main' panicked at src/main.rs:4:5:\nindex out of bounds";
+        let log = "2024-05-01 10:00:00 INFO started\n2024-05-01 10:00:01 WARN slow query\n2024-05-01 10:00:02 ERROR timeout";
+        for text in [java, python, rust, log] {
+            assert_eq!(detect_content_type(text), ContentType::Log, "{}", text);
+        }
+
+        let chips = detect_intent(log, &[], &ChipLayout::default(), &[]).chips;
+        assert_eq!(chips[1].payload, "extract_errors");
+    }
+
+    #[test]
+    fn test_detect_table() {
+        let table = "name\tage\nAlice\t30\nBob\t25";
+        assert_eq!(detect_content_type(table), ContentType::Table);
+    }
+
+    #[test]
+    fn test_detect_markdown_table() {
+        let table = "| name | age |\n| --- | --- |\n| Alice | 30 |";
+        assert_eq!(detect_content_type(table), ContentType::Table);
+    }
+
+    #[test]
+    fn test_generate_chips_table_local_rule() {
+        let chips = detect_intent("name\tage\nAlice\t30\nBob\t25", &[], &ChipLayout::default(), &[]).chips;
+        assert!(matches!(chips[0].action_type, ActionType::LocalRule));
+        assert_eq!(chips[0].payload, "to_markdown_table");
+
+        let chips = detect_intent("| name | age |\n| --- | --- |\n| Alice | 30 |", &[], &ChipLayout::default(), &[]).chips;
+        assert_eq!(chips[0].payload, "markdown_table_to_tsv");
+        assert_eq!(chips[1].payload, "extract_column:1");
+    }
+
+    #[test]
+    fn test_table_info_in_intent() {
+        let intent = detect_intent("name,age,city\nAlice,30,Paris\nBob,25,Rome", &[], &ChipLayout::default(), &[]);
+        let table = intent.table.unwrap();
+        assert_eq!(table.format, TableFormat::Csv);
+        assert!(table.has_header);
+        assert_eq!(table.columns, ["name", "age", "city"]);
+        assert_eq!(table.row_count, 2);
+
+        // Two fields per line reads as prose, not CSV
+        assert_ne!(detect_content_type("Hello, world\nGood, bye"), ContentType::Table);
+        assert_eq!(detect_intent("plain words", &[], &ChipLayout::default(), &[]).table, None);
+    }
+
+    #[test]
+    fn test_detect_list() {
+        let list = "- Item 1\n- Item 2\n- Item 3";
+        assert_eq!(detect_content_type(list), ContentType::List);
+    }
+
+    #[test]
+    fn test_detect_prose() {
+        let prose = "This is a long sentence. And here is another one. This should be detected as prose.";
+        assert_eq!(detect_content_type(prose), ContentType::Prose);
+    }
+
+    #[test]
+    fn test_mixed_content_ranks_and_merges() {
+        let text = "The parser keeps failing on empty input. I think the guard below is wrong, \
+                    can you take a look before the release?\n\
+                    const empty = input.length === 0;\n\
+                    Thanks for the help, it's much appreciated.";
+        let result = detect_intent(text, &[], &ChipLayout::default(), &[]);
+        assert_eq!(result.content_type, ContentType::Prose);
+        let types: Vec<ContentType> = result.candidates.iter().map(|c| c.content_type).collect();
+        assert_eq!(types, [ContentType::Prose, ContentType::Code]);
+        assert!(result.candidates[0].confidence > result.candidates[1].confidence);
+        // The last slot goes to the best code chip
+        assert_eq!(result.chips[2].label, tr("chip.add_comments"));
+        assert!(result.language.is_some());
+    }
+
+    #[test]
+    fn test_sentence_mentioning_keywords_is_not_code() {
+        let text = "Let me know when you import the data. I will let you know what I find.";
+        assert_eq!(detect_content_type(text), ContentType::Prose);
+        assert_eq!(rank_content_types(text).len(), 1);
+    }
+
+    #[test]
+    fn test_generate_chips_json() {
+        let chips = detect_intent(r#"{"test": 1}"#, &[], &ChipLayout::default(), &[]).chips;
+        assert_eq!(chips.len(), 3);
+        assert!(chips[0].label.contains("JSON"));
+    }
+
+    #[test]
+    fn test_generate_chips_code() {
+        let chips = detect_intent("function test() {\n    return 1;\n}", &[], &ChipLayout::default(), &[]).chips;
+        assert_eq!(chips.len(), 3);
+        assert!(chips.iter().any(|c| c.label.contains("注释") || c.label.contains("重构")));
+    }
+
+    fn custom(matcher: IntentMatcher, payload: &str) -> CustomIntent {
+        CustomIntent {
+            id: payload.to_string(),
+            matcher,
+            label: "Custom".to_string(),
+            action_type: ActionType::AIPrompt,
+            payload: payload.to_string(),
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn test_custom_intents_come_first() {
+        let intents = [
+            custom(IntentMatcher::Regex { pattern: r"JIRA-\d+".to_string() }, "Link the ticket"),
+            custom(IntentMatcher::ContentType { content_type: ContentType::Json }, "Describe the schema"),
+        ];
+
+        let chips = detect_intent(r#"{"ticket": "JIRA-42"}"#, &intents, &ChipLayout::default(), &[]).chips;
+        assert_eq!(chips.len(), 3);
+        assert_eq!(chips[0].payload, "Link the ticket");
+        assert_eq!(chips[0].action_id, "custom:Link the ticket");
+        assert_eq!(chips[1].payload, "Describe the schema");
+        assert!(chips[2].label.contains("JSON"));
+        assert_eq!(chips[2].action_id, "format_json");
+        assert_eq!(chips[2].id, "json.format_json");
+        assert_eq!(chips[2].shortcut.as_deref(), Some("3"));
+
+        let chips = detect_intent("no match here", &intents, &ChipLayout::default(), &[]).chips;
+        assert!(chips.iter().all(|c| c.label != "Custom"));
+    }
+
+    #[test]
+    fn test_chip_ids_are_stable_and_handles_are_not() {
+        let first = detect_intent("- b\n- a\n- c", &[], &ChipLayout::default(), &[]).chips;
+        let second = detect_intent("- b\n- a\n- c", &[], &ChipLayout::default(), &[]).chips;
+        assert_eq!(first[0].id, "list.sort_list");
+        assert!(first.iter().zip(&second).all(|(a, b)| a.id == b.id && a.handle != b.handle));
+    }
+
+    #[test]
+    fn test_custom_intent_replaces_same_builtin() {
+        let mut intent = custom(IntentMatcher::ContentType { content_type: ContentType::List }, "sort_list");
+        intent.action_type = ActionType::LocalRule;
+        let chips = detect_intent("- b\n- a", &[intent], &ChipLayout::default(), &[]).chips;
+        assert_eq!(chips.iter().filter(|c| c.payload == "sort_list").count(), 1);
+        assert_eq!(chips[0].label, "Custom");
+    }
+
+    #[test]
+    fn test_disabled_and_invalid_intents_are_skipped() {
+        let mut disabled = custom(IntentMatcher::Regex { pattern: ".".to_string() }, "disabled");
+        disabled.enabled = false;
+        let invalid = custom(IntentMatcher::Regex { pattern: "(".to_string() }, "invalid");
+        assert!(invalid.validate().is_err());
+
+        let chips = detect_intent("text", &[disabled, invalid], &ChipLayout::default(), &[]).chips;
+        assert!(chips.iter().all(|c| c.label != "Custom"));
+    }
+
+    #[test]
+    fn test_custom_pattern_compiles_once() {
+        let pattern = r"ORDER-\d{6}-cache";
+        let first = custom_pattern(pattern).unwrap();
+        assert!(CUSTOM_PATTERNS.lock().unwrap().contains_key(pattern));
+        assert_eq!(custom_pattern(pattern).unwrap().as_str(), first.as_str());
+        assert!(custom_pattern("(").is_none());
+        assert!(CUSTOM_PATTERNS.lock().unwrap().get("(").is_some_and(Option::is_none));
+    }
+
+    #[test]
+    fn test_add_notes_chip() {
+        let mut result = detect_intent("https://example.com", &[], &ChipLayout::default(), &[]);
+        add_notes_chip(&mut result, DEFAULT_MAX_CHIPS);
+        assert_eq!(result.chips.len(), DEFAULT_MAX_CHIPS);
+        let last = result.chips.last().unwrap();
+        assert_eq!(last.payload, NOTES_COMMAND);
+        assert_eq!(last.shortcut.as_deref(), Some("3"));
+
+        add_notes_chip(&mut result, DEFAULT_MAX_CHIPS);
+        assert_eq!(result.chips.iter().filter(|c| c.payload == NOTES_COMMAND).count(), 1);
+    }
+
+    #[test]
+    fn test_translation_follows_language() {
+        let zh = detect_intent("今天我们讨论了新版本的发布计划。大家都同意下周上线！请准备好相关文档和测试报告。", &[], &ChipLayout::default(), &[]);
+        assert_eq!(zh.content_type, ContentType::Prose);
+        assert_eq!(zh.language.as_deref(), Some("zh"));
+        assert!(zh.chips.iter().any(|c| c.payload == "Translate this text to English"));
+
+        let en = detect_intent(
+            "We discussed the release plan for the new version today. Everyone agreed to ship next week! Please prepare the documents.",
+            &[],
+            &ChipLayout::default(),
+            &[],
+        );
+        assert_eq!(en.language.as_deref(), Some("en"));
+        assert!(en.chips.iter().any(|c| c.payload == "Translate this text to Chinese"));
+        assert_eq!(translation_target("今天我们讨论了新版本的发布计划。大家都同意下周上线！"), Locale::En);
+
+        // With a translation service the chip stays, but runs the command
+        let mut zh = zh;
+        use_translation_command(&mut zh);
+        let chip = zh.chips.iter().find(|c| c.action_id == "translate_en").unwrap();
+        assert_eq!(chip.action_type, ActionType::Command);
+        assert_eq!(chip.payload, TRANSLATE_COMMAND);
+    }
+
+    #[test]
+    fn test_chip_layout_orders_hides_and_limits() {
+        let ids = "550e8400-e29b-41d4-a716-446655440000";
+        let layout = ChipLayout {
+            max_chips: 2,
+            types: vec![ContentTypeChips {
+                content_type: ContentType::Identifier,
+                max_chips: None,
+                order: vec!["identify_ids".to_string()],
+                hidden: vec!["toggle_case".to_string()],
+            }],
+        };
+        let payloads: Vec<String> = detect_intent(ids, &[], &layout, &[]).chips.into_iter().map(|c| c.payload).collect();
+        assert_eq!(payloads, ["identify_ids", "new_uuid"]);
+
+        // Per-type limit wins, and other types keep the global one
+        let layout = ChipLayout {
+            max_chips: 2,
+            types: vec![ContentTypeChips {
+                content_type: ContentType::Identifier,
+                max_chips: Some(1),
+                order: Vec::new(),
+                hidden: Vec::new(),
+            }],
+        };
+        assert_eq!(detect_intent(ids, &[], &layout, &[]).chips.len(), 1);
+        assert_eq!(detect_intent("- b\n- a\n- c", &[], &layout, &[]).chips.len(), 2);
+    }
+
+    #[test]
+    fn test_usage_ranks_chips() {
+        let now = chrono::Utc::now().timestamp_millis();
+        let day = 24 * 60 * 60 * 1000;
+        let used = |action_id: &str, score: f64, last_used: i64| ChipUsage {
+            content_type: ContentType::Identifier,
+            action_id: action_id.to_string(),
+            score,
+            last_used,
+        };
+        let ids = "550e8400-e29b-41d4-a716-446655440000";
+        let payloads = |usage: &[ChipUsage]| -> Vec<String> {
+            detect_intent(ids, &[], &ChipLayout::default(), usage).chips.into_iter().map(|c| c.payload).collect()
+        };
+
+        // Picked often long ago loses to picked a few times lately
+        let usage = [used("toggle_case", 20.0, now - 90 * day), used("identify_ids", 3.0, now - day)];
+        assert_eq!(payloads(&usage), ["identify_ids", "toggle_case", "new_uuid"]);
+
+        // Usage for another content type doesn't count
+        let mut other = used("identify_ids", 50.0, now);
+        other.content_type = ContentType::Json;
+        assert_eq!(payloads(&[other]), ["new_uuid", "toggle_case", "identify_ids"]);
+    }
+
+    #[test]
+    fn test_decayed_score_halves_every_two_weeks() {
+        let two_weeks = 14 * 24 * 60 * 60 * 1000;
+        assert!((decayed_score(4.0, 0, two_weeks) - 2.0).abs() < 1e-9);
+        assert_eq!(decayed_score(4.0, 10, 5), 4.0);
+    }
+
+    #[test]
+    fn test_structured_content_has_no_language() {
+        assert_eq!(detect_intent(r#"{"greeting": "hello world"}"#, &[], &ChipLayout::default(), &[]).language, None);
+    }
+
+    #[test]
+    fn test_performance() {
+        let text = "This is a test text with multiple sentences. It should be processed quickly.";
+        let start = std::time::Instant::now();
+        let _chips = detect_intent(text, &[], &ChipLayout::default(), &[]).chips;
+        let elapsed = start.elapsed();
+        assert!(elapsed.as_millis() < 10, "Intent detection took {}ms", elapsed.as_millis());
+    }
+}
@@ -0,0 +1,48 @@
+use whatlang::{Lang, Script};
+
+/// Language of `text` as an ISO 639-1 code where one exists ("zh", "en"),
+/// otherwise whatlang's ISO 639-3 code. `None` when detection is unsure.
+pub fn detect_language(text: &str) -> Option<&'static str> {
+    let info = whatlang::detect(text)?;
+    // Han script alone is conclusive; short Latin text often isn't
+    if !info.is_reliable() && info.script() != Script::Mandarin {
+        return None;
+    }
+    Some(short_code(info.lang()))
+}
+
+fn short_code(lang: Lang) -> &'static str {
+    match lang {
+        Lang::Cmn => "zh",
+        Lang::Eng => "en",
+        Lang::Jpn => "ja",
+        Lang::Kor => "ko",
+        Lang::Fra => "fr",
+        Lang::Deu => "de",
+        Lang::Spa => "es",
+        Lang::Por => "pt",
+        Lang::Ita => "it",
+        Lang::Rus => "ru",
+        other => other.code(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_chinese_and_english() {
+        assert_eq!(detect_language("今天天气很好，我们一起去公园散步吧。"), Some("zh"));
+        assert_eq!(
+            detect_language("The weather is lovely today, so let's take a walk in the park together."),
+            Some("en")
+        );
+    }
+
+    #[test]
+    fn test_unsure_is_none() {
+        assert_eq!(detect_language(""), None);
+        assert_eq!(detect_language("ok"), None);
+    }
+}
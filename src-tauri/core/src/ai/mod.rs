@@ -0,0 +1,17 @@
+mod http;
+mod provider;
+mod ollama;
+mod openai;
+mod types;
+pub mod intent;
+pub mod classify;
+pub mod chunk;
+pub mod commit;
+mod language;
+
+pub use http::{build_client, client_builder, HttpSettings};
+pub use provider::{complete, AiProvider};
+pub use ollama::OllamaProvider;
+pub use openai::OpenAIProvider;
+pub use types::*;
+pub use intent::{detect_intent, ActionChip, ChipLayout, ChipUsage, CustomIntent, IntentResult};
@@ -1,25 +1,35 @@
 use async_trait::async_trait;
 use futures::StreamExt;
 use reqwest::Client;
+use std::sync::RwLock;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 
+use super::http::{build_client, HttpSettings};
 use super::provider::AiProvider;
 use super::types::{AIConfig, AIError, AIProviderType, ChatMessage, ModelInfo, StreamChunk};
 
 pub struct OllamaProvider {
-    client: Client,
+    // Replaced when proxy/TLS settings change
+    client: RwLock<Client>,
 }
 
 impl OllamaProvider {
     pub fn new() -> Self {
         Self {
-            client: Client::builder()
-                .timeout(std::time::Duration::from_secs(120))
-                .build()
-                .expect("Failed to create HTTP client"),
+            client: RwLock::new(
+                build_client(&HttpSettings::default()).expect("Failed to create HTTP client"),
+            ),
         }
     }
+
+    pub fn set_client(&self, client: Client) {
+        *self.client.write().unwrap() = client;
+    }
+
+    fn client(&self) -> Client {
+        self.client.read().unwrap().clone()
+    }
 }
 
 impl Default for OllamaProvider {
@@ -40,6 +50,10 @@ struct OllamaGenerateRequest {
 struct OllamaOptions {
     temperature: f32,
     num_predict: u32,
+    /// Ollama's own default window is small and silently drops the start
+    /// of longer prompts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    num_ctx: Option<u32>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -81,17 +95,17 @@ impl AiProvider for OllamaProvider {
             options: OllamaOptions {
                 temperature: config.temperature,
                 num_predict: config.max_tokens,
+                num_ctx: config.context_tokens,
             },
         };
 
         let url = format!("{}/api/generate", config.base_url.trim_end_matches('/'));
 
-        let response = self
-            .client
-            .post(&url)
-            .json(&request)
-            .send()
-            .await?;
+        let mut builder = self.client().post(&url).json(&request);
+        if let Some(secs) = config.timeout_secs {
+            builder = builder.timeout(std::time::Duration::from_secs(secs));
+        }
+        let response = builder.send().await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -146,7 +160,7 @@ impl AiProvider for OllamaProvider {
         let url = format!("{}/api/tags", config.base_url.trim_end_matches('/'));
 
         let response = self
-            .client
+            .client()
             .get(&url)
             .timeout(std::time::Duration::from_secs(5))
             .send()
@@ -175,7 +189,7 @@ impl AiProvider for OllamaProvider {
         let url = format!("{}/api/tags", config.base_url.trim_end_matches('/'));
 
         let response = self
-            .client
+            .client()
             .get(&url)
             .timeout(std::time::Duration::from_secs(5))
             .send()
@@ -1,25 +1,35 @@
 use async_trait::async_trait;
 use futures::StreamExt;
 use reqwest::Client;
+use std::sync::RwLock;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 
+use super::http::{build_client, HttpSettings};
 use super::provider::AiProvider;
 use super::types::{AIConfig, AIError, AIProviderType, ChatMessage, ModelInfo, StreamChunk};
 
 pub struct OpenAIProvider {
-    client: Client,
+    // Replaced when proxy/TLS settings change
+    client: RwLock<Client>,
 }
 
 impl OpenAIProvider {
     pub fn new() -> Self {
         Self {
-            client: Client::builder()
-                .timeout(std::time::Duration::from_secs(120))
-                .build()
-                .expect("Failed to create HTTP client"),
+            client: RwLock::new(
+                build_client(&HttpSettings::default()).expect("Failed to create HTTP client"),
+            ),
         }
     }
+
+    pub fn set_client(&self, client: Client) {
+        *self.client.write().unwrap() = client;
+    }
+
+    fn client(&self) -> Client {
+        self.client.read().unwrap().clone()
+    }
 }
 
 impl Default for OpenAIProvider {
@@ -101,14 +111,16 @@ impl AiProvider for OpenAIProvider {
             config.base_url.trim_end_matches('/')
         );
 
-        let response = self
-            .client
+        let mut builder = self
+            .client()
             .post(&url)
             .header("Authorization", format!("Bearer {}", api_key))
             .header("Content-Type", "application/json")
-            .json(&request)
-            .send()
-            .await?;
+            .json(&request);
+        if let Some(secs) = config.timeout_secs {
+            builder = builder.timeout(std::time::Duration::from_secs(secs));
+        }
+        let response = builder.send().await?;
 
         if response.status() == reqwest::StatusCode::UNAUTHORIZED {
             return Err(AIError::AuthenticationFailed);
@@ -181,7 +193,7 @@ impl AiProvider for OpenAIProvider {
         let url = format!("{}/models", config.base_url.trim_end_matches('/'));
 
         let response = self
-            .client
+            .client()
             .get(&url)
             .header("Authorization", format!("Bearer {}", api_key))
             .timeout(std::time::Duration::from_secs(10))
@@ -240,6 +252,8 @@ mod tests {
             api_key: std::env::var("OPENAI_API_KEY").ok(),
             max_tokens: 2048,
             temperature: 0.7,
+            timeout_secs: None,
+            context_tokens: None,
         };
         let result = provider.health_check(&config).await;
         println!("OpenAI health check: {:?}", result);
@@ -0,0 +1,52 @@
+use async_trait::async_trait;
+use std::time::Duration;
+use tokio::sync::mpsc;
+
+use super::types::{AIConfig, AIError, ChatMessage, ModelInfo, StreamChunk};
+
+#[async_trait]
+pub trait AiProvider: Send + Sync {
+    async fn send_stream(
+        &self,
+        messages: Vec<ChatMessage>,
+        config: &AIConfig,
+        tx: mpsc::Sender<Result<StreamChunk, AIError>>,
+    ) -> Result<(), AIError>;
+
+    async fn list_models(&self, config: &AIConfig) -> Result<Vec<ModelInfo>, AIError>;
+
+    async fn health_check(&self, config: &AIConfig) -> Result<bool, AIError>;
+}
+
+/// Upper bound for a non-streaming completion.
+const COMPLETION_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Run a request to completion and return the full response, for callers
+/// that don't stream to the UI.
+pub async fn complete(
+    provider: &dyn AiProvider,
+    messages: Vec<ChatMessage>,
+    config: &AIConfig,
+) -> Result<String, AIError> {
+    let (tx, mut rx) = mpsc::channel::<Result<StreamChunk, AIError>>(100);
+
+    let send = provider.send_stream(messages, config, tx);
+    let collect = async {
+        let mut content = String::new();
+        while let Some(chunk) = rx.recv().await {
+            let chunk = chunk?;
+            content.push_str(&chunk.content);
+            if chunk.done {
+                break;
+            }
+        }
+        Ok::<_, AIError>(content)
+    };
+
+    let limit = config.timeout_secs.map(Duration::from_secs).unwrap_or(COMPLETION_TIMEOUT);
+    let (sent, collected) = tokio::time::timeout(limit, async { tokio::join!(send, collect) })
+        .await
+        .map_err(|_| AIError::Timeout)?;
+    sent?;
+    collected
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::i18n::tr;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AIConfig {
@@ -10,6 +12,12 @@ pub struct AIConfig {
     pub api_key: Option<String>,
     pub max_tokens: u32,
     pub temperature: f32,
+    /// Overrides the provider's default request timeout
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    /// Context window of the model; long input is split to fit it
+    #[serde(default)]
+    pub context_tokens: Option<u32>,
 }
 
 impl Default for AIConfig {
@@ -21,6 +29,8 @@ impl Default for AIConfig {
             api_key: None,
             max_tokens: 2048,
             temperature: 0.7,
+            timeout_secs: None,
+            context_tokens: None,
         }
     }
 }
@@ -70,6 +80,25 @@ pub enum AIError {
     ParseError(String),
 }
 
+impl AIError {
+    /// Message for the user in the current locale, with the technical detail appended.
+    pub fn localized(&self) -> String {
+        let (key, detail) = match self {
+            AIError::ConnectionFailed(d) => ("ai.connection_failed", Some(d)),
+            AIError::AuthenticationFailed => ("ai.auth_failed", None),
+            AIError::Timeout => ("ai.timeout", None),
+            AIError::ModelNotFound(d) => ("ai.model_not_found", Some(d)),
+            AIError::Cancelled => ("ai.cancelled", None),
+            AIError::ApiError(d) => ("ai.api_error", Some(d)),
+            AIError::ParseError(d) => ("ai.parse_error", Some(d)),
+        };
+        match detail {
+            Some(detail) => format!("{}: {}", tr(key), detail),
+            None => tr(key).to_string(),
+        }
+    }
+}
+
 impl From<reqwest::Error> for AIError {
     fn from(err: reqwest::Error) -> Self {
         if err.is_timeout() {
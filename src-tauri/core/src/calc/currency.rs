@@ -0,0 +1,94 @@
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::RwLock;
+use std::time::Duration;
+
+use super::CalcError;
+use crate::ai::{client_builder, HttpSettings};
+
+/// Daily reference rates published by the European Central Bank
+const RATES_URL: &str = "https://api.frankfurter.dev/v1/latest?base=USD";
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Symbols and names, and the currency they stand for. `¥` is taken as
+/// yuan; yen is `円` or "yen".
+const NAMES: &[(&[&str], &str)] = &[
+    (&["$", "dollar", "dollars"], "USD"),
+    (&["€", "euro", "euros"], "EUR"),
+    (&["£", "sterling"], "GBP"),
+    (&["¥", "￥", "元", "rmb", "yuan"], "CNY"),
+    (&["円", "yen"], "JPY"),
+    (&["₩", "won"], "KRW"),
+    (&["₹", "rupee", "rupees"], "INR"),
+];
+
+/// Rates in use, set by the app once fetched or loaded from its cache.
+static RATES: Lazy<RwLock<Option<CurrencyRates>>> = Lazy::new(|| RwLock::new(None));
+
+/// How much one `base` buys of each other currency, on `date`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyRates {
+    pub base: String,
+    pub date: String,
+    pub rates: HashMap<String, f64>,
+    /// Unix time the rates were downloaded
+    #[serde(default)]
+    pub fetched_at: i64,
+}
+
+impl CurrencyRates {
+    fn rate(&self, code: &str) -> Result<f64, CalcError> {
+        if code == self.base {
+            return Ok(1.0);
+        }
+        self.rates
+            .get(code)
+            .copied()
+            .filter(|rate| *rate > 0.0)
+            .ok_or_else(|| CalcError::UnknownCurrency(code.to_string()))
+    }
+
+    pub fn convert(&self, amount: f64, from: &str, to: &str) -> Result<f64, CalcError> {
+        Ok(amount / self.rate(from)? * self.rate(to)?)
+    }
+}
+
+pub fn set_rates(rates: CurrencyRates) {
+    *RATES.write().unwrap() = Some(rates);
+}
+
+pub fn rates() -> Option<CurrencyRates> {
+    RATES.read().unwrap().clone()
+}
+
+/// ISO code for a currency symbol, name or code: `€`, `yen`, `usd`.
+pub fn currency_code(name: &str) -> Option<String> {
+    let name = name.trim().to_lowercase();
+    if let Some((_, code)) = NAMES.iter().find(|(names, _)| names.contains(&name.as_str())) {
+        return Some(code.to_string());
+    }
+    (name.len() == 3 && name.chars().all(|c| c.is_ascii_alphabetic())).then(|| name.to_uppercase())
+}
+
+/// Today's rates, through the proxy of `settings`.
+pub async fn fetch_rates(settings: &HttpSettings) -> Result<CurrencyRates, CalcError> {
+    let failed = |e: &dyn std::fmt::Display| CalcError::Rates(e.to_string());
+    let client = client_builder(settings)
+        .map_err(|e| failed(&e))?
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .map_err(|e| failed(&e))?;
+    let mut rates: CurrencyRates = client
+        .get(RATES_URL)
+        .send()
+        .await
+        .and_then(|r| r.error_for_status())
+        .map_err(|e| failed(&e))?
+        .json()
+        .await
+        .map_err(|e| failed(&e))?;
+    rates.fetched_at = chrono::Utc::now().timestamp();
+    Ok(rates)
+}
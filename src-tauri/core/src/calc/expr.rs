@@ -0,0 +1,214 @@
+use super::{parse_number, CalcError};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token {
+    Number(f64),
+    Op(char),
+    Open,
+    Close,
+}
+
+fn tokenize(text: &str) -> Result<Vec<Token>, CalcError> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '0'..='9' | '.' => {
+                let mut number = String::new();
+                while let Some(&d) = chars.peek() {
+                    if !(d.is_ascii_digit() || d == '.' || d == ',') {
+                        break;
+                    }
+                    number.push(d);
+                    chars.next();
+                }
+                tokens.push(Token::Number(parse_number(&number).ok_or(CalcError::Unexpected(number))?));
+            }
+            '+' | '-' | '*' | '/' | '^' | '%' => {
+                tokens.push(Token::Op(c));
+                chars.next();
+            }
+            '×' => {
+                tokens.push(Token::Op('*'));
+                chars.next();
+            }
+            '÷' => {
+                tokens.push(Token::Op('/'));
+                chars.next();
+            }
+            '(' => {
+                tokens.push(Token::Open);
+                chars.next();
+            }
+            ')' => {
+                tokens.push(Token::Close);
+                chars.next();
+            }
+            c if c.is_alphabetic() => {
+                let mut word = String::new();
+                while let Some(&d) = chars.peek() {
+                    if !d.is_alphabetic() {
+                        break;
+                    }
+                    word.push(d);
+                    chars.next();
+                }
+                // "3 x 4", "15% of 200"
+                match word.to_lowercase().as_str() {
+                    "x" | "of" => tokens.push(Token::Op('*')),
+                    _ => return Err(CalcError::Unexpected(word)),
+                }
+            }
+            _ => return Err(CalcError::Unexpected(c.to_string())),
+        }
+    }
+    Ok(tokens)
+}
+
+/// A number, and whether it was written as a percentage.
+#[derive(Debug, Clone, Copy)]
+struct Value {
+    n: f64,
+    percent: bool,
+}
+
+impl Value {
+    fn number(n: f64) -> Self {
+        Self { n, percent: false }
+    }
+}
+
+/// Deepest nesting of parentheses, signs and powers; any deeper is rejected
+/// before it can overflow the stack.
+const MAX_DEPTH: usize = 64;
+
+/// Recursive descent over `+ - * / ^`, parentheses, signs and `%` as a percentage.
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    fn eat_op(&mut self, ops: &[char]) -> Option<char> {
+        match self.peek() {
+            Some(Token::Op(op)) if ops.contains(&op) => {
+                self.pos += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    fn expr(&mut self) -> Result<Value, CalcError> {
+        let mut value = self.term()?;
+        while let Some(op) = self.eat_op(&['+', '-']) {
+            let rhs = self.term()?;
+            // 200 + 15% adds 15% of 200
+            let amount = if rhs.percent && !value.percent { value.n * rhs.n } else { rhs.n };
+            value = Value {
+                n: if op == '+' { value.n + amount } else { value.n - amount },
+                percent: value.percent && rhs.percent,
+            };
+        }
+        Ok(value)
+    }
+
+    fn term(&mut self) -> Result<Value, CalcError> {
+        let mut value = self.unary()?;
+        while let Some(op) = self.eat_op(&['*', '/']) {
+            let rhs = self.unary()?;
+            if op == '/' && rhs.n == 0.0 {
+                return Err(CalcError::DivisionByZero);
+            }
+            value = Value::number(if op == '*' { value.n * rhs.n } else { value.n / rhs.n });
+        }
+        Ok(value)
+    }
+
+    // Every nested `(`, sign and `^` comes back through here
+    fn unary(&mut self) -> Result<Value, CalcError> {
+        if self.depth == MAX_DEPTH {
+            return Err(CalcError::Syntax);
+        }
+        self.depth += 1;
+        let value = self.signed();
+        self.depth -= 1;
+        value
+    }
+
+    // Signs bind looser than `^`: -2^2 = -4
+    fn signed(&mut self) -> Result<Value, CalcError> {
+        match self.eat_op(&['-', '+']) {
+            Some('-') => {
+                let value = self.unary()?;
+                Ok(Value { n: -value.n, ..value })
+            }
+            Some(_) => self.unary(),
+            None => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<Value, CalcError> {
+        let base = self.percent()?;
+        if self.eat_op(&['^']).is_some() {
+            // Right-associative: 2^3^2 = 2^9
+            return Ok(Value::number(base.n.powf(self.unary()?.n)));
+        }
+        Ok(base)
+    }
+
+    fn percent(&mut self) -> Result<Value, CalcError> {
+        let value = self.primary()?;
+        if self.eat_op(&['%']).is_some() {
+            return Ok(Value { n: value.n / 100.0, percent: true });
+        }
+        Ok(value)
+    }
+
+    fn primary(&mut self) -> Result<Value, CalcError> {
+        let token = self.peek().ok_or(CalcError::Syntax)?;
+        self.pos += 1;
+        match token {
+            Token::Number(n) => Ok(Value::number(n)),
+            Token::Open => {
+                let value = self.expr()?;
+                if self.peek() != Some(Token::Close) {
+                    return Err(CalcError::Syntax);
+                }
+                self.pos += 1;
+                Ok(value)
+            }
+            _ => Err(CalcError::Syntax),
+        }
+    }
+}
+
+/// Value of an arithmetic expression; a lone number is its own value.
+pub(super) fn evaluate(text: &str) -> Result<f64, CalcError> {
+    let mut parser = Parser { tokens: tokenize(text)?, pos: 0, depth: 0 };
+    let value = parser.expr()?;
+    if parser.pos != parser.tokens.len() {
+        return Err(CalcError::Syntax);
+    }
+    Ok(value.n)
+}
+
+/// Whether `text` is a calculation rather than a lone number, or digits
+/// joined by dashes like a phone number or date.
+pub(super) fn is_sum(text: &str) -> bool {
+    let Ok(tokens) = tokenize(text) else {
+        return false;
+    };
+    let has_op = tokens.iter().any(|t| matches!(t, Token::Op(op) if *op != '%'));
+    let dashes_only =
+        !text.contains(' ') && tokens.iter().all(|t| matches!(t, Token::Number(_) | Token::Op('-')));
+    has_op && !dashes_only
+}
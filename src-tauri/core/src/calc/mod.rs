@@ -0,0 +1,259 @@
+//! Calculator behind the math chips and the `evaluate` command: arithmetic
+//! with percentages, and conversions between units or, with the exchange
+//! rates the app caches, currencies.
+
+pub mod currency;
+mod expr;
+mod units;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use thiserror::Error;
+
+pub use currency::CurrencyRates;
+
+// "72 inches in cm", "-40 °F to C", "$25 in EUR", "(3 + 4) usd to ¥"
+static CONVERSION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?i)^\s*([$€£¥￥₩₹])?\s*(.+?)\s*([a-z°]+(?:\s?[a-z]+)?|[$€£¥￥₩₹元円])?\s+(?:in|to|as|into|->|=>)\s+([a-z°]+(?:\s?[a-z]+)?|[$€£¥￥₩₹元円])\s*$",
+    )
+    .unwrap()
+});
+
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum CalcError {
+    #[error("enter one expression on a single line")]
+    NotOneLine,
+    #[error("not a valid expression")]
+    Syntax,
+    #[error("unexpected \"{0}\"")]
+    Unexpected(String),
+    #[error("division by zero")]
+    DivisionByZero,
+    #[error("the result is too large")]
+    NotFinite,
+    #[error("unknown unit or currency \"{0}\"")]
+    UnknownUnit(String),
+    #[error("can't convert {0} to {1}")]
+    Incompatible(String, String),
+    #[error("exchange rates haven't been downloaded yet")]
+    NoRates,
+    #[error("no exchange rate for {0}")]
+    UnknownCurrency(String),
+    #[error("failed to fetch exchange rates: {0}")]
+    Rates(String),
+}
+
+/// The answer to an expression.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Evaluation {
+    pub value: f64,
+    /// Unit or currency code of a conversion's result
+    pub unit: Option<String>,
+    /// `value` formatted for display, with its unit
+    pub text: String,
+}
+
+enum Unit {
+    Measure(units::Measure),
+    Currency(String),
+}
+
+fn find_unit(name: &str) -> Result<Unit, CalcError> {
+    if let Some(measure) = units::find_measure(name) {
+        return Ok(Unit::Measure(measure));
+    }
+    currency::currency_code(name)
+        .map(Unit::Currency)
+        .ok_or_else(|| CalcError::UnknownUnit(name.to_string()))
+}
+
+/// "<amount> <unit> in <unit>", the amount being any expression.
+struct Conversion<'a> {
+    amount: &'a str,
+    from: &'a str,
+    to: &'a str,
+}
+
+fn parse_conversion(text: &str) -> Option<Conversion<'_>> {
+    let caps = CONVERSION.captures(text)?;
+    Some(Conversion {
+        amount: caps.get(2)?.as_str(),
+        from: caps.get(1).or_else(|| caps.get(3))?.as_str(),
+        to: caps.get(4)?.as_str(),
+    })
+}
+
+fn convert(conversion: &Conversion, rates: Option<&CurrencyRates>) -> Result<Evaluation, CalcError> {
+    let amount = expr::evaluate(conversion.amount)?;
+    match (find_unit(conversion.from)?, find_unit(conversion.to)?) {
+        (Unit::Measure(from), Unit::Measure(to)) => {
+            let (value, label) = units::convert(amount, from, to)?;
+            Ok(Evaluation {
+                value,
+                text: format!("{} {}", format_number(value)?, label),
+                unit: Some(label),
+            })
+        }
+        (Unit::Currency(from), Unit::Currency(to)) => {
+            let value = rates.ok_or(CalcError::NoRates)?.convert(amount, &from, &to)?;
+            if !value.is_finite() {
+                return Err(CalcError::NotFinite);
+            }
+            Ok(Evaluation {
+                value,
+                text: format!("{:.2} {}", value, to),
+                unit: Some(to),
+            })
+        }
+        _ => Err(CalcError::Incompatible(conversion.from.to_string(), conversion.to.to_string())),
+    }
+}
+
+/// `1,499.50` → 1499.5. Commas only count as thousands separators.
+fn parse_number(s: &str) -> Option<f64> {
+    let (int_part, fraction) = s.split_once('.').unwrap_or((s, ""));
+    let mut groups = int_part.split(',');
+    let first = groups.next().unwrap_or_default();
+    let grouped = !int_part.contains(',')
+        || (!first.is_empty() && first.len() <= 3 && groups.all(|g| g.len() == 3));
+    if !grouped || fraction.contains(',') {
+        return None;
+    }
+    s.replace(',', "").parse().ok()
+}
+
+/// Up to 10 decimal places, without trailing zeros.
+fn format_number(value: f64) -> Result<String, CalcError> {
+    if !value.is_finite() {
+        return Err(CalcError::NotFinite);
+    }
+    let fixed = format!("{:.10}", value);
+    let trimmed = fixed.trim_end_matches('0').trim_end_matches('.');
+    Ok(if trimmed == "-0" { "0".to_string() } else { trimmed.to_string() })
+}
+
+/// The expression without surrounding space or a trailing `=`.
+fn one_line(expression: &str) -> Result<&str, CalcError> {
+    let text = expression.trim();
+    let text = text.strip_suffix('=').unwrap_or(text).trim_end();
+    if text.contains('\n') {
+        return Err(CalcError::NotOneLine);
+    }
+    Ok(text)
+}
+
+fn evaluate_with(expression: &str, rates: Option<&CurrencyRates>) -> Result<Evaluation, CalcError> {
+    let text = one_line(expression)?;
+    if let Some(conversion) = parse_conversion(text) {
+        return convert(&conversion, rates);
+    }
+    let value = expr::evaluate(text)?;
+    Ok(Evaluation { value, unit: None, text: format_number(value)? })
+}
+
+/// Evaluate arithmetic such as `1,200 * (1 + 15%)` or `200 - 10%`, or a
+/// conversion such as `72 inches in cm` or `$25 in EUR`. Currencies use the
+/// rates last given to [`currency::set_rates`].
+pub fn evaluate(expression: &str) -> Result<Evaluation, CalcError> {
+    evaluate_with(expression, currency::rates().as_ref())
+}
+
+/// Whether `expression` converts between currencies, so needs exchange rates.
+pub fn needs_rates(expression: &str) -> bool {
+    let Some(conversion) = one_line(expression).ok().and_then(parse_conversion) else {
+        return false;
+    };
+    [conversion.from, conversion.to]
+        .iter()
+        .any(|unit| matches!(find_unit(unit), Ok(Unit::Currency(_))))
+}
+
+/// The answer to a one-line arithmetic expression or conversion, trailing
+/// `=` allowed. `None` for anything else, including a lone number.
+pub fn calculate(text: &str) -> Option<String> {
+    let line = one_line(text).ok()?;
+    if parse_conversion(line).is_none() && !expr::is_sum(line) {
+        return None;
+    }
+    evaluate(line).ok().map(|evaluation| evaluation.text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_calculate_expressions() {
+        assert_eq!(calculate("1499*1.13").as_deref(), Some("1693.87"));
+        assert_eq!(calculate("(2 + 3) × 4 =").as_deref(), Some("20"));
+        assert_eq!(calculate("2^3^2").as_deref(), Some("512"));
+        assert_eq!(calculate("-3 - -2").as_deref(), Some("-1"));
+        assert_eq!(calculate("-2^2").as_deref(), Some("-4"));
+        assert_eq!(calculate("1,200 * 15%").as_deref(), Some("180"));
+        assert_eq!(calculate("10 ÷ 4").as_deref(), Some("2.5"));
+    }
+
+    #[test]
+    fn test_calculate_percentages() {
+        assert_eq!(calculate("15% of 200").as_deref(), Some("30"));
+        assert_eq!(calculate("200 + 15%").as_deref(), Some("230"));
+        assert_eq!(calculate("80 - 25%").as_deref(), Some("60"));
+        assert_eq!(calculate("10% + 5%").as_deref(), Some("0.15"));
+    }
+
+    #[test]
+    fn test_calculate_deep_nesting() {
+        let nested = |depth: usize| format!("{}1+1{}", "(".repeat(depth), ")".repeat(depth));
+        assert_eq!(calculate(&nested(20)).as_deref(), Some("2"));
+        assert_eq!(calculate(&nested(100_000)), None);
+        assert_eq!(calculate(&format!("{}1+1", "- ".repeat(100_000))), None);
+        assert_eq!(calculate(&format!("2{}", "^2".repeat(100_000))), None);
+    }
+
+    #[test]
+    fn test_calculate_conversions() {
+        assert_eq!(calculate("72 inches in cm").as_deref(), Some("182.88 cm"));
+        assert_eq!(calculate("5 km to miles").as_deref(), Some("3.1068559612 mi"));
+        assert_eq!(calculate("-40 F to C").as_deref(), Some("-40 °C"));
+        assert_eq!(calculate("2 lbs in g").as_deref(), Some("907.18474 g"));
+        assert_eq!(calculate("(2 + 3) fl oz in ml").as_deref(), Some("147.8676478125 ml"));
+        assert_eq!(calculate("3 kg in cm"), None);
+    }
+
+    #[test]
+    fn test_currency_conversions() {
+        let rates = CurrencyRates {
+            base: "USD".to_string(),
+            date: "2024-06-01".to_string(),
+            rates: HashMap::from([("EUR".to_string(), 0.92), ("CNY".to_string(), 7.24)]),
+            fetched_at: 0,
+        };
+        let euros = evaluate_with("$25 in EUR", Some(&rates)).unwrap();
+        assert_eq!((euros.text.as_str(), euros.unit.as_deref()), ("23.00 EUR", Some("EUR")));
+        assert_eq!(evaluate_with("100 eur to ¥", Some(&rates)).unwrap().text, "786.96 CNY");
+        assert_eq!(evaluate_with("5 usd in xyz", Some(&rates)), Err(CalcError::UnknownCurrency("XYZ".to_string())));
+        assert_eq!(evaluate_with("5 usd in kg", Some(&rates)), Err(CalcError::Incompatible("usd".to_string(), "kg".to_string())));
+        assert_eq!(evaluate_with("$25 in EUR", None), Err(CalcError::NoRates));
+        assert!(needs_rates("$25 in EUR"));
+        assert!(!needs_rates("72 inches in cm"));
+    }
+
+    #[test]
+    fn test_evaluate() {
+        assert_eq!(evaluate("42").unwrap().text, "42");
+        assert_eq!(evaluate("1/0"), Err(CalcError::DivisionByZero));
+        assert_eq!(evaluate("2 + apples"), Err(CalcError::Unexpected("apples".to_string())));
+        assert_eq!(evaluate("1 +\n2"), Err(CalcError::NotOneLine));
+    }
+
+    #[test]
+    fn test_calculate_rejects() {
+        for text in ["42", "50%", "555-1234", "2024-06-01", "1/0", "(1 + 2", "hello world", "1 +\n2"] {
+            assert_eq!(calculate(text), None, "{}", text);
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use super::CalcError;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Dimension {
+    Length,
+    Mass,
+    Volume,
+    Temperature,
+}
+
+/// Unit spellings, their dimension, and their size in the dimension's base
+/// unit (m, kg, L). Temperatures are converted separately.
+const UNITS: &[(&[&str], Dimension, f64)] = &[
+    (&["mm", "millimeter", "millimeters", "millimetre", "millimetres"], Dimension::Length, 0.001),
+    (&["cm", "centimeter", "centimeters", "centimetre", "centimetres"], Dimension::Length, 0.01),
+    (&["m", "meter", "meters", "metre", "metres"], Dimension::Length, 1.0),
+    (&["km", "kilometer", "kilometers", "kilometre", "kilometres"], Dimension::Length, 1000.0),
+    (&["in", "inch", "inches"], Dimension::Length, 0.0254),
+    (&["ft", "foot", "feet"], Dimension::Length, 0.3048),
+    (&["yd", "yard", "yards"], Dimension::Length, 0.9144),
+    (&["mi", "mile", "miles"], Dimension::Length, 1609.344),
+    (&["mg", "milligram", "milligrams"], Dimension::Mass, 0.000_001),
+    (&["g", "gram", "grams"], Dimension::Mass, 0.001),
+    (&["kg", "kilogram", "kilograms", "kilo", "kilos"], Dimension::Mass, 1.0),
+    (&["t", "tonne", "tonnes"], Dimension::Mass, 1000.0),
+    (&["oz", "ounce", "ounces"], Dimension::Mass, 0.028_349_523_125),
+    (&["lb", "lbs", "pound", "pounds"], Dimension::Mass, 0.453_592_37),
+    (&["st", "stone", "stones"], Dimension::Mass, 6.350_293_18),
+    (&["ml", "milliliter", "milliliters", "millilitre", "millilitres"], Dimension::Volume, 0.001),
+    (&["l", "liter", "liters", "litre", "litres"], Dimension::Volume, 1.0),
+    (&["fl oz", "floz"], Dimension::Volume, 0.029_573_529_562_5),
+    (&["cup", "cups"], Dimension::Volume, 0.236_588_236_5),
+    (&["pt", "pint", "pints"], Dimension::Volume, 0.473_176_473),
+    (&["qt", "quart", "quarts"], Dimension::Volume, 0.946_352_946),
+    (&["gal", "gallon", "gallons"], Dimension::Volume, 3.785_411_784),
+    (&["c", "°c", "celsius"], Dimension::Temperature, 0.0),
+    (&["f", "°f", "fahrenheit"], Dimension::Temperature, 0.0),
+    (&["k", "kelvin"], Dimension::Temperature, 0.0),
+];
+
+/// A unit of measurement, by its short name.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct Measure {
+    name: &'static str,
+    dimension: Dimension,
+    factor: f64,
+}
+
+pub(super) fn find_measure(name: &str) -> Option<Measure> {
+    let name = name.to_lowercase();
+    UNITS
+        .iter()
+        .find(|(names, _, _)| names.contains(&name.as_str()))
+        .map(|(names, dimension, factor)| Measure { name: names[0], dimension: *dimension, factor: *factor })
+}
+
+fn to_kelvin(value: f64, unit: &str) -> f64 {
+    match unit {
+        "c" => value + 273.15,
+        "f" => (value - 32.0) * 5.0 / 9.0 + 273.15,
+        _ => value,
+    }
+}
+
+fn from_kelvin(kelvin: f64, unit: &str) -> f64 {
+    match unit {
+        "c" => kelvin - 273.15,
+        "f" => (kelvin - 273.15) * 9.0 / 5.0 + 32.0,
+        _ => kelvin,
+    }
+}
+
+/// `value` in `from` expressed in `to`, and the label to show it with.
+pub(super) fn convert(value: f64, from: Measure, to: Measure) -> Result<(f64, String), CalcError> {
+    if from.dimension != to.dimension {
+        return Err(CalcError::Incompatible(from.name.to_string(), to.name.to_string()));
+    }
+    Ok(if from.dimension == Dimension::Temperature {
+        let label = if to.name == "k" { "K".to_string() } else { format!("°{}", to.name.to_uppercase()) };
+        (from_kelvin(to_kelvin(value, from.name), to.name), label)
+    } else {
+        (value * from.factor / to.factor, to.name.to_string())
+    })
+}
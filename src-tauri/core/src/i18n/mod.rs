@@ -0,0 +1,180 @@
+use std::sync::atomic::{AtomicU8, Ordering};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Locale {
+    ZhCn = 0,
+    En = 1,
+    Ja = 2,
+}
+
+/// Locale tags accepted in `AppConfig::locale`.
+pub const SUPPORTED_LOCALES: &[&str] = &["zh-CN", "en", "ja"];
+
+impl Locale {
+    /// `zh`, `zh-CN`, `zh_Hans` map to Chinese, `en`/`en-US` to English,
+    /// `ja`/`ja-JP` to Japanese.
+    /// Unknown tags fall back to Chinese, the original UI language.
+    pub fn from_tag(tag: &str) -> Self {
+        let lang = tag.split(['-', '_']).next().unwrap_or("").to_ascii_lowercase();
+        match lang.as_str() {
+            "en" => Locale::En,
+            "ja" => Locale::Ja,
+            _ => Locale::ZhCn,
+        }
+    }
+}
+
+static CURRENT: AtomicU8 = AtomicU8::new(Locale::ZhCn as u8);
+
+pub fn set_locale(locale: Locale) {
+    CURRENT.store(locale as u8, Ordering::Relaxed);
+}
+
+pub fn current() -> Locale {
+    match CURRENT.load(Ordering::Relaxed) {
+        1 => Locale::En,
+        2 => Locale::Ja,
+        _ => Locale::ZhCn,
+    }
+}
+
+/// (key, zh-CN, en, ja)
+const MESSAGES: &[(&str, &str, &str, &str)] = &[
+    ("chip.format_json", "格式化 JSON", "Format JSON", "JSON を整形"),
+    ("chip.minify_json", "压缩 JSON", "Minify JSON", "JSON を圧縮"),
+    ("chip.json_to_yaml", "转换为 YAML", "Convert to YAML", "YAML に変換"),
+    ("chip.color_to_hex", "转为 HEX", "Convert to HEX", "HEX に変換"),
+    ("chip.color_to_rgb", "转为 RGB", "Convert to RGB", "RGB に変換"),
+    ("chip.color_to_hsl", "转为 HSL", "Convert to HSL", "HSL に変換"),
+    ("chip.color_palette", "生成色板", "Palette listing", "パレットを生成"),
+    ("chip.convert_timestamp", "转换时间戳", "Convert timestamp", "タイムスタンプを変換"),
+    ("chip.new_uuid", "生成新 UUID", "Generate new UUID", "新しい UUID を生成"),
+    ("chip.toggle_case", "切换大小写", "Uppercase/lowercase", "大文字/小文字を切替"),
+    ("chip.identify_ids", "识别格式", "Lookup format", "形式を識別"),
+    ("chip.markdown_link", "生成 Markdown 链接", "Markdown link with title", "Markdown リンクを作成"),
+    ("chip.save_to_notes", "保存到笔记", "Save to notes", "ノートに保存"),
+    ("chip.citation", "生成引用", "Cite with title and summary", "引用を作成"),
+    ("chip.strip_tracking", "去除跟踪参数", "Strip tracking parameters", "トラッキングパラメータを削除"),
+    ("chip.qr_code", "生成二维码", "Generate QR code", "QR コードを生成"),
+    ("chip.qr_image", "复制二维码图片", "Copy QR code image", "QR コード画像をコピー"),
+    ("chip.paths_to_posix", "转为 / 分隔", "Use forward slashes", "/ 区切りに変換"),
+    ("chip.paths_to_windows", "转为 \\ 分隔", "Use backslashes", "\\ 区切りに変換"),
+    ("chip.transcribe_audio", "转写音频", "Transcribe audio", "音声を文字起こし"),
+    ("chip.hash_sha256", "复制 SHA-256", "Copy SHA-256", "SHA-256 をコピー"),
+    ("chip.hash_md5", "复制 MD5", "Copy MD5", "MD5 をコピー"),
+    ("chip.hash_sha1", "复制 SHA-1", "Copy SHA-1", "SHA-1 をコピー"),
+    ("chip.hash_crc32", "复制 CRC32", "Copy CRC32", "CRC32 をコピー"),
+    ("chip.shell_quote_paths", "转义为命令行参数", "Quote for shell", "シェル用にクォート"),
+    ("chip.extract_file_names", "仅保留文件名", "File names only", "ファイル名のみ"),
+    ("chip.decode_base64", "解码 Base64", "Decode Base64", "Base64 をデコード"),
+    ("chip.save_decoded", "解码并保存为文件", "Decode and save as file", "デコードしてファイルに保存"),
+    ("chip.format_sql", "格式化 SQL", "Format SQL", "SQL を整形"),
+    ("chip.explain_sql", "解释查询", "Explain query", "クエリを解説"),
+    ("chip.optimize_sql", "优化查询", "Optimize query", "クエリを最適化"),
+    ("chip.summarize_diff", "总结改动", "Summarize changes", "変更を要約"),
+    ("chip.commit_message", "生成提交说明", "Write commit message", "コミットメッセージを作成"),
+    ("chip.strip_diff", "去除 diff 标记", "Strip diff markers", "diff 記号を削除"),
+    ("chip.summarize_thread", "总结邮件往来", "Summarize thread", "スレッドを要約"),
+    ("chip.draft_reply", "起草回复", "Draft reply", "返信を下書き"),
+    ("chip.strip_quoted", "去除引用内容", "Strip quoted text", "引用部分を削除"),
+    ("chip.explain_error", "解释错误", "Explain this error", "エラーを解説"),
+    ("chip.extract_errors", "提取错误信息", "Extract error messages", "エラーを抽出"),
+    ("chip.summarize_log", "总结日志", "Summarize log", "ログを要約"),
+    ("chip.add_comments", "添加注释", "Add comments", "コメントを追加"),
+    ("chip.refactor", "重构优化", "Refactor", "リファクタリング"),
+    ("chip.explain_code", "解释代码", "Explain code", "コードを解説"),
+    ("chip.table_to_tsv", "转换为 TSV", "Convert to TSV", "TSV に変換"),
+    ("chip.to_markdown_table", "转换为 Markdown 表格", "Convert to Markdown table", "Markdown 表に変換"),
+    ("chip.first_column", "提取第一列", "Extract first column", "1 列目を抽出"),
+    ("chip.sort_table", "排序数据", "Sort rows", "行を並べ替え"),
+    ("chip.sort_list", "排序列表", "Sort list", "リストを並べ替え"),
+    ("chip.dedupe", "去重", "Remove duplicates", "重複を削除"),
+    ("chip.comma_separated", "转为逗号分隔", "Comma-separate", "カンマ区切りに変換"),
+    ("chip.summarize", "总结要点", "Summarize", "要点をまとめる"),
+    ("chip.fix_grammar", "修正语法", "Fix grammar", "文法を修正"),
+    ("chip.extract_urls", "提取链接", "Extract links", "リンクを抽出"),
+    ("chip.translate_en", "翻译成英文", "Translate to English", "英語に翻訳"),
+    ("chip.translate_zh", "翻译成中文", "Translate to Chinese", "中国語に翻訳"),
+    ("chip.translate_ja", "翻译成日文", "Translate to Japanese", "日本語に翻訳"),
+    ("chip.remove_empty_lines", "去空行", "Remove empty lines", "空行を削除"),
+    ("chip.trim", "去首尾空格", "Trim whitespace", "前後の空白を削除"),
+    ("chip.collapse_spaces", "合并空格", "Collapse spaces", "連続する空白をまとめる"),
+    ("chip.fix_encoding", "修复乱码", "Fix garbled text", "文字化けを修復"),
+    ("ai.connection_failed", "连接失败", "Connection failed", "接続に失敗しました"),
+    ("ai.auth_failed", "认证失败：API Key 无效", "Authentication failed: invalid API key", "認証に失敗しました：API キーが無効です"),
+    ("ai.timeout", "请求超时", "Request timeout", "リクエストがタイムアウトしました"),
+    ("ai.model_not_found", "模型不存在", "Model not found", "モデルが見つかりません"),
+    ("ai.cancelled", "请求已取消", "Request cancelled", "リクエストはキャンセルされました"),
+    ("ai.api_error", "API 错误", "API error", "API エラー"),
+    ("ai.parse_error", "响应解析失败", "Parse error", "レスポンスの解析に失敗しました"),
+    ("tray.status_running", "FlowPaste 运行中", "FlowPaste is running", "FlowPaste 実行中"),
+    ("tray.status_paused", "FlowPaste 已暂停记录剪贴板", "FlowPaste: clipboard capture paused", "FlowPaste：クリップボードの記録を一時停止中"),
+    ("tray.toggle_panel", "显示/隐藏面板", "Show/hide panel", "パネルの表示/非表示"),
+    ("tray.recent", "最近复制", "Recent clipboard", "最近のコピー"),
+    ("tray.rules", "快捷规则", "Quick rules", "クイックルール"),
+    ("tray.empty", "（空）", "(empty)", "（なし）"),
+    ("tray.pause", "暂停记录剪贴板", "Pause clipboard capture", "クリップボードの記録を一時停止"),
+    ("tray.quit", "退出", "Quit", "終了"),
+    ("share.title", "FlowPaste 分享", "FlowPaste share", "FlowPaste 共有"),
+    ("share.prompt", "输入 FlowPaste 上显示的 6 位验证码", "Enter the 6-digit code shown in FlowPaste", "FlowPaste に表示された 6 桁のコードを入力"),
+    ("share.open", "查看", "Open", "開く"),
+    ("share.wrong_code", "验证码错误", "Wrong code", "コードが違います"),
+    ("share.ended", "分享已结束或已过期", "This share has ended or expired", "この共有は終了したか期限切れです"),
+    ("generate.password", "生成密码", "Generate password", "パスワードを生成"),
+    ("generate.password_hint", "按设置中的长度和字符生成并复制", "Copied, using the length and characters from settings", "設定の長さと文字で生成してコピー"),
+    ("generate.uuid", "生成 UUID", "Generate UUID", "UUID を生成"),
+    ("generate.uuid_hint", "随机 UUID v4 并复制", "Random UUID v4, copied", "ランダムな UUID v4 をコピー"),
+    ("generate.lorem_ipsum", "生成占位文本", "Generate lorem ipsum", "ダミーテキストを生成"),
+    ("generate.lorem_ipsum_hint", "一段 Lorem ipsum 并复制", "One paragraph, copied", "Lorem ipsum を 1 段落コピー"),
+    ("generate.card_number", "生成测试卡号", "Generate test card number", "テスト用カード番号を生成"),
+    ("generate.card_number_hint", "通过 Luhn 校验的 Visa 测试号码并复制", "A Visa test number passing the Luhn check, copied", "Luhn チェックを通る Visa のテスト番号をコピー"),
+];
+
+/// Text for `key` in `locale`; unknown keys are returned unchanged so a
+/// missing entry is visible rather than blank.
+pub fn tr_in(locale: Locale, key: &'static str) -> &'static str {
+    MESSAGES
+        .iter()
+        .find(|(k, _, _, _)| *k == key)
+        .map(|(_, zh, en, ja)| match locale {
+            Locale::ZhCn => *zh,
+            Locale::En => *en,
+            Locale::Ja => *ja,
+        })
+        .unwrap_or(key)
+}
+
+/// Text for `key` in the current locale.
+pub fn tr(key: &'static str) -> &'static str {
+    tr_in(current(), key)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_tag() {
+        assert_eq!(Locale::from_tag("en"), Locale::En);
+        assert_eq!(Locale::from_tag("en-US"), Locale::En);
+        assert_eq!(Locale::from_tag("zh_Hans"), Locale::ZhCn);
+        assert_eq!(Locale::from_tag("ja-JP"), Locale::Ja);
+        assert_eq!(Locale::from_tag("fr"), Locale::ZhCn);
+    }
+
+    #[test]
+    fn test_lookup() {
+        assert_eq!(tr_in(Locale::ZhCn, "chip.dedupe"), "去重");
+        assert_eq!(tr_in(Locale::En, "chip.dedupe"), "Remove duplicates");
+        assert_eq!(tr_in(Locale::Ja, "chip.dedupe"), "重複を削除");
+        assert_eq!(tr_in(Locale::En, "missing.key"), "missing.key");
+    }
+
+    #[test]
+    fn test_every_key_is_unique() {
+        for (i, (key, _, _, _)) in MESSAGES.iter().enumerate() {
+            assert!(!MESSAGES[..i].iter().any(|(k, _, _, _)| k == key), "duplicate key {}", key);
+        }
+    }
+}
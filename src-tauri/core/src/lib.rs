@@ -0,0 +1,9 @@
+//! The parts of FlowPaste that don't need a window: text rules, the privacy
+//! shield, content detection and AI providers. Shared by the app and the CLI.
+
+pub mod ai;
+pub mod calc;
+pub mod i18n;
+pub mod privacy;
+pub mod regex;
+pub mod text;
@@ -80,6 +80,34 @@ pub fn mask_pii(text: &str) -> MaskResult {
     }
 }
 
+/// Mask `text` against a mapping that outlives one call: values already in
+/// `mapping` keep their placeholder and new ones get unused placeholders, so
+/// text masked at different times can be restored with the same mapping.
+pub fn mask_pii_with(text: &str, mapping: &mut MaskMapping) -> String {
+    let mut masked = text.to_string();
+    let mut items = scan_pii(text).items;
+    items.sort_by_key(|i| std::cmp::Reverse(i.start));
+
+    for item in items {
+        let existing = mapping
+            .mappings
+            .iter()
+            .find(|(_, value)| **value == item.value)
+            .map(|(placeholder, _)| placeholder.clone());
+        let placeholder = existing.unwrap_or_else(|| {
+            let placeholder = (1..)
+                .map(|i| generate_placeholder(item.pii_type, i))
+                .find(|p| !mapping.mappings.contains_key(p))
+                .unwrap_or_default();
+            mapping.mappings.insert(placeholder.clone(), item.value.clone());
+            placeholder
+        });
+        masked.replace_range(item.start..item.end, &placeholder);
+    }
+
+    masked
+}
+
 pub fn restore_pii(text: &str, mapping: &MaskMapping) -> String {
     let mut restored = text.to_string();
 
@@ -119,6 +147,17 @@ mod tests {
         assert_eq!(restored, original);
     }
 
+    #[test]
+    fn test_mask_with_shared_mapping() {
+        let mut mapping = MaskMapping::default();
+        let first = mask_pii_with("手机：13800138001", &mut mapping);
+        let second = mask_pii_with("手机：13900139002，旧号：13800138001", &mut mapping);
+
+        assert_eq!(first, "手机：{{FP_PHONE_1}}");
+        assert_eq!(second, "手机：{{FP_PHONE_2}}，旧号：{{FP_PHONE_1}}");
+        assert_eq!(restore_pii(&format!("{} / {}", first, second), &mapping), "手机：13800138001 / 手机：13900139002，旧号：13800138001");
+    }
+
     #[test]
     fn test_mask_no_pii() {
         let original = "这是普通文本";
@@ -0,0 +1,8 @@
+mod patterns;
+mod scanner;
+mod masker;
+mod policy;
+
+pub use scanner::{scan_pii, PIIScanResult};
+pub use masker::{mask_pii, mask_pii_with, restore_pii, MaskMapping, MaskResult};
+pub use policy::{resolve_shield, ShieldAction, ShieldDecision, ShieldPolicy, SHIELD_POLICIES};
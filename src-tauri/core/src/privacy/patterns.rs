@@ -24,6 +24,11 @@ impl PIIType {
             PIIType::APIKey => "APIKEY",
         }
     }
+
+    /// Items that identify a person or grant access on their own.
+    pub fn is_high_severity(&self) -> bool {
+        matches!(self, PIIType::IDCard | PIIType::BankCard | PIIType::APIKey)
+    }
 }
 
 pub struct PIIPattern {
@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+
+use super::scanner::PIIScanResult;
+
+/// Policy names accepted in `AppConfig::shield_policy`.
+pub const SHIELD_POLICIES: &[&str] = &["always", "ask", "never"];
+
+/// How prompts bound for cloud providers are treated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShieldPolicy {
+    /// Mask every detected item
+    Always,
+    /// Send as is, but ask first when high-severity items are present
+    Ask,
+    /// Never mask
+    Never,
+}
+
+impl ShieldPolicy {
+    /// Unknown names fall back to `Always`, the safe choice.
+    pub fn from_name(name: &str) -> Self {
+        match name {
+            "ask" => ShieldPolicy::Ask,
+            "never" => ShieldPolicy::Never,
+            _ => ShieldPolicy::Always,
+        }
+    }
+}
+
+/// The user's answer after being asked about high-severity items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ShieldDecision {
+    Mask,
+    Send,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShieldAction {
+    Mask,
+    Send,
+    /// Nothing may be sent until the user decides
+    Confirm,
+}
+
+/// What to do with a prompt. Local providers and providers with the shield
+/// turned off are never masked.
+pub fn resolve_shield(
+    policy: ShieldPolicy,
+    shielded_provider: bool,
+    scan: &PIIScanResult,
+    decision: Option<ShieldDecision>,
+) -> ShieldAction {
+    if !shielded_provider || !scan.has_pii {
+        return ShieldAction::Send;
+    }
+    match policy {
+        ShieldPolicy::Always => ShieldAction::Mask,
+        ShieldPolicy::Never => ShieldAction::Send,
+        ShieldPolicy::Ask => {
+            if !scan.items.iter().any(|item| item.pii_type.is_high_severity()) {
+                return ShieldAction::Send;
+            }
+            match decision {
+                Some(ShieldDecision::Mask) => ShieldAction::Mask,
+                Some(ShieldDecision::Send) => ShieldAction::Send,
+                None => ShieldAction::Confirm,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::privacy::scan_pii;
+
+    #[test]
+    fn test_local_or_unshielded_is_never_masked() {
+        let scan = scan_pii("身份证 110101199003074518");
+        assert_eq!(resolve_shield(ShieldPolicy::Always, false, &scan, None), ShieldAction::Send);
+    }
+
+    #[test]
+    fn test_always_and_never() {
+        let scan = scan_pii("邮箱：test@example.com");
+        assert_eq!(resolve_shield(ShieldPolicy::Always, true, &scan, None), ShieldAction::Mask);
+        assert_eq!(resolve_shield(ShieldPolicy::Never, true, &scan, None), ShieldAction::Send);
+    }
+
+    #[test]
+    fn test_ask_only_for_high_severity() {
+        let low = scan_pii("邮箱：test@example.com");
+        assert_eq!(resolve_shield(ShieldPolicy::Ask, true, &low, None), ShieldAction::Send);
+
+        let high = scan_pii("身份证 110101199003074518");
+        assert_eq!(resolve_shield(ShieldPolicy::Ask, true, &high, None), ShieldAction::Confirm);
+        assert_eq!(
+            resolve_shield(ShieldPolicy::Ask, true, &high, Some(ShieldDecision::Mask)),
+            ShieldAction::Mask
+        );
+        assert_eq!(
+            resolve_shield(ShieldPolicy::Ask, true, &high, Some(ShieldDecision::Send)),
+            ShieldAction::Send
+        );
+    }
+}
@@ -0,0 +1,155 @@
+use chrono::{DateTime, Local, NaiveDate};
+use regex::Captures;
+use serde::Serialize;
+
+use super::RegexError;
+
+/// Computes the replacement for one match. `param` is the text after `:` in the
+/// rule's replacement (`increment:5`). Returning `None` leaves the match untouched.
+pub type ReplaceFn = fn(&Captures, &str) -> Option<String>;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallbackInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+}
+
+struct Callback {
+    info: CallbackInfo,
+    apply: ReplaceFn,
+}
+
+static CALLBACKS: &[Callback] = &[
+    Callback {
+        info: CallbackInfo {
+            name: "iso_date",
+            description: "Reformat a matched date as ISO 8601 (YYYY-MM-DD)",
+        },
+        apply: iso_date,
+    },
+    Callback {
+        info: CallbackInfo {
+            name: "epoch_to_local",
+            description: "Convert a matched Unix timestamp (seconds or milliseconds) to local time",
+        },
+        apply: epoch_to_local,
+    },
+    Callback {
+        info: CallbackInfo {
+            name: "increment",
+            description: "Add the parameter (default 1) to a matched integer",
+        },
+        apply: increment,
+    },
+];
+
+pub fn list_callbacks() -> Vec<CallbackInfo> {
+    CALLBACKS.iter().map(|c| c.info.clone()).collect()
+}
+
+/// Resolve a rule replacement of the form `name` or `name:param`.
+pub fn resolve(replacement: &str) -> Result<(ReplaceFn, &str), RegexError> {
+    let (name, param) = replacement.split_once(':').unwrap_or((replacement, ""));
+    CALLBACKS
+        .iter()
+        .find(|c| c.info.name == name)
+        .map(|c| (c.apply, param))
+        .ok_or_else(|| RegexError::RuleNotFound(format!("callback '{}'", name)))
+}
+
+// Slash dates are read as US month/day, dotted dates as day.month
+const DATE_FORMATS: &[&str] = &[
+    "%Y-%m-%d",
+    "%Y/%m/%d",
+    "%Y.%m.%d",
+    "%m/%d/%Y",
+    "%d.%m.%Y",
+    "%Y年%m月%d日",
+    "%b %d, %Y",
+    "%B %d, %Y",
+    "%b %d %Y",
+    "%B %d %Y",
+    "%d %b %Y",
+    "%d %B %Y",
+];
+
+/// Read a date written in any of `DATE_FORMATS`.
+pub(super) fn parse_date(raw: &str) -> Option<NaiveDate> {
+    // "Sept." / "Dec." abbreviations aren't understood by chrono's %b
+    let raw = raw.trim().replace("Sept", "Sep").replacen(". ", " ", 1);
+    DATE_FORMATS
+        .iter()
+        .find_map(|fmt| NaiveDate::parse_from_str(&raw, fmt).ok())
+}
+
+fn iso_date(caps: &Captures, _param: &str) -> Option<String> {
+    parse_date(caps.get(0)?.as_str()).map(|d| d.format("%Y-%m-%d").to_string())
+}
+
+fn epoch_to_local(caps: &Captures, _param: &str) -> Option<String> {
+    let raw = caps.get(0)?.as_str();
+    let value: i64 = raw.parse().ok()?;
+    let utc = match raw.len() {
+        13 => DateTime::from_timestamp_millis(value)?,
+        10 => DateTime::from_timestamp(value, 0)?,
+        _ => return None,
+    };
+    Some(utc.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S").to_string())
+}
+
+fn increment(caps: &Captures, param: &str) -> Option<String> {
+    let raw = caps.get(0)?.as_str();
+    let step: i64 = if param.is_empty() { 1 } else { param.trim().parse().ok()? };
+    let value: i64 = raw.parse().ok()?;
+    let next = value.checked_add(step)?;
+
+    // Keep zero padding ("007" -> "008")
+    let width = if raw.starts_with('0') && raw.len() > 1 { raw.len() } else { 0 };
+    Some(format!("{:0width$}", next, width = width))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    fn run(name: &str, pattern: &str, text: &str) -> Option<String> {
+        let (f, param) = resolve(name).unwrap();
+        let caps = Regex::new(pattern).unwrap().captures(text)?;
+        f(&caps, param)
+    }
+
+    #[test]
+    fn test_iso_date_formats() {
+        assert_eq!(run("iso_date", ".+", "12/25/2023").as_deref(), Some("2023-12-25"));
+        assert_eq!(run("iso_date", ".+", "25.12.2023").as_deref(), Some("2023-12-25"));
+        assert_eq!(run("iso_date", ".+", "2023年1月5日").as_deref(), Some("2023-01-05"));
+        assert_eq!(run("iso_date", ".+", "Dec. 5, 2023").as_deref(), Some("2023-12-05"));
+        assert_eq!(run("iso_date", ".+", "not a date"), None);
+    }
+
+    #[test]
+    fn test_epoch_to_local() {
+        let expected = DateTime::from_timestamp(1700000000, 0)
+            .unwrap()
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+        assert_eq!(run("epoch_to_local", r"\d+", "1700000000").as_deref(), Some(expected.as_str()));
+        assert_eq!(run("epoch_to_local", r"\d+", "1700000000000").as_deref(), Some(expected.as_str()));
+        assert_eq!(run("epoch_to_local", r"\d+", "12345"), None);
+    }
+
+    #[test]
+    fn test_increment() {
+        assert_eq!(run("increment", r"\d+", "v41").as_deref(), Some("42"));
+        assert_eq!(run("increment:10", r"\d+", "007").as_deref(), Some("017"));
+        assert_eq!(run("increment:-1", r"-?\d+", "0").as_deref(), Some("-1"));
+    }
+
+    #[test]
+    fn test_unknown_callback() {
+        assert!(matches!(resolve("nope"), Err(RegexError::RuleNotFound(_))));
+    }
+}
@@ -0,0 +1,819 @@
+use once_cell::sync::Lazy;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+
+mod callbacks;
+mod script;
+mod scripting;
+mod sql;
+mod transforms;
+
+pub use callbacks::{list_callbacks, CallbackInfo};
+
+const RULE_TIMEOUT_MS: u64 = 50;
+const RHAI_TIMEOUT_MS: u64 = 500;
+const MAX_OUTPUT_SIZE: usize = 10 * 1024 * 1024; // 10MB
+const CUSTOM_PATTERN_SIZE_LIMIT: usize = 1024 * 1024; // compiled program size, 1MB
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Rule {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    pub pattern: String,
+    pub replacement: String,
+    pub is_builtin: bool,
+    #[serde(default)]
+    pub kind: RuleKind,
+    #[serde(default)]
+    pub flags: RuleFlags,
+    #[serde(default)]
+    pub category: RuleCategory,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Position within the panel; lower sorts first, ties keep definition order.
+    #[serde(default)]
+    pub sort_order: i32,
+    /// Accelerator of a rule pinned with a shortcut; registered as a global
+    /// hotkey that applies the rule to the clipboard.
+    #[serde(default)]
+    pub shortcut: Option<String>,
+}
+
+/// Panel grouping for rules. User-created rules default to `Custom`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RuleCategory {
+    Cleanup,
+    Format,
+    Extract,
+    Dev,
+    Cjk,
+    #[default]
+    Custom,
+}
+
+/// Regex compile options, applied with `RegexBuilder` so patterns don't need
+/// inline `(?im)` groups.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RuleFlags {
+    pub case_insensitive: bool,
+    pub multiline: bool,
+    pub dot_matches_new_line: bool,
+    pub unicode: bool,
+}
+
+impl Default for RuleFlags {
+    fn default() -> Self {
+        Self {
+            case_insensitive: false,
+            multiline: false,
+            dot_matches_new_line: false,
+            unicode: true,
+        }
+    }
+}
+
+/// How a rule is executed. `Transform` rules don't use a regex: `pattern`
+/// names a builtin local transform (e.g. `to_markdown_table`). `Callback` rules
+/// match `pattern` and compute each replacement with the named callback in
+/// `replacement` (e.g. `iso_date`, `increment:5`). `Script` rules hold a
+/// multi-step sed-like script in `pattern` (see `script::parse_script`).
+/// `Rhai` rules hold a Rhai program in `pattern` that gets the text as `text`
+/// and returns the result (see `scripting::compile` for the helpers).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RuleKind {
+    #[default]
+    Regex,
+    Transform,
+    Callback,
+    Script,
+    Rhai,
+}
+
+#[derive(Debug, Error)]
+pub enum RegexError {
+    #[error("invalid regex pattern: {0}")]
+    InvalidPattern(String),
+    #[error("rule not found: {0}")]
+    RuleNotFound(String),
+    #[error("rule execution timeout")]
+    Timeout,
+    #[error("output exceeds size limit")]
+    OutputTooLarge,
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+    #[error("rule worker failed: {0}")]
+    Worker(String),
+    #[error("script error: {0}")]
+    Script(String),
+}
+
+struct CompiledRule {
+    rule: Rule,
+    regex: Regex,
+}
+
+static BUILTIN_RULES: Lazy<Vec<CompiledRule>> = Lazy::new(|| {
+    let rules = vec![
+        Rule {
+            id: "remove_empty_lines".to_string(),
+            name: "Remove Empty Lines".to_string(),
+            description: "Remove consecutive empty lines".to_string(),
+            pattern: r"\n\s*\n+".to_string(),
+            replacement: "\n".to_string(),
+            is_builtin: true,
+            kind: RuleKind::Regex,
+            flags: RuleFlags::default(),
+            category: RuleCategory::Cleanup,
+            tags: vec!["whitespace".to_string()],
+            sort_order: 0,
+            shortcut: None,
+        },
+        Rule {
+            id: "trim_whitespace".to_string(),
+            name: "Trim Whitespace".to_string(),
+            description: "Remove leading/trailing whitespace from each line".to_string(),
+            pattern: r"^[ \t]+|[ \t]+$".to_string(),
+            replacement: "".to_string(),
+            is_builtin: true,
+            kind: RuleKind::Regex,
+            flags: RuleFlags {
+                multiline: true,
+                ..Default::default()
+            },
+            category: RuleCategory::Cleanup,
+            tags: vec!["whitespace".to_string()],
+            sort_order: 0,
+            shortcut: None,
+        },
+        Rule {
+            id: "cjk_spacing".to_string(),
+            name: "CJK Spacing".to_string(),
+            description: "Add space between CJK and Western characters".to_string(),
+            pattern: r"([\p{Han}\p{Hiragana}\p{Katakana}])([A-Za-z0-9])".to_string(),
+            replacement: "$1 $2".to_string(),
+            is_builtin: true,
+            kind: RuleKind::Regex,
+            flags: RuleFlags::default(),
+            category: RuleCategory::Cjk,
+            tags: vec!["spacing".to_string()],
+            sort_order: 0,
+            shortcut: None,
+        },
+        Rule {
+            id: "cjk_spacing_reverse".to_string(),
+            name: "CJK Spacing Reverse".to_string(),
+            description: "Add space between Western and CJK characters".to_string(),
+            pattern: r"([A-Za-z0-9])([\p{Han}\p{Hiragana}\p{Katakana}])".to_string(),
+            replacement: "$1 $2".to_string(),
+            is_builtin: true,
+            kind: RuleKind::Regex,
+            flags: RuleFlags::default(),
+            category: RuleCategory::Cjk,
+            tags: vec!["spacing".to_string()],
+            sort_order: 0,
+            shortcut: None,
+        },
+        Rule {
+            id: "to_plain_text".to_string(),
+            name: "To Plain Text".to_string(),
+            description: "Remove markdown/HTML formatting".to_string(),
+            pattern: r"(\*\*|__|~~|`|<[^>]+>|\[([^\]]+)\]\([^)]+\))".to_string(),
+            replacement: "$2".to_string(),
+            is_builtin: true,
+            kind: RuleKind::Regex,
+            flags: RuleFlags::default(),
+            category: RuleCategory::Cleanup,
+            tags: vec!["markdown".to_string(), "html".to_string()],
+            sort_order: 0,
+            shortcut: None,
+        },
+        Rule {
+            id: "collapse_spaces".to_string(),
+            name: "Collapse Spaces".to_string(),
+            description: "Replace multiple spaces with single space".to_string(),
+            pattern: r"[ \t]+".to_string(),
+            replacement: " ".to_string(),
+            is_builtin: true,
+            kind: RuleKind::Regex,
+            flags: RuleFlags::default(),
+            category: RuleCategory::Cleanup,
+            tags: vec!["whitespace".to_string()],
+            sort_order: 0,
+            shortcut: None,
+        },
+        Rule {
+            id: "dates_to_iso".to_string(),
+            name: "Dates To ISO 8601".to_string(),
+            description: "Reformat dates like 12/25/2023 or 2023年12月25日 as 2023-12-25".to_string(),
+            pattern: r"\b(?:\d{4}[/.]\d{1,2}[/.]\d{1,2}|\d{1,2}[/.]\d{1,2}[/.]\d{4}|\d{4}年\d{1,2}月\d{1,2}日|(?:Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec)[a-z]*\.? \d{1,2},? \d{4})".to_string(),
+            replacement: "iso_date".to_string(),
+            is_builtin: true,
+            kind: RuleKind::Callback,
+            flags: RuleFlags::default(),
+            category: RuleCategory::Dev,
+            tags: vec!["date".to_string()],
+            sort_order: 0,
+            shortcut: None,
+        },
+        Rule {
+            id: "epoch_to_local".to_string(),
+            name: "Timestamps To Local Time".to_string(),
+            description: "Convert 10/13-digit Unix timestamps to local date and time".to_string(),
+            pattern: r"\b\d{10}(?:\d{3})?\b".to_string(),
+            replacement: "epoch_to_local".to_string(),
+            is_builtin: true,
+            kind: RuleKind::Callback,
+            flags: RuleFlags::default(),
+            category: RuleCategory::Dev,
+            tags: vec!["date".to_string()],
+            sort_order: 0,
+            shortcut: None,
+        },
+        Rule {
+            id: "increment_numbers".to_string(),
+            name: "Increment Numbers".to_string(),
+            description: "Add one to every integer".to_string(),
+            pattern: r"\d+".to_string(),
+            replacement: "increment".to_string(),
+            is_builtin: true,
+            kind: RuleKind::Callback,
+            flags: RuleFlags::default(),
+            category: RuleCategory::Dev,
+            tags: vec!["number".to_string()],
+            sort_order: 0,
+            shortcut: None,
+        },
+    ];
+
+    rules
+        .into_iter()
+        .filter_map(|rule| {
+            match build_regex(&rule.pattern, &rule.flags, None) {
+                Ok(regex) => Some(CompiledRule { rule, regex }),
+                Err(e) => {
+                    log::error!("Failed to compile builtin rule '{}': {}", rule.id, e);
+                    None
+                }
+            }
+        })
+        .collect()
+});
+
+fn build_regex(
+    pattern: &str,
+    flags: &RuleFlags,
+    size_limit: Option<usize>,
+) -> Result<Regex, regex::Error> {
+    let mut builder = RegexBuilder::new(pattern);
+    builder
+        .case_insensitive(flags.case_insensitive)
+        .multi_line(flags.multiline)
+        .dot_matches_new_line(flags.dot_matches_new_line)
+        .unicode(flags.unicode);
+    if let Some(limit) = size_limit {
+        builder.size_limit(limit);
+    }
+    builder.build()
+}
+
+static RULE_INDEX: Lazy<HashMap<String, usize>> = Lazy::new(|| {
+    BUILTIN_RULES
+        .iter()
+        .enumerate()
+        .map(|(i, r)| (r.rule.id.clone(), i))
+        .collect()
+});
+
+pub fn get_builtin_rules() -> Vec<Rule> {
+    BUILTIN_RULES
+        .iter()
+        .map(|r| r.rule.clone())
+        .chain(transforms::BUILTIN_TRANSFORMS.iter().map(|t| t.rule.clone()))
+        .collect()
+}
+
+/// Sort rules for display and optionally keep only one category. `order`
+/// holds user-defined positions that override each rule's own `sort_order`.
+pub fn arrange_rules(
+    rules: Vec<Rule>,
+    order: &HashMap<String, i32>,
+    category: Option<RuleCategory>,
+) -> Vec<Rule> {
+    let mut rules: Vec<Rule> = rules
+        .into_iter()
+        .filter(|r| category.is_none() || category == Some(r.category))
+        .map(|mut r| {
+            if let Some(pos) = order.get(&r.id) {
+                r.sort_order = *pos;
+            }
+            r
+        })
+        .collect();
+    rules.sort_by_key(|r| r.sort_order);
+    rules
+}
+
+/// Check that a user rule can run: its regex compiles and any named
+/// transform/callback exists.
+pub fn validate_rule(rule: &Rule) -> Result<(), RegexError> {
+    match rule.kind {
+        RuleKind::Transform => {
+            transforms::find_transform(&rule.pattern)
+                .ok_or_else(|| RegexError::RuleNotFound(rule.pattern.clone()))?;
+        }
+        RuleKind::Script => {
+            script::parse_script(&rule.pattern, CUSTOM_PATTERN_SIZE_LIMIT)?;
+        }
+        RuleKind::Rhai => {
+            scripting::compile(&rule.pattern)?;
+        }
+        RuleKind::Regex | RuleKind::Callback => {
+            build_regex(&rule.pattern, &rule.flags, Some(CUSTOM_PATTERN_SIZE_LIMIT))
+                .map_err(|e| RegexError::InvalidPattern(e.to_string()))?;
+            if rule.kind == RuleKind::Callback {
+                callbacks::resolve(&rule.replacement)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Apply a builtin rule by id. Parameterized transforms accept their parameter
+/// inline as `id:param` (e.g. `prefix_lines:// `), which is how chip payloads carry it.
+pub fn apply_rule(text: &str, rule_id: &str) -> Result<String, RegexError> {
+    if let Some(idx) = RULE_INDEX.get(rule_id) {
+        return apply_compiled_rule(text, &BUILTIN_RULES[*idx]);
+    }
+
+    let (id, param) = match rule_id.split_once(':') {
+        Some((id, param)) => (id, Some(param)),
+        None => (rule_id, None),
+    };
+
+    let transform = transforms::find_transform(id)
+        .ok_or_else(|| RegexError::RuleNotFound(rule_id.to_string()))?;
+    apply_transform(text, transform, param.unwrap_or(&transform.rule.replacement))
+}
+
+/// Whether `rule_id` (optionally `id:param`) names a builtin rule or transform.
+pub fn is_builtin_rule(rule_id: &str) -> bool {
+    RULE_INDEX.contains_key(rule_id)
+        || transforms::find_transform(rule_id.split(':').next().unwrap_or(rule_id)).is_some()
+}
+
+/// Apply rules in order, feeding each output into the next. Ids are resolved
+/// against `custom_rules` first, then the builtin rules.
+pub fn apply_pipeline(
+    text: &str,
+    rule_ids: &[String],
+    custom_rules: &[Rule],
+) -> Result<String, RegexError> {
+    rule_ids.iter().try_fold(text.to_string(), |acc, id| {
+        match custom_rules.iter().find(|r| &r.id == id) {
+            Some(rule) => apply_custom_rule(&acc, rule),
+            None => apply_rule(&acc, id),
+        }
+    })
+}
+
+pub fn apply_custom_rule(text: &str, rule: &Rule) -> Result<String, RegexError> {
+    if rule.kind == RuleKind::Transform {
+        let transform = transforms::find_transform(&rule.pattern)
+            .ok_or_else(|| RegexError::RuleNotFound(rule.pattern.clone()))?;
+        return apply_transform(text, transform, &rule.replacement);
+    }
+
+    if rule.kind == RuleKind::Script {
+        let steps = script::parse_script(&rule.pattern, CUSTOM_PATTERN_SIZE_LIMIT)?;
+        return run_with_deadline(text, &rule.id, RULE_TIMEOUT_MS, move |text, cancelled| {
+            script::run_script(text, &steps, cancelled)
+        });
+    }
+
+    if rule.kind == RuleKind::Rhai {
+        // Compiled on the worker: Rhai's AST can't cross threads
+        let source = rule.pattern.clone();
+        let timeout = Duration::from_millis(RHAI_TIMEOUT_MS);
+        return run_with_deadline(text, &rule.id, RHAI_TIMEOUT_MS, move |text, _| {
+            scripting::run(text, &scripting::compile(&source)?, timeout)
+        });
+    }
+
+    let regex = build_regex(&rule.pattern, &rule.flags, Some(CUSTOM_PATTERN_SIZE_LIMIT))
+        .map_err(|e| RegexError::InvalidPattern(e.to_string()))?;
+    let compiled = CompiledRule {
+        rule: rule.clone(),
+        regex,
+    };
+    let rule_id = compiled.rule.id.clone();
+    run_with_deadline(text, &rule_id, RULE_TIMEOUT_MS, move |text, cancelled| {
+        run_compiled_rule(text, &compiled, cancelled)
+    })
+}
+
+/// Run a user-supplied rule on a worker thread with a hard deadline. The caller
+/// gets `Timeout` as soon as the deadline passes, even if a single match is still
+/// running; the worker observes the cancel flag at its next checkpoint and exits.
+fn run_with_deadline<F>(
+    text: &str,
+    rule_id: &str,
+    timeout_ms: u64,
+    run: F,
+) -> Result<String, RegexError>
+where
+    F: FnOnce(&str, &AtomicBool) -> Result<String, RegexError> + Send + 'static,
+{
+    let cancelled = Arc::new(AtomicBool::new(false));
+    let (tx, rx) = mpsc::channel();
+
+    let worker_cancelled = Arc::clone(&cancelled);
+    let worker_text = text.to_string();
+
+    thread::Builder::new()
+        .name("regex-rule".to_string())
+        .spawn(move || {
+            let _ = tx.send(run(&worker_text, &worker_cancelled));
+        })
+        .map_err(|e| RegexError::Worker(e.to_string()))?;
+
+    match rx.recv_timeout(Duration::from_millis(timeout_ms)) {
+        Ok(result) => result,
+        Err(mpsc::RecvTimeoutError::Timeout) => {
+            cancelled.store(true, Ordering::Relaxed);
+            log::warn!("Rule '{}' timed out after {}ms", rule_id, timeout_ms);
+            Err(RegexError::Timeout)
+        }
+        Err(mpsc::RecvTimeoutError::Disconnected) => {
+            Err(RegexError::Worker("worker exited without a result".to_string()))
+        }
+    }
+}
+
+fn apply_transform(
+    text: &str,
+    transform: &transforms::BuiltinTransform,
+    param: &str,
+) -> Result<String, RegexError> {
+    let result = (transform.apply)(text, param)?;
+    if result.len() > MAX_OUTPUT_SIZE {
+        log::warn!("Transform '{}' output exceeded size limit", transform.rule.id);
+        return Err(RegexError::OutputTooLarge);
+    }
+    Ok(result)
+}
+
+fn apply_compiled_rule(text: &str, compiled: &CompiledRule) -> Result<String, RegexError> {
+    run_compiled_rule(text, compiled, &AtomicBool::new(false))
+}
+
+fn run_compiled_rule(
+    text: &str,
+    compiled: &CompiledRule,
+    cancelled: &AtomicBool,
+) -> Result<String, RegexError> {
+    // Fast path: no match means no allocation-heavy capture walk
+    if !compiled.regex.is_match(text) {
+        return Ok(text.to_string());
+    }
+
+    let callback = match compiled.rule.kind {
+        RuleKind::Callback => Some(callbacks::resolve(&compiled.rule.replacement)?),
+        _ => None,
+    };
+
+    let start = Instant::now();
+    let timeout = Duration::from_millis(RULE_TIMEOUT_MS);
+
+    let mut result = String::with_capacity(text.len());
+    let mut last_end = 0;
+
+    for cap in compiled.regex.captures_iter(text) {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err(RegexError::Timeout);
+        }
+
+        if start.elapsed() > timeout {
+            log::warn!("Rule '{}' timed out after {}ms", compiled.rule.id, RULE_TIMEOUT_MS);
+            return Err(RegexError::Timeout);
+        }
+
+        let full_match = cap.get(0).unwrap();
+        result.push_str(&text[last_end..full_match.start()]);
+
+        match callback {
+            Some((replace, param)) => match replace(&cap, param) {
+                Some(replaced) => result.push_str(&replaced),
+                None => result.push_str(full_match.as_str()),
+            },
+            // Use expand() for efficient replacement with capture groups
+            None => cap.expand(&compiled.rule.replacement, &mut result),
+        }
+
+        last_end = full_match.end();
+
+        // Check output size limit
+        if result.len() > MAX_OUTPUT_SIZE {
+            log::warn!("Rule '{}' output exceeded size limit", compiled.rule.id);
+            return Err(RegexError::OutputTooLarge);
+        }
+    }
+
+    result.push_str(&text[last_end..]);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_empty_lines() {
+        let text = "line1\n\n\nline2\n\nline3";
+        let result = apply_rule(text, "remove_empty_lines").unwrap();
+        assert_eq!(result, "line1\nline2\nline3");
+    }
+
+    #[test]
+    fn test_trim_whitespace() {
+        let text = "  hello  \n  world  ";
+        let result = apply_rule(text, "trim_whitespace").unwrap();
+        assert_eq!(result, "hello\nworld");
+    }
+
+    #[test]
+    fn test_cjk_spacing() {
+        let text = "中文English混合";
+        let result = apply_rule(text, "cjk_spacing").unwrap();
+        let result = apply_rule(&result, "cjk_spacing_reverse").unwrap();
+        assert_eq!(result, "中文 English 混合");
+    }
+
+    #[test]
+    fn test_collapse_spaces() {
+        let text = "hello    world";
+        let result = apply_rule(text, "collapse_spaces").unwrap();
+        assert_eq!(result, "hello world");
+    }
+
+    #[test]
+    fn test_to_plain_text() {
+        let text = "**bold** and [link](url)";
+        let result = apply_rule(text, "to_plain_text").unwrap();
+        assert_eq!(result, "bold and link");
+    }
+
+    #[test]
+    fn test_rule_not_found() {
+        let result = apply_rule("test", "nonexistent");
+        assert!(matches!(result, Err(RegexError::RuleNotFound(_))));
+    }
+
+    #[test]
+    fn test_get_builtin_rules() {
+        let rules = get_builtin_rules();
+        assert!(rules.len() >= 5);
+        assert!(rules.iter().any(|r| r.id == "remove_empty_lines"));
+        assert!(rules.iter().any(|r| r.id == "collapse_spaces"));
+        assert!(rules.iter().any(|r| r.id == "to_markdown_table" && r.kind == RuleKind::Transform));
+    }
+
+    #[test]
+    fn test_parameterized_rule_payload() {
+        let text = "a\nb\n";
+        assert_eq!(apply_rule(text, "prefix_lines").unwrap(), "> a\n> b\n");
+        assert_eq!(apply_rule(text, "prefix_lines:// ").unwrap(), "// a\n// b\n");
+        assert_eq!(apply_rule(text, "suffix_lines:;").unwrap(), "a;\nb;\n");
+        assert_eq!(apply_rule(text, "number_lines:{n}) ").unwrap(), "1) a\n2) b\n");
+        assert!(matches!(
+            apply_rule(text, "nonexistent:x"),
+            Err(RegexError::RuleNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_custom_rule_runs_on_worker() {
+        let rule = Rule {
+            id: "dashes".to_string(),
+            name: "Dashes".to_string(),
+            description: String::new(),
+            pattern: r"\s+".to_string(),
+            replacement: "-".to_string(),
+            is_builtin: false,
+            kind: RuleKind::Regex,
+            ..Default::default()
+        };
+        assert_eq!(apply_custom_rule("a b  c", &rule).unwrap(), "a-b-c");
+        assert_eq!(apply_custom_rule("abc", &rule).unwrap(), "abc");
+    }
+
+    #[test]
+    fn test_custom_rule_invalid_pattern() {
+        let rule = Rule {
+            id: "bad".to_string(),
+            name: "Bad".to_string(),
+            description: String::new(),
+            pattern: "(unclosed".to_string(),
+            replacement: String::new(),
+            is_builtin: false,
+            kind: RuleKind::Regex,
+            ..Default::default()
+        };
+        assert!(matches!(
+            apply_custom_rule("text", &rule),
+            Err(RegexError::InvalidPattern(_))
+        ));
+    }
+
+    #[test]
+    fn test_cancelled_rule_stops() {
+        let compiled = CompiledRule {
+            rule: get_builtin_rules().remove(0),
+            regex: Regex::new("a").unwrap(),
+        };
+        let result = run_compiled_rule("aaa", &compiled, &AtomicBool::new(true));
+        assert!(matches!(result, Err(RegexError::Timeout)));
+    }
+
+    #[test]
+    fn test_custom_rule_flags() {
+        let mut rule = Rule {
+            id: "drop_todo".to_string(),
+            pattern: r"^todo:.*$\n?".to_string(),
+            ..Default::default()
+        };
+        let text = "keep\nTODO: a\nTodo: b\n";
+        assert_eq!(apply_custom_rule(text, &rule).unwrap(), text);
+
+        rule.flags.case_insensitive = true;
+        rule.flags.multiline = true;
+        assert_eq!(apply_custom_rule(text, &rule).unwrap(), "keep\n");
+
+        let rule = Rule {
+            pattern: "a.b".to_string(),
+            replacement: "x".to_string(),
+            flags: RuleFlags {
+                dot_matches_new_line: true,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(apply_custom_rule("a\nb", &rule).unwrap(), "x");
+    }
+
+    #[test]
+    fn test_rule_flags_deserialize_defaults() {
+        let rule: Rule = serde_json::from_str(
+            r#"{"id":"r","name":"R","description":"","pattern":"a","replacement":"b","isBuiltin":false,"flags":{"caseInsensitive":true}}"#,
+        )
+        .unwrap();
+        assert!(rule.flags.case_insensitive);
+        assert!(rule.flags.unicode);
+        assert_eq!(rule.kind, RuleKind::Regex);
+    }
+
+    #[test]
+    fn test_arrange_rules_by_category_and_order() {
+        let rules = get_builtin_rules();
+        let cjk = arrange_rules(rules.clone(), &HashMap::new(), Some(RuleCategory::Cjk));
+        assert_eq!(cjk.len(), 2);
+        assert!(cjk.iter().all(|r| r.category == RuleCategory::Cjk));
+
+        let mut order = HashMap::new();
+        order.insert("collapse_spaces".to_string(), -1);
+        let arranged = arrange_rules(rules, &order, None);
+        assert_eq!(arranged[0].id, "collapse_spaces");
+        assert_eq!(arranged[1].id, "remove_empty_lines");
+    }
+
+    #[test]
+    fn test_validate_rule() {
+        let rule = Rule {
+            pattern: r"\d+".to_string(),
+            replacement: "increment".to_string(),
+            kind: RuleKind::Callback,
+            ..Default::default()
+        };
+        assert!(validate_rule(&rule).is_ok());
+        assert!(validate_rule(&Rule { replacement: "nope".to_string(), ..rule.clone() }).is_err());
+        assert!(validate_rule(&Rule { pattern: "(".to_string(), ..rule }).is_err());
+    }
+
+    #[test]
+    fn test_apply_pipeline() {
+        let custom = vec![Rule {
+            id: "shout".to_string(),
+            pattern: "hello".to_string(),
+            replacement: "HELLO".to_string(),
+            ..Default::default()
+        }];
+        let ids = vec!["trim_whitespace".to_string(), "collapse_spaces".to_string(), "shout".to_string()];
+        let result = apply_pipeline("  hello    world  ", &ids, &custom).unwrap();
+        assert_eq!(result, "HELLO world");
+
+        let ids = vec!["trim_whitespace".to_string(), "missing".to_string()];
+        assert!(matches!(apply_pipeline("x", &ids, &custom), Err(RegexError::RuleNotFound(_))));
+    }
+
+    #[test]
+    fn test_is_builtin_rule() {
+        assert!(is_builtin_rule("collapse_spaces"));
+        assert!(is_builtin_rule("prefix_lines:// "));
+        assert!(!is_builtin_rule("shout"));
+    }
+
+    #[test]
+    fn test_script_rule() {
+        let rule = Rule {
+            id: "cleanup".to_string(),
+            pattern: "s/^\\s+//gm\n/^#/d\ns/,/;/g".to_string(),
+            kind: RuleKind::Script,
+            ..Default::default()
+        };
+        assert!(validate_rule(&rule).is_ok());
+        assert_eq!(apply_custom_rule("  a,b\n# note\n c,d", &rule).unwrap(), "a;b\nc;d");
+    }
+
+    #[test]
+    fn test_rhai_rule() {
+        let rule = Rule {
+            id: "sum".to_string(),
+            pattern: r#"let total = 0;
+for n in regex_find_all(text, "[0-9]+") { total += parse_int(n); }
+text + "\n= " + total"#
+                .to_string(),
+            kind: RuleKind::Rhai,
+            ..Default::default()
+        };
+        assert!(validate_rule(&rule).is_ok());
+        let result = apply_pipeline(" 1 + 2 + 39", &["trim_whitespace".to_string(), "sum".to_string()], &[rule]);
+        assert_eq!(result.unwrap(), "1 + 2 + 39\n= 42");
+
+        let broken = Rule {
+            pattern: "text +".to_string(),
+            kind: RuleKind::Rhai,
+            ..Default::default()
+        };
+        assert!(matches!(validate_rule(&broken), Err(RegexError::InvalidPattern(_))));
+    }
+
+    #[test]
+    fn test_callback_rules() {
+        let result = apply_rule("Due 12/25/2023, shipped 2024年1月5日", "dates_to_iso").unwrap();
+        assert_eq!(result, "Due 2023-12-25, shipped 2024-01-05");
+
+        let result = apply_rule("item 9, item 10", "increment_numbers").unwrap();
+        assert_eq!(result, "item 10, item 11");
+    }
+
+    #[test]
+    fn test_custom_callback_rule_with_param() {
+        let rule = Rule {
+            id: "bump".to_string(),
+            name: "Bump".to_string(),
+            description: String::new(),
+            pattern: r"v(\d+)".to_string(),
+            replacement: "increment:2".to_string(),
+            is_builtin: false,
+            kind: RuleKind::Callback,
+            ..Default::default()
+        };
+        // Callbacks see the whole match, so "v1" isn't an integer and stays as-is
+        assert_eq!(apply_custom_rule("v1", &rule).unwrap(), "v1");
+
+        let rule = Rule { pattern: r"\d+".to_string(), ..rule };
+        assert_eq!(apply_custom_rule("v1", &rule).unwrap(), "v3");
+    }
+
+    #[test]
+    fn test_extract_urls_rule() {
+        let result = apply_rule("Docs: https://tauri.app and https://docs.rs.", "extract_urls").unwrap();
+        assert_eq!(result, "https://tauri.app\nhttps://docs.rs");
+    }
+
+    #[test]
+    fn test_custom_transform_rule() {
+        let rule = Rule {
+            id: "my_table".to_string(),
+            name: "My Table".to_string(),
+            description: String::new(),
+            pattern: "to_markdown_table".to_string(),
+            replacement: String::new(),
+            is_builtin: false,
+            kind: RuleKind::Transform,
+            ..Default::default()
+        };
+        let result = apply_custom_rule("a,b\n1,2", &rule).unwrap();
+        assert!(result.starts_with("| a"));
+    }
+}
@@ -0,0 +1,235 @@
+use regex::{Regex, RegexBuilder};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use super::RegexError;
+
+/// One parsed script line.
+pub enum ScriptStep {
+    /// `s/pattern/replacement/flags`; `global` comes from the `g` flag.
+    Substitute {
+        regex: Regex,
+        replacement: String,
+        global: bool,
+    },
+    /// `/pattern/d` removes every line containing a match.
+    DeleteLines { regex: Regex },
+}
+
+/// Parse a sed-like script: one step per line, blank lines and `#` comments
+/// ignored. Unlike sed, each step runs over the whole text, so use the `m` flag
+/// when `^`/`$` should match at line boundaries.
+///
+/// Supported flags: `g` (all matches), `i` (case-insensitive), `m` (multiline),
+/// `s` (dot matches newline). Replacements accept sed syntax: `\1`, `&`, `\n`, `\t`.
+pub fn parse_script(source: &str, size_limit: usize) -> Result<Vec<ScriptStep>, RegexError> {
+    let mut steps = Vec::new();
+
+    for (idx, raw) in source.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let step_err = |msg: &str| RegexError::InvalidPattern(format!("line {}: {}", idx + 1, msg));
+
+        if let Some(rest) = line.strip_prefix('s') {
+            let delimiter = rest
+                .chars()
+                .next()
+                .filter(|c| !c.is_alphanumeric() && *c != '\\' && !c.is_whitespace())
+                .ok_or_else(|| step_err("expected a delimiter after 's'"))?;
+            let parts = split_unescaped(&rest[delimiter.len_utf8()..], delimiter);
+            if parts.len() != 3 {
+                return Err(step_err("expected s/pattern/replacement/flags"));
+            }
+
+            let (regex, global) = build(&parts[0], &parts[2], size_limit).map_err(|e| step_err(&e))?;
+            steps.push(ScriptStep::Substitute {
+                regex,
+                replacement: convert_replacement(&parts[1]),
+                global,
+            });
+        } else if let Some(rest) = line.strip_prefix('/') {
+            let parts = split_unescaped(rest, '/');
+            if parts.len() != 2 || parts[1].trim() != "d" {
+                return Err(step_err("expected /pattern/d"));
+            }
+
+            let (regex, _) = build(&parts[0], "", size_limit).map_err(|e| step_err(&e))?;
+            steps.push(ScriptStep::DeleteLines { regex });
+        } else {
+            return Err(step_err("unknown command"));
+        }
+    }
+
+    if steps.is_empty() {
+        return Err(RegexError::InvalidPattern("script has no steps".to_string()));
+    }
+    Ok(steps)
+}
+
+pub fn run_script(
+    text: &str,
+    steps: &[ScriptStep],
+    cancelled: &AtomicBool,
+) -> Result<String, RegexError> {
+    let mut current = text.to_string();
+
+    for step in steps {
+        if cancelled.load(Ordering::Relaxed) {
+            return Err(RegexError::Timeout);
+        }
+
+        current = match step {
+            ScriptStep::Substitute {
+                regex,
+                replacement,
+                global,
+            } => {
+                let limit = if *global { 0 } else { 1 };
+                regex.replacen(&current, limit, replacement.as_str()).into_owned()
+            }
+            ScriptStep::DeleteLines { regex } => {
+                let mut kept: Vec<&str> = current.lines().filter(|l| !regex.is_match(l)).collect();
+                if current.ends_with('\n') && !kept.is_empty() {
+                    kept.push("");
+                }
+                kept.join("\n")
+            }
+        };
+
+        if current.len() > super::MAX_OUTPUT_SIZE {
+            return Err(RegexError::OutputTooLarge);
+        }
+    }
+
+    Ok(current)
+}
+
+fn build(pattern: &str, flags: &str, size_limit: usize) -> Result<(Regex, bool), String> {
+    let mut builder = RegexBuilder::new(pattern);
+    builder.size_limit(size_limit);
+    let mut global = false;
+
+    for flag in flags.trim().chars() {
+        match flag {
+            'g' => global = true,
+            'i' => {
+                builder.case_insensitive(true);
+            }
+            'm' => {
+                builder.multi_line(true);
+            }
+            's' => {
+                builder.dot_matches_new_line(true);
+            }
+            other => return Err(format!("unknown flag '{}'", other)),
+        }
+    }
+
+    builder.build().map(|r| (r, global)).map_err(|e| e.to_string())
+}
+
+/// Split on `delimiter`, treating `\<delimiter>` as a literal delimiter. Other
+/// escapes are kept for the regex/replacement parsers.
+fn split_unescaped(s: &str, delimiter: char) -> Vec<String> {
+    let mut parts = vec![String::new()];
+    let mut chars = s.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some(next) if next == delimiter => parts.last_mut().unwrap().push(next),
+                Some(next) => {
+                    let part = parts.last_mut().unwrap();
+                    part.push('\\');
+                    part.push(next);
+                }
+                None => parts.last_mut().unwrap().push('\\'),
+            }
+        } else if c == delimiter {
+            parts.push(String::new());
+        } else {
+            parts.last_mut().unwrap().push(c);
+        }
+    }
+
+    parts
+}
+
+/// Translate sed replacement syntax into `regex` crate expansion syntax.
+fn convert_replacement(sed: &str) -> String {
+    let mut out = String::with_capacity(sed.len());
+    let mut chars = sed.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(d) if d.is_ascii_digit() => {
+                    out.push_str("${");
+                    out.push(d);
+                    out.push('}');
+                }
+                Some('n') => out.push('\n'),
+                Some('t') => out.push('\t'),
+                Some('$') => out.push_str("$$"),
+                Some(other) => out.push(other),
+                None => out.push('\\'),
+            },
+            '&' => out.push_str("${0}"),
+            '$' => out.push_str("$$"),
+            _ => out.push(c),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(script: &str, text: &str) -> Result<String, RegexError> {
+        let steps = parse_script(script, 1 << 20)?;
+        run_script(text, &steps, &AtomicBool::new(false))
+    }
+
+    #[test]
+    fn test_substitute_first_and_global() {
+        assert_eq!(run("s/a/b/", "aaa").unwrap(), "baa");
+        assert_eq!(run("s/a/b/g", "aaa").unwrap(), "bbb");
+        assert_eq!(run("s/A/b/gi", "aAa").unwrap(), "bbb");
+    }
+
+    #[test]
+    fn test_multi_step_with_comments() {
+        let script = "# normalize\ns/[ \\t]+$//gm\n\n/^DEBUG/d\ns|(\\w+)@(\\w+)|\\2 at \\1|g";
+        let text = "user@host  \nDEBUG noise\nok\n";
+        assert_eq!(run(script, text).unwrap(), "host at user\nok\n");
+    }
+
+    #[test]
+    fn test_replacement_escapes() {
+        assert_eq!(run(r"s/\d+/[&]/g", "a1b22").unwrap(), "a[1]b[22]");
+        assert_eq!(run(r"s/x/$5\n/", "x").unwrap(), "$5\n");
+        assert_eq!(run(r"s/\//-/g", "a/b").unwrap(), "a-b");
+    }
+
+    #[test]
+    fn test_parse_errors_report_line() {
+        let err = run("s/a/b/\ns/(/x/", "a").err().unwrap();
+        assert!(err.to_string().contains("line 2"));
+        assert!(run("s/a/b/q", "a").is_err());
+        assert!(run("p", "a").is_err());
+        assert!(run("# only comments", "a").is_err());
+    }
+
+    #[test]
+    fn test_cancelled_script() {
+        let steps = parse_script("s/a/b/", 1 << 20).unwrap();
+        assert!(matches!(
+            run_script("a", &steps, &AtomicBool::new(true)),
+            Err(RegexError::Timeout)
+        ));
+    }
+}
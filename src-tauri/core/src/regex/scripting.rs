@@ -0,0 +1,215 @@
+use chrono::format::{Item, StrftimeItems};
+use chrono::Local;
+use regex::{Regex, RegexBuilder};
+use rhai::module_resolvers::DummyModuleResolver;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, Scope, AST};
+use std::time::{Duration, Instant};
+
+use super::{callbacks, RegexError, CUSTOM_PATTERN_SIZE_LIMIT, MAX_OUTPUT_SIZE};
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+// Limits that keep a runaway script from freezing or exhausting the app
+const MAX_OPERATIONS: u64 = 5_000_000;
+const MAX_ARRAY_SIZE: usize = 100_000;
+const MAX_MAP_SIZE: usize = 10_000;
+const MAX_CALL_LEVELS: usize = 32;
+const MAX_EXPR_DEPTH: usize = 64;
+
+/// Compile a Rhai transform script. Scripts can't import modules or touch
+/// files; they get the clipboard text as `text` and the helpers registered in
+/// `engine`.
+pub fn compile(source: &str) -> Result<AST, RegexError> {
+    if source.trim().is_empty() {
+        return Err(RegexError::InvalidPattern("script is empty".to_string()));
+    }
+    engine(None)
+        .compile(source)
+        .map_err(|e| RegexError::InvalidPattern(e.to_string()))
+}
+
+/// Run a compiled script on `text`. The script's value is the new text; a
+/// script that ends in a statement returns whatever it left in `text`.
+/// Execution stops with `Timeout` once `timeout` has passed.
+pub fn run(text: &str, ast: &AST, timeout: Duration) -> Result<String, RegexError> {
+    let engine = engine(Some(Instant::now() + timeout));
+    let mut scope = Scope::new();
+    scope.push("text", text.to_string());
+
+    let value = engine
+        .eval_ast_with_scope::<Dynamic>(&mut scope, ast)
+        .map_err(|e| match *e {
+            EvalAltResult::ErrorTerminated(..) | EvalAltResult::ErrorTooManyOperations(..) => {
+                RegexError::Timeout
+            }
+            EvalAltResult::ErrorDataTooLarge(..) => RegexError::OutputTooLarge,
+            other => RegexError::Script(other.to_string()),
+        })?;
+
+    let output = if value.is_unit() {
+        scope.get_value::<String>("text").unwrap_or_default()
+    } else if value.is_string() {
+        value.into_string().unwrap_or_default()
+    } else {
+        return Err(RegexError::Script(format!(
+            "script must return text, got {}",
+            value.type_name()
+        )));
+    };
+    if output.len() > MAX_OUTPUT_SIZE {
+        return Err(RegexError::OutputTooLarge);
+    }
+    Ok(output)
+}
+
+fn engine(deadline: Option<Instant>) -> Engine {
+    let mut engine = Engine::new();
+    engine
+        .set_module_resolver(DummyModuleResolver::new())
+        .set_max_operations(MAX_OPERATIONS)
+        .set_max_string_size(MAX_OUTPUT_SIZE)
+        .set_max_array_size(MAX_ARRAY_SIZE)
+        .set_max_map_size(MAX_MAP_SIZE)
+        .set_max_call_levels(MAX_CALL_LEVELS)
+        .set_max_expr_depths(MAX_EXPR_DEPTH, MAX_EXPR_DEPTH)
+        .on_print(|s| log::debug!("Script: {}", s))
+        .on_debug(|s, _, pos| log::debug!("Script {}: {}", pos, s));
+    if let Some(deadline) = deadline {
+        engine.on_progress(move |_| (Instant::now() >= deadline).then_some(Dynamic::UNIT));
+    }
+
+    engine
+        .register_fn("regex_is_match", |text: &str, pattern: &str| -> ScriptResult<bool> {
+            Ok(build(pattern)?.is_match(text))
+        })
+        .register_fn(
+            "regex_replace",
+            |text: &str, pattern: &str, replacement: &str| -> ScriptResult<String> {
+                Ok(build(pattern)?.replace_all(text, replacement).into_owned())
+            },
+        )
+        .register_fn("regex_find_all", |text: &str, pattern: &str| -> ScriptResult<Array> {
+            Ok(build(pattern)?
+                .find_iter(text)
+                .map(|m| Dynamic::from(m.as_str().to_string()))
+                .collect())
+        })
+        .register_fn("regex_split", |text: &str, pattern: &str| -> ScriptResult<Array> {
+            Ok(build(pattern)?
+                .split(text)
+                .map(|s| Dynamic::from(s.to_string()))
+                .collect())
+        })
+        .register_fn("join", |items: Array, separator: &str| -> String {
+            items.iter().map(|i| i.to_string()).collect::<Vec<_>>().join(separator)
+        })
+        .register_fn("json_parse", |text: &str| -> ScriptResult<Dynamic> {
+            let value: serde_json::Value =
+                serde_json::from_str(text).map_err(|e| format!("invalid JSON: {}", e))?;
+            rhai::serde::to_dynamic(value)
+        })
+        .register_fn("json_stringify", |value: Dynamic| -> ScriptResult<String> {
+            to_json(value, false)
+        })
+        .register_fn("json_pretty", |value: Dynamic| -> ScriptResult<String> {
+            to_json(value, true)
+        })
+        .register_fn("now", |format: &str| -> ScriptResult<String> {
+            Ok(Local::now().format_with_items(strftime(format)?.into_iter()).to_string())
+        })
+        .register_fn("format_date", |date: &str, format: &str| -> ScriptResult<Dynamic> {
+            let items = strftime(format)?;
+            Ok(match callbacks::parse_date(date) {
+                Some(d) => d.format_with_items(items.into_iter()).to_string().into(),
+                None => Dynamic::UNIT,
+            })
+        });
+    engine
+}
+
+fn build(pattern: &str) -> ScriptResult<Regex> {
+    RegexBuilder::new(pattern)
+        .size_limit(CUSTOM_PATTERN_SIZE_LIMIT)
+        .build()
+        .map_err(|e| format!("invalid regex '{}': {}", pattern, e).into())
+}
+
+/// Strings are serialized as JSON text themselves, so `json_pretty(text)`
+/// reformats a JSON document.
+fn to_json(value: Dynamic, pretty: bool) -> ScriptResult<String> {
+    let value: serde_json::Value = match value.clone().into_string() {
+        Ok(s) => serde_json::from_str(&s).unwrap_or(serde_json::Value::String(s)),
+        Err(_) => rhai::serde::from_dynamic(&value)?,
+    };
+    let json = if pretty {
+        serde_json::to_string_pretty(&value)
+    } else {
+        serde_json::to_string(&value)
+    };
+    json.map_err(|e| e.to_string().into())
+}
+
+// chrono panics while formatting an invalid specifier, so reject it up front
+fn strftime(format: &str) -> ScriptResult<Vec<Item<'_>>> {
+    let items: Vec<Item> = StrftimeItems::new(format).collect();
+    if items.iter().any(|i| matches!(i, Item::Error)) {
+        return Err(format!("invalid date format '{}'", format).into());
+    }
+    Ok(items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(script: &str, text: &str) -> Result<String, RegexError> {
+        run(text, &compile(script)?, Duration::from_secs(5))
+    }
+
+    #[test]
+    fn test_returns_value_or_text() {
+        assert_eq!(eval("text.to_upper()", "abc").unwrap(), "ABC");
+        assert_eq!(eval("text.trim();", "  abc ").unwrap(), "abc");
+        assert_eq!(
+            eval("let lines = text.split('\\n'); lines.reverse(); lines.join(\"\\n\")", "a\nb").unwrap(),
+            "b\na"
+        );
+        assert!(matches!(eval("42", "x"), Err(RegexError::Script(_))));
+    }
+
+    #[test]
+    fn test_helpers() {
+        assert_eq!(eval(r#"regex_replace(text, "\\d+", "_")"#, "a1b22").unwrap(), "a_b_");
+        assert_eq!(
+            eval(r#"regex_find_all(text, "[a-z]+@[a-z.]+").join(",")"#, "x@a.com y@b.org").unwrap(),
+            "x@a.com,y@b.org"
+        );
+        assert_eq!(
+            eval(r#"let v = json_parse(text); v.count += 1; json_stringify(v)"#, r#"{"count":1}"#).unwrap(),
+            r#"{"count":2}"#
+        );
+        assert_eq!(eval(r#"format_date(text, "%d/%m/%Y")"#, "2023年1月5日").unwrap(), "05/01/2023");
+        assert!(matches!(eval(r#"now("%Q")"#, ""), Err(RegexError::Script(_))));
+        assert!(matches!(eval(r#"regex_is_match(text, "(")"#, ""), Err(RegexError::Script(_))));
+    }
+
+    #[test]
+    fn test_limits() {
+        assert!(matches!(eval("loop {}", "x"), Err(RegexError::Timeout)));
+        let ast = compile("let n = 0; loop { n += 1; }").unwrap();
+        assert!(matches!(
+            run("x", &ast, Duration::from_millis(10)),
+            Err(RegexError::Timeout)
+        ));
+        assert!(matches!(
+            eval("let s = text; loop { s += s; }", "x"),
+            Err(RegexError::OutputTooLarge)
+        ));
+    }
+
+    #[test]
+    fn test_no_module_imports() {
+        assert!(eval(r#"import "secrets" as s; text"#, "x").is_err());
+        assert!(compile("let x = ").is_err());
+    }
+}
@@ -0,0 +1,327 @@
+use super::RegexError;
+
+/// Upper-cased on output. Kept to words that are rarely column names.
+const KEYWORDS: &[&str] = &[
+    "ADD", "ALL", "ALTER", "AND", "AS", "ASC", "BETWEEN", "BY", "CASE", "CONSTRAINT", "CREATE",
+    "CROSS", "DELETE", "DESC", "DISTINCT", "DROP", "ELSE", "END", "EXISTS", "FOREIGN", "FROM",
+    "FULL", "GROUP", "HAVING", "IF", "IN", "INNER", "INSERT", "INTO", "IS", "JOIN", "LEFT", "LIKE",
+    "LIMIT", "NOT", "NULL", "OFFSET", "ON", "OR", "ORDER", "OUTER", "PRIMARY", "REFERENCES",
+    "RETURNING", "RIGHT", "SELECT", "SET", "TABLE", "THEN", "UNION", "UNIQUE", "UPDATE", "VALUES",
+    "VIEW", "WHEN", "WHERE", "WITH",
+];
+
+/// Clauses that start a line and indent their contents below it.
+const BLOCK_CLAUSES: &[&str] = &[
+    "SELECT", "FROM", "WHERE", "GROUP BY", "ORDER BY", "HAVING", "SET", "VALUES", "RETURNING",
+];
+
+/// Clauses that start a line and keep their contents on it.
+const LINE_CLAUSES: &[&str] = &[
+    "WITH", "INSERT INTO", "UPDATE", "DELETE FROM", "UNION", "UNION ALL", "LIMIT", "OFFSET",
+    "JOIN", "INNER JOIN", "CROSS JOIN", "LEFT JOIN", "RIGHT JOIN", "FULL JOIN",
+    "LEFT OUTER JOIN", "RIGHT OUTER JOIN", "FULL OUTER JOIN",
+];
+
+const INDENT: &str = "  ";
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Token<'a> {
+    Word(&'a str),
+    /// String literal or quoted identifier, kept verbatim
+    Quoted(&'a str),
+    Comment(&'a str),
+    Symbol(&'a str),
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || matches!(c, '_' | '@' | '$')
+}
+
+/// Length of the quoted run at the start of `rest`; a doubled quote escapes itself.
+fn quoted_len(rest: &str, quote: char) -> Option<usize> {
+    let mut chars = rest.char_indices().skip(1).peekable();
+    while let Some((i, c)) = chars.next() {
+        if c == quote {
+            if chars.peek().is_some_and(|&(_, next)| next == quote) {
+                chars.next();
+                continue;
+            }
+            return Some(i + 1);
+        }
+    }
+    None
+}
+
+fn tokenize(sql: &str) -> Result<Vec<Token<'_>>, RegexError> {
+    let unterminated = |what: &str| RegexError::InvalidInput(format!("unterminated {}", what));
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < sql.len() {
+        let rest = &sql[i..];
+        let c = rest.chars().next().unwrap_or_default();
+        let len = if c.is_whitespace() {
+            c.len_utf8()
+        } else if rest.starts_with("--") {
+            let len = rest.find('\n').unwrap_or(rest.len());
+            tokens.push(Token::Comment(rest[..len].trim_end()));
+            len
+        } else if rest.starts_with("/*") {
+            let len = rest.find("*/").ok_or_else(|| unterminated("comment"))? + 2;
+            tokens.push(Token::Comment(&rest[..len]));
+            len
+        } else if matches!(c, '\'' | '"' | '`') {
+            let len = quoted_len(rest, c).ok_or_else(|| unterminated("quote"))?;
+            tokens.push(Token::Quoted(&rest[..len]));
+            len
+        } else if is_word_char(c) {
+            let len = rest.find(|ch: char| !is_word_char(ch)).unwrap_or(rest.len());
+            tokens.push(Token::Word(&rest[..len]));
+            len
+        } else if matches!(c, '(' | ')' | ',' | ';' | '.') {
+            tokens.push(Token::Symbol(&rest[..1]));
+            1
+        } else {
+            // Operator run such as `>=`, `<>` or `::`
+            let len = rest
+                .find(|ch: char| {
+                    is_word_char(ch) || ch.is_whitespace() || "()',;.\"`".contains(ch)
+                })
+                .unwrap_or(rest.len())
+                .max(c.len_utf8());
+            tokens.push(Token::Symbol(&rest[..len]));
+            len
+        };
+        i += len;
+    }
+    Ok(tokens)
+}
+
+/// What was written last, which decides the spacing before the next token.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Prev {
+    /// Start of a line
+    Fresh,
+    Keyword,
+    Word,
+    Open,
+    Close,
+    Comma,
+    Dot,
+    Operator,
+    /// A sign or placeholder prefix such as `-1` or `:id`
+    Unary,
+}
+
+struct Formatter {
+    out: String,
+    /// Indent level of the current query's clauses
+    depth: usize,
+    /// Open parentheses; subqueries remember the depth to restore
+    parens: Vec<Option<usize>>,
+    prev: Prev,
+    case_depth: usize,
+    in_between: bool,
+}
+
+impl Formatter {
+    fn newline(&mut self, depth: usize) {
+        let trimmed = self.out.trim_end_matches(' ').len();
+        self.out.truncate(trimmed);
+        if !self.out.is_empty() && !self.out.ends_with('\n') {
+            self.out.push('\n');
+        }
+        self.out.push_str(&INDENT.repeat(depth));
+        self.prev = Prev::Fresh;
+    }
+
+    fn write(&mut self, s: &str, space: bool, prev: Prev) {
+        if space && self.prev != Prev::Fresh {
+            self.out.push(' ');
+        }
+        self.out.push_str(s);
+        self.prev = prev;
+    }
+
+    /// Outside function-call and list parentheses, where clauses and commas break lines.
+    fn at_query_level(&self) -> bool {
+        !matches!(self.parens.last(), Some(None))
+    }
+
+    fn word(&mut self, word: &str) {
+        let upper = word.to_ascii_uppercase();
+        if !KEYWORDS.contains(&upper.as_str()) {
+            let space = !matches!(self.prev, Prev::Open | Prev::Dot | Prev::Unary);
+            self.write(word, space, Prev::Word);
+            return;
+        }
+
+        match upper.as_str() {
+            "AND" | "OR" if self.in_between => self.in_between = false,
+            "AND" | "OR" if self.case_depth == 0 && self.at_query_level() => {
+                self.newline(self.depth + 1)
+            }
+            "BETWEEN" => self.in_between = true,
+            "CASE" => self.case_depth += 1,
+            "END" => self.case_depth = self.case_depth.saturating_sub(1),
+            _ => {}
+        }
+        let space = !matches!(self.prev, Prev::Open | Prev::Dot | Prev::Unary);
+        self.write(&upper, space, Prev::Keyword);
+    }
+
+    fn symbol(&mut self, symbol: &str, next: Option<&Token>) {
+        match symbol {
+            "(" => {
+                let subquery = matches!(next, Some(Token::Word(w))
+                    if w.eq_ignore_ascii_case("SELECT") || w.eq_ignore_ascii_case("WITH"));
+                // `count(`, but `IN (` and `) (`
+                let space = matches!(self.prev, Prev::Keyword | Prev::Close | Prev::Comma | Prev::Operator);
+                self.write("(", space, Prev::Open);
+                if subquery {
+                    self.parens.push(Some(self.depth));
+                    self.depth += 2;
+                } else {
+                    self.parens.push(None);
+                }
+            }
+            ")" => {
+                if let Some(Some(depth)) = self.parens.pop() {
+                    self.depth = depth;
+                    self.newline(depth + 1);
+                }
+                self.write(")", false, Prev::Close);
+            }
+            "," => {
+                self.write(",", false, Prev::Comma);
+                if self.at_query_level() {
+                    self.newline(self.depth + 1);
+                }
+            }
+            ";" => {
+                self.write(";", false, Prev::Fresh);
+                self.out.push_str("\n\n");
+                self.depth = 0;
+                self.parens.clear();
+                self.case_depth = 0;
+                self.in_between = false;
+            }
+            "." => self.write(".", false, Prev::Dot),
+            _ => {
+                let unary = matches!(
+                    self.prev,
+                    Prev::Fresh | Prev::Keyword | Prev::Open | Prev::Comma | Prev::Operator
+                );
+                let space = !matches!(self.prev, Prev::Open | Prev::Dot | Prev::Unary);
+                self.write(symbol, space, if unary { Prev::Unary } else { Prev::Operator });
+            }
+        }
+    }
+}
+
+/// Longest clause starting at `tokens[0]`, with the number of words it spans.
+fn match_clause(tokens: &[Token]) -> Option<(&'static str, bool, usize)> {
+    let block = BLOCK_CLAUSES.iter().map(|c| (*c, true));
+    let line = LINE_CLAUSES.iter().map(|c| (*c, false));
+    block
+        .chain(line)
+        .filter_map(|(clause, is_block)| {
+            let words: Vec<&str> = clause.split(' ').collect();
+            let matches = words.len() <= tokens.len()
+                && words.iter().zip(tokens).all(|(w, t)| {
+                    matches!(t, Token::Word(word) if word.eq_ignore_ascii_case(w))
+                });
+            matches.then_some((clause, is_block, words.len()))
+        })
+        .max_by_key(|(_, _, len)| *len)
+}
+
+/// Re-flow SQL with one clause per line and upper-cased keywords. Only
+/// whitespace and keyword case change; the statement isn't parsed.
+pub(super) fn format_sql(text: &str, _param: &str) -> Result<String, RegexError> {
+    let tokens = tokenize(text)?;
+    if tokens.is_empty() {
+        return Err(RegexError::InvalidInput("no SQL found".to_string()));
+    }
+
+    let mut f = Formatter {
+        out: String::new(),
+        depth: 0,
+        parens: Vec::new(),
+        prev: Prev::Fresh,
+        case_depth: 0,
+        in_between: false,
+    };
+    let mut i = 0;
+    while i < tokens.len() {
+        match tokens[i] {
+            Token::Word(word) => {
+                let clause = f.at_query_level().then(|| match_clause(&tokens[i..])).flatten();
+                if let Some((clause, is_block, len)) = clause {
+                    f.newline(f.depth);
+                    f.write(clause, false, Prev::Keyword);
+                    if is_block {
+                        f.newline(f.depth + 1);
+                    }
+                    f.in_between = false;
+                    i += len;
+                    continue;
+                }
+                f.word(word);
+            }
+            Token::Quoted(quoted) => {
+                let space = !matches!(f.prev, Prev::Open | Prev::Dot | Prev::Unary);
+                f.write(quoted, space, Prev::Word);
+            }
+            Token::Comment(comment) => {
+                f.write(comment, true, Prev::Word);
+                if comment.starts_with("--") {
+                    f.newline(f.depth);
+                }
+            }
+            Token::Symbol(symbol) => f.symbol(symbol, tokens.get(i + 1)),
+        }
+        i += 1;
+    }
+
+    Ok(f.out.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_select() {
+        let sql = "select id, name from users u left join orders o on o.user_id = u.id \
+                   where u.active = 1 and o.total between 10 and 20 order by name desc;";
+        assert_eq!(
+            format_sql(sql, "").unwrap(),
+            "SELECT\n  id,\n  name\nFROM\n  users u\nLEFT JOIN orders o ON o.user_id = u.id\n\
+             WHERE\n  u.active = 1\n  AND o.total BETWEEN 10 AND 20\nORDER BY\n  name DESC;"
+        );
+    }
+
+    #[test]
+    fn test_format_subquery_and_functions() {
+        let sql = "SELECT count(*) FROM t WHERE id IN (select user_id from orders where total > -5)";
+        assert_eq!(
+            format_sql(sql, "").unwrap(),
+            "SELECT\n  count(*)\nFROM\n  t\nWHERE\n  id IN (\n    SELECT\n      user_id\n    FROM\n      orders\n    WHERE\n      total > -5\n  )"
+        );
+    }
+
+    #[test]
+    fn test_format_keeps_literals_and_comments() {
+        let sql = "-- active only\nupdate users set name = 'it''s from here', note = \"Select\" where id = :id";
+        assert_eq!(
+            format_sql(sql, "").unwrap(),
+            "-- active only\nUPDATE users\nSET\n  name = 'it''s from here',\n  note = \"Select\"\nWHERE\n  id = :id"
+        );
+    }
+
+    #[test]
+    fn test_format_rejects_bad_input() {
+        assert!(matches!(format_sql("  ", ""), Err(RegexError::InvalidInput(_))));
+        assert!(matches!(format_sql("select 'oops", ""), Err(RegexError::InvalidInput(_))));
+    }
+}
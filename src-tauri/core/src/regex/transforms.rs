@@ -0,0 +1,955 @@
+use chrono::Local;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use uuid::Uuid;
+
+use super::sql::format_sql;
+use super::{RegexError, Rule, RuleCategory, RuleKind};
+use crate::calc::calculate;
+use crate::text::{
+    classify_id, clean_url, convert_encoding, decode_base64, detect_delimiter, encoding_for_label, file_name,
+    find_colors, fix_mojibake,
+    is_separator_row, parse_table, parse_timestamp, replace_colors, shell_quote,
+    split_delimited_line, split_markdown_row, strip_quoted, strip_tracking, to_posix_path,
+    to_windows_path,
+};
+
+/// Transform entry point: `(text, param)`. Transforms that take no parameter ignore it.
+pub type TransformFn = fn(&str, &str) -> Result<String, RegexError>;
+
+/// A local, non-regex text transform exposed through the rules engine.
+pub struct BuiltinTransform {
+    pub rule: Rule,
+    pub apply: TransformFn,
+}
+
+static URL_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?i)(?:https?|ftp)://[^\s<>"'`，。、（）「」]+"#).unwrap()
+});
+
+static EMAIL_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b[A-Za-z0-9._%+-]+@[A-Za-z0-9.-]+\.[A-Za-z]{2,}\b").unwrap()
+});
+
+static IPV4_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b(?:(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\.){3}(?:25[0-5]|2[0-4]\d|1\d\d|[1-9]?\d)\b").unwrap()
+});
+
+// Level tokens and exception names that mark a log line as an error
+static ERROR_LINE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i:\b(?:err|error|errors|fatal|crit|critical|severe|panic|panicked|caused by)\b)|\w(?:Error|Exception)\b")
+        .unwrap()
+});
+
+static UUID_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"\b[0-9A-Fa-f]{8}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{4}-[0-9A-Fa-f]{12}\b").unwrap()
+});
+
+// Integers/decimals, optionally signed or with thousands separators
+static NUMBER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"-?(?:\d{1,3}(?:,\d{3})+|\d+)(?:\.\d+)?").unwrap()
+});
+
+fn transform(
+    id: &str,
+    name: &str,
+    description: &str,
+    category: RuleCategory,
+    apply: TransformFn,
+) -> BuiltinTransform {
+    BuiltinTransform {
+        rule: Rule {
+            id: id.to_string(),
+            name: name.to_string(),
+            description: description.to_string(),
+            pattern: id.to_string(),
+            replacement: String::new(),
+            is_builtin: true,
+            kind: RuleKind::Transform,
+            category,
+            ..Default::default()
+        },
+        apply,
+    }
+}
+
+/// Parameterized transform; `default_param` is stored in `replacement` and used
+/// when the caller doesn't supply one.
+fn param_transform(
+    id: &str,
+    name: &str,
+    description: &str,
+    default_param: &str,
+    apply: TransformFn,
+) -> BuiltinTransform {
+    let mut t = transform(id, name, description, RuleCategory::Format, apply);
+    t.rule.replacement = default_param.to_string();
+    t
+}
+
+pub static BUILTIN_TRANSFORMS: Lazy<Vec<BuiltinTransform>> = Lazy::new(|| {
+    vec![
+        transform(
+            "to_markdown_table",
+            "To Markdown Table",
+            "Convert TSV/CSV text into an aligned Markdown table",
+            RuleCategory::Format,
+            to_markdown_table,
+        ),
+        transform(
+            "markdown_table_to_tsv",
+            "Markdown Table To TSV",
+            "Convert a Markdown table into tab-separated values",
+            RuleCategory::Format,
+            markdown_table_to_tsv,
+        ),
+        transform(
+            "extract_urls",
+            "Extract URLs",
+            "List every URL found in the text, one per line",
+            RuleCategory::Extract,
+            extract_urls,
+        ),
+        transform(
+            "extract_emails",
+            "Extract Emails",
+            "List every email address found in the text, one per line",
+            RuleCategory::Extract,
+            extract_emails,
+        ),
+        transform(
+            "extract_numbers",
+            "Extract Numbers",
+            "List every number found in the text, one per line",
+            RuleCategory::Extract,
+            extract_numbers,
+        ),
+        transform(
+            "extract_ips",
+            "Extract IP Addresses",
+            "List every IPv4 address found in the text, one per line",
+            RuleCategory::Extract,
+            extract_ips,
+        ),
+        transform(
+            "extract_errors",
+            "Extract Error Messages",
+            "Keep only the error lines of a log or stack trace, without repeats",
+            RuleCategory::Extract,
+            extract_errors,
+        ),
+        transform(
+            "strip_diff_markers",
+            "Strip Diff Markers",
+            "Keep the new side of a unified diff, without headers or +/- markers",
+            RuleCategory::Dev,
+            strip_diff_markers,
+        ),
+        transform(
+            "decode_base64",
+            "Decode Base64",
+            "Decode a Base64 blob or data URI that holds text",
+            RuleCategory::Dev,
+            decode_base64_text,
+        ),
+        transform(
+            "new_uuid",
+            "New UUID",
+            "Replace each UUID with a new random one, or create one if there are none",
+            RuleCategory::Dev,
+            new_uuid,
+        ),
+        transform(
+            "toggle_case",
+            "Toggle Case",
+            "Upper-case the text, or lower-case it if it's already all upper case",
+            RuleCategory::Format,
+            toggle_case,
+        ),
+        transform(
+            "identify_ids",
+            "Identify IDs",
+            "Name the likely format of each UUID or hash, one per line",
+            RuleCategory::Dev,
+            identify_ids,
+        ),
+        transform(
+            "convert_timestamp",
+            "Convert Timestamp",
+            "Show each Unix timestamp or ISO datetime as local time, UTC and Unix seconds",
+            RuleCategory::Dev,
+            convert_timestamp,
+        ),
+        transform(
+            "color_to_hex",
+            "Colors To HEX",
+            "Rewrite every RGB/HSL color as #rrggbb",
+            RuleCategory::Format,
+            color_to_hex,
+        ),
+        transform(
+            "color_to_rgb",
+            "Colors To RGB",
+            "Rewrite every HEX/HSL color as rgb()",
+            RuleCategory::Format,
+            color_to_rgb,
+        ),
+        transform(
+            "color_to_hsl",
+            "Colors To HSL",
+            "Rewrite every HEX/RGB color as hsl()",
+            RuleCategory::Format,
+            color_to_hsl,
+        ),
+        transform(
+            "color_palette",
+            "Color Palette",
+            "List each distinct color once, in HEX, RGB and HSL",
+            RuleCategory::Extract,
+            color_palette,
+        ),
+        {
+            let mut t = param_transform(
+                "extract_column",
+                "Extract Column",
+                "List one column of a CSV/TSV/Markdown table; the parameter is a 1-based index or a header name",
+                "1",
+                extract_column,
+            );
+            t.rule.category = RuleCategory::Extract;
+            t
+        },
+        transform(
+            "strip_tracking_params",
+            "Strip Tracking Parameters",
+            "Remove utm_*, fbclid, gclid and similar click-tracking parameters from every URL",
+            RuleCategory::Cleanup,
+            strip_tracking_params,
+        ),
+        transform(
+            "clean_url",
+            "Clean URL",
+            "Strip tracking and share parameters from every URL and normalize its scheme, host and port",
+            RuleCategory::Cleanup,
+            clean_urls,
+        ),
+        transform(
+            "qr_code",
+            "QR Code",
+            "Render the text as a QR code drawn with block characters",
+            RuleCategory::Format,
+            qr_code,
+        ),
+        transform(
+            "strip_quoted_text",
+            "Strip Quoted Text",
+            "Keep only the newest message of an email thread, without `>` quotes or earlier messages",
+            RuleCategory::Cleanup,
+            strip_quoted_text,
+        ),
+        {
+            let mut t = param_transform(
+                "fix_encoding",
+                "Fix Encoding",
+                "Recover garbled text decoded with the wrong encoding (GBK, Big5, Shift_JIS, UTF-8); the parameter `<misread as>:<real encoding>`, e.g. `windows-1252:gbk`, overrides the guess",
+                "",
+                fix_encoding,
+            );
+            t.rule.category = RuleCategory::Cleanup;
+            t
+        },
+        transform(
+            "calculate",
+            "Calculate",
+            "Evaluate an arithmetic expression or unit conversion such as `72 inches in cm`",
+            RuleCategory::Format,
+            calculate_text,
+        ),
+        transform(
+            "paths_to_posix",
+            "Paths To POSIX",
+            "Use forward slashes in every path, one per line",
+            RuleCategory::Format,
+            paths_to_posix,
+        ),
+        transform(
+            "paths_to_windows",
+            "Paths To Windows",
+            "Use backslashes in every path, one per line",
+            RuleCategory::Format,
+            paths_to_windows,
+        ),
+        transform(
+            "shell_quote_paths",
+            "Quote Paths For Shell",
+            "Quote each path that contains spaces or shell characters",
+            RuleCategory::Dev,
+            shell_quote_paths,
+        ),
+        transform(
+            "extract_file_names",
+            "Extract File Names",
+            "Keep only the last component of each path",
+            RuleCategory::Extract,
+            extract_file_names,
+        ),
+        transform(
+            "format_sql",
+            "Format SQL",
+            "Put each SQL clause on its own line and upper-case keywords",
+            RuleCategory::Dev,
+            format_sql,
+        ),
+        param_transform(
+            "prefix_lines",
+            "Prefix Lines",
+            "Prepend text to every line (e.g. `> ` for quotes, `// ` for comments)",
+            "> ",
+            prefix_lines,
+        ),
+        param_transform(
+            "suffix_lines",
+            "Suffix Lines",
+            "Append text to every line",
+            ",",
+            suffix_lines,
+        ),
+        param_transform(
+            "number_lines",
+            "Number Lines",
+            "Number non-empty lines; `{n}` in the parameter is replaced by the line number",
+            "{n}. ",
+            number_lines,
+        ),
+    ]
+});
+
+pub fn find_transform(id: &str) -> Option<&'static BuiltinTransform> {
+    BUILTIN_TRANSFORMS.iter().find(|t| t.rule.id == id)
+}
+
+/// Approximate monospace display width: CJK and fullwidth characters take two columns.
+fn display_width(s: &str) -> usize {
+    s.chars()
+        .map(|c| match c as u32 {
+            0x1100..=0x115F
+            | 0x2E80..=0x303E
+            | 0x3041..=0x33FF
+            | 0x3400..=0x4DBF
+            | 0x4E00..=0x9FFF
+            | 0xA000..=0xA4CF
+            | 0xAC00..=0xD7A3
+            | 0xF900..=0xFAFF
+            | 0xFE30..=0xFE4F
+            | 0xFF00..=0xFF60
+            | 0xFFE0..=0xFFE6 => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+fn pad(cell: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(cell));
+    format!("{}{}", cell, " ".repeat(padding))
+}
+
+fn to_markdown_table(text: &str, _param: &str) -> Result<String, RegexError> {
+    let delimiter = detect_delimiter(text);
+    let rows: Vec<Vec<String>> = text
+        .lines()
+        .filter(|l| !l.trim().is_empty())
+        .map(|l| {
+            split_delimited_line(l, delimiter)
+                .into_iter()
+                .map(|c| c.replace('|', "\\|"))
+                .collect()
+        })
+        .collect();
+
+    let columns = rows.iter().map(|r| r.len()).max().unwrap_or(0);
+    if rows.is_empty() || columns < 2 {
+        return Err(RegexError::InvalidInput("no tabular data found".to_string()));
+    }
+
+    // Separator dashes need at least 3 columns to be valid Markdown
+    let mut widths = vec![3; columns];
+    for row in &rows {
+        for (i, cell) in row.iter().enumerate() {
+            widths[i] = widths[i].max(display_width(cell));
+        }
+    }
+
+    let render_row = |row: &[String]| {
+        let cells: Vec<String> = (0..columns)
+            .map(|i| pad(row.get(i).map(String::as_str).unwrap_or(""), widths[i]))
+            .collect();
+        format!("| {} |", cells.join(" | "))
+    };
+
+    let mut lines = Vec::with_capacity(rows.len() + 1);
+    lines.push(render_row(&rows[0]));
+    let separator: Vec<String> = widths.iter().map(|w| "-".repeat(*w)).collect();
+    lines.push(format!("| {} |", separator.join(" | ")));
+    for row in &rows[1..] {
+        lines.push(render_row(row));
+    }
+
+    Ok(lines.join("\n"))
+}
+
+fn markdown_table_to_tsv(text: &str, _param: &str) -> Result<String, RegexError> {
+    let rows: Vec<Vec<String>> = text
+        .lines()
+        .filter(|l| l.trim_start().starts_with('|'))
+        .map(split_markdown_row)
+        .filter(|cells| !is_separator_row(cells))
+        .collect();
+
+    if rows.is_empty() {
+        return Err(RegexError::InvalidInput("no Markdown table found".to_string()));
+    }
+
+    Ok(rows
+        .iter()
+        .map(|r| r.join("\t"))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn extract_column(text: &str, param: &str) -> Result<String, RegexError> {
+    let table =
+        parse_table(text).ok_or_else(|| RegexError::InvalidInput("no tabular data found".to_string()))?;
+    let param = param.trim();
+    let index = match param.parse::<usize>() {
+        Ok(n) if n >= 1 => n - 1,
+        _ => table
+            .info
+            .columns
+            .iter()
+            .position(|c| c.eq_ignore_ascii_case(param))
+            .ok_or_else(|| RegexError::InvalidInput(format!("no column '{}'", param)))?,
+    };
+    if index >= table.info.column_count {
+        return Err(RegexError::InvalidInput(format!(
+            "table has only {} columns",
+            table.info.column_count
+        )));
+    }
+
+    Ok(table
+        .rows
+        .iter()
+        .map(|row| row.get(index).map(String::as_str).unwrap_or(""))
+        .collect::<Vec<_>>()
+        .join("\n"))
+}
+
+fn extract_matches<'t>(
+    text: &'t str,
+    regex: &Regex,
+    what: &str,
+    clean: impl Fn(&'t str) -> &'t str,
+) -> Result<String, RegexError> {
+    let matches: Vec<&str> = regex
+        .find_iter(text)
+        .map(|m| clean(m.as_str()))
+        .filter(|m| !m.is_empty())
+        .collect();
+
+    if matches.is_empty() {
+        return Err(RegexError::InvalidInput(format!("no {} found", what)));
+    }
+    Ok(matches.join("\n"))
+}
+
+fn extract_urls(text: &str, _param: &str) -> Result<String, RegexError> {
+    // Sentence punctuation directly after a URL is almost never part of it
+    extract_matches(text, &URL_REGEX, "URLs", |m| {
+        let trimmed = m.trim_end_matches(['.', ',', ';', ':', '!', '?']);
+        if trimmed.ends_with(')') && !trimmed.contains('(') {
+            &trimmed[..trimmed.len() - 1]
+        } else {
+            trimmed
+        }
+    })
+}
+
+fn extract_emails(text: &str, _param: &str) -> Result<String, RegexError> {
+    extract_matches(text, &EMAIL_REGEX, "email addresses", |m| m)
+}
+
+fn extract_numbers(text: &str, _param: &str) -> Result<String, RegexError> {
+    extract_matches(text, &NUMBER_REGEX, "numbers", |m| m)
+}
+
+fn extract_ips(text: &str, _param: &str) -> Result<String, RegexError> {
+    extract_matches(text, &IPV4_REGEX, "IP addresses", |m| m)
+}
+
+fn extract_errors(text: &str, _param: &str) -> Result<String, RegexError> {
+    let mut seen = std::collections::HashSet::new();
+    let lines: Vec<&str> = text
+        .lines()
+        .map(str::trim)
+        .filter(|line| ERROR_LINE_REGEX.is_match(line) && seen.insert(*line))
+        .collect();
+
+    if lines.is_empty() {
+        return Err(RegexError::InvalidInput("no error messages found".to_string()));
+    }
+    Ok(lines.join("\n"))
+}
+
+fn strip_diff_markers(text: &str, _param: &str) -> Result<String, RegexError> {
+    let mut kept = Vec::new();
+    let mut found = false;
+    let mut in_header = false;
+    let mut lines = text.lines().peekable();
+    while let Some(line) = lines.next() {
+        if line.starts_with("diff ") {
+            in_header = true;
+        } else if line.starts_with("--- ") && lines.peek().is_some_and(|l| l.starts_with("+++ ")) {
+            lines.next();
+            in_header = true;
+        } else if line.starts_with("@@") {
+            in_header = false;
+        } else {
+            // Removed lines and `\ No newline at end of file` notes go too
+            if !in_header && !line.starts_with(['-', '\\']) {
+                kept.push(line.strip_prefix(['+', ' ']).unwrap_or(line));
+            }
+            continue;
+        }
+        found = true;
+    }
+
+    if !found {
+        return Err(RegexError::InvalidInput("no diff found".to_string()));
+    }
+    Ok(kept.join("\n"))
+}
+
+fn decode_base64_text(text: &str, _param: &str) -> Result<String, RegexError> {
+    let decoded = decode_base64(text)
+        .ok_or_else(|| RegexError::InvalidInput("not valid Base64".to_string()))?;
+    decoded
+        .as_text()
+        .map(str::to_string)
+        .ok_or_else(|| RegexError::InvalidInput("decoded data is binary".to_string()))
+}
+
+fn new_uuid(text: &str, _param: &str) -> Result<String, RegexError> {
+    if !UUID_REGEX.is_match(text) {
+        return Ok(Uuid::new_v4().to_string());
+    }
+    Ok(UUID_REGEX
+        .replace_all(text, |caps: &regex::Captures| {
+            let uuid = Uuid::new_v4().to_string();
+            if caps[0].chars().any(|c| c.is_ascii_uppercase()) {
+                uuid.to_uppercase()
+            } else {
+                uuid
+            }
+        })
+        .into_owned())
+}
+
+fn toggle_case(text: &str, _param: &str) -> Result<String, RegexError> {
+    if text.chars().any(char::is_lowercase) {
+        Ok(text.to_uppercase())
+    } else {
+        Ok(text.to_lowercase())
+    }
+}
+
+fn identify_ids(text: &str, _param: &str) -> Result<String, RegexError> {
+    Ok(map_lines(text, |line| {
+        let token = line.trim();
+        match classify_id(token) {
+            Some(kind) => format!("{}: {}", token, kind.describe()),
+            None if token.is_empty() => String::new(),
+            None => format!("{}: unknown format", token),
+        }
+    }))
+}
+
+fn convert_timestamp(text: &str, _param: &str) -> Result<String, RegexError> {
+    let mut found = false;
+    let result = map_lines(text, |line| match parse_timestamp(line) {
+        Some(utc) => {
+            found = true;
+            format!(
+                "{} | {} | {}",
+                utc.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S %:z"),
+                utc.format("%Y-%m-%d %H:%M:%S UTC"),
+                utc.timestamp()
+            )
+        }
+        None => line.to_string(),
+    });
+
+    if !found {
+        return Err(RegexError::InvalidInput("no timestamps found".to_string()));
+    }
+    Ok(result)
+}
+
+fn color_to_hex(text: &str, _param: &str) -> Result<String, RegexError> {
+    Ok(replace_colors(text, |c| c.to_hex()))
+}
+
+fn color_to_rgb(text: &str, _param: &str) -> Result<String, RegexError> {
+    Ok(replace_colors(text, |c| c.to_rgb()))
+}
+
+fn color_to_hsl(text: &str, _param: &str) -> Result<String, RegexError> {
+    Ok(replace_colors(text, |c| c.to_hsl()))
+}
+
+fn color_palette(text: &str, _param: &str) -> Result<String, RegexError> {
+    let mut seen = std::collections::HashSet::new();
+    let lines: Vec<String> = find_colors(text)
+        .iter()
+        .filter(|c| seen.insert(c.to_hex()))
+        .map(|c| format!("{}  {}  {}", c.to_hex(), c.to_rgb(), c.to_hsl()))
+        .collect();
+
+    if lines.is_empty() {
+        return Err(RegexError::InvalidInput("no colors found".to_string()));
+    }
+    Ok(lines.join("\n"))
+}
+
+fn strip_tracking_params(text: &str, _param: &str) -> Result<String, RegexError> {
+    if !URL_REGEX.is_match(text) {
+        return Err(RegexError::InvalidInput("no URLs found".to_string()));
+    }
+    Ok(URL_REGEX
+        .replace_all(text, |caps: &regex::Captures| strip_tracking(&caps[0]))
+        .into_owned())
+}
+
+fn clean_urls(text: &str, _param: &str) -> Result<String, RegexError> {
+    if !URL_REGEX.is_match(text) {
+        return Err(RegexError::InvalidInput("no URLs found".to_string()));
+    }
+    Ok(URL_REGEX
+        .replace_all(text, |caps: &regex::Captures| clean_url(&caps[0]))
+        .into_owned())
+}
+
+fn qr_code(text: &str, _param: &str) -> Result<String, RegexError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Err(RegexError::InvalidInput("nothing to encode".to_string()));
+    }
+    let code = qrcode::QrCode::new(text.as_bytes())
+        .map_err(|e| RegexError::InvalidInput(format!("cannot make a QR code: {}", e)))?;
+    Ok(code.render::<qrcode::render::unicode::Dense1x2>().build())
+}
+
+fn strip_quoted_text(text: &str, _param: &str) -> Result<String, RegexError> {
+    strip_quoted(text).ok_or_else(|| RegexError::InvalidInput("no quoted text found".to_string()))
+}
+
+fn fix_encoding(text: &str, param: &str) -> Result<String, RegexError> {
+    let param = param.trim();
+    if param.is_empty() {
+        return fix_mojibake(text)
+            .map(|fixed| fixed.text)
+            .ok_or_else(|| RegexError::InvalidInput("the text doesn't look garbled".to_string()));
+    }
+    let (misread_as, encoding) = param
+        .split_once(':')
+        .ok_or_else(|| RegexError::InvalidInput("expected `<misread as>:<real encoding>`".to_string()))?;
+    let label = |name: &str| {
+        encoding_for_label(name).ok_or_else(|| RegexError::InvalidInput(format!("unknown encoding '{}'", name.trim())))
+    };
+    convert_encoding(text, label(misread_as)?, label(encoding)?).ok_or_else(|| {
+        RegexError::InvalidInput(format!("the text isn't {} read as {}", encoding.trim(), misread_as.trim()))
+    })
+}
+
+fn calculate_text(text: &str, _param: &str) -> Result<String, RegexError> {
+    calculate(text).ok_or_else(|| RegexError::InvalidInput("not a calculation".to_string()))
+}
+
+/// Apply `f` to each non-blank line.
+fn map_paths(text: &str, f: impl Fn(&str) -> String) -> String {
+    map_lines(text, |line| if line.trim().is_empty() { String::new() } else { f(line) })
+}
+
+fn paths_to_posix(text: &str, _param: &str) -> Result<String, RegexError> {
+    Ok(map_paths(text, to_posix_path))
+}
+
+fn paths_to_windows(text: &str, _param: &str) -> Result<String, RegexError> {
+    Ok(map_paths(text, to_windows_path))
+}
+
+fn shell_quote_paths(text: &str, _param: &str) -> Result<String, RegexError> {
+    Ok(map_paths(text, shell_quote))
+}
+
+fn extract_file_names(text: &str, _param: &str) -> Result<String, RegexError> {
+    Ok(map_paths(text, |line| file_name(line).to_string()))
+}
+
+/// Apply `f` to each line, preserving the original line ending style and a
+/// trailing newline if present.
+fn map_lines(text: &str, mut f: impl FnMut(&str) -> String) -> String {
+    let ending = if text.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut result = text.lines().map(&mut f).collect::<Vec<_>>().join(ending);
+    if text.ends_with('\n') {
+        result.push_str(ending);
+    }
+    result
+}
+
+fn prefix_lines(text: &str, param: &str) -> Result<String, RegexError> {
+    Ok(map_lines(text, |line| format!("{}{}", param, line)))
+}
+
+fn suffix_lines(text: &str, param: &str) -> Result<String, RegexError> {
+    Ok(map_lines(text, |line| format!("{}{}", line, param)))
+}
+
+fn number_lines(text: &str, param: &str) -> Result<String, RegexError> {
+    let template = if param.contains("{n}") {
+        param.to_string()
+    } else {
+        format!("{{n}}{}", param)
+    };
+
+    let mut n = 0;
+    Ok(map_lines(text, |line| {
+        if line.trim().is_empty() {
+            return line.to_string();
+        }
+        n += 1;
+        format!("{}{}", template.replace("{n}", &n.to_string()), line)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tsv_to_markdown_table() {
+        let result = to_markdown_table("name\tage\nAlice\t30\nBob\t5", "").unwrap();
+        assert_eq!(
+            result,
+            "| name  | age |\n| ----- | --- |\n| Alice | 30  |\n| Bob   | 5   |"
+        );
+    }
+
+    #[test]
+    fn test_csv_to_markdown_table_cjk_and_pipes() {
+        let result = to_markdown_table("名字,备注\n张三,a|b", "").unwrap();
+        assert_eq!(result, "| 名字 | 备注 |\n| ---- | ---- |\n| 张三 | a\\|b |");
+    }
+
+    #[test]
+    fn test_markdown_table_round_trip() {
+        let tsv = "name\tage\nAlice\t30";
+        let md = to_markdown_table(tsv, "").unwrap();
+        assert_eq!(markdown_table_to_tsv(&md, "").unwrap(), tsv);
+    }
+
+    #[test]
+    fn test_markdown_table_to_tsv_alignment_row() {
+        let md = "| a | b |\n|:---|---:|\n| 1 | x\\|y |";
+        assert_eq!(markdown_table_to_tsv(md, "").unwrap(), "a\tb\n1\tx|y");
+    }
+
+    #[test]
+    fn test_to_markdown_table_rejects_single_column() {
+        assert!(matches!(
+            to_markdown_table("just\nwords", ""),
+            Err(RegexError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_fix_encoding() {
+        assert_eq!(fix_encoding("ä½\u{a0}å¥½ä¸–ç•Œ", "").unwrap(), "你好世界");
+        assert_eq!(fix_encoding("Ä³Ìå", "latin1:gbk").unwrap(), "某体");
+        assert!(fix_encoding("plain text", "").is_err());
+        assert!(fix_encoding("abc", "ebcdic:gbk").is_err());
+    }
+
+    #[test]
+    fn test_extract_column() {
+        let csv = "name,age\nAlice,30\n\"Bob, Jr.\",5";
+        assert_eq!(extract_column(csv, "1").unwrap(), "Alice\nBob, Jr.");
+        assert_eq!(extract_column(csv, "Age").unwrap(), "30\n5");
+        assert!(matches!(extract_column(csv, "3"), Err(RegexError::InvalidInput(_))));
+        assert!(matches!(extract_column("plain text", "1"), Err(RegexError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_extract_urls() {
+        let text = "See https://example.com/a?b=1, and (http://foo.org/x). 中文https://bar.cn/路径，结束";
+        assert_eq!(
+            extract_urls(text, "").unwrap(),
+            "https://example.com/a?b=1\nhttp://foo.org/x\nhttps://bar.cn/路径"
+        );
+    }
+
+    #[test]
+    fn test_extract_emails_and_ips() {
+        let text = "mail a@b.com or c.d@e.org from 10.0.0.1 / 999.1.1.1";
+        assert_eq!(extract_emails(text, "").unwrap(), "a@b.com\nc.d@e.org");
+        assert_eq!(extract_ips(text, "").unwrap(), "10.0.0.1");
+    }
+
+    #[test]
+    fn test_extract_numbers() {
+        let text = "Total 1,234.50 items, -3 left, version 2";
+        assert_eq!(extract_numbers(text, "").unwrap(), "1,234.50\n-3\n2");
+    }
+
+    #[test]
+    fn test_extract_no_matches() {
+        assert!(matches!(extract_urls("nothing here", ""), Err(RegexError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_extract_errors() {
+        let log = "2024-05-01 10:00:00 INFO started\n\
+                   2024-05-01 10:00:01 ERROR db timeout\n\
+                   java.lang.IllegalStateException: closed\n\
+                   \tat com.example.Db.query(Db.java:42)\n\
+                   2024-05-01 10:00:01 ERROR db timeout\n\
+                   Caused by: java.net.SocketException: reset";
+        assert_eq!(
+            extract_errors(log, "").unwrap(),
+            "2024-05-01 10:00:01 ERROR db timeout\n\
+             java.lang.IllegalStateException: closed\n\
+             Caused by: java.net.SocketException: reset"
+        );
+        assert!(matches!(extract_errors("all good", ""), Err(RegexError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_strip_diff_markers() {
+        let diff = "diff --git a/src/a.rs b/src/a.rs\n\
+                    index 1234567..89abcde 100644\n\
+                    --- a/src/a.rs\n\
+                    +++ b/src/a.rs\n\
+                    @@ -1,3 +1,3 @@\n\
+                    \x20fn main() {\n\
+                    -    old();\n\
+                    +    new();\n\
+                    \x20}\n\
+                    \\ No newline at end of file";
+        assert_eq!(strip_diff_markers(diff, "").unwrap(), "fn main() {\n    new();\n}");
+        assert!(matches!(strip_diff_markers("+ not a diff", ""), Err(RegexError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_decode_base64_text() {
+        assert_eq!(decode_base64_text("aGVsbG8=", "").unwrap(), "hello");
+        assert!(matches!(
+            decode_base64_text("data:image/png;base64,iVBORw0KGgo=", ""),
+            Err(RegexError::InvalidInput(_))
+        ));
+    }
+
+    #[test]
+    fn test_new_uuid_keeps_case() {
+        let upper = "ID 550E8400-E29B-41D4-A716-446655440000;";
+        let result = new_uuid(upper, "").unwrap();
+        assert_ne!(result, upper);
+        assert!(result.starts_with("ID ") && result.ends_with(';'));
+        assert_eq!(result, result.to_uppercase());
+        assert!(UUID_REGEX.is_match(&new_uuid("", "").unwrap()));
+    }
+
+    #[test]
+    fn test_toggle_case() {
+        assert_eq!(toggle_case("abc-DEF", "").unwrap(), "ABC-DEF");
+        assert_eq!(toggle_case("ABC-DEF", "").unwrap(), "abc-def");
+    }
+
+    #[test]
+    fn test_identify_ids() {
+        assert_eq!(
+            identify_ids("d41d8cd98f00b204e9800998ecf8427e\n\nnope", "").unwrap(),
+            "d41d8cd98f00b204e9800998ecf8427e: MD5 hash (32 hex digits)\n\nnope: unknown format"
+        );
+    }
+
+    #[test]
+    fn test_convert_timestamp() {
+        let local = chrono::DateTime::from_timestamp(1700000000, 0)
+            .unwrap()
+            .with_timezone(&Local)
+            .format("%Y-%m-%d %H:%M:%S %:z")
+            .to_string();
+        let expected = format!("{} | 2023-11-14 22:13:20 UTC | 1700000000", local);
+        assert_eq!(
+            convert_timestamp("1700000000\n2023-11-14T22:13:20Z\nn/a", "").unwrap(),
+            format!("{}\n{}\nn/a", expected, expected)
+        );
+        assert!(matches!(convert_timestamp("soon", ""), Err(RegexError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_color_transforms() {
+        assert_eq!(color_to_rgb("a: #f80; b: #fff", "").unwrap(), "a: rgb(255, 136, 0); b: rgb(255, 255, 255)");
+        assert_eq!(color_to_hex("hsl(0, 100%, 50%)", "").unwrap(), "#ff0000");
+        assert_eq!(
+            color_palette("#ff0000, rgb(255, 0, 0)\n#00f", "").unwrap(),
+            "#ff0000  rgb(255, 0, 0)  hsl(0, 100%, 50%)\n#0000ff  rgb(0, 0, 255)  hsl(240, 100%, 50%)"
+        );
+        assert!(matches!(color_palette("plain", ""), Err(RegexError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_strip_tracking_params() {
+        assert_eq!(
+            strip_tracking_params("see https://a.com/p?utm_source=x&id=2 and http://b.org/?fbclid=1", "").unwrap(),
+            "see https://a.com/p?id=2 and http://b.org/"
+        );
+        assert!(matches!(strip_tracking_params("no links", ""), Err(RegexError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_clean_urls() {
+        assert_eq!(
+            clean_urls("HTTPS://WWW.Bilibili.com/video/BV1?share_source=copy_web&vd_source=9 看", "").unwrap(),
+            "https://www.bilibili.com/video/BV1 看"
+        );
+    }
+
+    #[test]
+    fn test_qr_code() {
+        let qr = qr_code("https://example.com", "").unwrap();
+        assert!(qr.lines().count() > 10);
+        assert!(qr.contains('█'));
+        assert!(matches!(qr_code("x".repeat(4000).as_str(), ""), Err(RegexError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_strip_quoted_text() {
+        assert_eq!(strip_quoted_text("Yes.\n\n> Lunch?\n> Ann", "").unwrap(), "Yes.");
+        assert!(matches!(strip_quoted_text("plain", ""), Err(RegexError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_path_transforms() {
+        let paths = "C:\\Users\\me\\a b.txt\n\nC:\\tmp\\";
+        assert_eq!(paths_to_posix(paths, "").unwrap(), "C:/Users/me/a b.txt\n\nC:/tmp/");
+        assert_eq!(shell_quote_paths(paths, "").unwrap(), "\"C:\\Users\\me\\a b.txt\"\n\nC:\\tmp\\");
+        assert_eq!(extract_file_names(paths, "").unwrap(), "a b.txt\n\ntmp");
+    }
+
+    #[test]
+    fn test_number_lines_skips_blank_lines() {
+        let result = number_lines("a\n\nb\r\nc", "{n}. ").unwrap();
+        assert_eq!(result, "1. a\r\n\r\n2. b\r\n3. c");
+        assert_eq!(number_lines("a\nb", "\t").unwrap(), "1\ta\n2\tb");
+    }
+}
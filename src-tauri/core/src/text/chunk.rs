@@ -0,0 +1,138 @@
+use super::stats::{is_cjk, is_sentence_end, text_stats};
+
+// Rough token cost per character, matching `text_stats`
+const CJK_TOKENS: f64 = 1.0;
+const OTHER_TOKENS: f64 = 0.25;
+
+/// Where `text` may be split, coarsest first: before Markdown headings,
+/// after blank lines, after line breaks, after sentences.
+const LEVELS: usize = 4;
+
+fn is_heading(line: &str) -> bool {
+    let hashes = line.bytes().take_while(|&b| b == b'#').count();
+    (1..=6).contains(&hashes) && line[hashes..].starts_with(' ')
+}
+
+fn boundaries(text: &str, level: usize) -> Vec<usize> {
+    match level {
+        0 => text
+            .match_indices('\n')
+            .map(|(i, _)| i + 1)
+            .filter(|&i| is_heading(&text[i..]))
+            .collect(),
+        1 => text.match_indices("\n\n").map(|(i, _)| i + 2).collect(),
+        2 => text.match_indices('\n').map(|(i, _)| i + 1).collect(),
+        _ => text
+            .char_indices()
+            .filter(|&(_, c)| is_sentence_end(c))
+            .map(|(i, c)| i + c.len_utf8())
+            .collect(),
+    }
+}
+
+/// `text` cut at the boundaries of `level`, separators kept.
+fn pieces(text: &str, level: usize) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut start = 0;
+    for end in boundaries(text, level) {
+        if end > start && end < text.len() {
+            pieces.push(&text[start..end]);
+            start = end;
+        }
+    }
+    pieces.push(&text[start..]);
+    pieces
+}
+
+fn flush(current: &mut String, out: &mut Vec<String>) {
+    let chunk = current.trim();
+    if !chunk.is_empty() {
+        out.push(chunk.to_string());
+    }
+    current.clear();
+}
+
+fn pack(text: &str, level: usize, max_tokens: usize, out: &mut Vec<String>) {
+    let mut current = String::new();
+    let mut current_tokens = 0;
+    for piece in pieces(text, level) {
+        let tokens = text_stats(piece).estimated_tokens;
+        if current_tokens + tokens <= max_tokens {
+            current.push_str(piece);
+            current_tokens += tokens;
+            continue;
+        }
+        flush(&mut current, out);
+        current_tokens = 0;
+        if tokens <= max_tokens {
+            current.push_str(piece);
+            current_tokens = tokens;
+        } else if level + 1 < LEVELS {
+            pack(piece, level + 1, max_tokens, out);
+        } else {
+            cut(piece, max_tokens, out);
+        }
+    }
+    flush(&mut current, out);
+}
+
+/// Last resort for a single sentence longer than a chunk.
+fn cut(text: &str, max_tokens: usize, out: &mut Vec<String>) {
+    let mut current = String::new();
+    let mut tokens = 0.0;
+    for c in text.chars() {
+        let cost = if is_cjk(c) { CJK_TOKENS } else { OTHER_TOKENS };
+        if tokens + cost > max_tokens as f64 && !current.is_empty() {
+            flush(&mut current, out);
+            tokens = 0.0;
+        }
+        current.push(c);
+        tokens += cost;
+    }
+    flush(&mut current, out);
+}
+
+/// Split `text` into chunks of about `max_tokens` at most, keeping sections,
+/// then paragraphs, lines and sentences together where they fit.
+pub fn split_chunks(text: &str, max_tokens: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    pack(text, 0, max_tokens.max(1), &mut chunks);
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_short_text_is_one_chunk() {
+        assert_eq!(
+            split_chunks("Hello world.\n\nSecond.", 100),
+            vec!["Hello world.\n\nSecond."]
+        );
+        assert!(split_chunks("   ", 100).is_empty());
+    }
+
+    #[test]
+    fn test_splits_at_headings_first() {
+        let section = |title: &str| format!("# {}\n\n{}\n\n", title, "word ".repeat(40));
+        let text = format!("{}{}{}", section("One"), section("Two"), section("Three"));
+        let chunks = split_chunks(&text, 80);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks[1].starts_with("# Two"));
+        assert!(chunks.iter().all(|c| text_stats(c).estimated_tokens <= 80));
+    }
+
+    #[test]
+    fn test_long_paragraphs_fall_back_to_sentences_and_chars() {
+        let text = "这是一个很长的句子。".repeat(30);
+        let chunks = split_chunks(&text, 25);
+        assert!(chunks.len() >= 12);
+        assert!(chunks.iter().all(|c| text_stats(c).estimated_tokens <= 25));
+        assert_eq!(chunks.concat(), text);
+
+        let chunks = split_chunks(&"x".repeat(100), 10);
+        assert_eq!(chunks.len(), 3);
+        assert!(chunks.iter().all(|c| text_stats(c).estimated_tokens <= 10));
+    }
+}
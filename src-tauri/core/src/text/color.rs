@@ -0,0 +1,234 @@
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+// `#rgb[a]`, `#rrggbb[aa]`, and CSS `rgb()/rgba()/hsl()/hsla()` calls
+static COLOR_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)#(?:[0-9a-f]{8}|[0-9a-f]{6}|[0-9a-f]{3,4})\b|\b(?:rgba?|hsla?)\([^()]*\)").unwrap()
+});
+
+/// Notation a color was written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorFormat {
+    Hex,
+    Rgb,
+    Hsl,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    /// 0.0-1.0
+    pub a: f64,
+    pub format: ColorFormat,
+}
+
+fn fmt_alpha(a: f64) -> String {
+    format!("{}", (a * 100.0).round() / 100.0)
+}
+
+impl Color {
+    pub fn to_hex(self) -> String {
+        let hex = format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b);
+        if self.a < 1.0 {
+            format!("{}{:02x}", hex, (self.a * 255.0).round() as u8)
+        } else {
+            hex
+        }
+    }
+
+    pub fn to_rgb(self) -> String {
+        if self.a < 1.0 {
+            format!("rgba({}, {}, {}, {})", self.r, self.g, self.b, fmt_alpha(self.a))
+        } else {
+            format!("rgb({}, {}, {})", self.r, self.g, self.b)
+        }
+    }
+
+    pub fn to_hsl(self) -> String {
+        let (r, g, b) = (self.r as f64 / 255.0, self.g as f64 / 255.0, self.b as f64 / 255.0);
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let l = (max + min) / 2.0;
+        let d = max - min;
+        let (h, s) = if d == 0.0 {
+            (0.0, 0.0)
+        } else {
+            let s = d / (1.0 - (2.0 * l - 1.0).abs());
+            let h = if max == r {
+                ((g - b) / d).rem_euclid(6.0)
+            } else if max == g {
+                (b - r) / d + 2.0
+            } else {
+                (r - g) / d + 4.0
+            };
+            (h * 60.0, s)
+        };
+
+        let (h, s, l) = (h.round() as u16 % 360, (s * 100.0).round(), (l * 100.0).round());
+        if self.a < 1.0 {
+            format!("hsla({}, {}%, {}%, {})", h, s, l, fmt_alpha(self.a))
+        } else {
+            format!("hsl({}, {}%, {}%)", h, s, l)
+        }
+    }
+}
+
+fn parse_hex(hex: &str) -> Option<(u8, u8, u8, f64)> {
+    let digits: Vec<u8> = hex
+        .chars()
+        .map(|c| c.to_digit(16).map(|d| d as u8))
+        .collect::<Option<_>>()?;
+    let channels: Vec<u8> = match digits.len() {
+        3 | 4 => digits.iter().map(|d| d * 17).collect(),
+        6 | 8 => digits.chunks(2).map(|p| p[0] * 16 + p[1]).collect(),
+        _ => return None,
+    };
+    let a = channels.get(3).map_or(1.0, |a| *a as f64 / 255.0);
+    Some((channels[0], channels[1], channels[2], a))
+}
+
+/// `50%` as 0.5, anything else as a plain number.
+fn parse_number(part: &str) -> Option<f64> {
+    match part.strip_suffix('%') {
+        Some(pct) => pct.parse::<f64>().ok().map(|v| v / 100.0),
+        None => part.parse().ok(),
+    }
+    .filter(|v| v.is_finite())
+}
+
+fn hsl_to_rgb(h: f64, s: f64, l: f64) -> (u8, u8, u8) {
+    let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
+    let h = h.rem_euclid(360.0) / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u8 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = l - c / 2.0;
+    let to_u8 = |v: f64| ((v + m) * 255.0).round().clamp(0.0, 255.0) as u8;
+    (to_u8(r), to_u8(g), to_u8(b))
+}
+
+pub fn parse_color(text: &str) -> Option<Color> {
+    let text = text.trim();
+    if let Some(hex) = text.strip_prefix('#') {
+        let (r, g, b, a) = parse_hex(hex)?;
+        return Some(Color { r, g, b, a, format: ColorFormat::Hex });
+    }
+
+    let lower = text.to_ascii_lowercase();
+    let (func, args) = lower.strip_suffix(')')?.split_once('(')?;
+    // Both `rgb(1, 2, 3, 0.5)` and `rgb(1 2 3 / 50%)`
+    let parts: Vec<&str> = args
+        .split(|c: char| c == ',' || c == '/' || c.is_whitespace())
+        .filter(|p| !p.is_empty())
+        .collect();
+    if !(3..=4).contains(&parts.len()) {
+        return None;
+    }
+    let a = match parts.get(3) {
+        Some(part) => parse_number(part)?.clamp(0.0, 1.0),
+        None => 1.0,
+    };
+
+    match func.trim() {
+        "rgb" | "rgba" => {
+            let channel = |part: &str| {
+                let value = if part.ends_with('%') { parse_number(part)? * 255.0 } else { parse_number(part)? };
+                (0.0..=255.0).contains(&value).then(|| value.round() as u8)
+            };
+            Some(Color {
+                r: channel(parts[0])?,
+                g: channel(parts[1])?,
+                b: channel(parts[2])?,
+                a,
+                format: ColorFormat::Rgb,
+            })
+        }
+        "hsl" | "hsla" => {
+            let h = parse_number(parts[0].trim_end_matches("deg"))?;
+            // Saturation and lightness are percentages, with or without the sign
+            let pct = |part: &str| {
+                let value = parse_number(part.trim_end_matches('%'))? / 100.0;
+                (0.0..=1.0).contains(&value).then_some(value)
+            };
+            let (r, g, b) = hsl_to_rgb(h, pct(parts[1])?, pct(parts[2])?);
+            Some(Color { r, g, b, a, format: ColorFormat::Hsl })
+        }
+        _ => None,
+    }
+}
+
+/// Colors in `text`, in order; unparseable lookalikes are skipped.
+pub fn find_colors(text: &str) -> Vec<Color> {
+    COLOR_REGEX
+        .find_iter(text)
+        .filter_map(|m| parse_color(m.as_str()))
+        .collect()
+}
+
+/// Rewrite every color in `text` with `f`, leaving everything else alone.
+pub fn replace_colors(text: &str, f: impl Fn(&Color) -> String) -> String {
+    COLOR_REGEX
+        .replace_all(text, |caps: &Captures| match parse_color(&caps[0]) {
+            Some(color) => f(&color),
+            None => caps[0].to_string(),
+        })
+        .into_owned()
+}
+
+/// Whether `text` is nothing but colors and separators.
+pub fn is_color_list(text: &str) -> bool {
+    if find_colors(text).is_empty() {
+        return false;
+    }
+    let rest = replace_colors(text, |_| String::new());
+    // Lookalikes that failed to parse are still in `rest`
+    !COLOR_REGEX.is_match(&rest)
+        && rest.chars().all(|c| c.is_whitespace() || matches!(c, ',' | ';'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_convert() {
+        let orange = parse_color("#FF8800").unwrap();
+        assert_eq!(orange.to_rgb(), "rgb(255, 136, 0)");
+        assert_eq!(orange.to_hsl(), "hsl(32, 100%, 50%)");
+
+        let short = parse_color("#f80").unwrap();
+        assert_eq!(short.to_hex(), "#ff8800");
+
+        let rgba = parse_color("rgba(255, 0, 0, 0.5)").unwrap();
+        assert_eq!(rgba.to_hex(), "#ff000080");
+        assert_eq!(rgba.to_hsl(), "hsla(0, 100%, 50%, 0.5)");
+
+        let hsl = parse_color("hsl(210deg 50% 40% / 1)").unwrap();
+        assert_eq!(hsl.format, ColorFormat::Hsl);
+        assert_eq!(hsl.to_hex(), "#336699");
+    }
+
+    #[test]
+    fn test_parse_rejects() {
+        assert_eq!(parse_color("#ggg"), None);
+        assert_eq!(parse_color("#12345"), None);
+        assert_eq!(parse_color("rgb(300, 0, 0)"), None);
+        assert_eq!(parse_color("hsl(0, 150%, 50%)"), None);
+    }
+
+    #[test]
+    fn test_is_color_list() {
+        assert!(is_color_list("#fff, #000000;\nrgb(1, 2, 3)"));
+        assert!(!is_color_list("color: #fff"));
+        assert!(!is_color_list("#fff rgb(999, 0, 0)"));
+        assert!(!is_color_list("no colors"));
+    }
+}
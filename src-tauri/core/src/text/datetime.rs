@@ -0,0 +1,62 @@
+use chrono::{DateTime, Local, NaiveDateTime, TimeZone, Utc};
+
+// Offset-less datetimes are taken as local time
+const NAIVE_FORMATS: &[&str] = &["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f", "%Y-%m-%dT%H:%M"];
+
+/// Unix seconds from 2001 up to 2100; anything outside is more likely an id
+/// or a phone number than a timestamp.
+const EPOCH_RANGE: std::ops::RangeInclusive<i64> = 1_000_000_000..=4_102_444_800;
+
+/// Parse a 10-digit (seconds) or 13-digit (milliseconds) Unix timestamp, an
+/// RFC 3339 datetime, or an ISO-style datetime without offset.
+pub fn parse_timestamp(text: &str) -> Option<DateTime<Utc>> {
+    let text = text.trim();
+    if text.chars().all(|c| c.is_ascii_digit()) {
+        let value: i64 = text.parse().ok()?;
+        let seconds = match text.len() {
+            10 => value,
+            13 => value / 1000,
+            _ => return None,
+        };
+        if !EPOCH_RANGE.contains(&seconds) {
+            return None;
+        }
+        return match text.len() {
+            13 => DateTime::from_timestamp_millis(value),
+            _ => DateTime::from_timestamp(value, 0),
+        };
+    }
+
+    if let Ok(dt) = DateTime::parse_from_rfc3339(text) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    NAIVE_FORMATS
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(text, fmt).ok())
+        .and_then(|naive| Local.from_local_datetime(&naive).earliest())
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_epoch() {
+        let expected = DateTime::from_timestamp(1700000000, 0);
+        assert_eq!(parse_timestamp("1700000000"), expected);
+        assert_eq!(parse_timestamp(" 1700000000000 "), expected);
+        assert_eq!(parse_timestamp("0000000001"), None);
+        assert_eq!(parse_timestamp("170000000"), None);
+    }
+
+    #[test]
+    fn test_parse_iso() {
+        let expected = DateTime::from_timestamp(1700000000, 0);
+        assert_eq!(parse_timestamp("2023-11-14T22:13:20Z"), expected);
+        assert_eq!(parse_timestamp("2023-11-15T06:13:20+08:00"), expected);
+        assert!(parse_timestamp("2023-11-14 22:13:20.5").is_some());
+        assert_eq!(parse_timestamp("2023-11-14"), None);
+        assert_eq!(parse_timestamp("yesterday"), None);
+    }
+}
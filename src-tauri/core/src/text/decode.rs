@@ -0,0 +1,120 @@
+use base64::alphabet;
+use base64::engine::general_purpose::{GeneralPurpose, GeneralPurposeConfig};
+use base64::engine::DecodePaddingMode;
+use base64::Engine;
+
+// Pasted blobs lose their padding often enough that it can't be required
+const CONFIG: GeneralPurposeConfig =
+    GeneralPurposeConfig::new().with_decode_padding_mode(DecodePaddingMode::Indifferent);
+const STANDARD: GeneralPurpose = GeneralPurpose::new(&alphabet::STANDARD, CONFIG);
+const URL_SAFE: GeneralPurpose = GeneralPurpose::new(&alphabet::URL_SAFE, CONFIG);
+
+/// Bytes of a decoded Base64 blob or `data:` URI.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decoded {
+    pub bytes: Vec<u8>,
+    /// From the data URI, or sniffed from well-known file signatures
+    pub mime: Option<String>,
+}
+
+impl Decoded {
+    /// The bytes as text, unless they're not UTF-8 or contain control characters.
+    pub fn as_text(&self) -> Option<&str> {
+        let text = std::str::from_utf8(&self.bytes).ok()?;
+        let printable = text.chars().all(|c| !c.is_control() || c.is_whitespace());
+        printable.then_some(text)
+    }
+
+    /// File extension to save the bytes under.
+    pub fn extension(&self) -> &'static str {
+        match self.mime.as_deref() {
+            Some("image/png") => "png",
+            Some("image/jpeg") => "jpg",
+            Some("image/gif") => "gif",
+            Some("image/webp") => "webp",
+            Some("image/svg+xml") => "svg",
+            Some("application/pdf") => "pdf",
+            Some("application/zip") => "zip",
+            Some("application/gzip") => "gz",
+            Some("application/json") => "json",
+            Some(mime) if mime.starts_with("text/") => "txt",
+            _ if self.as_text().is_some() => "txt",
+            _ => "bin",
+        }
+    }
+}
+
+fn sniff_mime(bytes: &[u8]) -> Option<&'static str> {
+    let mime = if bytes.starts_with(b"\x89PNG") {
+        "image/png"
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        "image/jpeg"
+    } else if bytes.starts_with(b"GIF8") {
+        "image/gif"
+    } else if bytes.starts_with(b"RIFF") && bytes.get(8..12) == Some(b"WEBP") {
+        "image/webp"
+    } else if bytes.starts_with(b"%PDF") {
+        "application/pdf"
+    } else if bytes.starts_with(b"PK\x03\x04") {
+        "application/zip"
+    } else if bytes.starts_with(&[0x1F, 0x8B]) {
+        "application/gzip"
+    } else {
+        return None;
+    };
+    Some(mime)
+}
+
+/// Decode standard or URL-safe Base64, or a `data:...;base64,` URI.
+/// Whitespace inside the blob is ignored.
+pub fn decode_base64(text: &str) -> Option<Decoded> {
+    let text = text.trim();
+    let (mime, payload) = match text.strip_prefix("data:") {
+        Some(rest) => {
+            let (header, payload) = rest.split_once(',')?;
+            // `image/png;base64`, `text/plain;charset=utf-8;base64`
+            let params = header.strip_suffix(";base64")?;
+            let mime = params.split(';').next().filter(|m| !m.is_empty());
+            (mime.map(str::to_ascii_lowercase), payload)
+        }
+        None => (None, text),
+    };
+
+    let compact: String = payload.chars().filter(|c| !c.is_whitespace()).collect();
+    let engine = if compact.contains(['-', '_']) { URL_SAFE } else { STANDARD };
+    let bytes = engine.decode(compact).ok().filter(|b| !b.is_empty())?;
+    let mime = mime.or_else(|| sniff_mime(&bytes).map(str::to_string));
+    Some(Decoded { bytes, mime })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_plain_and_unpadded() {
+        let decoded = decode_base64("SGVsbG8sIHdvcmxkIQ==").unwrap();
+        assert_eq!(decoded.as_text(), Some("Hello, world!"));
+        assert_eq!(decoded.extension(), "txt");
+        assert_eq!(decode_base64("SGVsbG8sIHdvcmxkIQ").unwrap().as_text(), Some("Hello, world!"));
+        assert_eq!(decode_base64("5L2g5aW9\n5LiW55WM").unwrap().as_text(), Some("你好世界"));
+    }
+
+    #[test]
+    fn test_decode_data_uri_and_binary() {
+        let decoded = decode_base64("data:image/png;base64,iVBORw0KGgo=").unwrap();
+        assert_eq!(decoded.mime.as_deref(), Some("image/png"));
+        assert_eq!(decoded.as_text(), None);
+        assert_eq!(decoded.extension(), "png");
+
+        // Signature sniffed without a data URI
+        assert_eq!(decode_base64("JVBERi0xLjQKJcfs").unwrap().extension(), "pdf");
+    }
+
+    #[test]
+    fn test_decode_invalid() {
+        assert_eq!(decode_base64("not base64!"), None);
+        assert_eq!(decode_base64(""), None);
+        assert_eq!(decode_base64("data:text/plain,hello"), None);
+    }
+}
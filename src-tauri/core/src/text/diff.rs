@@ -0,0 +1,138 @@
+use serde::Serialize;
+use similar::{ChangeTag, TextDiff};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiffTag {
+    Equal,
+    Insert,
+    Delete,
+}
+
+impl From<ChangeTag> for DiffTag {
+    fn from(tag: ChangeTag) -> Self {
+        match tag {
+            ChangeTag::Equal => DiffTag::Equal,
+            ChangeTag::Insert => DiffTag::Insert,
+            ChangeTag::Delete => DiffTag::Delete,
+        }
+    }
+}
+
+/// Part of a line; `emphasized` marks the words that actually changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffSegment {
+    pub emphasized: bool,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffLine {
+    pub tag: DiffTag,
+    /// 1-based line number in `before`, absent for inserted lines
+    pub old_line: Option<usize>,
+    /// 1-based line number in `after`, absent for deleted lines
+    pub new_line: Option<usize>,
+    pub segments: Vec<DiffSegment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiffResult {
+    pub lines: Vec<DiffLine>,
+    pub insertions: usize,
+    pub deletions: usize,
+    /// Similarity between 0.0 and 1.0
+    pub ratio: f32,
+}
+
+/// Line diff with word-level emphasis inside changed lines.
+pub fn diff_texts(before: &str, after: &str) -> DiffResult {
+    let diff = TextDiff::from_lines(before, after);
+    let mut lines = Vec::new();
+    let mut insertions = 0;
+    let mut deletions = 0;
+
+    for op in diff.ops() {
+        for change in diff.iter_inline_changes(op) {
+            let tag = DiffTag::from(change.tag());
+            match tag {
+                DiffTag::Insert => insertions += 1,
+                DiffTag::Delete => deletions += 1,
+                DiffTag::Equal => {}
+            }
+
+            let mut segments: Vec<DiffSegment> = change
+                .iter_strings_lossy()
+                .map(|(emphasized, text)| DiffSegment {
+                    emphasized,
+                    text: text.into_owned(),
+                })
+                .collect();
+
+            // Line terminators are implied by the line structure
+            if let Some(last) = segments.last_mut() {
+                let trimmed_len = last.text.trim_end_matches(['\n', '\r']).len();
+                last.text.truncate(trimmed_len);
+            }
+            segments.retain(|s| !s.text.is_empty());
+
+            lines.push(DiffLine {
+                tag,
+                old_line: change.old_index().map(|i| i + 1),
+                new_line: change.new_index().map(|i| i + 1),
+                segments,
+            });
+        }
+    }
+
+    DiffResult {
+        lines,
+        insertions,
+        deletions,
+        ratio: diff.ratio(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_texts() {
+        let result = diff_texts("a\nb", "a\nb");
+        assert_eq!((result.insertions, result.deletions), (0, 0));
+        assert_eq!(result.lines.len(), 2);
+        assert_eq!(result.ratio, 1.0);
+    }
+
+    #[test]
+    fn test_line_insert_and_delete() {
+        let result = diff_texts("a\nb\nc\n", "a\nc\nd\n");
+        assert_eq!(result.deletions, 1);
+        assert_eq!(result.insertions, 1);
+
+        let deleted = result.lines.iter().find(|l| l.tag == DiffTag::Delete).unwrap();
+        assert_eq!(deleted.old_line, Some(2));
+        assert_eq!(deleted.new_line, None);
+        assert_eq!(deleted.segments[0].text, "b");
+
+        let inserted = result.lines.iter().find(|l| l.tag == DiffTag::Insert).unwrap();
+        assert_eq!(inserted.new_line, Some(3));
+    }
+
+    #[test]
+    fn test_word_level_emphasis() {
+        let result = diff_texts("hello big world\n", "hello small world\n");
+        let inserted = result.lines.iter().find(|l| l.tag == DiffTag::Insert).unwrap();
+        let emphasized: Vec<&str> = inserted
+            .segments
+            .iter()
+            .filter(|s| s.emphasized)
+            .map(|s| s.text.as_str())
+            .collect();
+        assert_eq!(emphasized, vec!["small"]);
+    }
+}
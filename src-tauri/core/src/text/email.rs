@@ -0,0 +1,103 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+// "On Mon, 3 Jun 2024 at 10:00, Ann <ann@example.com> wrote:" and the Chinese client equivalent
+static ATTRIBUTION: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^\s*(?:On\s.{5,200}\swrote:|在.{5,200}写道[:：])\s*$").unwrap());
+static ORIGINAL_MESSAGE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^\s*-{2,}\s*(?:original message|forwarded message|原始邮件|转发的邮件)\s*-{2,}\s*$").unwrap()
+});
+static FROM_HEADER: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\s*(?:From|发件人)\s*[:：]\s*\S").unwrap());
+static OTHER_HEADER: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^\s*(?:To|Cc|Sent|Date|Subject|收件人|抄送|发送时间|日期|主题)\s*[:：]").unwrap()
+});
+
+fn is_quoted(line: &str) -> bool {
+    line.trim_start().starts_with('>')
+}
+
+/// A `From:` line with at least one more header right below it.
+fn is_header_block(lines: &[&str], i: usize) -> bool {
+    FROM_HEADER.is_match(lines[i]) && lines[i + 1..].iter().take(4).any(|l| OTHER_HEADER.is_match(l))
+}
+
+/// Where an earlier message starts: a reply attribution, an "Original
+/// Message" divider, or a header block below some text of the reply.
+fn is_thread_marker(lines: &[&str], i: usize, has_body: bool) -> bool {
+    ATTRIBUTION.is_match(lines[i])
+        || ORIGINAL_MESSAGE.is_match(lines[i])
+        || (has_body && is_header_block(lines, i))
+}
+
+/// Email with earlier messages quoted or appended: a header block, a reply
+/// attribution above `>` quotes, or `>` quotes closed by a `-- ` signature.
+pub fn is_email_thread(text: &str) -> bool {
+    let lines: Vec<&str> = text.lines().collect();
+    let quoted = lines.iter().filter(|l| is_quoted(l)).count();
+    let has_signature = lines.iter().any(|l| *l == "-- " || *l == "--");
+    let has_marker = (0..lines.len()).any(|i| is_thread_marker(&lines, i, true));
+
+    (0..lines.len()).any(|i| is_header_block(&lines, i))
+        || (has_marker && (quoted > 0 || lines.len() > 3))
+        || (quoted >= 2 && quoted < lines.len() && has_signature)
+}
+
+/// The newest message alone: `>` quotes are dropped, and everything from the
+/// first earlier message on is cut. `None` when there's nothing to strip.
+pub fn strip_quoted(text: &str) -> Option<String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut kept: Vec<&str> = Vec::new();
+    let mut stripped = false;
+    // The newest message's own headers don't count as its body
+    let mut has_body = false;
+    for (i, line) in lines.iter().enumerate() {
+        if is_thread_marker(&lines, i, has_body) {
+            stripped = true;
+            break;
+        }
+        if is_quoted(line) {
+            stripped = true;
+            continue;
+        }
+        has_body |= !line.trim().is_empty() && !FROM_HEADER.is_match(line) && !OTHER_HEADER.is_match(line);
+        kept.push(line);
+    }
+
+    if !stripped {
+        return None;
+    }
+    while kept.last().is_some_and(|l| l.trim().is_empty()) {
+        kept.pop();
+    }
+    Some(kept.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const REPLY: &str = "Sounds good, see you then.\n\n-- \nBob\n\nOn Mon, 3 Jun 2024 at 10:00, Ann <ann@example.com> wrote:\n> Lunch at noon?\n> Ann";
+
+    #[test]
+    fn test_is_email_thread() {
+        assert!(is_email_thread(REPLY));
+        assert!(is_email_thread("Thanks!\n\nFrom: Ann <ann@example.com>\nSent: Monday\nSubject: Lunch\n\nNoon?"));
+        assert!(is_email_thread("Agreed.\n> first point\n> second point\n-- \nBob"));
+        // A Markdown blockquote isn't a thread
+        assert!(!is_email_thread("> quoted\n> lines"));
+        assert!(!is_email_thread("From: here to there, the road is long."));
+    }
+
+    #[test]
+    fn test_strip_quoted() {
+        assert_eq!(strip_quoted(REPLY).as_deref(), Some("Sounds good, see you then.\n\n-- \nBob"));
+
+        let outlook = "From: Bob\nTo: Ann\nSubject: Re: Lunch\n\nYes.\n\n-----Original Message-----\nFrom: Ann\nNoon?";
+        assert_eq!(strip_quoted(outlook).as_deref(), Some("From: Bob\nTo: Ann\nSubject: Re: Lunch\n\nYes."));
+
+        let forwarded = "FYI\n\nFrom: Ann\nSent: Monday\nSubject: Lunch\n\nNoon?";
+        assert_eq!(strip_quoted(forwarded).as_deref(), Some("FYI"));
+
+        assert_eq!(strip_quoted("No quotes here"), None);
+    }
+}
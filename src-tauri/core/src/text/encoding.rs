@@ -0,0 +1,158 @@
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, BIG5, GBK, SHIFT_JIS, UTF_8, WINDOWS_1252};
+use serde::Serialize;
+
+/// Encodings text is commonly misread as, tried in this order.
+const MISREAD_AS: &[&Encoding] = &[WINDOWS_1252, GBK, BIG5, SHIFT_JIS];
+/// Legacy encodings a misread text may really be in
+const LEGACY: &[&Encoding] = &[GBK, BIG5, SHIFT_JIS];
+/// Below this many non-ASCII bytes a guess at the legacy encoding is noise
+const MIN_GUESS_BYTES: usize = 8;
+
+/// Text that was decoded with the wrong encoding, put back together.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Recovered {
+    pub text: String,
+    /// The encoding the bytes were really in, e.g. `UTF-8`
+    pub encoding: &'static str,
+    /// The one they were wrongly decoded as, e.g. `GBK`
+    pub misread_as: &'static str,
+}
+
+/// Encoding by name or label, such as `gbk`, `big5`, `shift_jis`, `latin1`.
+pub fn encoding_for_label(label: &str) -> Option<&'static Encoding> {
+    Encoding::for_label(label.trim().as_bytes())
+}
+
+/// The bytes `text` came from if it was decoded as `misread_as`, `None`
+/// when it can't have been.
+fn original_bytes(text: &str, misread_as: &'static Encoding) -> Option<Vec<u8>> {
+    let (bytes, _, unmappable) = misread_as.encode(text);
+    (!unmappable).then(|| bytes.into_owned())
+}
+
+/// Decode as `encoding`, `None` on any malformed sequence.
+fn decode_strict(bytes: &[u8], encoding: &'static Encoding) -> Option<String> {
+    encoding
+        .decode_without_bom_handling_and_without_replacement(bytes)
+        .map(|text| text.into_owned())
+}
+
+/// CJK misread as Windows-1252 comes out as runs of accented letters and
+/// symbols (`ÄãºÃ`); European text has them one at a time.
+fn has_latin1_runs(text: &str) -> bool {
+    let mut run = 0;
+    for c in text.chars() {
+        if ('\u{80}'..='\u{ff}').contains(&c) || "ŒœŠšŸŽž€‚ƒ„…†‡ˆ‰‹›‘’“”•–—˜™".contains(c) {
+            run += 1;
+            if run >= 3 {
+                return true;
+            }
+        } else {
+            run = 0;
+        }
+    }
+    false
+}
+
+/// Undo `text` having been decoded as `misread_as` when it was `encoding`.
+pub fn convert(text: &str, misread_as: &'static Encoding, encoding: &'static Encoding) -> Option<String> {
+    decode_strict(&original_bytes(text, misread_as)?, encoding)
+}
+
+/// Recover text that arrived garbled because its bytes were decoded with
+/// the wrong encoding: UTF-8 read as Windows-1252, GBK (`浣犲ソ`), Big5 or
+/// Shift_JIS, or GBK, Big5 and Shift_JIS read as Windows-1252 (`ÄãºÃ`).
+/// `None` when the text doesn't look misread.
+pub fn fix_mojibake(text: &str) -> Option<Recovered> {
+    if text.is_ascii() {
+        return None;
+    }
+    for &misread_as in MISREAD_AS {
+        let Some(bytes) = original_bytes(text, misread_as) else {
+            continue;
+        };
+
+        // Legacy text almost never happens to be valid multi-byte UTF-8
+        if let Ok(utf8) = std::str::from_utf8(&bytes) {
+            if utf8 != text && utf8.chars().count() < text.chars().count() {
+                return Some(Recovered {
+                    text: utf8.to_string(),
+                    encoding: UTF_8.name(),
+                    misread_as: misread_as.name(),
+                });
+            }
+            continue;
+        }
+
+        // Text in one CJK encoding is often valid in another, so only text
+        // that looks misread as Windows-1252 is guessed at
+        if misread_as != WINDOWS_1252
+            || !has_latin1_runs(text)
+            || bytes.iter().filter(|b| !b.is_ascii()).count() < MIN_GUESS_BYTES
+        {
+            continue;
+        }
+        let mut detector = EncodingDetector::new();
+        detector.feed(&bytes, true);
+        let guess = detector.guess(None, true);
+        if guess == misread_as || !LEGACY.contains(&guess) {
+            continue;
+        }
+        if let Some(fixed) = decode_strict(&bytes, guess) {
+            if fixed != text {
+                return Some(Recovered {
+                    text: fixed,
+                    encoding: guess.name(),
+                    misread_as: misread_as.name(),
+                });
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn misread(text: &str, encoding: &'static Encoding, misread_as: &'static Encoding) -> String {
+        let (bytes, _, _) = encoding.encode(text);
+        misread_as.decode_without_bom_handling(&bytes).0.into_owned()
+    }
+
+    #[test]
+    fn test_fix_utf8_misread() {
+        let garbled = misread("你好世界", UTF_8, GBK);
+        let fixed = fix_mojibake(&garbled).unwrap();
+        assert_eq!(fixed.text, "你好世界");
+        assert_eq!((fixed.encoding, fixed.misread_as), ("UTF-8", "GBK"));
+
+        let garbled = misread("Café crème", UTF_8, WINDOWS_1252);
+        assert_eq!(fix_mojibake(&garbled).unwrap().text, "Café crème");
+    }
+
+    #[test]
+    fn test_fix_legacy_misread() {
+        let text = "这是一段从旧系统复制出来的中文文本，内容足够长。";
+        let fixed = fix_mojibake(&misread(text, GBK, WINDOWS_1252)).unwrap();
+        assert_eq!(fixed.text, text);
+        assert_eq!(fixed.encoding, "GBK");
+
+        assert_eq!(convert(&misread("繁體中文", BIG5, WINDOWS_1252), WINDOWS_1252, BIG5).unwrap(), "繁體中文");
+    }
+
+    #[test]
+    fn test_leaves_clean_text() {
+        for text in [
+            "Plain ASCII text",
+            "Café crème brûlée, naïve résumé",
+            "你好，世界！这是一段正常的中文文本，不需要修复。",
+            "繁體中文的正常文字，也不需要修復。",
+            "こんにちは、これは普通の日本語の文章です。",
+        ] {
+            assert_eq!(fix_mojibake(text), None, "{}", text);
+        }
+    }
+}
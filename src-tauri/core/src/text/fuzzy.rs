@@ -0,0 +1,87 @@
+// Score parts; only their order matters
+const MATCH: u32 = 10;
+const CONSECUTIVE: u32 = 15;
+const WORD_START: u32 = 20;
+const SUBSTRING: u32 = 100;
+const PREFIX: u32 = 50;
+
+fn fold(text: &str) -> Vec<char> {
+    text.chars().flat_map(char::to_lowercase).collect()
+}
+
+fn is_word_start(chars: &[char], i: usize) -> bool {
+    i == 0 || !chars[i - 1].is_alphanumeric() || !chars[i].is_ascii()
+}
+
+/// Score of one query word: its letters must appear in order; runs of
+/// letters and matches at word starts count more, and a plain substring
+/// most.
+fn term_score(term: &[char], candidate: &[char]) -> Option<u32> {
+    if term.is_empty() {
+        return Some(0);
+    }
+    if let Some(start) = candidate.windows(term.len()).position(|w| w == term) {
+        let mut score = SUBSTRING + MATCH * term.len() as u32;
+        if start == 0 {
+            score += PREFIX;
+        }
+        if is_word_start(candidate, start) {
+            score += WORD_START;
+        }
+        return Some(score);
+    }
+
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for &c in term {
+        let i = next + candidate[next..].iter().position(|&x| x == c)?;
+        score += MATCH;
+        if previous.is_some_and(|p| p + 1 == i) {
+            score += CONSECUTIVE;
+        }
+        if is_word_start(candidate, i) {
+            score += WORD_START;
+        }
+        previous = Some(i);
+        next = i + 1;
+    }
+    // Letters spread over a long text are a weak match
+    let span = previous.unwrap_or(0) + 1;
+    Some(score.saturating_sub((span - term.len()) as u32).max(1))
+}
+
+/// How well `query` matches `candidate`, ignoring case; `None` when some
+/// word of the query doesn't match at all. An empty query matches
+/// everything with 0.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<u32> {
+    let candidate = fold(candidate);
+    query
+        .split_whitespace()
+        .map(|term| term_score(&fold(term), &candidate))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fuzzy_matches_in_order() {
+        assert!(fuzzy_score("trws", "Trim whitespace").is_some());
+        assert!(fuzzy_score("Whitespace", "trim whitespace").is_some());
+        assert!(fuzzy_score("swt", "Trim whitespace").is_none());
+        assert!(fuzzy_score("trim x", "Trim whitespace").is_none());
+        assert_eq!(fuzzy_score("", "anything"), Some(0));
+        assert!(fuzzy_score("翻译", "AI 翻译成英文").is_some());
+    }
+
+    #[test]
+    fn test_fuzzy_ranking() {
+        let score = |q, c| fuzzy_score(q, c).unwrap();
+        // Prefix beats substring beats scattered letters
+        assert!(score("up", "Uppercase") > score("up", "Lookup"));
+        assert!(score("case", "Lowercase") > score("lwc", "Lowercase"));
+        assert!(score("tw", "Trim whitespace") > score("tw", "Date format with"));
+    }
+}
@@ -0,0 +1,303 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use uuid::Uuid;
+
+pub const PASSWORD_LENGTH: std::ops::RangeInclusive<usize> = 4..=256;
+pub const LOREM_PARAGRAPHS: std::ops::RangeInclusive<usize> = 1..=20;
+
+const LOWERCASE: &str = "abcdefghijklmnopqrstuvwxyz";
+const UPPERCASE: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+const DIGITS: &str = "0123456789";
+const SYMBOLS: &str = "!@#$%^&*()-_=+[]{};:,.?/~";
+/// Easily misread in many fonts
+const AMBIGUOUS: &str = "0O1lI|";
+
+const LOREM_OPENING: &str = "Lorem ipsum dolor sit amet, consectetur adipiscing elit";
+const LOREM_WORDS: &[&str] = &[
+    "a", "ac", "accumsan", "aenean", "aliquam", "amet", "ante", "arcu", "at", "auctor", "augue",
+    "bibendum", "commodo", "congue", "cras", "cursus", "dapibus", "diam", "dictum", "dolor",
+    "donec", "dui", "egestas", "eget", "eleifend", "elementum", "enim", "erat", "eros", "est",
+    "et", "etiam", "eu", "euismod", "facilisis", "fames", "faucibus", "felis", "fermentum",
+    "feugiat", "fringilla", "fusce", "gravida", "habitant", "iaculis", "id", "imperdiet", "in",
+    "integer", "interdum", "ipsum", "justo", "lacinia", "lacus", "laoreet", "lectus", "leo",
+    "libero", "ligula", "lobortis", "lorem", "luctus", "maecenas", "magna", "malesuada",
+    "massa", "mattis", "mauris", "metus", "mi", "molestie", "mollis", "morbi", "nam", "nec",
+    "neque", "netus", "nibh", "nisi", "nisl", "non", "nulla", "nunc", "odio", "orci", "ornare",
+    "pellentesque", "pharetra", "phasellus", "placerat", "porta", "porttitor", "posuere",
+    "praesent", "pretium", "proin", "pulvinar", "purus", "quam", "quis", "risus", "rutrum",
+    "sagittis", "sapien", "scelerisque", "sed", "sem", "semper", "senectus", "sit", "sodales",
+    "suscipit", "tellus", "tempor", "tempus", "tincidunt", "tortor", "tristique", "turpis",
+    "ullamcorper", "ultrices", "ultricies", "urna", "ut", "varius", "vel", "velit", "vitae",
+    "vivamus", "viverra", "volutpat", "vulputate",
+];
+
+#[derive(Debug, Error)]
+pub enum GenerateError {
+    #[error("password length must be between {} and {}", PASSWORD_LENGTH.start(), PASSWORD_LENGTH.end())]
+    PasswordLength,
+    #[error("choose at least one kind of character")]
+    NoCharacters,
+    #[error("paragraphs must be between {} and {}", LOREM_PARAGRAPHS.start(), LOREM_PARAGRAPHS.end())]
+    Paragraphs,
+}
+
+/// Which characters a generated password may hold; it holds at least one
+/// of each kind chosen.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct PasswordPolicy {
+    pub length: usize,
+    pub lowercase: bool,
+    pub uppercase: bool,
+    pub digits: bool,
+    pub symbols: bool,
+    /// Leave out `0 O 1 l I |`
+    pub exclude_ambiguous: bool,
+}
+
+impl Default for PasswordPolicy {
+    fn default() -> Self {
+        Self {
+            length: 20,
+            lowercase: true,
+            uppercase: true,
+            digits: true,
+            symbols: true,
+            exclude_ambiguous: false,
+        }
+    }
+}
+
+impl PasswordPolicy {
+    /// The character sets chosen, each non-empty.
+    fn sets(&self) -> Vec<Vec<char>> {
+        [
+            (self.lowercase, LOWERCASE),
+            (self.uppercase, UPPERCASE),
+            (self.digits, DIGITS),
+            (self.symbols, SYMBOLS),
+        ]
+        .into_iter()
+        .filter(|(enabled, _)| *enabled)
+        .map(|(_, set)| {
+            set.chars()
+                .filter(|c| !(self.exclude_ambiguous && AMBIGUOUS.contains(*c)))
+                .collect()
+        })
+        .collect()
+    }
+
+    pub fn validate(&self) -> Result<(), GenerateError> {
+        if !PASSWORD_LENGTH.contains(&self.length) {
+            return Err(GenerateError::PasswordLength);
+        }
+        let sets = self.sets();
+        if sets.is_empty() {
+            return Err(GenerateError::NoCharacters);
+        }
+        // Too short to hold one of each kind
+        if self.length < sets.len() {
+            return Err(GenerateError::PasswordLength);
+        }
+        Ok(())
+    }
+}
+
+/// Card networks with a test number prefix.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CardBrand {
+    #[default]
+    Visa,
+    Mastercard,
+    Amex,
+}
+
+impl CardBrand {
+    /// Leading digits and total length.
+    fn layout(&self) -> (&'static [&'static str], usize) {
+        match self {
+            CardBrand::Visa => (&["4"], 16),
+            CardBrand::Mastercard => (&["51", "52", "53", "54", "55"], 16),
+            CardBrand::Amex => (&["34", "37"], 15),
+        }
+    }
+}
+
+/// Something to generate, as sent by the panel.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Generator {
+    Password(PasswordPolicy),
+    Uuid,
+    LoremIpsum {
+        #[serde(default = "default_paragraphs")]
+        paragraphs: usize,
+    },
+    /// A number that passes the Luhn check but belongs to no account, for
+    /// filling in test forms
+    CardNumber {
+        #[serde(default)]
+        brand: CardBrand,
+    },
+}
+
+fn default_paragraphs() -> usize {
+    1
+}
+
+impl Generator {
+    pub fn generate(&self) -> Result<String, GenerateError> {
+        match self {
+            Generator::Password(policy) => password(policy),
+            Generator::Uuid => Ok(Uuid::new_v4().to_string()),
+            Generator::LoremIpsum { paragraphs } => lorem_ipsum(*paragraphs),
+            Generator::CardNumber { brand } => Ok(card_number(*brand)),
+        }
+    }
+}
+
+/// A random password from the OS-seeded generator, with at least one
+/// character of each chosen kind.
+pub fn password(policy: &PasswordPolicy) -> Result<String, GenerateError> {
+    policy.validate()?;
+    let sets = policy.sets();
+    let all: Vec<char> = sets.concat();
+    let mut rng = rand::thread_rng();
+
+    let mut chars: Vec<char> = sets.iter().filter_map(|set| set.choose(&mut rng)).copied().collect();
+    while chars.len() < policy.length {
+        chars.extend(all.choose(&mut rng));
+    }
+    chars.shuffle(&mut rng);
+    Ok(chars.into_iter().collect())
+}
+
+/// Placeholder paragraphs; the first opens with the familiar "Lorem ipsum".
+pub fn lorem_ipsum(paragraphs: usize) -> Result<String, GenerateError> {
+    if !LOREM_PARAGRAPHS.contains(&paragraphs) {
+        return Err(GenerateError::Paragraphs);
+    }
+    let mut rng = rand::thread_rng();
+    let mut out = Vec::with_capacity(paragraphs);
+    for p in 0..paragraphs {
+        let mut sentences = Vec::new();
+        for s in 0..rng.gen_range(4..=7) {
+            let words: Vec<&str> = (0..rng.gen_range(6..=14))
+                .filter_map(|_| LOREM_WORDS.choose(&mut rng).copied())
+                .collect();
+            let sentence = if p == 0 && s == 0 {
+                format!("{}, {}", LOREM_OPENING, words.join(" "))
+            } else {
+                words.join(" ")
+            };
+            let mut chars = sentence.chars();
+            let first = chars.next().map(|c| c.to_uppercase().collect::<String>()).unwrap_or_default();
+            sentences.push(format!("{}{}.", first, chars.as_str()));
+        }
+        out.push(sentences.join(" "));
+    }
+    Ok(out.join("\n\n"))
+}
+
+/// Luhn check digit for `digits`, which must be ASCII digits.
+fn luhn_check_digit(digits: &str) -> u32 {
+    let sum: u32 = digits
+        .bytes()
+        .rev()
+        .enumerate()
+        .map(|(i, b)| {
+            let d = (b - b'0') as u32;
+            // Doubled from the rightmost digit, as the check digit goes after it
+            if i % 2 == 0 {
+                let doubled = d * 2;
+                if doubled > 9 { doubled - 9 } else { doubled }
+            } else {
+                d
+            }
+        })
+        .sum();
+    (10 - sum % 10) % 10
+}
+
+/// Whether `number` (spaces and dashes allowed) passes the Luhn check.
+pub fn luhn_valid(number: &str) -> bool {
+    let digits: String = number.chars().filter(|c| !matches!(c, ' ' | '-')).collect();
+    if digits.len() < 2 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return false;
+    }
+    let (body, check) = digits.split_at(digits.len() - 1);
+    luhn_check_digit(body).to_string() == check
+}
+
+pub fn card_number(brand: CardBrand) -> String {
+    let (prefixes, length) = brand.layout();
+    let mut rng = rand::thread_rng();
+    let mut number = prefixes.choose(&mut rng).copied().unwrap_or_default().to_string();
+    while number.len() < length - 1 {
+        number.push(char::from(b'0' + rng.gen_range(0..10u8)));
+    }
+    let check = luhn_check_digit(&number);
+    number.push(char::from_digit(check, 10).unwrap_or('0'));
+    number
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_password() {
+        let policy = PasswordPolicy {
+            length: 8,
+            symbols: false,
+            exclude_ambiguous: true,
+            ..PasswordPolicy::default()
+        };
+        for _ in 0..50 {
+            let password = password(&policy).unwrap();
+            assert_eq!(password.chars().count(), 8);
+            assert!(password.chars().any(|c| c.is_ascii_lowercase()));
+            assert!(password.chars().any(|c| c.is_ascii_uppercase()));
+            assert!(password.chars().any(|c| c.is_ascii_digit()));
+            assert!(password.chars().all(|c| c.is_ascii_alphanumeric() && !AMBIGUOUS.contains(c)));
+        }
+
+        let none = PasswordPolicy {
+            lowercase: false,
+            uppercase: false,
+            digits: false,
+            symbols: false,
+            ..PasswordPolicy::default()
+        };
+        assert!(matches!(password(&none), Err(GenerateError::NoCharacters)));
+        assert!(password(&PasswordPolicy { length: 3, ..PasswordPolicy::default() }).is_err());
+    }
+
+    #[test]
+    fn test_card_number() {
+        assert!(luhn_valid("4111 1111 1111 1111"));
+        assert!(!luhn_valid("4111 1111 1111 1112"));
+        for brand in [CardBrand::Visa, CardBrand::Mastercard, CardBrand::Amex] {
+            let number = card_number(brand);
+            assert!(luhn_valid(&number), "{}", number);
+            assert_eq!(number.len(), brand.layout().1);
+        }
+        assert!(card_number(CardBrand::Amex).starts_with('3'));
+    }
+
+    #[test]
+    fn test_generator() {
+        let lorem: Generator = serde_json::from_str(r#"{"kind":"loremIpsum","paragraphs":2}"#).unwrap();
+        let text = lorem.generate().unwrap();
+        assert!(text.starts_with("Lorem ipsum dolor sit amet"));
+        assert_eq!(text.split("\n\n").count(), 2);
+
+        let password: Generator = serde_json::from_str(r#"{"kind":"password","length":12}"#).unwrap();
+        assert_eq!(password.generate().unwrap().chars().count(), 12);
+        assert_eq!(Generator::Uuid.generate().unwrap().len(), 36);
+        assert!(Generator::LoremIpsum { paragraphs: 0 }.generate().is_err());
+    }
+}
@@ -0,0 +1,108 @@
+use std::io::{self, Read};
+
+use md5::Md5;
+use serde::{Deserialize, Serialize};
+use sha1::Sha1;
+use sha2::{Digest, Sha256};
+
+/// Bytes read from a file at a time
+const BUFFER_SIZE: usize = 64 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HashAlgorithm {
+    Md5,
+    Sha1,
+    Sha256,
+    Crc32,
+}
+
+enum Hasher {
+    Md5(Md5),
+    Sha1(Sha1),
+    Sha256(Sha256),
+    Crc32(crc32fast::Hasher),
+}
+
+impl Hasher {
+    fn new(algorithm: HashAlgorithm) -> Self {
+        match algorithm {
+            HashAlgorithm::Md5 => Self::Md5(Md5::new()),
+            HashAlgorithm::Sha1 => Self::Sha1(Sha1::new()),
+            HashAlgorithm::Sha256 => Self::Sha256(Sha256::new()),
+            HashAlgorithm::Crc32 => Self::Crc32(crc32fast::Hasher::new()),
+        }
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        match self {
+            Self::Md5(h) => h.update(bytes),
+            Self::Sha1(h) => h.update(bytes),
+            Self::Sha256(h) => h.update(bytes),
+            Self::Crc32(h) => h.update(bytes),
+        }
+    }
+
+    /// Lowercase hex, as `sha256sum` and download pages print it.
+    fn finish(self) -> String {
+        match self {
+            Self::Md5(h) => to_hex(&h.finalize()),
+            Self::Sha1(h) => to_hex(&h.finalize()),
+            Self::Sha256(h) => to_hex(&h.finalize()),
+            Self::Crc32(h) => format!("{:08x}", h.finalize()),
+        }
+    }
+}
+
+fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+pub fn hash_bytes(bytes: &[u8], algorithm: HashAlgorithm) -> String {
+    let mut hasher = Hasher::new(algorithm);
+    hasher.update(bytes);
+    hasher.finish()
+}
+
+/// Digest of everything `reader` yields, read in blocks so large files
+/// aren't loaded at once.
+pub fn hash_reader(mut reader: impl Read, algorithm: HashAlgorithm) -> io::Result<String> {
+    let mut hasher = Hasher::new(algorithm);
+    let mut buffer = vec![0; BUFFER_SIZE];
+    loop {
+        match reader.read(&mut buffer) {
+            Ok(0) => break,
+            Ok(n) => hasher.update(&buffer[..n]),
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash_bytes() {
+        let text = b"The quick brown fox jumps over the lazy dog";
+        assert_eq!(hash_bytes(text, HashAlgorithm::Md5), "9e107d9d372bb6826bd81d3542a419d6");
+        assert_eq!(hash_bytes(text, HashAlgorithm::Sha1), "2fd4e1c67a2d28fced849ee1bb76e7391b93eb12");
+        assert_eq!(
+            hash_bytes(text, HashAlgorithm::Sha256),
+            "d7a8fbb307d7809469ca9abcb0082e4f8d5651e46d3cdb762d02d0bf37c9e592"
+        );
+        assert_eq!(hash_bytes(text, HashAlgorithm::Crc32), "414fa339");
+        assert_eq!(hash_bytes(b"", HashAlgorithm::Crc32), "00000000");
+    }
+
+    #[test]
+    fn test_hash_reader() {
+        let data = vec![7u8; BUFFER_SIZE * 2 + 5];
+        assert_eq!(
+            hash_reader(data.as_slice(), HashAlgorithm::Sha256).unwrap(),
+            hash_bytes(&data, HashAlgorithm::Sha256)
+        );
+    }
+}
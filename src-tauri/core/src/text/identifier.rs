@@ -0,0 +1,95 @@
+/// What an opaque identifier most likely is, judged by its shape alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IdKind {
+    /// Hyphenated UUID and its version digit
+    Uuid(u8),
+    Md5,
+    Sha1,
+    Sha256,
+    Sha512,
+    /// Abbreviated git commit id, with its length
+    ShortSha(usize),
+    /// Any other even-length hex string, with its length
+    Hex(usize),
+}
+
+impl IdKind {
+    pub fn describe(&self) -> String {
+        match self {
+            IdKind::Uuid(1) => "UUID v1 (time-based)".to_string(),
+            IdKind::Uuid(4) => "UUID v4 (random)".to_string(),
+            IdKind::Uuid(7) => "UUID v7 (time-ordered)".to_string(),
+            IdKind::Uuid(version) => format!("UUID v{}", version),
+            IdKind::Md5 => "MD5 hash (32 hex digits)".to_string(),
+            IdKind::Sha1 => "SHA-1 hash or git commit (40 hex digits)".to_string(),
+            IdKind::Sha256 => "SHA-256 hash (64 hex digits)".to_string(),
+            IdKind::Sha512 => "SHA-512 hash (128 hex digits)".to_string(),
+            IdKind::ShortSha(len) => format!("Abbreviated git commit ({} hex digits)", len),
+            IdKind::Hex(len) => format!("Hex string ({} bytes)", len / 2),
+        }
+    }
+}
+
+fn uuid_version(s: &str) -> Option<u8> {
+    let bytes = s.as_bytes();
+    let shaped = bytes.len() == 36
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            8 | 13 | 18 | 23 => *b == b'-',
+            _ => b.is_ascii_hexdigit(),
+        });
+    shaped.then(|| (bytes[14] as char).to_digit(16).unwrap_or(0) as u8)
+}
+
+pub fn classify_id(token: &str) -> Option<IdKind> {
+    let token = token.trim();
+    if let Some(version) = uuid_version(token) {
+        return Some(IdKind::Uuid(version));
+    }
+    if !token.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    // Mixed-case hex is more likely a fragment of something else
+    if token.chars().any(|c| c.is_ascii_lowercase()) && token.chars().any(|c| c.is_ascii_uppercase()) {
+        return None;
+    }
+
+    let kind = match token.len() {
+        32 => IdKind::Md5,
+        40 => IdKind::Sha1,
+        64 => IdKind::Sha256,
+        128 => IdKind::Sha512,
+        // Needs both digits and letters so plain numbers and words like "defaced" don't count
+        len @ 7..=12
+            if token.chars().any(|c| c.is_ascii_digit())
+                && token.chars().any(|c| c.is_ascii_alphabetic()) =>
+        {
+            IdKind::ShortSha(len)
+        }
+        len if len >= 16 && len % 2 == 0 => IdKind::Hex(len),
+        _ => return None,
+    };
+    Some(kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_classify_id() {
+        assert_eq!(classify_id("550e8400-e29b-41d4-a716-446655440000"), Some(IdKind::Uuid(4)));
+        assert_eq!(classify_id("d41d8cd98f00b204e9800998ecf8427e"), Some(IdKind::Md5));
+        assert_eq!(classify_id("DA39A3EE5E6B4B0D3255BFEF95601890AFD80709"), Some(IdKind::Sha1));
+        assert_eq!(classify_id("4aa7bc6"), Some(IdKind::ShortSha(7)));
+        assert_eq!(classify_id("00ff00ff00ff00ff"), Some(IdKind::Hex(16)));
+    }
+
+    #[test]
+    fn test_classify_id_rejects() {
+        assert_eq!(classify_id("1234567"), None);
+        assert_eq!(classify_id("defaced"), None);
+        assert_eq!(classify_id("d41d8cd98f00b204E9800998ecf8427e"), None);
+        assert_eq!(classify_id("550e8400-e29b-41d4-a716"), None);
+        assert_eq!(classify_id("hello"), None);
+    }
+}
@@ -0,0 +1,426 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::header::{ACCEPT, CONTENT_TYPE, LOCATION};
+use reqwest::redirect::Policy;
+use reqwest::Url;
+use serde::Serialize;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::time::Duration;
+use thiserror::Error;
+
+use super::url::{decode_entities, html_title};
+use crate::ai::{client_builder, AIError, HttpSettings};
+
+static META_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)<meta\s[^>]*>").unwrap());
+static ATTRIBUTE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?s)([a-zA-Z:_-]+)\s*=\s*(?:"([^"]*)"|'([^']*)')"#).unwrap());
+
+/// Pages larger than this are cut off; the metadata is in the first few KiB.
+const MAX_PAGE_BYTES: usize = 512 * 1024;
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_REDIRECTS: usize = 5;
+/// Descriptions are cut to this many characters.
+const MAX_DESCRIPTION_CHARS: usize = 300;
+
+#[derive(Debug, Error)]
+pub enum FetchError {
+    #[error("invalid URL: {0}")]
+    InvalidUrl(String),
+    #[error("refusing to fetch {0}: it points to a private or local address")]
+    Blocked(String),
+    #[error("can't resolve {0}")]
+    Resolve(String),
+    #[error("too many redirects")]
+    TooManyRedirects,
+    #[error("server answered {0}")]
+    Status(u16),
+    #[error("request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error(transparent)]
+    Client(#[from] AIError),
+}
+
+/// What a page says about itself, from Open Graph tags and `<title>`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UrlMetadata {
+    /// Address the metadata was read from, after redirects
+    pub url: String,
+    pub title: Option<String>,
+    pub description: Option<String>,
+    /// Absolute http(s) URL of the preview image
+    pub image: Option<String>,
+    pub site_name: Option<String>,
+}
+
+/// Content of the first `<meta>` tag whose `property` or `name` is one of `keys`.
+fn meta_content(html: &str, keys: &[&str]) -> Option<String> {
+    let tags: Vec<Vec<(String, String)>> = META_TAG
+        .find_iter(html)
+        .map(|tag| {
+            ATTRIBUTE
+                .captures_iter(tag.as_str())
+                .map(|caps| {
+                    let value = caps
+                        .get(2)
+                        .or_else(|| caps.get(3))
+                        .map_or("", |m| m.as_str());
+                    (caps[1].to_ascii_lowercase(), value.to_string())
+                })
+                .collect()
+        })
+        .collect();
+    // Earlier keys are preferred: `og:description` over `description`
+    keys.iter().find_map(|key| {
+        tags.iter().find_map(|attributes| {
+            let named = attributes.iter().any(|(name, value)| {
+                (name == "property" || name == "name") && value.eq_ignore_ascii_case(key)
+            });
+            let content = attributes.iter().find(|(name, _)| name == "content")?;
+            let text = decode_entities(&content.1)
+                .split_whitespace()
+                .collect::<Vec<_>>()
+                .join(" ");
+            (named && !text.is_empty()).then_some(text)
+        })
+    })
+}
+
+/// Metadata of the page at `page_url` from its HTML.
+pub fn html_metadata(html: &str, page_url: &str) -> UrlMetadata {
+    let description = meta_content(
+        html,
+        &["og:description", "twitter:description", "description"],
+    )
+    .map(|d| {
+        if d.chars().count() > MAX_DESCRIPTION_CHARS {
+            let cut: String = d.chars().take(MAX_DESCRIPTION_CHARS).collect();
+            format!("{}…", cut.trim_end())
+        } else {
+            d
+        }
+    });
+    // Relative images are resolved against the page; other schemes are dropped
+    let image =
+        meta_content(html, &["og:image", "og:image:url", "twitter:image"]).and_then(|src| {
+            let url = Url::parse(page_url).ok()?.join(&src).ok()?;
+            matches!(url.scheme(), "http" | "https").then(|| url.to_string())
+        });
+    UrlMetadata {
+        url: page_url.to_string(),
+        title: meta_content(html, &["og:title"])
+            .or_else(|| html_title(html))
+            .or_else(|| meta_content(html, &["twitter:title"])),
+        description,
+        image,
+        site_name: meta_content(html, &["og:site_name", "application-name"]),
+    }
+}
+
+/// Whether `ip` is reachable on the public internet. Loopback, private,
+/// link-local, carrier-grade NAT and reserved ranges are not.
+pub fn is_public_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => is_public_ipv4(ip),
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public_ipv4(ip),
+            None => is_public_ipv6(ip),
+        },
+    }
+}
+
+fn is_public_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    !(ip.is_private()
+        || ip.is_loopback()
+        || ip.is_link_local()
+        || ip.is_unspecified()
+        || ip.is_broadcast()
+        || ip.is_documentation()
+        || ip.is_multicast()
+        || a == 0
+        || a >= 240
+        || (a == 100 && (64..128).contains(&b))
+        || (a == 198 && (b == 18 || b == 19)))
+}
+
+fn is_public_ipv6(ip: Ipv6Addr) -> bool {
+    let first = ip.segments()[0];
+    !(ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // Unique local fc00::/7 and link-local fe80::/10
+        || (first & 0xfe00) == 0xfc00
+        || (first & 0xffc0) == 0xfe80
+        // Documentation 2001:db8::/32
+        || (first == 0x2001 && ip.segments()[1] == 0x0db8))
+}
+
+/// IP address `url` names directly, as opposed to a domain.
+fn ip_host(url: &Url) -> Option<IpAddr> {
+    let host = url.host_str()?;
+    host.trim_start_matches('[')
+        .trim_end_matches(']')
+        .parse()
+        .ok()
+}
+
+/// Addresses `url` resolves to, refused unless all of them are public.
+async fn public_addrs(url: &Url) -> Result<Vec<SocketAddr>, FetchError> {
+    let host = url
+        .host_str()
+        .ok_or_else(|| FetchError::InvalidUrl(url.to_string()))?;
+    let port = url.port_or_known_default().unwrap_or(80);
+    let addrs: Vec<SocketAddr> = match ip_host(url) {
+        Some(ip) => vec![SocketAddr::new(ip, port)],
+        None => tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|_| FetchError::Resolve(host.to_string()))?
+            .collect(),
+    };
+    if addrs.is_empty() {
+        return Err(FetchError::Resolve(host.to_string()));
+    }
+    if addrs.iter().any(|addr| !is_public_ip(addr.ip())) {
+        return Err(FetchError::Blocked(host.to_string()));
+    }
+    Ok(addrs)
+}
+
+/// Fetch `url` and read its metadata. Every hop of a redirect chain is
+/// checked to resolve to a public address, and the connection is pinned to
+/// the checked addresses so the name can't be re-resolved elsewhere.
+/// Through a proxy the check still runs before the proxy is asked, but the
+/// proxy resolves the name itself, so the pinning doesn't apply there.
+/// Non-HTML responses give metadata with only the URL.
+pub async fn fetch_metadata(settings: &HttpSettings, url: &str) -> Result<UrlMetadata, FetchError> {
+    let mut url = Url::parse(url.trim()).map_err(|e| FetchError::InvalidUrl(e.to_string()))?;
+    for _ in 0..=MAX_REDIRECTS {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(FetchError::InvalidUrl(format!(
+                "unsupported scheme: {}",
+                url.scheme()
+            )));
+        }
+        let addrs = public_addrs(&url).await?;
+        let mut builder = client_builder(settings)?
+            .redirect(Policy::none())
+            .timeout(FETCH_TIMEOUT);
+        if let (None, Some(domain)) = (ip_host(&url), url.host_str()) {
+            builder = builder.resolve_to_addrs(domain, &addrs);
+        }
+        let mut response = builder
+            .build()?
+            .get(url.clone())
+            .header(ACCEPT, "text/html,application/xhtml+xml;q=0.9,*/*;q=0.5")
+            .send()
+            .await?;
+
+        let status = response.status();
+        if status.is_redirection() {
+            let location = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|v| v.to_str().ok())
+                .ok_or(FetchError::Status(status.as_u16()))?;
+            url = url
+                .join(location)
+                .map_err(|e| FetchError::InvalidUrl(e.to_string()))?;
+            continue;
+        }
+        if !status.is_success() {
+            return Err(FetchError::Status(status.as_u16()));
+        }
+
+        let is_html = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .is_some_and(|v| v.contains("html"));
+        if !is_html {
+            return Ok(UrlMetadata {
+                url: url.to_string(),
+                ..UrlMetadata::default()
+            });
+        }
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            body.extend_from_slice(&chunk);
+            if body.len() >= MAX_PAGE_BYTES {
+                body.truncate(MAX_PAGE_BYTES);
+                break;
+            }
+        }
+        return Ok(html_metadata(&String::from_utf8_lossy(&body), url.as_str()));
+    }
+    Err(FetchError::TooManyRedirects)
+}
+
+fn escape_link_text(text: &str) -> String {
+    text.replace('[', "\\[").replace(']', "\\]")
+}
+
+/// `[title](url)`, titled with the URL when the page has no title.
+pub fn markdown_link(url: &str, metadata: Option<&UrlMetadata>) -> String {
+    let title = metadata.and_then(|m| m.title.as_deref()).unwrap_or(url);
+    format!("[{}]({})", escape_link_text(title), url)
+}
+
+/// Markdown citation: the link with the site and date it was accessed, then
+/// the description as a quote.
+pub fn citation(url: &str, metadata: Option<&UrlMetadata>, accessed: &str) -> String {
+    let mut source = vec![markdown_link(url, metadata)];
+    if let Some(site) = metadata.and_then(|m| m.site_name.as_deref()) {
+        source.push(site.to_string());
+    }
+    source.push(accessed.to_string());
+    let mut lines = vec![source.join(" · ")];
+    if let Some(description) = metadata.and_then(|m| m.description.as_deref()) {
+        lines.push(format!("> {}", description));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_html_metadata() {
+        let html = r#"<head>
+            <title>Fallback</title>
+            <meta content="Rust &amp; You" property="og:title">
+            <meta name="description" content="Plain description">
+            <meta property='og:description' content='A   language
+                empowering everyone'>
+            <meta property="og:image" content="/img/card.png">
+            <meta property="og:site_name" content="Rust Blog">
+        </head>"#;
+        let metadata = html_metadata(html, "https://blog.rust-lang.org/2024/post.html");
+        assert_eq!(metadata.title.as_deref(), Some("Rust & You"));
+        assert_eq!(
+            metadata.description.as_deref(),
+            Some("A language empowering everyone")
+        );
+        assert_eq!(
+            metadata.image.as_deref(),
+            Some("https://blog.rust-lang.org/img/card.png")
+        );
+        assert_eq!(metadata.site_name.as_deref(), Some("Rust Blog"));
+
+        let bare = html_metadata(
+            r#"<meta property="og:image" content="javascript:alert(1)">"#,
+            "https://a.com/",
+        );
+        assert_eq!(
+            bare,
+            UrlMetadata {
+                url: "https://a.com/".to_string(),
+                ..UrlMetadata::default()
+            }
+        );
+
+        let url = "https://a.com/x";
+        assert_eq!(citation(url, Some(&metadata), "2024-06-01"), "[Rust & You](https://a.com/x) · Rust Blog · 2024-06-01\n> A language empowering everyone");
+        assert_eq!(
+            citation(url, None, "2024-06-01"),
+            "[https://a.com/x](https://a.com/x) · 2024-06-01"
+        );
+    }
+
+    #[test]
+    fn test_is_public_ip() {
+        for ip in ["93.184.216.34", "2606:4700::1111", "8.8.8.8"] {
+            assert!(is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+        for ip in [
+            "127.0.0.1",
+            "10.1.2.3",
+            "172.16.0.1",
+            "192.168.1.1",
+            "169.254.169.254",
+            "100.64.0.1",
+            "0.0.0.0",
+            "::1",
+            "fd00::1",
+            "fe80::1",
+            "::ffff:127.0.0.1",
+        ] {
+            assert!(!is_public_ip(ip.parse().unwrap()), "{}", ip);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_refuses_private_hosts() {
+        let settings = HttpSettings::default();
+        for url in [
+            "http://127.0.0.1:8080/",
+            "http://[::1]/",
+            "http://169.254.169.254/latest/meta-data",
+            "file:///etc/passwd",
+        ] {
+            let result = fetch_metadata(&settings, url).await;
+            assert!(
+                matches!(
+                    result,
+                    Err(FetchError::Blocked(_) | FetchError::InvalidUrl(_))
+                ),
+                "{}",
+                url
+            );
+        }
+    }
+
+    /// Proxy on localhost that answers every request with `response`,
+    /// counting the requests it gets.
+    async fn proxy(response: &'static str) -> (HttpSettings, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let settings = HttpSettings {
+            proxy_url: format!("http://{}", listener.local_addr().unwrap()),
+            ..HttpSettings::default()
+        };
+        let requests = Arc::new(AtomicUsize::new(0));
+        let counter = requests.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let mut buf = [0u8; 4096];
+                let _ = stream.read(&mut buf).await;
+                let _ = stream.write_all(response.as_bytes()).await;
+            }
+        });
+        (settings, requests)
+    }
+
+    #[tokio::test]
+    async fn test_fetch_through_proxy_checks_targets_first() {
+        let page = concat!(
+            "HTTP/1.1 200 OK\r\nContent-Type: text/html\r\nContent-Length: 22\r\n",
+            "Connection: close\r\n\r\n<title>Proxied</title>"
+        );
+        let (settings, requests) = proxy(page).await;
+        for url in ["http://127.0.0.1:8080/", "http://localhost/", "http://10.0.0.1/"] {
+            let result = fetch_metadata(&settings, url).await;
+            assert!(matches!(result, Err(FetchError::Blocked(_))), "{}", url);
+        }
+        assert_eq!(requests.load(Ordering::SeqCst), 0);
+
+        let metadata = fetch_metadata(&settings, "http://93.184.215.14/").await.unwrap();
+        assert_eq!(metadata.title.as_deref(), Some("Proxied"));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+
+        // A redirect to a private address is refused without asking the proxy again
+        let redirect = concat!(
+            "HTTP/1.1 302 Found\r\nLocation: http://127.0.0.1/admin\r\n",
+            "Content-Length: 0\r\nConnection: close\r\n\r\n"
+        );
+        let (settings, requests) = proxy(redirect).await;
+        let result = fetch_metadata(&settings, "http://93.184.215.14/").await;
+        assert!(matches!(result, Err(FetchError::Blocked(_))));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+    }
+}
@@ -0,0 +1,44 @@
+mod chunk;
+mod color;
+mod datetime;
+mod decode;
+mod diff;
+mod email;
+mod encoding;
+mod fuzzy;
+mod generate;
+mod hash;
+mod identifier;
+mod metadata;
+mod ocr;
+mod paths;
+mod stats;
+mod table;
+mod tracking;
+mod url;
+
+pub use chunk::split_chunks;
+pub use color::{find_colors, is_color_list, replace_colors, ColorFormat};
+pub use datetime::parse_timestamp;
+pub use decode::decode_base64;
+pub use diff::{diff_texts, DiffResult};
+pub use email::{is_email_thread, strip_quoted};
+pub use encoding::{convert as convert_encoding, encoding_for_label, fix_mojibake, Recovered};
+pub use fuzzy::fuzzy_score;
+pub use generate::{
+    card_number, lorem_ipsum, luhn_valid, password, CardBrand, GenerateError, Generator, PasswordPolicy,
+};
+pub use hash::{hash_bytes, hash_reader, HashAlgorithm};
+pub use identifier::{classify_id, IdKind};
+pub use metadata::{citation, fetch_metadata, html_metadata, is_public_ip, markdown_link, FetchError, UrlMetadata};
+pub use ocr::clean_ocr_text;
+pub use paths::{
+    audio_file_path, file_name, file_path, is_path_list, is_windows_path, shell_quote, to_posix_path,
+    to_windows_path,
+};
+pub use stats::{text_stats, TextStats};
+pub use table::{
+    detect_delimiter, is_separator_row, parse_table, split_delimited_line, split_markdown_row,
+    TableFormat, TableInfo,
+};
+pub use url::{clean_url, has_tracking_params, html_title, is_url_list, strip_tracking};
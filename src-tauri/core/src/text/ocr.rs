@@ -0,0 +1,64 @@
+use super::stats::is_cjk;
+
+/// Tidy text read by OCR: drops the page break Tesseract ends with, the
+/// spaces it puts between CJK characters, trailing spaces and runs of
+/// blank lines.
+pub fn clean_ocr_text(raw: &str) -> String {
+    let mut lines: Vec<String> = Vec::new();
+    for line in raw.replace('\u{c}', "").lines() {
+        let line = join_cjk(line.trim_end());
+        // One blank line between paragraphs, none at the start
+        let after_blank = match lines.last() {
+            Some(last) => last.is_empty(),
+            None => true,
+        };
+        if line.is_empty() && after_blank {
+            continue;
+        }
+        lines.push(line);
+    }
+    while lines.last().is_some_and(|l| l.is_empty()) {
+        lines.pop();
+    }
+    lines.join("\n")
+}
+
+/// `你 好 ， 世 界` becomes `你好，世界`; spaces next to Latin text stay.
+fn join_cjk(line: &str) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut joined = String::with_capacity(line.len());
+    for (i, &c) in chars.iter().enumerate() {
+        if c == ' ' {
+            let before = chars[..i].iter().rev().find(|c| **c != ' ');
+            let after = chars[i + 1..].iter().find(|c| **c != ' ');
+            if before.is_some_and(|c| is_cjk_or_punct(*c))
+                && after.is_some_and(|c| is_cjk_or_punct(*c))
+            {
+                continue;
+            }
+        }
+        joined.push(c);
+    }
+    joined
+}
+
+fn is_cjk_or_punct(c: char) -> bool {
+    // CJK symbols and punctuation, full-width forms
+    is_cjk(c) || matches!(c as u32, 0x3000..=0x303F | 0xFF00..=0xFFEF)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clean_ocr_text() {
+        assert_eq!(
+            clean_ocr_text("你 好 ， 世 界 \n\n\n\nHello  world \n\n\u{c}"),
+            "你好，世界\n\nHello  world"
+        );
+        assert_eq!(clean_ocr_text("\n\n发 布 版本 v2"), "发布版本 v2");
+        assert_eq!(clean_ocr_text("  indented code\n"), "  indented code");
+        assert_eq!(clean_ocr_text("\u{c}"), "");
+    }
+}
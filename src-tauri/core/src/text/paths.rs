@@ -0,0 +1,126 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+// `C:\Users\me`, `D:/data`, `\\server\share`
+static WINDOWS_PATH: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"^(?:[A-Za-z]:[\\/]|\\\\[^\\/\s]+\\)[^<>|"?*\x00-\x1f]*$"#).unwrap());
+// `/usr/bin`, `~/notes.txt`, `./src/main.rs`; `//` is a comment, not a path
+static POSIX_PATH: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?:~|\.{1,2})?/[^/\s][^\x00-\x1f]*$").unwrap());
+
+/// Audio formats whisper.cpp reads without ffmpeg.
+const AUDIO_EXTENSIONS: &[&str] = &["wav", "mp3", "flac", "ogg"];
+
+/// A path as copied, without the quotes Explorer's "Copy as path" adds.
+fn unquote(line: &str) -> &str {
+    let line = line.trim();
+    line.strip_prefix('"')
+        .and_then(|l| l.strip_suffix('"'))
+        .or_else(|| line.strip_prefix('\'').and_then(|l| l.strip_suffix('\'')))
+        .unwrap_or(line)
+}
+
+pub fn is_windows_path(line: &str) -> bool {
+    WINDOWS_PATH.is_match(unquote(line))
+}
+
+fn is_posix_path(line: &str) -> bool {
+    POSIX_PATH.is_match(unquote(line)) && !line.contains("://")
+}
+
+/// Every non-blank line is a Windows or POSIX path.
+pub fn is_path_list(text: &str) -> bool {
+    let mut lines = text.lines().filter(|l| !l.trim().is_empty()).peekable();
+    lines.peek().is_some() && lines.all(|l| is_windows_path(l) || is_posix_path(l))
+}
+
+/// `C:\a\b` ↔ `C:/a/b`. Only the separators change.
+pub fn to_posix_path(line: &str) -> String {
+    unquote(line).replace('\\', "/")
+}
+
+pub fn to_windows_path(line: &str) -> String {
+    unquote(line).replace('/', "\\")
+}
+
+/// Quote a path for a shell: single quotes for POSIX paths, double quotes
+/// for Windows ones, nothing when every character is safe unquoted.
+pub fn shell_quote(line: &str) -> String {
+    let path = unquote(line);
+    let safe = path
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "_-./~:\\+,@%=".contains(c));
+    if safe {
+        path.to_string()
+    } else if is_windows_path(path) {
+        format!("\"{}\"", path)
+    } else {
+        format!("'{}'", path.replace('\'', r"'\''"))
+    }
+}
+
+/// Last component of a path, ignoring a trailing separator.
+pub fn file_name(line: &str) -> &str {
+    let path = unquote(line).trim_end_matches(['/', '\\']);
+    path.rsplit(['/', '\\']).next().unwrap_or(path)
+}
+
+/// The path in `text` when it is a single file (its name has an
+/// extension), without quotes.
+pub fn file_path(text: &str) -> Option<&str> {
+    let line = text.trim();
+    if line.contains('\n') || !(is_windows_path(line) || is_posix_path(line)) {
+        return None;
+    }
+    let path = unquote(line);
+    file_name(path).rsplit_once('.').filter(|(_, ext)| !ext.is_empty()).map(|_| path)
+}
+
+/// The path in `text` when it is a single audio file, without quotes.
+pub fn audio_file_path(text: &str) -> Option<&str> {
+    let path = file_path(text)?;
+    let (_, extension) = file_name(path).rsplit_once('.')?;
+    AUDIO_EXTENSIONS
+        .contains(&extension.to_ascii_lowercase().as_str())
+        .then_some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_path_list() {
+        assert!(is_path_list("C:\\Users\\me\\file.txt\n\"D:\\My Docs\\a b.pdf\""));
+        assert!(is_path_list("/usr/local/bin\n~/notes.md\n./src/main.rs\n\\\\server\\share\\x"));
+        assert!(!is_path_list("// just a comment"));
+        assert!(!is_path_list("/usr/bin\nnot a path"));
+        assert!(!is_path_list(""));
+    }
+
+    #[test]
+    fn test_path_conversions() {
+        assert_eq!(to_posix_path("\"C:\\Users\\me\\a.txt\""), "C:/Users/me/a.txt");
+        assert_eq!(to_windows_path("./src/main.rs"), ".\\src\\main.rs");
+        assert_eq!(file_name("/home/me/photos/"), "photos");
+        assert_eq!(file_name("C:\\Users\\me\\a.txt"), "a.txt");
+    }
+
+    #[test]
+    fn test_audio_file_path() {
+        assert_eq!(audio_file_path("\"D:\\Voice\\memo 1.MP3\"\n"), Some("D:\\Voice\\memo 1.MP3"));
+        assert_eq!(audio_file_path("~/rec/call.ogg"), Some("~/rec/call.ogg"));
+        assert_eq!(audio_file_path("/home/me/notes.txt"), None);
+        assert_eq!(audio_file_path("/a.wav\n/b.wav"), None);
+        assert_eq!(audio_file_path("/music/wav"), None);
+        assert_eq!(file_path("'/tmp/setup.iso'"), Some("/tmp/setup.iso"));
+        assert_eq!(file_path("/usr/local/bin"), None);
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("/usr/local/bin"), "/usr/local/bin");
+        assert_eq!(shell_quote("/tmp/it's here"), r"'/tmp/it'\''s here'");
+        assert_eq!(shell_quote("C:\\Program Files\\App"), "\"C:\\Program Files\\App\"");
+    }
+}
@@ -0,0 +1,132 @@
+use serde::Serialize;
+
+// Reading speeds used for the estimate: words/minute for alphabetic scripts,
+// characters/minute for CJK.
+const WORDS_PER_MINUTE: f64 = 200.0;
+const CJK_CHARS_PER_MINUTE: f64 = 300.0;
+// Rough BPE ratio for Latin text; CJK averages about one token per character.
+const CHARS_PER_TOKEN: f64 = 4.0;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextStats {
+    pub characters: usize,
+    pub characters_no_spaces: usize,
+    /// Latin words plus individual CJK characters
+    pub words: usize,
+    pub cjk_characters: usize,
+    pub lines: usize,
+    pub paragraphs: usize,
+    pub sentences: usize,
+    pub read_time_seconds: u64,
+    pub estimated_tokens: usize,
+}
+
+pub(crate) fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF     // Hiragana, Katakana
+        | 0x3400..=0x4DBF   // CJK Extension A
+        | 0x4E00..=0x9FFF   // CJK Unified Ideographs
+        | 0xAC00..=0xD7A3   // Hangul syllables
+        | 0xF900..=0xFAFF   // CJK Compatibility Ideographs
+        | 0x20000..=0x2A6DF // CJK Extension B
+    )
+}
+
+pub(crate) fn is_sentence_end(c: char) -> bool {
+    matches!(c, '.' | '!' | '?' | '。' | '！' | '？' | '…')
+}
+
+pub fn text_stats(text: &str) -> TextStats {
+    if text.is_empty() {
+        return TextStats::default();
+    }
+
+    let mut stats = TextStats::default();
+    let mut latin_words = 0;
+    let mut in_word = false;
+    let mut other_chars = 0;
+
+    for c in text.chars() {
+        stats.characters += 1;
+        if !c.is_whitespace() {
+            stats.characters_no_spaces += 1;
+        }
+
+        if is_cjk(c) {
+            stats.cjk_characters += 1;
+            in_word = false;
+            continue;
+        }
+
+        other_chars += 1;
+        // Apostrophes and hyphens inside words ("don't", "e-mail") don't split them
+        if c.is_alphanumeric() || (in_word && (c == '\'' || c == '-' || c == '_')) {
+            if !in_word {
+                latin_words += 1;
+                in_word = true;
+            }
+        } else {
+            in_word = false;
+        }
+    }
+
+    stats.words = latin_words + stats.cjk_characters;
+    stats.lines = text.lines().count();
+    stats.paragraphs = text
+        .split("\n\n")
+        .filter(|p| !p.trim().is_empty())
+        .count();
+    stats.sentences = text
+        .split(is_sentence_end)
+        .filter(|s| s.chars().any(|c| c.is_alphanumeric()))
+        .count();
+
+    let minutes = latin_words as f64 / WORDS_PER_MINUTE
+        + stats.cjk_characters as f64 / CJK_CHARS_PER_MINUTE;
+    stats.read_time_seconds = (minutes * 60.0).ceil() as u64;
+    stats.estimated_tokens =
+        stats.cjk_characters + (other_chars as f64 / CHARS_PER_TOKEN).ceil() as usize;
+
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_text() {
+        assert_eq!(text_stats(""), TextStats::default());
+    }
+
+    #[test]
+    fn test_english_stats() {
+        let stats = text_stats("Hello world. Don't panic!\nSecond line?");
+        assert_eq!(stats.characters, 38);
+        assert_eq!(stats.characters_no_spaces, 33);
+        assert_eq!(stats.words, 6);
+        assert_eq!(stats.lines, 2);
+        assert_eq!(stats.sentences, 3);
+        assert_eq!(stats.paragraphs, 1);
+        assert_eq!(stats.cjk_characters, 0);
+    }
+
+    #[test]
+    fn test_cjk_aware_words() {
+        let stats = text_stats("我爱 Rust 编程。今天天气很好！");
+        assert_eq!(stats.cjk_characters, 10);
+        assert_eq!(stats.words, 11);
+        assert_eq!(stats.sentences, 2);
+        assert!(stats.estimated_tokens >= 10);
+    }
+
+    #[test]
+    fn test_read_time_and_paragraphs() {
+        let text = format!("{}\n\n{}", "word ".repeat(200), "word ".repeat(200));
+        let stats = text_stats(&text);
+        assert_eq!(stats.words, 400);
+        assert_eq!(stats.paragraphs, 2);
+        assert_eq!(stats.read_time_seconds, 120);
+    }
+}
@@ -0,0 +1,253 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::Serialize;
+use std::collections::HashSet;
+
+// Markdown table delimiter row, e.g. `| --- | :---: |`
+static MD_SEPARATOR_CELL: Lazy<Regex> = Lazy::new(|| Regex::new(r"^:?-{3,}:?$").unwrap());
+static MD_TABLE_SEPARATOR: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?m)^\s*\|?\s*:?-{3,}:?\s*(\|\s*:?-{3,}:?\s*)*\|?\s*$").unwrap()
+});
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TableFormat {
+    Csv,
+    Tsv,
+    /// Pipe table
+    Markdown,
+}
+
+/// Shape of a table, reported with the intent so the panel can show it.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TableInfo {
+    pub format: TableFormat,
+    pub has_header: bool,
+    pub column_count: usize,
+    /// Header cells when `has_header`, otherwise empty
+    pub columns: Vec<String>,
+    /// Rows below the header
+    pub row_count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Table {
+    pub info: TableInfo,
+    /// Data rows, without the header
+    pub rows: Vec<Vec<String>>,
+}
+
+/// Split one delimited line, honoring double-quoted fields (`"a, b"` and `""` escapes).
+pub fn split_delimited_line(line: &str, delimiter: char) -> Vec<String> {
+    let mut cells = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if in_quotes => {
+                if chars.peek() == Some(&'"') {
+                    current.push('"');
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                }
+            }
+            '"' if current.trim().is_empty() => {
+                current.clear();
+                in_quotes = true;
+            }
+            c if c == delimiter && !in_quotes => {
+                cells.push(current.trim().to_string());
+                current.clear();
+            }
+            _ => current.push(c),
+        }
+    }
+    cells.push(current.trim().to_string());
+    cells
+}
+
+/// Tab wins over comma: TSV cells commonly contain commas, the reverse is rare.
+pub fn detect_delimiter(text: &str) -> char {
+    if text.lines().any(|l| l.contains('\t')) {
+        '\t'
+    } else {
+        ','
+    }
+}
+
+/// Split delimited text into records. Like `split_delimited_line`, a quote
+/// only opens at the start of a field, and a quoted field may span lines.
+fn split_records(text: &str, delimiter: char) -> Vec<&str> {
+    let mut records = Vec::new();
+    let mut start = 0;
+    let mut in_quotes = false;
+    let mut field_start = true;
+    let mut chars = text.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        if in_quotes {
+            if c == '"' {
+                if chars.peek().is_some_and(|&(_, next)| next == '"') {
+                    chars.next();
+                } else {
+                    in_quotes = false;
+                    field_start = false;
+                }
+            }
+            continue;
+        }
+        match c {
+            '"' if field_start => in_quotes = true,
+            '\n' => {
+                records.push(text[start..i].trim_end_matches('\r'));
+                start = i + 1;
+                field_start = true;
+            }
+            c if c == delimiter => field_start = true,
+            c if c.is_whitespace() => {}
+            _ => field_start = false,
+        }
+    }
+    records.push(&text[start..]);
+    records
+}
+
+/// Split a Markdown table row on unescaped pipes, dropping the outer borders.
+pub fn split_markdown_row(line: &str) -> Vec<String> {
+    let trimmed = line.trim();
+    let inner = trimmed.strip_prefix('|').unwrap_or(trimmed);
+    let inner = if inner.ends_with('|') && !inner.ends_with("\\|") {
+        &inner[..inner.len() - 1]
+    } else {
+        inner
+    };
+
+    let mut cells = Vec::new();
+    let mut current = String::new();
+    let mut chars = inner.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                current.push('|');
+                chars.next();
+            }
+            '|' => {
+                cells.push(current.trim().to_string());
+                current.clear();
+            }
+            _ => current.push(c),
+        }
+    }
+    cells.push(current.trim().to_string());
+    cells
+}
+
+pub fn is_separator_row(cells: &[String]) -> bool {
+    !cells.is_empty() && cells.iter().all(|c| MD_SEPARATOR_CELL.is_match(c))
+}
+
+fn is_markdown_table(text: &str) -> bool {
+    MD_TABLE_SEPARATOR.is_match(text)
+        && text.lines().filter(|l| l.trim_start().starts_with('|')).count() >= 2
+}
+
+/// A first row of distinct labels that never reappear in their column.
+/// Spreadsheet copies nearly always start with one, so this leans towards yes.
+fn looks_like_header(rows: &[Vec<String>]) -> bool {
+    let Some((header, data)) = rows.split_first() else {
+        return false;
+    };
+    let is_number = |s: &str| s.replace(',', "").parse::<f64>().is_ok();
+    let distinct = header.iter().collect::<HashSet<_>>().len() == header.len();
+    distinct
+        && header.iter().all(|cell| !cell.is_empty() && !is_number(cell))
+        && data.iter().all(|row| row.iter().zip(header).all(|(cell, label)| cell != label))
+}
+
+/// Parse CSV, TSV or a Markdown pipe table. Delimited text needs at least two
+/// rows and two columns, with most rows as wide as the first, so prose that
+/// happens to contain commas isn't taken for CSV.
+pub fn parse_table(text: &str) -> Option<Table> {
+    let (format, mut rows): (TableFormat, Vec<Vec<String>>) = if is_markdown_table(text) {
+        let rows = text
+            .lines()
+            .filter(|l| l.trim_start().starts_with('|'))
+            .map(split_markdown_row)
+            .filter(|cells| !is_separator_row(cells))
+            .collect();
+        (TableFormat::Markdown, rows)
+    } else {
+        let delimiter = detect_delimiter(text);
+        let format = if delimiter == '\t' { TableFormat::Tsv } else { TableFormat::Csv };
+        let rows = split_records(text, delimiter)
+            .into_iter()
+            .filter(|record| !record.trim().is_empty())
+            .map(|record| split_delimited_line(record, delimiter))
+            .collect();
+        (format, rows)
+    };
+
+    let column_count = rows.first()?.len();
+    if format != TableFormat::Markdown {
+        let consistent = rows.iter().filter(|r| r.len() == column_count).count();
+        if rows.len() < 2 || column_count < 2 || consistent * 5 < rows.len() * 4 {
+            return None;
+        }
+    }
+
+    // Markdown tables always have a header above the separator row
+    let has_header = format == TableFormat::Markdown || looks_like_header(&rows);
+    let columns = if has_header { rows.remove(0) } else { Vec::new() };
+    Some(Table {
+        info: TableInfo {
+            format,
+            has_header,
+            column_count,
+            columns,
+            row_count: rows.len(),
+        },
+        rows,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_delimited_line_quotes() {
+        let cells = split_delimited_line(r#"a,"b, c","say ""hi""""#, ',');
+        assert_eq!(cells, vec!["a", "b, c", r#"say "hi""#]);
+    }
+
+    #[test]
+    fn test_parse_csv_with_header_and_multiline_cell() {
+        let table = parse_table("name,note\nAlice,\"line one\nline two\"\nBob,\"5\"\" screen\"").unwrap();
+        assert_eq!(table.info.format, TableFormat::Csv);
+        assert!(table.info.has_header);
+        assert_eq!(table.info.columns, ["name", "note"]);
+        assert_eq!(table.rows, [["Alice", "line one\nline two"], ["Bob", "5\" screen"]]);
+    }
+
+    #[test]
+    fn test_parse_tsv_and_markdown() {
+        let tsv = parse_table("1\t2\n3\t4").unwrap();
+        assert_eq!(tsv.info.format, TableFormat::Tsv);
+        assert!(!tsv.info.has_header);
+        assert_eq!(tsv.info.row_count, 2);
+
+        let md = parse_table("| a | b |\n| --- | --- |\n| 1 | x\\|y |").unwrap();
+        assert_eq!(md.info.format, TableFormat::Markdown);
+        assert_eq!(md.info.columns, ["a", "b"]);
+        assert_eq!(md.rows, [["1", "x|y"]]);
+    }
+
+    #[test]
+    fn test_parse_rejects_ragged_text() {
+        assert_eq!(parse_table("just one line, with, commas"), None);
+        assert_eq!(parse_table("a, b, c\nno commas here\nor here\nnor here"), None);
+    }
+}
@@ -0,0 +1,110 @@
+//! Query parameters that identify a click or a share rather than the page.
+//! Names are compared lowercased. Keep these tables up to date as sites
+//! change their share links; a parameter belongs in `SITES` rather than
+//! `ANY_SITE` whenever some other site may need it to find the page.
+
+/// Stripped from every URL.
+const ANY_SITE: &[&str] = &[
+    "fbclid", "gclid", "dclid", "gbraid", "wbraid", "msclkid", "yclid", "igshid", "mc_cid",
+    "mc_eid", "_hsenc", "_hsmi", "mkt_tok", "spm", "ref_src", "si", "_ga", "_gl", "twclid",
+    "ttclid", "li_fat_id", "oly_anon_id", "oly_enc_id", "vero_id", "wickedid",
+];
+
+/// Stripped from every URL, by prefix.
+const ANY_SITE_PREFIXES: &[&str] = &["utm_", "pk_", "hsa_"];
+
+/// Stripped only from URLs on these hosts or their subdomains.
+struct Site {
+    hosts: &'static [&'static str],
+    params: &'static [&'static str],
+}
+
+const SITES: &[Site] = &[
+    // WeChat articles need only __biz, mid, idx and sn
+    Site {
+        hosts: &["weixin.qq.com"],
+        params: &[
+            "chksm", "scene", "subscene", "sessionid", "clicktime", "enterid", "ascene", "devicetype",
+            "version", "nettype", "abtest_cookie", "exportkey", "pass_ticket", "wx_header", "key",
+            "uin", "from", "isappinstalled", "srcid", "sharer_sharetime", "sharer_shareid",
+            "sharer_username", "share_source", "mpshare", "realreporttime", "poc_token",
+        ],
+    },
+    Site {
+        hosts: &["weibo.com", "weibo.cn"],
+        params: &[
+            "from", "wm", "sourcetype", "dt_dapp", "dt_platform", "luicode", "lfid", "featurecode",
+            "jumpfrom", "s_channel", "s_trans",
+        ],
+    },
+    Site {
+        hosts: &["bilibili.com", "b23.tv"],
+        params: &[
+            "share_source", "share_medium", "share_plat", "share_session_id", "share_tag", "share_from",
+            "bbid", "ts", "unique_k", "vd_source", "spm_id_from", "from_spmid", "buvid", "timestamp",
+        ],
+    },
+    Site {
+        hosts: &["xiaohongshu.com", "xhslink.com"],
+        params: &[
+            "xhsshare", "appuid", "apptime", "share_from_user_hidden", "share_id", "author_share",
+            "shareredid", "app_platform", "app_version",
+        ],
+    },
+    Site {
+        hosts: &["zhihu.com"],
+        params: &["share_code", "utm_psn"],
+    },
+    Site {
+        hosts: &["douyin.com", "iesdouyin.com"],
+        params: &["previous_page", "share_token", "u_code", "did", "iid", "with_sec_did", "timestamp"],
+    },
+    Site {
+        hosts: &["taobao.com", "tmall.com"],
+        params: &[
+            "ut_sk", "sourcetype", "suid", "shareuniqueid", "un", "share_crt_v", "sp_tk", "cpp",
+            "shareurl", "short_name", "bxsign", "tbsocialpopkey", "wxsign",
+        ],
+    },
+    Site {
+        hosts: &["twitter.com", "x.com"],
+        params: &["s", "t"],
+    },
+    Site {
+        hosts: &["youtube.com", "youtu.be"],
+        params: &["feature", "pp"],
+    },
+];
+
+fn on_host(host: &str, domain: &str) -> bool {
+    host == domain || host.strip_suffix(domain).is_some_and(|sub| sub.ends_with('.'))
+}
+
+/// Whether query parameter `key` of a URL on `host` (lowercase) only tracks.
+pub fn is_tracking_param(host: &str, key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    ANY_SITE.contains(&key.as_str())
+        || ANY_SITE_PREFIXES.iter().any(|prefix| key.starts_with(prefix))
+        || SITES
+            .iter()
+            .any(|site| site.hosts.iter().any(|d| on_host(host, d)) && site.params.contains(&key.as_str()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_tracking_param() {
+        assert!(is_tracking_param("example.com", "UTM_Source"));
+        assert!(is_tracking_param("example.com", "fbclid"));
+        assert!(is_tracking_param("mp.weixin.qq.com", "chksm"));
+        assert!(!is_tracking_param("mp.weixin.qq.com", "__biz"));
+        assert!(is_tracking_param("m.weibo.cn", "from"));
+        assert!(is_tracking_param("x.com", "s"));
+        // Site parameters stay on other sites
+        assert!(!is_tracking_param("example.com", "from"));
+        assert!(!is_tracking_param("notweibo.com", "from"));
+        assert!(!is_tracking_param("example.com", "s"));
+    }
+}
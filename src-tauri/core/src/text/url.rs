@@ -0,0 +1,208 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::tracking;
+
+static URL_TOKEN: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)^https?://[^\s/?#]+[^\s]*$").unwrap());
+
+static OG_TITLE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?is)<meta\s[^>]*property\s*=\s*["']og:title["'][^>]*content\s*=\s*["']([^"']*)["']"#).unwrap()
+});
+static TITLE_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)<title[^>]*>(.*?)</title>").unwrap());
+
+/// Whether `text` is nothing but http(s) URLs separated by whitespace.
+pub fn is_url_list(text: &str) -> bool {
+    let mut tokens = text.split_whitespace().peekable();
+    tokens.peek().is_some() && tokens.all(|t| URL_TOKEN.is_match(t))
+}
+
+/// Scheme, authority and the rest of `url`.
+fn split_authority(url: &str) -> Option<(&str, &str, &str)> {
+    let (scheme, rest) = url.split_once("://")?;
+    let end = rest.find(['/', '?', '#']).unwrap_or(rest.len());
+    Some((scheme, &rest[..end], &rest[end..]))
+}
+
+/// Lowercase host of `url`, without user or port; empty if it has none.
+fn host(url: &str) -> String {
+    let Some((_, authority, _)) = split_authority(url) else {
+        return String::new();
+    };
+    let host = authority.rsplit('@').next().unwrap_or(authority);
+    let host = match host.strip_prefix('[') {
+        Some(ipv6) => ipv6.split(']').next().unwrap_or(ipv6),
+        None => host.split(':').next().unwrap_or(host),
+    };
+    host.to_ascii_lowercase()
+}
+
+fn is_tracking_pair(host: &str, pair: &str) -> bool {
+    tracking::is_tracking_param(host, pair.split('=').next().unwrap_or(pair))
+}
+
+pub fn has_tracking_params(url: &str) -> bool {
+    let host = host(url);
+    let query = url.split('#').next().unwrap_or(url).split_once('?').map(|(_, q)| q);
+    query.is_some_and(|q| q.split('&').any(|pair| is_tracking_pair(&host, pair)))
+}
+
+/// `url` without `utm_*` and other click-tracking or share parameters,
+/// including those of sites such as WeChat and Weibo; the fragment is kept.
+pub fn strip_tracking(url: &str) -> String {
+    let host = host(url);
+    let (rest, fragment) = match url.split_once('#') {
+        Some((rest, fragment)) => (rest, Some(fragment)),
+        None => (url, None),
+    };
+    let mut result = match rest.split_once('?') {
+        Some((base, query)) => {
+            let kept: Vec<&str> = query
+                .split('&')
+                .filter(|pair| !pair.is_empty() && !is_tracking_pair(&host, pair))
+                .collect();
+            if kept.is_empty() {
+                base.to_string()
+            } else {
+                format!("{}?{}", base, kept.join("&"))
+            }
+        }
+        None => rest.to_string(),
+    };
+    if let Some(fragment) = fragment {
+        result.push('#');
+        result.push_str(fragment);
+    }
+    result
+}
+
+/// `url` tidied for sharing: `strip_tracking`, with the scheme and host
+/// lowercased and no default port, empty query or empty fragment.
+pub fn clean_url(url: &str) -> String {
+    let Some((scheme, authority, rest)) = split_authority(url) else {
+        return strip_tracking(url);
+    };
+    let scheme = scheme.to_ascii_lowercase();
+    let (user, host) = match authority.rsplit_once('@') {
+        Some((user, host)) => (Some(user), host),
+        None => (None, authority),
+    };
+    let mut host = host.to_ascii_lowercase();
+    let default_port = match scheme.as_str() {
+        "http" => Some(":80"),
+        "https" => Some(":443"),
+        _ => None,
+    };
+    if let Some(port) = default_port {
+        if let Some(bare) = host.strip_suffix(port) {
+            host = bare.to_string();
+        }
+    }
+    let authority = match user {
+        Some(user) => format!("{}@{}", user, host),
+        None => host,
+    };
+    let cleaned = strip_tracking(&format!("{}://{}{}", scheme, authority, rest));
+    match cleaned.strip_suffix('#') {
+        Some(bare) => bare.to_string(),
+        None => cleaned,
+    }
+}
+
+pub(super) fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').filter(|end| *end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "amp" => Some('&'),
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "quot" => Some('"'),
+                "apos" | "#39" => Some('\''),
+                "nbsp" => Some(' '),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .map(|hex| u32::from_str_radix(hex, 16))
+                    .or_else(|| entity.strip_prefix('#').map(str::parse::<u32>))
+                    .and_then(Result::ok)
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Page title from `og:title`, falling back to `<title>`.
+pub fn html_title(html: &str) -> Option<String> {
+    let raw = OG_TITLE
+        .captures(html)
+        .or_else(|| TITLE_TAG.captures(html))
+        .map(|caps| caps[1].to_string())?;
+    let title = decode_entities(&raw).split_whitespace().collect::<Vec<_>>().join(" ");
+    (!title.is_empty()).then_some(title)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_url_list() {
+        assert!(is_url_list("https://example.com\n  http://foo.org/a?b=1 "));
+        assert!(!is_url_list("see https://example.com"));
+        assert!(!is_url_list("ftp://example.com"));
+        assert!(!is_url_list(""));
+    }
+
+    #[test]
+    fn test_strip_tracking() {
+        assert_eq!(
+            strip_tracking("https://a.com/p?id=1&utm_source=x&UTM_medium=y&fbclid=z#top"),
+            "https://a.com/p?id=1#top"
+        );
+        assert_eq!(strip_tracking("https://a.com/?utm_source=x"), "https://a.com/");
+        assert_eq!(strip_tracking("https://a.com/p?q=rust"), "https://a.com/p?q=rust");
+        assert!(has_tracking_params("https://a.com/?gclid=1"));
+        assert!(!has_tracking_params("https://a.com/#utm_source=x"));
+        assert_eq!(
+            strip_tracking("https://mp.weixin.qq.com/s?__biz=MzA&mid=1&idx=1&sn=ab&chksm=cd&scene=21#wechat_redirect"),
+            "https://mp.weixin.qq.com/s?__biz=MzA&mid=1&idx=1&sn=ab#wechat_redirect"
+        );
+        assert_eq!(strip_tracking("https://weibo.com/123/abc?from=page_1005&wm=3333"), "https://weibo.com/123/abc");
+        assert_eq!(strip_tracking("https://example.com/?from=home"), "https://example.com/?from=home");
+    }
+
+    #[test]
+    fn test_clean_url() {
+        assert_eq!(clean_url("HTTPS://Example.COM:443/Path?utm_source=x#"), "https://example.com/Path");
+        assert_eq!(clean_url("http://user:PW@Host.org:8080/a?"), "http://user:PW@host.org:8080/a");
+        assert_eq!(clean_url("https://x.com/rust/status/1?s=20&t=abc"), "https://x.com/rust/status/1");
+        assert_eq!(clean_url("https://a.com/p?q=1#top"), "https://a.com/p?q=1#top");
+    }
+
+    #[test]
+    fn test_html_title() {
+        let html = "<html><head><title>\n  Rust &amp; You &#8212; Home\n</title></head></html>";
+        assert_eq!(html_title(html).as_deref(), Some("Rust & You — Home"));
+
+        let og = r#"<title>Site</title><meta property="og:title" content="Article &quot;One&quot;">"#;
+        assert_eq!(html_title(og).as_deref(), Some(r#"Article "One""#));
+        assert_eq!(html_title("<title>  </title>"), None);
+    }
+}
@@ -1,11 +0,0 @@
-mod provider;
-mod ollama;
-mod openai;
-mod types;
-pub mod intent;
-
-pub use provider::AiProvider;
-pub use ollama::OllamaProvider;
-pub use openai::OpenAIProvider;
-pub use types::*;
-pub use intent::{detect_intent, ActionChip};
@@ -0,0 +1,222 @@
+mod routes;
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use hyper::StatusCode;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime};
+use thiserror::Error;
+use tokio::sync::broadcast;
+
+use crate::clipboard::{self, ClipboardError, TransformError};
+use crate::config::{AppConfig, ConfigError, ConfigManager, UsageMetric};
+use crate::hotkey::{self, HeadlessError};
+use crate::localhost::Running;
+use crate::regex::{self, RegexError};
+
+/// Keyring entry holding the bearer token, next to the provider API keys.
+const TOKEN_ACCOUNT: &str = "http_api";
+const EVENT_BUFFER: usize = 32;
+
+#[derive(Debug, Error)]
+pub enum ApiError {
+    #[error("invalid request: {0}")]
+    BadRequest(String),
+    #[error(transparent)]
+    Clipboard(#[from] ClipboardError),
+    #[error(transparent)]
+    Transform(#[from] TransformError),
+    #[error(transparent)]
+    Rule(#[from] RegexError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Ai(#[from] HeadlessError),
+}
+
+impl ApiError {
+    fn status(&self) -> StatusCode {
+        match self {
+            ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
+            ApiError::Rule(RegexError::RuleNotFound(_)) => StatusCode::NOT_FOUND,
+            ApiError::Rule(_) | ApiError::Transform(TransformError::Rule(_)) => StatusCode::UNPROCESSABLE_ENTITY,
+            ApiError::Clipboard(_) | ApiError::Transform(_) => StatusCode::CONFLICT,
+            ApiError::Ai(_) => StatusCode::BAD_GATEWAY,
+            ApiError::Config(_) => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// The optional localhost REST/WebSocket API. Managed as state; `configure`
+/// starts, restarts or stops it to match the settings.
+pub struct ApiServer {
+    running: Mutex<Option<Running>>,
+    token: Mutex<Option<String>>,
+    events: broadcast::Sender<Value>,
+}
+
+impl Default for ApiServer {
+    fn default() -> Self {
+        Self {
+            running: Mutex::new(None),
+            token: Mutex::new(None),
+            events: broadcast::channel(EVENT_BUFFER).0,
+        }
+    }
+}
+
+/// Apply `http_api`/`http_api_port`.
+pub fn configure<R: Runtime>(app: &AppHandle<R>, config: &AppConfig) {
+    let state = app.state::<ApiServer>();
+    let mut running = state.running.lock().unwrap_or_else(|e| e.into_inner());
+    let wanted = config.http_api.then_some(config.http_api_port);
+    if running.as_ref().map(|r| r.port) == wanted {
+        return;
+    }
+
+    if let Some(old) = running.take() {
+        log::info!("HTTP API on port {} stopped", old.port);
+        old.stop();
+    }
+    if let Some(port) = wanted {
+        match routes::start(app.clone(), port) {
+            Ok(server) => {
+                log::info!("HTTP API listening on http://127.0.0.1:{}/v1", port);
+                *running = Some(server);
+            }
+            Err(e) => log::error!("Failed to start HTTP API on port {}: {}", port, e),
+        }
+    }
+}
+
+/// Tell WebSocket clients about new clipboard text.
+pub fn clipboard_changed<R: Runtime>(app: &AppHandle<R>, text: &str) {
+    let state = app.state::<ApiServer>();
+    // Fails only when nobody is listening
+    let _ = state.events.send(json!({ "event": "clipboard", "text": text }));
+}
+
+/// The bearer token clients must send, created on first use.
+pub fn token<R: Runtime>(app: &AppHandle<R>) -> Result<String, ConfigError> {
+    let state = app.state::<ApiServer>();
+    let mut token = state.token.lock().unwrap_or_else(|e| e.into_inner());
+    if let Some(token) = token.as_ref() {
+        return Ok(token.clone());
+    }
+    let config = app.state::<ConfigManager>();
+    let stored = match config.get_api_key(TOKEN_ACCOUNT)? {
+        Some(stored) => stored,
+        None => {
+            let created = new_token();
+            config.set_api_key(TOKEN_ACCOUNT, &created)?;
+            created
+        }
+    };
+    *token = Some(stored.clone());
+    Ok(stored)
+}
+
+/// Replace the token; clients using the old one are rejected from now on.
+pub fn regenerate_token<R: Runtime>(app: &AppHandle<R>) -> Result<String, ConfigError> {
+    let created = new_token();
+    app.state::<ConfigManager>().set_api_key(TOKEN_ACCOUNT, &created)?;
+    *app.state::<ApiServer>().token.lock().unwrap_or_else(|e| e.into_inner()) = Some(created.clone());
+    Ok(created)
+}
+
+pub(crate) fn new_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+#[derive(Deserialize)]
+struct WriteParams {
+    text: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TransformParams {
+    rule_ids: Vec<String>,
+    text: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct AiParams {
+    prompt: String,
+    text: Option<String>,
+}
+
+fn params<T: serde::de::DeserializeOwned>(params: Value) -> Result<T, ApiError> {
+    serde_json::from_value(params).map_err(|e| ApiError::BadRequest(e.to_string()))
+}
+
+/// Run one API method; REST routes and WebSocket requests both end here.
+/// `Value::Null` means there is nothing to return.
+pub(crate) async fn call<R: Runtime>(app: &AppHandle<R>, method: &str, args: Value) -> Result<Value, ApiError> {
+    match method {
+        "clipboard.read" => {
+            let text = blocking(app, |app| clipboard::read_clipboard(&app)).await??.text;
+            Ok(json!({ "text": text.ok_or(ClipboardError::Empty)? }))
+        }
+        "clipboard.write" => {
+            let WriteParams { text } = params(args)?;
+            blocking(app, move |app| clipboard::write_clipboard(&app, &text)).await??;
+            Ok(Value::Null)
+        }
+        "rules" => {
+            let mut rules = regex::get_builtin_rules();
+            rules.extend(app.state::<ConfigManager>().get_custom_rules()?);
+            Ok(json!(rules))
+        }
+        "transform" => {
+            let TransformParams { rule_ids, text } = params(args)?;
+            if rule_ids.is_empty() {
+                return Err(ApiError::BadRequest("ruleIds is empty".to_string()));
+            }
+            match text {
+                Some(text) => {
+                    let config = app.state::<ConfigManager>();
+                    let result = regex::apply_pipeline(&text, &rule_ids, &config.get_custom_rules()?)?;
+                    config.track(UsageMetric::Transform, 1);
+                    Ok(json!({ "text": result }))
+                }
+                None => {
+                    let summary = blocking(app, move |app| clipboard::transform_clipboard(&app, &rule_ids)).await??;
+                    Ok(json!(summary))
+                }
+            }
+        }
+        "ai" => {
+            let AiParams { prompt, text } = params(args)?;
+            if prompt.trim().is_empty() {
+                return Err(ApiError::BadRequest("prompt is empty".to_string()));
+            }
+            let text = match text {
+                Some(text) => text,
+                None => blocking(app, |app| clipboard::read_clipboard(&app))
+                    .await??
+                    .text
+                    .ok_or(ClipboardError::Empty)?,
+            };
+            Ok(json!({ "text": hotkey::run_prompt(app, &prompt, &text).await? }))
+        }
+        other => Err(ApiError::BadRequest(format!("unknown method '{}'", other))),
+    }
+}
+
+/// Clipboard access blocks, so it runs off the async workers.
+async fn blocking<R: Runtime, T: Send + 'static>(
+    app: &AppHandle<R>,
+    f: impl FnOnce(AppHandle<R>) -> T + Send + 'static,
+) -> Result<T, ClipboardError> {
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || f(app))
+        .await
+        .map_err(|e| ClipboardError::Unavailable(e.to_string()))
+}
@@ -0,0 +1,207 @@
+use futures::{SinkExt, StreamExt};
+use hyper::body::Incoming;
+use hyper::header::{HeaderValue, AUTHORIZATION, CONNECTION, SEC_WEBSOCKET_ACCEPT, SEC_WEBSOCKET_KEY, UPGRADE};
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Runtime};
+use tokio::sync::{broadcast, mpsc};
+use tokio_tungstenite::tungstenite::handshake::derive_accept_key;
+use tokio_tungstenite::tungstenite::protocol::Role;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+
+use super::{call, ApiServer};
+use crate::localhost::{self, Body, Running};
+
+const WEBSOCKET_PATH: &str = "/v1/ws";
+
+/// Serve the API on `127.0.0.1:port`.
+pub fn start<R: Runtime>(app: AppHandle<R>, port: u16) -> std::io::Result<Running> {
+    localhost::serve("HTTP API", port, move |req| handle(app.clone(), req))
+}
+
+/// The API method behind a REST route.
+fn route(method: &Method, path: &str) -> Option<&'static str> {
+    match (method, path.trim_end_matches('/')) {
+        (&Method::GET, "/v1/clipboard") => Some("clipboard.read"),
+        (&Method::PUT, "/v1/clipboard") => Some("clipboard.write"),
+        (&Method::GET, "/v1/rules") => Some("rules"),
+        (&Method::POST, "/v1/transform") => Some("transform"),
+        (&Method::POST, "/v1/ai") => Some("ai"),
+        _ => None,
+    }
+}
+
+/// Whether the request carries `token`, as a bearer token or, for browser
+/// WebSocket clients that can't set headers, a `token` query parameter.
+fn authorized(authorization: Option<&str>, query: Option<&str>, token: &str) -> bool {
+    let bearer = authorization.and_then(|h| h.strip_prefix("Bearer ")).map(str::trim);
+    let from_query = query
+        .into_iter()
+        .flat_map(|q| q.split('&'))
+        .find_map(|pair| pair.strip_prefix("token="));
+    bearer
+        .into_iter()
+        .chain(from_query)
+        .any(|given| localhost::constant_time_eq(given.as_bytes(), token.as_bytes()))
+}
+
+async fn handle<R: Runtime>(app: AppHandle<R>, req: Request<Incoming>) -> Response<Body> {
+    let token = match super::token(&app) {
+        Ok(token) => token,
+        Err(e) => {
+            log::error!("HTTP API token unavailable: {}", e);
+            return localhost::status(StatusCode::SERVICE_UNAVAILABLE);
+        }
+    };
+    let authorization = req.headers().get(AUTHORIZATION).and_then(|h| h.to_str().ok());
+    if !authorized(authorization, req.uri().query(), &token) {
+        return localhost::json(StatusCode::UNAUTHORIZED, &json!({ "error": "missing or wrong token" }));
+    }
+
+    if req.uri().path() == WEBSOCKET_PATH {
+        return upgrade(app, req);
+    }
+    let Some(method) = route(req.method(), req.uri().path()) else {
+        return localhost::json(StatusCode::NOT_FOUND, &json!({ "error": "no such endpoint" }));
+    };
+
+    let args = if req.method() == Method::GET {
+        Value::Null
+    } else {
+        let body = match localhost::read_body(req).await {
+            Ok(body) => body,
+            Err(response) => return response,
+        };
+        match serde_json::from_str(&body) {
+            Ok(args) => args,
+            Err(e) => return localhost::json(StatusCode::BAD_REQUEST, &json!({ "error": e.to_string() })),
+        }
+    };
+
+    match call(&app, method, args).await {
+        Ok(Value::Null) => localhost::status(StatusCode::NO_CONTENT),
+        Ok(result) => localhost::json(StatusCode::OK, &result),
+        Err(e) => localhost::json(e.status(), &json!({ "error": e.to_string() })),
+    }
+}
+
+/// Accept a WebSocket handshake and hand the connection to `run_socket`.
+fn upgrade<R: Runtime>(app: AppHandle<R>, mut req: Request<Incoming>) -> Response<Body> {
+    let Some(key) = req.headers().get(SEC_WEBSOCKET_KEY) else {
+        return localhost::json(StatusCode::BAD_REQUEST, &json!({ "error": "expected a WebSocket handshake" }));
+    };
+    let accept = derive_accept_key(key.as_bytes());
+    let upgrading = hyper::upgrade::on(&mut req);
+
+    tauri::async_runtime::spawn(async move {
+        match upgrading.await {
+            Ok(upgraded) => {
+                let socket = WebSocketStream::from_raw_socket(TokioIo::new(upgraded), Role::Server, None).await;
+                run_socket(app, socket).await;
+            }
+            Err(e) => log::warn!("WebSocket upgrade failed: {}", e),
+        }
+    });
+
+    let mut response = localhost::status(StatusCode::SWITCHING_PROTOCOLS);
+    let headers = response.headers_mut();
+    headers.insert(UPGRADE, HeaderValue::from_static("websocket"));
+    headers.insert(CONNECTION, HeaderValue::from_static("Upgrade"));
+    if let Ok(accept) = HeaderValue::from_str(&accept) {
+        headers.insert(SEC_WEBSOCKET_ACCEPT, accept);
+    }
+    response
+}
+
+/// A WebSocket request: `{"id": 1, "method": "transform", "params": {...}}`,
+/// answered with `{"id": 1, "result": ...}` or `{"id": 1, "error": "..."}`.
+#[derive(Deserialize)]
+struct SocketRequest {
+    #[serde(default)]
+    id: Value,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+/// Push clipboard events and answer requests until the client disconnects.
+/// Requests run concurrently, so a slow AI call doesn't hold up events.
+async fn run_socket<R: Runtime, S>(app: AppHandle<R>, socket: WebSocketStream<S>)
+where
+    S: tokio::io::AsyncRead + tokio::io::AsyncWrite + Unpin,
+{
+    let (mut outgoing, mut incoming) = socket.split();
+    let mut events = app.state::<ApiServer>().events.subscribe();
+    let (replies, mut pending) = mpsc::unbounded_channel::<Value>();
+
+    loop {
+        let message = tokio::select! {
+            event = events.recv() => match event {
+                Ok(event) => event,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::debug!("WebSocket client missed {} events", skipped);
+                    continue;
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            },
+            Some(reply) = pending.recv() => reply,
+            received = incoming.next() => match received {
+                Some(Ok(Message::Text(text))) => {
+                    let request: SocketRequest = match serde_json::from_str(&text) {
+                        Ok(request) => request,
+                        Err(e) => {
+                            let _ = replies.send(json!({ "id": Value::Null, "error": e.to_string() }));
+                            continue;
+                        }
+                    };
+                    let app = app.clone();
+                    let replies = replies.clone();
+                    tauri::async_runtime::spawn(async move {
+                        let reply = match call(&app, &request.method, request.params).await {
+                            Ok(result) => json!({ "id": request.id, "result": result }),
+                            Err(e) => json!({ "id": request.id, "error": e.to_string() }),
+                        };
+                        let _ = replies.send(reply);
+                    });
+                    continue;
+                }
+                Some(Ok(Message::Close(_))) | None => break,
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => {
+                    log::debug!("WebSocket closed: {}", e);
+                    break;
+                }
+            },
+        };
+        if outgoing.send(Message::Text(message.to_string())).await.is_err() {
+            break;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route() {
+        assert_eq!(route(&Method::GET, "/v1/clipboard"), Some("clipboard.read"));
+        assert_eq!(route(&Method::PUT, "/v1/clipboard/"), Some("clipboard.write"));
+        assert_eq!(route(&Method::POST, "/v1/transform"), Some("transform"));
+        assert_eq!(route(&Method::GET, "/v1/transform"), None);
+        assert_eq!(route(&Method::GET, "/v2/rules"), None);
+    }
+
+    #[test]
+    fn test_authorized() {
+        assert!(authorized(Some("Bearer s3cret"), None, "s3cret"));
+        assert!(authorized(None, Some("a=1&token=s3cret"), "s3cret"));
+        assert!(!authorized(Some("Bearer wrong"), None, "s3cret"));
+        assert!(!authorized(Some("s3cret"), None, "s3cret"));
+        assert!(!authorized(None, Some("token="), "s3cret"));
+        assert!(!authorized(None, None, "s3cret"));
+    }
+}
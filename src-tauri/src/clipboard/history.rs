@@ -0,0 +1,156 @@
+use serde::Serialize;
+use std::sync::Mutex;
+
+const MAX_HISTORY: usize = 50;
+
+/// One transform applied to the clipboard text.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformStep {
+    pub input: String,
+    pub output: String,
+    /// Rule id(s) or AI action that produced the output
+    pub label: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HistorySnapshot {
+    pub undo: Vec<String>,
+    pub redo: Vec<String>,
+}
+
+#[derive(Default)]
+struct Stacks {
+    undo: Vec<TransformStep>,
+    redo: Vec<TransformStep>,
+}
+
+/// Session-scoped undo/redo stack of clipboard transforms. Not persisted.
+#[derive(Default)]
+pub struct TransformHistory {
+    stacks: Mutex<Stacks>,
+}
+
+impl TransformHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a new step; clears the redo stack like any editor would.
+    pub fn push(&self, input: String, output: String, label: String) {
+        if input == output {
+            return;
+        }
+        let mut stacks = self.stacks.lock().unwrap();
+        stacks.redo.clear();
+        stacks.undo.push(TransformStep { input, output, label });
+        if stacks.undo.len() > MAX_HISTORY {
+            stacks.undo.remove(0);
+        }
+    }
+
+    /// Pop the latest step onto the redo stack and return it; its `input` is
+    /// the text to restore.
+    pub fn undo(&self) -> Option<TransformStep> {
+        let mut stacks = self.stacks.lock().unwrap();
+        let step = stacks.undo.pop()?;
+        stacks.redo.push(step.clone());
+        Some(step)
+    }
+
+    /// Re-apply the last undone step; its `output` is the text to restore.
+    pub fn redo(&self) -> Option<TransformStep> {
+        let mut stacks = self.stacks.lock().unwrap();
+        let step = stacks.redo.pop()?;
+        stacks.undo.push(step.clone());
+        Some(step)
+    }
+
+    /// Put a step back where it came from after the clipboard write failed.
+    pub fn revert_undo(&self) {
+        let mut stacks = self.stacks.lock().unwrap();
+        if let Some(step) = stacks.redo.pop() {
+            stacks.undo.push(step);
+        }
+    }
+
+    pub fn revert_redo(&self) {
+        let mut stacks = self.stacks.lock().unwrap();
+        if let Some(step) = stacks.undo.pop() {
+            stacks.redo.push(step);
+        }
+    }
+
+    /// Labels only, most recent last.
+    pub fn snapshot(&self) -> HistorySnapshot {
+        let stacks = self.stacks.lock().unwrap();
+        HistorySnapshot {
+            undo: stacks.undo.iter().map(|s| s.label.clone()).collect(),
+            redo: stacks.redo.iter().map(|s| s.label.clone()).collect(),
+        }
+    }
+
+    pub fn clear(&self) {
+        let mut stacks = self.stacks.lock().unwrap();
+        stacks.undo.clear();
+        stacks.redo.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn push(history: &TransformHistory, input: &str, output: &str) {
+        history.push(input.to_string(), output.to_string(), format!("{}->{}", input, output));
+    }
+
+    #[test]
+    fn test_undo_redo_chain() {
+        let history = TransformHistory::new();
+        push(&history, "a", "b");
+        push(&history, "b", "c");
+        push(&history, "c", "d");
+
+        assert_eq!(history.undo().unwrap().input, "c");
+        assert_eq!(history.undo().unwrap().input, "b");
+        assert_eq!(history.redo().unwrap().output, "c");
+        assert_eq!(history.undo().unwrap().input, "b");
+        assert_eq!(history.undo().unwrap().input, "a");
+        assert!(history.undo().is_none());
+    }
+
+    #[test]
+    fn test_push_clears_redo() {
+        let history = TransformHistory::new();
+        push(&history, "a", "b");
+        history.undo();
+        push(&history, "a", "x");
+        assert!(history.redo().is_none());
+        assert_eq!(history.snapshot().undo, vec!["a->x"]);
+    }
+
+    #[test]
+    fn test_noop_and_capacity() {
+        let history = TransformHistory::new();
+        push(&history, "same", "same");
+        assert!(history.undo().is_none());
+
+        for i in 0..MAX_HISTORY + 5 {
+            push(&history, &i.to_string(), &(i + 1).to_string());
+        }
+        assert_eq!(history.snapshot().undo.len(), MAX_HISTORY);
+        assert_eq!(history.snapshot().undo[0], "5->6");
+    }
+
+    #[test]
+    fn test_revert_after_failed_write() {
+        let history = TransformHistory::new();
+        push(&history, "a", "b");
+        history.undo();
+        history.revert_undo();
+        assert_eq!(history.snapshot().undo.len(), 1);
+        assert!(history.snapshot().redo.is_empty());
+    }
+}
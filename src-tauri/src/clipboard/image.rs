@@ -0,0 +1,43 @@
+use std::io;
+use std::path::Path;
+use tauri::image::Image;
+use tauri::{AppHandle, Runtime};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use super::ClipboardError;
+
+/// Encode 8-bit RGBA pixels as a PNG file.
+pub fn encode_png(width: u32, height: u32, rgba: &[u8]) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    let mut encoder = png::Encoder::new(&mut bytes, width, height);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.write_header()?.write_image_data(rgba)?;
+    Ok(bytes)
+}
+
+/// Write the image on the clipboard to `path` as a PNG.
+pub fn save_clipboard_image<R: Runtime>(
+    app: &AppHandle<R>,
+    path: &Path,
+) -> Result<(), ClipboardError> {
+    let image = app
+        .clipboard()
+        .read_image()
+        .map_err(|_| ClipboardError::NoImage)?;
+    encode_png(image.width(), image.height(), image.rgba())
+        .and_then(|png| std::fs::write(path, png))
+        .map_err(|e| ClipboardError::Unavailable(format!("cannot save image: {}", e)))
+}
+
+/// Replace the clipboard with an RGBA image.
+pub fn write_clipboard_image<R: Runtime>(
+    app: &AppHandle<R>,
+    width: u32,
+    height: u32,
+    rgba: Vec<u8>,
+) -> Result<(), ClipboardError> {
+    app.clipboard()
+        .write_image(&Image::new_owned(rgba, width, height))
+        .map_err(|e| ClipboardError::Unavailable(e.to_string()))
+}
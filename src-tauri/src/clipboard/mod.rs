@@ -1,5 +1,17 @@
+mod history;
+mod image;
+mod paste;
+mod recent;
+mod transform;
+
+pub use history::{HistorySnapshot, TransformHistory};
+pub use image::{encode_png, save_clipboard_image, write_clipboard_image};
+pub use paste::{capture_selection, simulate_paste};
+pub use recent::{preview, spawn_watcher, RecentClipboard};
+pub use transform::{transform_clipboard, TransformError, TransformSummary};
+
 use serde::Serialize;
-use tauri::AppHandle;
+use tauri::{AppHandle, Runtime};
 use tauri_plugin_clipboard_manager::ClipboardExt;
 use thiserror::Error;
 
@@ -33,11 +45,15 @@ pub enum ClipboardError {
     Unavailable(String),
     #[error("clipboard is empty")]
     Empty,
+    #[error("clipboard holds no image")]
+    NoImage,
     #[error("unsupported clipboard content")]
     Unsupported,
+    #[error("failed to simulate key input: {0}")]
+    PasteFailed(String),
 }
 
-pub fn read_clipboard(app: &AppHandle) -> Result<ClipboardContent, ClipboardError> {
+pub fn read_clipboard<R: Runtime>(app: &AppHandle<R>) -> Result<ClipboardContent, ClipboardError> {
     let clipboard = app.clipboard();
     let mut last_err: Option<String> = None;
 
@@ -85,7 +101,7 @@ pub fn read_clipboard(app: &AppHandle) -> Result<ClipboardContent, ClipboardErro
     }
 }
 
-pub fn write_clipboard(app: &AppHandle, text: &str) -> Result<(), ClipboardError> {
+pub fn write_clipboard<R: Runtime>(app: &AppHandle<R>, text: &str) -> Result<(), ClipboardError> {
     if text.is_empty() {
         return Err(ClipboardError::Empty);
     }
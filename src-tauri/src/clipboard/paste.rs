@@ -0,0 +1,58 @@
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use std::thread;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Runtime};
+
+use super::{read_clipboard, ClipboardError};
+
+// Give the user time to release the hotkey's modifiers, or they would
+// combine with the simulated shortcut (Ctrl+Shift+V instead of Ctrl+V)
+const MODIFIER_RELEASE_DELAY: Duration = Duration::from_millis(150);
+
+/// How long to wait for the focused app to answer a simulated copy.
+const COPY_TIMEOUT: Duration = Duration::from_millis(600);
+const COPY_POLL_INTERVAL: Duration = Duration::from_millis(30);
+
+#[cfg(target_os = "macos")]
+const SHORTCUT_MODIFIER: Key = Key::Meta;
+#[cfg(not(target_os = "macos"))]
+const SHORTCUT_MODIFIER: Key = Key::Control;
+
+/// Send the platform paste shortcut to the focused application. Blocking.
+pub fn simulate_paste() -> Result<(), ClipboardError> {
+    send_shortcut('v')
+}
+
+/// Copy the focused application's selection and return it. Falls back to
+/// the current clipboard text when the selection is empty or equal to it.
+/// Blocking.
+pub fn capture_selection<R: Runtime>(app: &AppHandle<R>) -> Result<String, ClipboardError> {
+    let before = read_clipboard(app).ok().and_then(|c| c.text);
+    send_shortcut('c')?;
+
+    let deadline = Instant::now() + COPY_TIMEOUT;
+    while Instant::now() < deadline {
+        thread::sleep(COPY_POLL_INTERVAL);
+        if let Some(text) = read_clipboard(app).ok().and_then(|c| c.text) {
+            if Some(&text) != before.as_ref() {
+                return Ok(text);
+            }
+        }
+    }
+
+    before.ok_or(ClipboardError::Empty)
+}
+
+fn send_shortcut(key: char) -> Result<(), ClipboardError> {
+    thread::sleep(MODIFIER_RELEASE_DELAY);
+
+    let err = |e: enigo::InputError| ClipboardError::PasteFailed(e.to_string());
+    let mut enigo =
+        Enigo::new(&Settings::default()).map_err(|e| ClipboardError::PasteFailed(e.to_string()))?;
+
+    enigo.key(SHORTCUT_MODIFIER, Direction::Press).map_err(err)?;
+    let result = enigo.key(Key::Unicode(key), Direction::Click).map_err(err);
+    // Always release the modifier, even if the click failed
+    enigo.key(SHORTCUT_MODIFIER, Direction::Release).map_err(err)?;
+    result
+}
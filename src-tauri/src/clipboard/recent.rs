@@ -0,0 +1,218 @@
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::config::ConfigManager;
+use crate::regex;
+use crate::text;
+
+const MAX_RECENT: usize = 20;
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const PREVIEW_CHARS: usize = 80;
+
+/// Payload of the `clipboard:cycled` event, for the on-screen toast.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CycleResult {
+    pub preview: String,
+    pub index: usize,
+    pub total: usize,
+}
+
+#[derive(Default)]
+struct Ring {
+    // Most recent first
+    items: VecDeque<String>,
+    cursor: usize,
+}
+
+/// Session-scoped list of recent clipboard texts, kept by polling.
+#[derive(Default)]
+pub struct RecentClipboard {
+    ring: Mutex<Ring>,
+    // Set from the tray; copies made meanwhile are never recorded
+    paused: AtomicBool,
+}
+
+impl RecentClipboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record newly observed clipboard text. Seeing the entry the cursor is
+    /// on (e.g. one we just cycled to) is not a change.
+    pub fn observe(&self, text: &str) {
+        if text.trim().is_empty() {
+            return;
+        }
+        let mut ring = self.ring.lock().unwrap();
+        if ring.items.get(ring.cursor).is_some_and(|t| t == text) {
+            return;
+        }
+
+        ring.items.retain(|t| t != text);
+        ring.items.push_front(text.to_string());
+        ring.items.truncate(MAX_RECENT);
+        ring.cursor = 0;
+    }
+
+    /// Move the cursor by `step` (wrapping) and return the entry to put on
+    /// the clipboard. `None` with fewer than two entries.
+    pub fn cycle(&self, step: isize) -> Option<(String, CycleResult)> {
+        let mut ring = self.ring.lock().unwrap();
+        let total = ring.items.len();
+        if total < 2 {
+            return None;
+        }
+
+        ring.cursor = (ring.cursor as isize + step).rem_euclid(total as isize) as usize;
+        let text = ring.items[ring.cursor].clone();
+        let result = CycleResult {
+            preview: preview(&text, PREVIEW_CHARS),
+            index: ring.cursor,
+            total,
+        };
+        Some((text, result))
+    }
+
+    /// Forget `text`, e.g. once it has been cleared from the clipboard.
+    pub fn remove(&self, text: &str) {
+        let mut ring = self.ring.lock().unwrap();
+        ring.items.retain(|t| t != text);
+        ring.cursor = 0;
+    }
+
+    pub fn clear(&self) {
+        let mut ring = self.ring.lock().unwrap();
+        ring.items.clear();
+        ring.cursor = 0;
+    }
+
+    pub fn items(&self) -> Vec<String> {
+        self.ring.lock().unwrap().items.iter().cloned().collect()
+    }
+
+    pub fn set_paused(&self, paused: bool) {
+        self.paused.store(paused, Ordering::Relaxed);
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused.load(Ordering::Relaxed)
+    }
+}
+
+/// The first `max_chars` characters of `text`, with `…` when cut.
+pub fn preview(text: &str, max_chars: usize) -> String {
+    let mut preview: String = text.chars().take(max_chars).collect();
+    if preview.len() < text.len() {
+        preview.push('…');
+    }
+    preview
+}
+
+/// Poll the clipboard for text changes on a background thread.
+pub fn spawn_watcher<R: Runtime>(app: AppHandle<R>) {
+    let spawned = std::thread::Builder::new()
+        .name("clipboard-watcher".to_string())
+        .spawn(move || {
+            let mut last = String::new();
+            loop {
+                if let Ok(mut text) = app.clipboard().read_text() {
+                    let recent = app.state::<RecentClipboard>();
+                    if text != last && !recent.is_paused() {
+                        if let Some(cleaned) = clean_copied_urls(&app, &text) {
+                            text = cleaned;
+                        }
+                        recent.observe(&text);
+                        crate::tray::refresh(&app);
+                        crate::api::clipboard_changed(&app, &text);
+                    }
+                    last = text;
+                }
+                std::thread::sleep(POLL_INTERVAL);
+            }
+        });
+
+    if let Err(e) = spawned {
+        log::error!("Failed to start clipboard watcher: {}", e);
+    }
+}
+
+/// With `clean_copied_urls` set and only links copied, put them back on
+/// the clipboard without tracking parameters and return them.
+fn clean_copied_urls<R: Runtime>(app: &AppHandle<R>, copied: &str) -> Option<String> {
+    let enabled = app
+        .try_state::<ConfigManager>()
+        .and_then(|config| config.effective_config().ok())
+        .is_some_and(|c| c.clean_copied_urls);
+    if !enabled || !text::is_url_list(copied) {
+        return None;
+    }
+    let cleaned = regex::apply_rule(copied, "clean_url").ok().filter(|c| c != copied)?;
+    if let Err(e) = app.clipboard().write_text(cleaned.clone()) {
+        log::warn!("Failed to write cleaned link to the clipboard: {}", e);
+        return None;
+    }
+    Some(cleaned)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cycle_wraps_both_ways() {
+        let recent = RecentClipboard::new();
+        recent.observe("a");
+        recent.observe("b");
+        recent.observe("c");
+        assert_eq!(recent.items(), vec!["c", "b", "a"]);
+
+        assert_eq!(recent.cycle(1).unwrap().0, "b");
+        assert_eq!(recent.cycle(1).unwrap().0, "a");
+        assert_eq!(recent.cycle(1).unwrap().0, "c");
+        assert_eq!(recent.cycle(-1).unwrap().0, "a");
+    }
+
+    #[test]
+    fn test_observing_cycled_entry_keeps_position() {
+        let recent = RecentClipboard::new();
+        recent.observe("a");
+        recent.observe("b");
+        let (text, result) = recent.cycle(1).unwrap();
+        assert_eq!(text, "a");
+        assert_eq!(result.index, 1);
+
+        // The watcher sees our own write; that is not a new copy
+        recent.observe(&text);
+        assert_eq!(recent.items(), vec!["b", "a"]);
+        assert_eq!(recent.cycle(1).unwrap().0, "b");
+
+        // A fresh copy moves to the front and resets the cursor
+        recent.observe("c");
+        assert_eq!(recent.items(), vec!["c", "b", "a"]);
+        assert_eq!(recent.cycle(1).unwrap().0, "b");
+    }
+
+    #[test]
+    fn test_cycle_needs_two_entries() {
+        let recent = RecentClipboard::new();
+        recent.observe("  ");
+        recent.observe("only");
+        assert!(recent.cycle(1).is_none());
+    }
+
+    #[test]
+    fn test_preview_is_truncated() {
+        let recent = RecentClipboard::new();
+        recent.observe(&"x".repeat(200));
+        recent.observe("short");
+        let (_, result) = recent.cycle(1).unwrap();
+        assert_eq!(result.preview.chars().count(), PREVIEW_CHARS + 1);
+        assert_eq!(result.total, 2);
+    }
+}
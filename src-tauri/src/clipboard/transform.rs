@@ -0,0 +1,69 @@
+use serde::Serialize;
+use tauri::{AppHandle, Manager, Runtime};
+use thiserror::Error;
+
+use super::{read_clipboard, write_clipboard, ClipboardError, TransformHistory};
+use crate::config::{ConfigError, ConfigManager, UsageMetric, UsageTarget};
+use crate::regex::{self, RegexError};
+
+#[derive(Debug, Error)]
+pub enum TransformError {
+    #[error(transparent)]
+    Clipboard(#[from] ClipboardError),
+    #[error(transparent)]
+    Rule(#[from] RegexError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("clipboard has no text")]
+    NoText,
+    #[error("no rules given")]
+    NoRules,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransformSummary {
+    pub rule_ids: Vec<String>,
+    pub input_chars: usize,
+    pub output_chars: usize,
+    pub changed: bool,
+}
+
+/// Read the clipboard text, run it through `rule_ids` (builtin or custom) and
+/// write the result back. Unchanged text is not rewritten. Blocking.
+pub fn transform_clipboard<R: Runtime>(
+    app: &AppHandle<R>,
+    rule_ids: &[String],
+) -> Result<TransformSummary, TransformError> {
+    if rule_ids.is_empty() {
+        return Err(TransformError::NoRules);
+    }
+
+    let config = app.state::<ConfigManager>();
+    let text = read_clipboard(app)?.text.ok_or(TransformError::NoText)?;
+    let custom_rules = config.get_custom_rules()?;
+    let result = regex::apply_pipeline(&text, rule_ids, &custom_rules)?;
+    config.track(UsageMetric::Transform, 1);
+
+    let summary = TransformSummary {
+        rule_ids: rule_ids.to_vec(),
+        input_chars: text.chars().count(),
+        output_chars: result.chars().count(),
+        changed: result != text,
+    };
+
+    if summary.changed {
+        write_clipboard(app, &result)?;
+        app.state::<TransformHistory>().push(text, result, rule_ids.join(", "));
+    }
+
+    for id in rule_ids {
+        // Parameterized ids (`prefix_lines:> `) count toward the base rule
+        let id = id.split(':').next().unwrap_or(id);
+        if let Err(e) = config.record_usage(UsageTarget::Rule, id) {
+            log::warn!("Failed to record usage for rule '{}': {}", id, e);
+        }
+    }
+
+    Ok(summary)
+}
@@ -1,19 +1,139 @@
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Emitter, State};
+use tauri::{AppHandle, Emitter, Manager, State};
 use tokio::sync::{mpsc, RwLock};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::collections::HashMap;
 
 use crate::ai::{
     AIConfig, AIError, AIProviderType, AiProvider, ChatMessage, ModelInfo,
-    OllamaProvider, OpenAIProvider, StreamChunk, ActionChip, detect_intent,
+    OllamaProvider, OpenAIProvider, StreamChunk, build_client, HttpSettings, ActionChip, CustomIntent, IntentResult, detect_intent,
 };
-use crate::privacy::{self};
+use crate::ai::{self, classify};
+use crate::ai::chunk::{self, ChunkMode, DEFAULT_CONTEXT_TOKENS};
+use crate::ai::intent::{self, ActionType, ContentType};
+use crate::config::{AppConfig, ConfigManager, UsageMetric, UsageTarget};
+use crate::regex;
+use crate::text;
+use crate::privacy::{self, MaskMapping, PIIScanResult, ShieldAction, ShieldDecision, ShieldPolicy};
+
+/// Merge rounds before the remaining partial answers are merged at once.
+const MAX_MERGE_ROUNDS: usize = 3;
+/// Longest wait between streamed chunks when the provider sets no timeout
+const STREAM_IDLE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(30);
 
 pub struct AIState {
     ollama: OllamaProvider,
     openai: OpenAIProvider,
     active_requests: RwLock<HashMap<String, tokio::sync::oneshot::Sender<()>>>,
+    // Read from hotkey handlers, which are not async
+    last_result: Mutex<Option<String>>,
+    // Same proxy/TLS settings as the providers, for non-AI fetches
+    http_client: Mutex<reqwest::Client>,
+    // Last model list Ollama returned, kept for when it is unreachable
+    local_models: Mutex<Vec<ModelInfo>>,
+}
+
+impl AIState {
+    /// Use `client` (built from the proxy/TLS settings) for all providers.
+    pub fn set_http_client(&self, client: reqwest::Client) {
+        self.ollama.set_client(client.clone());
+        self.openai.set_client(client.clone());
+        *self.http_client.lock().unwrap() = client;
+    }
+
+    pub fn http_client(&self) -> reqwest::Client {
+        self.http_client.lock().unwrap().clone()
+    }
+
+    /// Most recent completed AI response in this session.
+    pub fn last_result(&self) -> Option<String> {
+        self.last_result.lock().unwrap().clone()
+    }
+
+    /// Ask Ollama at `base_url` for its models and remember them.
+    pub async fn refresh_local_models(&self, base_url: &str) -> Result<Vec<ModelInfo>, AIError> {
+        let config = AIConfig {
+            base_url: base_url.to_string(),
+            ..Default::default()
+        };
+        let models = self.ollama.list_models(&config).await?;
+        *self.local_models.lock().unwrap() = models.clone();
+        Ok(models)
+    }
+
+    /// Answer `prompt` about `text` even when the text is too long for one
+    /// request: it is split into chunks of `budget` tokens, the prompt is run
+    /// on each and the answers are merged or joined. `progress(done, total)`
+    /// is called before each request.
+    pub async fn complete_in_chunks(
+        &self,
+        prompt: &str,
+        text: &str,
+        config: &AIConfig,
+        budget: usize,
+        progress: impl Fn(usize, usize),
+    ) -> Result<ChunkedAnswer, AIError> {
+        let mut answer = ChunkedAnswer::default();
+        let chunks = text::split_chunks(text, budget);
+        if chunks.len() <= 1 {
+            answer.content = self.ask(chunk::with_content(prompt, text), config, &mut answer).await?;
+        } else {
+            let mode = ChunkMode::for_prompt(prompt);
+            // The final merge is counted up front; extra merge rounds add theirs
+            let mut total = chunks.len() + usize::from(mode == ChunkMode::Merge);
+            let mut done = 0;
+            let mut parts = Vec::with_capacity(chunks.len());
+            for (i, part) in chunks.iter().enumerate() {
+                progress(done, total);
+                let message = chunk::part_prompt(prompt, part, i, chunks.len(), mode);
+                parts.push(self.ask(message, config, &mut answer).await?);
+                done += 1;
+            }
+            if mode == ChunkMode::Merge {
+                let mut round = 1;
+                while parts.len() > 1 {
+                    let groups = if round < MAX_MERGE_ROUNDS {
+                        chunk::group_parts(parts, budget)
+                    } else {
+                        vec![parts]
+                    };
+                    if groups.len() > 1 {
+                        total += groups.len();
+                    }
+                    parts = Vec::with_capacity(groups.len());
+                    for group in groups {
+                        progress(done, total);
+                        parts.push(self.ask(chunk::merge_prompt(prompt, &group), config, &mut answer).await?);
+                        done += 1;
+                    }
+                    round += 1;
+                }
+                answer.content = parts.pop().unwrap_or_default();
+            } else {
+                answer.content = chunk::join_parts(&parts);
+            }
+        }
+        *self.last_result.lock().unwrap() = Some(answer.content.clone());
+        Ok(answer)
+    }
+
+    /// One request of a chunked answer, counted into its token usage.
+    async fn ask(&self, message: String, config: &AIConfig, answer: &mut ChunkedAnswer) -> Result<String, AIError> {
+        answer.prompt_tokens += text::text_stats(&message).estimated_tokens;
+        let reply = self.collect(vec![ChatMessage::user(message)], config).await?;
+        answer.completion_tokens += text::text_stats(&reply).estimated_tokens;
+        Ok(reply)
+    }
+
+    /// Run a request to completion without recording the response as the
+    /// last result.
+    async fn collect(&self, messages: Vec<ChatMessage>, config: &AIConfig) -> Result<String, AIError> {
+        let provider: &dyn AiProvider = match config.provider {
+            AIProviderType::Ollama => &self.ollama,
+            AIProviderType::OpenAI => &self.openai,
+        };
+        ai::complete(provider, messages, config).await
+    }
 }
 
 impl Default for AIState {
@@ -22,10 +142,24 @@ impl Default for AIState {
             ollama: OllamaProvider::new(),
             openai: OpenAIProvider::new(),
             active_requests: RwLock::new(HashMap::new()),
+            last_result: Mutex::new(None),
+            http_client: Mutex::new(
+                build_client(&HttpSettings::default()).expect("Failed to create HTTP client"),
+            ),
+            local_models: Mutex::new(Vec::new()),
         }
     }
 }
 
+/// Result of `AIState::complete_in_chunks`, with the estimated tokens of
+/// all its requests.
+#[derive(Debug, Default)]
+pub struct ChunkedAnswer {
+    pub content: String,
+    pub prompt_tokens: usize,
+    pub completion_tokens: usize,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AIChunkPayload {
@@ -34,6 +168,16 @@ pub struct AIChunkPayload {
     pub request_id: String,
 }
 
+/// Sent before each request of a chunked answer; `total` can grow when the
+/// partial answers take more than one round to merge.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AIProgressPayload {
+    pub done: usize,
+    pub total: usize,
+    pub request_id: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AIErrorPayload {
@@ -54,17 +198,23 @@ fn error_to_code(err: &AIError) -> &'static str {
     }
 }
 
+/// Ollama's models, or the ones it last listed while it can't be reached.
 #[tauri::command]
 pub async fn list_local_models(
     state: State<'_, Arc<AIState>>,
 ) -> Result<Vec<ModelInfo>, String> {
-    let config = AIConfig::default();
-
-    state
-        .ollama
-        .list_models(&config)
-        .await
-        .map_err(|e| e.to_string())
+    let base_url = AIConfig::default().base_url;
+    match state.refresh_local_models(&base_url).await {
+        Ok(models) => Ok(models),
+        Err(e) => {
+            let cached = state.local_models.lock().unwrap().clone();
+            if cached.is_empty() {
+                return Err(e.to_string());
+            }
+            log::debug!("Ollama unreachable, listing cached models: {}", e);
+            Ok(cached)
+        }
+    }
 }
 
 #[tauri::command]
@@ -84,25 +234,74 @@ pub async fn check_ollama_health(
         .map_err(|e| e.to_string())
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum AIRequestStatus {
+    Started,
+    /// The "ask" shield policy found high-severity PII. Nothing was sent;
+    /// resend with a `shield_decision`.
+    NeedsConfirmation { scan: PIIScanResult },
+}
+
+/// Start a streaming request. Whether the prompt is masked is decided here
+/// from the stored shield policy; `shield_decision` only answers a previous
+/// `NeedsConfirmation`. `content` is the text the prompt is about; when it
+/// doesn't fit in the model's context it is processed in chunks, with
+/// `ai:progress` events instead of streamed text.
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn send_ai_request(
     app: AppHandle,
     state: State<'_, Arc<AIState>>,
     prompt: String,
+    content: Option<String>,
     config: AIConfig,
     request_id: String,
-    use_privacy_shield: bool,
-) -> Result<(), String> {
-    // Privacy shield processing
-    let (processed_prompt, mask_result) = if use_privacy_shield
-        && config.provider == AIProviderType::OpenAI
-    {
-        let result = privacy::mask_pii(&prompt);
-        (result.masked.clone(), Some(result))
-    } else {
-        (prompt, None)
+    shield_decision: Option<ShieldDecision>,
+) -> Result<AIRequestStatus, String> {
+    let settings = app.state::<ConfigManager>();
+    let app_config = settings.effective_config().map_err(|e| e.to_string())?;
+    let scan = match &content {
+        Some(content) => privacy::scan_pii(&chunk::with_content(&prompt, content)),
+        None => privacy::scan_pii(&prompt),
     };
+    let action = privacy::resolve_shield(
+        ShieldPolicy::from_name(&app_config.shield_policy),
+        app_config.is_shielded(config.provider),
+        &scan,
+        shield_decision,
+    );
+    if action == ShieldAction::Confirm {
+        return Ok(AIRequestStatus::NeedsConfirmation { scan });
+    }
+
+    // Prompt and content share one mapping so either can be restored
+    let mut mapping = (action == ShieldAction::Mask).then(MaskMapping::default);
+    let mut mask = |text: &str| match mapping.as_mut() {
+        Some(mapping) => privacy::mask_pii_with(text, mapping),
+        None => text.to_string(),
+    };
+    let prompt = mask(&prompt);
+    let content = content.map(|c| mask(&c));
+
+    settings.track(UsageMetric::AiRequest, 1);
+    if let Some(mapping) = &mapping {
+        settings.track(UsageMetric::PiiMasked, mapping.mappings.len() as u64);
+    }
 
+    let context_tokens = config.context_tokens.unwrap_or(DEFAULT_CONTEXT_TOKENS);
+    let budget = chunk::input_budget(context_tokens, config.max_tokens, &prompt);
+    let processed_prompt = match content {
+        Some(content) if text::text_stats(&content).estimated_tokens > budget => {
+            let state = Arc::clone(&state);
+            tokio::spawn(run_chunked(app, state, prompt, content, config, budget, request_id, mapping));
+            return Ok(AIRequestStatus::Started);
+        }
+        Some(content) => chunk::with_content(&prompt, &content),
+        None => prompt,
+    };
+
+    let prompt_tokens = text::text_stats(&processed_prompt).estimated_tokens;
     let messages = vec![ChatMessage::user(processed_prompt)];
 
     let (tx, mut rx) = mpsc::channel::<Result<StreamChunk, AIError>>(100);
@@ -116,7 +315,6 @@ pub async fn send_ai_request(
 
     let app_clone = app.clone();
     let request_id_clone = request_id.clone();
-    let mapping = mask_result.as_ref().map(|r| r.mapping.clone());
 
     // Spawn streaming task with cancellation support
     let config_clone = config.clone();
@@ -142,7 +340,7 @@ pub async fn send_ai_request(
         if let Err(e) = result {
             let _ = app_clone.emit("ai:error", AIErrorPayload {
                 code: error_to_code(&e).to_string(),
-                message: e.to_string(),
+                message: e.localized(),
                 request_id: request_id_clone.clone(),
             });
         }
@@ -155,6 +353,10 @@ pub async fn send_ai_request(
     // Process streaming chunks
     let app_emit = app.clone();
     let request_id_emit = request_id.clone();
+    let state_result = Arc::clone(&state);
+    let (provider, model) = (config.provider, config.model.clone());
+    // The provider's timeout also bounds the wait for each chunk
+    let idle_timeout = config.timeout_secs.map_or(STREAM_IDLE_TIMEOUT, std::time::Duration::from_secs);
 
     tokio::spawn(async move {
         let mut full_content = String::new();
@@ -173,6 +375,11 @@ pub async fn send_ai_request(
                                 } else {
                                     full_content.clone()
                                 };
+                                *state_result.last_result.lock().unwrap() = Some(final_content.clone());
+                                let completion_tokens = text::text_stats(&full_content).estimated_tokens;
+                                app_emit
+                                    .state::<ConfigManager>()
+                                    .track_ai_tokens(provider, &model, prompt_tokens, completion_tokens);
 
                                 let _ = app_emit.emit("ai:chunk", AIChunkPayload {
                                     content: final_content,
@@ -191,7 +398,7 @@ pub async fn send_ai_request(
                         Some(Err(e)) => {
                             let _ = app_emit.emit("ai:error", AIErrorPayload {
                                 code: error_to_code(&e).to_string(),
-                                message: e.to_string(),
+                                message: e.localized(),
                                 request_id: request_id_emit.clone(),
                             });
                             break;
@@ -199,10 +406,10 @@ pub async fn send_ai_request(
                         None => break,
                     }
                 }
-                _ = tokio::time::sleep(tokio::time::Duration::from_secs(30)) => {
+                _ = tokio::time::sleep(idle_timeout) => {
                     let _ = app_emit.emit("ai:error", AIErrorPayload {
                         code: "TIMEOUT".to_string(),
-                        message: "Request timeout".to_string(),
+                        message: AIError::Timeout.localized(),
                         request_id: request_id_emit.clone(),
                     });
                     break;
@@ -211,7 +418,65 @@ pub async fn send_ai_request(
         }
     });
 
-    Ok(())
+    Ok(AIRequestStatus::Started)
+}
+
+/// The chunked path of `send_ai_request`: one `ai:chunk` with the whole
+/// answer at the end, cancellable like a streamed request.
+#[allow(clippy::too_many_arguments)]
+async fn run_chunked(
+    app: AppHandle,
+    state: Arc<AIState>,
+    prompt: String,
+    content: String,
+    config: AIConfig,
+    budget: usize,
+    request_id: String,
+    mapping: Option<MaskMapping>,
+) {
+    let (cancel_tx, cancel_rx) = tokio::sync::oneshot::channel::<()>();
+    state.active_requests.write().await.insert(request_id.clone(), cancel_tx);
+
+    let progress = |done, total| {
+        let _ = app.emit("ai:progress", AIProgressPayload {
+            done,
+            total,
+            request_id: request_id.clone(),
+        });
+    };
+    let result = tokio::select! {
+        res = state.complete_in_chunks(&prompt, &content, &config, budget, progress) => res,
+        _ = cancel_rx => Err(AIError::Cancelled),
+    };
+    state.active_requests.write().await.remove(&request_id);
+
+    match result {
+        Ok(answer) => {
+            let final_content = match &mapping {
+                Some(m) => privacy::restore_pii(&answer.content, m),
+                None => answer.content,
+            };
+            *state.last_result.lock().unwrap() = Some(final_content.clone());
+            app.state::<ConfigManager>().track_ai_tokens(
+                config.provider,
+                &config.model,
+                answer.prompt_tokens,
+                answer.completion_tokens,
+            );
+            let _ = app.emit("ai:chunk", AIChunkPayload {
+                content: final_content,
+                done: true,
+                request_id,
+            });
+        }
+        Err(e) => {
+            let _ = app.emit("ai:error", AIErrorPayload {
+                code: error_to_code(&e).to_string(),
+                message: e.localized(),
+                request_id,
+            });
+        }
+    }
 }
 
 #[tauri::command]
@@ -227,6 +492,134 @@ pub async fn cancel_ai_request(
 }
 
 #[tauri::command]
-pub fn detect_content_intent(text: String) -> Vec<ActionChip> {
-    detect_intent(&text)
+pub fn get_last_ai_result(state: State<'_, Arc<AIState>>) -> Option<String> {
+    state.last_result()
+}
+
+/// Content type, language and chips (built-in merged with the user's
+/// custom intents) for `text`. With `ai_intent` on, ambiguous text is also
+/// classified by the local Ollama model, within the configured budget.
+#[tauri::command]
+pub async fn detect_content_intent(
+    app: AppHandle,
+    text: String,
+    config: State<'_, ConfigManager>,
+    state: State<'_, Arc<AIState>>,
+) -> Result<IntentResult, String> {
+    let custom = config.get_custom_intents().unwrap_or_else(|e| {
+        log::warn!("Failed to load custom intents: {}", e);
+        Vec::new()
+    });
+    let app_config = config.effective_config().unwrap_or_else(|e| {
+        log::warn!("Failed to load config: {}", e);
+        AppConfig::default()
+    });
+    let usage = config.get_chip_usage().unwrap_or_else(|e| {
+        log::warn!("Failed to load chip usage: {}", e);
+        Vec::new()
+    });
+    // So a later copy of a currency conversion gets its answer chip
+    super::ensure_rates(&app, &text);
+    let mut result = detect_intent(&text, &custom, &app_config.chip_layout, &usage);
+
+    if app_config.ai_intent && classify::needs_classification(&result) {
+        classify_with_ai(&state, &app_config, &text, &mut result).await;
+    }
+    if !app_config.translation_service.is_empty() {
+        intent::use_translation_command(&mut result);
+    }
+    if !app_config.notes_path.trim().is_empty() && !text.trim().is_empty() {
+        let max_chips = app_config.chip_layout.max_chips(result.content_type);
+        intent::add_notes_chip(&mut result, max_chips);
+    }
+    Ok(result)
+}
+
+/// Merge the local model's take on `text` into `result`. Any failure or a
+/// blown budget leaves the heuristic result as it is.
+async fn classify_with_ai(state: &AIState, app_config: &AppConfig, text: &str, result: &mut IntentResult) {
+    let Some(provider) = app_config.provider("Ollama") else {
+        return;
+    };
+    let model = app_config.ai_intent_model.trim();
+    let ai_config = AIConfig {
+        provider: AIProviderType::Ollama,
+        base_url: provider.base_url.clone(),
+        model: if model.is_empty() { provider.model.clone() } else { model.to_string() },
+        max_tokens: 256,
+        temperature: 0.0,
+        ..AIConfig::default()
+    };
+    let budget = std::time::Duration::from_millis(app_config.ai_intent_budget_ms);
+    let messages = vec![ChatMessage::user(classify::classification_prompt(text))];
+
+    match tokio::time::timeout(budget, state.collect(messages, &ai_config)).await {
+        Ok(Ok(response)) => match classify::parse_classification(&response) {
+            Some(classification) => {
+                let max_chips = app_config.chip_layout.max_chips(result.content_type);
+                classify::merge_classification(result, classification, max_chips);
+            }
+            None => log::warn!("Unreadable intent classification from {}", ai_config.model),
+        },
+        Ok(Err(e)) => log::warn!("Intent classification failed: {}", e),
+        Err(_) => log::debug!("Intent classification exceeded {}ms", app_config.ai_intent_budget_ms),
+    }
+}
+
+#[tauri::command]
+pub async fn get_custom_intents(config: State<'_, ConfigManager>) -> Result<Vec<CustomIntent>, String> {
+    config.get_custom_intents().map_err(|e| e.to_string())
+}
+
+/// Add or replace a custom intent. Local-rule, webhook and workflow chips
+/// must name an existing rule, webhook or workflow.
+#[tauri::command]
+pub async fn save_custom_intent(
+    intent: CustomIntent,
+    config: State<'_, ConfigManager>,
+) -> Result<(), String> {
+    intent.validate()?;
+    if intent.action_type == ActionType::LocalRule
+        && !regex::is_builtin_rule(&intent.payload)
+        && !config
+            .get_custom_rules()
+            .map_err(|e| e.to_string())?
+            .iter()
+            .any(|r| r.id == intent.payload)
+    {
+        return Err(format!("rule not found: {}", intent.payload));
+    }
+    if intent.action_type == ActionType::Webhook
+        && config.get_webhook(&intent.payload).map_err(|e| e.to_string())?.is_none()
+    {
+        return Err(format!("webhook not found: {}", intent.payload));
+    }
+    if intent.action_type == ActionType::Workflow
+        && config.get_workflow(&intent.payload).map_err(|e| e.to_string())?.is_none()
+    {
+        return Err(format!("workflow not found: {}", intent.payload));
+    }
+    config.save_custom_intent(&intent).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_custom_intent(id: String, config: State<'_, ConfigManager>) -> Result<(), String> {
+    config.delete_custom_intent(&id).map_err(|e| e.to_string())
+}
+
+/// Called by the panel when a chip is activated. Chips are keyed by action id,
+/// which is stable across detections and UI languages (chip ids are not). The
+/// pick also ranks future chips for `content_type`.
+#[tauri::command]
+pub async fn record_chip_usage(
+    chip: ActionChip,
+    content_type: ContentType,
+    config: State<'_, ConfigManager>,
+) -> Result<(), String> {
+    config
+        .record_usage(UsageTarget::Chip, &chip.action_id)
+        .map_err(|e| e.to_string())?;
+    config
+        .record_chip_pick(content_type, &chip.action_id)
+        .map_err(|e| e.to_string())
 }
@@ -0,0 +1,127 @@
+use chrono::Utc;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::calc::{self, currency, CurrencyRates, Evaluation};
+use crate::config::ConfigManager;
+
+const RATES_FILE: &str = "currency_rates.json";
+/// Rates older than this are downloaded again when next needed
+const RATES_MAX_AGE_SECS: i64 = 12 * 60 * 60;
+/// Wait after a failed download, doubling with each failure up to the max
+const RETRY_MIN_SECS: i64 = 60;
+const RETRY_MAX_SECS: i64 = 60 * 60;
+
+/// Background download of the rates: one at a time, backing off while offline.
+struct Refresh {
+    running: bool,
+    failures: u32,
+    retry_at: i64,
+}
+
+static REFRESH: Mutex<Refresh> = Mutex::new(Refresh { running: false, failures: 0, retry_at: 0 });
+
+fn rates_path<R: Runtime>(app: &AppHandle<R>) -> Option<PathBuf> {
+    app.path().app_data_dir().ok().map(|dir| dir.join(RATES_FILE))
+}
+
+fn is_stale(rates: Option<&CurrencyRates>) -> bool {
+    match rates {
+        Some(rates) => Utc::now().timestamp() - rates.fetched_at > RATES_MAX_AGE_SECS,
+        None => true,
+    }
+}
+
+/// Download today's exchange rates, use them and save them for next time.
+async fn refresh_rates<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    let settings = app.state::<ConfigManager>().effective_config().map_err(|e| e.to_string())?.http_settings();
+    let rates = currency::fetch_rates(&settings).await.map_err(|e| e.to_string())?;
+    if let Some(path) = rates_path(app) {
+        let saved = serde_json::to_vec(&rates).map_err(|e| e.to_string()).and_then(|json| {
+            if let Some(dir) = path.parent() {
+                std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+            }
+            std::fs::write(&path, json).map_err(|e| e.to_string())
+        });
+        if let Err(e) = saved {
+            log::warn!("Failed to save exchange rates to {}: {}", path.display(), e);
+        }
+    }
+    log::debug!("Fetched {} exchange rates for {}", rates.rates.len(), rates.date);
+    currency::set_rates(rates);
+    Ok(())
+}
+
+/// Use the exchange rates saved last time, so currency chips work offline,
+/// and renew them in the background if they are old. Nothing is downloaded
+/// until a currency has been converted once.
+pub fn restore_rates<R: Runtime>(app: &AppHandle<R>) {
+    let Some(path) = rates_path(app) else {
+        return;
+    };
+    let rates: CurrencyRates = match std::fs::read(&path) {
+        Ok(json) => match serde_json::from_slice(&json) {
+            Ok(rates) => rates,
+            Err(e) => {
+                log::warn!("Ignoring unreadable exchange rates in {}: {}", path.display(), e);
+                return;
+            }
+        },
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+        Err(e) => {
+            log::warn!("Failed to read exchange rates from {}: {}", path.display(), e);
+            return;
+        }
+    };
+    currency::set_rates(rates);
+    refresh_in_background(app);
+}
+
+/// Start downloading exchange rates if they are missing or old, unless a
+/// download is already running or failed recently. Returns at once.
+fn refresh_in_background<R: Runtime>(app: &AppHandle<R>) {
+    if !is_stale(currency::rates().as_ref()) {
+        return;
+    }
+    {
+        let mut refresh = REFRESH.lock().unwrap();
+        if refresh.running || Utc::now().timestamp() < refresh.retry_at {
+            return;
+        }
+        refresh.running = true;
+    }
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let result = refresh_rates(&app).await;
+        let mut refresh = REFRESH.lock().unwrap();
+        refresh.running = false;
+        match result {
+            Ok(()) => {
+                refresh.failures = 0;
+                refresh.retry_at = 0;
+            }
+            Err(e) => {
+                refresh.failures += 1;
+                let wait = (RETRY_MIN_SECS << (refresh.failures - 1).min(6)).min(RETRY_MAX_SECS);
+                refresh.retry_at = Utc::now().timestamp() + wait;
+                log::warn!("Failed to refresh exchange rates, retrying in {}s: {}", wait, e);
+            }
+        }
+    });
+}
+
+/// Renew exchange rates in the background if `text` converts currencies
+/// and they are missing or old. Chips use whatever rates are cached now.
+pub(crate) fn ensure_rates<R: Runtime>(app: &AppHandle<R>, text: &str) {
+    if calc::needs_rates(text) {
+        refresh_in_background(app);
+    }
+}
+
+/// Answer an arithmetic expression or a unit or currency conversion.
+#[tauri::command]
+pub async fn evaluate(app: AppHandle, expression: String) -> Result<Evaluation, String> {
+    ensure_rates(&app, &expression);
+    calc::evaluate(&expression).map_err(|e| e.to_string())
+}
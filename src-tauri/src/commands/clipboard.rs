@@ -1,6 +1,9 @@
-use tauri::AppHandle;
+use tauri::{AppHandle, State};
 
-use crate::clipboard::{self, ClipboardContent};
+use crate::clipboard::{
+    self, ClipboardContent, HistorySnapshot, RecentClipboard, TransformHistory, TransformSummary,
+};
+use crate::hotkey::{HotkeyAction, HotkeyManager};
 
 #[tauri::command]
 pub async fn read_clipboard(app: AppHandle) -> Result<ClipboardContent, String> {
@@ -17,3 +20,79 @@ pub async fn write_clipboard(app: AppHandle, text: String) -> Result<(), String>
         .map_err(|e| e.to_string())?;
     result.map_err(|e| e.to_string())
 }
+
+/// Apply one rule, or a pipeline of rules in order, to the clipboard in a single call.
+#[tauri::command]
+pub async fn transform_clipboard(
+    app: AppHandle,
+    rule_ids: Vec<String>,
+    manager: State<'_, HotkeyManager>,
+) -> Result<TransformSummary, String> {
+    let action = HotkeyAction::ApplyRules { rule_ids: rule_ids.clone() };
+    let result = tauri::async_runtime::spawn_blocking(move || clipboard::transform_clipboard(&app, &rule_ids))
+        .await
+        .map_err(|e| e.to_string())?;
+    let summary = result.map_err(|e| e.to_string())?;
+    manager.set_last_action(action);
+    Ok(summary)
+}
+
+/// Record a transform the frontend applied (AI rewrite, rule chip) so it can be undone.
+#[tauri::command]
+pub fn record_transform(
+    input: String,
+    output: String,
+    label: String,
+    history: State<'_, TransformHistory>,
+) {
+    history.push(input, output, label);
+}
+
+/// Restore the clipboard to the input of the latest transform.
+/// Returns the restored text, or `None` when there is nothing to undo.
+#[tauri::command]
+pub fn undo_transform(
+    app: AppHandle,
+    history: State<'_, TransformHistory>,
+) -> Result<Option<String>, String> {
+    let Some(step) = history.undo() else {
+        return Ok(None);
+    };
+    if let Err(e) = clipboard::write_clipboard(&app, &step.input) {
+        history.revert_undo();
+        return Err(e.to_string());
+    }
+    Ok(Some(step.input))
+}
+
+/// Re-apply the last undone transform to the clipboard.
+#[tauri::command]
+pub fn redo_transform(
+    app: AppHandle,
+    history: State<'_, TransformHistory>,
+) -> Result<Option<String>, String> {
+    let Some(step) = history.redo() else {
+        return Ok(None);
+    };
+    if let Err(e) = clipboard::write_clipboard(&app, &step.output) {
+        history.revert_redo();
+        return Err(e.to_string());
+    }
+    Ok(Some(step.output))
+}
+
+#[tauri::command]
+pub fn get_transform_history(history: State<'_, TransformHistory>) -> HistorySnapshot {
+    history.snapshot()
+}
+
+#[tauri::command]
+pub fn clear_transform_history(history: State<'_, TransformHistory>) {
+    history.clear();
+}
+
+/// Recent clipboard texts seen this session, most recent first.
+#[tauri::command]
+pub fn get_recent_clipboard(recent: State<'_, RecentClipboard>) -> Vec<String> {
+    recent.items()
+}
@@ -0,0 +1,44 @@
+use tauri::AppHandle;
+use tauri_plugin_shell::ShellExt;
+
+use crate::ai::commit;
+use crate::hotkey;
+use crate::text;
+use crate::transcription;
+
+/// The staged changes of the repository at `dir`.
+async fn staged_diff(app: &AppHandle, dir: &str) -> Result<String, String> {
+    let output = app
+        .shell()
+        .command("git")
+        .args(["diff", "--staged", "--no-color", "--no-ext-diff"])
+        .current_dir(transcription::expand_home(app, dir))
+        .output()
+        .await
+        .map_err(|e| format!("failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    let diff = String::from_utf8_lossy(&output.stdout).into_owned();
+    if diff.trim().is_empty() {
+        return Err(format!("nothing staged in {}", dir));
+    }
+    Ok(diff)
+}
+
+/// Write a Conventional Commits message for `text`: a diff, or the path of
+/// a repository whose staged changes are used. The diff goes through the
+/// privacy shield like any other prompt.
+#[tauri::command]
+pub async fn generate_commit_message(app: AppHandle, text: String) -> Result<String, String> {
+    let trimmed = text.trim();
+    let diff = if !trimmed.contains('\n') && text::is_path_list(trimmed) {
+        staged_diff(&app, trimmed).await?
+    } else {
+        text
+    };
+    let reply = hotkey::run_prompt(&app, commit::COMMIT_PROMPT, &diff)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(commit::clean_commit_message(&reply))
+}
@@ -1,6 +1,26 @@
-use tauri::State;
+use std::fs;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
 
-use crate::config::{AppConfig, ConfigManager};
+use crate::ai::build_client;
+use crate::ai::intent::ActionType;
+use crate::commands::AIState;
+use crate::i18n::{self, Locale};
+use crate::api;
+use crate::expansion;
+use crate::mcp;
+use crate::native_messaging;
+use crate::tray;
+use crate::logging;
+
+use crate::config::{
+    AppConfig, ConfigBackup, ConfigBundle, ConfigManager, DashboardStats, ImportMode, Profile, UsagePeriod, UsageStats,
+    BUNDLE_VERSION,
+};
+use crate::hotkey::{self, builtin_action, HotkeyManager};
+use crate::regex::{self, Rule};
+use crate::snippet;
 
 #[tauri::command]
 pub async fn get_config(state: State<'_, ConfigManager>) -> Result<AppConfig, String> {
@@ -9,10 +29,108 @@ pub async fn get_config(state: State<'_, ConfigManager>) -> Result<AppConfig, St
 
 #[tauri::command]
 pub async fn set_config(
+    app: AppHandle,
     config: AppConfig,
     state: State<'_, ConfigManager>,
+    ai: State<'_, Arc<AIState>>,
 ) -> Result<(), String> {
-    state.set_config(&config).map_err(|e| e.to_string())
+    let effective = config.clone().with_env_overrides();
+    // Built first so a bad proxy or certificate is rejected before saving
+    let client = build_client(&effective.http_settings()).map_err(|e| e.to_string())?;
+    state.set_config(&config).map_err(|e| e.to_string())?;
+    ai.set_http_client(client);
+    apply_settings(&app, &effective);
+    emit_config_changed(&app, &config);
+    Ok(())
+}
+
+/// Apply settings that live outside the database (HTTP clients, locale,
+/// logging, window behavior, background servers), at startup and after
+/// settings were replaced wholesale. Takes the stored settings; environment
+/// overrides are added here.
+pub fn apply_runtime_settings(app: &AppHandle, config: &AppConfig) {
+    let config = &config.clone().with_env_overrides();
+    match build_client(&config.http_settings()) {
+        Ok(client) => app.state::<Arc<AIState>>().set_http_client(client),
+        Err(e) => log::error!("Failed to apply proxy/TLS settings: {}", e),
+    }
+    apply_settings(app, config);
+}
+
+/// Everything in `apply_runtime_settings` but the HTTP client, from the
+/// effective settings.
+fn apply_settings(app: &AppHandle, config: &AppConfig) {
+    i18n::set_locale(Locale::from_tag(&config.locale));
+    logging::configure(&config.log_level, config.log_to_file);
+    // Not managed yet at startup, where hotkey setup reads the debounce itself
+    if let Some(hotkeys) = app.try_state::<HotkeyManager>() {
+        hotkeys.set_debounce(Duration::from_millis(config.hotkey_debounce_ms));
+    }
+    apply_window_settings(app, config);
+    // Menu labels follow the locale
+    tray::refresh(app);
+    mcp::configure(app, config);
+    api::configure(app, config);
+    native_messaging::configure(app, config);
+    expansion::configure(app, config);
+}
+
+/// Stacking and workspace behavior of the panel. Opacity and hide-on-blur
+/// are applied by the panel itself from `config:changed`.
+fn apply_window_settings(app: &AppHandle, config: &AppConfig) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    if let Err(e) = window.set_always_on_top(config.always_on_top) {
+        log::warn!("Failed to set always-on-top: {}", e);
+    }
+    if let Err(e) = window.set_visible_on_all_workspaces(config.visible_on_all_workspaces) {
+        log::warn!("Failed to set visible-on-all-workspaces: {}", e);
+    }
+}
+
+/// Tell every window about the new settings so none has to poll.
+fn emit_config_changed(app: &AppHandle, config: &AppConfig) {
+    if let Err(e) = app.emit("config:changed", config) {
+        log::warn!("Failed to emit config:changed: {}", e);
+    }
+}
+
+/// A frontend preference stored under `namespace.name`, or null if unset.
+#[tauri::command]
+pub async fn get_setting(
+    key: String,
+    state: State<'_, ConfigManager>,
+) -> Result<Option<serde_json::Value>, String> {
+    state.get_setting(&key).map_err(|e| e.to_string())
+}
+
+/// Store any JSON value under `namespace.name`; null deletes the key.
+#[tauri::command]
+pub async fn set_setting(
+    key: String,
+    value: serde_json::Value,
+    state: State<'_, ConfigManager>,
+) -> Result<(), String> {
+    state.set_setting(&key, &value).map_err(|e| e.to_string())
+}
+
+/// Local feature counters for the stats view.
+#[tauri::command]
+pub async fn get_usage_stats(
+    period: UsagePeriod,
+    state: State<'_, ConfigManager>,
+) -> Result<UsageStats, String> {
+    state.get_usage_stats(period).map_err(|e| e.to_string())
+}
+
+/// Daily activity, top rules and AI token estimates for the stats screen.
+#[tauri::command]
+pub async fn get_dashboard_stats(
+    range: UsagePeriod,
+    state: State<'_, ConfigManager>,
+) -> Result<DashboardStats, String> {
+    state.get_dashboard_stats(range).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
@@ -31,3 +149,231 @@ pub async fn set_api_key(
 ) -> Result<(), String> {
     state.set_api_key(&provider, &key).map_err(|e| e.to_string())
 }
+
+/// Bearer token for the local HTTP API, created on first use.
+#[tauri::command]
+pub async fn get_http_api_token(app: AppHandle) -> Result<String, String> {
+    api::token(&app).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn regenerate_http_api_token(app: AppHandle) -> Result<String, String> {
+    api::regenerate_token(&app).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_database_encryption(state: State<'_, ConfigManager>) -> Result<bool, String> {
+    state.is_encrypted_at_rest().map_err(|e| e.to_string())
+}
+
+/// Encrypt (or decrypt) stored rules, snippets and prompts. Existing rows
+/// are migrated in place; the key lives in the OS keyring.
+#[tauri::command]
+pub async fn set_database_encryption(
+    enabled: bool,
+    state: State<'_, ConfigManager>,
+) -> Result<(), String> {
+    state.set_encrypted_at_rest(enabled).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_profiles(state: State<'_, ConfigManager>) -> Result<Vec<Profile>, String> {
+    state.get_profiles().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_active_profile(state: State<'_, ConfigManager>) -> Result<Option<String>, String> {
+    state.get_active_profile().map_err(|e| e.to_string())
+}
+
+/// Save the current settings and action hotkeys as profile `name`,
+/// overwriting a profile with the same name.
+#[tauri::command]
+pub async fn save_profile(name: String, state: State<'_, ConfigManager>) -> Result<Profile, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("profile name is empty".to_string());
+    }
+
+    let profile = Profile {
+        name: name.to_string(),
+        config: state.get_config().map_err(|e| e.to_string())?,
+        action_hotkeys: state.get_action_hotkeys().map_err(|e| e.to_string())?,
+    };
+    state.save_profile(&profile).map_err(|e| e.to_string())?;
+    Ok(profile)
+}
+
+#[tauri::command]
+pub async fn delete_profile(name: String, state: State<'_, ConfigManager>) -> Result<(), String> {
+    state.delete_profile(&name).map_err(|e| e.to_string())
+}
+
+/// Apply a saved profile and re-register hotkeys. The profile is validated
+/// first; its settings are written in a single transaction.
+#[tauri::command]
+pub async fn switch_profile(
+    app: AppHandle,
+    name: String,
+    state: State<'_, ConfigManager>,
+    hotkeys: State<'_, HotkeyManager>,
+) -> Result<AppConfig, String> {
+    let profile = state.get_profile(&name).map_err(|e| e.to_string())?;
+
+    profile.config.validate().map_err(|e| format!("profile '{}': {}", name, e))?;
+    for binding in &profile.action_hotkeys {
+        if builtin_action(&binding.action).is_none() {
+            return Err(format!("profile '{}': unknown hotkey action: {}", name, binding.action));
+        }
+        HotkeyManager::normalize_hotkey(&binding.hotkey)
+            .map_err(|e| format!("profile '{}', action '{}': {}", name, binding.action, e))?;
+    }
+
+    let config = state.apply_profile(&profile).map_err(|e| e.to_string())?;
+
+    hotkeys.unregister_all(&app).await;
+    hotkey::restore_bindings(&app).await;
+    apply_runtime_settings(&app, &config);
+    emit_config_changed(&app, &config);
+    Ok(config)
+}
+
+/// Automatic backups of settings.db, newest first.
+#[tauri::command]
+pub async fn list_config_backups(state: State<'_, ConfigManager>) -> Result<Vec<ConfigBackup>, String> {
+    state.list_backups().map_err(|e| e.to_string())
+}
+
+/// Roll settings.db back to backup `id` and re-apply everything derived
+/// from it, as after a profile switch.
+#[tauri::command]
+pub async fn restore_config_backup(
+    app: AppHandle,
+    id: String,
+    state: State<'_, ConfigManager>,
+    hotkeys: State<'_, HotkeyManager>,
+) -> Result<AppConfig, String> {
+    let config = state.restore_backup(&id).map_err(|e| e.to_string())?;
+
+    expansion::reload_abbreviations(&app);
+    hotkeys.unregister_all(&app).await;
+    hotkey::restore_bindings(&app).await;
+    apply_runtime_settings(&app, &config);
+    emit_config_changed(&app, &config);
+    Ok(config)
+}
+
+/// Write settings, rules, snippets and hotkeys (no API keys) to `path` as JSON.
+#[tauri::command]
+pub async fn export_config(path: String, state: State<'_, ConfigManager>) -> Result<(), String> {
+    let bundle = state.export_bundle().map_err(|e| e.to_string())?;
+    let json = serde_json::to_string_pretty(&bundle).map_err(|e| e.to_string())?;
+    fs::write(&path, json).map_err(|e| e.to_string())
+}
+
+/// Load a bundle written by `export_config`. Nothing is written unless the
+/// whole bundle validates; hotkeys are re-registered afterwards.
+#[tauri::command]
+pub async fn import_config(
+    app: AppHandle,
+    path: String,
+    mode: ImportMode,
+    state: State<'_, ConfigManager>,
+    hotkeys: State<'_, HotkeyManager>,
+) -> Result<(), String> {
+    let json = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let bundle = ConfigBundle::from_json(&json).map_err(|e| format!("invalid config bundle: {}", e))?;
+
+    let mut known_rules = bundle.custom_rules.clone();
+    if mode == ImportMode::Merge {
+        known_rules.extend(state.get_custom_rules().map_err(|e| e.to_string())?);
+    }
+    validate_bundle(&bundle, &known_rules)?;
+
+    state.import_bundle(&bundle, mode).map_err(|e| e.to_string())?;
+
+    expansion::reload_abbreviations(&app);
+    hotkeys.unregister_all(&app).await;
+    hotkey::restore_bindings(&app).await;
+    apply_runtime_settings(&app, &bundle.settings);
+    emit_config_changed(&app, &bundle.settings);
+    Ok(())
+}
+
+/// Errors name the offending entry, e.g. "rule 'dashes': ...".
+fn validate_bundle(bundle: &ConfigBundle, known_rules: &[Rule]) -> Result<(), String> {
+    if bundle.version > BUNDLE_VERSION {
+        return Err(format!(
+            "bundle version {} is newer than supported version {}",
+            bundle.version, BUNDLE_VERSION
+        ));
+    }
+
+    let check_hotkey = |what: String, hotkey: &str| {
+        HotkeyManager::normalize_hotkey(hotkey)
+            .map(|_| ())
+            .map_err(|e| format!("{}: {}", what, e))
+    };
+    let check_rule_ids = |what: String, rule_ids: &[String]| match rule_ids
+        .iter()
+        .find(|id| !regex::is_builtin_rule(id) && !known_rules.iter().any(|r| &r.id == *id))
+    {
+        Some(unknown) => Err(format!("{}: rule not found: {}", what, unknown)),
+        None => Ok(()),
+    };
+
+    bundle.settings.validate().map_err(|e| format!("settings: {}", e))?;
+
+    for rule in &bundle.custom_rules {
+        if rule.id.trim().is_empty() {
+            return Err("custom rule with empty id".to_string());
+        }
+        if regex::get_builtin_rules().iter().any(|r| r.id == rule.id) {
+            return Err(format!("rule id '{}' is reserved by a builtin rule", rule.id));
+        }
+        regex::validate_rule(rule).map_err(|e| format!("rule '{}': {}", rule.id, e))?;
+        if let Some(shortcut) = &rule.shortcut {
+            check_hotkey(format!("rule '{}' shortcut", rule.id), shortcut)?;
+        }
+    }
+
+    for snippet in &bundle.snippets {
+        if snippet.id.trim().is_empty() || snippet.name.trim().is_empty() {
+            return Err("snippet with empty id or name".to_string());
+        }
+        snippet::validate_template(&snippet.template)
+            .map_err(|e| format!("snippet '{}': {}", snippet.id, e))?;
+        if let Some(abbreviation) = &snippet.abbreviation {
+            snippet::validate_abbreviation(abbreviation).map_err(|e| format!("snippet '{}': {}", snippet.id, e))?;
+        }
+    }
+
+    for intent in &bundle.custom_intents {
+        let what = format!("intent '{}'", intent.id);
+        intent.validate().map_err(|e| format!("{}: {}", what, e))?;
+        if intent.action_type == ActionType::LocalRule {
+            check_rule_ids(what, std::slice::from_ref(&intent.payload))?;
+        }
+    }
+
+    for binding in &bundle.action_hotkeys {
+        if builtin_action(&binding.action).is_none() {
+            return Err(format!("unknown hotkey action: {}", binding.action));
+        }
+        check_hotkey(format!("action '{}'", binding.action), &binding.hotkey)?;
+    }
+
+    for binding in &bundle.rule_hotkeys {
+        let what = format!("rule hotkey '{}'", binding.id);
+        check_hotkey(what.clone(), &binding.hotkey)?;
+        check_rule_ids(what, &binding.rule_ids)?;
+    }
+
+    for binding in &bundle.headless_hotkeys {
+        let what = format!("headless hotkey '{}'", binding.id);
+        check_hotkey(what.clone(), &binding.hotkey)?;
+        check_rule_ids(what, &binding.pipeline.rule_ids)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,15 @@
+use tauri::AppHandle;
+
+use crate::folder_sync::{self, Keep, SyncKind, SyncReport};
+
+/// Sync rules, snippets and prompts with the sync folder now.
+#[tauri::command]
+pub async fn sync_folder(app: AppHandle) -> Result<SyncReport, String> {
+    folder_sync::sync(&app).await.map_err(|e| e.to_string())
+}
+
+/// Settle a sync conflict by keeping this machine's or the folder's copy.
+#[tauri::command]
+pub async fn resolve_sync_conflict(app: AppHandle, kind: SyncKind, id: String, keep: Keep) -> Result<(), String> {
+    folder_sync::resolve_conflict(&app, kind, id, keep).await.map_err(|e| e.to_string())
+}
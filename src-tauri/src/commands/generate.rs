@@ -0,0 +1,13 @@
+use tauri::AppHandle;
+
+use crate::clipboard;
+use crate::text::Generator;
+
+/// Generate a password, UUID, placeholder text or test card number and put
+/// it on the clipboard.
+#[tauri::command]
+pub fn generate_to_clipboard(app: AppHandle, generator: Generator) -> Result<String, String> {
+    let value = generator.generate().map_err(|e| e.to_string())?;
+    clipboard::write_clipboard(&app, &value).map_err(|e| e.to_string())?;
+    Ok(value)
+}
@@ -1,5 +1,10 @@
+use std::time::Duration;
 use tauri::{AppHandle, State};
-use crate::hotkey::HotkeyManager;
+use crate::config::{ActionHotkey, ConfigManager, HeadlessHotkey, RuleHotkey};
+use crate::hotkey::{
+    self, headless_binding_name, rule_binding_name, HotkeyAction, HotkeyManager, HotkeyValidation, RegisteredHotkey,
+};
+use crate::regex;
 
 #[tauri::command]
 pub async fn register_hotkey(
@@ -28,3 +33,247 @@ pub async fn is_hotkey_registered(
 ) -> Result<bool, String> {
     Ok(manager.is_registered().await)
 }
+
+/// Check format, conflicts with other bindings and OS availability before
+/// binding. Pass `binding` when editing an existing binding.
+#[tauri::command]
+pub async fn validate_hotkey(
+    app: AppHandle,
+    accelerator: String,
+    binding: Option<String>,
+    manager: State<'_, HotkeyManager>,
+) -> Result<HotkeyValidation, String> {
+    Ok(manager.validate_hotkey(&app, &accelerator, binding.as_deref()).await)
+}
+
+#[tauri::command]
+pub async fn get_registered_hotkeys(
+    manager: State<'_, HotkeyManager>,
+) -> Result<Vec<RegisteredHotkey>, String> {
+    Ok(manager.registered_hotkeys().await)
+}
+
+/// Canonical display form of a hotkey, e.g. "control + shift + v" -> "Ctrl+Shift+V".
+#[tauri::command]
+pub fn normalize_hotkey(accelerator: String) -> Result<String, String> {
+    HotkeyManager::normalize_hotkey(&accelerator).map_err(|e| e.to_string())
+}
+
+/// Wait for the user to press a key combination and return it as an
+/// accelerator. `None` means the capture was cancelled with Escape or timed out.
+#[tauri::command]
+pub async fn start_hotkey_capture(timeout_ms: Option<u64>) -> Result<Option<String>, String> {
+    let timeout = timeout_ms
+        .map(Duration::from_millis)
+        .unwrap_or(hotkey::DEFAULT_CAPTURE_TIMEOUT);
+    tauri::async_runtime::spawn_blocking(move || hotkey::capture_next(timeout))
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Names of the built-in actions that can be given a hotkey.
+#[tauri::command]
+pub fn get_hotkey_actions() -> Vec<&'static str> {
+    hotkey::ACTION_BINDINGS.to_vec()
+}
+
+#[tauri::command]
+pub async fn get_action_hotkeys(
+    config: State<'_, ConfigManager>,
+) -> Result<Vec<ActionHotkey>, String> {
+    config.get_action_hotkeys().map_err(|e| e.to_string())
+}
+
+/// Bind a built-in action (see `hotkey::ACTION_BINDINGS`) to an accelerator,
+/// or unbind it when `hotkey` is `None`.
+#[tauri::command]
+pub async fn set_action_hotkey(
+    app: AppHandle,
+    action: String,
+    hotkey: Option<String>,
+    manager: State<'_, HotkeyManager>,
+    config: State<'_, ConfigManager>,
+) -> Result<(), String> {
+    let handler = hotkey::builtin_action(&action)
+        .ok_or_else(|| format!("unknown hotkey action: {}", action))?;
+
+    match hotkey {
+        Some(hotkey) => {
+            let hotkey = HotkeyManager::normalize_hotkey(&hotkey).map_err(|e| e.to_string())?;
+            manager
+                .register_binding(&app, &action, &hotkey, handler)
+                .await
+                .map_err(|e| e.to_string())?;
+            config
+                .save_action_hotkey(&ActionHotkey { action, hotkey })
+                .map_err(|e| e.to_string())
+        }
+        None => {
+            manager
+                .unregister_binding(&app, &action)
+                .await
+                .map_err(|e| e.to_string())?;
+            config.delete_action_hotkey(&action).map_err(|e| e.to_string())
+        }
+    }
+}
+
+#[tauri::command]
+pub async fn get_rule_hotkeys(
+    config: State<'_, ConfigManager>,
+) -> Result<Vec<RuleHotkey>, String> {
+    config.get_rule_hotkeys().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn bind_rule_hotkey(
+    app: AppHandle,
+    mut binding: RuleHotkey,
+    manager: State<'_, HotkeyManager>,
+    config: State<'_, ConfigManager>,
+) -> Result<(), String> {
+    if binding.rule_ids.is_empty() {
+        return Err("rule hotkey needs at least one rule".to_string());
+    }
+
+    let custom_rules = config.get_custom_rules().map_err(|e| e.to_string())?;
+    if let Some(unknown) = binding
+        .rule_ids
+        .iter()
+        .find(|id| !regex::is_builtin_rule(id) && !custom_rules.iter().any(|r| &r.id == *id))
+    {
+        return Err(format!("rule not found: {}", unknown));
+    }
+    binding.hotkey = HotkeyManager::normalize_hotkey(&binding.hotkey).map_err(|e| e.to_string())?;
+
+    manager
+        .register_binding(
+            &app,
+            &rule_binding_name(&binding.id),
+            &binding.hotkey,
+            HotkeyAction::ApplyRules {
+                rule_ids: binding.rule_ids.clone(),
+            },
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+    config.save_rule_hotkey(&binding).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn unbind_rule_hotkey(
+    app: AppHandle,
+    id: String,
+    manager: State<'_, HotkeyManager>,
+    config: State<'_, ConfigManager>,
+) -> Result<(), String> {
+    manager
+        .unregister_binding(&app, &rule_binding_name(&id))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    config.delete_rule_hotkey(&id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_headless_hotkeys(
+    config: State<'_, ConfigManager>,
+) -> Result<Vec<HeadlessHotkey>, String> {
+    config.get_headless_hotkeys().map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn bind_headless_hotkey(
+    app: AppHandle,
+    mut binding: HeadlessHotkey,
+    manager: State<'_, HotkeyManager>,
+    config: State<'_, ConfigManager>,
+) -> Result<(), String> {
+    let pipeline = &binding.pipeline;
+    if pipeline.rule_ids.is_empty() && pipeline.prompt.as_deref().unwrap_or("").trim().is_empty() {
+        return Err("headless hotkey needs a rule or a prompt".to_string());
+    }
+
+    let custom_rules = config.get_custom_rules().map_err(|e| e.to_string())?;
+    if let Some(unknown) = pipeline
+        .rule_ids
+        .iter()
+        .find(|id| !regex::is_builtin_rule(id) && !custom_rules.iter().any(|r| &r.id == *id))
+    {
+        return Err(format!("rule not found: {}", unknown));
+    }
+    binding.hotkey = HotkeyManager::normalize_hotkey(&binding.hotkey).map_err(|e| e.to_string())?;
+
+    manager
+        .register_binding(
+            &app,
+            &headless_binding_name(&binding.id),
+            &binding.hotkey,
+            HotkeyAction::Headless {
+                pipeline: binding.pipeline.clone(),
+            },
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+
+    config.save_headless_hotkey(&binding).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn unbind_headless_hotkey(
+    app: AppHandle,
+    id: String,
+    manager: State<'_, HotkeyManager>,
+    config: State<'_, ConfigManager>,
+) -> Result<(), String> {
+    manager
+        .unregister_binding(&app, &headless_binding_name(&id))
+        .await
+        .map_err(|e| e.to_string())?;
+
+    config.delete_headless_hotkey(&id).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn get_hotkey_exclusions(
+    config: State<'_, ConfigManager>,
+) -> Result<Vec<String>, String> {
+    config.get_hotkey_exclusions().map_err(|e| e.to_string())
+}
+
+/// Replace the list of applications (name or executable) where global
+/// hotkeys are ignored.
+#[tauri::command]
+pub async fn set_hotkey_exclusions(
+    apps: Vec<String>,
+    manager: State<'_, HotkeyManager>,
+    config: State<'_, ConfigManager>,
+) -> Result<(), String> {
+    config.set_hotkey_exclusions(&apps).map_err(|e| e.to_string())?;
+    manager.set_excluded_apps(config.get_hotkey_exclusions().map_err(|e| e.to_string())?);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_hotkey_pass_through(
+    config: State<'_, ConfigManager>,
+) -> Result<Vec<String>, String> {
+    config.get_hotkey_pass_through().map_err(|e| e.to_string())
+}
+
+/// Choose whether `binding` is forwarded to the previous app, instead of
+/// acting on the panel, while the panel has focus.
+#[tauri::command]
+pub async fn set_hotkey_pass_through(
+    binding: String,
+    enabled: bool,
+    manager: State<'_, HotkeyManager>,
+    config: State<'_, ConfigManager>,
+) -> Result<(), String> {
+    config
+        .set_hotkey_pass_through(&binding, enabled)
+        .map_err(|e| e.to_string())?;
+    manager.set_pass_through_bindings(config.get_hotkey_pass_through().map_err(|e| e.to_string())?);
+    Ok(())
+}
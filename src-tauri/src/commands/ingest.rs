@@ -0,0 +1,13 @@
+use std::path::PathBuf;
+use tauri::AppHandle;
+
+use crate::ingest::{self, IngestedFile};
+
+/// Read the text of a file dropped onto the panel.
+#[tauri::command]
+pub async fn read_dropped_file(app: AppHandle, path: String) -> Result<IngestedFile, String> {
+    tauri::async_runtime::spawn_blocking(move || ingest::read_file(&app, &PathBuf::from(path)))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
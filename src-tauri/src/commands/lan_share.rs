@@ -0,0 +1,21 @@
+use tauri::AppHandle;
+
+use crate::lan_share::{self, ShareInfo};
+
+/// Share `text` once with another device on the local network.
+#[tauri::command]
+pub fn share_text(app: AppHandle, text: &str) -> Result<ShareInfo, String> {
+    lan_share::share(&app, text).map_err(|e| e.to_string())
+}
+
+/// Share item `index` of the clipboard history, 0 being the latest copy.
+#[tauri::command]
+pub fn share_recent_clipboard(app: AppHandle, index: usize) -> Result<ShareInfo, String> {
+    lan_share::share_recent(&app, index).map_err(|e| e.to_string())
+}
+
+/// End the current share before it is fetched or expires.
+#[tauri::command]
+pub fn stop_lan_share(app: AppHandle) {
+    lan_share::finish(&app, None);
+}
@@ -0,0 +1,16 @@
+use log::Level;
+
+use crate::logging::{self, LogEntry};
+
+/// The last `lines` log records at `level` or more severe (default info),
+/// oldest first, for the diagnostics view.
+#[tauri::command]
+pub async fn get_recent_logs(lines: usize, level: Option<String>) -> Result<Vec<LogEntry>, String> {
+    let min_level = match level {
+        Some(name) => name
+            .parse::<Level>()
+            .map_err(|_| format!("unknown log level: {}", name))?,
+        None => Level::Info,
+    };
+    Ok(logging::recent(lines, min_level))
+}
@@ -3,13 +3,51 @@ mod privacy;
 mod ai;
 mod clipboard;
 mod config;
+mod logs;
 mod regex;
 mod hotkey;
+mod text;
+mod snippet;
+mod webhook;
+mod ocr;
+mod qr;
+mod transcription;
+mod translation;
+mod notes;
+mod native_messaging;
+mod lan_share;
+mod ingest;
+mod palette;
+mod workflow;
+mod generate;
+mod commit;
+mod calc;
+mod folder_sync;
+mod result_window;
 
 pub use greet::*;
 pub use privacy::*;
 pub use ai::*;
 pub use clipboard::*;
 pub use config::*;
+pub use logs::*;
 pub use regex::*;
 pub use hotkey::*;
+pub use text::*;
+pub use snippet::*;
+pub use webhook::*;
+pub use ocr::*;
+pub use qr::*;
+pub use transcription::*;
+pub use translation::*;
+pub use notes::*;
+pub use native_messaging::*;
+pub use lan_share::*;
+pub use ingest::*;
+pub use palette::*;
+pub use workflow::*;
+pub use generate::*;
+pub use commit::*;
+pub use calc::*;
+pub use folder_sync::*;
+pub use result_window::*;
@@ -0,0 +1,16 @@
+use tauri::AppHandle;
+
+use crate::native_messaging;
+
+/// Register FlowPaste as the native messaging host of `browser` (`chrome` or
+/// `firefox`) for the comma-separated extension ids; returns the manifest path.
+#[tauri::command]
+pub fn install_native_host(
+    app: AppHandle,
+    browser: &str,
+    extension_ids: &str,
+) -> Result<String, String> {
+    let path =
+        native_messaging::install_host(&app, browser, extension_ids).map_err(|e| e.to_string())?;
+    Ok(path.to_string_lossy().into_owned())
+}
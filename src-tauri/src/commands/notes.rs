@@ -0,0 +1,10 @@
+use tauri::AppHandle;
+
+use crate::notes;
+
+/// Append `text` to the configured notes file and return the file's path.
+#[tauri::command]
+pub fn save_to_notes(app: AppHandle, text: &str) -> Result<String, String> {
+    let path = notes::save(&app, text).map_err(|e| e.to_string())?;
+    Ok(path.to_string_lossy().into_owned())
+}
@@ -0,0 +1,24 @@
+use tauri::AppHandle;
+
+use crate::hotkey;
+use crate::ocr::{self, OcrError};
+
+/// Read the text in a screen region the user selects, put it on the
+/// clipboard and reopen the panel on it. Returns `None` when the selection
+/// is cancelled.
+#[tauri::command]
+pub async fn capture_screen_text(app: AppHandle) -> Result<Option<String>, String> {
+    let result = {
+        let app = app.clone();
+        tauri::async_runtime::spawn_blocking(move || ocr::capture_text(&app))
+            .await
+            .map_err(|e| e.to_string())?
+    };
+    // The panel was hidden for the capture; bring it back either way
+    hotkey::show_panel(&app, "panel:show");
+    match result {
+        Ok(text) => Ok(Some(text)),
+        Err(OcrError::Cancelled) => Ok(None),
+        Err(e) => Err(e.to_string()),
+    }
+}
@@ -0,0 +1,160 @@
+use serde::Serialize;
+use std::collections::HashMap;
+use tauri::State;
+
+use crate::ai::intent::ActionType;
+use crate::clipboard::{self, RecentClipboard};
+use crate::config::{ConfigManager, UsageTarget};
+use crate::i18n::tr;
+use crate::regex;
+use crate::text;
+
+const MAX_RESULTS: usize = 50;
+/// Characters of a clipboard history item shown and searched
+const HISTORY_PREVIEW_CHARS: usize = 120;
+/// `Generator` kinds with their title and subtitle keys
+const GENERATORS: &[(&str, &str, &str)] = &[
+    ("password", "generate.password", "generate.password_hint"),
+    ("uuid", "generate.uuid", "generate.uuid_hint"),
+    ("loremIpsum", "generate.lorem_ipsum", "generate.lorem_ipsum_hint"),
+    ("cardNumber", "generate.card_number", "generate.card_number_hint"),
+];
+
+/// What running a palette entry does. Ties in score are listed in this order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PaletteKind {
+    Rule,
+    Prompt,
+    Snippet,
+    /// Makes a new value (password, UUID, ...) and copies it
+    Generator,
+    History,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PaletteAction {
+    pub kind: PaletteKind,
+    /// Rule or snippet id, the prompt of a prompt, the generator's `kind`,
+    /// or the position in the clipboard history (0 = latest)
+    pub target: String,
+    pub title: String,
+    pub subtitle: String,
+    pub score: u32,
+}
+
+struct Candidate {
+    kind: PaletteKind,
+    target: String,
+    title: String,
+    subtitle: String,
+    /// Also searched, weighted below the title
+    keywords: String,
+    uses: u64,
+}
+
+impl Candidate {
+    /// Title matches count double, so a hit in the name beats one in the
+    /// description.
+    fn score(&self, query: &str) -> Option<u32> {
+        let title = text::fuzzy_score(query, &self.title).map(|s| s * 2);
+        let keywords = text::fuzzy_score(query, &self.keywords);
+        title.max(keywords)
+    }
+}
+
+/// Rules, prompts, snippets, generators and clipboard history matching
+/// `query`, best first, so the panel can run anything from the keyboard. An
+/// empty query lists everything, most used rules first.
+#[tauri::command]
+pub async fn search_actions(
+    query: String,
+    config: State<'_, ConfigManager>,
+    recent: State<'_, RecentClipboard>,
+) -> Result<Vec<PaletteAction>, String> {
+    let uses: HashMap<String, u64> = config
+        .get_rule_stats()
+        .unwrap_or_else(|e| {
+            log::warn!("Failed to load rule usage: {}", e);
+            Vec::new()
+        })
+        .into_iter()
+        .filter(|s| s.target == UsageTarget::Rule)
+        .map(|s| (s.id, s.count))
+        .collect();
+
+    let mut candidates = Vec::new();
+    let custom_rules = config.get_custom_rules().map_err(|e| e.to_string())?;
+    for rule in regex::get_builtin_rules().into_iter().chain(custom_rules) {
+        candidates.push(Candidate {
+            kind: PaletteKind::Rule,
+            uses: uses.get(&rule.id).copied().unwrap_or(0),
+            keywords: format!("{} {}", rule.description, rule.tags.join(" ")),
+            subtitle: rule.description,
+            title: rule.name,
+            target: rule.id,
+        });
+    }
+    for intent in config.get_custom_intents().map_err(|e| e.to_string())? {
+        if intent.enabled && intent.action_type == ActionType::AIPrompt {
+            candidates.push(Candidate {
+                kind: PaletteKind::Prompt,
+                keywords: intent.payload.clone(),
+                subtitle: clipboard::preview(&intent.payload, HISTORY_PREVIEW_CHARS),
+                title: intent.label,
+                target: intent.payload,
+                uses: 0,
+            });
+        }
+    }
+    for snippet in config.get_snippets().map_err(|e| e.to_string())? {
+        candidates.push(Candidate {
+            kind: PaletteKind::Snippet,
+            keywords: format!("{} {} {}", snippet.description, snippet.folder, snippet.tags.join(" ")),
+            subtitle: if snippet.description.is_empty() { snippet.folder } else { snippet.description },
+            title: snippet.name,
+            target: snippet.id,
+            uses: 0,
+        });
+    }
+    for (kind, title, subtitle) in GENERATORS {
+        candidates.push(Candidate {
+            kind: PaletteKind::Generator,
+            target: kind.to_string(),
+            title: tr(title).to_string(),
+            subtitle: tr(subtitle).to_string(),
+            keywords: format!("{} generate random", kind),
+            uses: 0,
+        });
+    }
+    for (i, item) in recent.items().into_iter().enumerate() {
+        let preview = clipboard::preview(item.trim(), HISTORY_PREVIEW_CHARS);
+        candidates.push(Candidate {
+            kind: PaletteKind::History,
+            target: i.to_string(),
+            title: preview.lines().next().unwrap_or_default().to_string(),
+            keywords: preview.clone(),
+            subtitle: preview,
+            uses: 0,
+        });
+    }
+
+    let mut matches: Vec<(u32, Candidate)> = candidates
+        .into_iter()
+        .filter_map(|c| Some((c.score(&query)?, c)))
+        .collect();
+    // Stable, so equal entries keep their kind's own order
+    matches.sort_by(|(a, x), (b, y)| b.cmp(a).then(y.uses.cmp(&x.uses)).then(x.kind.cmp(&y.kind)));
+    Ok(matches
+        .into_iter()
+        .take(MAX_RESULTS)
+        .map(|(score, c)| PaletteAction {
+            kind: c.kind,
+            target: c.target,
+            title: c.title,
+            subtitle: c.subtitle,
+            score,
+        })
+        .collect())
+}
@@ -0,0 +1,38 @@
+use chrono::Local;
+use tauri::{AppHandle, Manager};
+
+use crate::clipboard;
+use crate::qr;
+
+/// Put `text` on the clipboard as a QR code image.
+#[tauri::command]
+pub fn copy_qr_code(app: AppHandle, text: &str) -> Result<(), String> {
+    let image = qr::render(text).map_err(|e| e.to_string())?;
+    clipboard::write_clipboard_image(&app, image.width, image.height, image.rgba)
+        .map_err(|e| e.to_string())
+}
+
+/// Save `text` as a QR code PNG in Downloads and return the file's path.
+#[tauri::command]
+pub fn save_qr_code(app: AppHandle, text: &str) -> Result<String, String> {
+    let image = qr::render(text).map_err(|e| e.to_string())?;
+    let png =
+        clipboard::encode_png(image.width, image.height, &image.rgba).map_err(|e| e.to_string())?;
+    let dir = app.path().download_dir().map_err(|e| e.to_string())?;
+    let path = dir.join(format!(
+        "flowpaste-qr-{}.png",
+        Local::now().format("%Y%m%d-%H%M%S")
+    ));
+    std::fs::write(&path, png).map_err(|e| e.to_string())?;
+    log::info!("Saved QR code to {}", path.display());
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Read the QR code in the clipboard image; its text replaces the image.
+#[tauri::command]
+pub async fn decode_qr_code(app: AppHandle) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || qr::decode_clipboard(&app))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
@@ -1,16 +1,115 @@
-use crate::regex::{self, Rule};
+use tauri::{AppHandle, State};
+
+use crate::config::{ConfigManager, RuleStat, UsageMetric, UsageTarget};
+use crate::hotkey::{pinned_binding_name, HotkeyManager};
+use crate::regex::{self, CallbackInfo, Rule, RuleCategory};
 
 #[tauri::command]
 pub fn get_builtin_rules() -> Vec<Rule> {
     regex::get_builtin_rules()
 }
 
+fn record_rule_usage(state: &ConfigManager, rule_id: &str) {
+    // Parameterized ids (`prefix_lines:> `) count toward the base rule
+    let id = rule_id.split(':').next().unwrap_or(rule_id);
+    if let Err(e) = state.record_usage(UsageTarget::Rule, id) {
+        log::warn!("Failed to record usage for rule '{}': {}", id, e);
+    }
+    state.track(UsageMetric::Transform, 1);
+}
+
+#[tauri::command]
+pub fn apply_rule(
+    text: String,
+    rule_id: String,
+    state: State<'_, ConfigManager>,
+) -> Result<String, String> {
+    let result = regex::apply_rule(&text, &rule_id).map_err(|e| e.to_string())?;
+    record_rule_usage(&state, &rule_id);
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn apply_custom_rule(
+    text: String,
+    rule: Rule,
+    state: State<'_, ConfigManager>,
+) -> Result<String, String> {
+    let result = regex::apply_custom_rule(&text, &rule).map_err(|e| e.to_string())?;
+    record_rule_usage(&state, &rule.id);
+    Ok(result)
+}
+
+#[tauri::command]
+pub fn get_rule_callbacks() -> Vec<CallbackInfo> {
+    regex::list_callbacks()
+}
+
+#[tauri::command]
+pub async fn get_rules(
+    category: Option<RuleCategory>,
+    state: State<'_, ConfigManager>,
+) -> Result<Vec<Rule>, String> {
+    let mut rules = regex::get_builtin_rules();
+    rules.extend(state.get_custom_rules().map_err(|e| e.to_string())?);
+    let order = state.get_rule_order().map_err(|e| e.to_string())?;
+    Ok(regex::arrange_rules(rules, &order, category))
+}
+
+#[tauri::command]
+pub async fn save_custom_rule(
+    app: AppHandle,
+    mut rule: Rule,
+    state: State<'_, ConfigManager>,
+    hotkeys: State<'_, HotkeyManager>,
+) -> Result<(), String> {
+    if rule.id.trim().is_empty() {
+        return Err("rule id must not be empty".to_string());
+    }
+    if regex::get_builtin_rules().iter().any(|r| r.id == rule.id) {
+        return Err(format!("rule id '{}' is reserved by a builtin rule", rule.id));
+    }
+    regex::validate_rule(&rule).map_err(|e| e.to_string())?;
+
+    rule.is_builtin = false;
+    rule.shortcut = match rule.shortcut.as_deref().map(str::trim) {
+        Some(shortcut) if !shortcut.is_empty() => {
+            Some(HotkeyManager::normalize_hotkey(shortcut).map_err(|e| e.to_string())?)
+        }
+        _ => None,
+    };
+
+    // Register first so a taken shortcut doesn't end up in storage
+    hotkeys
+        .sync_pinned_rule(&app, &rule)
+        .await
+        .map_err(|e| e.to_string())?;
+    state.save_custom_rule(&rule).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_custom_rule(
+    app: AppHandle,
+    id: String,
+    state: State<'_, ConfigManager>,
+    hotkeys: State<'_, HotkeyManager>,
+) -> Result<(), String> {
+    hotkeys
+        .unregister_binding(&app, &pinned_binding_name(&id))
+        .await
+        .map_err(|e| e.to_string())?;
+    state.delete_custom_rule(&id).map_err(|e| e.to_string())
+}
+
 #[tauri::command]
-pub fn apply_rule(text: String, rule_id: String) -> Result<String, String> {
-    regex::apply_rule(&text, &rule_id).map_err(|e| e.to_string())
+pub async fn set_rule_order(
+    rule_ids: Vec<String>,
+    state: State<'_, ConfigManager>,
+) -> Result<(), String> {
+    state.set_rule_order(&rule_ids).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-pub fn apply_custom_rule(text: String, rule: Rule) -> Result<String, String> {
-    regex::apply_custom_rule(&text, &rule).map_err(|e| e.to_string())
+pub async fn get_rule_stats(state: State<'_, ConfigManager>) -> Result<Vec<RuleStat>, String> {
+    state.get_rule_stats().map_err(|e| e.to_string())
 }
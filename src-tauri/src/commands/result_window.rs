@@ -0,0 +1,32 @@
+use tauri::{AppHandle, State, WebviewWindow};
+
+use crate::result_window::{self, InlineResult, ResultWindow};
+
+/// Show `result` in the small window at the cursor. The calling window
+/// hears `result:closed` once it is accepted or dismissed.
+#[tauri::command]
+pub async fn show_inline_result(app: AppHandle, window: WebviewWindow, mut result: InlineResult) -> Result<(), String> {
+    result.source = Some(window.label().to_string());
+    result_window::show(&app, result).map_err(|e| e.to_string())
+}
+
+/// The result on show, for the result window when it loads.
+#[tauri::command]
+pub fn get_inline_result(state: State<'_, ResultWindow>) -> Option<InlineResult> {
+    state.current()
+}
+
+#[tauri::command]
+pub async fn accept_inline_result(app: AppHandle) -> Result<(), String> {
+    result_window::accept(&app).await.map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub fn dismiss_inline_result(app: AppHandle) {
+    result_window::dismiss(&app)
+}
+
+#[tauri::command]
+pub async fn close_result_window(app: AppHandle) -> Result<(), String> {
+    result_window::close(&app).map_err(|e| e.to_string())
+}
@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager, State};
+
+use crate::clipboard;
+use crate::config::ConfigManager;
+use crate::expansion;
+use crate::snippet::{
+    self, RenderContext, RenderedSnippet, Snippet, SnippetError, SnippetField, SnippetQuery, TagCount,
+};
+
+#[tauri::command]
+pub async fn get_snippets(state: State<'_, ConfigManager>) -> Result<Vec<Snippet>, String> {
+    state.get_snippets().map_err(|e| e.to_string())
+}
+
+/// Add or replace a snippet. Abbreviations must be unique.
+#[tauri::command]
+pub async fn save_snippet(
+    app: AppHandle,
+    mut snippet: Snippet,
+    state: State<'_, ConfigManager>,
+) -> Result<(), String> {
+    if snippet.id.trim().is_empty() || snippet.name.trim().is_empty() {
+        return Err("snippet id and name must not be empty".to_string());
+    }
+    snippet::validate_template(&snippet.template).map_err(|e| e.to_string())?;
+    snippet.folder = snippet::normalize_folder(&snippet.folder);
+    snippet.tags = snippet::normalize_tags(&snippet.tags);
+    snippet.abbreviation = snippet.abbreviation.map(|a| a.trim().to_string()).filter(|a| !a.is_empty());
+    if let Some(abbreviation) = &snippet.abbreviation {
+        snippet::validate_abbreviation(abbreviation).map_err(|e| e.to_string())?;
+        let existing = state.get_snippets().map_err(|e| e.to_string())?;
+        if let Some(other) = existing
+            .iter()
+            .find(|s| s.id != snippet.id && s.abbreviation.as_ref() == Some(abbreviation))
+        {
+            return Err(format!("abbreviation '{}' is already used by '{}'", abbreviation, other.name));
+        }
+    }
+    state.save_snippet(&snippet).map_err(|e| e.to_string())?;
+    expansion::reload_abbreviations(&app);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn search_snippets(query: SnippetQuery, state: State<'_, ConfigManager>) -> Result<Vec<Snippet>, String> {
+    let snippets = state.get_snippets().map_err(|e| e.to_string())?;
+    Ok(snippet::search(snippets, &query))
+}
+
+#[tauri::command]
+pub async fn get_snippet_folders(state: State<'_, ConfigManager>) -> Result<Vec<String>, String> {
+    let snippets = state.get_snippets().map_err(|e| e.to_string())?;
+    Ok(snippet::folders(&snippets))
+}
+
+#[tauri::command]
+pub async fn get_snippet_tags(state: State<'_, ConfigManager>) -> Result<Vec<TagCount>, String> {
+    let snippets = state.get_snippets().map_err(|e| e.to_string())?;
+    Ok(snippet::tags(&snippets))
+}
+
+/// Rename or move a folder along with its subfolders. Renaming to `""`
+/// moves the snippets to the top level.
+#[tauri::command]
+pub async fn rename_snippet_folder(
+    from: String,
+    to: String,
+    state: State<'_, ConfigManager>,
+) -> Result<(), String> {
+    let from = snippet::normalize_folder(&from);
+    let to = snippet::normalize_folder(&to);
+    if from.is_empty() {
+        return Err("folder must not be empty".to_string());
+    }
+    for mut snippet in state.get_snippets().map_err(|e| e.to_string())? {
+        if !snippet::in_folder(&snippet.folder, &from) {
+            continue;
+        }
+        let rest = &snippet.folder[from.len()..];
+        snippet.folder = snippet::normalize_folder(&format!("{}{}", to, rest));
+        state.save_snippet(&snippet).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn delete_snippet(app: AppHandle, id: String, state: State<'_, ConfigManager>) -> Result<(), String> {
+    state.delete_snippet(&id).map_err(|e| e.to_string())?;
+    expansion::reload_abbreviations(&app);
+    Ok(())
+}
+
+/// Fill-ins the UI should prompt for before rendering.
+#[tauri::command]
+pub fn get_snippet_fields(template: String) -> Result<Vec<SnippetField>, String> {
+    snippet::snippet_fields(&template).map_err(|e| e.to_string())
+}
+
+fn render_stored(
+    app: &AppHandle,
+    id: &str,
+    values: HashMap<String, String>,
+) -> Result<RenderedSnippet, String> {
+    let config = app.state::<ConfigManager>();
+    let snippet = config
+        .get_snippet(id)
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| SnippetError::NotFound(id.to_string()).to_string())?;
+
+    let clipboard = if snippet.template.contains("{clipboard}") {
+        clipboard::read_clipboard(app).ok().and_then(|c| c.text)
+    } else {
+        None
+    };
+    let ctx = RenderContext {
+        clipboard,
+        values,
+        now: chrono::Local::now(),
+    };
+    snippet::render(&snippet.template, &ctx).map_err(|e| e.to_string())
+}
+
+/// Render a stored snippet for preview without touching the clipboard.
+#[tauri::command]
+pub async fn render_snippet(
+    app: AppHandle,
+    id: String,
+    values: HashMap<String, String>,
+) -> Result<RenderedSnippet, String> {
+    tauri::async_runtime::spawn_blocking(move || render_stored(&app, &id, values))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Render a stored snippet and write it to the clipboard. Fails if any
+/// fill-in has neither a value nor a default.
+#[tauri::command]
+pub async fn insert_snippet(
+    app: AppHandle,
+    id: String,
+    values: HashMap<String, String>,
+) -> Result<RenderedSnippet, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let rendered = render_stored(&app, &id, values)?;
+        if !rendered.missing.is_empty() {
+            return Err(SnippetError::MissingValues(rendered.missing).to_string());
+        }
+        clipboard::write_clipboard(&app, &rendered.text).map_err(|e| e.to_string())?;
+        Ok(rendered)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
@@ -0,0 +1,106 @@
+use chrono::Local;
+use std::fs::File;
+use tauri::{AppHandle, Manager};
+
+use crate::clipboard;
+use crate::config::ConfigManager;
+use crate::text::{self, DiffResult, HashAlgorithm, TextStats, UrlMetadata};
+use crate::transcription;
+
+#[tauri::command]
+pub fn get_text_stats(text: &str) -> TextStats {
+    text::text_stats(text)
+}
+
+#[tauri::command]
+pub fn diff_texts(before: &str, after: &str) -> DiffResult {
+    text::diff_texts(before, after)
+}
+
+/// Decode a Base64 blob or data URI into the Downloads folder and return the
+/// new file's path.
+#[tauri::command]
+pub fn save_decoded_file(app: AppHandle, text: &str) -> Result<String, String> {
+    let decoded = text::decode_base64(text).ok_or_else(|| "not valid Base64".to_string())?;
+    let dir = app.path().download_dir().map_err(|e| e.to_string())?;
+    let name = format!(
+        "flowpaste-{}.{}",
+        Local::now().format("%Y%m%d-%H%M%S"),
+        decoded.extension()
+    );
+    let path = dir.join(name);
+    std::fs::write(&path, &decoded.bytes).map_err(|e| e.to_string())?;
+    log::info!("Saved {} decoded bytes to {}", decoded.bytes.len(), path.display());
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// Checksum of the file whose path is `text`, or else of the text itself,
+/// in lowercase hex; the digest also replaces the clipboard.
+#[tauri::command]
+pub async fn compute_hash(app: AppHandle, text: String, algorithm: HashAlgorithm) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let digest = match text::file_path(&text) {
+            Some(path) => {
+                let file = File::open(transcription::expand_home(&app, path))
+                    .map_err(|e| format!("{}: {}", path, e))?;
+                text::hash_reader(file, algorithm).map_err(|e| e.to_string())?
+            }
+            None => text::hash_bytes(text.as_bytes(), algorithm),
+        };
+        clipboard::write_clipboard(&app, &digest).map_err(|e| e.to_string())?;
+        Ok(digest)
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Metadata of every whitespace-separated URL in `text`, `None` for pages
+/// that can't be fetched.
+async fn fetch_all(app: &AppHandle, text: &str) -> Result<Vec<(String, Option<UrlMetadata>)>, String> {
+    let settings = app.state::<ConfigManager>().effective_config().map_err(|e| e.to_string())?.http_settings();
+    let urls: Vec<String> = text.split_whitespace().map(str::to_string).collect();
+    if urls.is_empty() {
+        return Err("no URLs found".to_string());
+    }
+    let fetched = futures::future::join_all(urls.iter().map(|url| text::fetch_metadata(&settings, url))).await;
+    Ok(urls
+        .into_iter()
+        .zip(fetched)
+        .map(|(url, metadata)| {
+            let metadata = metadata.map_err(|e| log::warn!("Failed to fetch metadata for {}: {}", url, e)).ok();
+            (url, metadata)
+        })
+        .collect())
+}
+
+/// Title, description, preview image and site name of a page.
+#[tauri::command]
+pub async fn fetch_url_metadata(app: AppHandle, url: String) -> Result<UrlMetadata, String> {
+    let settings = app.state::<ConfigManager>().effective_config().map_err(|e| e.to_string())?.http_settings();
+    text::fetch_metadata(&settings, &url).await.map_err(|e| e.to_string())
+}
+
+/// Turn each URL into a Markdown link titled with its page title, falling
+/// back to the URL itself when the page can't be fetched.
+#[tauri::command]
+pub async fn make_markdown_links(app: AppHandle, text: String) -> Result<String, String> {
+    let links: Vec<String> = fetch_all(&app, &text)
+        .await?
+        .iter()
+        .map(|(url, metadata)| text::markdown_link(url, metadata.as_ref()))
+        .collect();
+    Ok(links.join("\n"))
+}
+
+/// Turn each URL into a citation with its title, site, today's date and
+/// description.
+#[tauri::command]
+pub async fn make_citation(app: AppHandle, text: String) -> Result<String, String> {
+    let accessed = Local::now().format("%Y-%m-%d").to_string();
+    let citations: Vec<String> = fetch_all(&app, &text)
+        .await?
+        .iter()
+        .map(|(url, metadata)| text::citation(url, metadata.as_ref(), &accessed))
+        .collect();
+    Ok(citations.join("\n\n"))
+}
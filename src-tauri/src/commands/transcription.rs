@@ -0,0 +1,13 @@
+use tauri::AppHandle;
+
+use crate::transcription;
+
+/// Transcribe the audio file whose path is `text`; the transcript also
+/// replaces the clipboard. Progress comes as `transcription:progress` events.
+#[tauri::command]
+pub async fn transcribe_audio(app: AppHandle, text: String) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || transcription::transcribe(&app, &text))
+        .await
+        .map_err(|e| e.to_string())?
+        .map_err(|e| e.to_string())
+}
@@ -0,0 +1,11 @@
+use tauri::AppHandle;
+
+use crate::translation::{self, TranslationResult};
+
+/// Translate `text` with the configured DeepL or Google service, to the
+/// language its translate chip offers.
+#[tauri::command]
+pub async fn translate_text(app: AppHandle, text: String) -> Result<TranslationResult, String> {
+    translation::translate(&app, &text, None).await.map_err(|e| e.to_string())
+}
+
@@ -0,0 +1,53 @@
+use tauri::{AppHandle, State};
+
+use crate::config::{ConfigManager, Webhook};
+use crate::hotkey::{webhook_binding_name, HotkeyManager};
+use crate::webhook::{self, Source, WebhookResult};
+
+#[tauri::command]
+pub async fn get_webhooks(config: State<'_, ConfigManager>) -> Result<Vec<Webhook>, String> {
+    config.get_webhooks().map_err(|e| e.to_string())
+}
+
+/// Add or replace a webhook and (re)bind its shortcut.
+#[tauri::command]
+pub async fn save_webhook(
+    app: AppHandle,
+    mut webhook: Webhook,
+    config: State<'_, ConfigManager>,
+    hotkeys: State<'_, HotkeyManager>,
+) -> Result<(), String> {
+    webhook.method = webhook.method.trim().to_ascii_uppercase();
+    webhook::validate(&webhook)?;
+    webhook.hotkey = match webhook.hotkey.as_deref().map(str::trim) {
+        Some(hotkey) if !hotkey.is_empty() => {
+            Some(HotkeyManager::normalize_hotkey(hotkey).map_err(|e| e.to_string())?)
+        }
+        _ => None,
+    };
+
+    // Register first so a taken shortcut doesn't end up in storage
+    hotkeys.sync_webhook(&app, &webhook).await.map_err(|e| e.to_string())?;
+    config.save_webhook(&webhook).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_webhook(
+    app: AppHandle,
+    id: String,
+    config: State<'_, ConfigManager>,
+    hotkeys: State<'_, HotkeyManager>,
+) -> Result<(), String> {
+    hotkeys
+        .unregister_binding(&app, &webhook_binding_name(&id))
+        .await
+        .map_err(|e| e.to_string())?;
+    config.delete_webhook(&id).map_err(|e| e.to_string())
+}
+
+/// Send `text` to a webhook, as a chip does. Failures come back in the
+/// result (and the `webhook:result` event), not as an error.
+#[tauri::command]
+pub async fn run_webhook(app: AppHandle, id: String, text: String) -> WebhookResult {
+    webhook::run(&app, &id, Source::Text(text)).await
+}
@@ -0,0 +1,68 @@
+use tauri::{AppHandle, State};
+
+use crate::config::{ConfigManager, StepAction, Workflow};
+use crate::hotkey::{workflow_binding_name, HotkeyManager};
+use crate::regex;
+use crate::workflow::{self, WorkflowResult};
+
+#[tauri::command]
+pub async fn get_workflows(config: State<'_, ConfigManager>) -> Result<Vec<Workflow>, String> {
+    config.get_workflows().map_err(|e| e.to_string())
+}
+
+/// Add or replace a workflow and (re)bind its shortcut. Its rules and
+/// webhooks must exist.
+#[tauri::command]
+pub async fn save_workflow(
+    app: AppHandle,
+    mut workflow: Workflow,
+    config: State<'_, ConfigManager>,
+    hotkeys: State<'_, HotkeyManager>,
+) -> Result<(), String> {
+    workflow::validate(&workflow)?;
+    let custom_rules = config.get_custom_rules().map_err(|e| e.to_string())?;
+    for step in &workflow.steps {
+        match &step.action {
+            StepAction::ApplyRule { rule_id }
+                if !regex::is_builtin_rule(rule_id) && !custom_rules.iter().any(|r| &r.id == rule_id) =>
+            {
+                return Err(format!("rule not found: {}", rule_id));
+            }
+            StepAction::Webhook { id } if config.get_webhook(id).map_err(|e| e.to_string())?.is_none() => {
+                return Err(format!("webhook not found: {}", id));
+            }
+            _ => {}
+        }
+    }
+    workflow.hotkey = match workflow.hotkey.as_deref().map(str::trim) {
+        Some(hotkey) if !hotkey.is_empty() => {
+            Some(HotkeyManager::normalize_hotkey(hotkey).map_err(|e| e.to_string())?)
+        }
+        _ => None,
+    };
+
+    // Register first so a taken shortcut doesn't end up in storage
+    hotkeys.sync_workflow(&app, &workflow).await.map_err(|e| e.to_string())?;
+    config.save_workflow(&workflow).map_err(|e| e.to_string())
+}
+
+#[tauri::command]
+pub async fn delete_workflow(
+    app: AppHandle,
+    id: String,
+    config: State<'_, ConfigManager>,
+    hotkeys: State<'_, HotkeyManager>,
+) -> Result<(), String> {
+    hotkeys
+        .unregister_binding(&app, &workflow_binding_name(&id))
+        .await
+        .map_err(|e| e.to_string())?;
+    config.delete_workflow(&id).map_err(|e| e.to_string())
+}
+
+/// Run a workflow on `text`, as a chip does, or on the clipboard. Failures
+/// come back in the result (and the `workflow:result` event).
+#[tauri::command]
+pub async fn run_workflow(app: AppHandle, id: String, text: Option<String>) -> WorkflowResult {
+    workflow::run(&app, &id, text).await
+}
@@ -0,0 +1,202 @@
+use std::env;
+use std::path::PathBuf;
+
+use super::ConfigError;
+
+const APP_ID: &str = "com.flowpaste.app";
+#[cfg(target_os = "windows")]
+const APP_NAME: &str = "FlowPaste";
+
+fn autostart_err(e: impl ToString) -> ConfigError {
+    ConfigError::Autostart(e.to_string())
+}
+
+#[cfg_attr(
+    not(any(target_os = "macos", target_os = "linux", target_os = "windows")),
+    allow(dead_code)
+)]
+fn current_exe() -> Result<PathBuf, ConfigError> {
+    env::current_exe().map_err(autostart_err)
+}
+
+/// Install or remove the login item. Safe to call when it's already in
+/// the requested state.
+pub fn set_enabled(enabled: bool) -> Result<(), ConfigError> {
+    if enabled {
+        install()
+    } else {
+        uninstall()
+    }
+}
+
+#[cfg(target_os = "macos")]
+fn entry_path() -> Result<PathBuf, ConfigError> {
+    let home = env::var_os("HOME").ok_or_else(|| autostart_err("HOME is not set"))?;
+    Ok(PathBuf::from(home)
+        .join("Library/LaunchAgents")
+        .join(format!("{}.plist", APP_ID)))
+}
+
+#[cfg(target_os = "macos")]
+fn install() -> Result<(), ConfigError> {
+    let path = entry_path()?;
+    let plist = launch_agent(&current_exe()?);
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(autostart_err)?;
+    }
+    std::fs::write(&path, plist).map_err(autostart_err)
+}
+
+#[cfg(target_os = "macos")]
+fn launch_agent(exe: &std::path::Path) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+    <key>Label</key>
+    <string>{}</string>
+    <key>ProgramArguments</key>
+    <array>
+        <string>{}</string>
+    </array>
+    <key>RunAtLoad</key>
+    <true/>
+</dict>
+</plist>
+"#,
+        APP_ID,
+        xml_escape(&exe.to_string_lossy())
+    )
+}
+
+#[cfg(target_os = "macos")]
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+#[cfg(target_os = "linux")]
+fn entry_path() -> Result<PathBuf, ConfigError> {
+    entry_path_in(env::var_os("XDG_CONFIG_HOME"), env::var_os("HOME"))
+}
+
+#[cfg(target_os = "linux")]
+fn entry_path_in(
+    config_home: Option<std::ffi::OsString>,
+    home: Option<std::ffi::OsString>,
+) -> Result<PathBuf, ConfigError> {
+    let config_home = match config_home {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => {
+            let home = home.ok_or_else(|| autostart_err("HOME is not set"))?;
+            PathBuf::from(home).join(".config")
+        }
+    };
+    Ok(config_home.join("autostart").join(format!("{}.desktop", APP_ID)))
+}
+
+#[cfg(target_os = "linux")]
+fn install() -> Result<(), ConfigError> {
+    let path = entry_path()?;
+    let entry = desktop_entry(&current_exe()?);
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir).map_err(autostart_err)?;
+    }
+    std::fs::write(&path, entry).map_err(autostart_err)
+}
+
+#[cfg(target_os = "linux")]
+fn desktop_entry(exe: &std::path::Path) -> String {
+    // Exec needs quoting when the path has spaces; `"` and `\` are escaped inside quotes
+    let exec = exe.to_string_lossy().replace('\\', "\\\\").replace('"', "\\\"");
+    format!(
+        "[Desktop Entry]\nType=Application\nName=FlowPaste\nExec=\"{}\"\nX-GNOME-Autostart-enabled=true\n",
+        exec
+    )
+}
+
+#[cfg(any(target_os = "macos", target_os = "linux"))]
+fn uninstall() -> Result<(), ConfigError> {
+    match std::fs::remove_file(entry_path()?) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(autostart_err(e)),
+        _ => Ok(()),
+    }
+}
+
+#[cfg(target_os = "windows")]
+const RUN_KEY: &str = r"HKCU\Software\Microsoft\Windows\CurrentVersion\Run";
+
+#[cfg(target_os = "windows")]
+fn reg(args: &[&str]) -> Result<bool, ConfigError> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    std::process::Command::new("reg")
+        .args(args)
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map(|out| out.status.success())
+        .map_err(autostart_err)
+}
+
+#[cfg(target_os = "windows")]
+fn install() -> Result<(), ConfigError> {
+    let exe = format!("\"{}\"", current_exe()?.to_string_lossy());
+    if reg(&["add", RUN_KEY, "/v", APP_NAME, "/t", "REG_SZ", "/d", &exe, "/f"])? {
+        Ok(())
+    } else {
+        Err(autostart_err("failed to write the Run registry key"))
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn uninstall() -> Result<(), ConfigError> {
+    // Fails when the value doesn't exist, which is the state we want
+    if !reg(&["delete", RUN_KEY, "/v", APP_NAME, "/f"])? {
+        log::debug!("No autostart registry value to remove");
+    }
+    Ok(())
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn install() -> Result<(), ConfigError> {
+    Err(autostart_err("launch at login is not supported on this platform"))
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn uninstall() -> Result<(), ConfigError> {
+    Ok(())
+}
+
+#[cfg(all(test, any(target_os = "macos", target_os = "linux")))]
+mod tests {
+    use super::*;
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_entry_path_in() {
+        let path = entry_path_in(Some("/xdg".into()), Some("/home/me".into())).unwrap();
+        assert_eq!(path, PathBuf::from("/xdg/autostart/com.flowpaste.app.desktop"));
+        let path = entry_path_in(Some("".into()), Some("/home/me".into())).unwrap();
+        assert_eq!(path, PathBuf::from("/home/me/.config/autostart/com.flowpaste.app.desktop"));
+        assert!(entry_path_in(None, None).is_err());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_desktop_entry_quotes_exec() {
+        let entry = desktop_entry(std::path::Path::new("/opt/Flow Paste/flow\"paste"));
+        assert!(entry.starts_with("[Desktop Entry]\n"));
+        assert!(entry.contains("Exec=\"/opt/Flow Paste/flow\\\"paste\"\n"));
+    }
+
+    #[cfg(target_os = "macos")]
+    #[test]
+    fn test_launch_agent_escapes_path() {
+        let plist = launch_agent(std::path::Path::new("/Applications/A&B <1>.app/Contents/MacOS/flowpaste"));
+        assert!(plist.contains("<string>com.flowpaste.app</string>"));
+        assert!(plist.contains("<string>/Applications/A&amp;B &lt;1&gt;.app/Contents/MacOS/flowpaste</string>"));
+    }
+}
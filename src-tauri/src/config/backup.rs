@@ -0,0 +1,156 @@
+use chrono::{NaiveDateTime, Utc};
+use rusqlite::backup::{Backup, Progress};
+use rusqlite::{Connection, DatabaseName, OpenFlags};
+use serde::Serialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use super::{autostart, encryption, AppConfig, ConfigError, ConfigManager};
+
+/// Older backups beyond this many are deleted.
+const MAX_BACKUPS: usize = 10;
+const FILE_PREFIX: &str = "settings-";
+const TIMESTAMP_FORMAT: &str = "%Y%m%d-%H%M%S%3f";
+
+fn io_err(e: std::io::Error) -> ConfigError {
+    ConfigError::Io(e.to_string())
+}
+
+fn db_err(e: rusqlite::Error) -> ConfigError {
+    ConfigError::Database(e.to_string())
+}
+
+/// A snapshot of settings.db taken before it was changed.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigBackup {
+    /// File name without prefix and extension, e.g. `20250101-120000000-settings`
+    pub id: String,
+    /// RFC 3339, UTC
+    pub created_at: String,
+    /// What was about to change: "settings", "import", "profile", ...
+    pub reason: String,
+    pub size_bytes: u64,
+}
+
+impl ConfigBackup {
+    fn from_path(path: &Path) -> Option<Self> {
+        let id = path
+            .file_name()?
+            .to_str()?
+            .strip_prefix(FILE_PREFIX)?
+            .strip_suffix(".db")?
+            .to_string();
+        // `<date>-<time>-<reason>`
+        let mut parts = id.splitn(3, '-');
+        let stamp = format!("{}-{}", parts.next()?, parts.next()?);
+        let reason = parts.next()?.to_string();
+        let created_at = NaiveDateTime::parse_from_str(&stamp, TIMESTAMP_FORMAT)
+            .ok()?
+            .and_utc()
+            .to_rfc3339();
+        let size_bytes = fs::metadata(path).ok()?.len();
+        Some(Self {
+            id,
+            created_at,
+            reason,
+            size_bytes,
+        })
+    }
+}
+
+/// Copy the live database to a new backup file and drop the oldest ones.
+/// Uses SQLite's backup API, so pages still in the WAL are included.
+pub(super) fn create(conn: &Connection, dir: &Path, reason: &str) -> Result<(), ConfigError> {
+    fs::create_dir_all(dir).map_err(io_err)?;
+    let stamp = Utc::now().format(TIMESTAMP_FORMAT);
+    let path = dir.join(format!("{}{}-{}.db", FILE_PREFIX, stamp, reason));
+    conn.backup(DatabaseName::Main, &path, None::<fn(Progress)>)
+        .map_err(db_err)?;
+
+    for old in list(dir)?.into_iter().skip(MAX_BACKUPS) {
+        if let Err(e) = fs::remove_file(backup_path(dir, &old.id)) {
+            log::warn!("Failed to remove old config backup {}: {}", old.id, e);
+        }
+    }
+    Ok(())
+}
+
+/// Backups in `dir`, newest first.
+fn list(dir: &Path) -> Result<Vec<ConfigBackup>, ConfigError> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut backups: Vec<ConfigBackup> = fs::read_dir(dir)
+        .map_err(io_err)?
+        .filter_map(|entry| ConfigBackup::from_path(&entry.ok()?.path()))
+        .collect();
+    // Ids start with the timestamp, so they sort chronologically
+    backups.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(backups)
+}
+
+fn backup_path(dir: &Path, id: &str) -> PathBuf {
+    dir.join(format!("{}{}.db", FILE_PREFIX, id))
+}
+
+impl ConfigManager {
+    /// Back up before a write. A failed backup is logged rather than
+    /// blocking the change it precedes.
+    pub(super) fn backup(&self, conn: &Connection, reason: &str) {
+        if let Err(e) = create(conn, &self.backup_dir, reason) {
+            log::error!("Failed to back up settings before {}: {}", reason, e);
+        }
+    }
+
+    pub(super) fn remove_backups(&self) {
+        let backups = list(&self.backup_dir).unwrap_or_default();
+        for backup in backups {
+            if let Err(e) = fs::remove_file(backup_path(&self.backup_dir, &backup.id)) {
+                log::warn!("Failed to remove config backup {}: {}", backup.id, e);
+            }
+        }
+    }
+
+    pub fn list_backups(&self) -> Result<Vec<ConfigBackup>, ConfigError> {
+        list(&self.backup_dir)
+    }
+
+    /// Replace the database with backup `id`. The current state is backed up
+    /// first, so a restore can itself be undone. Returns the restored settings.
+    pub fn restore_backup(&self, id: &str) -> Result<AppConfig, ConfigError> {
+        // Only ids we listed, so `id` can't point outside the backup dir
+        if !self.list_backups()?.iter().any(|b| b.id == id) {
+            return Err(ConfigError::BackupNotFound(id.to_string()));
+        }
+
+        {
+            // Opened before the safety backup, whose rotation may remove `id`
+            let source = Connection::open_with_flags(
+                backup_path(&self.backup_dir, id),
+                OpenFlags::SQLITE_OPEN_READ_ONLY,
+            )
+            .map_err(db_err)?;
+            let mut conn = self.conn()?;
+            self.backup(&conn, "restore");
+            Backup::new(&source, &mut conn)
+                .and_then(|b| b.run_to_completion(100, Duration::from_millis(50), None))
+                .map_err(db_err)?;
+            // Backups from older versions may predate newer tables
+            super::init_schema(&mut conn, &self.backup_dir)?;
+
+            let cipher = encryption::load_cipher(&conn).unwrap_or_else(|e| {
+                log::error!("Failed to load database encryption key: {}", e);
+                None
+            });
+            *self.cipher.lock().map_err(|_| ConfigError::Crypto("cipher lock poisoned".into()))? = cipher;
+        }
+
+        let config = self.get_config()?;
+        if let Err(e) = autostart::set_enabled(config.autostart) {
+            log::warn!("Failed to restore launch at login: {}", e);
+        }
+        Ok(config)
+    }
+}
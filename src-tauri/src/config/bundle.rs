@@ -0,0 +1,223 @@
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    autostart, providers, write_settings, ActionHotkey, AppConfig, ConfigError, ConfigManager, HeadlessHotkey,
+    RuleHotkey,
+};
+use crate::ai::CustomIntent;
+use crate::regex::Rule;
+use crate::snippet::Snippet;
+
+/// Format version written by `export_bundle`; newer bundles are rejected.
+/// Version 2 keeps provider settings in `providers`; version 1 had flat
+/// `ollamaBaseUrl`/`openaiBaseUrl`/`modelName`/`privacyShield` keys.
+pub const BUNDLE_VERSION: u32 = 2;
+
+/// Tables a `Replace` import empties first.
+const BUNDLE_TABLES: &[&str] = &[
+    "custom_rules",
+    "rule_order",
+    "snippets",
+    "custom_intents",
+    "action_hotkeys",
+    "rule_hotkeys",
+    "headless_hotkeys",
+    "hotkey_exclusions",
+    "hotkey_pass_through",
+];
+
+fn db_err(e: rusqlite::Error) -> ConfigError {
+    ConfigError::Database(e.to_string())
+}
+
+/// Everything needed to set up another machine. API keys stay in the
+/// keyring and are never included.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigBundle {
+    pub version: u32,
+    pub settings: AppConfig,
+    #[serde(default)]
+    pub custom_rules: Vec<Rule>,
+    /// Rule ids, first shown first
+    #[serde(default)]
+    pub rule_order: Vec<String>,
+    #[serde(default)]
+    pub snippets: Vec<Snippet>,
+    #[serde(default)]
+    pub custom_intents: Vec<CustomIntent>,
+    #[serde(default)]
+    pub action_hotkeys: Vec<ActionHotkey>,
+    #[serde(default)]
+    pub rule_hotkeys: Vec<RuleHotkey>,
+    #[serde(default)]
+    pub headless_hotkeys: Vec<HeadlessHotkey>,
+    #[serde(default)]
+    pub hotkey_exclusions: Vec<String>,
+    #[serde(default)]
+    pub hotkey_pass_through: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportMode {
+    /// Add bundle entries, overwriting ones with the same id
+    Merge,
+    /// Drop existing entries first
+    Replace,
+}
+
+impl ConfigBundle {
+    /// Read a bundle written by any version of `export_bundle`, moving the
+    /// flat provider keys of version 1 settings into `providers`.
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let mut value: serde_json::Value = serde_json::from_str(json)?;
+        if value["version"].as_u64().is_some_and(|v| v < 2) {
+            providers::migrate_flat_json(&mut value["settings"]);
+        }
+        serde_json::from_value(value)
+    }
+}
+
+impl ConfigManager {
+    pub fn export_bundle(&self) -> Result<ConfigBundle, ConfigError> {
+        let mut order: Vec<(String, i32)> = self.get_rule_order()?.into_iter().collect();
+        order.sort_by_key(|(_, pos)| *pos);
+
+        Ok(ConfigBundle {
+            version: BUNDLE_VERSION,
+            settings: self.get_config()?,
+            custom_rules: self.get_custom_rules()?,
+            rule_order: order.into_iter().map(|(id, _)| id).collect(),
+            snippets: self.get_snippets()?,
+            custom_intents: self.get_custom_intents()?,
+            action_hotkeys: self.get_action_hotkeys()?,
+            rule_hotkeys: self.get_rule_hotkeys()?,
+            headless_hotkeys: self.get_headless_hotkeys()?,
+            hotkey_exclusions: self.get_hotkey_exclusions()?,
+            hotkey_pass_through: self.get_hotkey_pass_through()?,
+        })
+    }
+
+    /// Write a bundle that has already been validated, in one transaction so
+    /// a failure leaves the current data as it was.
+    pub fn import_bundle(&self, bundle: &ConfigBundle, mode: ImportMode) -> Result<(), ConfigError> {
+        bundle.settings.validate()?;
+        // Encoded before the database is locked; sensitive columns may be encrypted
+        let rules = bundle
+            .custom_rules
+            .iter()
+            .map(|r| Ok((&r.id, self.encode(r)?)))
+            .collect::<Result<Vec<_>, ConfigError>>()?;
+        let snippets = bundle
+            .snippets
+            .iter()
+            .map(|s| Ok((&s.id, self.encode(s)?)))
+            .collect::<Result<Vec<_>, ConfigError>>()?;
+        let intents = bundle
+            .custom_intents
+            .iter()
+            .map(|i| Ok((&i.id, self.encode(i)?)))
+            .collect::<Result<Vec<_>, ConfigError>>()?;
+        let pipelines = bundle
+            .headless_hotkeys
+            .iter()
+            .map(|h| self.encode(&h.pipeline))
+            .collect::<Result<Vec<_>, ConfigError>>()?;
+        autostart::set_enabled(bundle.settings.autostart)?;
+
+        let mut conn = self.conn()?;
+        self.backup(&conn, "import");
+        let tx = conn.transaction().map_err(db_err)?;
+        if mode == ImportMode::Replace {
+            for table in BUNDLE_TABLES {
+                tx.execute(&format!("DELETE FROM {}", table), []).map_err(db_err)?;
+            }
+        }
+
+        write_settings(&tx, &bundle.settings)?;
+        for (id, data) in rules {
+            tx.execute("INSERT OR REPLACE INTO custom_rules (id, data) VALUES (?1, ?2)", params![id, data])
+                .map_err(db_err)?;
+        }
+        if !bundle.rule_order.is_empty() {
+            tx.execute("DELETE FROM rule_order", []).map_err(db_err)?;
+            for (pos, id) in bundle.rule_order.iter().enumerate() {
+                tx.execute(
+                    "INSERT OR REPLACE INTO rule_order (rule_id, sort_order) VALUES (?1, ?2)",
+                    params![id, pos as i32],
+                )
+                .map_err(db_err)?;
+            }
+        }
+        for (id, data) in snippets {
+            tx.execute("INSERT OR REPLACE INTO snippets (id, data) VALUES (?1, ?2)", params![id, data])
+                .map_err(db_err)?;
+        }
+        for (id, data) in intents {
+            tx.execute(
+                "INSERT INTO custom_intents (id, data) VALUES (?1, ?2)
+                 ON CONFLICT(id) DO UPDATE SET data = ?2",
+                params![id, data],
+            )
+            .map_err(db_err)?;
+        }
+        for binding in &bundle.action_hotkeys {
+            tx.execute(
+                "INSERT OR REPLACE INTO action_hotkeys (action, hotkey) VALUES (?1, ?2)",
+                params![binding.action, binding.hotkey],
+            )
+            .map_err(db_err)?;
+        }
+        for binding in &bundle.rule_hotkeys {
+            let rule_ids = serde_json::to_string(&binding.rule_ids).map_err(|e| ConfigError::Database(e.to_string()))?;
+            tx.execute(
+                "INSERT OR REPLACE INTO rule_hotkeys (id, hotkey, rule_ids) VALUES (?1, ?2, ?3)",
+                params![binding.id, binding.hotkey, rule_ids],
+            )
+            .map_err(db_err)?;
+        }
+        for (binding, pipeline) in bundle.headless_hotkeys.iter().zip(pipelines) {
+            tx.execute(
+                "INSERT OR REPLACE INTO headless_hotkeys (id, hotkey, pipeline) VALUES (?1, ?2, ?3)",
+                params![binding.id, binding.hotkey, pipeline],
+            )
+            .map_err(db_err)?;
+        }
+        for app in bundle.hotkey_exclusions.iter().map(|a| a.trim()).filter(|a| !a.is_empty()) {
+            tx.execute("INSERT OR IGNORE INTO hotkey_exclusions (app) VALUES (?1)", params![app])
+                .map_err(db_err)?;
+        }
+        for binding in &bundle.hotkey_pass_through {
+            tx.execute("INSERT OR IGNORE INTO hotkey_pass_through (binding) VALUES (?1)", params![binding])
+                .map_err(db_err)?;
+        }
+
+        tx.commit().map_err(db_err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_json_migrates_v1_settings() {
+        let json = r#"{
+            "version": 1,
+            "settings": {
+                "hotkey": "CommandOrControl+Shift+V",
+                "aiProvider": "Ollama",
+                "theme": "system",
+                "ollamaBaseUrl": "http://gpu-box:11434",
+                "modelName": "qwen2.5",
+                "privacyShield": false
+            }
+        }"#;
+        let bundle = ConfigBundle::from_json(json).unwrap();
+        let ollama = bundle.settings.provider("Ollama").unwrap();
+        assert_eq!((ollama.base_url.as_str(), ollama.model.as_str()), ("http://gpu-box:11434", "qwen2.5"));
+        assert!(!bundle.settings.provider("OpenAI").unwrap().privacy_shield);
+    }
+}
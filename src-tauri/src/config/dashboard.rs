@@ -0,0 +1,214 @@
+use chrono::NaiveDate;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::HashMap;
+
+use super::usage_stats::{self, DailyUsage, UsageCounts, UsagePeriod};
+use super::{ConfigError, ConfigManager};
+use crate::ai::AIProviderType;
+use crate::regex;
+
+/// Rules listed in the dashboard, most used first.
+const TOP_RULES: usize = 10;
+
+pub(super) fn init_tables(conn: &Connection) -> rusqlite::Result<()> {
+    // `rule_usage` keeps all-time counts for ranking; this one is per day
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS rule_usage_daily (
+            day TEXT NOT NULL,
+            rule_id TEXT NOT NULL,
+            count INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (day, rule_id)
+        )",
+        [],
+    )?;
+    // Token counts are estimated from the text; providers don't all report them
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS ai_token_usage (
+            day TEXT NOT NULL,
+            provider TEXT NOT NULL,
+            model TEXT NOT NULL,
+            requests INTEGER NOT NULL DEFAULT 0,
+            prompt_tokens INTEGER NOT NULL DEFAULT 0,
+            completion_tokens INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (day, provider, model)
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleCount {
+    pub id: String,
+    /// Display name, or the id for rules that no longer exist
+    pub name: String,
+    pub count: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiTokenUsage {
+    /// `Ollama` or `OpenAI`
+    pub provider: String,
+    pub model: String,
+    pub requests: u64,
+    /// Estimated, see `text_stats`
+    pub prompt_tokens: u64,
+    pub completion_tokens: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardStats {
+    pub period: UsagePeriod,
+    pub totals: UsageCounts,
+    /// Every day of the period, oldest first, including days without activity
+    pub days: Vec<DailyUsage>,
+    pub top_rules: Vec<RuleCount>,
+    /// Per provider and model, most tokens first
+    pub ai_tokens: Vec<AiTokenUsage>,
+}
+
+fn db_err(e: rusqlite::Error) -> ConfigError {
+    ConfigError::Database(e.to_string())
+}
+
+fn provider_name(provider: AIProviderType) -> &'static str {
+    match provider {
+        AIProviderType::OpenAI => "OpenAI",
+        AIProviderType::Ollama => "Ollama",
+    }
+}
+
+/// `days` with a zero entry for each missing day from `first` to `last`.
+fn fill_days(days: Vec<DailyUsage>, first: NaiveDate, last: NaiveDate) -> Vec<DailyUsage> {
+    let mut by_day: HashMap<String, UsageCounts> = days.into_iter().map(|d| (d.day, d.counts)).collect();
+    first
+        .iter_days()
+        .take_while(|day| *day <= last)
+        .map(|day| {
+            let day = day.format("%Y-%m-%d").to_string();
+            let counts = by_day.remove(&day).unwrap_or_default();
+            DailyUsage { day, counts }
+        })
+        .collect()
+}
+
+impl ConfigManager {
+    /// Count one use of a rule today, unless usage stats are off.
+    pub(super) fn count_rule_use(&self, rule_id: &str) -> Result<(), ConfigError> {
+        let conn = self.conn()?;
+        if !usage_stats::stats_enabled(&conn)? {
+            return Ok(());
+        }
+        conn.execute(
+            "INSERT INTO rule_usage_daily (day, rule_id, count) VALUES (?1, ?2, 1)
+             ON CONFLICT(day, rule_id) DO UPDATE SET count = count + 1",
+            params![usage_stats::today(), rule_id],
+        )
+        .map_err(db_err)?;
+        Ok(())
+    }
+
+    /// Count one AI request and its estimated tokens today, unless usage
+    /// stats are off. Failures are logged, never returned.
+    pub fn track_ai_tokens(&self, provider: AIProviderType, model: &str, prompt_tokens: usize, completion_tokens: usize) {
+        let result = self.conn().and_then(|conn| {
+            if !usage_stats::stats_enabled(&conn)? {
+                return Ok(());
+            }
+            conn.execute(
+                "INSERT INTO ai_token_usage (day, provider, model, requests, prompt_tokens, completion_tokens)
+                 VALUES (?1, ?2, ?3, 1, ?4, ?5)
+                 ON CONFLICT(day, provider, model) DO UPDATE SET
+                    requests = requests + 1,
+                    prompt_tokens = prompt_tokens + ?4,
+                    completion_tokens = completion_tokens + ?5",
+                params![
+                    usage_stats::today(),
+                    provider_name(provider),
+                    model,
+                    prompt_tokens as i64,
+                    completion_tokens as i64
+                ],
+            )
+            .map_err(db_err)?;
+            Ok(())
+        });
+        if let Err(e) = result {
+            log::warn!("Failed to record AI token usage: {}", e);
+        }
+    }
+
+    /// Everything the stats screen shows for `period`.
+    pub fn get_dashboard_stats(&self, period: UsagePeriod) -> Result<DashboardStats, ConfigError> {
+        let usage = self.get_usage_stats(period)?;
+        let since = usage_stats::since(period);
+        let today = chrono::Local::now().date_naive();
+        let first = match period {
+            UsagePeriod::All => usage
+                .days
+                .first()
+                .and_then(|d| NaiveDate::parse_from_str(&d.day, "%Y-%m-%d").ok())
+                .unwrap_or(today),
+            _ => NaiveDate::parse_from_str(&since, "%Y-%m-%d").unwrap_or(today),
+        };
+        let days = fill_days(usage.days, first, today);
+
+        let names: HashMap<String, String> = regex::get_builtin_rules()
+            .into_iter()
+            .chain(self.get_custom_rules()?)
+            .map(|rule| (rule.id, rule.name))
+            .collect();
+
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT rule_id, SUM(count) AS total FROM rule_usage_daily
+                 WHERE day >= ?1 GROUP BY rule_id ORDER BY total DESC, rule_id LIMIT ?2",
+            )
+            .map_err(db_err)?;
+        let top_rules = stmt
+            .query_map(params![since, TOP_RULES as i64], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, i64>(1)?))
+            })
+            .map_err(db_err)?
+            .map(|row| {
+                let (id, count) = row.map_err(db_err)?;
+                let name = names.get(&id).cloned().unwrap_or_else(|| id.clone());
+                Ok(RuleCount { id, name, count: count.max(0) as u64 })
+            })
+            .collect::<Result<Vec<_>, ConfigError>>()?;
+
+        let mut stmt = conn
+            .prepare(
+                "SELECT provider, model, SUM(requests), SUM(prompt_tokens), SUM(completion_tokens)
+                 FROM ai_token_usage WHERE day >= ?1 GROUP BY provider, model
+                 ORDER BY SUM(prompt_tokens) + SUM(completion_tokens) DESC",
+            )
+            .map_err(db_err)?;
+        let ai_tokens = stmt
+            .query_map(params![since], |row| {
+                Ok(AiTokenUsage {
+                    provider: row.get(0)?,
+                    model: row.get(1)?,
+                    requests: row.get::<_, i64>(2)?.max(0) as u64,
+                    prompt_tokens: row.get::<_, i64>(3)?.max(0) as u64,
+                    completion_tokens: row.get::<_, i64>(4)?.max(0) as u64,
+                })
+            })
+            .map_err(db_err)?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(db_err)?;
+
+        Ok(DashboardStats {
+            period,
+            totals: usage.totals,
+            days,
+            top_rules,
+            ai_tokens,
+        })
+    }
+}
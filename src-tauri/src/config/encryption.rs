@@ -0,0 +1,305 @@
+use aes_gcm::aead::{Aead, AeadCore, KeyInit, OsRng};
+use aes_gcm::{Aes256Gcm, Key, Nonce};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use keyring::Entry;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use super::{ConfigError, ConfigManager, SERVICE_NAME};
+
+const KEY_ENTRY: &str = "settings-db-key";
+const ENABLED_KEY: &str = "encryptAtRest";
+/// Prefix of encrypted values; plaintext values are JSON and never start with it.
+const PREFIX: &str = "enc:v1:";
+const NONCE_LEN: usize = 12;
+
+/// (table, key column, value column) holding user content: rule patterns,
+/// snippet templates, AI prompts, webhook requests and workflows.
+const ENCRYPTED_COLUMNS: &[(&str, &str, &str)] = &[
+    ("custom_rules", "id", "data"),
+    ("snippets", "id", "data"),
+    ("custom_intents", "id", "data"),
+    ("headless_hotkeys", "id", "pipeline"),
+    ("webhooks", "id", "data"),
+    ("workflows", "id", "data"),
+];
+
+fn crypto_err(e: impl ToString) -> ConfigError {
+    ConfigError::Crypto(e.to_string())
+}
+
+fn db_err(e: rusqlite::Error) -> ConfigError {
+    ConfigError::Database(e.to_string())
+}
+
+/// AES-256-GCM over single column values, keyed from the OS keyring.
+#[derive(Clone)]
+pub(super) struct FieldCipher {
+    cipher: Aes256Gcm,
+}
+
+impl FieldCipher {
+    fn new(key: &[u8]) -> Result<Self, ConfigError> {
+        if key.len() != 32 {
+            return Err(crypto_err("stored key has the wrong length"));
+        }
+        Ok(Self {
+            cipher: Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key)),
+        })
+    }
+
+    /// Load the key from the keyring, creating one if `create` is set.
+    fn from_keyring(create: bool) -> Result<Self, ConfigError> {
+        let entry = Entry::new(SERVICE_NAME, KEY_ENTRY).map_err(|e| ConfigError::Keyring(e.to_string()))?;
+        match entry.get_password() {
+            Ok(encoded) => Self::new(&BASE64.decode(encoded).map_err(crypto_err)?),
+            Err(keyring::Error::NoEntry) if create => {
+                let key = Aes256Gcm::generate_key(OsRng);
+                entry
+                    .set_password(&BASE64.encode(key))
+                    .map_err(|e| ConfigError::Keyring(e.to_string()))?;
+                Self::new(&key)
+            }
+            Err(keyring::Error::NoEntry) => Err(ConfigError::KeyMissing),
+            Err(e) => Err(ConfigError::Keyring(e.to_string())),
+        }
+    }
+
+    fn seal(&self, plain: &str) -> Result<String, ConfigError> {
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let mut out = nonce.to_vec();
+        out.extend(self.cipher.encrypt(&nonce, plain.as_bytes()).map_err(crypto_err)?);
+        Ok(format!("{}{}", PREFIX, BASE64.encode(out)))
+    }
+
+    fn open(&self, sealed: &str) -> Result<String, ConfigError> {
+        let raw = BASE64.decode(sealed).map_err(crypto_err)?;
+        if raw.len() < NONCE_LEN {
+            return Err(crypto_err("encrypted value is truncated"));
+        }
+        let (nonce, data) = raw.split_at(NONCE_LEN);
+        let plain = self
+            .cipher
+            .decrypt(Nonce::from_slice(nonce), data)
+            .map_err(|_| crypto_err("failed to decrypt value"))?;
+        String::from_utf8(plain).map_err(crypto_err)
+    }
+}
+
+/// Whether the stored data is encrypted, independent of the key being loadable.
+fn stored_enabled(conn: &Connection) -> Result<bool, ConfigError> {
+    let enabled: Option<String> = conn
+        .query_row("SELECT value FROM settings WHERE key = ?1", params![ENABLED_KEY], |row| row.get(0))
+        .optional()
+        .map_err(db_err)?;
+    Ok(enabled.as_deref() == Some("true"))
+}
+
+/// Cipher to use at startup, or `None` when encryption is off. Also keeps
+/// overwritten rows from lingering in free pages while encryption is on.
+pub(super) fn load_cipher(conn: &Connection) -> Result<Option<FieldCipher>, ConfigError> {
+    if !stored_enabled(conn)? {
+        return Ok(None);
+    }
+    conn.pragma_update(None, "secure_delete", true).map_err(db_err)?;
+    FieldCipher::from_keyring(false).map(Some)
+}
+
+/// Rewrite every sensitive value with `cipher` (or as plaintext when `None`).
+/// Values already in the target form pass through unchanged.
+fn migrate(conn: &Connection, from: Option<&FieldCipher>, to: Option<&FieldCipher>) -> Result<(), ConfigError> {
+    for (table, key_col, value_col) in ENCRYPTED_COLUMNS {
+        let rows: Vec<(String, String)> = {
+            let mut stmt = conn
+                .prepare(&format!("SELECT {}, {} FROM {}", key_col, value_col, table))
+                .map_err(db_err)?;
+            let rows = stmt
+                .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+                .map_err(db_err)?;
+            rows.collect::<Result<_, _>>().map_err(db_err)?
+        };
+
+        for (id, stored) in rows {
+            let plain = open_with(from, &stored)?;
+            let value = match to {
+                Some(cipher) => cipher.seal(&plain)?,
+                None => plain,
+            };
+            conn.execute(
+                &format!("UPDATE {} SET {} = ?1 WHERE {} = ?2", table, value_col, key_col),
+                params![value, id],
+            )
+            .map_err(db_err)?;
+        }
+    }
+    Ok(())
+}
+
+fn open_with(cipher: Option<&FieldCipher>, stored: &str) -> Result<String, ConfigError> {
+    match (stored.strip_prefix(PREFIX), cipher) {
+        (None, _) => Ok(stored.to_string()),
+        (Some(sealed), Some(cipher)) => cipher.open(sealed),
+        (Some(_), None) => Err(ConfigError::KeyMissing),
+    }
+}
+
+impl ConfigManager {
+    fn cipher(&self) -> Result<Option<FieldCipher>, ConfigError> {
+        self.cipher
+            .lock()
+            .map(|c| c.clone())
+            .map_err(|_| crypto_err("cipher lock poisoned"))
+    }
+
+    /// Serialize `value` for a sensitive column, encrypting it if enabled.
+    pub(super) fn encode<T: Serialize>(&self, value: &T) -> Result<String, ConfigError> {
+        let json = serde_json::to_string(value).map_err(|e| ConfigError::Database(e.to_string()))?;
+        match self.cipher()? {
+            Some(cipher) => cipher.seal(&json),
+            None => Ok(json),
+        }
+    }
+
+    /// Read a value written by `encode`. Plaintext from before encryption was
+    /// turned on is still accepted.
+    pub(super) fn decode<T: DeserializeOwned>(&self, stored: &str) -> Result<T, ConfigError> {
+        let json = open_with(self.cipher()?.as_ref(), stored)?;
+        serde_json::from_str(&json).map_err(|e| ConfigError::Database(e.to_string()))
+    }
+
+    /// Read from the stored flag, so a lost key still reports encrypted data.
+    pub fn is_encrypted_at_rest(&self) -> Result<bool, ConfigError> {
+        stored_enabled(&*self.conn()?)
+    }
+
+    /// Turn encryption of rules, snippets and prompts on or off, rewriting
+    /// existing rows in one transaction. Afterwards the database is vacuumed
+    /// so no copy of the previous form is left in free pages or the WAL.
+    pub fn set_encrypted_at_rest(&self, enabled: bool) -> Result<(), ConfigError> {
+        let mut conn = self.conn()?;
+        if stored_enabled(&conn)? == enabled {
+            return Ok(());
+        }
+        // Rows sealed with a key that failed to load at startup can't be
+        // rewritten; turning encryption off must not drop them silently
+        let current = self.cipher()?;
+        if !enabled && current.is_none() {
+            return Err(ConfigError::KeyMissing);
+        }
+
+        let next = if enabled { Some(FieldCipher::from_keyring(true)?) } else { None };
+
+        let tx = conn.transaction().map_err(db_err)?;
+        migrate(&tx, current.as_ref(), next.as_ref())?;
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+            params![ENABLED_KEY, enabled.to_string()],
+        )
+        .map_err(db_err)?;
+        tx.commit().map_err(db_err)?;
+
+        *self.cipher.lock().map_err(|_| crypto_err("cipher lock poisoned"))? = next;
+
+        conn.pragma_update(None, "secure_delete", enabled).map_err(db_err)?;
+        conn.execute_batch("VACUUM; PRAGMA wal_checkpoint(TRUNCATE);")
+            .map_err(db_err)?;
+
+        // Backups hold rows in the previous form: plaintext, or sealed with a
+        // key that is about to be deleted
+        self.remove_backups();
+
+        if !enabled {
+            let entry = Entry::new(SERVICE_NAME, KEY_ENTRY).map_err(|e| ConfigError::Keyring(e.to_string()))?;
+            match entry.delete_credential() {
+                Ok(()) | Err(keyring::Error::NoEntry) => {}
+                Err(e) => log::warn!("Failed to remove database key from keyring: {}", e),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cipher(byte: u8) -> FieldCipher {
+        FieldCipher::new(&[byte; 32]).unwrap()
+    }
+
+    #[test]
+    fn test_seal_open_round_trip() {
+        let cipher = cipher(7);
+        let sealed = cipher.seal(r#"{"id":"sig","template":"Best, {{name}}"}"#).unwrap();
+        assert!(sealed.starts_with(PREFIX));
+        assert!(!sealed.contains("template"));
+        assert_eq!(cipher.open(&sealed[PREFIX.len()..]).unwrap(), r#"{"id":"sig","template":"Best, {{name}}"}"#);
+        // A fresh nonce every time
+        assert_ne!(cipher.seal("x").unwrap(), cipher.seal("x").unwrap());
+    }
+
+    #[test]
+    fn test_open_rejects_bad_input() {
+        let sealed = cipher(7).seal("secret").unwrap();
+        assert!(cipher(8).open(&sealed[PREFIX.len()..]).is_err());
+        assert!(cipher(7).open("AAAA").is_err());
+        assert!(cipher(7).open("not base64!").is_err());
+        assert!(FieldCipher::new(&[0; 16]).is_err());
+    }
+
+    #[test]
+    fn test_open_with_prefix() {
+        let cipher = cipher(7);
+        let sealed = cipher.seal("[1,2]").unwrap();
+        assert_eq!(open_with(Some(&cipher), &sealed).unwrap(), "[1,2]");
+        // Plaintext from before encryption was turned on
+        assert_eq!(open_with(Some(&cipher), "[1,2]").unwrap(), "[1,2]");
+        assert_eq!(open_with(None, "[1,2]").unwrap(), "[1,2]");
+        assert!(matches!(open_with(None, &sealed), Err(ConfigError::KeyMissing)));
+    }
+
+    #[test]
+    fn test_stored_enabled() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch("CREATE TABLE settings (key TEXT PRIMARY KEY, value TEXT NOT NULL)")
+            .unwrap();
+        assert!(!stored_enabled(&conn).unwrap());
+        conn.execute("INSERT INTO settings (key, value) VALUES (?1, 'true')", params![ENABLED_KEY])
+            .unwrap();
+        assert!(stored_enabled(&conn).unwrap());
+        conn.execute("UPDATE settings SET value = 'false' WHERE key = ?1", params![ENABLED_KEY])
+            .unwrap();
+        assert!(!stored_enabled(&conn).unwrap());
+    }
+
+    #[test]
+    fn test_migrate_plaintext_rows() {
+        let conn = Connection::open_in_memory().unwrap();
+        for (table, key_col, value_col) in ENCRYPTED_COLUMNS {
+            conn.execute_batch(&format!(
+                "CREATE TABLE {} ({} TEXT PRIMARY KEY, {} TEXT NOT NULL)",
+                table, key_col, value_col
+            ))
+            .unwrap();
+        }
+        conn.execute("INSERT INTO snippets (id, data) VALUES ('a', '{\"t\":1}')", []).unwrap();
+        conn.execute("INSERT INTO webhooks (id, data) VALUES ('b', '{\"u\":2}')", []).unwrap();
+        let stored = |table: &str, id: &str| -> String {
+            conn.query_row(&format!("SELECT data FROM {} WHERE id = ?1", table), params![id], |row| row.get(0))
+                .unwrap()
+        };
+
+        let cipher = cipher(7);
+        migrate(&conn, None, Some(&cipher)).unwrap();
+        let sealed = stored("snippets", "a");
+        assert!(sealed.starts_with(PREFIX));
+        assert_eq!(open_with(Some(&cipher), &sealed).unwrap(), "{\"t\":1}");
+        assert!(stored("webhooks", "b").starts_with(PREFIX));
+
+        migrate(&conn, Some(&cipher), None).unwrap();
+        assert_eq!(stored("snippets", "a"), "{\"t\":1}");
+        assert_eq!(stored("webhooks", "b"), "{\"u\":2}");
+    }
+}
@@ -0,0 +1,126 @@
+use std::env;
+
+use super::AppConfig;
+
+impl AppConfig {
+    /// These settings with `FLOWPASTE_*` environment variables layered
+    /// over them, for managed or CI machines that are provisioned without
+    /// touching the DB. Only for use at runtime: the result must never be
+    /// stored, saved in a profile or exported.
+    pub fn with_env_overrides(mut self) -> Self {
+        apply_overrides(&mut self, |name| env::var(name).ok());
+        self
+    }
+}
+
+/// Values that don't parse are logged and ignored.
+fn apply_overrides(config: &mut AppConfig, lookup: impl Fn(&str) -> Option<String>) {
+    let var = |name: &str| lookup(name).filter(|v| !v.trim().is_empty());
+    let flag = |name: &str| {
+        var(name).and_then(|v| match v.trim().to_ascii_lowercase().as_str() {
+            "1" | "true" | "yes" | "on" => Some(true),
+            "0" | "false" | "no" | "off" => Some(false),
+            _ => {
+                log::warn!("Ignoring {}: expected true or false, got '{}'", name, v);
+                None
+            }
+        })
+    };
+
+    let strings = [
+        ("FLOWPASTE_HOTKEY", &mut config.hotkey),
+        ("FLOWPASTE_PROVIDER", &mut config.ai_provider),
+        ("FLOWPASTE_THEME", &mut config.theme),
+        ("FLOWPASTE_PANEL_MODE", &mut config.panel_mode),
+        ("FLOWPASTE_PROXY", &mut config.proxy_url),
+        ("FLOWPASTE_NO_PROXY", &mut config.no_proxy),
+        ("FLOWPASTE_CA_CERT", &mut config.ca_cert_path),
+        ("FLOWPASTE_LOCALE", &mut config.locale),
+        ("FLOWPASTE_LOG_LEVEL", &mut config.log_level),
+        ("FLOWPASTE_SHIELD_POLICY", &mut config.shield_policy),
+    ];
+    for (name, field) in strings {
+        if let Some(value) = var(name) {
+            *field = value.trim().to_string();
+        }
+    }
+
+    let flags = [
+        ("FLOWPASTE_AUTO_PASTE", &mut config.auto_paste),
+        ("FLOWPASTE_SHOW_AT_CURSOR", &mut config.show_at_cursor),
+        ("FLOWPASTE_LOG_TO_FILE", &mut config.log_to_file),
+    ];
+    for (name, field) in flags {
+        if let Some(value) = flag(name) {
+            *field = value;
+        }
+    }
+
+    // Provider settings apply to the named provider, or the active one
+    for (name, provider) in [("FLOWPASTE_OLLAMA_URL", "Ollama"), ("FLOWPASTE_OPENAI_URL", "OpenAI")] {
+        if let (Some(value), Some(entry)) = (var(name), config.provider_mut(provider)) {
+            entry.base_url = value.trim().to_string();
+        }
+    }
+    let active = config.ai_provider.clone();
+    let model = var("FLOWPASTE_MODEL");
+    let shield = flag("FLOWPASTE_PRIVACY_SHIELD");
+    if let Some(entry) = config.provider_mut(&active) {
+        if let Some(model) = model {
+            entry.model = model.trim().to_string();
+        }
+        if let Some(shield) = shield {
+            entry.privacy_shield = shield;
+        }
+    }
+
+    if let Some(value) = var("FLOWPASTE_HOTKEY_DEBOUNCE_MS") {
+        match value.trim().parse() {
+            Ok(ms) => config.hotkey_debounce_ms = ms,
+            Err(_) => log::warn!("Ignoring FLOWPASTE_HOTKEY_DEBOUNCE_MS: '{}' is not a number", value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn overridden(vars: &[(&str, &str)]) -> AppConfig {
+        let vars: HashMap<String, String> = vars.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect();
+        let mut config = AppConfig::default();
+        apply_overrides(&mut config, |name| vars.get(name).cloned());
+        config
+    }
+
+    #[test]
+    fn test_apply_overrides() {
+        let config = overridden(&[
+            ("FLOWPASTE_THEME", " dark "),
+            ("FLOWPASTE_AUTO_PASTE", "yes"),
+            ("FLOWPASTE_PROVIDER", "OpenAI"),
+            ("FLOWPASTE_MODEL", "gpt-4o"),
+            ("FLOWPASTE_OLLAMA_URL", "http://gpu-box:11434"),
+            ("FLOWPASTE_HOTKEY_DEBOUNCE_MS", "250"),
+        ]);
+        assert_eq!(config.theme, "dark");
+        assert!(config.auto_paste);
+        assert_eq!(config.hotkey_debounce_ms, 250);
+        assert_eq!(config.provider("OpenAI").unwrap().model, "gpt-4o");
+        assert_eq!(config.provider("Ollama").unwrap().base_url, "http://gpu-box:11434");
+    }
+
+    #[test]
+    fn test_apply_overrides_ignores_bad_values() {
+        let defaults = AppConfig::default();
+        let config = overridden(&[
+            ("FLOWPASTE_THEME", "  "),
+            ("FLOWPASTE_AUTO_PASTE", "maybe"),
+            ("FLOWPASTE_HOTKEY_DEBOUNCE_MS", "soon"),
+        ]);
+        assert_eq!(config.theme, defaults.theme);
+        assert_eq!(config.auto_paste, defaults.auto_paste);
+        assert_eq!(config.hotkey_debounce_ms, defaults.hotkey_debounce_ms);
+    }
+}
@@ -0,0 +1,83 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde_json::Value;
+
+use super::{ConfigError, ConfigManager};
+
+/// Larger values belong in a dedicated table, not a preference.
+const MAX_VALUE_BYTES: usize = 64 * 1024;
+
+pub(super) fn init_tables(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS extension_settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn db_err(e: rusqlite::Error) -> ConfigError {
+    ConfigError::Database(e.to_string())
+}
+
+/// Keys are `namespace.name`, e.g. `panel.layout`, each part made of
+/// letters, digits, `_` and `-`, so features can't overwrite each other
+/// by accident.
+fn check_key(key: &str) -> Result<(), ConfigError> {
+    let valid_part = |part: &str| {
+        !part.is_empty() && part.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+    };
+    match key.split_once('.') {
+        Some((namespace, name)) if valid_part(namespace) && name.split('.').all(valid_part) => Ok(()),
+        _ => Err(ConfigError::InvalidSetting(format!(
+            "'{}' must look like namespace.name",
+            key
+        ))),
+    }
+}
+
+impl ConfigManager {
+    /// Frontend preferences that don't warrant an `AppConfig` field.
+    pub fn get_setting(&self, key: &str) -> Result<Option<Value>, ConfigError> {
+        check_key(key)?;
+        let stored: Option<String> = self
+            .conn()?
+            .query_row(
+                "SELECT value FROM extension_settings WHERE key = ?1",
+                params![key],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_err)?;
+
+        stored
+            .map(|s| serde_json::from_str(&s).map_err(|e| ConfigError::Database(e.to_string())))
+            .transpose()
+    }
+
+    /// Store `value` under `key`; `null` removes it.
+    pub fn set_setting(&self, key: &str, value: &Value) -> Result<(), ConfigError> {
+        check_key(key)?;
+        let conn = self.conn()?;
+        if value.is_null() {
+            conn.execute("DELETE FROM extension_settings WHERE key = ?1", params![key])
+                .map_err(db_err)?;
+            return Ok(());
+        }
+
+        let json = value.to_string();
+        if json.len() > MAX_VALUE_BYTES {
+            return Err(ConfigError::InvalidSetting(format!(
+                "value for '{}' is larger than {} bytes",
+                key, MAX_VALUE_BYTES
+            )));
+        }
+        conn.execute(
+            "INSERT OR REPLACE INTO extension_settings (key, value) VALUES (?1, ?2)",
+            params![key, json],
+        )
+        .map_err(db_err)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use super::{ConfigError, ConfigManager};
+
+pub(super) fn init_tables(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS action_hotkeys (
+            action TEXT PRIMARY KEY,
+            hotkey TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS hotkey_exclusions (
+            app TEXT PRIMARY KEY
+        );
+        CREATE TABLE IF NOT EXISTS hotkey_pass_through (
+            binding TEXT PRIMARY KEY
+        );
+        CREATE TABLE IF NOT EXISTS headless_hotkeys (
+            id TEXT PRIMARY KEY,
+            hotkey TEXT NOT NULL,
+            pipeline TEXT NOT NULL
+        );",
+    )
+}
+
+/// Accelerator for a built-in hotkey action (the panel toggle lives in `AppConfig`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionHotkey {
+    pub action: String,
+    pub hotkey: String,
+}
+
+/// A transform run entirely in the backend: rules first, then an optional
+/// AI prompt. The panel is never shown.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeadlessPipeline {
+    #[serde(default)]
+    pub rule_ids: Vec<String>,
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// Copy the focused app's selection first instead of using the clipboard
+    #[serde(default = "default_true")]
+    pub capture_selection: bool,
+    /// Paste the result back into the focused app
+    #[serde(default = "default_true")]
+    pub paste: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// A global hotkey that runs a `HeadlessPipeline`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HeadlessHotkey {
+    pub id: String,
+    pub hotkey: String,
+    pub pipeline: HeadlessPipeline,
+}
+
+fn db_err(e: rusqlite::Error) -> ConfigError {
+    ConfigError::Database(e.to_string())
+}
+
+impl ConfigManager {
+    pub fn get_action_hotkeys(&self) -> Result<Vec<ActionHotkey>, ConfigError> {
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare("SELECT action, hotkey FROM action_hotkeys ORDER BY action")
+            .map_err(db_err)?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(ActionHotkey {
+                    action: row.get(0)?,
+                    hotkey: row.get(1)?,
+                })
+            })
+            .map_err(db_err)?;
+
+        rows.collect::<Result<Vec<_>, _>>().map_err(db_err)
+    }
+
+    pub fn save_action_hotkey(&self, binding: &ActionHotkey) -> Result<(), ConfigError> {
+        self.conn()?
+            .execute(
+                "INSERT OR REPLACE INTO action_hotkeys (action, hotkey) VALUES (?1, ?2)",
+                params![binding.action, binding.hotkey],
+            )
+            .map_err(db_err)?;
+        Ok(())
+    }
+
+    pub fn delete_action_hotkey(&self, action: &str) -> Result<(), ConfigError> {
+        self.conn()?
+            .execute("DELETE FROM action_hotkeys WHERE action = ?1", params![action])
+            .map_err(db_err)?;
+        Ok(())
+    }
+
+    /// Applications (name or executable) in which global hotkeys are ignored.
+    pub fn get_hotkey_exclusions(&self) -> Result<Vec<String>, ConfigError> {
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare("SELECT app FROM hotkey_exclusions ORDER BY app")
+            .map_err(db_err)?;
+
+        let rows = stmt.query_map([], |row| row.get(0)).map_err(db_err)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(db_err)
+    }
+
+    pub fn set_hotkey_exclusions(&self, apps: &[String]) -> Result<(), ConfigError> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction().map_err(db_err)?;
+        tx.execute("DELETE FROM hotkey_exclusions", []).map_err(db_err)?;
+        for app in apps.iter().map(|a| a.trim()).filter(|a| !a.is_empty()) {
+            tx.execute(
+                "INSERT OR IGNORE INTO hotkey_exclusions (app) VALUES (?1)",
+                params![app],
+            )
+            .map_err(db_err)?;
+        }
+        tx.commit().map_err(db_err)
+    }
+
+    /// Bindings that go to the previous app while the panel has focus.
+    pub fn get_hotkey_pass_through(&self) -> Result<Vec<String>, ConfigError> {
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare("SELECT binding FROM hotkey_pass_through ORDER BY binding")
+            .map_err(db_err)?;
+
+        let rows = stmt.query_map([], |row| row.get(0)).map_err(db_err)?;
+        rows.collect::<Result<Vec<_>, _>>().map_err(db_err)
+    }
+
+    pub fn set_hotkey_pass_through(&self, binding: &str, enabled: bool) -> Result<(), ConfigError> {
+        let sql = if enabled {
+            "INSERT OR IGNORE INTO hotkey_pass_through (binding) VALUES (?1)"
+        } else {
+            "DELETE FROM hotkey_pass_through WHERE binding = ?1"
+        };
+        self.conn()?.execute(sql, params![binding]).map_err(db_err)?;
+        Ok(())
+    }
+
+    /// Pipelines are stored as JSON so new fields don't need migrations.
+    pub fn get_headless_hotkeys(&self) -> Result<Vec<HeadlessHotkey>, ConfigError> {
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare("SELECT id, hotkey, pipeline FROM headless_hotkeys")
+            .map_err(db_err)?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })
+            .map_err(db_err)?;
+
+        let mut hotkeys = Vec::new();
+        for row in rows {
+            let (id, hotkey, pipeline) = row.map_err(db_err)?;
+            match self.decode::<HeadlessPipeline>(&pipeline) {
+                Ok(pipeline) => hotkeys.push(HeadlessHotkey { id, hotkey, pipeline }),
+                Err(e) => log::warn!("Skipping unreadable headless hotkey '{}': {}", id, e),
+            }
+        }
+
+        Ok(hotkeys)
+    }
+
+    pub fn save_headless_hotkey(&self, binding: &HeadlessHotkey) -> Result<(), ConfigError> {
+        let pipeline = self.encode(&binding.pipeline)?;
+        self.conn()?
+            .execute(
+                "INSERT OR REPLACE INTO headless_hotkeys (id, hotkey, pipeline) VALUES (?1, ?2, ?3)",
+                params![binding.id, binding.hotkey, pipeline],
+            )
+            .map_err(db_err)?;
+        Ok(())
+    }
+
+    pub fn delete_headless_hotkey(&self, id: &str) -> Result<(), ConfigError> {
+        self.conn()?
+            .execute("DELETE FROM headless_hotkeys WHERE id = ?1", params![id])
+            .map_err(db_err)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,64 @@
+use rusqlite::{params, Connection};
+
+use super::{ConfigError, ConfigManager};
+use crate::ai::CustomIntent;
+
+pub(super) fn init_tables(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS custom_intents (
+            id TEXT PRIMARY KEY,
+            data TEXT NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn db_err(e: rusqlite::Error) -> ConfigError {
+    ConfigError::Database(e.to_string())
+}
+
+impl ConfigManager {
+    pub fn get_custom_intents(&self) -> Result<Vec<CustomIntent>, ConfigError> {
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare("SELECT id, data FROM custom_intents ORDER BY rowid")
+            .map_err(db_err)?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(db_err)?;
+
+        let mut intents = Vec::new();
+        for row in rows {
+            let (id, data) = row.map_err(db_err)?;
+            match self.decode::<CustomIntent>(&data) {
+                Ok(intent) => intents.push(intent),
+                Err(e) => log::warn!("Skipping unreadable intent '{}': {}", id, e),
+            }
+        }
+        Ok(intents)
+    }
+
+    pub fn save_custom_intent(&self, intent: &CustomIntent) -> Result<(), ConfigError> {
+        let data = self.encode(intent)?;
+        // An upsert keeps the rowid, so edited intents stay in place
+        self.conn()?
+            .execute(
+                "INSERT INTO custom_intents (id, data) VALUES (?1, ?2)
+                 ON CONFLICT(id) DO UPDATE SET data = ?2",
+                params![intent.id, data],
+            )
+            .map_err(db_err)?;
+        Ok(())
+    }
+
+    pub fn delete_custom_intent(&self, id: &str) -> Result<(), ConfigError> {
+        self.conn()?
+            .execute("DELETE FROM custom_intents WHERE id = ?1", params![id])
+            .map_err(db_err)?;
+        Ok(())
+    }
+}
@@ -1,22 +1,266 @@
 use rusqlite::{params, Connection, OpenFlags};
 use keyring::Entry;
 use serde::{Deserialize, Serialize};
-use std::sync::Mutex;
+use std::sync::{Mutex, MutexGuard};
 use std::fs;
+use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Manager, Runtime};
 use thiserror::Error;
 
-const SERVICE_NAME: &str = "flow-paste";
+use crate::ai::ChipLayout;
+use crate::text::PasswordPolicy;
+
+mod autostart;
+mod backup;
+mod bundle;
+mod dashboard;
+mod encryption;
+mod env;
+mod extension;
+mod hotkeys;
+mod intents;
+mod profiles;
+mod providers;
+mod rules;
+mod snippets;
+mod sync;
+mod usage;
+mod usage_stats;
+mod validate;
+mod webhooks;
+mod workflows;
+
+pub use backup::ConfigBackup;
+pub use bundle::{ConfigBundle, ImportMode, BUNDLE_VERSION};
+pub use dashboard::DashboardStats;
+pub use hotkeys::{ActionHotkey, HeadlessHotkey, HeadlessPipeline};
+pub use profiles::Profile;
+pub use providers::ProviderConfig;
+pub use rules::RuleHotkey;
+pub use usage::{RuleStat, UsageTarget};
+pub use usage_stats::{UsageMetric, UsagePeriod, UsageStats};
+pub use validate::FieldError;
+pub use webhooks::{Webhook, WebhookHeader, WebhookPrivacy};
+pub use workflows::{StepAction, Workflow};
+
+/// Keyring service of every secret the app stores.
+pub(crate) const SERVICE_NAME: &str = "flow-paste";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AppConfig {
     pub hotkey: String,
+    /// Active entry in `providers`
     pub ai_provider: String,
-    pub ollama_base_url: String,
-    pub openai_base_url: String,
-    pub model_name: String,
+    #[serde(default = "providers::default_providers")]
+    pub providers: Vec<ProviderConfig>,
     pub theme: String,
+    /// Send the paste shortcut after hotkey actions write to the clipboard
+    #[serde(default)]
+    pub auto_paste: bool,
+    /// "toggle": each press shows/hides the panel; "hold": the panel is
+    /// visible only while the hotkey is held
+    #[serde(default = "default_panel_mode")]
+    pub panel_mode: String,
+    /// Open the panel next to the mouse cursor instead of its last position
+    #[serde(default)]
+    pub show_at_cursor: bool,
+    /// Presses of the same hotkey closer together than this are ignored
+    #[serde(default = "default_hotkey_debounce_ms")]
+    pub hotkey_debounce_ms: u64,
+    /// Start the app when the user logs in
+    #[serde(default)]
+    pub autostart: bool,
+    /// Proxy for AI provider requests; empty uses the system settings
+    #[serde(default)]
+    pub proxy_url: String,
+    /// Comma-separated hosts that bypass `proxy_url`
+    #[serde(default)]
+    pub no_proxy: String,
+    /// Extra PEM root certificates, for networks with TLS interception
+    #[serde(default)]
+    pub ca_cert_path: String,
+    /// Language of backend-generated text such as action chip labels
+    #[serde(default = "default_locale")]
+    pub locale: String,
+    /// Keep the panel above other windows
+    #[serde(default = "default_true")]
+    pub always_on_top: bool,
+    /// Show the panel on every virtual desktop / Space
+    #[serde(default)]
+    pub visible_on_all_workspaces: bool,
+    /// Panel opacity, 0.2 to 1.0
+    #[serde(default = "default_window_opacity")]
+    pub window_opacity: f64,
+    /// Hide the panel when it loses focus
+    #[serde(default = "default_true")]
+    pub hide_on_blur: bool,
+    /// Minimum level kept in the in-app log and log file: error ... trace
+    #[serde(default = "default_log_level")]
+    pub log_level: String,
+    /// Also write logs to a rolling file in the app data dir
+    #[serde(default = "default_true")]
+    pub log_to_file: bool,
+    /// Count feature usage locally for the stats view; never leaves the machine
+    #[serde(default = "default_true")]
+    pub usage_stats: bool,
+    /// Privacy shield for cloud providers: "always" masks, "ask" confirms
+    /// before sending high-severity PII, "never" sends as is
+    #[serde(default = "default_shield_policy")]
+    pub shield_policy: String,
+    /// How many action chips to offer, and their order per content type
+    #[serde(default)]
+    pub chip_layout: ChipLayout,
+    /// Ask the local Ollama model about content the heuristics can't place
+    #[serde(default)]
+    pub ai_intent: bool,
+    /// Model for `ai_intent`; empty uses the Ollama provider's model
+    #[serde(default)]
+    pub ai_intent_model: String,
+    /// How long chips may wait for the model before the heuristic ones are shown
+    #[serde(default = "default_ai_intent_budget_ms")]
+    pub ai_intent_budget_ms: u64,
+    /// Serve clipboard and rule tools to MCP clients on localhost
+    #[serde(default)]
+    pub mcp_server: bool,
+    #[serde(default = "default_mcp_port")]
+    pub mcp_port: u16,
+    /// Token-protected REST/WebSocket API on localhost for scripts
+    #[serde(default)]
+    pub http_api: bool,
+    #[serde(default = "default_http_api_port")]
+    pub http_api_port: u16,
+    /// Replace typed snippet abbreviations (`;sig`) with the snippet text
+    #[serde(default)]
+    pub text_expansion: bool,
+    /// Comma-separated apps (name or executable) where abbreviations are left alone
+    #[serde(default)]
+    pub text_expansion_excluded_apps: String,
+    /// Tesseract executable used to read text from screenshots
+    #[serde(default = "default_ocr_engine")]
+    pub ocr_engine: String,
+    /// Tesseract languages joined with `+`, e.g. `chi_sim+eng`
+    #[serde(default = "default_ocr_languages")]
+    pub ocr_languages: String,
+    /// ZBar `zbarimg` executable used to read QR codes from images
+    #[serde(default = "default_qr_decoder")]
+    pub qr_decoder: String,
+    /// Poppler `pdftotext` executable used to read dropped PDFs
+    #[serde(default = "default_pdf_extractor")]
+    pub pdf_extractor: String,
+    /// whisper.cpp CLI used to transcribe audio files
+    #[serde(default = "default_whisper_command")]
+    pub whisper_command: String,
+    /// whisper.cpp model file (`ggml-*.bin`); transcription is off until set
+    #[serde(default)]
+    pub whisper_model: String,
+    /// Spoken language code, or `auto` to detect it
+    #[serde(default = "default_whisper_language")]
+    pub whisper_language: String,
+    /// Machine translation service for translate chips: `DeepL`, `Google`,
+    /// or `""` to translate with the AI provider
+    #[serde(default)]
+    pub translation_service: String,
+    /// Markdown file, or folder such as an Obsidian vault, results are saved
+    /// to; `""` until set up
+    #[serde(default)]
+    pub notes_path: String,
+    /// Name of the file inside `notes_path` when it is a folder, e.g. `{date}.md`
+    #[serde(default = "default_notes_file_template")]
+    pub notes_file_template: String,
+    /// Answer the companion browser extension through native messaging
+    #[serde(default)]
+    pub browser_extension: bool,
+    /// Comma-separated extension ids allowed to connect
+    #[serde(default)]
+    pub browser_extension_ids: String,
+    /// Length and characters of generated passwords
+    #[serde(default)]
+    pub password_policy: PasswordPolicy,
+    /// Clear the clipboard once it has held personal data this long; 0 = never
+    #[serde(default)]
+    pub clear_pii_after_minutes: u32,
+    /// Empty the clipboard and transform history each night
+    #[serde(default)]
+    pub purge_history_nightly: bool,
+    /// Ask Ollama for its model list every hour
+    #[serde(default)]
+    pub refresh_models_hourly: bool,
+    /// Folder, e.g. inside Dropbox or a Syncthing share, that rules,
+    /// snippets and prompts are synced with; `""` for no sync
+    #[serde(default)]
+    pub sync_folder: String,
+    /// Rewrite a copied link without its tracking parameters
+    #[serde(default)]
+    pub clean_copied_urls: bool,
+}
+
+fn default_notes_file_template() -> String {
+    "{date}.md".to_string()
+}
+
+fn default_pdf_extractor() -> String {
+    "pdftotext".to_string()
+}
+
+fn default_ocr_engine() -> String {
+    "tesseract".to_string()
+}
+
+fn default_ocr_languages() -> String {
+    "chi_sim+eng".to_string()
+}
+
+fn default_qr_decoder() -> String {
+    "zbarimg".to_string()
+}
+
+fn default_whisper_command() -> String {
+    "whisper-cli".to_string()
+}
+
+fn default_whisper_language() -> String {
+    "auto".to_string()
+}
+
+fn default_panel_mode() -> String {
+    "toggle".to_string()
+}
+
+fn default_locale() -> String {
+    "zh-CN".to_string()
+}
+
+fn default_hotkey_debounce_ms() -> u64 {
+    250
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_window_opacity() -> f64 {
+    1.0
+}
+
+fn default_shield_policy() -> String {
+    "always".to_string()
+}
+
+fn default_ai_intent_budget_ms() -> u64 {
+    800
+}
+
+fn default_mcp_port() -> u16 {
+    47321
+}
+
+fn default_http_api_port() -> u16 {
+    47322
+}
+
+fn default_log_level() -> String {
+    "info".to_string()
 }
 
 impl Default for AppConfig {
@@ -24,10 +268,53 @@ impl Default for AppConfig {
         Self {
             hotkey: "Ctrl+Shift+V".to_string(),
             ai_provider: "Ollama".to_string(),
-            ollama_base_url: "http://localhost:11434".to_string(),
-            openai_base_url: "https://api.openai.com/v1".to_string(),
-            model_name: "llama3.2".to_string(),
+            providers: providers::default_providers(),
             theme: "system".to_string(),
+            auto_paste: false,
+            panel_mode: default_panel_mode(),
+            show_at_cursor: false,
+            hotkey_debounce_ms: default_hotkey_debounce_ms(),
+            autostart: false,
+            proxy_url: String::new(),
+            no_proxy: String::new(),
+            ca_cert_path: String::new(),
+            locale: default_locale(),
+            always_on_top: true,
+            visible_on_all_workspaces: false,
+            window_opacity: default_window_opacity(),
+            hide_on_blur: true,
+            log_level: default_log_level(),
+            log_to_file: true,
+            usage_stats: true,
+            shield_policy: default_shield_policy(),
+            chip_layout: ChipLayout::default(),
+            ai_intent: false,
+            ai_intent_model: String::new(),
+            ai_intent_budget_ms: default_ai_intent_budget_ms(),
+            mcp_server: false,
+            mcp_port: default_mcp_port(),
+            http_api: false,
+            http_api_port: default_http_api_port(),
+            text_expansion: false,
+            text_expansion_excluded_apps: String::new(),
+            ocr_engine: default_ocr_engine(),
+            ocr_languages: default_ocr_languages(),
+            qr_decoder: default_qr_decoder(),
+            pdf_extractor: default_pdf_extractor(),
+            whisper_command: default_whisper_command(),
+            whisper_model: String::new(),
+            whisper_language: default_whisper_language(),
+            translation_service: String::new(),
+            notes_path: String::new(),
+            notes_file_template: default_notes_file_template(),
+            browser_extension: false,
+            browser_extension_ids: String::new(),
+            password_policy: PasswordPolicy::default(),
+            clear_pii_after_minutes: 0,
+            purge_history_nightly: false,
+            refresh_models_hourly: false,
+            sync_folder: String::new(),
+            clean_copied_urls: false,
         }
     }
 }
@@ -42,10 +329,28 @@ pub enum ConfigError {
     Keyring(String),
     #[error("io error: {0}")]
     Io(String),
+    #[error("profile not found: {0}")]
+    ProfileNotFound(String),
+    #[error("backup not found: {0}")]
+    BackupNotFound(String),
+    #[error("invalid setting: {0}")]
+    InvalidSetting(String),
+    #[error("encryption error: {0}")]
+    Crypto(String),
+    #[error("the database encryption key is missing from the keyring")]
+    KeyMissing,
+    #[error("autostart error: {0}")]
+    Autostart(String),
+    #[error("invalid config: {}", validate::format_field_errors(.0))]
+    Invalid(Vec<FieldError>),
 }
 
 pub struct ConfigManager {
     db: Mutex<Connection>,
+    /// Set while rules, snippets and prompts are encrypted at rest
+    cipher: Mutex<Option<encryption::FieldCipher>>,
+    /// Rotating copies of settings.db taken before each change
+    backup_dir: PathBuf,
 }
 
 impl ConfigManager {
@@ -61,7 +366,7 @@ impl ConfigManager {
         }
 
         let db_path = config_dir.join("settings.db");
-        let conn = Connection::open_with_flags(
+        let mut conn = Connection::open_with_flags(
             &db_path,
             OpenFlags::SQLITE_OPEN_READ_WRITE
                 | OpenFlags::SQLITE_OPEN_CREATE
@@ -73,28 +378,34 @@ impl ConfigManager {
         conn.pragma_update(None, "journal_mode", "WAL")
             .map_err(|e| ConfigError::Database(e.to_string()))?;
 
-        // Create settings table
-        conn.execute(
-            "CREATE TABLE IF NOT EXISTS settings (
-                key TEXT PRIMARY KEY,
-                value TEXT NOT NULL
-            )",
-            [],
-        )
-        .map_err(|e| ConfigError::Database(e.to_string()))?;
+        let backup_dir = config_dir.join("backups");
+        init_schema(&mut conn, &backup_dir)?;
+
+        // Without the key encrypted rows are skipped, but the app still starts
+        let cipher = encryption::load_cipher(&conn).unwrap_or_else(|e| {
+            log::error!("Failed to load database encryption key: {}", e);
+            None
+        });
 
         log::info!("Config manager initialized at {:?}", db_path);
 
         Ok(Self {
             db: Mutex::new(conn),
+            cipher: Mutex::new(cipher),
+            backup_dir,
         })
     }
 
-    pub fn get_config(&self) -> Result<AppConfig, ConfigError> {
-        let conn = self
-            .db
+    fn conn(&self) -> Result<MutexGuard<'_, Connection>, ConfigError> {
+        self.db
             .lock()
-            .map_err(|_| ConfigError::Database("database lock poisoned".into()))?;
+            .map_err(|_| ConfigError::Database("database lock poisoned".into()))
+    }
+
+    /// Settings as stored, for the settings UI, profiles and exports.
+    /// Runtime code reads `effective_config` instead.
+    pub fn get_config(&self) -> Result<AppConfig, ConfigError> {
+        let conn = self.conn()?;
 
         let mut stmt = conn
             .prepare("SELECT key, value FROM settings")
@@ -113,10 +424,68 @@ impl ConfigManager {
             match key.as_str() {
                 "hotkey" => config.hotkey = value,
                 "aiProvider" => config.ai_provider = value,
-                "ollamaBaseUrl" => config.ollama_base_url = value,
-                "openaiBaseUrl" => config.openai_base_url = value,
-                "modelName" => config.model_name = value,
+                "providers" => match serde_json::from_str(&value) {
+                    Ok(providers) => config.providers = providers,
+                    Err(e) => log::warn!("Ignoring unreadable provider settings: {}", e),
+                },
                 "theme" => config.theme = value,
+                "autoPaste" => config.auto_paste = value == "true",
+                "panelMode" => config.panel_mode = value,
+                "showAtCursor" => config.show_at_cursor = value == "true",
+                "hotkeyDebounceMs" => {
+                    config.hotkey_debounce_ms = value.parse().unwrap_or_else(|_| default_hotkey_debounce_ms())
+                }
+                "autostart" => config.autostart = value == "true",
+                "proxyUrl" => config.proxy_url = value,
+                "noProxy" => config.no_proxy = value,
+                "caCertPath" => config.ca_cert_path = value,
+                "locale" => config.locale = value,
+                "alwaysOnTop" => config.always_on_top = value == "true",
+                "visibleOnAllWorkspaces" => config.visible_on_all_workspaces = value == "true",
+                "windowOpacity" => {
+                    config.window_opacity = value.parse().unwrap_or_else(|_| default_window_opacity())
+                }
+                "hideOnBlur" => config.hide_on_blur = value == "true",
+                "logLevel" => config.log_level = value,
+                "logToFile" => config.log_to_file = value == "true",
+                "usageStats" => config.usage_stats = value == "true",
+                "shieldPolicy" => config.shield_policy = value,
+                "chipLayout" => match serde_json::from_str(&value) {
+                    Ok(layout) => config.chip_layout = layout,
+                    Err(e) => log::warn!("Ignoring unreadable chip layout: {}", e),
+                },
+                "aiIntent" => config.ai_intent = value == "true",
+                "aiIntentModel" => config.ai_intent_model = value,
+                "aiIntentBudgetMs" => {
+                    config.ai_intent_budget_ms = value.parse().unwrap_or_else(|_| default_ai_intent_budget_ms())
+                }
+                "mcpServer" => config.mcp_server = value == "true",
+                "mcpPort" => config.mcp_port = value.parse().unwrap_or_else(|_| default_mcp_port()),
+                "httpApi" => config.http_api = value == "true",
+                "httpApiPort" => config.http_api_port = value.parse().unwrap_or_else(|_| default_http_api_port()),
+                "textExpansion" => config.text_expansion = value == "true",
+                "textExpansionExcludedApps" => config.text_expansion_excluded_apps = value,
+                "ocrEngine" => config.ocr_engine = value,
+                "ocrLanguages" => config.ocr_languages = value,
+                "qrDecoder" => config.qr_decoder = value,
+                "pdfExtractor" => config.pdf_extractor = value,
+                "whisperCommand" => config.whisper_command = value,
+                "whisperModel" => config.whisper_model = value,
+                "whisperLanguage" => config.whisper_language = value,
+                "translationService" => config.translation_service = value,
+                "notesPath" => config.notes_path = value,
+                "notesFileTemplate" => config.notes_file_template = value,
+                "browserExtension" => config.browser_extension = value == "true",
+                "browserExtensionIds" => config.browser_extension_ids = value,
+                "passwordPolicy" => match serde_json::from_str(&value) {
+                    Ok(policy) => config.password_policy = policy,
+                    Err(e) => log::warn!("Ignoring unreadable password policy: {}", e),
+                },
+                "clearPiiAfterMinutes" => config.clear_pii_after_minutes = value.parse().unwrap_or(0),
+                "purgeHistoryNightly" => config.purge_history_nightly = value == "true",
+                "refreshModelsHourly" => config.refresh_models_hourly = value == "true",
+                "syncFolder" => config.sync_folder = value,
+                "cleanCopiedUrls" => config.clean_copied_urls = value == "true",
                 _ => {}
             }
         }
@@ -124,30 +493,24 @@ impl ConfigManager {
         Ok(config)
     }
 
+    /// Settings in effect: the stored ones with `FLOWPASTE_*` environment
+    /// overrides applied. Never store what this returns.
+    pub fn effective_config(&self) -> Result<AppConfig, ConfigError> {
+        self.get_config().map(AppConfig::with_env_overrides)
+    }
+
+    /// Validate and store `config`, installing or removing the login item
+    /// to match `autostart`. The previous settings are backed up first.
     pub fn set_config(&self, config: &AppConfig) -> Result<(), ConfigError> {
-        let conn = self
-            .db
-            .lock()
-            .map_err(|_| ConfigError::Database("database lock poisoned".into()))?;
-
-        let pairs = [
-            ("hotkey", &config.hotkey),
-            ("aiProvider", &config.ai_provider),
-            ("ollamaBaseUrl", &config.ollama_base_url),
-            ("openaiBaseUrl", &config.openai_base_url),
-            ("modelName", &config.model_name),
-            ("theme", &config.theme),
-        ];
-
-        for (key, value) in pairs {
-            conn.execute(
-                "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
-                params![key, value],
-            )
-            .map_err(|e| ConfigError::Database(e.to_string()))?;
-        }
+        config.validate()?;
+        self.backup(&*self.conn()?, "settings");
+        self.store_config(config)
+    }
 
-        Ok(())
+    /// `set_config` without validation or backup, for callers that did both.
+    fn store_config(&self, config: &AppConfig) -> Result<(), ConfigError> {
+        autostart::set_enabled(config.autostart)?;
+        write_settings(&*self.conn()?, config)
     }
 
     pub fn get_api_key(&self, provider: &str) -> Result<Option<String>, ConfigError> {
@@ -179,3 +542,124 @@ impl ConfigManager {
         }
     }
 }
+
+/// Create missing tables and run migrations, backing up before any
+/// migration that rewrites existing data.
+fn init_schema(conn: &mut Connection, backup_dir: &Path) -> Result<(), ConfigError> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        )",
+        [],
+    )
+    .map_err(|e| ConfigError::Database(e.to_string()))?;
+
+    hotkeys::init_tables(conn).map_err(|e| ConfigError::Database(e.to_string()))?;
+    rules::init_tables(conn).map_err(|e| ConfigError::Database(e.to_string()))?;
+    usage::init_tables(conn).map_err(|e| ConfigError::Database(e.to_string()))?;
+    usage_stats::init_tables(conn).map_err(|e| ConfigError::Database(e.to_string()))?;
+    dashboard::init_tables(conn).map_err(|e| ConfigError::Database(e.to_string()))?;
+    snippets::init_tables(conn).map_err(|e| ConfigError::Database(e.to_string()))?;
+    intents::init_tables(conn).map_err(|e| ConfigError::Database(e.to_string()))?;
+    sync::init_tables(conn).map_err(|e| ConfigError::Database(e.to_string()))?;
+    profiles::init_tables(conn).map_err(|e| ConfigError::Database(e.to_string()))?;
+    extension::init_tables(conn).map_err(|e| ConfigError::Database(e.to_string()))?;
+    webhooks::init_tables(conn).map_err(|e| ConfigError::Database(e.to_string()))?;
+    workflows::init_tables(conn).map_err(|e| ConfigError::Database(e.to_string()))?;
+    providers::migrate_flat_keys(conn, backup_dir)?;
+    profiles::migrate_flat_keys(conn)
+}
+
+/// Write every `AppConfig` field to the settings table.
+fn write_settings(conn: &Connection, config: &AppConfig) -> Result<(), ConfigError> {
+    let auto_paste = config.auto_paste.to_string();
+    let show_at_cursor = config.show_at_cursor.to_string();
+    let hotkey_debounce_ms = config.hotkey_debounce_ms.to_string();
+    let providers = serde_json::to_string(&config.providers)
+        .map_err(|e| ConfigError::Database(e.to_string()))?;
+    let autostart = config.autostart.to_string();
+    let always_on_top = config.always_on_top.to_string();
+    let visible_on_all_workspaces = config.visible_on_all_workspaces.to_string();
+    let window_opacity = config.window_opacity.to_string();
+    let hide_on_blur = config.hide_on_blur.to_string();
+    let log_to_file = config.log_to_file.to_string();
+    let usage_stats = config.usage_stats.to_string();
+    let chip_layout = serde_json::to_string(&config.chip_layout)
+        .map_err(|e| ConfigError::Database(e.to_string()))?;
+    let ai_intent = config.ai_intent.to_string();
+    let ai_intent_budget_ms = config.ai_intent_budget_ms.to_string();
+    let mcp_server = config.mcp_server.to_string();
+    let mcp_port = config.mcp_port.to_string();
+    let http_api = config.http_api.to_string();
+    let http_api_port = config.http_api_port.to_string();
+    let text_expansion = config.text_expansion.to_string();
+    let browser_extension = config.browser_extension.to_string();
+    let password_policy = serde_json::to_string(&config.password_policy)
+        .map_err(|e| ConfigError::Database(e.to_string()))?;
+    let clear_pii_after_minutes = config.clear_pii_after_minutes.to_string();
+    let purge_history_nightly = config.purge_history_nightly.to_string();
+    let refresh_models_hourly = config.refresh_models_hourly.to_string();
+    let clean_copied_urls = config.clean_copied_urls.to_string();
+    let pairs = [
+        ("hotkey", &config.hotkey),
+        ("aiProvider", &config.ai_provider),
+        ("providers", &providers),
+        ("theme", &config.theme),
+        ("autoPaste", &auto_paste),
+        ("panelMode", &config.panel_mode),
+        ("showAtCursor", &show_at_cursor),
+        ("hotkeyDebounceMs", &hotkey_debounce_ms),
+        ("autostart", &autostart),
+        ("proxyUrl", &config.proxy_url),
+        ("noProxy", &config.no_proxy),
+        ("caCertPath", &config.ca_cert_path),
+        ("locale", &config.locale),
+        ("alwaysOnTop", &always_on_top),
+        ("visibleOnAllWorkspaces", &visible_on_all_workspaces),
+        ("windowOpacity", &window_opacity),
+        ("hideOnBlur", &hide_on_blur),
+        ("logLevel", &config.log_level),
+        ("logToFile", &log_to_file),
+        ("usageStats", &usage_stats),
+        ("shieldPolicy", &config.shield_policy),
+        ("chipLayout", &chip_layout),
+        ("aiIntent", &ai_intent),
+        ("aiIntentModel", &config.ai_intent_model),
+        ("aiIntentBudgetMs", &ai_intent_budget_ms),
+        ("mcpServer", &mcp_server),
+        ("mcpPort", &mcp_port),
+        ("httpApi", &http_api),
+        ("httpApiPort", &http_api_port),
+        ("textExpansion", &text_expansion),
+        ("textExpansionExcludedApps", &config.text_expansion_excluded_apps),
+        ("ocrEngine", &config.ocr_engine),
+        ("ocrLanguages", &config.ocr_languages),
+        ("qrDecoder", &config.qr_decoder),
+        ("pdfExtractor", &config.pdf_extractor),
+        ("whisperCommand", &config.whisper_command),
+        ("whisperModel", &config.whisper_model),
+        ("whisperLanguage", &config.whisper_language),
+        ("translationService", &config.translation_service),
+        ("notesPath", &config.notes_path),
+        ("notesFileTemplate", &config.notes_file_template),
+        ("browserExtension", &browser_extension),
+        ("browserExtensionIds", &config.browser_extension_ids),
+        ("passwordPolicy", &password_policy),
+        ("clearPiiAfterMinutes", &clear_pii_after_minutes),
+        ("purgeHistoryNightly", &purge_history_nightly),
+        ("refreshModelsHourly", &refresh_models_hourly),
+        ("syncFolder", &config.sync_folder),
+        ("cleanCopiedUrls", &clean_copied_urls),
+    ];
+
+    for (key, value) in pairs {
+        conn.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+            params![key, value],
+        )
+        .map_err(|e| ConfigError::Database(e.to_string()))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,165 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use super::{providers, write_settings, ActionHotkey, AppConfig, ConfigError, ConfigManager};
+
+const ACTIVE_PROFILE_KEY: &str = "activeProfile";
+
+pub(super) fn init_tables(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS profiles (
+            name TEXT PRIMARY KEY,
+            data TEXT NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Move the flat provider keys of profiles saved before providers had
+/// their own records into `providers`, as done for the settings table.
+pub(super) fn migrate_flat_keys(conn: &Connection) -> Result<(), ConfigError> {
+    let mut stmt = conn.prepare("SELECT name, data FROM profiles").map_err(db_err)?;
+    let rows: Vec<(String, String)> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(db_err)?
+        .collect::<Result<_, _>>()
+        .map_err(db_err)?;
+    drop(stmt);
+
+    for (name, data) in rows {
+        let Ok(mut profile) = serde_json::from_str::<serde_json::Value>(&data) else {
+            continue;
+        };
+        if !providers::migrate_flat_json(&mut profile["config"]) {
+            continue;
+        }
+        let data = serde_json::to_string(&profile).map_err(json_err)?;
+        conn.execute("UPDATE profiles SET data = ?1 WHERE name = ?2", params![data, name])
+            .map_err(db_err)?;
+        log::info!("Migrated provider settings of profile '{}'", name);
+    }
+    Ok(())
+}
+
+/// A named set of settings (provider, model, privacy, panel hotkey) and
+/// action hotkeys, e.g. "Work" and "Personal".
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Profile {
+    pub name: String,
+    pub config: AppConfig,
+    #[serde(default)]
+    pub action_hotkeys: Vec<ActionHotkey>,
+}
+
+fn db_err(e: rusqlite::Error) -> ConfigError {
+    ConfigError::Database(e.to_string())
+}
+
+fn json_err(e: serde_json::Error) -> ConfigError {
+    ConfigError::Database(e.to_string())
+}
+
+impl ConfigManager {
+    pub fn get_profiles(&self) -> Result<Vec<Profile>, ConfigError> {
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare("SELECT data FROM profiles ORDER BY name")
+            .map_err(db_err)?;
+
+        let rows = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(db_err)?;
+
+        let mut profiles = Vec::new();
+        for row in rows {
+            let data = row.map_err(db_err)?;
+            match serde_json::from_str(&data) {
+                Ok(profile) => profiles.push(profile),
+                Err(e) => log::warn!("Skipping unreadable profile: {}", e),
+            }
+        }
+        Ok(profiles)
+    }
+
+    pub fn save_profile(&self, profile: &Profile) -> Result<(), ConfigError> {
+        let data = serde_json::to_string(profile).map_err(json_err)?;
+        self.conn()?
+            .execute(
+                "INSERT OR REPLACE INTO profiles (name, data) VALUES (?1, ?2)",
+                params![profile.name, data],
+            )
+            .map_err(db_err)?;
+        Ok(())
+    }
+
+    pub fn delete_profile(&self, name: &str) -> Result<(), ConfigError> {
+        let conn = self.conn()?;
+        conn.execute("DELETE FROM profiles WHERE name = ?1", params![name])
+            .map_err(db_err)?;
+        conn.execute(
+            "DELETE FROM settings WHERE key = ?1 AND value = ?2",
+            params![ACTIVE_PROFILE_KEY, name],
+        )
+        .map_err(db_err)?;
+        Ok(())
+    }
+
+    /// Profile whose settings were last applied, if any.
+    pub fn get_active_profile(&self) -> Result<Option<String>, ConfigError> {
+        self.conn()?
+            .query_row(
+                "SELECT value FROM settings WHERE key = ?1",
+                params![ACTIVE_PROFILE_KEY],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_err)
+    }
+
+    pub fn get_profile(&self, name: &str) -> Result<Profile, ConfigError> {
+        let data: String = self
+            .conn()?
+            .query_row(
+                "SELECT data FROM profiles WHERE name = ?1",
+                params![name],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_err)?
+            .ok_or_else(|| ConfigError::ProfileNotFound(name.to_string()))?;
+        serde_json::from_str(&data).map_err(json_err)
+    }
+
+    /// Replace the current settings and action hotkeys with the profile's in
+    /// one transaction, and mark it active. Launch at login is per machine,
+    /// so the current value is kept. Returns the settings now in effect.
+    pub fn apply_profile(&self, profile: &Profile) -> Result<AppConfig, ConfigError> {
+        profile.config.validate()?;
+        let mut config = profile.config.clone();
+        config.autostart = self.get_config()?.autostart;
+
+        let mut conn = self.conn()?;
+        self.backup(&conn, "profile");
+        let tx = conn.transaction().map_err(db_err)?;
+
+        write_settings(&tx, &config)?;
+        tx.execute("DELETE FROM action_hotkeys", []).map_err(db_err)?;
+        for binding in &profile.action_hotkeys {
+            tx.execute(
+                "INSERT OR REPLACE INTO action_hotkeys (action, hotkey) VALUES (?1, ?2)",
+                params![binding.action, binding.hotkey],
+            )
+            .map_err(db_err)?;
+        }
+        tx.execute(
+            "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+            params![ACTIVE_PROFILE_KEY, profile.name],
+        )
+        .map_err(db_err)?;
+
+        tx.commit().map_err(db_err)?;
+        Ok(config)
+    }
+}
@@ -0,0 +1,217 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::{backup, AppConfig, ConfigError};
+use crate::ai::chunk::DEFAULT_CONTEXT_TOKENS;
+use crate::ai::{AIProviderType, HttpSettings};
+
+const DEFAULT_TIMEOUT_SECS: u64 = 120;
+
+/// Connection settings for one AI provider. `AppConfig::ai_provider` names
+/// the active entry.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderConfig {
+    /// "Ollama" or "OpenAI"
+    pub provider: String,
+    pub base_url: String,
+    pub model: String,
+    /// Mask detected PII before text is sent; local providers are never masked
+    #[serde(default = "default_true")]
+    pub privacy_shield: bool,
+    /// Seconds before a request is abandoned
+    #[serde(default = "default_timeout_secs")]
+    pub timeout_secs: u64,
+    /// Context window of the model, in tokens; longer input is sent in chunks
+    #[serde(default = "default_context_tokens")]
+    pub context_tokens: u32,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn default_timeout_secs() -> u64 {
+    DEFAULT_TIMEOUT_SECS
+}
+
+fn default_context_tokens() -> u32 {
+    DEFAULT_CONTEXT_TOKENS
+}
+
+impl ProviderConfig {
+    fn new(provider: &str, base_url: &str, model: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            base_url: base_url.to_string(),
+            model: model.to_string(),
+            privacy_shield: true,
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+            context_tokens: DEFAULT_CONTEXT_TOKENS,
+        }
+    }
+}
+
+pub(super) fn default_providers() -> Vec<ProviderConfig> {
+    vec![
+        ProviderConfig::new("Ollama", "http://localhost:11434", "llama3.2"),
+        ProviderConfig::new("OpenAI", "https://api.openai.com/v1", "gpt-4o-mini"),
+    ]
+}
+
+impl AppConfig {
+    pub fn active_provider(&self) -> Option<&ProviderConfig> {
+        self.provider(&self.ai_provider)
+    }
+
+    pub fn provider(&self, name: &str) -> Option<&ProviderConfig> {
+        self.providers.iter().find(|p| p.provider == name)
+    }
+
+    /// Whether prompts for `kind` go through the privacy shield: cloud
+    /// providers only, unless their shield is turned off.
+    pub fn is_shielded(&self, kind: AIProviderType) -> bool {
+        match kind {
+            AIProviderType::Ollama => false,
+            AIProviderType::OpenAI => self.provider("OpenAI").map(|p| p.privacy_shield).unwrap_or(true),
+        }
+    }
+
+    pub fn http_settings(&self) -> HttpSettings {
+        HttpSettings {
+            proxy_url: self.proxy_url.clone(),
+            no_proxy: self.no_proxy.clone(),
+            ca_cert_path: self.ca_cert_path.clone(),
+        }
+    }
+
+    pub(super) fn provider_mut(&mut self, name: &str) -> Option<&mut ProviderConfig> {
+        self.providers.iter_mut().find(|p| p.provider == name)
+    }
+}
+
+/// Settings keys from before providers had their own records.
+const FLAT_KEYS: &[&str] = &["ollamaBaseUrl", "openaiBaseUrl", "modelName", "privacyShield"];
+
+/// Provider records built from the flat keys, read through `get`. The
+/// shared model is kept for the provider that was active; the other one
+/// gets its default.
+fn providers_from_flat(get: impl Fn(&str) -> Option<String>) -> Vec<ProviderConfig> {
+    let active = get("aiProvider").unwrap_or_else(|| "Ollama".to_string());
+    let mut providers = default_providers();
+    for entry in &mut providers {
+        let url_key = match entry.provider.as_str() {
+            "Ollama" => "ollamaBaseUrl",
+            _ => "openaiBaseUrl",
+        };
+        if let Some(url) = get(url_key) {
+            entry.base_url = url;
+        }
+        if entry.provider == active {
+            if let Some(model) = get("modelName") {
+                entry.model = model;
+            }
+        }
+        if let Some(shield) = get("privacyShield") {
+            entry.privacy_shield = shield == "true";
+        }
+    }
+    providers
+}
+
+/// Move the flat keys of serialized settings, as stored in profiles and
+/// exported bundles, into `providers`. Returns whether anything changed.
+pub(super) fn migrate_flat_json(settings: &mut serde_json::Value) -> bool {
+    let Some(object) = settings.as_object_mut() else {
+        return false;
+    };
+    if object.contains_key("providers") || !FLAT_KEYS.iter().any(|k| object.contains_key(*k)) {
+        return false;
+    }
+    let providers = providers_from_flat(|key| match object.get(key)? {
+        serde_json::Value::String(s) => Some(s.clone()),
+        serde_json::Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    });
+    for key in FLAT_KEYS {
+        object.remove(*key);
+    }
+    object.insert(
+        "providers".to_string(),
+        serde_json::to_value(providers).unwrap_or_default(),
+    );
+    true
+}
+
+/// Move the flat `ollamaBaseUrl`/`openaiBaseUrl`/`modelName`/`privacyShield`
+/// settings keys into a `providers` record.
+pub(super) fn migrate_flat_keys(conn: &mut Connection, backup_dir: &Path) -> Result<(), ConfigError> {
+    let db_err = |e: rusqlite::Error| ConfigError::Database(e.to_string());
+
+    let mut stmt = conn
+        .prepare("SELECT key, value FROM settings")
+        .map_err(db_err)?;
+    let settings: HashMap<String, String> = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(db_err)?
+        .collect::<Result<_, _>>()
+        .map_err(db_err)?;
+    drop(stmt);
+
+    if settings.contains_key("providers") || !FLAT_KEYS.iter().any(|k| settings.contains_key(*k)) {
+        return Ok(());
+    }
+
+    if let Err(e) = backup::create(conn, backup_dir, "migration") {
+        log::error!("Failed to back up settings before migration: {}", e);
+    }
+
+    let providers = providers_from_flat(|key| settings.get(key).cloned());
+    let json = serde_json::to_string(&providers).map_err(|e| ConfigError::Database(e.to_string()))?;
+    let tx = conn.transaction().map_err(db_err)?;
+    tx.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES ('providers', ?1)",
+        params![json],
+    )
+    .map_err(db_err)?;
+    for key in FLAT_KEYS {
+        tx.execute("DELETE FROM settings WHERE key = ?1", params![key])
+            .map_err(db_err)?;
+    }
+    tx.commit().map_err(db_err)?;
+
+    log::info!("Migrated provider settings to per-provider records");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_migrate_flat_json() {
+        let mut settings = json!({
+            "aiProvider": "OpenAI",
+            "ollamaBaseUrl": "http://gpu-box:11434",
+            "openaiBaseUrl": "https://proxy.example.com/v1",
+            "modelName": "gpt-4o",
+            "privacyShield": false,
+            "theme": "dark"
+        });
+        assert!(migrate_flat_json(&mut settings));
+        assert!(settings.get("modelName").is_none());
+        assert_eq!(settings["theme"], "dark");
+
+        let providers: Vec<ProviderConfig> = serde_json::from_value(settings["providers"].clone()).unwrap();
+        let [ollama, openai] = [&providers[0], &providers[1]];
+        assert_eq!((ollama.base_url.as_str(), ollama.model.as_str()), ("http://gpu-box:11434", "llama3.2"));
+        assert_eq!((openai.base_url.as_str(), openai.model.as_str()), ("https://proxy.example.com/v1", "gpt-4o"));
+        assert!(!openai.privacy_shield);
+
+        // Already migrated
+        assert!(!migrate_flat_json(&mut settings));
+    }
+}
@@ -0,0 +1,159 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use super::{ConfigError, ConfigManager};
+use crate::regex::Rule;
+
+pub(super) fn init_tables(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS custom_rules (
+            id TEXT PRIMARY KEY,
+            data TEXT NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS rule_order (
+            rule_id TEXT PRIMARY KEY,
+            sort_order INTEGER NOT NULL
+        );
+        CREATE TABLE IF NOT EXISTS rule_hotkeys (
+            id TEXT PRIMARY KEY,
+            hotkey TEXT NOT NULL,
+            rule_ids TEXT NOT NULL
+        );",
+    )
+}
+
+/// A global hotkey that runs a rule pipeline on the clipboard.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleHotkey {
+    pub id: String,
+    pub hotkey: String,
+    pub rule_ids: Vec<String>,
+}
+
+fn db_err(e: rusqlite::Error) -> ConfigError {
+    ConfigError::Database(e.to_string())
+}
+
+impl ConfigManager {
+    /// User-defined rules, stored as JSON so new `Rule` fields don't need migrations.
+    pub fn get_custom_rules(&self) -> Result<Vec<Rule>, ConfigError> {
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare("SELECT id, data FROM custom_rules")
+            .map_err(db_err)?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(db_err)?;
+
+        let mut rules = Vec::new();
+        for row in rows {
+            let (id, data) = row.map_err(db_err)?;
+            match self.decode::<Rule>(&data) {
+                Ok(rule) => rules.push(rule),
+                Err(e) => log::warn!("Skipping unreadable custom rule '{}': {}", id, e),
+            }
+        }
+
+        Ok(rules)
+    }
+
+    pub fn save_custom_rule(&self, rule: &Rule) -> Result<(), ConfigError> {
+        let data = self.encode(rule)?;
+        self.conn()?
+            .execute(
+                "INSERT OR REPLACE INTO custom_rules (id, data) VALUES (?1, ?2)",
+                params![rule.id, data],
+            )
+            .map_err(db_err)?;
+        Ok(())
+    }
+
+    pub fn delete_custom_rule(&self, id: &str) -> Result<(), ConfigError> {
+        let conn = self.conn()?;
+        conn.execute("DELETE FROM custom_rules WHERE id = ?1", params![id])
+            .map_err(db_err)?;
+        conn.execute("DELETE FROM rule_order WHERE rule_id = ?1", params![id])
+            .map_err(db_err)?;
+        Ok(())
+    }
+
+    pub fn get_rule_order(&self) -> Result<HashMap<String, i32>, ConfigError> {
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare("SELECT rule_id, sort_order FROM rule_order")
+            .map_err(db_err)?;
+
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, String>(0)?, row.get::<_, i32>(1)?)))
+            .map_err(db_err)?;
+
+        rows.collect::<Result<HashMap<_, _>, _>>().map_err(db_err)
+    }
+
+    /// Replace the user-defined ordering with the given ids, first id first.
+    pub fn set_rule_order(&self, rule_ids: &[String]) -> Result<(), ConfigError> {
+        let mut conn = self.conn()?;
+        let tx = conn.transaction().map_err(db_err)?;
+        tx.execute("DELETE FROM rule_order", []).map_err(db_err)?;
+        for (pos, id) in rule_ids.iter().enumerate() {
+            tx.execute(
+                "INSERT OR REPLACE INTO rule_order (rule_id, sort_order) VALUES (?1, ?2)",
+                params![id, pos as i32],
+            )
+            .map_err(db_err)?;
+        }
+        tx.commit().map_err(db_err)
+    }
+
+    pub fn get_rule_hotkeys(&self) -> Result<Vec<RuleHotkey>, ConfigError> {
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare("SELECT id, hotkey, rule_ids FROM rule_hotkeys")
+            .map_err(db_err)?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                ))
+            })
+            .map_err(db_err)?;
+
+        let mut hotkeys = Vec::new();
+        for row in rows {
+            let (id, hotkey, rule_ids) = row.map_err(db_err)?;
+            match serde_json::from_str::<Vec<String>>(&rule_ids) {
+                Ok(rule_ids) => hotkeys.push(RuleHotkey { id, hotkey, rule_ids }),
+                Err(e) => log::warn!("Skipping unreadable rule hotkey '{}': {}", id, e),
+            }
+        }
+
+        Ok(hotkeys)
+    }
+
+    pub fn save_rule_hotkey(&self, binding: &RuleHotkey) -> Result<(), ConfigError> {
+        let rule_ids =
+            serde_json::to_string(&binding.rule_ids).map_err(|e| ConfigError::Database(e.to_string()))?;
+        self.conn()?
+            .execute(
+                "INSERT OR REPLACE INTO rule_hotkeys (id, hotkey, rule_ids) VALUES (?1, ?2, ?3)",
+                params![binding.id, binding.hotkey, rule_ids],
+            )
+            .map_err(db_err)?;
+        Ok(())
+    }
+
+    pub fn delete_rule_hotkey(&self, id: &str) -> Result<(), ConfigError> {
+        self.conn()?
+            .execute("DELETE FROM rule_hotkeys WHERE id = ?1", params![id])
+            .map_err(db_err)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use rusqlite::{params, Connection, OptionalExtension};
+
+use super::{ConfigError, ConfigManager};
+use crate::snippet::Snippet;
+
+pub(super) fn init_tables(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS snippets (
+            id TEXT PRIMARY KEY,
+            data TEXT NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn db_err(e: rusqlite::Error) -> ConfigError {
+    ConfigError::Database(e.to_string())
+}
+
+impl ConfigManager {
+    pub fn get_snippets(&self) -> Result<Vec<Snippet>, ConfigError> {
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare("SELECT id, data FROM snippets")
+            .map_err(db_err)?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(db_err)?;
+
+        let mut snippets = Vec::new();
+        for row in rows {
+            let (id, data) = row.map_err(db_err)?;
+            match self.decode::<Snippet>(&data) {
+                Ok(snippet) => snippets.push(snippet),
+                Err(e) => log::warn!("Skipping unreadable snippet '{}': {}", id, e),
+            }
+        }
+
+        snippets.sort_by(|a, b| a.name.to_lowercase().cmp(&b.name.to_lowercase()));
+        Ok(snippets)
+    }
+
+    pub fn get_snippet(&self, id: &str) -> Result<Option<Snippet>, ConfigError> {
+        let data: Option<String> = self
+            .conn()?
+            .query_row("SELECT data FROM snippets WHERE id = ?1", params![id], |row| row.get(0))
+            .optional()
+            .map_err(db_err)?;
+
+        data.map(|d| self.decode(&d)).transpose()
+    }
+
+    pub fn save_snippet(&self, snippet: &Snippet) -> Result<(), ConfigError> {
+        let data = self.encode(snippet)?;
+        self.conn()?
+            .execute(
+                "INSERT OR REPLACE INTO snippets (id, data) VALUES (?1, ?2)",
+                params![snippet.id, data],
+            )
+            .map_err(db_err)?;
+        Ok(())
+    }
+
+    pub fn delete_snippet(&self, id: &str) -> Result<(), ConfigError> {
+        self.conn()?
+            .execute("DELETE FROM snippets WHERE id = ?1", params![id])
+            .map_err(db_err)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use rusqlite::{params, Connection};
+use std::collections::HashMap;
+
+use super::{ConfigError, ConfigManager};
+
+pub(super) fn init_tables(conn: &Connection) -> rusqlite::Result<()> {
+    // What each synced item looked like after the last sync with a folder,
+    // to tell which side changed it since
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS sync_state (
+            folder TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            id TEXT NOT NULL,
+            hash TEXT NOT NULL,
+            PRIMARY KEY (folder, kind, id)
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+fn db_err(e: rusqlite::Error) -> ConfigError {
+    ConfigError::Database(e.to_string())
+}
+
+impl ConfigManager {
+    /// Item id to content hash as of the last sync of `kind` with `folder`.
+    pub fn get_sync_state(&self, folder: &str, kind: &str) -> Result<HashMap<String, String>, ConfigError> {
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare("SELECT id, hash FROM sync_state WHERE folder = ?1 AND kind = ?2")
+            .map_err(db_err)?;
+        let rows = stmt
+            .query_map(params![folder, kind], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(db_err)?;
+        rows.collect::<Result<_, _>>().map_err(db_err)
+    }
+
+    /// Record `hash` as the synced content of an item; `None` forgets it.
+    pub fn set_sync_state(&self, folder: &str, kind: &str, id: &str, hash: Option<&str>) -> Result<(), ConfigError> {
+        let conn = self.conn()?;
+        match hash {
+            Some(hash) => conn.execute(
+                "INSERT OR REPLACE INTO sync_state (folder, kind, id, hash) VALUES (?1, ?2, ?3, ?4)",
+                params![folder, kind, id, hash],
+            ),
+            None => conn.execute(
+                "DELETE FROM sync_state WHERE folder = ?1 AND kind = ?2 AND id = ?3",
+                params![folder, kind, id],
+            ),
+        }
+        .map_err(db_err)?;
+        Ok(())
+    }
+}
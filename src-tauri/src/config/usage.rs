@@ -0,0 +1,178 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use super::{ConfigError, ConfigManager};
+use crate::ai::intent::{content_type_key, decayed_score, ContentType};
+use crate::ai::ChipUsage;
+
+pub(super) fn init_tables(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS rule_usage (
+            target TEXT NOT NULL,
+            target_id TEXT NOT NULL,
+            count INTEGER NOT NULL DEFAULT 0,
+            last_used INTEGER NOT NULL,
+            PRIMARY KEY (target, target_id)
+        )",
+        [],
+    )?;
+    // Per content type, for ranking chips; `score` decays over time
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS chip_usage (
+            content_type TEXT NOT NULL,
+            action_id TEXT NOT NULL,
+            score REAL NOT NULL,
+            last_used INTEGER NOT NULL,
+            PRIMARY KEY (content_type, action_id)
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UsageTarget {
+    Rule,
+    Chip,
+}
+
+impl UsageTarget {
+    fn as_str(&self) -> &'static str {
+        match self {
+            UsageTarget::Rule => "rule",
+            UsageTarget::Chip => "chip",
+        }
+    }
+
+    fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "rule" => Some(UsageTarget::Rule),
+            "chip" => Some(UsageTarget::Chip),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleStat {
+    pub target: UsageTarget,
+    pub id: String,
+    pub count: u64,
+    /// Unix timestamp in milliseconds
+    pub last_used: i64,
+}
+
+fn db_err(e: rusqlite::Error) -> ConfigError {
+    ConfigError::Database(e.to_string())
+}
+
+impl ConfigManager {
+    pub fn record_usage(&self, target: UsageTarget, id: &str) -> Result<(), ConfigError> {
+        let now = chrono::Utc::now().timestamp_millis();
+        self.conn()?
+            .execute(
+                "INSERT INTO rule_usage (target, target_id, count, last_used) VALUES (?1, ?2, 1, ?3)
+                 ON CONFLICT(target, target_id) DO UPDATE SET count = count + 1, last_used = ?3",
+                params![target.as_str(), id, now],
+            )
+            .map_err(db_err)?;
+        if target == UsageTarget::Rule {
+            self.count_rule_use(id)?;
+        }
+        Ok(())
+    }
+
+    /// Count a pick of the chip `action_id` while `content_type` was detected.
+    pub fn record_chip_pick(&self, content_type: ContentType, action_id: &str) -> Result<(), ConfigError> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let key = content_type_key(content_type);
+        let conn = self.conn()?;
+        let previous: Option<(f64, i64)> = conn
+            .query_row(
+                "SELECT score, last_used FROM chip_usage WHERE content_type = ?1 AND action_id = ?2",
+                params![key, action_id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .ok();
+        let score = previous.map_or(0.0, |(score, last_used)| decayed_score(score, last_used, now)) + 1.0;
+        conn.execute(
+            "INSERT OR REPLACE INTO chip_usage (content_type, action_id, score, last_used)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![key, action_id, score, now],
+        )
+        .map_err(db_err)?;
+        Ok(())
+    }
+
+    /// Recorded chip picks for every content type.
+    pub fn get_chip_usage(&self) -> Result<Vec<ChipUsage>, ConfigError> {
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare("SELECT content_type, action_id, score, last_used FROM chip_usage")
+            .map_err(db_err)?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, f64>(2)?,
+                    row.get::<_, i64>(3)?,
+                ))
+            })
+            .map_err(db_err)?;
+
+        let mut usage = Vec::new();
+        for row in rows {
+            let (key, action_id, score, last_used) = row.map_err(db_err)?;
+            // Rows for content types that no longer exist are skipped
+            if let Ok(content_type) = serde_json::from_value(serde_json::Value::String(key)) {
+                usage.push(ChipUsage {
+                    content_type,
+                    action_id,
+                    score,
+                    last_used,
+                });
+            }
+        }
+        Ok(usage)
+    }
+
+    /// All recorded usage, most used first.
+    pub fn get_rule_stats(&self) -> Result<Vec<RuleStat>, ConfigError> {
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT target, target_id, count, last_used FROM rule_usage
+                 ORDER BY count DESC, last_used DESC",
+            )
+            .map_err(db_err)?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                    row.get::<_, i64>(3)?,
+                ))
+            })
+            .map_err(db_err)?;
+
+        let mut stats = Vec::new();
+        for row in rows {
+            let (target, id, count, last_used) = row.map_err(db_err)?;
+            if let Some(target) = UsageTarget::from_str(&target) {
+                stats.push(RuleStat {
+                    target,
+                    id,
+                    count: count.max(0) as u64,
+                    last_used,
+                });
+            }
+        }
+
+        Ok(stats)
+    }
+}
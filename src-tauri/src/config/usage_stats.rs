@@ -0,0 +1,185 @@
+use chrono::{Duration, Local};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use super::{ConfigError, ConfigManager};
+
+pub(super) fn init_tables(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS usage_counters (
+            day TEXT NOT NULL,
+            metric TEXT NOT NULL,
+            count INTEGER NOT NULL DEFAULT 0,
+            PRIMARY KEY (day, metric)
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// Feature counters kept in settings.db only; nothing is ever sent anywhere.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageMetric {
+    Transform,
+    AiRequest,
+    PiiMasked,
+    HotkeyActivation,
+}
+
+impl UsageMetric {
+    fn as_str(&self) -> &'static str {
+        match self {
+            UsageMetric::Transform => "transform",
+            UsageMetric::AiRequest => "ai_request",
+            UsageMetric::PiiMasked => "pii_masked",
+            UsageMetric::HotkeyActivation => "hotkey_activation",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum UsagePeriod {
+    /// Today
+    Day,
+    /// The last 7 days, including today
+    Week,
+    /// The last 30 days, including today
+    Month,
+    All,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageCounts {
+    pub transforms: u64,
+    pub ai_requests: u64,
+    pub pii_masked: u64,
+    pub hotkey_activations: u64,
+}
+
+impl UsageCounts {
+    fn add(&mut self, metric: &str, count: u64) {
+        match metric {
+            "transform" => self.transforms += count,
+            "ai_request" => self.ai_requests += count,
+            "pii_masked" => self.pii_masked += count,
+            "hotkey_activation" => self.hotkey_activations += count,
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DailyUsage {
+    /// Local date, `YYYY-MM-DD`
+    pub day: String,
+    #[serde(flatten)]
+    pub counts: UsageCounts,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageStats {
+    pub period: UsagePeriod,
+    pub totals: UsageCounts,
+    /// Days with any activity, oldest first
+    pub days: Vec<DailyUsage>,
+}
+
+fn db_err(e: rusqlite::Error) -> ConfigError {
+    ConfigError::Database(e.to_string())
+}
+
+pub(super) fn today() -> String {
+    Local::now().format("%Y-%m-%d").to_string()
+}
+
+/// First day of `period` as `YYYY-MM-DD`, which compares correctly as text;
+/// `""` for all time.
+pub(super) fn since(period: UsagePeriod) -> String {
+    let days_back = match period {
+        UsagePeriod::Day => 0,
+        UsagePeriod::Week => 6,
+        UsagePeriod::Month => 29,
+        UsagePeriod::All => return String::new(),
+    };
+    (Local::now() - Duration::days(days_back)).format("%Y-%m-%d").to_string()
+}
+
+/// Whether the user left `usageStats` on.
+pub(super) fn stats_enabled(conn: &Connection) -> Result<bool, ConfigError> {
+    let enabled: Option<String> = conn
+        .query_row("SELECT value FROM settings WHERE key = 'usageStats'", [], |row| row.get(0))
+        .optional()
+        .map_err(db_err)?;
+    Ok(enabled.as_deref() != Some("false"))
+}
+
+impl ConfigManager {
+    /// Add `amount` to today's counter unless the user opted out with
+    /// `usageStats = false`.
+    pub fn count_usage(&self, metric: UsageMetric, amount: u64) -> Result<(), ConfigError> {
+        if amount == 0 {
+            return Ok(());
+        }
+        let conn = self.conn()?;
+        if !stats_enabled(&conn)? {
+            return Ok(());
+        }
+
+        conn.execute(
+            "INSERT INTO usage_counters (day, metric, count) VALUES (?1, ?2, ?3)
+             ON CONFLICT(day, metric) DO UPDATE SET count = count + ?3",
+            params![today(), metric.as_str(), amount as i64],
+        )
+        .map_err(db_err)?;
+        Ok(())
+    }
+
+    /// `count_usage` for call sites where a failure shouldn't matter.
+    pub fn track(&self, metric: UsageMetric, amount: u64) {
+        if let Err(e) = self.count_usage(metric, amount) {
+            log::warn!("Failed to record {} usage: {}", metric.as_str(), e);
+        }
+    }
+
+    pub fn get_usage_stats(&self, period: UsagePeriod) -> Result<UsageStats, ConfigError> {
+        let since = since(period);
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare(
+                "SELECT day, metric, count FROM usage_counters
+                 WHERE day >= ?1 ORDER BY day",
+            )
+            .map_err(db_err)?;
+        let rows = stmt
+            .query_map(params![since], |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, i64>(2)?,
+                ))
+            })
+            .map_err(db_err)?;
+
+        let mut totals = UsageCounts::default();
+        let mut days: Vec<DailyUsage> = Vec::new();
+        for row in rows {
+            let (day, metric, count) = row.map_err(db_err)?;
+            let count = count.max(0) as u64;
+            totals.add(&metric, count);
+            match days.last_mut() {
+                Some(last) if last.day == day => last.counts.add(&metric, count),
+                _ => {
+                    let mut counts = UsageCounts::default();
+                    counts.add(&metric, count);
+                    days.push(DailyUsage { day, counts });
+                }
+            }
+        }
+
+        Ok(UsageStats { period, totals, days })
+    }
+}
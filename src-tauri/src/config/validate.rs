@@ -0,0 +1,300 @@
+use reqwest::Url;
+use serde::Serialize;
+
+use super::{AppConfig, ConfigError};
+use crate::ai::intent::MAX_CHIPS_LIMIT;
+use crate::hotkey::HotkeyManager;
+use crate::i18n::SUPPORTED_LOCALES;
+use crate::logging::LOG_LEVELS;
+use crate::native_messaging;
+use crate::notes;
+use crate::privacy::SHIELD_POLICIES;
+use crate::translation;
+
+const PROVIDERS: &[&str] = &["Ollama", "OpenAI"];
+const THEMES: &[&str] = &["system", "light", "dark"];
+const PANEL_MODES: &[&str] = &["toggle", "hold"];
+/// Below this the panel is hard to find again
+const MIN_OPACITY: f64 = 0.2;
+/// Longer than this and the panel no longer feels instant
+const AI_INTENT_BUDGET_MS: std::ops::RangeInclusive<u64> = 100..=5000;
+/// Lower ports need elevated rights on most systems
+const MIN_PORT: u16 = 1024;
+/// Anything smaller leaves no room for the prompt and the reply
+const MIN_CONTEXT_TOKENS: u32 = 2048;
+/// A day; past that the nightly purge clears the history anyway
+const MAX_CLEAR_PII_MINUTES: u32 = 24 * 60;
+
+/// A rejected `AppConfig` field, named as the frontend sees it
+/// (`providers[1].baseUrl`).
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
+}
+
+pub(super) fn format_field_errors(errors: &[FieldError]) -> String {
+    errors
+        .iter()
+        .map(|e| format!("{}: {}", e.field, e.message))
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+impl AppConfig {
+    /// Check every field and report all problems at once.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut errors = Vec::new();
+        let mut check = |field: &str, result: Result<(), String>| {
+            if let Err(message) = result {
+                errors.push(FieldError {
+                    field: field.to_string(),
+                    message,
+                });
+            }
+        };
+
+        // Anything the panel hotkey can be registered as, including double-taps
+        check(
+            "hotkey",
+            HotkeyManager::normalize_hotkey(&self.hotkey).map(|_| ()).map_err(|e| e.to_string()),
+        );
+        check(
+            "aiProvider",
+            match self.active_provider() {
+                Some(_) => Ok(()),
+                None => Err(format!("no provider entry for '{}'", self.ai_provider)),
+            },
+        );
+        for (i, entry) in self.providers.iter().enumerate() {
+            let field = |name: &str| format!("providers[{}].{}", i, name);
+            check(&field("provider"), one_of(&entry.provider, PROVIDERS));
+            if self.providers[..i].iter().any(|p| p.provider == entry.provider) {
+                check(&field("provider"), Err(format!("'{}' is listed twice", entry.provider)));
+            }
+            check(&field("baseUrl"), http_url(&entry.base_url));
+            check(&field("model"), non_empty(&entry.model));
+            if entry.timeout_secs == 0 {
+                check(&field("timeoutSecs"), Err("must be at least 1".to_string()));
+            }
+            if entry.context_tokens < MIN_CONTEXT_TOKENS {
+                check(&field("contextTokens"), Err(format!("must be at least {}", MIN_CONTEXT_TOKENS)));
+            }
+        }
+        if !self.proxy_url.trim().is_empty() {
+            check("proxyUrl", http_url(&self.proxy_url));
+        }
+        let ca_path = self.ca_cert_path.trim();
+        if !ca_path.is_empty() && !std::path::Path::new(ca_path).is_file() {
+            check("caCertPath", Err(format!("'{}' is not a file", ca_path)));
+        }
+        check("theme", one_of(&self.theme, THEMES));
+        check("panelMode", one_of(&self.panel_mode, PANEL_MODES));
+        check("locale", one_of(&self.locale, SUPPORTED_LOCALES));
+        check("logLevel", one_of(&self.log_level, LOG_LEVELS));
+        check("shieldPolicy", one_of(&self.shield_policy, SHIELD_POLICIES));
+        if !(MIN_OPACITY..=1.0).contains(&self.window_opacity) {
+            check(
+                "windowOpacity",
+                Err(format!("must be between {} and 1", MIN_OPACITY)),
+            );
+        }
+
+        check("chipLayout.maxChips", chip_count(self.chip_layout.max_chips));
+        for (i, entry) in self.chip_layout.types.iter().enumerate() {
+            let field = |name: &str| format!("chipLayout.types[{}].{}", i, name);
+            if let Some(max) = entry.max_chips {
+                check(&field("maxChips"), chip_count(max));
+            }
+            if self.chip_layout.types[..i].iter().any(|t| t.content_type == entry.content_type) {
+                check(&field("contentType"), Err("is listed twice".to_string()));
+            }
+        }
+
+        if !AI_INTENT_BUDGET_MS.contains(&self.ai_intent_budget_ms) {
+            check(
+                "aiIntentBudgetMs",
+                Err(format!(
+                    "must be between {} and {}",
+                    AI_INTENT_BUDGET_MS.start(),
+                    AI_INTENT_BUDGET_MS.end()
+                )),
+            );
+        }
+
+        if self.mcp_port < MIN_PORT {
+            check("mcpPort", Err(format!("must be {} or higher", MIN_PORT)));
+        }
+        if self.http_api_port < MIN_PORT {
+            check("httpApiPort", Err(format!("must be {} or higher", MIN_PORT)));
+        } else if self.mcp_server && self.http_api && self.http_api_port == self.mcp_port {
+            check("httpApiPort", Err("is already used by the MCP server".to_string()));
+        }
+
+        check("ocrEngine", non_empty(&self.ocr_engine));
+        check("ocrLanguages", tesseract_languages(&self.ocr_languages));
+        check("qrDecoder", non_empty(&self.qr_decoder));
+        check("pdfExtractor", non_empty(&self.pdf_extractor));
+        check("whisperCommand", non_empty(&self.whisper_command));
+        let whisper_model = self.whisper_model.trim();
+        if !whisper_model.is_empty() && !std::path::Path::new(whisper_model).is_file() {
+            check("whisperModel", Err(format!("'{}' is not a file", whisper_model)));
+        }
+        check("whisperLanguage", whisper_language(&self.whisper_language));
+        check("translationService", one_of(&self.translation_service, translation::SERVICES));
+        check("notesPath", notes::check_location(&self.notes_path));
+        check("notesFileTemplate", notes::check_file_template(&self.notes_file_template));
+        check("browserExtensionIds", native_messaging::check_extension_ids(&self.browser_extension_ids));
+        check("passwordPolicy", self.password_policy.validate().map_err(|e| e.to_string()));
+        if self.clear_pii_after_minutes > MAX_CLEAR_PII_MINUTES {
+            check(
+                "clearPiiAfterMinutes",
+                Err(format!("must be at most {} minutes", MAX_CLEAR_PII_MINUTES)),
+            );
+        }
+        let sync_folder = std::path::Path::new(self.sync_folder.trim());
+        if !sync_folder.as_os_str().is_empty() && (!sync_folder.is_absolute() || !sync_folder.is_dir()) {
+            check("syncFolder", Err(format!("'{}' is not an existing folder", sync_folder.display())));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(errors))
+        }
+    }
+}
+
+fn non_empty(value: &str) -> Result<(), String> {
+    if value.trim().is_empty() {
+        Err("must not be empty".to_string())
+    } else {
+        Ok(())
+    }
+}
+
+fn one_of(value: &str, allowed: &[&str]) -> Result<(), String> {
+    if allowed.contains(&value) {
+        Ok(())
+    } else {
+        Err(format!("'{}' is not one of {}", value, allowed.join(", ")))
+    }
+}
+
+/// Tesseract language codes joined with `+` (`chi_sim+eng`).
+fn tesseract_languages(value: &str) -> Result<(), String> {
+    let valid = |code: &str| !code.is_empty() && code.chars().all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if value.split('+').all(valid) {
+        Ok(())
+    } else {
+        Err(format!("'{}' is not a list like chi_sim+eng", value))
+    }
+}
+
+/// `auto` or a language code like `zh` or `en`.
+fn whisper_language(value: &str) -> Result<(), String> {
+    let value = value.trim();
+    if value == "auto" || ((2..=3).contains(&value.len()) && value.chars().all(|c| c.is_ascii_lowercase())) {
+        Ok(())
+    } else {
+        Err(format!("'{}' is not a language code like zh or en, or auto", value))
+    }
+}
+
+fn chip_count(value: usize) -> Result<(), String> {
+    if (1..=MAX_CHIPS_LIMIT).contains(&value) {
+        Ok(())
+    } else {
+        Err(format!("must be between 1 and {}", MAX_CHIPS_LIMIT))
+    }
+}
+
+fn http_url(value: &str) -> Result<(), String> {
+    let url = Url::parse(value.trim()).map_err(|e| format!("'{}' is not a valid URL: {}", value, e))?;
+    // http(s) URLs without a host don't parse, so the scheme is all that's left
+    match url.scheme() {
+        "http" | "https" => Ok(()),
+        _ => Err(format!("'{}' must use http or https", value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn field_errors(config: &AppConfig) -> Vec<String> {
+        match config.validate() {
+            Ok(()) => Vec::new(),
+            Err(ConfigError::Invalid(errors)) => errors.into_iter().map(|e| e.field).collect(),
+            Err(e) => panic!("unexpected error: {}", e),
+        }
+    }
+
+    #[test]
+    fn test_default_config_is_valid() {
+        assert!(field_errors(&AppConfig::default()).is_empty());
+    }
+
+    #[test]
+    fn test_validate_reports_every_field() {
+        let mut config = AppConfig {
+            theme: "blue".to_string(),
+            window_opacity: 0.05,
+            mcp_port: 80,
+            ..AppConfig::default()
+        };
+        config.providers[0].base_url = "ftp://localhost".to_string();
+        config.providers[0].timeout_secs = 0;
+        let fields = field_errors(&config);
+        assert!(fields.contains(&"theme".to_string()));
+        assert!(fields.contains(&"windowOpacity".to_string()));
+        assert!(fields.contains(&"mcpPort".to_string()));
+        assert!(fields.contains(&"providers[0].baseUrl".to_string()));
+        assert!(fields.contains(&"providers[0].timeoutSecs".to_string()));
+        assert_eq!(fields.len(), 5);
+    }
+
+    #[test]
+    fn test_validate_shared_port() {
+        let config = AppConfig {
+            mcp_server: true,
+            http_api: true,
+            http_api_port: AppConfig::default().mcp_port,
+            ..AppConfig::default()
+        };
+        assert_eq!(field_errors(&config), vec!["httpApiPort"]);
+    }
+
+    #[test]
+    fn test_field_checks() {
+        assert!(http_url("http://localhost:11434").is_ok());
+        assert!(http_url(" https://api.openai.com/v1 ").is_ok());
+        assert!(http_url("ftp://example.com").is_err());
+        assert!(http_url("localhost:11434").is_err());
+
+        assert!(tesseract_languages("chi_sim+eng").is_ok());
+        assert!(tesseract_languages("eng").is_ok());
+        assert!(tesseract_languages("chi_sim+").is_err());
+        assert!(tesseract_languages("eng,deu").is_err());
+
+        assert!(whisper_language("auto").is_ok());
+        assert!(whisper_language("zh").is_ok());
+        assert!(whisper_language("ZH").is_err());
+        assert!(whisper_language("chinese").is_err());
+
+        assert!(one_of("dark", THEMES).is_ok());
+        assert!(one_of("Dark", THEMES).is_err());
+        assert!(chip_count(0).is_err());
+        assert!(chip_count(MAX_CHIPS_LIMIT).is_ok());
+    }
+
+    #[test]
+    fn test_format_field_errors() {
+        let errors = vec![
+            FieldError { field: "theme".to_string(), message: "bad".to_string() },
+            FieldError { field: "mcpPort".to_string(), message: "too low".to_string() },
+        ];
+        assert_eq!(format_field_errors(&errors), "theme: bad; mcpPort: too low");
+    }
+}
@@ -0,0 +1,123 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use super::{ConfigError, ConfigManager};
+
+pub(super) fn init_tables(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS webhooks (
+            id TEXT PRIMARY KEY,
+            data TEXT NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// What happens when the payload text contains personal data.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookPrivacy {
+    /// Send the text as it is
+    #[default]
+    Off,
+    /// Replace personal data with placeholders before sending
+    Mask,
+    /// Don't send at all
+    Block,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WebhookHeader {
+    pub name: String,
+    pub value: String,
+}
+
+/// An HTTP request the text can be sent to from a chip or hotkey.
+/// `{{clipboard}}` in the URL or body is replaced with the text.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    pub id: String,
+    pub name: String,
+    pub url: String,
+    #[serde(default = "default_method")]
+    pub method: String,
+    #[serde(default)]
+    pub headers: Vec<WebhookHeader>,
+    #[serde(default = "default_body_template")]
+    pub body_template: String,
+    #[serde(default)]
+    pub privacy: WebhookPrivacy,
+    /// Global shortcut that sends the selection (or clipboard)
+    #[serde(default)]
+    pub hotkey: Option<String>,
+    /// Copy the focused app's selection first when run from the hotkey
+    #[serde(default = "default_true")]
+    pub capture_selection: bool,
+}
+
+fn default_method() -> String {
+    "POST".to_string()
+}
+
+fn default_body_template() -> String {
+    "{{clipboard}}".to_string()
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn db_err(e: rusqlite::Error) -> ConfigError {
+    ConfigError::Database(e.to_string())
+}
+
+impl ConfigManager {
+    /// Stored encoded: headers often carry credentials.
+    pub fn get_webhooks(&self) -> Result<Vec<Webhook>, ConfigError> {
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare("SELECT id, data FROM webhooks ORDER BY rowid")
+            .map_err(db_err)?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(db_err)?;
+
+        let mut webhooks = Vec::new();
+        for row in rows {
+            let (id, data) = row.map_err(db_err)?;
+            match self.decode::<Webhook>(&data) {
+                Ok(webhook) => webhooks.push(webhook),
+                Err(e) => log::warn!("Skipping unreadable webhook '{}': {}", id, e),
+            }
+        }
+        Ok(webhooks)
+    }
+
+    pub fn get_webhook(&self, id: &str) -> Result<Option<Webhook>, ConfigError> {
+        Ok(self.get_webhooks()?.into_iter().find(|w| w.id == id))
+    }
+
+    pub fn save_webhook(&self, webhook: &Webhook) -> Result<(), ConfigError> {
+        let data = self.encode(webhook)?;
+        self.conn()?
+            .execute(
+                "INSERT INTO webhooks (id, data) VALUES (?1, ?2)
+                 ON CONFLICT(id) DO UPDATE SET data = ?2",
+                params![webhook.id, data],
+            )
+            .map_err(db_err)?;
+        Ok(())
+    }
+
+    pub fn delete_webhook(&self, id: &str) -> Result<(), ConfigError> {
+        self.conn()?
+            .execute("DELETE FROM webhooks WHERE id = ?1", params![id])
+            .map_err(db_err)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,113 @@
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+
+use super::{ConfigError, ConfigManager};
+
+pub(super) fn init_tables(conn: &Connection) -> rusqlite::Result<()> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS workflows (
+            id TEXT PRIMARY KEY,
+            data TEXT NOT NULL
+        )",
+        [],
+    )?;
+    Ok(())
+}
+
+/// One thing a workflow does to the text it carries from step to step.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum StepAction {
+    /// Copy the focused app's selection, falling back to the clipboard
+    CaptureSelection,
+    ReadClipboard,
+    /// Replace personal data with placeholders; output steps put it back
+    MaskPii,
+    /// Ask the active AI provider, following the privacy shield policy
+    AiPrompt { prompt: String },
+    #[serde(rename_all = "camelCase")]
+    ApplyRule { rule_id: String },
+    /// Send the text to a stored webhook
+    Webhook { id: String },
+    WriteClipboard,
+    /// Write the clipboard and paste into the focused app
+    Paste,
+    /// Desktop notification with `message`, or the start of the text
+    Notify {
+        #[serde(default)]
+        message: Option<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowStep {
+    #[serde(flatten)]
+    pub action: StepAction,
+    /// Go on with the text unchanged when this step fails
+    #[serde(default)]
+    pub continue_on_error: bool,
+}
+
+/// Steps run in order on one text, from a hotkey or a chip.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Workflow {
+    pub id: String,
+    pub name: String,
+    pub steps: Vec<WorkflowStep>,
+    #[serde(default)]
+    pub hotkey: Option<String>,
+}
+
+fn db_err(e: rusqlite::Error) -> ConfigError {
+    ConfigError::Database(e.to_string())
+}
+
+impl ConfigManager {
+    pub fn get_workflows(&self) -> Result<Vec<Workflow>, ConfigError> {
+        let conn = self.conn()?;
+        let mut stmt = conn
+            .prepare("SELECT id, data FROM workflows ORDER BY rowid")
+            .map_err(db_err)?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })
+            .map_err(db_err)?;
+
+        let mut workflows = Vec::new();
+        for row in rows {
+            let (id, data) = row.map_err(db_err)?;
+            match self.decode::<Workflow>(&data) {
+                Ok(workflow) => workflows.push(workflow),
+                Err(e) => log::warn!("Skipping unreadable workflow '{}': {}", id, e),
+            }
+        }
+        Ok(workflows)
+    }
+
+    pub fn get_workflow(&self, id: &str) -> Result<Option<Workflow>, ConfigError> {
+        Ok(self.get_workflows()?.into_iter().find(|w| w.id == id))
+    }
+
+    pub fn save_workflow(&self, workflow: &Workflow) -> Result<(), ConfigError> {
+        let data = self.encode(workflow)?;
+        self.conn()?
+            .execute(
+                "INSERT INTO workflows (id, data) VALUES (?1, ?2)
+                 ON CONFLICT(id) DO UPDATE SET data = ?2",
+                params![workflow.id, data],
+            )
+            .map_err(db_err)?;
+        Ok(())
+    }
+
+    pub fn delete_workflow(&self, id: &str) -> Result<(), ConfigError> {
+        self.conn()?
+            .execute("DELETE FROM workflows WHERE id = ?1", params![id])
+            .map_err(db_err)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,205 @@
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use thiserror::Error;
+
+use crate::clipboard::{self, ClipboardError};
+use crate::config::{AppConfig, ConfigError, ConfigManager};
+use crate::hotkey::exclusion;
+use crate::hotkey::listener::{self, TypedEvent};
+use crate::snippet::{render, Abbreviation, AbbreviationMatcher, RenderContext, SnippetError, Typed};
+
+/// Time for the paste to land before the previous clipboard is put back.
+const RESTORE_DELAY: Duration = Duration::from_millis(300);
+
+#[derive(Debug, Error)]
+enum ExpandError {
+    #[error(transparent)]
+    Snippet(#[from] SnippetError),
+    #[error(transparent)]
+    Clipboard(#[from] ClipboardError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error("failed to simulate key input: {0}")]
+    Input(String),
+}
+
+/// Background text expansion. Managed as state; the key listener starts the
+/// first time expansion is turned on and afterwards only follows the flag.
+#[derive(Default)]
+pub struct TextExpander {
+    enabled: AtomicBool,
+    excluded_apps: Mutex<Vec<String>>,
+    /// `None` until loaded, and again after snippets change
+    abbreviations: Mutex<Option<Vec<Abbreviation>>>,
+    started: AtomicBool,
+}
+
+impl TextExpander {
+    fn abbreviations<R: Runtime>(&self, app: &AppHandle<R>) -> Vec<Abbreviation> {
+        let mut cached = self.abbreviations.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(abbreviations) = cached.as_ref() {
+            return abbreviations.clone();
+        }
+        let Some(config) = app.try_state::<ConfigManager>() else {
+            return Vec::new();
+        };
+        let loaded: Vec<Abbreviation> = match config.get_snippets() {
+            Ok(snippets) => snippets
+                .into_iter()
+                .filter_map(|s| {
+                    let text = s.abbreviation?;
+                    Some(Abbreviation { text, snippet_id: s.id })
+                })
+                .collect(),
+            Err(e) => {
+                log::warn!("Failed to load snippet abbreviations: {}", e);
+                Vec::new()
+            }
+        };
+        *cached = Some(loaded.clone());
+        loaded
+    }
+
+    fn is_excluded(&self) -> bool {
+        let excluded = self.excluded_apps.lock().unwrap_or_else(|e| e.into_inner());
+        !excluded.is_empty()
+            && exclusion::frontmost_app().is_some_and(|app| exclusion::is_excluded(&app.name, &app.process_path, &excluded))
+    }
+}
+
+/// Apply `text_expansion`/`text_expansion_excluded_apps`.
+pub fn configure<R: Runtime>(app: &AppHandle<R>, config: &AppConfig) {
+    let state = app.state::<TextExpander>();
+    state.enabled.store(config.text_expansion, Ordering::SeqCst);
+    *state.excluded_apps.lock().unwrap_or_else(|e| e.into_inner()) = config
+        .text_expansion_excluded_apps
+        .split(',')
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty())
+        .collect();
+    if config.text_expansion && !state.started.swap(true, Ordering::SeqCst) {
+        spawn(app.clone());
+    }
+}
+
+/// Pick up added, changed or removed abbreviations.
+pub fn reload_abbreviations<R: Runtime>(app: &AppHandle<R>) {
+    *app.state::<TextExpander>().abbreviations.lock().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+fn spawn<R: Runtime>(app: AppHandle<R>) {
+    let events = listener::subscribe_typed();
+    let spawned = thread::Builder::new()
+        .name("text-expander".to_string())
+        .spawn(move || {
+            let mut matcher = AbbreviationMatcher::default();
+            let mut shortcut_held = false;
+            while let Ok(event) = events.recv() {
+                let state = app.state::<TextExpander>();
+                let Some(typed) = classify(event, &mut shortcut_held) else {
+                    continue;
+                };
+                if !state.enabled.load(Ordering::SeqCst) {
+                    matcher.input(Typed::Reset, &[]);
+                    continue;
+                }
+                let Some(found) = matcher.input(typed, &state.abbreviations(&app)) else {
+                    continue;
+                };
+                if state.is_excluded() {
+                    continue;
+                }
+                if let Err(e) = expand(&app, &found) {
+                    log::warn!("Failed to expand '{}': {}", found.text, e);
+                }
+                // Our own backspaces and paste come back through the listener
+                for _ in events.try_iter() {}
+            }
+        });
+
+    if let Err(e) = spawned {
+        log::error!("Failed to start text expander: {}", e);
+    }
+}
+
+/// Turn a key event into what the matcher needs. Keys pressed with Ctrl,
+/// Alt or Meta are shortcuts, not typing.
+fn classify(event: TypedEvent, shortcut_held: &mut bool) -> Option<Typed> {
+    use rdev::Key as K;
+
+    let (key, pressed, text) = match event {
+        TypedEvent::Click => return Some(Typed::Reset),
+        TypedEvent::Key { key, pressed, text } => (key, pressed, text),
+    };
+    if matches!(key, K::ControlLeft | K::ControlRight | K::Alt | K::MetaLeft | K::MetaRight) {
+        *shortcut_held = pressed;
+        return None;
+    }
+    if !pressed {
+        return None;
+    }
+    if *shortcut_held {
+        return Some(Typed::Reset);
+    }
+    match key {
+        K::Backspace => Some(Typed::Backspace),
+        K::Return
+        | K::KpReturn
+        | K::Tab
+        | K::Escape
+        | K::Delete
+        | K::UpArrow
+        | K::DownArrow
+        | K::LeftArrow
+        | K::RightArrow
+        | K::Home
+        | K::End
+        | K::PageUp
+        | K::PageDown => Some(Typed::Reset),
+        _ => text
+            .filter(|t| !t.is_empty() && !t.chars().any(char::is_control))
+            .map(Typed::Text),
+    }
+}
+
+/// Erase the abbreviation and paste the snippet in its place, keeping the
+/// user's clipboard. Fill-ins without a default are left empty.
+fn expand<R: Runtime>(app: &AppHandle<R>, found: &Abbreviation) -> Result<(), ExpandError> {
+    let snippet = app
+        .state::<ConfigManager>()
+        .get_snippet(&found.snippet_id)?
+        .ok_or_else(|| SnippetError::NotFound(found.snippet_id.clone()))?;
+    let previous = clipboard::read_clipboard(app).ok().and_then(|c| c.text);
+    let ctx = RenderContext {
+        clipboard: previous.clone(),
+        values: HashMap::new(),
+        now: chrono::Local::now(),
+    };
+    let rendered = render(&snippet.template, &ctx)?;
+
+    tap(Key::Backspace, found.text.chars().count())?;
+    clipboard::write_clipboard(app, &rendered.text)?;
+    clipboard::simulate_paste()?;
+    if let Some(cursor) = rendered.cursor {
+        tap(Key::LeftArrow, rendered.text.chars().count() - cursor)?;
+    }
+
+    if let Some(previous) = previous {
+        thread::sleep(RESTORE_DELAY);
+        clipboard::write_clipboard(app, &previous)?;
+    }
+    Ok(())
+}
+
+fn tap(key: Key, times: usize) -> Result<(), ExpandError> {
+    let mut enigo = Enigo::new(&Settings::default()).map_err(|e| ExpandError::Input(e.to_string()))?;
+    for _ in 0..times {
+        enigo.key(key, Direction::Click).map_err(|e| ExpandError::Input(e.to_string()))?;
+    }
+    Ok(())
+}
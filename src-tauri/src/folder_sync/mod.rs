@@ -0,0 +1,463 @@
+//! Two-way sync of custom rules, snippets and AI prompt chips with a folder
+//! that Dropbox, iCloud Drive, Syncthing or the like keeps the same across
+//! machines. Each item is one JSON file. What every item looked like after
+//! the last sync tells which side changed it; items changed on both sides
+//! are left alone until the user picks one.
+
+mod plan;
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime};
+use thiserror::Error;
+
+use crate::ai::intent::ActionType;
+use crate::ai::CustomIntent;
+use crate::config::{ConfigError, ConfigManager};
+use crate::hotkey::{self, HotkeyManager};
+use crate::regex::{self, Rule};
+use crate::snippet::{self, Snippet};
+use crate::text::{self, HashAlgorithm};
+use crate::expansion;
+use plan::Step;
+
+/// Held while a sync runs, so the scheduler and the settings page can't
+/// write the same files at once
+static SYNC_LOCK: Mutex<()> = Mutex::new(());
+
+#[derive(Debug, Error)]
+pub enum SyncError {
+    #[error("no sync folder is set")]
+    NoFolder,
+    #[error("sync folder '{0}' doesn't exist")]
+    FolderMissing(String),
+    #[error("a sync is already running")]
+    Busy,
+    #[error("sync folder error: {0}")]
+    Io(String),
+    #[error("invalid item: {0}")]
+    Invalid(String),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+}
+
+fn io_err(e: std::io::Error) -> SyncError {
+    SyncError::Io(e.to_string())
+}
+
+fn json_err(e: serde_json::Error) -> SyncError {
+    SyncError::Invalid(e.to_string())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncKind {
+    Rule,
+    Snippet,
+    /// Custom chips that send a prompt to the AI
+    Prompt,
+}
+
+const KINDS: [SyncKind; 3] = [SyncKind::Rule, SyncKind::Snippet, SyncKind::Prompt];
+
+impl SyncKind {
+    fn name(self) -> &'static str {
+        match self {
+            SyncKind::Rule => "rule",
+            SyncKind::Snippet => "snippet",
+            SyncKind::Prompt => "prompt",
+        }
+    }
+
+    /// Subfolder of the sync folder holding this kind
+    fn dir(self) -> &'static str {
+        match self {
+            SyncKind::Rule => "rules",
+            SyncKind::Snippet => "snippets",
+            SyncKind::Prompt => "prompts",
+        }
+    }
+}
+
+/// An item changed on both sides since the last sync.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncConflict {
+    pub kind: SyncKind,
+    pub id: String,
+    /// Name or label, from whichever side still has the item
+    pub name: String,
+    /// `false` when it was deleted here
+    pub local_exists: bool,
+    /// `false` when its file was deleted
+    pub remote_exists: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncReport {
+    /// Items written to the folder
+    pub pushed: usize,
+    /// Items saved from the folder
+    pub pulled: usize,
+    /// Items deleted on either side
+    pub deleted: usize,
+    pub conflicts: Vec<SyncConflict>,
+    /// Files that couldn't be used, with the reason
+    pub skipped: Vec<String>,
+}
+
+/// Which side of a conflict to keep.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Keep {
+    Local,
+    Remote,
+}
+
+/// An item read from the sync folder.
+struct RemoteItem {
+    value: Value,
+    path: PathBuf,
+}
+
+fn to_value<T: Serialize>(item: &T) -> Result<Value, SyncError> {
+    serde_json::to_value(item).map_err(json_err)
+}
+
+fn from_value<T: DeserializeOwned>(value: &Value) -> Result<T, SyncError> {
+    serde_json::from_value(value.clone()).map_err(json_err)
+}
+
+fn content_hash(value: &Value) -> String {
+    text::hash_bytes(value.to_string().as_bytes(), HashAlgorithm::Sha256)
+}
+
+fn display_name(value: &Value) -> Option<String> {
+    ["name", "label"]
+        .iter()
+        .find_map(|key| value.get(key)?.as_str().map(str::to_string))
+}
+
+/// Hex digits of the id's hash in its file name
+const ID_HASH_LEN: usize = 12;
+
+/// `id` made safe to use as a file name. Ids that only differ in unsafe
+/// characters or case stay apart thanks to a hash of the id, as file
+/// systems synced from macOS or Windows ignore case.
+fn file_name(id: &str) -> String {
+    let stem: String = id
+        .chars()
+        .enumerate()
+        .map(|(i, c)| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '-' | '_' => c,
+            '.' if i > 0 => c,
+            _ => '_',
+        })
+        .collect();
+    let hash = text::hash_bytes(id.as_bytes(), HashAlgorithm::Sha256);
+    format!("{}-{}.json", stem, &hash[..ID_HASH_LEN])
+}
+
+/// Items of `kind` stored here, by id.
+fn local_items(config: &ConfigManager, kind: SyncKind) -> Result<HashMap<String, Value>, SyncError> {
+    let mut items = HashMap::new();
+    match kind {
+        SyncKind::Rule => {
+            for rule in config.get_custom_rules()? {
+                items.insert(rule.id.clone(), to_value(&rule)?);
+            }
+        }
+        SyncKind::Snippet => {
+            for snippet in config.get_snippets()? {
+                items.insert(snippet.id.clone(), to_value(&snippet)?);
+            }
+        }
+        SyncKind::Prompt => {
+            for intent in config.get_custom_intents()? {
+                if intent.action_type == ActionType::AIPrompt {
+                    items.insert(intent.id.clone(), to_value(&intent)?);
+                }
+            }
+        }
+    }
+    Ok(items)
+}
+
+/// Read and check one file, with the same checks as saving in the app.
+fn parse_item(kind: SyncKind, json: &str) -> Result<(String, Value), String> {
+    match kind {
+        SyncKind::Rule => {
+            let rule: Rule = serde_json::from_str(json).map_err(|e| e.to_string())?;
+            if rule.id.trim().is_empty() || regex::is_builtin_rule(&rule.id) {
+                return Err(format!("rule id '{}' can't be used", rule.id));
+            }
+            regex::validate_rule(&rule).map_err(|e| e.to_string())?;
+            Ok((rule.id.clone(), serde_json::to_value(&rule).map_err(|e| e.to_string())?))
+        }
+        SyncKind::Snippet => {
+            let snippet: Snippet = serde_json::from_str(json).map_err(|e| e.to_string())?;
+            if snippet.id.trim().is_empty() || snippet.name.trim().is_empty() {
+                return Err("snippet id and name must not be empty".to_string());
+            }
+            snippet::validate_template(&snippet.template).map_err(|e| e.to_string())?;
+            Ok((snippet.id.clone(), serde_json::to_value(&snippet).map_err(|e| e.to_string())?))
+        }
+        SyncKind::Prompt => {
+            let intent: CustomIntent = serde_json::from_str(json).map_err(|e| e.to_string())?;
+            intent.validate()?;
+            if intent.action_type != ActionType::AIPrompt {
+                return Err("not an AI prompt".to_string());
+            }
+            Ok((intent.id.clone(), serde_json::to_value(&intent).map_err(|e| e.to_string())?))
+        }
+    }
+}
+
+/// Items of `kind` in the sync folder by id, and the files that were
+/// skipped with the reason.
+fn remote_items(folder: &Path, kind: SyncKind) -> Result<(HashMap<String, RemoteItem>, Vec<String>), SyncError> {
+    let dir = folder.join(kind.dir());
+    let mut items = HashMap::new();
+    let mut skipped = Vec::new();
+    if !dir.is_dir() {
+        return Ok((items, skipped));
+    }
+
+    let mut paths: Vec<PathBuf> = fs::read_dir(&dir)
+        .map_err(io_err)?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .filter(|path| {
+            let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+            !name.starts_with('.') && name.ends_with(".json") && path.is_file()
+        })
+        .collect();
+    paths.sort();
+
+    for path in paths {
+        let shown = format!("{}/{}", kind.dir(), path.file_name().unwrap_or_default().to_string_lossy());
+        let parsed = fs::read_to_string(&path)
+            .map_err(|e| e.to_string())
+            .and_then(|json| parse_item(kind, &json));
+        match parsed {
+            Ok((id, _)) if items.contains_key(&id) => {
+                skipped.push(format!("{}: another file already has id '{}'", shown, id));
+            }
+            Ok((id, value)) => {
+                items.insert(id, RemoteItem { value, path });
+            }
+            Err(e) => skipped.push(format!("{}: {}", shown, e)),
+        }
+    }
+    Ok((items, skipped))
+}
+
+/// Write an item to its file, replacing the old one in a single rename so
+/// the sync service never picks up half a file.
+fn write_remote(folder: &Path, kind: SyncKind, id: &str, existing: Option<&Path>, value: &Value) -> Result<(), SyncError> {
+    let dir = folder.join(kind.dir());
+    fs::create_dir_all(&dir).map_err(io_err)?;
+    let path = existing.map(Path::to_path_buf).unwrap_or_else(|| dir.join(file_name(id)));
+    let temp = dir.join(format!(".{}.tmp", file_name(id)));
+    let json = serde_json::to_string_pretty(value).map_err(json_err)?;
+    fs::write(&temp, json + "\n").map_err(io_err)?;
+    fs::rename(&temp, &path).map_err(io_err)
+}
+
+fn save_local(config: &ConfigManager, kind: SyncKind, value: &Value) -> Result<(), SyncError> {
+    match kind {
+        SyncKind::Rule => config.save_custom_rule(&from_value::<Rule>(value)?)?,
+        SyncKind::Snippet => config.save_snippet(&from_value::<Snippet>(value)?)?,
+        SyncKind::Prompt => config.save_custom_intent(&from_value::<CustomIntent>(value)?)?,
+    }
+    Ok(())
+}
+
+fn delete_local(config: &ConfigManager, kind: SyncKind, id: &str) -> Result<(), SyncError> {
+    match kind {
+        SyncKind::Rule => config.delete_custom_rule(id)?,
+        SyncKind::Snippet => config.delete_snippet(id)?,
+        SyncKind::Prompt => config.delete_custom_intent(id)?,
+    }
+    Ok(())
+}
+
+/// Sync one kind. Returns whether anything changed here.
+fn sync_kind(config: &ConfigManager, folder: &str, kind: SyncKind, report: &mut SyncReport) -> Result<bool, SyncError> {
+    let root = Path::new(folder);
+    let local = local_items(config, kind)?;
+    let (remote, skipped) = remote_items(root, kind)?;
+    report.skipped.extend(skipped);
+
+    let local_hashes: HashMap<String, String> = local.iter().map(|(id, v)| (id.clone(), content_hash(v))).collect();
+    let remote_hashes: HashMap<String, String> =
+        remote.iter().map(|(id, item)| (id.clone(), content_hash(&item.value))).collect();
+    let base = config.get_sync_state(folder, kind.name())?;
+
+    let mut changed = false;
+    for (id, step) in plan::plan(&local_hashes, &remote_hashes, &base) {
+        let synced = match step {
+            Step::Push => {
+                let existing = remote.get(&id).map(|item| item.path.as_path());
+                write_remote(root, kind, &id, existing, &local[&id])?;
+                report.pushed += 1;
+                local_hashes.get(&id)
+            }
+            Step::Pull => {
+                save_local(config, kind, &remote[&id].value)?;
+                changed = true;
+                report.pulled += 1;
+                remote_hashes.get(&id)
+            }
+            Step::DeleteRemote => {
+                fs::remove_file(&remote[&id].path).map_err(io_err)?;
+                report.deleted += 1;
+                None
+            }
+            Step::DeleteLocal => {
+                delete_local(config, kind, &id)?;
+                changed = true;
+                report.deleted += 1;
+                None
+            }
+            Step::Settle => local_hashes.get(&id),
+            Step::Conflict => {
+                let value = local.get(&id).or_else(|| remote.get(&id).map(|item| &item.value));
+                report.conflicts.push(SyncConflict {
+                    kind,
+                    name: value.and_then(display_name).unwrap_or_else(|| id.clone()),
+                    local_exists: local.contains_key(&id),
+                    remote_exists: remote.contains_key(&id),
+                    id,
+                });
+                continue;
+            }
+        };
+        config.set_sync_state(folder, kind.name(), &id, synced.map(String::as_str))?;
+    }
+    Ok(changed)
+}
+
+/// Settle a conflict by copying `keep`'s side over the other. Returns
+/// whether anything changed here.
+fn resolve_item(config: &ConfigManager, folder: &str, kind: SyncKind, id: &str, keep: Keep) -> Result<bool, SyncError> {
+    let root = Path::new(folder);
+    let local = local_items(config, kind)?.remove(id);
+    let remote = remote_items(root, kind)?.0.remove(id);
+
+    let (kept, changed) = match keep {
+        Keep::Local => {
+            match (&local, &remote) {
+                (Some(value), _) => write_remote(root, kind, id, remote.as_ref().map(|r| r.path.as_path()), value)?,
+                (None, Some(item)) => fs::remove_file(&item.path).map_err(io_err)?,
+                (None, None) => {}
+            }
+            (local, false)
+        }
+        Keep::Remote => {
+            let value = remote.map(|item| item.value);
+            match &value {
+                Some(value) => save_local(config, kind, value)?,
+                None => delete_local(config, kind, id)?,
+            }
+            (value, true)
+        }
+    };
+    let hash = kept.as_ref().map(content_hash);
+    config.set_sync_state(folder, kind.name(), id, hash.as_deref())?;
+    Ok(changed)
+}
+
+/// Run `f` on the configured sync folder with the sync lock held, off the
+/// async runtime, then reload what depends on rules and snippets.
+async fn with_folder<R, F>(app: &AppHandle<R>, f: F) -> Result<SyncReport, SyncError>
+where
+    R: Runtime,
+    F: FnOnce(&ConfigManager, &str) -> Result<(SyncReport, Vec<SyncKind>), SyncError> + Send + 'static,
+{
+    let folder = app.state::<ConfigManager>().effective_config()?.sync_folder.trim().to_string();
+    if folder.is_empty() {
+        return Err(SyncError::NoFolder);
+    }
+    if !Path::new(&folder).is_dir() {
+        return Err(SyncError::FolderMissing(folder));
+    }
+
+    let handle = app.clone();
+    let (report, changed) = tauri::async_runtime::spawn_blocking(move || {
+        let _lock = SYNC_LOCK.try_lock().map_err(|_| SyncError::Busy)?;
+        f(&handle.state::<ConfigManager>(), &folder)
+    })
+    .await
+    .map_err(|e| SyncError::Io(e.to_string()))??;
+
+    if changed.contains(&SyncKind::Rule) {
+        // Pulled rules may carry shortcuts
+        let hotkeys = app.state::<HotkeyManager>();
+        hotkeys.unregister_all(app).await;
+        hotkey::restore_bindings(app).await;
+    }
+    if changed.contains(&SyncKind::Snippet) {
+        expansion::reload_abbreviations(app);
+    }
+    Ok(report)
+}
+
+/// Sync rules, snippets and prompts with the configured folder.
+pub async fn sync<R: Runtime>(app: &AppHandle<R>) -> Result<SyncReport, SyncError> {
+    let report = with_folder(app, |config, folder| {
+        let mut report = SyncReport::default();
+        let mut changed = Vec::new();
+        for kind in KINDS {
+            if sync_kind(config, folder, kind, &mut report)? {
+                changed.push(kind);
+            }
+        }
+        Ok((report, changed))
+    })
+    .await?;
+    log::info!(
+        "Synced with folder: {} pushed, {} pulled, {} deleted, {} conflict(s)",
+        report.pushed,
+        report.pulled,
+        report.deleted,
+        report.conflicts.len()
+    );
+    Ok(report)
+}
+
+/// Settle the conflict on item `id` by keeping one side.
+pub async fn resolve_conflict<R: Runtime>(app: &AppHandle<R>, kind: SyncKind, id: String, keep: Keep) -> Result<(), SyncError> {
+    with_folder(app, move |config, folder| {
+        let changed = resolve_item(config, folder, kind, &id, keep)?;
+        Ok((SyncReport::default(), if changed { vec![kind] } else { Vec::new() }))
+    })
+    .await
+    .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_name() {
+        let name = file_name("custom_dashes-2");
+        assert!(name.starts_with("custom_dashes-2-") && name.ends_with(".json"), "{}", name);
+        assert_eq!(name.len(), "custom_dashes-2-.json".len() + ID_HASH_LEN);
+        assert!(file_name("work/mail sig").starts_with("work_mail_sig-"));
+        assert!(file_name(".hidden").starts_with("_hidden-"));
+        assert_eq!(file_name("a/b"), file_name("a/b"));
+    }
+
+    #[test]
+    fn test_file_names_stay_apart() {
+        for (a, b) in [("a/b", "a_b"), ("a b", "a?b"), ("Rule", "rule"), (".x", "_x")] {
+            assert_ne!(file_name(a).to_lowercase(), file_name(b).to_lowercase(), "{} and {}", a, b);
+        }
+    }
+}
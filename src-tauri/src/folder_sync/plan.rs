@@ -0,0 +1,92 @@
+use std::collections::{BTreeSet, HashMap};
+
+/// What to do with one item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Step {
+    /// Added or changed here: write it to the folder
+    Push,
+    /// Added or changed in the folder: save it here
+    Pull,
+    /// Deleted here: delete its file
+    DeleteRemote,
+    /// File deleted: delete it here
+    DeleteLocal,
+    /// Both sides already agree; only the sync state is out of date
+    Settle,
+    /// Changed differently on both sides since the last sync
+    Conflict,
+}
+
+/// Compare an item's content hash here, in the folder and after the last
+/// sync; `None` where it doesn't exist.
+pub fn decide(local: Option<&str>, remote: Option<&str>, base: Option<&str>) -> Option<Step> {
+    if local == remote {
+        return (base != local).then_some(Step::Settle);
+    }
+    if local == base {
+        return Some(if remote.is_some() { Step::Pull } else { Step::DeleteLocal });
+    }
+    if remote == base {
+        return Some(if local.is_some() { Step::Push } else { Step::DeleteRemote });
+    }
+    Some(Step::Conflict)
+}
+
+/// Steps for every item id found in any of the maps of id to content hash,
+/// in id order.
+pub fn plan(
+    local: &HashMap<String, String>,
+    remote: &HashMap<String, String>,
+    base: &HashMap<String, String>,
+) -> Vec<(String, Step)> {
+    let ids: BTreeSet<&String> = local.keys().chain(remote.keys()).chain(base.keys()).collect();
+    ids.into_iter()
+        .filter_map(|id| {
+            let [local, remote, base] = [local, remote, base].map(|items| items.get(id).map(String::as_str));
+            decide(local, remote, base).map(|step| (id.clone(), step))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decide() {
+        assert_eq!(decide(Some("a"), Some("a"), Some("a")), None);
+        assert_eq!(decide(Some("a"), Some("a"), None), Some(Step::Settle));
+        assert_eq!(decide(None, None, Some("a")), Some(Step::Settle));
+
+        assert_eq!(decide(Some("b"), Some("a"), Some("a")), Some(Step::Push));
+        assert_eq!(decide(Some("a"), None, None), Some(Step::Push));
+        assert_eq!(decide(None, Some("a"), Some("a")), Some(Step::DeleteRemote));
+        assert_eq!(decide(Some("a"), Some("b"), Some("a")), Some(Step::Pull));
+        assert_eq!(decide(None, Some("a"), None), Some(Step::Pull));
+        assert_eq!(decide(Some("a"), None, Some("a")), Some(Step::DeleteLocal));
+
+        assert_eq!(decide(Some("b"), Some("c"), Some("a")), Some(Step::Conflict));
+        assert_eq!(decide(Some("b"), None, Some("a")), Some(Step::Conflict));
+        // First sync with different versions on each side
+        assert_eq!(decide(Some("a"), Some("b"), None), Some(Step::Conflict));
+    }
+
+    #[test]
+    fn test_plan() {
+        let map = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+        };
+        let local = map(&[("same", "1"), ("edited", "2"), ("new", "1")]);
+        let remote = map(&[("same", "1"), ("edited", "1"), ("theirs", "1")]);
+        let base = map(&[("same", "1"), ("edited", "1"), ("gone", "1")]);
+        assert_eq!(
+            plan(&local, &remote, &base),
+            [
+                ("edited".to_string(), Step::Push),
+                ("gone".to_string(), Step::Settle),
+                ("new".to_string(), Step::Push),
+                ("theirs".to_string(), Step::Pull),
+            ]
+        );
+    }
+}
@@ -0,0 +1,195 @@
+use std::time::{Duration, Instant};
+
+use super::double_tap::TapModifier;
+use super::listener::{self, KeyEvent};
+
+pub const DEFAULT_CAPTURE_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum CaptureStep {
+    Pending,
+    Captured(String),
+    Cancelled,
+}
+
+/// Builds an accelerator from live key events: modifiers are collected
+/// while held and the first other key completes the combination.
+#[derive(Default)]
+pub struct HotkeyRecorder {
+    held: Vec<TapModifier>,
+}
+
+impl HotkeyRecorder {
+    pub fn on_key(&mut self, event: KeyEvent) -> CaptureStep {
+        if let Some(modifier) = TapModifier::from_key(event.key) {
+            if event.pressed {
+                if !self.held.contains(&modifier) {
+                    self.held.push(modifier);
+                }
+            } else {
+                self.held.retain(|m| *m != modifier);
+            }
+            return CaptureStep::Pending;
+        }
+
+        if !event.pressed {
+            return CaptureStep::Pending;
+        }
+        if event.key == rdev::Key::Escape && self.held.is_empty() {
+            return CaptureStep::Cancelled;
+        }
+
+        match key_name(event.key) {
+            Some(key) => CaptureStep::Captured(accelerator(&self.held, key)),
+            // Keys the parser can't bind are ignored rather than recorded
+            None => CaptureStep::Pending,
+        }
+    }
+}
+
+/// Wait for the next key combination on the global listener. Returns `None`
+/// on Escape or when `timeout` passes. Blocking.
+pub fn capture_next(timeout: Duration) -> Option<String> {
+    let events = listener::subscribe();
+    let deadline = Instant::now() + timeout;
+    let mut recorder = HotkeyRecorder::default();
+
+    loop {
+        let remaining = deadline.checked_duration_since(Instant::now())?;
+        let event = events.recv_timeout(remaining).ok()?;
+        match recorder.on_key(event) {
+            CaptureStep::Pending => continue,
+            CaptureStep::Captured(hotkey) => return Some(hotkey),
+            CaptureStep::Cancelled => return None,
+        }
+    }
+}
+
+/// Canonical modifier order: Ctrl, Alt, Shift, Meta.
+fn accelerator(held: &[TapModifier], key: &str) -> String {
+    let mut parts: Vec<&str> = [
+        (TapModifier::Ctrl, "Ctrl"),
+        (TapModifier::Alt, "Alt"),
+        (TapModifier::Shift, "Shift"),
+        (TapModifier::Meta, "Meta"),
+    ]
+    .iter()
+    .filter(|(m, _)| held.contains(m))
+    .map(|(_, name)| *name)
+    .collect();
+    parts.push(key);
+    parts.join("+")
+}
+
+/// Name of `key` as understood by `parse_key_code`.
+fn key_name(key: rdev::Key) -> Option<&'static str> {
+    use rdev::Key::*;
+    let name = match key {
+        KeyA => "A", KeyB => "B", KeyC => "C", KeyD => "D", KeyE => "E", KeyF => "F",
+        KeyG => "G", KeyH => "H", KeyI => "I", KeyJ => "J", KeyK => "K", KeyL => "L",
+        KeyM => "M", KeyN => "N", KeyO => "O", KeyP => "P", KeyQ => "Q", KeyR => "R",
+        KeyS => "S", KeyT => "T", KeyU => "U", KeyV => "V", KeyW => "W", KeyX => "X",
+        KeyY => "Y", KeyZ => "Z",
+        Num0 => "0", Num1 => "1", Num2 => "2", Num3 => "3", Num4 => "4",
+        Num5 => "5", Num6 => "6", Num7 => "7", Num8 => "8", Num9 => "9",
+        F1 => "F1", F2 => "F2", F3 => "F3", F4 => "F4", F5 => "F5", F6 => "F6",
+        F7 => "F7", F8 => "F8", F9 => "F9", F10 => "F10", F11 => "F11", F12 => "F12",
+        Kp0 => "Numpad0", Kp1 => "Numpad1", Kp2 => "Numpad2", Kp3 => "Numpad3",
+        Kp4 => "Numpad4", Kp5 => "Numpad5", Kp6 => "Numpad6", Kp7 => "Numpad7",
+        Kp8 => "Numpad8", Kp9 => "Numpad9",
+        KpReturn => "NumpadEnter",
+        KpPlus => "NumpadAdd",
+        KpMinus => "NumpadSubtract",
+        KpMultiply => "NumpadMultiply",
+        KpDivide => "NumpadDivide",
+        Space => "Space",
+        Return => "Enter",
+        Tab => "Tab",
+        Backspace => "Backspace",
+        Escape => "Escape",
+        Delete => "Delete",
+        Insert => "Insert",
+        Home => "Home",
+        End => "End",
+        PageUp => "PageUp",
+        PageDown => "PageDown",
+        UpArrow => "Up",
+        DownArrow => "Down",
+        LeftArrow => "Left",
+        RightArrow => "Right",
+        PrintScreen => "PrintScreen",
+        Pause => "Pause",
+        CapsLock => "CapsLock",
+        ScrollLock => "ScrollLock",
+        NumLock => "NumLock",
+        SemiColon => ";",
+        Equal => "=",
+        Comma => "Comma",
+        Minus => "-",
+        Dot => ".",
+        Slash => "/",
+        BackQuote => "`",
+        LeftBracket => "[",
+        BackSlash => "\\",
+        RightBracket => "]",
+        Quote => "'",
+        _ => return None,
+    };
+    Some(name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rdev::Key;
+
+    fn press(key: Key) -> KeyEvent {
+        KeyEvent { key, pressed: true }
+    }
+
+    fn release(key: Key) -> KeyEvent {
+        KeyEvent { key, pressed: false }
+    }
+
+    #[test]
+    fn test_records_combination_in_canonical_order() {
+        let mut recorder = HotkeyRecorder::default();
+        assert_eq!(recorder.on_key(press(Key::ShiftLeft)), CaptureStep::Pending);
+        assert_eq!(recorder.on_key(press(Key::ControlRight)), CaptureStep::Pending);
+        assert_eq!(
+            recorder.on_key(press(Key::KeyV)),
+            CaptureStep::Captured("Ctrl+Shift+V".to_string())
+        );
+    }
+
+    #[test]
+    fn test_released_modifiers_are_dropped() {
+        let mut recorder = HotkeyRecorder::default();
+        recorder.on_key(press(Key::Alt));
+        recorder.on_key(release(Key::Alt));
+        assert_eq!(recorder.on_key(press(Key::F5)), CaptureStep::Captured("F5".to_string()));
+    }
+
+    #[test]
+    fn test_escape_cancels_unless_modified() {
+        let mut recorder = HotkeyRecorder::default();
+        assert_eq!(recorder.on_key(press(Key::Escape)), CaptureStep::Cancelled);
+
+        let mut recorder = HotkeyRecorder::default();
+        recorder.on_key(press(Key::ControlLeft));
+        assert_eq!(
+            recorder.on_key(press(Key::Escape)),
+            CaptureStep::Captured("Ctrl+Escape".to_string())
+        );
+    }
+
+    #[test]
+    fn test_unbindable_keys_are_ignored() {
+        let mut recorder = HotkeyRecorder::default();
+        assert_eq!(recorder.on_key(press(Key::Function)), CaptureStep::Pending);
+        assert_eq!(
+            recorder.on_key(press(Key::Kp1)),
+            CaptureStep::Captured("Numpad1".to_string())
+        );
+    }
+}
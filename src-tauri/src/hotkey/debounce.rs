@@ -0,0 +1,86 @@
+use std::collections::{HashMap, HashSet};
+use std::time::{Duration, Instant};
+
+/// Default minimum gap between two triggers of the same shortcut.
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Filters shortcut events so one physical press fires exactly once.
+/// Key auto-repeat shows up as extra presses without a release in between
+/// (or, on X11, as release/press pairs in quick succession).
+#[derive(Default)]
+pub struct Debouncer {
+    held: HashSet<u32>,
+    last_fired: HashMap<u32, Instant>,
+}
+
+impl Debouncer {
+    /// Feed a press (`pressed`) or release of shortcut `id`. Returns whether
+    /// a press should trigger its action.
+    pub fn on_event(&mut self, id: u32, pressed: bool, now: Instant, window: Duration) -> bool {
+        if !pressed {
+            self.held.remove(&id);
+            return false;
+        }
+
+        if !self.held.insert(id) {
+            // Already down: auto-repeat
+            return false;
+        }
+
+        let recent = self
+            .last_fired
+            .get(&id)
+            .is_some_and(|last| now.duration_since(*last) < window);
+        if recent {
+            return false;
+        }
+
+        self.last_fired.insert(id, now);
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_millis(250);
+
+    #[test]
+    fn test_held_key_fires_once() {
+        let mut debouncer = Debouncer::default();
+        let t0 = Instant::now();
+        assert!(debouncer.on_event(1, true, t0, WINDOW));
+        assert!(!debouncer.on_event(1, true, t0 + Duration::from_millis(500), WINDOW));
+        assert!(!debouncer.on_event(1, true, t0 + Duration::from_millis(530), WINDOW));
+        assert!(!debouncer.on_event(1, false, t0 + Duration::from_millis(600), WINDOW));
+        assert!(debouncer.on_event(1, true, t0 + Duration::from_millis(900), WINDOW));
+    }
+
+    #[test]
+    fn test_release_press_pairs_within_window() {
+        let mut debouncer = Debouncer::default();
+        let t0 = Instant::now();
+        assert!(debouncer.on_event(1, true, t0, WINDOW));
+        debouncer.on_event(1, false, t0 + Duration::from_millis(30), WINDOW);
+        assert!(!debouncer.on_event(1, true, t0 + Duration::from_millis(60), WINDOW));
+    }
+
+    #[test]
+    fn test_shortcuts_are_independent() {
+        let mut debouncer = Debouncer::default();
+        let t0 = Instant::now();
+        assert!(debouncer.on_event(1, true, t0, WINDOW));
+        assert!(debouncer.on_event(2, true, t0, WINDOW));
+    }
+
+    #[test]
+    fn test_zero_window_only_drops_repeats() {
+        let mut debouncer = Debouncer::default();
+        let t0 = Instant::now();
+        assert!(debouncer.on_event(1, true, t0, Duration::ZERO));
+        assert!(!debouncer.on_event(1, true, t0, Duration::ZERO));
+        debouncer.on_event(1, false, t0, Duration::ZERO);
+        assert!(debouncer.on_event(1, true, t0, Duration::ZERO));
+    }
+}
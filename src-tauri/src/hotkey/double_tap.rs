@@ -0,0 +1,202 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::HotkeyError;
+
+/// Longest press that still counts as a tap.
+const TAP_MAX_HOLD: Duration = Duration::from_millis(250);
+/// Longest gap between the two taps.
+const DOUBLE_TAP_WINDOW: Duration = Duration::from_millis(400);
+
+const DOUBLE_TAP_PREFIX: &str = "doubletap+";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TapModifier {
+    Ctrl,
+    Shift,
+    Alt,
+    Meta,
+}
+
+impl TapModifier {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Ctrl => "Ctrl",
+            Self::Shift => "Shift",
+            Self::Alt => "Alt",
+            Self::Meta => "Meta",
+        }
+    }
+
+    pub(super) fn from_key(key: rdev::Key) -> Option<Self> {
+        use rdev::Key;
+        match key {
+            Key::ControlLeft | Key::ControlRight => Some(Self::Ctrl),
+            Key::ShiftLeft | Key::ShiftRight => Some(Self::Shift),
+            Key::Alt | Key::AltGr => Some(Self::Alt),
+            Key::MetaLeft | Key::MetaRight => Some(Self::Meta),
+            _ => None,
+        }
+    }
+}
+
+/// Whether `hotkey` uses the `DoubleTap+<Modifier>` form rather than an accelerator.
+pub fn is_double_tap(hotkey: &str) -> bool {
+    hotkey.trim().to_lowercase().starts_with(DOUBLE_TAP_PREFIX)
+}
+
+/// Parse `DoubleTap+Ctrl`, `DoubleTap+Shift`, `DoubleTap+Alt` or `DoubleTap+Meta`.
+pub fn parse_double_tap(hotkey: &str) -> Result<TapModifier, HotkeyError> {
+    let lower = hotkey.trim().to_lowercase();
+    let modifier = lower
+        .strip_prefix(DOUBLE_TAP_PREFIX)
+        .ok_or_else(|| HotkeyError::InvalidFormat(format!("Not a double-tap trigger: {}", hotkey)))?;
+
+    match modifier.trim() {
+        "ctrl" | "control" => Ok(TapModifier::Ctrl),
+        "shift" => Ok(TapModifier::Shift),
+        "alt" | "option" => Ok(TapModifier::Alt),
+        "meta" | "super" | "cmd" | "command" => Ok(TapModifier::Meta),
+        other => Err(HotkeyError::InvalidFormat(format!("Cannot double-tap: {}", other))),
+    }
+}
+
+/// Turns raw key events into double-tap detections. A tap is a short
+/// press and release of a modifier with no other key in between.
+#[derive(Default)]
+pub struct DoubleTapDetector {
+    pressed: Option<(TapModifier, Instant)>,
+    last_tap: Option<(TapModifier, Instant)>,
+}
+
+impl DoubleTapDetector {
+    /// Feed one key event; `modifier` is `None` for any non-modifier key.
+    pub fn on_key(&mut self, modifier: Option<TapModifier>, pressed: bool, now: Instant) -> Option<TapModifier> {
+        let Some(modifier) = modifier else {
+            // Any other key (Ctrl+C etc.) breaks the sequence
+            if pressed {
+                self.pressed = None;
+                self.last_tap = None;
+            }
+            return None;
+        };
+
+        if pressed {
+            match self.pressed {
+                // Auto-repeat while held
+                Some((held, _)) if held == modifier => {}
+                Some(_) => {
+                    // Two modifiers at once is a chord, not a tap
+                    self.pressed = None;
+                    self.last_tap = None;
+                }
+                None => self.pressed = Some((modifier, now)),
+            }
+            return None;
+        }
+
+        let (held, since) = self.pressed.take()?;
+        if held != modifier || now.duration_since(since) > TAP_MAX_HOLD {
+            self.last_tap = None;
+            return None;
+        }
+
+        match self.last_tap.take() {
+            Some((prev, at)) if prev == modifier && now.duration_since(at) <= DOUBLE_TAP_WINDOW => Some(modifier),
+            _ => {
+                self.last_tap = Some((modifier, now));
+                None
+            }
+        }
+    }
+}
+
+/// Run double-tap detection on the shared key listener for the lifetime of the app.
+pub fn spawn_listener<F>(on_double_tap: F)
+where
+    F: Fn(TapModifier) + Send + 'static,
+{
+    let events = super::listener::subscribe();
+    let spawned = thread::Builder::new()
+        .name("double-tap-listener".to_string())
+        .spawn(move || {
+            let mut detector = DoubleTapDetector::default();
+            for event in events {
+                let modifier = TapModifier::from_key(event.key);
+                if let Some(modifier) = detector.on_key(modifier, event.pressed, Instant::now()) {
+                    on_double_tap(modifier);
+                }
+            }
+        });
+
+    if let Err(e) = spawned {
+        log::error!("Failed to start double-tap listener: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ms(base: Instant, offset: u64) -> Instant {
+        base + Duration::from_millis(offset)
+    }
+
+    #[test]
+    fn test_parse_double_tap() {
+        assert!(is_double_tap("DoubleTap+Ctrl"));
+        assert!(!is_double_tap("Ctrl+Shift+V"));
+        assert_eq!(parse_double_tap("doubletap+shift").unwrap(), TapModifier::Shift);
+        assert_eq!(parse_double_tap("DoubleTap+Cmd").unwrap(), TapModifier::Meta);
+        assert!(parse_double_tap("DoubleTap+V").is_err());
+    }
+
+    #[test]
+    fn test_detects_double_tap() {
+        let t = Instant::now();
+        let mut d = DoubleTapDetector::default();
+        let ctrl = Some(TapModifier::Ctrl);
+        assert_eq!(d.on_key(ctrl, true, t), None);
+        assert_eq!(d.on_key(ctrl, false, ms(t, 80)), None);
+        assert_eq!(d.on_key(ctrl, true, ms(t, 200)), None);
+        assert_eq!(d.on_key(ctrl, false, ms(t, 260)), Some(TapModifier::Ctrl));
+        // A third tap starts a new sequence
+        assert_eq!(d.on_key(ctrl, true, ms(t, 300)), None);
+        assert_eq!(d.on_key(ctrl, false, ms(t, 350)), None);
+    }
+
+    #[test]
+    fn test_rejects_slow_or_interrupted_taps() {
+        let t = Instant::now();
+        let ctrl = Some(TapModifier::Ctrl);
+
+        // Too far apart
+        let mut d = DoubleTapDetector::default();
+        d.on_key(ctrl, true, t);
+        d.on_key(ctrl, false, ms(t, 50));
+        d.on_key(ctrl, true, ms(t, 600));
+        assert_eq!(d.on_key(ctrl, false, ms(t, 650)), None);
+
+        // Held too long
+        let mut d = DoubleTapDetector::default();
+        d.on_key(ctrl, true, t);
+        d.on_key(ctrl, false, ms(t, 50));
+        d.on_key(ctrl, true, ms(t, 100));
+        assert_eq!(d.on_key(ctrl, false, ms(t, 500)), None);
+
+        // Ctrl+C in between
+        let mut d = DoubleTapDetector::default();
+        d.on_key(ctrl, true, t);
+        d.on_key(ctrl, false, ms(t, 50));
+        d.on_key(None, true, ms(t, 60));
+        d.on_key(ctrl, true, ms(t, 100));
+        assert_eq!(d.on_key(ctrl, false, ms(t, 150)), None);
+
+        // Different modifiers
+        let mut d = DoubleTapDetector::default();
+        d.on_key(ctrl, true, t);
+        d.on_key(ctrl, false, ms(t, 50));
+        d.on_key(Some(TapModifier::Shift), true, ms(t, 100));
+        assert_eq!(d.on_key(Some(TapModifier::Shift), false, ms(t, 150)), None);
+    }
+}
@@ -0,0 +1,53 @@
+use std::path::{Path, PathBuf};
+
+pub struct FrontmostApp {
+    pub name: String,
+    pub process_path: PathBuf,
+}
+
+/// The application owning the focused window; `None` when the platform
+/// can't report it (e.g. Wayland).
+pub fn frontmost_app() -> Option<FrontmostApp> {
+    let window = active_win_pos_rs::get_active_window().ok()?;
+    Some(FrontmostApp {
+        name: window.app_name,
+        process_path: window.process_path,
+    })
+}
+
+/// An entry matches the app name or the executable's file name,
+/// case-insensitively; the extension is optional (`vmware` matches `vmware.exe`).
+pub fn is_excluded(app_name: &str, process_path: &Path, exclusions: &[String]) -> bool {
+    let file_name = process_path.file_name().and_then(|n| n.to_str()).unwrap_or("");
+    let file_stem = process_path.file_stem().and_then(|n| n.to_str()).unwrap_or("");
+
+    exclusions.iter().map(|e| e.trim()).filter(|e| !e.is_empty()).any(|entry| {
+        [app_name, file_name, file_stem]
+            .iter()
+            .any(|candidate| !candidate.is_empty() && candidate.eq_ignore_ascii_case(entry))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(entries: &[&str]) -> Vec<String> {
+        entries.iter().map(|e| e.to_string()).collect()
+    }
+
+    #[test]
+    fn test_matches_name_or_executable() {
+        let path = Path::new("/opt/vmware/bin/vmware.exe");
+        assert!(is_excluded("VMware Workstation", path, &list(&["vmware"])));
+        assert!(is_excluded("VMware Workstation", path, &list(&["VMWARE.EXE"])));
+        assert!(is_excluded("VMware Workstation", path, &list(&["vmware workstation"])));
+        assert!(!is_excluded("VMware Workstation", path, &list(&["vm"])));
+    }
+
+    #[test]
+    fn test_empty_entries_never_match() {
+        assert!(!is_excluded("", Path::new(""), &list(&["", "  "])));
+        assert!(!is_excluded("Code", Path::new("/usr/bin/code"), &[]));
+    }
+}
@@ -0,0 +1,92 @@
+use enigo::{Direction, Enigo, Key, Keyboard, Settings};
+use tauri_plugin_global_shortcut::{Code, Modifiers, Shortcut};
+
+/// Type `shortcut` into the focused application. The caller must make sure
+/// our own registration of it is inactive, or it would be caught again.
+pub fn send_shortcut(shortcut: &Shortcut) -> Result<(), String> {
+    let key = enigo_key(shortcut.key)
+        .ok_or_else(|| format!("key {} can't be forwarded", shortcut.key))?;
+    let modifiers: Vec<Key> = [
+        (Modifiers::CONTROL, Key::Control),
+        (Modifiers::ALT, Key::Alt),
+        (Modifiers::SHIFT, Key::Shift),
+        (Modifiers::META | Modifiers::SUPER, Key::Meta),
+    ]
+    .into_iter()
+    .filter(|(m, _)| shortcut.mods.intersects(*m))
+    .map(|(_, key)| key)
+    .collect();
+
+    let mut enigo = Enigo::new(&Settings::default()).map_err(|e| e.to_string())?;
+    let mut pressed = Vec::new();
+    let mut result = Ok(());
+    for modifier in &modifiers {
+        result = enigo.key(*modifier, Direction::Press).map_err(|e| e.to_string());
+        if result.is_err() {
+            break;
+        }
+        pressed.push(*modifier);
+    }
+    if result.is_ok() {
+        result = enigo.key(key, Direction::Click).map_err(|e| e.to_string());
+    }
+    // Always release what was pressed, even if a step failed
+    for modifier in pressed.iter().rev() {
+        if let Err(e) = enigo.key(*modifier, Direction::Release) {
+            log::warn!("Failed to release {:?}: {}", modifier, e);
+        }
+    }
+    result
+}
+
+/// Keys that can be replayed on every platform.
+fn enigo_key(code: Code) -> Option<Key> {
+    let name = code.to_string();
+    if let Some(rest) = name.strip_prefix("Key").or_else(|| name.strip_prefix("Digit")) {
+        if let (Some(c), 1) = (rest.chars().next(), rest.len()) {
+            return Some(Key::Unicode(c.to_ascii_lowercase()));
+        }
+    }
+
+    let key = match code {
+        Code::F1 => Key::F1,
+        Code::F2 => Key::F2,
+        Code::F3 => Key::F3,
+        Code::F4 => Key::F4,
+        Code::F5 => Key::F5,
+        Code::F6 => Key::F6,
+        Code::F7 => Key::F7,
+        Code::F8 => Key::F8,
+        Code::F9 => Key::F9,
+        Code::F10 => Key::F10,
+        Code::F11 => Key::F11,
+        Code::F12 => Key::F12,
+        Code::Space => Key::Space,
+        Code::Enter => Key::Return,
+        Code::Tab => Key::Tab,
+        Code::Backspace => Key::Backspace,
+        Code::Escape => Key::Escape,
+        Code::Delete => Key::Delete,
+        Code::Home => Key::Home,
+        Code::End => Key::End,
+        Code::PageUp => Key::PageUp,
+        Code::PageDown => Key::PageDown,
+        Code::ArrowUp => Key::UpArrow,
+        Code::ArrowDown => Key::DownArrow,
+        Code::ArrowLeft => Key::LeftArrow,
+        Code::ArrowRight => Key::RightArrow,
+        Code::Semicolon => Key::Unicode(';'),
+        Code::Equal => Key::Unicode('='),
+        Code::Comma => Key::Unicode(','),
+        Code::Minus => Key::Unicode('-'),
+        Code::Period => Key::Unicode('.'),
+        Code::Slash => Key::Unicode('/'),
+        Code::Backquote => Key::Unicode('`'),
+        Code::BracketLeft => Key::Unicode('['),
+        Code::Backslash => Key::Unicode('\\'),
+        Code::BracketRight => Key::Unicode(']'),
+        Code::Quote => Key::Unicode('\''),
+        _ => return None,
+    };
+    Some(key)
+}
@@ -0,0 +1,154 @@
+use std::sync::Arc;
+use tauri::{AppHandle, Manager, Runtime};
+use thiserror::Error;
+
+use crate::ai::chunk;
+use crate::ai::{AIConfig, AIError, AIProviderType};
+use crate::clipboard::{self, ClipboardError, TransformHistory};
+use crate::commands::AIState;
+use crate::config::{ConfigError, ConfigManager, HeadlessPipeline, ProviderConfig, UsageMetric};
+use crate::privacy::{self, MaskMapping, ShieldAction, ShieldDecision, ShieldPolicy};
+use crate::regex::{self, RegexError};
+
+#[derive(Debug, Error)]
+pub enum HeadlessError {
+    #[error(transparent)]
+    Clipboard(#[from] ClipboardError),
+    #[error(transparent)]
+    Rule(#[from] RegexError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Ai(#[from] AIError),
+    #[error("pipeline has no rules or prompt")]
+    Empty,
+    #[error("no settings for AI provider '{0}'")]
+    NoProvider(String),
+}
+
+/// Run `pipeline` on the selection (or clipboard) and write the result back.
+/// Prompts follow the privacy shield policy, with "ask" treated as "always".
+pub async fn run_headless<R: Runtime>(
+    app: &AppHandle<R>,
+    pipeline: &HeadlessPipeline,
+) -> Result<(), HeadlessError> {
+    let prompt = prompt(pipeline);
+    if pipeline.rule_ids.is_empty() && prompt.is_none() {
+        return Err(HeadlessError::Empty);
+    }
+
+    let input = {
+        let app = app.clone();
+        let capture = pipeline.capture_selection;
+        tauri::async_runtime::spawn_blocking(move || {
+            if capture {
+                clipboard::capture_selection(&app)
+            } else {
+                clipboard::read_clipboard(&app)?.text.ok_or(ClipboardError::Empty)
+            }
+        })
+        .await
+        .map_err(|e| ClipboardError::Unavailable(e.to_string()))??
+    };
+
+    let config = app.state::<ConfigManager>();
+    let mut output = if pipeline.rule_ids.is_empty() {
+        input.clone()
+    } else {
+        let result = regex::apply_pipeline(&input, &pipeline.rule_ids, &config.get_custom_rules()?)?;
+        config.track(UsageMetric::Transform, 1);
+        result
+    };
+
+    if let Some(prompt) = prompt {
+        output = run_prompt(app, prompt, &output).await?;
+    }
+
+    let label = headless_label(pipeline);
+    let paste = pipeline.paste;
+    let app = app.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        clipboard::write_clipboard(&app, &output)?;
+        app.state::<TransformHistory>().push(input, output, label);
+        if paste {
+            clipboard::simulate_paste()?;
+        }
+        Ok::<_, ClipboardError>(())
+    })
+    .await
+    .map_err(|e| ClipboardError::Unavailable(e.to_string()))??;
+
+    Ok(())
+}
+
+/// Send `prompt` about `text` to the active provider and return the answer.
+/// Follows the privacy shield policy; nobody can be asked from here, so
+/// "ask" masks. Text too long for the model is processed in chunks.
+pub async fn run_prompt<R: Runtime>(app: &AppHandle<R>, prompt: &str, text: &str) -> Result<String, HeadlessError> {
+    let config = app.state::<ConfigManager>();
+    let app_config = config.effective_config()?;
+    let provider = app_config
+        .active_provider()
+        .ok_or_else(|| HeadlessError::NoProvider(app_config.ai_provider.clone()))?;
+    let ai_config = ai_config(provider, &config)?;
+    let action = privacy::resolve_shield(
+        ShieldPolicy::from_name(&app_config.shield_policy),
+        app_config.is_shielded(ai_config.provider),
+        &privacy::scan_pii(&chunk::with_content(prompt, text)),
+        Some(ShieldDecision::Mask),
+    );
+    let mut mapping = (action == ShieldAction::Mask).then(MaskMapping::default);
+    let mut mask = |s: &str| match mapping.as_mut() {
+        Some(mapping) => privacy::mask_pii_with(s, mapping),
+        None => s.to_string(),
+    };
+    let (prompt, text) = (mask(prompt), mask(text));
+    config.track(UsageMetric::AiRequest, 1);
+    if let Some(m) = &mapping {
+        config.track(UsageMetric::PiiMasked, m.mappings.len() as u64);
+    }
+
+    let state = app.state::<Arc<AIState>>().inner().clone();
+    let budget = chunk::input_budget(provider.context_tokens, ai_config.max_tokens, &prompt);
+    let answer = state
+        .complete_in_chunks(&prompt, &text, &ai_config, budget, |done, total| {
+            log::debug!("Chunked prompt: {} of {} requests done", done, total)
+        })
+        .await?;
+    config.track_ai_tokens(ai_config.provider, &ai_config.model, answer.prompt_tokens, answer.completion_tokens);
+    Ok(match &mapping {
+        Some(m) => privacy::restore_pii(&answer.content, m),
+        None => answer.content,
+    })
+}
+
+/// Same settings the panel uses for its own requests.
+fn ai_config(provider: &ProviderConfig, config: &ConfigManager) -> Result<AIConfig, ConfigError> {
+    let (kind, api_key) = if provider.provider == "OpenAI" {
+        (AIProviderType::OpenAI, config.get_api_key("openai")?)
+    } else {
+        (AIProviderType::Ollama, None)
+    };
+    Ok(AIConfig {
+        provider: kind,
+        base_url: provider.base_url.clone(),
+        model: provider.model.clone(),
+        api_key,
+        timeout_secs: Some(provider.timeout_secs),
+        context_tokens: Some(provider.context_tokens),
+        ..AIConfig::default()
+    })
+}
+
+/// The pipeline's prompt, unless it is blank.
+fn prompt(pipeline: &HeadlessPipeline) -> Option<&str> {
+    pipeline.prompt.as_deref().map(str::trim).filter(|p| !p.is_empty())
+}
+
+fn headless_label(pipeline: &HeadlessPipeline) -> String {
+    let mut parts = pipeline.rule_ids.clone();
+    if prompt(pipeline).is_some() {
+        parts.push("AI".to_string());
+    }
+    parts.join(", ")
+}
@@ -0,0 +1,98 @@
+use once_cell::sync::Lazy;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Mutex, Once};
+use std::thread;
+
+/// A raw key press or release from the OS-level listener.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyEvent {
+    pub key: rdev::Key,
+    pub pressed: bool,
+}
+
+/// A key or mouse button event, in the order the OS reported them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InputEvent {
+    Key(KeyEvent),
+    Button { button: rdev::Button, pressed: bool },
+}
+
+/// A key event with the text a press typed, or a mouse click, for consumers
+/// following what the user types.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TypedEvent {
+    Key {
+        key: rdev::Key,
+        pressed: bool,
+        text: Option<String>,
+    },
+    Click,
+}
+
+static SUBSCRIBERS: Lazy<Mutex<Vec<Sender<KeyEvent>>>> = Lazy::new(|| Mutex::new(Vec::new()));
+static INPUT_SUBSCRIBERS: Lazy<Mutex<Vec<Sender<InputEvent>>>> = Lazy::new(|| Mutex::new(Vec::new()));
+static TYPED_SUBSCRIBERS: Lazy<Mutex<Vec<Sender<TypedEvent>>>> = Lazy::new(|| Mutex::new(Vec::new()));
+static STARTED: Once = Once::new();
+
+/// Receive every global key event until the receiver is dropped. The OS
+/// listener can only run once per process, so all consumers share it; it
+/// starts on first use. Needs accessibility permission on macOS and X11 on Linux.
+pub fn subscribe() -> Receiver<KeyEvent> {
+    let (tx, rx) = mpsc::channel();
+    SUBSCRIBERS.lock().unwrap().push(tx);
+    STARTED.call_once(start);
+    rx
+}
+
+/// Like `subscribe`, but mouse button events are included.
+pub fn subscribe_input() -> Receiver<InputEvent> {
+    let (tx, rx) = mpsc::channel();
+    INPUT_SUBSCRIBERS.lock().unwrap().push(tx);
+    STARTED.call_once(start);
+    rx
+}
+
+/// Like `subscribe_input`, with the typed text of key presses.
+pub fn subscribe_typed() -> Receiver<TypedEvent> {
+    let (tx, rx) = mpsc::channel();
+    TYPED_SUBSCRIBERS.lock().unwrap().push(tx);
+    STARTED.call_once(start);
+    rx
+}
+
+fn start() {
+    let spawned = thread::Builder::new()
+        .name("key-listener".to_string())
+        .spawn(|| {
+            let result = rdev::listen(|event| {
+                let typed = match event.event_type {
+                    rdev::EventType::KeyPress(key) => Some(TypedEvent::Key { key, pressed: true, text: event.name }),
+                    rdev::EventType::KeyRelease(key) => Some(TypedEvent::Key { key, pressed: false, text: None }),
+                    rdev::EventType::ButtonPress(_) => Some(TypedEvent::Click),
+                    _ => None,
+                };
+                if let Some(typed) = typed {
+                    TYPED_SUBSCRIBERS.lock().unwrap().retain(|tx| tx.send(typed.clone()).is_ok());
+                }
+                let input = match event.event_type {
+                    rdev::EventType::KeyPress(key) => InputEvent::Key(KeyEvent { key, pressed: true }),
+                    rdev::EventType::KeyRelease(key) => InputEvent::Key(KeyEvent { key, pressed: false }),
+                    rdev::EventType::ButtonPress(button) => InputEvent::Button { button, pressed: true },
+                    rdev::EventType::ButtonRelease(button) => InputEvent::Button { button, pressed: false },
+                    _ => return,
+                };
+                // Drop subscribers whose receiver is gone
+                INPUT_SUBSCRIBERS.lock().unwrap().retain(|tx| tx.send(input).is_ok());
+                if let InputEvent::Key(key_event) = input {
+                    SUBSCRIBERS.lock().unwrap().retain(|tx| tx.send(key_event).is_ok());
+                }
+            });
+            if let Err(e) = result {
+                log::error!("Global key listener stopped: {:?}", e);
+            }
+        });
+
+    if let Err(e) = spawned {
+        log::error!("Failed to start global key listener: {}", e);
+    }
+}
@@ -0,0 +1,174 @@
+use std::thread;
+
+use super::capture::{CaptureStep, HotkeyRecorder};
+use super::listener::{self, KeyEvent};
+
+/// Modifier glyphs as shown in macOS menus, with the name the parser reads.
+const GLYPHS: &[(char, &str)] = &[
+    ('⌃', "Ctrl"),
+    ('⌥', "Alt"),
+    ('⇧', "Shift"),
+    ('⌘', "Meta"),
+    ('🌐', "Fn"),
+];
+
+/// Characters typed with Option held on a US layout, by physical key.
+/// Recorders that capture the typed character produce these for ⌥-combos.
+const OPTION_LAYER: &[(char, &str)] = &[
+    ('å', "A"), ('∫', "B"), ('ç', "C"), ('∂', "D"), ('´', "E"), ('ƒ', "F"),
+    ('©', "G"), ('˙', "H"), ('ˆ', "I"), ('∆', "J"), ('˚', "K"), ('¬', "L"),
+    ('µ', "M"), ('˜', "N"), ('ø', "O"), ('π', "P"), ('œ', "Q"), ('®', "R"),
+    ('ß', "S"), ('†', "T"), ('¨', "U"), ('√', "V"), ('∑', "W"), ('≈', "X"),
+    ('¥', "Y"), ('Ω', "Z"),
+    ('º', "0"), ('¡', "1"), ('™', "2"), ('£', "3"), ('¢', "4"), ('∞', "5"),
+    ('§', "6"), ('¶', "7"), ('•', "8"), ('ª', "9"),
+    ('–', "-"), ('≠', "="), ('“', "["), ('‘', "]"), ('«', "\\"), ('…', ";"),
+    ('æ', "'"), ('≤', "Comma"), ('≥', "."), ('÷', "/"),
+];
+
+/// Rewrite macOS notation into the `+`-separated form the parser reads:
+/// `⌘⇧V` becomes `Meta+Shift+V`, `⌥Space` becomes `Alt+Space` and an
+/// Option-layer character like `⌥√` becomes `Alt+V`.
+pub fn expand_mac_notation(hotkey: &str) -> String {
+    let mut expanded = String::with_capacity(hotkey.len());
+    let mut chars = hotkey.trim().chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match GLYPHS.iter().find(|(glyph, _)| *glyph == c) {
+            Some((_, name)) => {
+                expanded.push_str(name);
+                expanded.push('+');
+                // `⌘+V` and `⌘ V` mean the same as `⌘V`
+                while chars.next_if(|c| *c == '+' || c.is_whitespace()).is_some() {}
+            }
+            None => expanded.push(c),
+        }
+    }
+
+    let (mods, key) = match expanded.rsplit_once('+') {
+        Some((mods, key)) => (Some(mods), key.trim()),
+        None => (None, expanded.trim()),
+    };
+    let mut key_chars = key.chars();
+    let base = match (key_chars.next(), key_chars.next()) {
+        (Some(c), None) => OPTION_LAYER.iter().find(|(ch, _)| *ch == c).map(|(_, base)| *base),
+        _ => None,
+    };
+    let Some(base) = base else {
+        return expanded;
+    };
+
+    let mut parts: Vec<&str> = mods.map(|m| m.split('+').map(str::trim).collect()).unwrap_or_default();
+    if !parts.iter().any(|p| p.eq_ignore_ascii_case("alt") || p.eq_ignore_ascii_case("option")) {
+        parts.push("Alt");
+    }
+    parts.push(base);
+    parts.join("+")
+}
+
+/// Recognizes Fn/Globe combinations, which the OS shortcut APIs can't
+/// register. Emits the accelerator (`Fn+Alt+V`) once per key press.
+#[derive(Default)]
+pub struct FnComboDetector {
+    fn_held: bool,
+    key_down: bool,
+    recorder: HotkeyRecorder,
+}
+
+impl FnComboDetector {
+    pub fn on_key(&mut self, event: KeyEvent) -> Option<String> {
+        if event.key == rdev::Key::Function {
+            self.fn_held = event.pressed;
+            return None;
+        }
+
+        // The recorder tracks the other held modifiers
+        let step = self.recorder.on_key(event);
+        let CaptureStep::Captured(accelerator) = step else {
+            if !event.pressed && !is_modifier(event.key) {
+                self.key_down = false;
+            }
+            return None;
+        };
+
+        // Auto-repeat while the key is held
+        if !self.fn_held || std::mem::replace(&mut self.key_down, true) {
+            return None;
+        }
+        Some(format!("Fn+{}", accelerator))
+    }
+}
+
+fn is_modifier(key: rdev::Key) -> bool {
+    super::double_tap::TapModifier::from_key(key).is_some()
+}
+
+/// Run Fn-combination detection on the shared key listener for the lifetime of the app.
+pub fn spawn_fn_listener<F>(on_combo: F)
+where
+    F: Fn(&str) + Send + 'static,
+{
+    let events = listener::subscribe();
+    let spawned = thread::Builder::new()
+        .name("fn-key-listener".to_string())
+        .spawn(move || {
+            let mut detector = FnComboDetector::default();
+            for event in events {
+                if let Some(combo) = detector.on_key(event) {
+                    on_combo(&combo);
+                }
+            }
+        });
+
+    if let Err(e) = spawned {
+        log::error!("Failed to start Fn key listener: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(key: rdev::Key, pressed: bool) -> KeyEvent {
+        KeyEvent { key, pressed }
+    }
+
+    #[test]
+    fn test_expand_glyphs() {
+        assert_eq!(expand_mac_notation("⌘⇧V"), "Meta+Shift+V");
+        assert_eq!(expand_mac_notation("⌥Space"), "Alt+Space");
+        assert_eq!(expand_mac_notation("⌃ ⌥ K"), "Ctrl+Alt+K");
+        assert_eq!(expand_mac_notation("⌘+,"), "Meta+,");
+        assert_eq!(expand_mac_notation("🌐V"), "Fn+V");
+        assert_eq!(expand_mac_notation("Ctrl+Shift+V"), "Ctrl+Shift+V");
+    }
+
+    #[test]
+    fn test_expand_option_layer() {
+        assert_eq!(expand_mac_notation("⌥√"), "Alt+V");
+        assert_eq!(expand_mac_notation("Option+≈"), "Option+X");
+        assert_eq!(expand_mac_notation("Cmd+Ω"), "Cmd+Alt+Z");
+        assert_eq!(expand_mac_notation("⌥≤"), "Alt+Comma");
+    }
+
+    #[test]
+    fn test_fn_combo() {
+        let mut d = FnComboDetector::default();
+        assert_eq!(d.on_key(key(rdev::Key::Function, true)), None);
+        assert_eq!(d.on_key(key(rdev::Key::KeyV, true)).as_deref(), Some("Fn+V"));
+        // Auto-repeat
+        assert_eq!(d.on_key(key(rdev::Key::KeyV, true)), None);
+        d.on_key(key(rdev::Key::KeyV, false));
+        d.on_key(key(rdev::Key::ShiftLeft, true));
+        assert_eq!(d.on_key(key(rdev::Key::KeyV, true)).as_deref(), Some("Fn+Shift+V"));
+    }
+
+    #[test]
+    fn test_no_combo_without_fn() {
+        let mut d = FnComboDetector::default();
+        assert_eq!(d.on_key(key(rdev::Key::KeyV, true)), None);
+        d.on_key(key(rdev::Key::Function, true));
+        d.on_key(key(rdev::Key::Function, false));
+        assert_eq!(d.on_key(key(rdev::Key::KeyV, true)), None);
+    }
+}
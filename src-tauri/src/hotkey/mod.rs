@@ -1,31 +1,346 @@
-use std::sync::Arc;
-use tauri::{AppHandle, Emitter, Manager, Runtime};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex, Once};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, PhysicalPosition, Runtime, WebviewWindow};
 use tauri_plugin_global_shortcut::{Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState};
 use tokio::sync::Mutex;
 use thiserror::Error;
 
+mod capture;
+mod debounce;
+mod double_tap;
+pub(crate) mod exclusion;
+mod forward;
+mod headless;
+pub(crate) mod listener;
+mod macos;
+mod mouse;
+mod position;
+
+pub use capture::{capture_next, DEFAULT_CAPTURE_TIMEOUT};
+pub use double_tap::{is_double_tap, parse_double_tap};
+pub use headless::{run_prompt, HeadlessError};
+
+use debounce::{Debouncer, DEFAULT_DEBOUNCE};
+use double_tap::TapModifier;
+use exclusion::FrontmostApp;
+use mouse::{is_mouse_trigger, parse_mouse_trigger, MouseTrigger};
+
+use crate::ai::intent::translation_prompt;
+use crate::clipboard::{self, RecentClipboard};
+use crate::commands::AIState;
+use crate::config::{ConfigManager, HeadlessPipeline, UsageMetric, Webhook, Workflow};
+use crate::notes;
+use crate::ocr::{self, OcrError};
+use crate::regex::Rule;
+use crate::translation;
+use crate::webhook;
+use crate::workflow;
+
+/// Binding name of the main panel toggle hotkey.
+pub const TOGGLE_PANEL_BINDING: &str = "toggle_panel";
+
+/// How long the second key of a chord is accepted after the first.
+const CHORD_TIMEOUT: Duration = Duration::from_millis(1500);
+
+/// Built-in actions bindable from settings, by binding name.
+pub const ACTION_BINDINGS: &[&str] = &[
+    "paste_plain_text",
+    "repeat_last_action",
+    "show_history",
+    "paste_last_ai_result",
+    "clipboard_next",
+    "clipboard_previous",
+    "screenshot_ocr",
+    "translate_clipboard",
+    "save_to_notes",
+];
+
+/// Action for a name in `ACTION_BINDINGS`.
+pub fn builtin_action(name: &str) -> Option<HotkeyAction> {
+    match name {
+        "paste_plain_text" => Some(HotkeyAction::PastePlainText),
+        "repeat_last_action" => Some(HotkeyAction::RepeatLastAction),
+        "show_history" => Some(HotkeyAction::ShowHistory),
+        "paste_last_ai_result" => Some(HotkeyAction::PasteLastAiResult),
+        "clipboard_next" => Some(HotkeyAction::CycleClipboard { step: 1 }),
+        "clipboard_previous" => Some(HotkeyAction::CycleClipboard { step: -1 }),
+        "screenshot_ocr" => Some(HotkeyAction::ScreenshotOcr),
+        "translate_clipboard" => Some(HotkeyAction::TranslateClipboard),
+        "save_to_notes" => Some(HotkeyAction::SaveToNotes),
+        _ => None,
+    }
+}
+
+/// Binding name for a stored rule hotkey, kept apart from built-in bindings.
+pub fn rule_binding_name(id: &str) -> String {
+    format!("rules:{}", id)
+}
+
+/// Binding name for a rule pinned with its own shortcut.
+pub fn pinned_binding_name(rule_id: &str) -> String {
+    format!("pinned:{}", rule_id)
+}
+
+/// Binding name for a stored headless pipeline hotkey.
+pub fn headless_binding_name(id: &str) -> String {
+    format!("headless:{}", id)
+}
+
+/// Binding name for a webhook's shortcut.
+pub fn webhook_binding_name(id: &str) -> String {
+    format!("webhook:{}", id)
+}
+
+/// Binding name for a workflow's shortcut.
+pub fn workflow_binding_name(id: &str) -> String {
+    format!("workflow:{}", id)
+}
+
 #[derive(Debug, Error)]
 pub enum HotkeyError {
     #[error("invalid hotkey format: {0}")]
     InvalidFormat(String),
     #[error("hotkey registration failed: {0}")]
     RegistrationFailed(String),
+    #[error("hotkey already bound to '{0}'")]
+    AlreadyBound(String),
+}
+
+/// What a registered shortcut does when pressed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum HotkeyAction {
+    TogglePanel,
+    /// Apply rules in order to the clipboard text and write the result back,
+    /// without showing the panel.
+    #[serde(rename_all = "camelCase")]
+    ApplyRules { rule_ids: Vec<String> },
+    /// Rewrite the clipboard as plain text, dropping rich formatting.
+    PastePlainText,
+    /// Run the last clipboard transform again on the current clipboard.
+    RepeatLastAction,
+    /// Show the panel on the transform history view.
+    ShowHistory,
+    /// Copy the most recent AI response without opening the panel.
+    PasteLastAiResult,
+    /// Rotate the clipboard through recent entries (`step` 1 = older, -1 = newer).
+    CycleClipboard { step: isize },
+    /// Capture the selection, run a pipeline (rules and/or AI prompt) and
+    /// paste the result, all without showing the panel.
+    Headless { pipeline: HeadlessPipeline },
+    /// Send the selection (or clipboard) to a stored webhook.
+    #[serde(rename_all = "camelCase")]
+    Webhook { id: String, capture_selection: bool },
+    /// Run a stored workflow on the clipboard text.
+    Workflow { id: String },
+    /// Read the text in a selected screen region and open the panel on it.
+    ScreenshotOcr,
+    /// Translate the clipboard with the machine translation service.
+    TranslateClipboard,
+    /// Append the clipboard text to the notes file.
+    SaveToNotes,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Trigger {
+    Shortcut(Shortcut),
+    DoubleTap(TapModifier),
+    /// Two-step sequence like `Ctrl+K, Ctrl+V`; chords may share a first step.
+    Chord(Shortcut, Shortcut),
+    /// Extra mouse button, optionally with modifiers (`Ctrl+Mouse4`).
+    Mouse(MouseTrigger),
+}
+
+impl Trigger {
+    /// Canonical string form; parses back to the same trigger.
+    fn format(&self) -> String {
+        match self {
+            Trigger::Shortcut(shortcut) => format_shortcut(shortcut),
+            Trigger::DoubleTap(modifier) => format!("DoubleTap+{}", modifier.name()),
+            Trigger::Mouse(trigger) => trigger.format(),
+            Trigger::Chord(first, second) => {
+                format!("{}, {}", format_shortcut(first), format_shortcut(second))
+            }
+        }
+    }
+
+    /// The global shortcut registered for as long as the trigger is bound.
+    fn registered(&self) -> Option<Shortcut> {
+        match self {
+            Trigger::Shortcut(s) | Trigger::Chord(s, _) => Some(*s),
+            _ => None,
+        }
+    }
+
+    /// Whether two triggers can't be bound at the same time. A chord's
+    /// second step is registered while the chord is armed, so it can't be
+    /// another binding's shortcut or first step either.
+    fn conflicts_with(&self, other: &Trigger) -> bool {
+        let takes_second_step = |chord: &Trigger, binding: &Trigger| match chord {
+            Trigger::Chord(_, second) => binding.registered() == Some(*second),
+            _ => false,
+        };
+        if takes_second_step(self, other) || takes_second_step(other, self) {
+            return true;
+        }
+        match (self, other) {
+            (Trigger::Shortcut(s), Trigger::Chord(first, _))
+            | (Trigger::Chord(first, _), Trigger::Shortcut(s)) => s == first,
+            _ => self == other,
+        }
+    }
+}
+
+/// A live binding as shown in settings.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisteredHotkey {
+    pub name: String,
+    /// Normalized accelerator
+    pub hotkey: String,
+    pub action: HotkeyAction,
+}
+
+/// Payload of the `hotkey:triggered` event.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyTriggered {
+    /// Binding name, e.g. `toggle_panel` or `rules:<id>`
+    pub binding: String,
+    pub action: HotkeyAction,
+    /// Milliseconds since the Unix epoch
+    pub timestamp: i64,
+    pub frontmost_app: Option<String>,
+}
+
+/// Result of checking a hotkey before binding it.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HotkeyValidation {
+    /// Parses and is free to bind
+    pub valid: bool,
+    /// Parse error, if the format is invalid
+    pub error: Option<String>,
+    /// FlowPaste binding that already uses this trigger
+    pub conflict: Option<String>,
+    /// Whether the OS accepted a trial registration; `None` when not checked
+    /// (double-tap triggers, invalid format, or an internal conflict).
+    pub os_available: Option<bool>,
+}
+
+/// Second-step shortcuts registered while a chord is armed.
+struct ArmedChord {
+    generation: u64,
+    shortcuts: Vec<Shortcut>,
+}
+
+struct Binding {
+    trigger: Trigger,
+    action: HotkeyAction,
 }
 
 pub struct HotkeyManager {
     // Use Mutex for exclusive access to registration/unregistration
-    current_shortcut: Arc<Mutex<Option<Shortcut>>>,
+    bindings: Arc<Mutex<HashMap<String, Binding>>>,
+    // Read from shortcut handlers, which are not async
+    last_action: StdMutex<Option<HotkeyAction>>,
+    // Mirror of the double-tap bindings for the key listener thread
+    double_taps: Arc<StdMutex<HashMap<TapModifier, (String, HotkeyAction)>>>,
+    listener_started: Once,
+    // Fn/Globe combinations, matched on the key listener thread
+    fn_combos: Arc<StdMutex<HashMap<Shortcut, (String, HotkeyAction)>>>,
+    fn_listener_started: Once,
+    // Mouse button bindings, matched on the input listener thread
+    mouse_bindings: Arc<StdMutex<HashMap<MouseTrigger, (String, HotkeyAction)>>>,
+    mouse_listener_started: Once,
+    armed_chord: StdMutex<Option<ArmedChord>>,
+    chord_generation: AtomicU64,
+    // Apps in which triggers are ignored, mirrored from config
+    excluded_apps: StdMutex<Vec<String>>,
+    debouncer: StdMutex<Debouncer>,
+    debounce_ms: AtomicU64,
+    // Bindings forwarded to the app behind the panel while it has focus
+    pass_through: StdMutex<HashSet<String>>,
+    panel_focused: AtomicBool,
 }
 
 impl HotkeyManager {
     pub fn new() -> Self {
         Self {
-            current_shortcut: Arc::new(Mutex::new(None)),
+            bindings: Arc::new(Mutex::new(HashMap::new())),
+            last_action: StdMutex::new(None),
+            double_taps: Arc::new(StdMutex::new(HashMap::new())),
+            listener_started: Once::new(),
+            fn_combos: Arc::new(StdMutex::new(HashMap::new())),
+            fn_listener_started: Once::new(),
+            mouse_bindings: Arc::new(StdMutex::new(HashMap::new())),
+            mouse_listener_started: Once::new(),
+            armed_chord: StdMutex::new(None),
+            chord_generation: AtomicU64::new(0),
+            excluded_apps: StdMutex::new(Vec::new()),
+            debouncer: StdMutex::new(Debouncer::default()),
+            debounce_ms: AtomicU64::new(DEFAULT_DEBOUNCE.as_millis() as u64),
+            pass_through: StdMutex::new(HashSet::new()),
+            panel_focused: AtomicBool::new(false),
         }
     }
 
+    pub fn set_pass_through_bindings(&self, names: Vec<String>) {
+        *self.pass_through.lock().unwrap() = names.into_iter().collect();
+    }
+
+    /// Track panel focus from window events.
+    pub fn set_panel_focused(&self, focused: bool) {
+        self.panel_focused.store(focused, Ordering::Relaxed);
+    }
+
+    /// Whether binding `name` should reach the previous app instead of
+    /// acting on the panel.
+    fn passes_through(&self, name: &str) -> bool {
+        self.panel_focused.load(Ordering::Relaxed) && self.pass_through.lock().unwrap().contains(name)
+    }
+
+    /// Minimum gap between two triggers of the same shortcut.
+    pub fn set_debounce(&self, window: Duration) {
+        self.debounce_ms.store(window.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    /// Whether a shortcut event should fire, dropping auto-repeat and
+    /// presses inside the debounce window.
+    fn should_fire(&self, shortcut: &Shortcut, pressed: bool) -> bool {
+        let window = Duration::from_millis(self.debounce_ms.load(Ordering::Relaxed));
+        self.debouncer
+            .lock()
+            .unwrap()
+            .on_event(shortcut.id(), pressed, Instant::now(), window)
+    }
+
+    pub fn set_excluded_apps(&self, apps: Vec<String>) {
+        *self.excluded_apps.lock().unwrap() = apps;
+    }
+
+    /// Whether hotkeys should be ignored because an excluded app is frontmost.
+    fn is_suppressed(&self, frontmost: Option<&FrontmostApp>) -> bool {
+        frontmost.is_some_and(|app| {
+            exclusion::is_excluded(&app.name, &app.process_path, &self.excluded_apps.lock().unwrap())
+        })
+    }
+
+    /// Remember a repeatable action for `RepeatLastAction`.
+    pub fn set_last_action(&self, action: HotkeyAction) {
+        *self.last_action.lock().unwrap() = Some(action);
+    }
+
+    pub fn last_action(&self) -> Option<HotkeyAction> {
+        self.last_action.lock().unwrap().clone()
+    }
+
     /// Parse hotkey string like "Ctrl+Shift+V" or "CommandOrControl+Shift+V"
     pub fn parse_hotkey(hotkey: &str) -> Result<Shortcut, HotkeyError> {
+        let hotkey = macos::expand_mac_notation(hotkey);
         let parts: Vec<&str> = hotkey.split('+').map(|s| s.trim()).collect();
 
         if parts.is_empty() {
@@ -46,6 +361,8 @@ impl HotkeyManager {
                 "shift" => modifiers |= Modifiers::SHIFT,
                 "alt" | "option" => modifiers |= Modifiers::ALT,
                 "meta" | "super" | "cmd" | "command" => modifiers |= Modifiers::META,
+                // Registered through the key listener; the OS shortcut APIs ignore Fn
+                "fn" | "globe" => modifiers |= Modifiers::FN,
                 "commandorcontrol" | "cmdorctrl" => {
                     #[cfg(target_os = "macos")]
                     {
@@ -64,101 +381,1023 @@ impl HotkeyManager {
         Ok(Shortcut::new(Some(modifiers), key))
     }
 
-    /// Register a hotkey with the given accelerator string (atomic operation)
+    /// Register the panel toggle hotkey with the given accelerator string (atomic operation)
     pub async fn register_hotkey<R: Runtime>(
         &self,
         app: &AppHandle<R>,
         hotkey: &str,
+    ) -> Result<(), HotkeyError> {
+        self.register_binding(app, TOGGLE_PANEL_BINDING, hotkey, HotkeyAction::TogglePanel)
+            .await
+    }
+
+    /// Unregister every binding, e.g. before reloading them from config.
+    pub async fn unregister_all<R: Runtime>(&self, app: &AppHandle<R>) {
+        let names: Vec<String> = self.bindings.lock().await.keys().cloned().collect();
+        for name in names {
+            if let Err(e) = self.unregister_binding(app, &name).await {
+                log::error!("Failed to unregister hotkey '{}': {}", name, e);
+            }
+        }
+    }
+
+    /// Bring the hotkey of a pinned rule in line with `rule.shortcut`:
+    /// register it when set, drop any previous binding when not.
+    pub async fn sync_pinned_rule<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        rule: &Rule,
+    ) -> Result<(), HotkeyError> {
+        let name = pinned_binding_name(&rule.id);
+        match rule.shortcut.as_deref() {
+            Some(shortcut) => {
+                let action = HotkeyAction::ApplyRules {
+                    rule_ids: vec![rule.id.clone()],
+                };
+                self.register_binding(app, &name, shortcut, action).await
+            }
+            None => self.unregister_binding(app, &name).await,
+        }
+    }
+
+    /// Same as `sync_pinned_rule`, for a webhook's shortcut.
+    pub async fn sync_webhook<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        webhook: &Webhook,
+    ) -> Result<(), HotkeyError> {
+        let name = webhook_binding_name(&webhook.id);
+        match webhook.hotkey.as_deref() {
+            Some(shortcut) => {
+                let action = HotkeyAction::Webhook {
+                    id: webhook.id.clone(),
+                    capture_selection: webhook.capture_selection,
+                };
+                self.register_binding(app, &name, shortcut, action).await
+            }
+            None => self.unregister_binding(app, &name).await,
+        }
+    }
+
+    /// Same as `sync_pinned_rule`, for a workflow's shortcut.
+    pub async fn sync_workflow<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        workflow: &Workflow,
+    ) -> Result<(), HotkeyError> {
+        let name = workflow_binding_name(&workflow.id);
+        match workflow.hotkey.as_deref() {
+            Some(shortcut) => {
+                let action = HotkeyAction::Workflow {
+                    id: workflow.id.clone(),
+                };
+                self.register_binding(app, &name, shortcut, action).await
+            }
+            None => self.unregister_binding(app, &name).await,
+        }
+    }
+
+    /// Unregister the panel toggle hotkey
+    pub async fn unregister_hotkey<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+    ) -> Result<(), HotkeyError> {
+        self.unregister_binding(app, TOGGLE_PANEL_BINDING).await
+    }
+
+    /// Check if the panel toggle hotkey is currently registered
+    pub async fn is_registered(&self) -> bool {
+        self.bindings.lock().await.contains_key(TOGGLE_PANEL_BINDING)
+    }
+
+    /// Parse an accelerator, a `Ctrl+K, Ctrl+V` chord, a `DoubleTap+<Modifier>`
+    /// trigger or a mouse button like `Ctrl+Mouse4`.
+    fn parse_trigger(hotkey: &str) -> Result<Trigger, HotkeyError> {
+        if is_double_tap(hotkey) {
+            return Ok(Trigger::DoubleTap(parse_double_tap(hotkey)?));
+        }
+        if is_mouse_trigger(hotkey) {
+            return Ok(Trigger::Mouse(parse_mouse_trigger(hotkey)?));
+        }
+
+        // Expand glyphs first so `⌘,` isn't read as a chord separator
+        let hotkey = macos::expand_mac_notation(hotkey);
+        match split_chord(&hotkey) {
+            Some((first, second)) => {
+                let first = Self::parse_hotkey(first)?;
+                let second = Self::parse_hotkey(second)?;
+                if first == second {
+                    return Err(HotkeyError::InvalidFormat("Chord steps must differ".to_string()));
+                }
+                if first.mods.contains(Modifiers::FN) || second.mods.contains(Modifiers::FN) {
+                    return Err(HotkeyError::InvalidFormat("Fn can't be used in chords".to_string()));
+                }
+                Ok(Trigger::Chord(first, second))
+            }
+            None => Ok(Trigger::Shortcut(Self::parse_hotkey(&hotkey)?)),
+        }
+    }
+
+    /// Canonical form of any supported hotkey string, e.g.
+    /// "control + shift + v" becomes "Ctrl+Shift+V".
+    pub fn normalize_hotkey(hotkey: &str) -> Result<String, HotkeyError> {
+        Self::parse_trigger(hotkey).map(|t| t.format())
+    }
+
+    /// All live bindings with their normalized accelerators, sorted by name.
+    pub async fn registered_hotkeys(&self) -> Vec<RegisteredHotkey> {
+        let mut hotkeys: Vec<RegisteredHotkey> = self
+            .bindings
+            .lock()
+            .await
+            .iter()
+            .map(|(name, binding)| RegisteredHotkey {
+                name: name.clone(),
+                hotkey: binding.trigger.format(),
+                action: binding.action.clone(),
+            })
+            .collect();
+        hotkeys.sort_by(|a, b| a.name.cmp(&b.name));
+        hotkeys
+    }
+
+    /// Check `hotkey` without binding it. `name` is the binding being edited,
+    /// so its own current trigger doesn't count as a conflict.
+    pub async fn validate_hotkey<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        hotkey: &str,
+        name: Option<&str>,
+    ) -> HotkeyValidation {
+        let trigger = match Self::parse_trigger(hotkey) {
+            Ok(trigger) => trigger,
+            Err(e) => {
+                return HotkeyValidation {
+                    error: Some(e.to_string()),
+                    ..Default::default()
+                }
+            }
+        };
+
+        let bindings = self.bindings.lock().await;
+        let conflict = bindings
+            .iter()
+            .find(|(n, b)| Some(n.as_str()) != name && b.trigger.conflicts_with(&trigger))
+            .map(|(n, _)| n.clone());
+        if conflict.is_some() {
+            return HotkeyValidation {
+                conflict,
+                ..Default::default()
+            };
+        }
+
+        // Shortcuts already registered are ours (e.g. a shared chord prefix)
+        let probe = match trigger {
+            Trigger::Shortcut(shortcut) | Trigger::Chord(shortcut, _) => Some(shortcut),
+            Trigger::DoubleTap(_) | Trigger::Mouse(_) => None,
+        }
+        .filter(|s| !s.mods.contains(Modifiers::FN) && !app.global_shortcut().is_registered(*s));
+
+        let os_available = probe.map(|shortcut| probe_shortcut(app, shortcut));
+        HotkeyValidation {
+            valid: os_available != Some(false),
+            os_available,
+            ..Default::default()
+        }
+    }
+
+    /// Register (or re-bind) a named binding. Replacing an existing binding is
+    /// atomic: the old trigger stays active if the new one fails to register.
+    /// `hotkey` is an accelerator, a chord, a `DoubleTap+<Modifier>` trigger or a mouse button.
+    pub async fn register_binding<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        name: &str,
+        hotkey: &str,
+        action: HotkeyAction,
     ) -> Result<(), HotkeyError> {
         // Parse and validate BEFORE making any changes
-        let new_shortcut = Self::parse_hotkey(hotkey)?;
+        let new_trigger = Self::parse_trigger(hotkey)?;
 
         // Exclusive lock for atomic registration
-        let mut current = self.current_shortcut.lock().await;
+        let mut bindings = self.bindings.lock().await;
+
+        if let Some((other, _)) = bindings
+            .iter()
+            .find(|(n, b)| n.as_str() != name && b.trigger.conflicts_with(&new_trigger))
+        {
+            return Err(HotkeyError::AlreadyBound(other.clone()));
+        }
+
+        let old_trigger = bindings.get(name).map(|b| b.trigger);
 
         // Unregister old hotkey if exists
-        if let Some(old_shortcut) = current.take() {
-            if let Err(e) = app.global_shortcut().unregister(old_shortcut) {
-                log::warn!("Failed to unregister old hotkey: {}", e);
-                // Restore old shortcut in state
-                *current = Some(old_shortcut);
+        if let Some(old) = old_trigger {
+            if let Err(e) = self.detach_trigger(app, &bindings, name, old) {
+                log::warn!("Failed to unregister old hotkey for '{}': {}", name, e);
                 // Continue anyway to attempt new registration
             }
         }
 
-        let window = app.get_webview_window("main")
-            .ok_or_else(|| HotkeyError::RegistrationFailed("Main window not found".to_string()))?;
-
-        let window_clone = window.clone();
-
-        // Register new hotkey
-        app.global_shortcut()
-            .on_shortcut(new_shortcut, move |_app, _shortcut, event| {
-                if event.state != ShortcutState::Pressed {
-                    return;
-                }
+        if let Err(e) = self.attach_trigger(app, &bindings, name, new_trigger, &action) {
+            log::error!("Failed to register new hotkey: {}", e);
 
-                log::info!("Global hotkey triggered");
-
-                match window_clone.is_visible() {
-                    Ok(visible) => {
-                        if visible {
-                            if let Err(e) = window_clone.hide() {
-                                log::error!("Failed to hide window: {}", e);
-                            }
-                        } else {
-                            if let Err(e) = window_clone.show() {
-                                log::error!("Failed to show window: {}", e);
-                            }
-                            if let Err(e) = window_clone.set_focus() {
-                                log::error!("Failed to focus window: {}", e);
-                            }
-                            if let Err(e) = window_clone.emit("panel:show", ()) {
-                                log::error!("Failed to emit panel:show event: {}", e);
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        log::error!("Failed to check window visibility: {}", e);
-                    }
+            // Registration failed, try to restore the old trigger
+            if let Some(old) = old_trigger {
+                let old_action = bindings[name].action.clone();
+                if let Err(e) = self.attach_trigger(app, &bindings, name, old, &old_action) {
+                    log::error!("Failed to restore old hotkey for '{}': {}", name, e);
+                    bindings.remove(name);
                 }
-            })
-            .map_err(|e| {
-                // Registration failed, try to restore old state if available
-                log::error!("Failed to register new hotkey: {}", e);
-                HotkeyError::RegistrationFailed(e.to_string())
-            })?;
+            }
+            return Err(HotkeyError::RegistrationFailed(e));
+        }
 
         // Update state only after successful registration
-        *current = Some(new_shortcut);
+        bindings.insert(
+            name.to_string(),
+            Binding {
+                trigger: new_trigger,
+                action,
+            },
+        );
 
-        log::info!("Hotkey registered: {}", hotkey);
+        log::info!("Hotkey registered for '{}': {}", name, hotkey);
         Ok(())
     }
 
-    /// Unregister the current hotkey
-    pub async fn unregister_hotkey<R: Runtime>(
+    /// Unregister a named binding; unknown names are a no-op
+    pub async fn unregister_binding<R: Runtime>(
         &self,
         app: &AppHandle<R>,
+        name: &str,
     ) -> Result<(), HotkeyError> {
-        let mut current = self.current_shortcut.lock().await;
+        let mut bindings = self.bindings.lock().await;
 
-        if let Some(shortcut) = &*current {
-            app.global_shortcut()
-                .unregister(*shortcut)
-                .map_err(|e| HotkeyError::RegistrationFailed(e.to_string()))?;
+        if let Some(trigger) = bindings.get(name).map(|b| b.trigger) {
+            self.detach_trigger(app, &bindings, name, trigger)
+                .map_err(HotkeyError::RegistrationFailed)?;
 
             // Only clear state after successful unregistration
-            *current = None;
-            log::info!("Hotkey unregistered");
+            bindings.remove(name);
+            log::info!("Hotkey unregistered for '{}'", name);
         }
 
         Ok(())
     }
 
-    /// Check if a hotkey is currently registered
-    pub async fn is_registered(&self) -> bool {
-        self.current_shortcut.lock().await.is_some()
+    /// Make `trigger` live for binding `name`. `bindings` is the state before
+    /// `name` is (re)inserted.
+    fn attach_trigger<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        bindings: &HashMap<String, Binding>,
+        name: &str,
+        trigger: Trigger,
+        action: &HotkeyAction,
+    ) -> Result<(), String> {
+        match trigger {
+            Trigger::Shortcut(shortcut) if shortcut.mods.contains(Modifiers::FN) => {
+                if !cfg!(target_os = "macos") {
+                    return Err("Fn key combinations are only supported on macOS".to_string());
+                }
+                self.fn_combos.lock().unwrap().insert(shortcut, (name.to_string(), action.clone()));
+                self.start_fn_listener(app);
+                Ok(())
+            }
+            Trigger::Shortcut(shortcut) => {
+                register_shortcut(app, name, shortcut, action.clone()).map_err(|e| e.to_string())
+            }
+            Trigger::DoubleTap(modifier) => {
+                self.double_taps.lock().unwrap().insert(modifier, (name.to_string(), action.clone()));
+                self.start_double_tap_listener(app);
+                Ok(())
+            }
+            Trigger::Mouse(trigger) => {
+                self.mouse_bindings.lock().unwrap().insert(trigger, (name.to_string(), action.clone()));
+                self.start_mouse_listener(app);
+                Ok(())
+            }
+            // Chord actions are looked up from `bindings` when the first step fires
+            Trigger::Chord(first, _) if chord_prefix_in_use(bindings, name, first) => Ok(()),
+            Trigger::Chord(first, _) => register_chord_prefix(app, first).map_err(|e| e.to_string()),
+        }
+    }
+
+    fn detach_trigger<R: Runtime>(
+        &self,
+        app: &AppHandle<R>,
+        bindings: &HashMap<String, Binding>,
+        name: &str,
+        trigger: Trigger,
+    ) -> Result<(), String> {
+        match trigger {
+            Trigger::Shortcut(shortcut) if shortcut.mods.contains(Modifiers::FN) => {
+                self.fn_combos.lock().unwrap().remove(&shortcut);
+                Ok(())
+            }
+            Trigger::Shortcut(shortcut) => {
+                app.global_shortcut().unregister(shortcut).map_err(|e| e.to_string())
+            }
+            Trigger::DoubleTap(modifier) => {
+                self.double_taps.lock().unwrap().remove(&modifier);
+                Ok(())
+            }
+            Trigger::Mouse(trigger) => {
+                self.mouse_bindings.lock().unwrap().remove(&trigger);
+                Ok(())
+            }
+            Trigger::Chord(first, _) if chord_prefix_in_use(bindings, name, first) => Ok(()),
+            Trigger::Chord(first, _) => {
+                app.global_shortcut().unregister(first).map_err(|e| e.to_string())
+            }
+        }
+    }
+
+    /// First step of a chord was pressed: register every second step that
+    /// follows `prefix` until one fires or `CHORD_TIMEOUT` passes.
+    async fn arm_chord<R: Runtime>(&self, app: &AppHandle<R>, prefix: Shortcut) {
+        self.disarm_chord(app, None);
+
+        let steps: Vec<(Shortcut, String, HotkeyAction)> = self
+            .bindings
+            .lock()
+            .await
+            .iter()
+            .filter_map(|(name, b)| match b.trigger {
+                Trigger::Chord(first, second) if first == prefix => {
+                    Some((second, name.clone(), b.action.clone()))
+                }
+                _ => None,
+            })
+            .collect();
+
+        let generation = self.chord_generation.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut registered = Vec::new();
+
+        for (second, name, action) in steps {
+            let result = app.global_shortcut().on_shortcut(second, move |app, _shortcut, event| {
+                if event.state != ShortcutState::Pressed {
+                    return;
+                }
+
+                log::info!("Chord hotkey triggered");
+                let app = app.clone();
+                let name = name.clone();
+                let action = action.clone();
+                // Unregister outside the plugin's handler
+                tauri::async_runtime::spawn(async move {
+                    app.state::<HotkeyManager>().disarm_chord(&app, Some(generation));
+                    dispatch_action(&app, &name, &action);
+                });
+            });
+
+            match result {
+                Ok(()) => registered.push(second),
+                Err(e) => log::warn!("Failed to arm chord step: {}", e),
+            }
+        }
+
+        *self.armed_chord.lock().unwrap() = Some(ArmedChord {
+            generation,
+            shortcuts: registered,
+        });
+
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(CHORD_TIMEOUT).await;
+            app.state::<HotkeyManager>().disarm_chord(&app, Some(generation));
+        });
+    }
+
+    /// Drop the armed chord's temporary shortcuts. With a generation, only
+    /// that arming is cleared, so a stale timeout can't cancel a newer one.
+    fn disarm_chord<R: Runtime>(&self, app: &AppHandle<R>, generation: Option<u64>) {
+        let mut armed = self.armed_chord.lock().unwrap();
+        if generation.is_some() && armed.as_ref().map(|a| a.generation) != generation {
+            return;
+        }
+
+        if let Some(chord) = armed.take() {
+            for shortcut in chord.shortcuts {
+                if let Err(e) = app.global_shortcut().unregister(shortcut) {
+                    log::warn!("Failed to disarm chord step: {}", e);
+                }
+            }
+        }
+    }
+
+    /// The key listener can only be started once per process; it stays
+    /// running and ignores taps with no binding.
+    fn start_double_tap_listener<R: Runtime>(&self, app: &AppHandle<R>) {
+        self.listener_started.call_once(|| {
+            let app = app.clone();
+            let double_taps = Arc::clone(&self.double_taps);
+            double_tap::spawn_listener(move |modifier| {
+                let binding = double_taps.lock().unwrap().get(&modifier).cloned();
+                if let Some((name, action)) = binding {
+                    log::info!("Double-tap hotkey triggered");
+                    dispatch_action(&app, &name, &action);
+                }
+            });
+        });
+    }
+
+    fn start_mouse_listener<R: Runtime>(&self, app: &AppHandle<R>) {
+        self.mouse_listener_started.call_once(|| {
+            let app = app.clone();
+            let mouse_bindings = Arc::clone(&self.mouse_bindings);
+            mouse::spawn_mouse_listener(move |trigger| {
+                let binding = mouse_bindings.lock().unwrap().get(&trigger).cloned();
+                if let Some((name, action)) = binding {
+                    log::info!("Mouse hotkey triggered");
+                    dispatch_action(&app, &name, &action);
+                }
+            });
+        });
+    }
+
+    fn start_fn_listener<R: Runtime>(&self, app: &AppHandle<R>) {
+        self.fn_listener_started.call_once(|| {
+            let app = app.clone();
+            let fn_combos = Arc::clone(&self.fn_combos);
+            macos::spawn_fn_listener(move |combo| {
+                let Ok(shortcut) = Self::parse_hotkey(combo) else {
+                    return;
+                };
+                let binding = fn_combos.lock().unwrap().get(&shortcut).cloned();
+                if let Some((name, action)) = binding {
+                    log::info!("Fn hotkey triggered");
+                    dispatch_action(&app, &name, &action);
+                }
+            });
+        });
+    }
+}
+
+/// Load hotkey settings from config and register the panel toggle and every
+/// stored binding. Failures are logged per binding so one bad entry doesn't
+/// block the rest.
+pub async fn restore_bindings<R: Runtime>(app: &AppHandle<R>) {
+    let config = app.state::<ConfigManager>();
+    let manager = app.state::<HotkeyManager>();
+
+    let hotkey = match config.effective_config() {
+        Ok(cfg) => {
+            manager.set_debounce(Duration::from_millis(cfg.hotkey_debounce_ms));
+            cfg.hotkey
+        }
+        Err(_) => "Ctrl+Shift+V".to_string(),
+    };
+
+    if let Err(e) = manager.register_hotkey(app, &hotkey).await {
+        log::error!("Failed to register hotkey '{}': {}", hotkey, e);
+    } else {
+        log::info!("Global shortcut registered: {}", hotkey);
+    }
+
+    match config.get_hotkey_exclusions() {
+        Ok(apps) => manager.set_excluded_apps(apps),
+        Err(e) => log::error!("Failed to load hotkey exclusions: {}", e),
+    }
+    match config.get_hotkey_pass_through() {
+        Ok(names) => manager.set_pass_through_bindings(names),
+        Err(e) => log::error!("Failed to load hotkey pass-through settings: {}", e),
+    }
+
+    let mut bindings: Vec<(String, String, HotkeyAction)> = Vec::new();
+
+    match config.get_action_hotkeys() {
+        Ok(hotkeys) => {
+            for binding in hotkeys {
+                match builtin_action(&binding.action) {
+                    Some(action) => bindings.push((binding.action, binding.hotkey, action)),
+                    None => log::warn!("Ignoring hotkey for unknown action '{}'", binding.action),
+                }
+            }
+        }
+        Err(e) => log::error!("Failed to load action hotkeys: {}", e),
+    }
+
+    match config.get_rule_hotkeys() {
+        Ok(hotkeys) => bindings.extend(hotkeys.into_iter().map(|b| {
+            let action = HotkeyAction::ApplyRules { rule_ids: b.rule_ids };
+            (rule_binding_name(&b.id), b.hotkey, action)
+        })),
+        Err(e) => log::error!("Failed to load rule hotkeys: {}", e),
+    }
+
+    // Shortcuts of pinned rules
+    match config.get_custom_rules() {
+        Ok(rules) => bindings.extend(rules.into_iter().filter_map(|rule| {
+            let hotkey = rule.shortcut?;
+            let action = HotkeyAction::ApplyRules {
+                rule_ids: vec![rule.id.clone()],
+            };
+            Some((pinned_binding_name(&rule.id), hotkey, action))
+        })),
+        Err(e) => log::error!("Failed to load custom rules: {}", e),
+    }
+
+    match config.get_headless_hotkeys() {
+        Ok(hotkeys) => bindings.extend(hotkeys.into_iter().map(|b| {
+            let action = HotkeyAction::Headless { pipeline: b.pipeline };
+            (headless_binding_name(&b.id), b.hotkey, action)
+        })),
+        Err(e) => log::error!("Failed to load headless hotkeys: {}", e),
+    }
+
+    match config.get_webhooks() {
+        Ok(webhooks) => bindings.extend(webhooks.into_iter().filter_map(|w| {
+            let hotkey = w.hotkey?;
+            let action = HotkeyAction::Webhook {
+                id: w.id.clone(),
+                capture_selection: w.capture_selection,
+            };
+            Some((webhook_binding_name(&w.id), hotkey, action))
+        })),
+        Err(e) => log::error!("Failed to load webhooks: {}", e),
+    }
+
+    match config.get_workflows() {
+        Ok(workflows) => bindings.extend(workflows.into_iter().filter_map(|w| {
+            let hotkey = w.hotkey?;
+            let action = HotkeyAction::Workflow { id: w.id.clone() };
+            Some((workflow_binding_name(&w.id), hotkey, action))
+        })),
+        Err(e) => log::error!("Failed to load workflows: {}", e),
+    }
+
+    for (name, hotkey, action) in bindings {
+        if let Err(e) = manager.register_binding(app, &name, &hotkey, action).await {
+            log::error!("Failed to register hotkey '{}' for '{}': {}", hotkey, name, e);
+        }
+    }
+}
+
+/// Trial-register a shortcut to see whether another application owns it.
+/// Not every platform reports this (X11 does, macOS often does not).
+fn probe_shortcut<R: Runtime>(app: &AppHandle<R>, shortcut: Shortcut) -> bool {
+    let shortcuts = app.global_shortcut();
+    match shortcuts.on_shortcut(shortcut, |_app, _shortcut, _event| {}) {
+        Ok(()) => {
+            if let Err(e) = shortcuts.unregister(shortcut) {
+                log::warn!("Failed to release probed hotkey: {}", e);
+            }
+            true
+        }
+        Err(e) => {
+            log::debug!("Hotkey probe failed: {}", e);
+            false
+        }
+    }
+}
+
+/// Move the window next to the cursor, on the monitor the cursor is on.
+pub(crate) fn move_to_cursor<R: Runtime>(app: &AppHandle<R>, window: &WebviewWindow<R>) -> tauri::Result<()> {
+    let cursor = app.cursor_position()?;
+    let monitor = match app.monitor_from_point(cursor.x, cursor.y)? {
+        Some(monitor) => monitor,
+        None => match window.current_monitor()? {
+            Some(monitor) => monitor,
+            None => return Ok(()),
+        },
+    };
+
+    let work_area = monitor.work_area();
+    let area = position::Rect {
+        x: work_area.position.x,
+        y: work_area.position.y,
+        width: work_area.size.width,
+        height: work_area.size.height,
+    };
+    let size = window.outer_size()?;
+    let (x, y) = position::place_near_cursor(
+        (cursor.x as i32, cursor.y as i32),
+        (size.width, size.height),
+        area,
+    );
+    window.set_position(PhysicalPosition::new(x, y))
+}
+
+/// Canonical accelerator for a shortcut, e.g. "Ctrl+Shift+V". Modifiers
+/// are ordered Fn, Ctrl, Alt, Shift, Meta and the result parses back unchanged.
+pub fn format_shortcut(shortcut: &Shortcut) -> String {
+    let mut parts: Vec<String> = [
+        (Modifiers::FN, "Fn"),
+        (Modifiers::CONTROL, "Ctrl"),
+        (Modifiers::ALT, "Alt"),
+        (Modifiers::SHIFT, "Shift"),
+        (Modifiers::META | Modifiers::SUPER, "Meta"),
+    ]
+    .iter()
+    .filter(|(m, _)| shortcut.mods.intersects(*m))
+    .map(|(_, name)| name.to_string())
+    .collect();
+    parts.push(key_label(shortcut.key));
+    parts.join("+")
+}
+
+/// Display name for a key, accepted by `parse_key_code`.
+fn key_label(code: Code) -> String {
+    let name = code.to_string();
+    if let Some(rest) = name.strip_prefix("Key").or_else(|| name.strip_prefix("Digit")) {
+        if rest.len() == 1 {
+            return rest.to_string();
+        }
+    }
+
+    match code {
+        Code::ArrowUp => "Up".to_string(),
+        Code::ArrowDown => "Down".to_string(),
+        Code::ArrowLeft => "Left".to_string(),
+        Code::ArrowRight => "Right".to_string(),
+        _ => name,
+    }
+}
+
+/// Split `Ctrl+K, Ctrl+V` into its steps. A comma right after `+` is the
+/// comma key (`Ctrl+,`), not a separator.
+fn split_chord(hotkey: &str) -> Option<(&str, &str)> {
+    let bytes = hotkey.as_bytes();
+    let idx = (0..bytes.len()).find(|&i| {
+        bytes[i] == b',' && hotkey[..i].trim_end().chars().last().is_some_and(|c| c != '+')
+    })?;
+    Some((&hotkey[..idx], &hotkey[idx + 1..]))
+}
+
+/// Whether a binding other than `name` is a chord starting with `prefix`.
+fn chord_prefix_in_use(bindings: &HashMap<String, Binding>, name: &str, prefix: Shortcut) -> bool {
+    bindings
+        .iter()
+        .any(|(n, b)| n != name && matches!(b.trigger, Trigger::Chord(first, _) if first == prefix))
+}
+
+fn register_shortcut<R: Runtime>(
+    app: &AppHandle<R>,
+    name: &str,
+    shortcut: Shortcut,
+    action: HotkeyAction,
+) -> Result<(), tauri_plugin_global_shortcut::Error> {
+    let name = name.to_string();
+    app.global_shortcut()
+        .on_shortcut(shortcut, move |app, shortcut, event| {
+            let pressed = event.state == ShortcutState::Pressed;
+            let fire = app.state::<HotkeyManager>().should_fire(shortcut, pressed);
+
+            match event.state {
+                ShortcutState::Pressed if fire && app.state::<HotkeyManager>().passes_through(&name) => {
+                    log::info!("Forwarding hotkey '{}' to the previous app", name);
+                    forward_to_previous_app(app, &name, *shortcut, &action);
+                }
+                ShortcutState::Pressed if fire => {
+                    log::info!("Global hotkey triggered");
+                    dispatch_action(app, &name, &action);
+                }
+                ShortcutState::Pressed => log::debug!("Ignoring repeated hotkey press"),
+                ShortcutState::Released => {
+                    if action == HotkeyAction::TogglePanel && hold_to_peek(app) {
+                        end_peek(app);
+                    }
+                }
+            }
+        })
+}
+
+/// Hand the keystroke to the app behind the focused panel. The panel is
+/// hidden so focus returns to that app, then shown again without focus.
+fn forward_to_previous_app<R: Runtime>(
+    app: &AppHandle<R>,
+    name: &str,
+    shortcut: Shortcut,
+    action: &HotkeyAction,
+) {
+    let app = app.clone();
+    let name = name.to_string();
+    let action = action.clone();
+    // Re-registering has to happen outside the plugin's handler
+    tauri::async_runtime::spawn(async move {
+        let manager = app.state::<HotkeyManager>();
+        // Keep the binding from changing while it is briefly unregistered
+        let _bindings = manager.bindings.lock().await;
+
+        let window = app.get_webview_window("main");
+        if let Some(window) = &window {
+            if let Err(e) = window.hide() {
+                log::error!("Failed to hide window: {}", e);
+            }
+        }
+
+        if let Err(e) = app.global_shortcut().unregister(shortcut) {
+            log::error!("Failed to release hotkey '{}' for forwarding: {}", name, e);
+        } else {
+            match tauri::async_runtime::spawn_blocking(move || forward::send_shortcut(&shortcut)).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => log::error!("Failed to forward hotkey '{}': {}", name, e),
+                Err(e) => log::error!("Failed to forward hotkey '{}': {}", name, e),
+            }
+            if let Err(e) = register_shortcut(&app, &name, shortcut, action) {
+                log::error!("Failed to restore hotkey '{}' after forwarding: {}", name, e);
+            }
+        }
+
+        if let Some(window) = window {
+            // Not focusable while it appears, so the keystroke's target keeps focus
+            if let Err(e) = window.set_focusable(false) {
+                log::warn!("Failed to show window without focus: {}", e);
+            }
+            if let Err(e) = window.show() {
+                log::error!("Failed to show window: {}", e);
+            }
+            if let Err(e) = window.set_focusable(true) {
+                log::error!("Failed to make window focusable again: {}", e);
+            }
+        }
+    });
+}
+
+fn register_chord_prefix<R: Runtime>(
+    app: &AppHandle<R>,
+    prefix: Shortcut,
+) -> Result<(), tauri_plugin_global_shortcut::Error> {
+    app.global_shortcut()
+        .on_shortcut(prefix, move |app, _shortcut, event| {
+            if event.state != ShortcutState::Pressed {
+                return;
+            }
+
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                app.state::<HotkeyManager>().arm_chord(&app, prefix).await;
+            });
+        })
+}
+
+/// Run the action of binding `name`, announcing it with `hotkey:triggered`
+/// so the webview can route bindings to its own flows.
+fn dispatch_action<R: Runtime>(app: &AppHandle<R>, name: &str, action: &HotkeyAction) {
+    let frontmost = exclusion::frontmost_app();
+    if app.state::<HotkeyManager>().is_suppressed(frontmost.as_ref()) {
+        log::debug!("Hotkey ignored: excluded application is frontmost");
+        return;
+    }
+    app.state::<ConfigManager>().track(UsageMetric::HotkeyActivation, 1);
+
+    let event = HotkeyTriggered {
+        binding: name.to_string(),
+        action: action.clone(),
+        timestamp: chrono::Utc::now().timestamp_millis(),
+        frontmost_app: frontmost.map(|app| app.name),
+    };
+    if let Err(e) = app.emit("hotkey:triggered", event) {
+        log::error!("Failed to emit hotkey:triggered event: {}", e);
+    }
+
+    run_action(app, action);
+}
+
+fn run_action<R: Runtime>(app: &AppHandle<R>, action: &HotkeyAction) {
+    match action {
+        HotkeyAction::TogglePanel if hold_to_peek(app) => show_panel(app, "panel:show"),
+        HotkeyAction::TogglePanel => toggle_panel(app),
+        HotkeyAction::ShowHistory => show_panel(app, "panel:history"),
+        HotkeyAction::ApplyRules { rule_ids } => {
+            app.state::<HotkeyManager>().set_last_action(action.clone());
+            let app = app.clone();
+            let rule_ids = rule_ids.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                match clipboard::transform_clipboard(&app, &rule_ids) {
+                    Ok(summary) => log::info!(
+                        "Applied {} rule(s) to clipboard via hotkey (changed: {})",
+                        summary.rule_ids.len(),
+                        summary.changed
+                    ),
+                    Err(e) => log::error!("Hotkey rule pipeline {:?} failed: {}", rule_ids, e),
+                }
+            });
+        }
+        HotkeyAction::PastePlainText => {
+            app.state::<HotkeyManager>().set_last_action(action.clone());
+            let app = app.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                if let Err(e) = rewrite_as_plain_text(&app) {
+                    log::error!("Paste as plain text failed: {}", e);
+                }
+            });
+        }
+        HotkeyAction::PasteLastAiResult => {
+            let Some(result) = app.state::<Arc<AIState>>().last_result() else {
+                log::info!("No AI result to paste yet");
+                return;
+            };
+            let app = app.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                if let Err(e) = write_and_paste(&app, &result) {
+                    log::error!("Paste last AI result failed: {}", e);
+                }
+            });
+        }
+        HotkeyAction::CycleClipboard { step } => {
+            let Some((text, result)) = app.state::<RecentClipboard>().cycle(*step) else {
+                log::info!("Not enough clipboard history to cycle");
+                return;
+            };
+            let app = app.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                if let Err(e) = clipboard::write_clipboard(&app, &text) {
+                    log::error!("Failed to cycle clipboard: {}", e);
+                    return;
+                }
+                if let Err(e) = app.emit("clipboard:cycled", result) {
+                    log::error!("Failed to emit clipboard:cycled event: {}", e);
+                }
+            });
+        }
+        HotkeyAction::Headless { pipeline } => {
+            app.state::<HotkeyManager>().set_last_action(action.clone());
+            let app = app.clone();
+            let pipeline = pipeline.clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = headless::run_headless(&app, &pipeline).await {
+                    log::error!("Headless pipeline failed: {}", e);
+                }
+            });
+        }
+        HotkeyAction::Webhook { id, capture_selection } => {
+            let app = app.clone();
+            let id = id.clone();
+            let source = if *capture_selection {
+                webhook::Source::Selection
+            } else {
+                webhook::Source::Clipboard
+            };
+            tauri::async_runtime::spawn(async move {
+                webhook::run(&app, &id, source).await;
+            });
+        }
+        HotkeyAction::Workflow { id } => {
+            let app = app.clone();
+            let id = id.clone();
+            tauri::async_runtime::spawn(async move {
+                workflow::run(&app, &id, None).await;
+            });
+        }
+        HotkeyAction::ScreenshotOcr => {
+            let app = app.clone();
+            tauri::async_runtime::spawn_blocking(move || match ocr::capture_text(&app) {
+                Ok(_) => show_panel(&app, "panel:show"),
+                Err(OcrError::Cancelled) => log::info!("Screen capture cancelled"),
+                Err(e) => log::error!("Screenshot OCR failed: {}", e),
+            });
+        }
+        HotkeyAction::SaveToNotes => {
+            let app = app.clone();
+            tauri::async_runtime::spawn_blocking(move || {
+                if let Err(e) = notes::save_clipboard(&app) {
+                    log::error!("Save to notes failed: {}", e);
+                }
+            });
+        }
+        HotkeyAction::TranslateClipboard => {
+            app.state::<HotkeyManager>().set_last_action(action.clone());
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let result = match translate_clipboard(&app).await {
+                    Ok(text) => write_and_paste(&app, &text).map_err(|e| e.to_string()),
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = result {
+                    log::error!("Translate clipboard failed: {}", e);
+                }
+            });
+        }
+        HotkeyAction::RepeatLastAction => match app.state::<HotkeyManager>().last_action() {
+            Some(last) => run_action(app, &last),
+            None => log::info!("No action to repeat"),
+        },
+    }
+}
+
+/// Translation of the clipboard text. Without a translation service the AI
+/// provider translates it, as the translate chip does.
+async fn translate_clipboard<R: Runtime>(app: &AppHandle<R>) -> Result<String, String> {
+    let config = app.state::<ConfigManager>().effective_config().map_err(|e| e.to_string())?;
+    if !config.translation_service.is_empty() {
+        return translation::translate_clipboard(app)
+            .await
+            .map(|result| result.text)
+            .map_err(|e| e.to_string());
+    }
+    let text = clipboard::read_clipboard(app)
+        .map_err(|e| e.to_string())?
+        .text
+        .filter(|t| !t.trim().is_empty())
+        .ok_or_else(|| clipboard::ClipboardError::Empty.to_string())?;
+    run_prompt(app, translation_prompt(&text), &text)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+fn rewrite_as_plain_text<R: Runtime>(app: &AppHandle<R>) -> Result<(), clipboard::ClipboardError> {
+    // Writing text replaces every clipboard format, which drops HTML/RTF
+    let text = clipboard::read_clipboard(app)?
+        .text
+        .ok_or(clipboard::ClipboardError::Unsupported)?;
+    write_and_paste(app, &text)
+}
+
+/// Write `text` to the clipboard, then paste it if `auto_paste` is enabled.
+pub(crate) fn write_and_paste<R: Runtime>(app: &AppHandle<R>, text: &str) -> Result<(), clipboard::ClipboardError> {
+    clipboard::write_clipboard(app, text)?;
+
+    let auto_paste = app
+        .state::<ConfigManager>()
+        .effective_config()
+        .map(|c| c.auto_paste)
+        .unwrap_or(false);
+    if auto_paste {
+        clipboard::simulate_paste()?;
+    }
+    Ok(())
+}
+
+/// Hide the panel if it is showing, otherwise show it.
+pub fn toggle_panel<R: Runtime>(app: &AppHandle<R>) {
+    let Some(window) = app.get_webview_window("main") else {
+        log::error!("Main window not found");
+        return;
+    };
+
+    match window.is_visible() {
+        Ok(true) => {
+            if let Err(e) = window.hide() {
+                log::error!("Failed to hide window: {}", e);
+            }
+        }
+        Ok(false) => show_panel(app, "panel:show"),
+        Err(e) => {
+            log::error!("Failed to check window visibility: {}", e);
+        }
+    }
+}
+
+fn hold_to_peek<R: Runtime>(app: &AppHandle<R>) -> bool {
+    app.state::<ConfigManager>()
+        .effective_config()
+        .map(|c| c.panel_mode == "hold")
+        .unwrap_or(false)
+}
+
+/// Hotkey released in hold mode: hide the panel. The frontend gets
+/// `panel:release` first so it can paste the highlighted item.
+fn end_peek<R: Runtime>(app: &AppHandle<R>) {
+    if app.state::<HotkeyManager>().is_suppressed(exclusion::frontmost_app().as_ref()) {
+        return;
+    }
+    let Some(window) = app.get_webview_window("main") else {
+        log::error!("Main window not found");
+        return;
+    };
+
+    if let Err(e) = window.emit("panel:release", ()) {
+        log::error!("Failed to emit panel:release event: {}", e);
+    }
+    if let Err(e) = window.hide() {
+        log::error!("Failed to hide window: {}", e);
+    }
+}
+
+/// Show and focus the panel, then tell the frontend which view to open.
+pub fn show_panel<R: Runtime>(app: &AppHandle<R>, event: &str) {
+    show_panel_with(app, event, ())
+}
+
+/// `show_panel`, passing `payload` with the event.
+pub fn show_panel_with<R: Runtime, S: Serialize + Clone>(app: &AppHandle<R>, event: &str, payload: S) {
+    let Some(window) = app.get_webview_window("main") else {
+        log::error!("Main window not found");
+        return;
+    };
+
+    let show_at_cursor = app
+        .state::<ConfigManager>()
+        .effective_config()
+        .map(|c| c.show_at_cursor)
+        .unwrap_or(false);
+    if show_at_cursor {
+        if let Err(e) = move_to_cursor(app, &window) {
+            log::warn!("Failed to position window at cursor: {}", e);
+        }
+    }
+
+    if let Err(e) = window.show() {
+        log::error!("Failed to show window: {}", e);
+    }
+    if let Err(e) = window.set_focus() {
+        log::error!("Failed to focus window: {}", e);
+    }
+    if let Err(e) = window.emit(event, payload) {
+        log::error!("Failed to emit {} event: {}", event, e);
     }
 }
 
@@ -217,6 +1456,18 @@ fn parse_key_code(key: &str) -> Result<Code, HotkeyError> {
         "F10" => Ok(Code::F10),
         "F11" => Ok(Code::F11),
         "F12" => Ok(Code::F12),
+        "F13" => Ok(Code::F13),
+        "F14" => Ok(Code::F14),
+        "F15" => Ok(Code::F15),
+        "F16" => Ok(Code::F16),
+        "F17" => Ok(Code::F17),
+        "F18" => Ok(Code::F18),
+        "F19" => Ok(Code::F19),
+        "F20" => Ok(Code::F20),
+        "F21" => Ok(Code::F21),
+        "F22" => Ok(Code::F22),
+        "F23" => Ok(Code::F23),
+        "F24" => Ok(Code::F24),
 
         // Special keys
         "SPACE" => Ok(Code::Space),
@@ -237,6 +1488,32 @@ fn parse_key_code(key: &str) -> Result<Code, HotkeyError> {
         "LEFT" | "ARROWLEFT" => Ok(Code::ArrowLeft),
         "RIGHT" | "ARROWRIGHT" => Ok(Code::ArrowRight),
 
+        // Numpad
+        "NUMPAD0" | "NUM0" => Ok(Code::Numpad0),
+        "NUMPAD1" | "NUM1" => Ok(Code::Numpad1),
+        "NUMPAD2" | "NUM2" => Ok(Code::Numpad2),
+        "NUMPAD3" | "NUM3" => Ok(Code::Numpad3),
+        "NUMPAD4" | "NUM4" => Ok(Code::Numpad4),
+        "NUMPAD5" | "NUM5" => Ok(Code::Numpad5),
+        "NUMPAD6" | "NUM6" => Ok(Code::Numpad6),
+        "NUMPAD7" | "NUM7" => Ok(Code::Numpad7),
+        "NUMPAD8" | "NUM8" => Ok(Code::Numpad8),
+        "NUMPAD9" | "NUM9" => Ok(Code::Numpad9),
+        "NUMPADENTER" => Ok(Code::NumpadEnter),
+        "NUMPADADD" | "NUMADD" => Ok(Code::NumpadAdd),
+        "NUMPADSUBTRACT" | "NUMSUB" => Ok(Code::NumpadSubtract),
+        "NUMPADMULTIPLY" | "NUMMULT" => Ok(Code::NumpadMultiply),
+        "NUMPADDIVIDE" | "NUMDIV" => Ok(Code::NumpadDivide),
+        "NUMPADDECIMAL" | "NUMDEC" => Ok(Code::NumpadDecimal),
+        "NUMPADEQUAL" => Ok(Code::NumpadEqual),
+
+        // Lock and system keys
+        "PRINTSCREEN" | "PRTSC" => Ok(Code::PrintScreen),
+        "PAUSE" | "BREAK" => Ok(Code::Pause),
+        "CAPSLOCK" => Ok(Code::CapsLock),
+        "SCROLLLOCK" => Ok(Code::ScrollLock),
+        "NUMLOCK" => Ok(Code::NumLock),
+
         // Punctuation and symbols
         ";" | "SEMICOLON" => Ok(Code::Semicolon),
         "=" | "EQUAL" | "EQUALS" => Ok(Code::Equal),
@@ -258,6 +1535,84 @@ fn parse_key_code(key: &str) -> Result<Code, HotkeyError> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_builtin_actions() {
+        for name in ACTION_BINDINGS {
+            assert!(builtin_action(name).is_some(), "{} has no action", name);
+        }
+        assert!(builtin_action(TOGGLE_PANEL_BINDING).is_none());
+    }
+
+    #[test]
+    fn test_last_action() {
+        let manager = HotkeyManager::new();
+        assert!(manager.last_action().is_none());
+        manager.set_last_action(HotkeyAction::PastePlainText);
+        assert_eq!(manager.last_action(), Some(HotkeyAction::PastePlainText));
+    }
+
+    #[test]
+    fn test_normalize_hotkey() {
+        assert_eq!(HotkeyManager::normalize_hotkey("control + shift + v").unwrap(), "Ctrl+Shift+V");
+        assert_eq!(HotkeyManager::normalize_hotkey("shift+alt+ctrl+f13").unwrap(), "Ctrl+Alt+Shift+F13");
+        assert_eq!(HotkeyManager::normalize_hotkey("cmd+1").unwrap(), "Meta+1");
+        assert_eq!(HotkeyManager::normalize_hotkey("Ctrl+arrowup").unwrap(), "Ctrl+Up");
+        assert_eq!(HotkeyManager::normalize_hotkey("ctrl+k,ctrl+,").unwrap(), "Ctrl+K, Ctrl+Comma");
+        assert_eq!(HotkeyManager::normalize_hotkey("doubletap+control").unwrap(), "DoubleTap+Ctrl");
+    }
+
+    #[test]
+    fn test_format_shortcut_round_trips() {
+        for hotkey in ["Ctrl+Shift+V", "Alt+Numpad5", "Ctrl+Semicolon", "Meta+PrintScreen", "Shift+Escape"] {
+            let shortcut = HotkeyManager::parse_hotkey(hotkey).unwrap();
+            let formatted = format_shortcut(&shortcut);
+            assert_eq!(HotkeyManager::parse_hotkey(&formatted).unwrap(), shortcut, "{}", formatted);
+        }
+    }
+
+    #[test]
+    fn test_parse_mac_notation() {
+        assert_eq!(HotkeyManager::normalize_hotkey("⌥Space").unwrap(), "Alt+Space");
+        assert_eq!(HotkeyManager::normalize_hotkey("⌘⇧V").unwrap(), "Meta+Shift+V");
+        assert_eq!(HotkeyManager::normalize_hotkey("⌥√").unwrap(), "Alt+V");
+        assert_eq!(HotkeyManager::normalize_hotkey("⌘,").unwrap(), "Meta+Comma");
+        assert_eq!(HotkeyManager::normalize_hotkey("globe+v").unwrap(), "Fn+V");
+        assert!(HotkeyManager::normalize_hotkey("Fn+K, Fn+V").is_err());
+    }
+
+    #[test]
+    fn test_parse_mouse_trigger() {
+        assert_eq!(HotkeyManager::normalize_hotkey("control+mouse4").unwrap(), "Ctrl+Mouse4");
+        assert_eq!(HotkeyManager::normalize_hotkey("XButton2").unwrap(), "Mouse5");
+        assert!(HotkeyManager::normalize_hotkey("Ctrl+Mouse6").is_err());
+    }
+
+    #[test]
+    fn test_parse_chord() {
+        let trigger = HotkeyManager::parse_trigger("Ctrl+K, Ctrl+V").unwrap();
+        assert!(matches!(trigger, Trigger::Chord(_, _)));
+        assert!(matches!(HotkeyManager::parse_trigger("Ctrl+,").unwrap(), Trigger::Shortcut(_)));
+        assert!(matches!(HotkeyManager::parse_trigger("Ctrl+, , Ctrl+V").unwrap(), Trigger::Chord(_, _)));
+        assert!(HotkeyManager::parse_trigger("Ctrl+K, Ctrl+K").is_err());
+        assert!(HotkeyManager::parse_trigger("Ctrl+K, Bogus+V").is_err());
+    }
+
+    #[test]
+    fn test_trigger_conflicts() {
+        let ctrl_k = HotkeyManager::parse_hotkey("Ctrl+K").unwrap();
+        let ctrl_v = HotkeyManager::parse_hotkey("Ctrl+V").unwrap();
+        let ctrl_c = HotkeyManager::parse_hotkey("Ctrl+C").unwrap();
+
+        assert!(Trigger::Shortcut(ctrl_k).conflicts_with(&Trigger::Chord(ctrl_k, ctrl_v)));
+        assert!(Trigger::Chord(ctrl_k, ctrl_v).conflicts_with(&Trigger::Chord(ctrl_k, ctrl_v)));
+        // Chords may share a first step
+        assert!(!Trigger::Chord(ctrl_k, ctrl_v).conflicts_with(&Trigger::Chord(ctrl_k, ctrl_c)));
+        // The second step can't be registered while Ctrl+V is bound
+        assert!(Trigger::Shortcut(ctrl_v).conflicts_with(&Trigger::Chord(ctrl_k, ctrl_v)));
+        assert!(Trigger::Chord(ctrl_v, ctrl_c).conflicts_with(&Trigger::Chord(ctrl_k, ctrl_v)));
+        assert!(!Trigger::Shortcut(ctrl_c).conflicts_with(&Trigger::Chord(ctrl_k, ctrl_v)));
+    }
+
     #[test]
     fn test_parse_hotkey_ctrl_shift_v() {
         let result = HotkeyManager::parse_hotkey("Ctrl+Shift+V");
@@ -310,4 +1665,18 @@ mod tests {
         assert!(parse_key_code("/").is_ok());
         assert!(parse_key_code("InvalidKey").is_err());
     }
+
+    #[test]
+    fn test_parse_extended_keys() {
+        assert_eq!(parse_key_code("F13").unwrap(), Code::F13);
+        assert_eq!(parse_key_code("F24").unwrap(), Code::F24);
+        assert_eq!(parse_key_code("Numpad7").unwrap(), Code::Numpad7);
+        assert_eq!(parse_key_code("Num0").unwrap(), Code::Numpad0);
+        assert_eq!(parse_key_code("NumpadEnter").unwrap(), Code::NumpadEnter);
+        assert_eq!(parse_key_code("PrintScreen").unwrap(), Code::PrintScreen);
+        assert_eq!(parse_key_code("Pause").unwrap(), Code::Pause);
+        assert_eq!(parse_key_code("CapsLock").unwrap(), Code::CapsLock);
+        assert!(HotkeyManager::parse_hotkey("Ctrl+NumpadAdd").is_ok());
+        assert!(parse_key_code("F25").is_err());
+    }
 }
@@ -0,0 +1,203 @@
+use std::thread;
+
+use super::double_tap::TapModifier;
+use super::listener::{self, InputEvent};
+use super::HotkeyError;
+
+/// Extra mouse buttons that can be bound. rdev doesn't report them on macOS.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MouseButton {
+    /// Mouse4, usually "back"
+    Back,
+    /// Mouse5, usually "forward"
+    Forward,
+}
+
+impl MouseButton {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Back => "Mouse4",
+            Self::Forward => "Mouse5",
+        }
+    }
+
+    /// X buttons are reported as 1/2 on Windows and 8/9 on X11.
+    fn from_rdev(button: rdev::Button) -> Option<Self> {
+        match button {
+            rdev::Button::Unknown(1) | rdev::Button::Unknown(8) => Some(Self::Back),
+            rdev::Button::Unknown(2) | rdev::Button::Unknown(9) => Some(Self::Forward),
+            _ => None,
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "mouse4" | "xbutton1" | "mouseback" => Some(Self::Back),
+            "mouse5" | "xbutton2" | "mouseforward" => Some(Self::Forward),
+            _ => None,
+        }
+    }
+}
+
+/// A mouse button with the modifiers that must be held, e.g. `Ctrl+Mouse4`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MouseTrigger {
+    pub ctrl: bool,
+    pub alt: bool,
+    pub shift: bool,
+    pub meta: bool,
+    pub button: MouseButton,
+}
+
+impl MouseTrigger {
+    fn new(held: &[TapModifier], button: MouseButton) -> Self {
+        Self {
+            ctrl: held.contains(&TapModifier::Ctrl),
+            alt: held.contains(&TapModifier::Alt),
+            shift: held.contains(&TapModifier::Shift),
+            meta: held.contains(&TapModifier::Meta),
+            button,
+        }
+    }
+
+    /// Canonical form with modifiers ordered Ctrl, Alt, Shift, Meta.
+    pub fn format(&self) -> String {
+        let mut parts: Vec<&str> = [
+            (self.ctrl, "Ctrl"),
+            (self.alt, "Alt"),
+            (self.shift, "Shift"),
+            (self.meta, "Meta"),
+        ]
+        .iter()
+        .filter(|(held, _)| *held)
+        .map(|(_, name)| *name)
+        .collect();
+        parts.push(self.button.name());
+        parts.join("+")
+    }
+}
+
+/// Whether `hotkey` ends in a mouse button rather than a key.
+pub fn is_mouse_trigger(hotkey: &str) -> bool {
+    hotkey
+        .rsplit('+')
+        .next()
+        .is_some_and(|last| MouseButton::parse(last.trim()).is_some())
+}
+
+/// Parse `Mouse4`, `Mouse5` or either with modifiers (`Ctrl+Shift+Mouse5`).
+pub fn parse_mouse_trigger(hotkey: &str) -> Result<MouseTrigger, HotkeyError> {
+    let parts: Vec<&str> = hotkey.split('+').map(str::trim).collect();
+    let (last, modifiers) = parts.split_last().unwrap();
+    let button = MouseButton::parse(last)
+        .ok_or_else(|| HotkeyError::InvalidFormat(format!("Unknown mouse button: {}", last)))?;
+
+    let mut held = Vec::new();
+    for part in modifiers {
+        let modifier = match part.to_lowercase().as_str() {
+            "ctrl" | "control" => TapModifier::Ctrl,
+            "shift" => TapModifier::Shift,
+            "alt" | "option" => TapModifier::Alt,
+            "meta" | "super" | "cmd" | "command" => TapModifier::Meta,
+            _ => return Err(HotkeyError::InvalidFormat(format!("Unknown modifier: {}", part))),
+        };
+        held.push(modifier);
+    }
+    Ok(MouseTrigger::new(&held, button))
+}
+
+/// Tracks held modifiers and reports bindable mouse button presses.
+#[derive(Default)]
+pub struct MouseTriggerDetector {
+    held: Vec<TapModifier>,
+}
+
+impl MouseTriggerDetector {
+    pub fn on_input(&mut self, event: InputEvent) -> Option<MouseTrigger> {
+        match event {
+            InputEvent::Key(key) => {
+                if let Some(modifier) = TapModifier::from_key(key.key) {
+                    self.held.retain(|m| *m != modifier);
+                    if key.pressed {
+                        self.held.push(modifier);
+                    }
+                }
+                None
+            }
+            InputEvent::Button { button, pressed: true } => {
+                MouseButton::from_rdev(button).map(|b| MouseTrigger::new(&self.held, b))
+            }
+            InputEvent::Button { .. } => None,
+        }
+    }
+}
+
+/// Run mouse trigger detection on the shared input listener for the lifetime of the app.
+pub fn spawn_mouse_listener<F>(on_trigger: F)
+where
+    F: Fn(MouseTrigger) + Send + 'static,
+{
+    let events = listener::subscribe_input();
+    let spawned = thread::Builder::new()
+        .name("mouse-listener".to_string())
+        .spawn(move || {
+            let mut detector = MouseTriggerDetector::default();
+            for event in events {
+                if let Some(trigger) = detector.on_input(event) {
+                    on_trigger(trigger);
+                }
+            }
+        });
+
+    if let Err(e) = spawned {
+        log::error!("Failed to start mouse listener: {}", e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(key: rdev::Key, pressed: bool) -> InputEvent {
+        InputEvent::Key(listener::KeyEvent { key, pressed })
+    }
+
+    fn button(code: u8, pressed: bool) -> InputEvent {
+        InputEvent::Button {
+            button: rdev::Button::Unknown(code),
+            pressed,
+        }
+    }
+
+    #[test]
+    fn test_parse_mouse_trigger() {
+        assert!(is_mouse_trigger("Mouse4"));
+        assert!(is_mouse_trigger("ctrl + mouse5"));
+        assert!(!is_mouse_trigger("Ctrl+M"));
+        assert_eq!(parse_mouse_trigger("shift+ctrl+XButton2").unwrap().format(), "Ctrl+Shift+Mouse5");
+        assert_eq!(parse_mouse_trigger("Mouse4").unwrap().format(), "Mouse4");
+        assert!(parse_mouse_trigger("Hyper+Mouse4").is_err());
+    }
+
+    #[test]
+    fn test_detects_buttons_with_modifiers() {
+        let mut d = MouseTriggerDetector::default();
+        assert_eq!(d.on_input(button(8, true)).map(|t| t.format()).as_deref(), Some("Mouse4"));
+        assert_eq!(d.on_input(button(8, false)), None);
+
+        d.on_input(key(rdev::Key::ControlLeft, true));
+        assert_eq!(d.on_input(button(2, true)).map(|t| t.format()).as_deref(), Some("Ctrl+Mouse5"));
+        d.on_input(key(rdev::Key::ControlLeft, false));
+        assert_eq!(d.on_input(button(9, true)).map(|t| t.format()).as_deref(), Some("Mouse5"));
+    }
+
+    #[test]
+    fn test_ignores_regular_buttons() {
+        let mut d = MouseTriggerDetector::default();
+        let left = InputEvent::Button {
+            button: rdev::Button::Left,
+            pressed: true,
+        };
+        assert_eq!(d.on_input(left), None);
+    }
+}
@@ -0,0 +1,78 @@
+/// Gap between the cursor and the panel's corner.
+const CURSOR_OFFSET: i32 = 12;
+
+/// Screen rectangle in physical pixels.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rect {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Top-left position for a `size` window next to `cursor`, inside `area`
+/// (the work area of the monitor under the cursor). The window goes
+/// below-right of the cursor, flips to the other side when it would
+/// overflow, and is clamped to the area as a last resort.
+pub fn place_near_cursor(cursor: (i32, i32), size: (u32, u32), area: Rect) -> (i32, i32) {
+    let (cx, cy) = cursor;
+    let (w, h) = (size.0 as i32, size.1 as i32);
+    let right = area.x + area.width as i32;
+    let bottom = area.y + area.height as i32;
+
+    let mut x = cx + CURSOR_OFFSET;
+    if x + w > right {
+        x = cx - CURSOR_OFFSET - w;
+    }
+    let mut y = cy + CURSOR_OFFSET;
+    if y + h > bottom {
+        y = cy - CURSOR_OFFSET - h;
+    }
+
+    // Larger than the area: keep the top-left corner visible
+    (x.min(right - w).max(area.x), y.min(bottom - h).max(area.y))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const AREA: Rect = Rect {
+        x: 0,
+        y: 0,
+        width: 1920,
+        height: 1040,
+    };
+
+    #[test]
+    fn test_below_right_of_cursor() {
+        assert_eq!(place_near_cursor((100, 100), (400, 300), AREA), (112, 112));
+    }
+
+    #[test]
+    fn test_flips_near_edges() {
+        assert_eq!(place_near_cursor((1900, 1000), (400, 300), AREA), (1488, 688));
+    }
+
+    #[test]
+    fn test_second_monitor_with_negative_origin() {
+        let left = Rect {
+            x: -1280,
+            y: 0,
+            width: 1280,
+            height: 1024,
+        };
+        assert_eq!(place_near_cursor((-20, 10), (400, 300), left), (-432, 22));
+    }
+
+    #[test]
+    fn test_clamps_oversized_window() {
+        let small = Rect {
+            x: 0,
+            y: 0,
+            width: 300,
+            height: 200,
+        };
+        assert_eq!(place_near_cursor((150, 100), (400, 300), small), (0, 0));
+    }
+}
@@ -0,0 +1,67 @@
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::io::{Read, Seek};
+use zip::ZipArchive;
+
+/// Part of the package holding the main text.
+const DOCUMENT_PART: &str = "word/document.xml";
+/// Refuse documents whose text part unpacks to more than this.
+const MAX_DOCUMENT_BYTES: u64 = 64 * 1024 * 1024;
+
+/// Text of a Word document, one paragraph per line.
+pub fn extract(file: impl Read + Seek) -> Result<String, String> {
+    let mut archive = ZipArchive::new(file).map_err(|e| format!("not a DOCX file: {}", e))?;
+    let part = archive
+        .by_name(DOCUMENT_PART)
+        .map_err(|_| "not a DOCX file: no document part".to_string())?;
+    if part.size() > MAX_DOCUMENT_BYTES {
+        return Err("document is too large".to_string());
+    }
+    let mut xml = String::new();
+    part.take(MAX_DOCUMENT_BYTES)
+        .read_to_string(&mut xml)
+        .map_err(|e| e.to_string())?;
+    document_text(&xml).map_err(|e| e.to_string())
+}
+
+/// Text runs of `word/document.xml`: paragraphs end a line, tabs and breaks
+/// are kept, everything else (formatting, fields) is skipped.
+fn document_text(xml: &str) -> Result<String, quick_xml::Error> {
+    let mut reader = Reader::from_str(xml);
+    let mut text = String::new();
+    let mut in_text = false;
+    loop {
+        match reader.read_event()? {
+            Event::Start(e) if e.name().as_ref() == b"w:t" => in_text = true,
+            Event::End(e) => match e.name().as_ref() {
+                b"w:t" => in_text = false,
+                b"w:p" => text.push('\n'),
+                _ => {}
+            },
+            Event::Empty(e) => match e.name().as_ref() {
+                b"w:tab" => text.push('\t'),
+                b"w:br" | b"w:cr" => text.push('\n'),
+                _ => {}
+            },
+            Event::Text(t) if in_text => text.push_str(&t.unescape()?),
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+    Ok(text.trim_end().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_document_text() {
+        let xml = r#"<?xml version="1.0"?><w:document><w:body>
+            <w:p><w:r><w:rPr><w:b/></w:rPr><w:t>Hello</w:t></w:r><w:r><w:t xml:space="preserve"> world &amp; more</w:t></w:r></w:p>
+            <w:p><w:r><w:t>a</w:t><w:tab/><w:t>b</w:t><w:br/><w:t>c</w:t></w:r></w:p>
+            <w:p><w:r><w:instrText>PAGE</w:instrText></w:r></w:p>
+        </w:body></w:document>"#;
+        assert_eq!(document_text(xml).unwrap(), "Hello world & more\na\tb\nc");
+    }
+}
@@ -0,0 +1,136 @@
+//! Text from files dropped onto the panel: plain text in whatever encoding,
+//! PDFs and Word documents.
+
+mod docx;
+mod pdf;
+mod text;
+
+use serde::Serialize;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::Path;
+use tauri::{AppHandle, Manager, Runtime};
+use thiserror::Error;
+
+use crate::config::{ConfigError, ConfigManager};
+
+/// Largest PDF or DOCX file opened.
+const MAX_FILE_BYTES: u64 = 50 * 1024 * 1024;
+/// Text kept from a file; the rest is dropped and the result marked truncated.
+const MAX_TEXT_BYTES: usize = 1024 * 1024;
+
+#[derive(Debug, Error)]
+pub enum IngestError {
+    #[error("'{0}' is not a file")]
+    NotAFile(String),
+    #[error("file is too large ({0} MB); at most 50 MB")]
+    TooLarge(u64),
+    #[error("'{0}' is not a text, PDF or DOCX file")]
+    Binary(String),
+    #[error(
+        "PDF extractor '{0}' not found; install Poppler (pdftotext) or set its path in settings"
+    )]
+    PdfExtractorNotFound(String),
+    #[error("can't read the file: {0}")]
+    Extract(String),
+    #[error("no text found in the file")]
+    NoText,
+    #[error(transparent)]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FileFormat {
+    Text,
+    Pdf,
+    Docx,
+}
+
+impl FileFormat {
+    /// Guessed from the extension; anything unknown is tried as text.
+    fn of(path: &Path) -> Self {
+        match path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase)
+            .as_deref()
+        {
+            Some("pdf") => FileFormat::Pdf,
+            Some("docx") => FileFormat::Docx,
+            _ => FileFormat::Text,
+        }
+    }
+}
+
+/// Text read from a file, ready for the panel.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IngestedFile {
+    pub name: String,
+    pub format: FileFormat,
+    pub text: String,
+    /// Detected encoding of a text file
+    pub encoding: Option<&'static str>,
+    /// Only the first part of the text was kept
+    pub truncated: bool,
+}
+
+/// Read the text of the file at `path`.
+pub fn read_file<R: Runtime>(app: &AppHandle<R>, path: &Path) -> Result<IngestedFile, IngestError> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string());
+    let metadata = std::fs::metadata(path)?;
+    if !metadata.is_file() {
+        return Err(IngestError::NotAFile(name));
+    }
+    let format = FileFormat::of(path);
+    if format != FileFormat::Text && metadata.len() > MAX_FILE_BYTES {
+        return Err(IngestError::TooLarge(metadata.len() / (1024 * 1024)));
+    }
+
+    let (text, encoding, cut) = match format {
+        FileFormat::Text => {
+            let mut bytes = Vec::new();
+            File::open(path)?
+                .take(MAX_TEXT_BYTES as u64)
+                .read_to_end(&mut bytes)?;
+            let (text, encoding) = text::decode(&bytes).ok_or(IngestError::Binary(name.clone()))?;
+            (text, Some(encoding), metadata.len() > MAX_TEXT_BYTES as u64)
+        }
+        FileFormat::Pdf => {
+            let config = app.state::<ConfigManager>().effective_config()?;
+            (
+                pdf::extract(config.pdf_extractor.trim(), path)?,
+                None,
+                false,
+            )
+        }
+        FileFormat::Docx => {
+            let text = docx::extract(File::open(path)?).map_err(IngestError::Extract)?;
+            (text, None, false)
+        }
+    };
+    if text.trim().is_empty() {
+        return Err(IngestError::NoText);
+    }
+    // Legacy encodings grow when decoded, so the decoded text is capped too
+    let (text, truncated) = text::truncate(text, MAX_TEXT_BYTES);
+    log::info!(
+        "Read {} chars from a dropped {:?} file{}",
+        text.chars().count(),
+        format,
+        if cut || truncated { " (truncated)" } else { "" }
+    );
+    Ok(IngestedFile {
+        name,
+        format,
+        text,
+        encoding,
+        truncated: cut || truncated,
+    })
+}
@@ -0,0 +1,29 @@
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::Command;
+
+use super::IngestError;
+
+/// Text of a PDF, read with Poppler's `pdftotext` (or a compatible
+/// `extractor`), page breaks dropped.
+pub fn extract(extractor: &str, path: &Path) -> Result<String, IngestError> {
+    let output = Command::new(extractor)
+        .args(["-enc", "UTF-8", "-nopgbrk"])
+        .arg(path)
+        .arg("-")
+        .output()
+        .map_err(|e| match e.kind() {
+            ErrorKind::NotFound => IngestError::PdfExtractorNotFound(extractor.to_string()),
+            _ => IngestError::Io(e),
+        })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr
+            .lines()
+            .rev()
+            .find(|l| !l.trim().is_empty())
+            .unwrap_or("unknown error");
+        return Err(IngestError::Extract(reason.trim().to_string()));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
@@ -0,0 +1,73 @@
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_8};
+
+/// Bytes looked at to tell text from binary.
+const SNIFF_BYTES: usize = 8 * 1024;
+
+/// Decode a text file, honoring a byte order mark, then trying UTF-8, then
+/// guessing the legacy encoding (GBK, Shift_JIS, Windows-1252…). `None` for
+/// binary data. A sequence cut off by truncation decodes as U+FFFD.
+pub fn decode(bytes: &[u8]) -> Option<(String, &'static str)> {
+    if let Some((encoding, bom)) = Encoding::for_bom(bytes) {
+        let (text, _) = encoding.decode_without_bom_handling(&bytes[bom..]);
+        return Some((text.into_owned(), encoding.name()));
+    }
+    if bytes[..bytes.len().min(SNIFF_BYTES)].contains(&0) {
+        return None;
+    }
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return Some((text.to_string(), UTF_8.name()));
+    }
+    let mut detector = EncodingDetector::new();
+    detector.feed(bytes, true);
+    let encoding = detector.guess(None, true);
+    let (text, _) = encoding.decode_without_bom_handling(bytes);
+    Some((text.into_owned(), encoding.name()))
+}
+
+/// `text` cut to at most `max` bytes on a character boundary; whether it was cut.
+pub fn truncate(mut text: String, max: usize) -> (String, bool) {
+    if text.len() <= max {
+        return (text, false);
+    }
+    let mut end = max;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    text.truncate(end);
+    (text, true)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode() {
+        assert_eq!(
+            decode("héllo 你好".as_bytes()),
+            Some(("héllo 你好".to_string(), "UTF-8"))
+        );
+        assert_eq!(
+            decode(b"\xEF\xBB\xBFbom"),
+            Some(("bom".to_string(), "UTF-8"))
+        );
+        assert_eq!(
+            decode(b"\xFF\xFEh\x00i\x00"),
+            Some(("hi".to_string(), "UTF-16LE"))
+        );
+
+        let (gbk, _, _) = encoding_rs::GBK.encode("这是一段用来识别编码的中文文本，内容足够长。");
+        let (text, encoding) = decode(&gbk).unwrap();
+        assert_eq!(text, "这是一段用来识别编码的中文文本，内容足够长。");
+        assert_eq!(encoding, "GBK");
+
+        assert_eq!(decode(b"\x89PNG\r\n\x1a\n\x00\x00"), None);
+    }
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(truncate("abc".to_string(), 5), ("abc".to_string(), false));
+        assert_eq!(truncate("你好".to_string(), 4), ("你".to_string(), true));
+    }
+}
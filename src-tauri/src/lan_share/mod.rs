@@ -0,0 +1,240 @@
+//! One-time sharing of a clipboard item with another device on the local
+//! network: the item is served at a random URL behind a 6-digit code until
+//! it is fetched once, the code is guessed wrong too often, or it expires.
+
+mod page;
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::OsRng;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Local};
+use hyper::body::Incoming;
+use hyper::{Method, Request, Response, StatusCode};
+use serde::Serialize;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, UdpSocket};
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use thiserror::Error;
+
+use crate::clipboard::RecentClipboard;
+use crate::i18n::tr;
+use crate::localhost::{self, Body, Running};
+use page::Labels;
+
+/// How long a share stays up when nobody fetches it.
+const SHARE_TTL: Duration = Duration::from_secs(5 * 60);
+/// Wrong codes allowed before the share is taken down, so the code can't
+/// be guessed.
+const MAX_ATTEMPTS: u32 = 5;
+
+#[derive(Debug, Error)]
+pub enum ShareError {
+    #[error("nothing to share")]
+    Empty,
+    #[error("no clipboard history item {0}")]
+    NoSuchItem(usize),
+    #[error("not connected to a local network")]
+    NoNetwork,
+    #[error("can't start sharing: {0}")]
+    Serve(#[from] io::Error),
+}
+
+/// Where the other device fetches the item, returned to the panel.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareInfo {
+    pub url: String,
+    pub code: String,
+    /// RFC 3339
+    pub expires_at: String,
+}
+
+struct Share {
+    id: u64,
+    text: String,
+    path: String,
+    code: String,
+    /// Code attempts so far, reserved before each is checked
+    attempts: AtomicU32,
+    /// Set once it ends; open keep-alive connections may still ask
+    ended: AtomicBool,
+}
+
+/// The share being served, if any. Managed as state; starting a new share
+/// ends the previous one.
+#[derive(Default)]
+pub struct LanShare {
+    active: Mutex<Option<(Arc<Share>, Running)>>,
+    next_id: AtomicU64,
+}
+
+/// Address other devices reach this one at. Connecting a UDP socket sends
+/// nothing; it only picks the interface of the default route.
+fn lan_address() -> Option<Ipv4Addr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect((Ipv4Addr::new(192, 0, 2, 1), 80)).ok()?;
+    match socket.local_addr().ok()?.ip() {
+        IpAddr::V4(ip) if !ip.is_loopback() && !ip.is_unspecified() => Some(ip),
+        _ => None,
+    }
+}
+
+fn random_code() -> String {
+    format!("{:06}", OsRng.next_u32() % 1_000_000)
+}
+
+fn random_path() -> String {
+    let mut bytes = [0u8; 6];
+    OsRng.fill_bytes(&mut bytes);
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+fn labels() -> Labels<'static> {
+    Labels {
+        title: tr("share.title"),
+        prompt: tr("share.prompt"),
+        open: tr("share.open"),
+    }
+}
+
+/// Serve `text` to the local network and return where to fetch it.
+pub fn share<R: Runtime>(app: &AppHandle<R>, text: &str) -> Result<ShareInfo, ShareError> {
+    if text.trim().is_empty() {
+        return Err(ShareError::Empty);
+    }
+    let ip = lan_address().ok_or(ShareError::NoNetwork)?;
+    let state = app.state::<LanShare>();
+    let share = Arc::new(Share {
+        id: state.next_id.fetch_add(1, Ordering::Relaxed),
+        text: text.to_string(),
+        path: format!("/{}", random_path()),
+        code: random_code(),
+        attempts: AtomicU32::new(0),
+        ended: AtomicBool::new(false),
+    });
+
+    let handler_app = app.clone();
+    let handler_share = Arc::clone(&share);
+    let running = localhost::serve_on("LAN share", ip, 0, move |req| {
+        handle(handler_app.clone(), Arc::clone(&handler_share), req)
+    })?;
+    let port = running.port;
+    let info = ShareInfo {
+        url: format!("http://{}:{}{}", ip, port, share.path),
+        code: share.code.clone(),
+        expires_at: DateTime::<Local>::from(std::time::SystemTime::now() + SHARE_TTL).to_rfc3339(),
+    };
+    let previous = state
+        .active
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .replace((Arc::clone(&share), running));
+    if let Some((old, server)) = previous {
+        old.ended.store(true, Ordering::Relaxed);
+        server.stop();
+    }
+    log::info!("Sharing {} chars on {}:{}", text.chars().count(), ip, port);
+
+    let expiring = app.clone();
+    let id = share.id;
+    tauri::async_runtime::spawn(async move {
+        tokio::time::sleep(SHARE_TTL).await;
+        finish(&expiring, Some(id));
+    });
+    Ok(info)
+}
+
+/// Share item `index` of the clipboard history, 0 being the latest copy.
+pub fn share_recent<R: Runtime>(app: &AppHandle<R>, index: usize) -> Result<ShareInfo, ShareError> {
+    let text = app
+        .state::<RecentClipboard>()
+        .items()
+        .into_iter()
+        .nth(index)
+        .ok_or(ShareError::NoSuchItem(index))?;
+    share(app, &text)
+}
+
+/// End share `id`, or whichever is active for `None`, and tell the panel.
+pub fn finish<R: Runtime>(app: &AppHandle<R>, id: Option<u64>) {
+    let state = app.state::<LanShare>();
+    let mut active = state.active.lock().unwrap_or_else(|e| e.into_inner());
+    let Some((current, _)) = active.as_ref() else {
+        return;
+    };
+    if id.is_some_and(|id| id != current.id) {
+        return;
+    }
+    if let Some((share, running)) = active.take() {
+        share.ended.store(true, Ordering::Relaxed);
+        running.stop();
+        log::info!("LAN share ended");
+        if let Err(e) = app.emit("share:ended", ()) {
+            log::warn!("Failed to emit share:ended event: {}", e);
+        }
+    }
+}
+
+async fn handle<R: Runtime>(
+    app: AppHandle<R>,
+    share: Arc<Share>,
+    req: Request<Incoming>,
+) -> Response<Body> {
+    if req.uri().path() != share.path {
+        return localhost::status(StatusCode::NOT_FOUND);
+    }
+    let labels = labels();
+    if share.ended.load(Ordering::Relaxed) {
+        return localhost::html(
+            StatusCode::GONE,
+            page::message_page(&labels, tr("share.ended")),
+        );
+    }
+    match *req.method() {
+        Method::GET => localhost::html(StatusCode::OK, page::code_form(&labels, None)),
+        Method::POST => {
+            let body = match localhost::read_body(req).await {
+                Ok(body) => body,
+                Err(response) => return response,
+            };
+            // Reserve the attempt first, so parallel requests can't all
+            // guess before the count reaches the limit
+            let attempt = share.attempts.fetch_add(1, Ordering::SeqCst) + 1;
+            if attempt > MAX_ATTEMPTS || share.ended.load(Ordering::SeqCst) {
+                return localhost::html(
+                    StatusCode::GONE,
+                    page::message_page(&labels, tr("share.ended")),
+                );
+            }
+            let given = page::code_from_form(&body).unwrap_or_default();
+            if localhost::constant_time_eq(given.as_bytes(), share.code.as_bytes()) {
+                // One-time: only the first request with the code gets the item
+                if share.ended.swap(true, Ordering::Relaxed) {
+                    return localhost::html(
+                        StatusCode::GONE,
+                        page::message_page(&labels, tr("share.ended")),
+                    );
+                }
+                finish(&app, Some(share.id));
+                return localhost::html(StatusCode::OK, page::text_page(&labels, &share.text));
+            }
+            log::warn!("Wrong LAN share code ({} of {})", attempt, MAX_ATTEMPTS);
+            if attempt >= MAX_ATTEMPTS {
+                finish(&app, Some(share.id));
+                return localhost::html(
+                    StatusCode::GONE,
+                    page::message_page(&labels, tr("share.ended")),
+                );
+            }
+            localhost::html(
+                StatusCode::FORBIDDEN,
+                page::code_form(&labels, Some(tr("share.wrong_code"))),
+            )
+        }
+        _ => localhost::status(StatusCode::METHOD_NOT_ALLOWED),
+    }
+}
@@ -0,0 +1,96 @@
+//! Pages the other device sees; kept free of app state so they can be tested.
+
+/// Labels of the pages, in the app's language.
+pub struct Labels<'a> {
+    pub title: &'a str,
+    pub prompt: &'a str,
+    pub open: &'a str,
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html><html><head><meta charset=\"utf-8\">\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\
+         <meta name=\"referrer\" content=\"no-referrer\"><title>{}</title>\
+         <style>body{{font-family:system-ui,sans-serif;max-width:40em;margin:2em auto;padding:0 1em}}\
+         input,textarea,button{{font-size:1.1em}}textarea{{width:100%;height:60vh}}</style>\
+         </head><body><h1>{}</h1>{}</body></html>",
+        escape(title),
+        escape(title),
+        body
+    )
+}
+
+/// The form asking for the code, with `message` above it after a failed try.
+pub fn code_form(labels: &Labels, message: Option<&str>) -> String {
+    let message = message
+        .map(|m| format!("<p><strong>{}</strong></p>", escape(m)))
+        .unwrap_or_default();
+    page(
+        labels.title,
+        &format!(
+            "{}<form method=\"post\"><p>{}</p>\
+             <input name=\"code\" inputmode=\"numeric\" pattern=\"[0-9]{{6}}\" maxlength=\"6\" \
+             autocomplete=\"one-time-code\" autofocus required> <button>{}</button></form>",
+            message,
+            escape(labels.prompt),
+            escape(labels.open)
+        ),
+    )
+}
+
+/// The shared text, selectable for copying.
+pub fn text_page(labels: &Labels, text: &str) -> String {
+    page(
+        labels.title,
+        &format!(
+            "<textarea readonly onfocus=\"this.select()\">{}</textarea>",
+            escape(text)
+        ),
+    )
+}
+
+/// A page with only `message`, e.g. once the share is over.
+pub fn message_page(labels: &Labels, message: &str) -> String {
+    page(labels.title, &format!("<p>{}</p>", escape(message)))
+}
+
+/// The `code` field of a submitted form.
+pub fn code_from_form(body: &str) -> Option<&str> {
+    body.split('&')
+        .find_map(|pair| pair.strip_prefix("code="))
+        .map(str::trim)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LABELS: Labels = Labels {
+        title: "Share",
+        prompt: "Code",
+        open: "Open",
+    };
+
+    #[test]
+    fn test_text_page_escapes() {
+        let html = text_page(&LABELS, "</textarea><script>alert(1)</script> & more");
+        assert!(html.contains("&lt;/textarea&gt;&lt;script&gt;alert(1)&lt;/script&gt; &amp; more"));
+        assert!(!html.contains("<script>"));
+        assert!(code_form(&LABELS, Some("<b>")).contains("&lt;b&gt;"));
+    }
+
+    #[test]
+    fn test_code_from_form() {
+        assert_eq!(code_from_form("code=123456"), Some("123456"));
+        assert_eq!(code_from_form("x=1&code=042000"), Some("042000"));
+        assert_eq!(code_from_form("x=1"), None);
+    }
+}
@@ -1,39 +1,84 @@
 use tauri::Manager;
 use std::sync::Arc;
 
+mod api;
 mod commands;
-mod privacy;
-mod ai;
 mod clipboard;
 mod config;
-mod regex;
+mod expansion;
+mod folder_sync;
 mod hotkey;
+mod ingest;
+mod lan_share;
+mod localhost;
+mod logging;
+mod mcp;
+mod native_messaging;
+mod notes;
+mod ocr;
+mod qr;
+mod result_window;
+mod scheduler;
+mod snippet;
+mod transcription;
+mod translation;
+mod tray;
+mod webhook;
+mod workflow;
 
+use flow_paste_core::{ai, calc, i18n, privacy, regex, text};
+
+use clipboard::{RecentClipboard, TransformHistory};
 use commands::AIState;
 use config::ConfigManager;
 use hotkey::HotkeyManager;
 
+pub use native_messaging::{is_host_invocation, run_host};
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    env_logger::init();
+    logging::init();
 
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .plugin(tauri_plugin_clipboard_manager::init())
         .manage(Arc::new(AIState::default()))
+        .manage(TransformHistory::new())
+        .manage(RecentClipboard::new())
+        .manage(mcp::McpServer::default())
+        .manage(api::ApiServer::default())
+        .manage(native_messaging::NativeBridge::default())
+        .manage(lan_share::LanShare::default())
+        .manage(expansion::TextExpander::default())
+        .manage(result_window::ResultWindow::default())
         .setup(|app| {
             log::info!("FlowPaste starting...");
 
+            match app.path().app_data_dir() {
+                Ok(dir) => logging::set_log_dir(dir.join("logs")),
+                Err(e) => log::warn!("No app data dir, file logging unavailable: {}", e),
+            }
+
             // Initialize Config Manager
             let config_manager = ConfigManager::init(app.handle())
                 .expect("Failed to initialize config manager");
+            if let Ok(config) = config_manager.get_config() {
+                commands::apply_runtime_settings(app.handle(), &config);
+            }
             app.manage(config_manager);
+            commands::restore_rates(app.handle());
 
             // Initialize Hotkey Manager
             let hotkey_manager = HotkeyManager::new();
             app.manage(hotkey_manager);
 
+            clipboard::spawn_watcher(app.handle().clone());
+            scheduler::spawn(app.handle().clone());
+            if let Err(e) = tray::init(app.handle()) {
+                log::error!("Failed to create tray icon: {}", e);
+            }
+
             let window = app.get_webview_window("main")
                 .expect("Main window not found - check tauri.conf.json");
 
@@ -48,26 +93,29 @@ pub fn run() {
                 window.open_devtools();
             }
 
-            // Register default hotkey from config
+            // Register the panel toggle and every stored binding
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                let config_mgr: tauri::State<ConfigManager> = app_handle.state();
-                let hotkey_mgr: tauri::State<HotkeyManager> = app_handle.state();
-
-                let hotkey_str = match config_mgr.get_config() {
-                    Ok(cfg) => cfg.hotkey,
-                    Err(_) => "Ctrl+Shift+V".to_string(),
-                };
-
-                if let Err(e) = hotkey_mgr.register_hotkey(&app_handle, &hotkey_str).await {
-                    log::error!("Failed to register hotkey '{}': {}", hotkey_str, e);
-                } else {
-                    log::info!("Global shortcut registered: {}", hotkey_str);
-                }
+                hotkey::restore_bindings(&app_handle).await;
             });
 
             Ok(())
         })
+        .on_window_event(|window, event| {
+            let tauri::WindowEvent::Focused(focused) = event else {
+                return;
+            };
+            // Managed in setup, which may run after the first window events
+            if let Some(manager) = window.try_state::<HotkeyManager>() {
+                if window.label() == "main" {
+                    manager.set_panel_focused(*focused);
+                }
+            }
+            // Clicking elsewhere dismisses an inline result
+            if window.label() == result_window::LABEL && !*focused {
+                result_window::dismiss_on_blur(window.app_handle());
+            }
+        })
         .invoke_handler(tauri::generate_handler![
             commands::greet,
             commands::scan_pii,
@@ -77,19 +125,120 @@ pub fn run() {
             commands::check_ollama_health,
             commands::send_ai_request,
             commands::cancel_ai_request,
+            commands::get_last_ai_result,
             commands::detect_content_intent,
+            commands::get_custom_intents,
+            commands::save_custom_intent,
+            commands::delete_custom_intent,
             commands::read_clipboard,
             commands::write_clipboard,
+            commands::transform_clipboard,
+            commands::record_transform,
+            commands::undo_transform,
+            commands::redo_transform,
+            commands::get_transform_history,
+            commands::clear_transform_history,
+            commands::get_recent_clipboard,
             commands::get_config,
             commands::set_config,
+            commands::get_setting,
+            commands::set_setting,
             commands::get_api_key,
             commands::set_api_key,
+            commands::get_http_api_token,
+            commands::regenerate_http_api_token,
+            commands::export_config,
+            commands::import_config,
+            commands::get_database_encryption,
+            commands::set_database_encryption,
+            commands::get_profiles,
+            commands::get_active_profile,
+            commands::save_profile,
+            commands::delete_profile,
+            commands::switch_profile,
+            commands::list_config_backups,
+            commands::restore_config_backup,
+            commands::get_recent_logs,
+            commands::get_usage_stats,
+            commands::get_dashboard_stats,
             commands::get_builtin_rules,
             commands::apply_rule,
             commands::apply_custom_rule,
+            commands::get_rule_callbacks,
+            commands::get_rules,
+            commands::save_custom_rule,
+            commands::delete_custom_rule,
+            commands::set_rule_order,
+            commands::get_rule_stats,
+            commands::record_chip_usage,
             commands::register_hotkey,
             commands::unregister_hotkey,
             commands::is_hotkey_registered,
+            commands::validate_hotkey,
+            commands::get_registered_hotkeys,
+            commands::normalize_hotkey,
+            commands::start_hotkey_capture,
+            commands::get_hotkey_actions,
+            commands::get_action_hotkeys,
+            commands::set_action_hotkey,
+            commands::get_hotkey_exclusions,
+            commands::set_hotkey_exclusions,
+            commands::get_hotkey_pass_through,
+            commands::set_hotkey_pass_through,
+            commands::get_rule_hotkeys,
+            commands::bind_rule_hotkey,
+            commands::unbind_rule_hotkey,
+            commands::get_headless_hotkeys,
+            commands::bind_headless_hotkey,
+            commands::unbind_headless_hotkey,
+            commands::get_text_stats,
+            commands::diff_texts,
+            commands::save_decoded_file,
+            commands::compute_hash,
+            commands::generate_to_clipboard,
+            commands::generate_commit_message,
+            commands::evaluate,
+            commands::sync_folder,
+            commands::resolve_sync_conflict,
+            commands::show_inline_result,
+            commands::get_inline_result,
+            commands::accept_inline_result,
+            commands::dismiss_inline_result,
+            commands::close_result_window,
+            commands::make_markdown_links,
+            commands::make_citation,
+            commands::fetch_url_metadata,
+            commands::get_webhooks,
+            commands::save_webhook,
+            commands::delete_webhook,
+            commands::run_webhook,
+            commands::get_workflows,
+            commands::save_workflow,
+            commands::delete_workflow,
+            commands::run_workflow,
+            commands::capture_screen_text,
+            commands::copy_qr_code,
+            commands::save_qr_code,
+            commands::decode_qr_code,
+            commands::transcribe_audio,
+            commands::translate_text,
+            commands::save_to_notes,
+            commands::install_native_host,
+            commands::share_text,
+            commands::share_recent_clipboard,
+            commands::stop_lan_share,
+            commands::read_dropped_file,
+            commands::search_actions,
+            commands::get_snippets,
+            commands::save_snippet,
+            commands::delete_snippet,
+            commands::search_snippets,
+            commands::get_snippet_folders,
+            commands::get_snippet_tags,
+            commands::rename_snippet_folder,
+            commands::get_snippet_fields,
+            commands::render_snippet,
+            commands::insert_snippet,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -0,0 +1,164 @@
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::{Bytes, Incoming};
+use hyper::header::{HeaderValue, CONTENT_TYPE};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde::Serialize;
+use std::convert::Infallible;
+use std::future::Future;
+use std::net::{Ipv4Addr, TcpListener as StdTcpListener};
+use std::sync::Arc;
+use tokio::net::TcpListener;
+use tokio::sync::oneshot;
+
+pub type Body = Full<Bytes>;
+
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// A server accepting connections on a port until stopped or dropped.
+pub struct Running {
+    pub port: u16,
+    shutdown: oneshot::Sender<()>,
+}
+
+impl Running {
+    pub fn stop(self) {
+        let _ = self.shutdown.send(());
+    }
+}
+
+/// Serve HTTP/1.1 on `127.0.0.1:port`, passing every request to `handler`.
+/// Binding happens before returning so a taken port is reported to the
+/// caller; port 0 picks a free one. Connections may be upgraded (WebSocket).
+pub fn serve<F, Fut>(name: &'static str, port: u16, handler: F) -> std::io::Result<Running>
+where
+    F: Fn(Request<Incoming>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Response<Body>> + Send + 'static,
+{
+    serve_on(name, Ipv4Addr::LOCALHOST, port, handler)
+}
+
+/// `serve` on another address, for the few servers meant to be reached
+/// from other devices.
+pub fn serve_on<F, Fut>(name: &'static str, ip: Ipv4Addr, port: u16, handler: F) -> std::io::Result<Running>
+where
+    F: Fn(Request<Incoming>) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Response<Body>> + Send + 'static,
+{
+    let listener = StdTcpListener::bind((ip, port))?;
+    let port = listener.local_addr()?.port();
+    listener.set_nonblocking(true)?;
+    let (shutdown, mut stopped) = oneshot::channel::<()>();
+    let handler = Arc::new(handler);
+
+    tauri::async_runtime::spawn(async move {
+        let listener = match TcpListener::from_std(listener) {
+            Ok(listener) => listener,
+            Err(e) => {
+                log::error!("{} server failed to listen: {}", name, e);
+                return;
+            }
+        };
+        loop {
+            let stream = tokio::select! {
+                _ = &mut stopped => break,
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        log::warn!("{} server failed to accept a connection: {}", name, e);
+                        continue;
+                    }
+                },
+            };
+            let handler = Arc::clone(&handler);
+            tauri::async_runtime::spawn(async move {
+                let service = service_fn(move |req| {
+                    let response = handler(req);
+                    async move { Ok::<_, Infallible>(response.await) }
+                });
+                let connection = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .with_upgrades();
+                if let Err(e) = connection.await {
+                    log::debug!("{} connection closed: {}", name, e);
+                }
+            });
+        }
+    });
+    Ok(Running { port, shutdown })
+}
+
+/// The request body as text, or the response to send instead.
+pub async fn read_body(req: Request<Incoming>) -> Result<String, Response<Body>> {
+    match Limited::new(req.into_body(), MAX_BODY_BYTES).collect().await {
+        Ok(body) => Ok(String::from_utf8_lossy(&body.to_bytes()).into_owned()),
+        Err(_) => Err(status(StatusCode::PAYLOAD_TOO_LARGE)),
+    }
+}
+
+pub fn status(code: StatusCode) -> Response<Body> {
+    let mut response = Response::new(Full::default());
+    *response.status_mut() = code;
+    response
+}
+
+pub fn json(code: StatusCode, value: &impl Serialize) -> Response<Body> {
+    let body = match serde_json::to_vec(value) {
+        Ok(body) => body,
+        Err(e) => {
+            log::error!("Failed to serialize response: {}", e);
+            return status(StatusCode::INTERNAL_SERVER_ERROR);
+        }
+    };
+    let mut response = Response::new(Full::new(Bytes::from(body)));
+    *response.status_mut() = code;
+    response.headers_mut().insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    response
+}
+
+pub fn html(code: StatusCode, page: String) -> Response<Body> {
+    let mut response = Response::new(Full::new(Bytes::from(page)));
+    *response.status_mut() = code;
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static("text/html; charset=utf-8"));
+    response
+}
+
+/// Compare a given token without leaking how much of it matched.
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Requests without an `Origin` come from local tools, not browsers.
+pub fn origin_allowed(origin: Option<&str>) -> bool {
+    let Some(origin) = origin else {
+        return true;
+    };
+    let Some(authority) = origin.strip_prefix("http://").or_else(|| origin.strip_prefix("https://")) else {
+        return false;
+    };
+    let host = match authority.rsplit_once(':') {
+        Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host,
+        _ => authority,
+    };
+    matches!(host, "localhost" | "127.0.0.1" | "[::1]")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_origin_allowed() {
+        assert!(origin_allowed(None));
+        assert!(origin_allowed(Some("http://localhost:5173")));
+        assert!(origin_allowed(Some("http://127.0.0.1")));
+        assert!(origin_allowed(Some("https://[::1]:8080")));
+        assert!(!origin_allowed(Some("http://localhost.evil.com")));
+        assert!(!origin_allowed(Some("https://example.com")));
+        assert!(!origin_allowed(Some("null")));
+    }
+}
@@ -0,0 +1,273 @@
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use once_cell::sync::OnceCell;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// Level names accepted in `AppConfig::log_level`.
+pub const LOG_LEVELS: &[&str] = &["error", "warn", "info", "debug", "trace"];
+
+const FILE_NAME: &str = "flowpaste.log";
+/// The active file is rolled over once it grows past this.
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+/// Rolled files kept next to the active one (`flowpaste.log.1` ...).
+const ROLLED_FILES: usize = 2;
+/// Records kept in memory for `get_recent_logs`.
+const BUFFER_CAPACITY: usize = 1000;
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    /// RFC 3339, local time
+    pub timestamp: String,
+    pub level: String,
+    pub target: String,
+    pub message: String,
+}
+
+/// Most recent records, oldest first.
+struct LogBuffer {
+    entries: VecDeque<(Level, LogEntry)>,
+    capacity: usize,
+}
+
+impl LogBuffer {
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::with_capacity(capacity),
+            capacity,
+        }
+    }
+
+    fn push(&mut self, level: Level, entry: LogEntry) {
+        if self.entries.len() == self.capacity {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((level, entry));
+    }
+
+    /// The last `lines` entries at `min_level` or more severe.
+    fn recent(&self, lines: usize, min_level: Level) -> Vec<LogEntry> {
+        let mut out: Vec<LogEntry> = self
+            .entries
+            .iter()
+            .rev()
+            .filter(|(level, _)| *level <= min_level)
+            .take(lines)
+            .map(|(_, entry)| entry.clone())
+            .collect();
+        out.reverse();
+        out
+    }
+}
+
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+}
+
+impl LogFile {
+    fn open(dir: PathBuf) -> std::io::Result<Self> {
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(FILE_NAME);
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        let size = file.metadata()?.len();
+        Ok(Self { path, file, size })
+    }
+
+    fn write_line(&mut self, line: &str) -> std::io::Result<()> {
+        if self.size + line.len() as u64 > MAX_FILE_BYTES {
+            self.roll()?;
+        }
+        self.file.write_all(line.as_bytes())?;
+        self.size += line.len() as u64;
+        Ok(())
+    }
+
+    /// `flowpaste.log` -> `.1` -> `.2`; the oldest is dropped.
+    fn roll(&mut self) -> std::io::Result<()> {
+        let rolled = |n: usize| self.path.with_extension(format!("log.{}", n));
+        for n in (1..ROLLED_FILES).rev() {
+            if rolled(n).exists() {
+                fs::rename(rolled(n), rolled(n + 1))?;
+            }
+        }
+        fs::rename(&self.path, rolled(1))?;
+        self.file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+/// Passes records to env_logger (stderr, `RUST_LOG`) and also keeps them in
+/// memory and, when enabled, in a rolling file.
+struct AppLogger {
+    stderr: env_logger::Logger,
+    level: Mutex<LevelFilter>,
+    buffer: Mutex<LogBuffer>,
+    dir: Mutex<Option<PathBuf>>,
+    file: Mutex<Option<LogFile>>,
+}
+
+impl AppLogger {
+    fn level(&self) -> LevelFilter {
+        self.level.lock().map(|l| *l).unwrap_or(LevelFilter::Info)
+    }
+
+    /// Our own crate logs at the configured level; dependencies only at warn
+    /// and above, so HTTP and windowing internals don't flood the file.
+    fn captures(&self, metadata: &Metadata) -> bool {
+        let level = if metadata.target().starts_with("flow_paste") {
+            self.level()
+        } else {
+            self.level().min(LevelFilter::Warn)
+        };
+        metadata.level() <= level
+    }
+}
+
+impl Log for AppLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.stderr.enabled(metadata) || self.captures(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.stderr.matches(record) {
+            self.stderr.log(record);
+        }
+        if !self.captures(record.metadata()) {
+            return;
+        }
+
+        let entry = LogEntry {
+            timestamp: chrono::Local::now().to_rfc3339(),
+            level: record.level().to_string(),
+            target: record.target().to_string(),
+            message: record.args().to_string(),
+        };
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(log_file) = file.as_mut() {
+                let line = format!("{} {:5} {}: {}\n", entry.timestamp, entry.level, entry.target, entry.message);
+                // Logging from here would recurse
+                if let Err(e) = log_file.write_line(&line) {
+                    eprintln!("Failed to write log file, disabling it: {}", e);
+                    *file = None;
+                }
+            }
+        }
+        if let Ok(mut buffer) = self.buffer.lock() {
+            buffer.push(record.level(), entry);
+        }
+    }
+
+    fn flush(&self) {
+        self.stderr.flush();
+        if let Ok(mut file) = self.file.lock() {
+            if let Some(log_file) = file.as_mut() {
+                let _ = log_file.file.flush();
+            }
+        }
+    }
+}
+
+static LOGGER: OnceCell<AppLogger> = OnceCell::new();
+
+/// Install the logger. Until `configure` runs, records are kept at info level
+/// in memory only.
+pub fn init() {
+    let logger = LOGGER.get_or_init(|| AppLogger {
+        stderr: env_logger::Builder::from_default_env().build(),
+        level: Mutex::new(LevelFilter::Info),
+        buffer: Mutex::new(LogBuffer::new(BUFFER_CAPACITY)),
+        dir: Mutex::new(None),
+        file: Mutex::new(None),
+    });
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(logger.stderr.filter().max(logger.level()));
+    }
+}
+
+/// Where the log file goes once file logging is on.
+pub fn set_log_dir(dir: PathBuf) {
+    if let Some(logger) = LOGGER.get() {
+        if let Ok(mut current) = logger.dir.lock() {
+            *current = Some(dir);
+        }
+    }
+}
+
+/// Apply the level and file settings from config. Unknown level names
+/// keep the current level.
+pub fn configure(level: &str, to_file: bool) {
+    let Some(logger) = LOGGER.get() else {
+        return;
+    };
+    if let Ok(filter) = level.parse::<LevelFilter>() {
+        if let Ok(mut current) = logger.level.lock() {
+            *current = filter;
+        }
+        log::set_max_level(logger.stderr.filter().max(filter));
+    }
+
+    let dir = logger.dir.lock().ok().and_then(|d| d.clone());
+    if let Ok(mut file) = logger.file.lock() {
+        match (to_file, dir, file.is_some()) {
+            (true, Some(dir), false) => match LogFile::open(dir) {
+                Ok(log_file) => *file = Some(log_file),
+                Err(e) => eprintln!("Failed to open log file: {}", e),
+            },
+            (false, _, true) => *file = None,
+            _ => {}
+        }
+    }
+}
+
+/// The last `lines` records at `min_level` or more severe, oldest first.
+pub fn recent(lines: usize, min_level: Level) -> Vec<LogEntry> {
+    LOGGER
+        .get()
+        .and_then(|logger| logger.buffer.lock().ok().map(|b| b.recent(lines, min_level)))
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(message: &str) -> LogEntry {
+        LogEntry {
+            timestamp: String::new(),
+            level: String::new(),
+            target: String::new(),
+            message: message.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_buffer_drops_oldest() {
+        let mut buffer = LogBuffer::new(2);
+        buffer.push(Level::Info, entry("a"));
+        buffer.push(Level::Info, entry("b"));
+        buffer.push(Level::Info, entry("c"));
+        let messages: Vec<_> = buffer.recent(10, Level::Trace).into_iter().map(|e| e.message).collect();
+        assert_eq!(messages, ["b", "c"]);
+    }
+
+    #[test]
+    fn test_recent_filters_by_level() {
+        let mut buffer = LogBuffer::new(10);
+        buffer.push(Level::Error, entry("boom"));
+        buffer.push(Level::Debug, entry("noise"));
+        buffer.push(Level::Warn, entry("careful"));
+        buffer.push(Level::Info, entry("hello"));
+
+        let messages: Vec<_> = buffer.recent(10, Level::Warn).into_iter().map(|e| e.message).collect();
+        assert_eq!(messages, ["boom", "careful"]);
+        let messages: Vec<_> = buffer.recent(1, Level::Info).into_iter().map(|e| e.message).collect();
+        assert_eq!(messages, ["hello"]);
+    }
+}
@@ -1,5 +1,10 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 fn main() {
+    // Started by a browser for the companion extension: relay messages only
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if flow_paste_lib::is_host_invocation(&args) {
+        std::process::exit(flow_paste_lib::run_host());
+    }
     flow_paste_lib::run()
 }
@@ -0,0 +1,75 @@
+mod protocol;
+mod server;
+
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::clipboard::{self, TransformSummary};
+use crate::config::{AppConfig, ConfigManager};
+use crate::localhost::Running;
+use crate::regex::Rule;
+use protocol::Host;
+
+/// The optional local MCP server. Managed as state; `configure` starts,
+/// restarts or stops it to match the settings.
+#[derive(Default)]
+pub struct McpServer {
+    running: Mutex<Option<Running>>,
+}
+
+/// Apply `mcp_server`/`mcp_port`. A restart forgets the placeholders handed
+/// out so far.
+pub fn configure<R: Runtime>(app: &AppHandle<R>, config: &AppConfig) {
+    let state = app.state::<McpServer>();
+    let mut running = state.running.lock().unwrap_or_else(|e| e.into_inner());
+    let wanted = config.mcp_server.then_some(config.mcp_port);
+    if running.as_ref().map(|r| r.port) == wanted {
+        return;
+    }
+
+    if let Some(old) = running.take() {
+        log::info!("MCP server on port {} stopped", old.port);
+        old.stop();
+    }
+    if let Some(port) = wanted {
+        match server::start(app.clone(), port) {
+            Ok(server) => {
+                log::info!("MCP server listening on http://127.0.0.1:{}/mcp", port);
+                *running = Some(server);
+            }
+            Err(e) => log::error!("Failed to start MCP server on port {}: {}", port, e),
+        }
+    }
+}
+
+/// The app side of the tools: the system clipboard and the user's rules.
+struct AppHost<R: Runtime> {
+    app: AppHandle<R>,
+}
+
+impl<R: Runtime> Host for AppHost<R> {
+    fn read_clipboard(&self) -> Result<String, String> {
+        clipboard::read_clipboard(&self.app)
+            .map_err(|e| e.to_string())?
+            .text
+            .ok_or_else(|| "clipboard has no text".to_string())
+    }
+
+    fn write_clipboard(&self, text: &str) -> Result<(), String> {
+        clipboard::write_clipboard(&self.app, text).map_err(|e| e.to_string())
+    }
+
+    fn transform_clipboard(&self, rule_ids: &[String]) -> Result<TransformSummary, String> {
+        clipboard::transform_clipboard(&self.app, rule_ids).map_err(|e| e.to_string())
+    }
+
+    fn custom_rules(&self) -> Vec<Rule> {
+        self.app
+            .state::<ConfigManager>()
+            .get_custom_rules()
+            .unwrap_or_else(|e| {
+                log::warn!("Failed to load custom rules for MCP: {}", e);
+                Vec::new()
+            })
+    }
+}
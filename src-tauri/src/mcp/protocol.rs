@@ -0,0 +1,324 @@
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::sync::Mutex;
+
+use crate::clipboard::TransformSummary;
+use crate::privacy::{self, MaskMapping};
+use crate::regex::{self, Rule};
+
+const PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+
+/// What the tools need from the app: the clipboard and the rule set.
+pub trait Host {
+    fn read_clipboard(&self) -> Result<String, String>;
+    fn write_clipboard(&self, text: &str) -> Result<(), String>;
+    /// Run rules on the clipboard in place, like the panel does.
+    fn transform_clipboard(&self, rule_ids: &[String]) -> Result<TransformSummary, String>;
+    fn custom_rules(&self) -> Vec<Rule>;
+}
+
+/// State of one running server. Every placeholder handed to a client stays
+/// restorable until the server stops.
+#[derive(Default)]
+pub struct Session {
+    mapping: Mutex<MaskMapping>,
+}
+
+impl Session {
+    fn mask(&self, text: &str) -> String {
+        let mut mapping = self.mapping.lock().unwrap_or_else(|e| e.into_inner());
+        privacy::mask_pii_with(text, &mut mapping)
+    }
+
+    fn restore(&self, text: &str) -> String {
+        let mapping = self.mapping.lock().unwrap_or_else(|e| e.into_inner());
+        privacy::restore_pii(text, &mapping)
+    }
+}
+
+/// Handle one JSON-RPC message or batch. Notifications get no response.
+pub fn handle(host: &impl Host, session: &Session, body: &str) -> Option<Value> {
+    let message: Value = match serde_json::from_str(body) {
+        Ok(message) => message,
+        Err(e) => return Some(error(Value::Null, PARSE_ERROR, &e.to_string())),
+    };
+    match message {
+        Value::Array(batch) => {
+            let responses: Vec<Value> = batch
+                .into_iter()
+                .filter_map(|m| handle_message(host, session, m))
+                .collect();
+            (!responses.is_empty()).then_some(Value::Array(responses))
+        }
+        message => handle_message(host, session, message),
+    }
+}
+
+#[derive(Deserialize)]
+struct Request {
+    #[serde(default)]
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+fn handle_message(host: &impl Host, session: &Session, message: Value) -> Option<Value> {
+    let request: Request = match serde_json::from_value(message) {
+        Ok(request) => request,
+        Err(e) => return Some(error(Value::Null, INVALID_REQUEST, &e.to_string())),
+    };
+    // Notifications (`notifications/initialized`, cancellations) need no reply
+    let id = request.id?;
+
+    let result = match request.method.as_str() {
+        "initialize" => Ok(initialize(&request.params)),
+        "ping" => Ok(json!({})),
+        "tools/list" => Ok(json!({ "tools": tools(host) })),
+        "tools/call" => call_tool(host, session, &request.params),
+        other => Err((METHOD_NOT_FOUND, format!("method not found: {}", other))),
+    };
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => error(id, code, &message),
+    })
+}
+
+fn error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = requested
+        .filter(|v| PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": { "listChanged": false } },
+        "serverInfo": { "name": "flow-paste", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Personal data in clipboard text is replaced with {{FP_...}} placeholders. \
+            Keep them as they are: write_clipboard and apply_rule put the original values back.",
+    })
+}
+
+fn tools(host: &impl Host) -> Value {
+    let rules: Vec<String> = regex::get_builtin_rules()
+        .into_iter()
+        .chain(host.custom_rules())
+        .map(|r| format!("{} ({})", r.id, r.name))
+        .collect();
+    json!([
+        {
+            "name": "read_clipboard",
+            "description": "Read the text on the clipboard, with personal data masked.",
+            "inputSchema": { "type": "object", "properties": {} },
+        },
+        {
+            "name": "write_clipboard",
+            "description": "Put text on the clipboard. Placeholders from the other tools are restored first.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "text": { "type": "string" } },
+                "required": ["text"],
+            },
+        },
+        {
+            "name": "apply_rule",
+            "description": format!(
+                "Run FlowPaste rules in order on `text` and return the result, or on the clipboard \
+                 in place when `text` is omitted. Available rules: {}",
+                rules.join(", ")
+            ),
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "rule_ids": { "type": "array", "items": { "type": "string" }, "minItems": 1 },
+                    "text": { "type": "string" },
+                },
+                "required": ["rule_ids"],
+            },
+        },
+        {
+            "name": "scan_pii",
+            "description": "Find personal data (phone numbers, emails, ID and bank cards, IPs, API keys) \
+                in `text` or the clipboard. Reports types and positions, never the values.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "text": { "type": "string" } },
+            },
+        },
+    ])
+}
+
+#[derive(Deserialize)]
+struct ToolCall {
+    name: String,
+    #[serde(default)]
+    arguments: ToolArguments,
+}
+
+#[derive(Default, Deserialize)]
+struct ToolArguments {
+    text: Option<String>,
+    #[serde(default)]
+    rule_ids: Vec<String>,
+}
+
+fn call_tool(host: &impl Host, session: &Session, params: &Value) -> Result<Value, (i64, String)> {
+    let call: ToolCall =
+        serde_json::from_value(params.clone()).map_err(|e| (INVALID_PARAMS, e.to_string()))?;
+    let args = call.arguments;
+
+    // Failures inside a tool are results the model should see, not protocol errors
+    let output = match call.name.as_str() {
+        "read_clipboard" => host.read_clipboard().map(|text| session.mask(&text)),
+        "write_clipboard" => {
+            let text = args.text.ok_or((INVALID_PARAMS, "missing `text`".to_string()))?;
+            let text = session.restore(&text);
+            host.write_clipboard(&text)
+                .map(|_| format!("Copied {} characters to the clipboard", text.chars().count()))
+        }
+        "apply_rule" => {
+            if args.rule_ids.is_empty() {
+                return Err((INVALID_PARAMS, "missing `rule_ids`".to_string()));
+            }
+            match args.text {
+                Some(text) => regex::apply_pipeline(&session.restore(&text), &args.rule_ids, &host.custom_rules())
+                    .map(|result| session.mask(&result))
+                    .map_err(|e| e.to_string()),
+                None => host
+                    .transform_clipboard(&args.rule_ids)
+                    .map(|summary| json!(summary).to_string()),
+            }
+        }
+        "scan_pii" => {
+            let text = match args.text {
+                Some(text) => Ok(session.restore(&text)),
+                None => host.read_clipboard(),
+            };
+            text.map(|text| scan_summary(&text).to_string())
+        }
+        other => return Err((INVALID_PARAMS, format!("unknown tool: {}", other))),
+    };
+
+    Ok(match output {
+        Ok(text) => json!({ "content": [{ "type": "text", "text": text }], "isError": false }),
+        Err(message) => json!({ "content": [{ "type": "text", "text": message }], "isError": true }),
+    })
+}
+
+/// Scan result without the matched values, which would defeat the masking.
+fn scan_summary(text: &str) -> Value {
+    let result = privacy::scan_pii(text);
+    let items: Vec<Value> = result
+        .items
+        .iter()
+        .map(|i| json!({ "type": i.pii_type, "start": i.start, "end": i.end }))
+        .collect();
+    json!({ "hasPii": result.has_pii, "items": items })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    struct FakeHost {
+        clipboard: RefCell<String>,
+    }
+
+    impl Host for FakeHost {
+        fn read_clipboard(&self) -> Result<String, String> {
+            Ok(self.clipboard.borrow().clone())
+        }
+
+        fn write_clipboard(&self, text: &str) -> Result<(), String> {
+            *self.clipboard.borrow_mut() = text.to_string();
+            Ok(())
+        }
+
+        fn transform_clipboard(&self, rule_ids: &[String]) -> Result<TransformSummary, String> {
+            let text = self.clipboard.borrow().clone();
+            let result = regex::apply_pipeline(&text, rule_ids, &[]).map_err(|e| e.to_string())?;
+            *self.clipboard.borrow_mut() = result.clone();
+            Ok(TransformSummary {
+                rule_ids: rule_ids.to_vec(),
+                input_chars: text.chars().count(),
+                output_chars: result.chars().count(),
+                changed: result != text,
+            })
+        }
+
+        fn custom_rules(&self) -> Vec<Rule> {
+            Vec::new()
+        }
+    }
+
+    fn call(host: &FakeHost, session: &Session, name: &str, arguments: Value) -> Value {
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 7,
+            "method": "tools/call",
+            "params": { "name": name, "arguments": arguments },
+        });
+        handle(host, session, &request.to_string()).unwrap()["result"].clone()
+    }
+
+    fn text(result: &Value) -> &str {
+        result["content"][0]["text"].as_str().unwrap()
+    }
+
+    #[test]
+    fn test_initialize_and_list() {
+        let host = FakeHost { clipboard: RefCell::new(String::new()) };
+        let session = Session::default();
+        let init = r#"{"jsonrpc":"2.0","id":1,"method":"initialize","params":{"protocolVersion":"2024-11-05"}}"#;
+        let response = handle(&host, &session, init).unwrap();
+        assert_eq!(response["result"]["protocolVersion"], "2024-11-05");
+
+        let notification = r#"{"jsonrpc":"2.0","method":"notifications/initialized"}"#;
+        assert!(handle(&host, &session, notification).is_none());
+
+        let list = handle(&host, &session, r#"{"jsonrpc":"2.0","id":2,"method":"tools/list"}"#).unwrap();
+        let names: Vec<&str> = list["result"]["tools"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|t| t["name"].as_str().unwrap())
+            .collect();
+        assert_eq!(names, ["read_clipboard", "write_clipboard", "apply_rule", "scan_pii"]);
+
+        let unknown = handle(&host, &session, r#"{"jsonrpc":"2.0","id":3,"method":"resources/list"}"#).unwrap();
+        assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(handle(&host, &session, "{").unwrap()["error"]["code"], PARSE_ERROR);
+    }
+
+    #[test]
+    fn test_clipboard_round_trip_keeps_pii_local() {
+        let host = FakeHost { clipboard: RefCell::new("call 13800138000  ".to_string()) };
+        let session = Session::default();
+
+        let read = call(&host, &session, "read_clipboard", json!({}));
+        assert_eq!(text(&read), "call {{FP_PHONE_1}}  ");
+
+        let applied = call(&host, &session, "apply_rule", json!({ "rule_ids": ["trim_whitespace"], "text": text(&read) }));
+        assert_eq!(text(&applied), "call {{FP_PHONE_1}}");
+
+        call(&host, &session, "write_clipboard", json!({ "text": "Phone: {{FP_PHONE_1}}" }));
+        assert_eq!(*host.clipboard.borrow(), "Phone: 13800138000");
+
+        let scan = call(&host, &session, "scan_pii", json!({}));
+        assert!(text(&scan).contains("\"hasPii\":true"));
+        assert!(!text(&scan).contains("13800138000"));
+
+        let failed = call(&host, &session, "apply_rule", json!({ "rule_ids": ["no_such_rule"], "text": "x" }));
+        assert_eq!(failed["isError"], true);
+    }
+}
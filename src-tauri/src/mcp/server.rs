@@ -0,0 +1,49 @@
+use hyper::body::Incoming;
+use hyper::header::{HeaderValue, ALLOW, ORIGIN};
+use hyper::{Method, Request, Response, StatusCode};
+use std::sync::Arc;
+use tauri::{AppHandle, Runtime};
+
+use super::protocol::{self, Session};
+use super::AppHost;
+use crate::localhost::{self, Body, Running};
+
+const ENDPOINT: &str = "/mcp";
+
+/// Serve MCP's streamable HTTP transport on `127.0.0.1:port`.
+pub fn start<R: Runtime>(app: AppHandle<R>, port: u16) -> std::io::Result<Running> {
+    let host = Arc::new(AppHost { app });
+    let session = Arc::new(Session::default());
+    localhost::serve("MCP", port, move |req| handle(Arc::clone(&host), Arc::clone(&session), req))
+}
+
+async fn handle<R: Runtime>(host: Arc<AppHost<R>>, session: Arc<Session>, req: Request<Incoming>) -> Response<Body> {
+    if req.uri().path() != ENDPOINT {
+        return localhost::status(StatusCode::NOT_FOUND);
+    }
+    // Stops web pages from reaching the server through DNS rebinding
+    if !localhost::origin_allowed(req.headers().get(ORIGIN).and_then(|o| o.to_str().ok())) {
+        return localhost::status(StatusCode::FORBIDDEN);
+    }
+    // No server-initiated messages, so no GET event stream
+    if req.method() != Method::POST {
+        let mut response = localhost::status(StatusCode::METHOD_NOT_ALLOWED);
+        response.headers_mut().insert(ALLOW, HeaderValue::from_static("POST"));
+        return response;
+    }
+
+    let body = match localhost::read_body(req).await {
+        Ok(body) => body,
+        Err(response) => return response,
+    };
+    // Clipboard access blocks
+    let reply = tauri::async_runtime::spawn_blocking(move || protocol::handle(host.as_ref(), &session, &body)).await;
+    match reply {
+        Ok(Some(json)) => localhost::json(StatusCode::OK, &json),
+        Ok(None) => localhost::status(StatusCode::ACCEPTED),
+        Err(e) => {
+            log::error!("MCP request handler failed: {}", e);
+            localhost::status(StatusCode::INTERNAL_SERVER_ERROR)
+        }
+    }
+}
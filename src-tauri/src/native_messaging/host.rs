@@ -0,0 +1,92 @@
+use serde_json::{json, Value};
+use std::io;
+use std::time::Duration;
+use thiserror::Error;
+
+use super::protocol;
+use super::{endpoint_entry, Endpoint};
+
+/// AI requests through the bridge may take a while.
+const RELAY_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Error)]
+enum HostError {
+    #[error("FlowPaste isn't running or its browser extension bridge is off")]
+    NotRunning,
+    #[error("can't read the bridge address: {0}")]
+    Keyring(String),
+    #[error("FlowPaste didn't answer: {0}")]
+    Request(#[from] reqwest::Error),
+}
+
+/// Where the running app listens, as it published it in the keyring.
+fn endpoint() -> Result<Endpoint, HostError> {
+    let entry = endpoint_entry().map_err(|e| HostError::Keyring(e.to_string()))?;
+    match entry.get_password() {
+        Ok(stored) => serde_json::from_str(&stored).map_err(|e| HostError::Keyring(e.to_string())),
+        Err(keyring::Error::NoEntry) => Err(HostError::NotRunning),
+        Err(e) => Err(HostError::Keyring(e.to_string())),
+    }
+}
+
+/// Pass `message` to the app and return its reply. The endpoint is looked
+/// up every time since the app picks a new port when it restarts.
+async fn relay(client: &reqwest::Client, message: &Value) -> Result<Value, HostError> {
+    let endpoint = endpoint()?;
+    let response = client
+        .post(format!("http://127.0.0.1:{}/", endpoint.port))
+        .bearer_auth(&endpoint.token)
+        .json(message)
+        .timeout(RELAY_TIMEOUT)
+        .send()
+        .await
+        .map_err(|e| {
+            if e.is_connect() {
+                HostError::NotRunning
+            } else {
+                HostError::Request(e)
+            }
+        })?;
+    Ok(response.error_for_status()?.json().await?)
+}
+
+/// Serve the browser over stdin/stdout until it disconnects. Nothing but
+/// framed messages may go to stdout, so errors are reported in replies.
+pub fn run() -> i32 {
+    let runtime = match tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+    {
+        Ok(runtime) => runtime,
+        Err(e) => {
+            eprintln!("Failed to start native messaging host: {}", e);
+            return 1;
+        }
+    };
+    let client = reqwest::Client::new();
+    let mut stdin = io::stdin().lock();
+    let mut stdout = io::stdout().lock();
+
+    loop {
+        let message = match protocol::read_message(&mut stdin) {
+            Ok(Some(message)) => message,
+            Ok(None) => return 0,
+            Err(e) => {
+                eprintln!("Failed to read native message: {}", e);
+                return 1;
+            }
+        };
+        let reply = runtime.block_on(relay(&client, &message)).unwrap_or_else(|e| {
+            json!({ "id": message.get("id").cloned().unwrap_or(Value::Null), "ok": false, "error": e.to_string() })
+        });
+        let written = protocol::write_message(&mut stdout, &reply).or_else(|e| {
+            // Too large for the browser; say so instead of dropping the reply
+            let error = json!({ "id": reply.get("id").cloned().unwrap_or(Value::Null), "ok": false, "error": e.to_string() });
+            protocol::write_message(&mut stdout, &error)
+        });
+        if let Err(e) = written {
+            eprintln!("Failed to write native message: {}", e);
+            return 1;
+        }
+    }
+}
@@ -0,0 +1,143 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+#[cfg(target_os = "windows")]
+use tauri::Manager;
+use tauri::{AppHandle, Runtime};
+
+use super::protocol::{self, Browser, HOST_NAME};
+use super::NativeMessagingError;
+
+/// Folders a browser looks for host manifests in; Chromium is covered along
+/// with Chrome since extensions install into either.
+#[cfg(target_os = "linux")]
+fn manifest_dirs<R: Runtime>(
+    _app: &AppHandle<R>,
+    browser: Browser,
+) -> Result<Vec<PathBuf>, NativeMessagingError> {
+    let home = PathBuf::from(env::var_os("HOME").ok_or(NativeMessagingError::NoHome)?);
+    let config_home = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => home.join(".config"),
+    };
+    Ok(match browser {
+        Browser::Chrome => vec![
+            config_home.join("google-chrome/NativeMessagingHosts"),
+            config_home.join("chromium/NativeMessagingHosts"),
+        ],
+        Browser::Firefox => vec![home.join(".mozilla/native-messaging-hosts")],
+    })
+}
+
+#[cfg(target_os = "macos")]
+fn manifest_dirs<R: Runtime>(
+    _app: &AppHandle<R>,
+    browser: Browser,
+) -> Result<Vec<PathBuf>, NativeMessagingError> {
+    let support = PathBuf::from(env::var_os("HOME").ok_or(NativeMessagingError::NoHome)?)
+        .join("Library/Application Support");
+    Ok(match browser {
+        Browser::Chrome => vec![
+            support.join("Google/Chrome/NativeMessagingHosts"),
+            support.join("Chromium/NativeMessagingHosts"),
+        ],
+        Browser::Firefox => vec![support.join("Mozilla/NativeMessagingHosts")],
+    })
+}
+
+/// Windows browsers find the manifest through the registry, so it can live
+/// with the app's own files.
+#[cfg(target_os = "windows")]
+fn manifest_dirs<R: Runtime>(
+    app: &AppHandle<R>,
+    browser: Browser,
+) -> Result<Vec<PathBuf>, NativeMessagingError> {
+    let dir = app
+        .path()
+        .app_config_dir()
+        .map_err(|e| NativeMessagingError::Install(e.to_string()))?;
+    let name = match browser {
+        Browser::Chrome => "chrome",
+        Browser::Firefox => "firefox",
+    };
+    Ok(vec![dir.join("native-messaging").join(name)])
+}
+
+#[cfg(not(any(target_os = "macos", target_os = "linux", target_os = "windows")))]
+fn manifest_dirs<R: Runtime>(
+    _app: &AppHandle<R>,
+    _browser: Browser,
+) -> Result<Vec<PathBuf>, NativeMessagingError> {
+    Err(NativeMessagingError::Install(
+        "not supported on this platform".to_string(),
+    ))
+}
+
+#[cfg(target_os = "windows")]
+fn register(browser: Browser, manifest: &std::path::Path) -> Result<(), NativeMessagingError> {
+    use std::os::windows::process::CommandExt;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    let vendor = match browser {
+        Browser::Chrome => r"Google\Chrome",
+        Browser::Firefox => "Mozilla",
+    };
+    let key = format!(
+        r"HKCU\Software\{}\NativeMessagingHosts\{}",
+        vendor, HOST_NAME
+    );
+    let manifest = manifest.to_string_lossy();
+    let status = std::process::Command::new("reg")
+        .args(["add", &key, "/ve", "/t", "REG_SZ", "/d", &manifest, "/f"])
+        .creation_flags(CREATE_NO_WINDOW)
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(NativeMessagingError::Install(
+            "failed to write the registry key".to_string(),
+        ))
+    }
+}
+
+#[cfg(not(target_os = "windows"))]
+fn register(_browser: Browser, _manifest: &std::path::Path) -> Result<(), NativeMessagingError> {
+    Ok(())
+}
+
+/// Register this executable as the native messaging host of `browser` for
+/// the given extensions and return the first manifest written.
+pub fn install<R: Runtime>(
+    app: &AppHandle<R>,
+    browser: Browser,
+    extension_ids: &[&str],
+) -> Result<PathBuf, NativeMessagingError> {
+    if extension_ids.is_empty() {
+        return Err(NativeMessagingError::NoExtensionIds);
+    }
+    let exe = env::current_exe()?;
+    let manifest = serde_json::to_string_pretty(&protocol::manifest(
+        browser,
+        &exe.to_string_lossy(),
+        extension_ids,
+    ))
+    .map_err(|e| NativeMessagingError::Install(e.to_string()))?;
+
+    let mut written = Vec::new();
+    for dir in manifest_dirs(app, browser)? {
+        fs::create_dir_all(&dir)?;
+        let path = dir.join(format!("{}.json", HOST_NAME));
+        fs::write(&path, &manifest)?;
+        register(browser, &path)?;
+        written.push(path);
+    }
+    log::info!(
+        "Installed native messaging host for {:?} at {:?}",
+        browser,
+        written
+    );
+    written
+        .into_iter()
+        .next()
+        .ok_or_else(|| NativeMessagingError::Install("no manifest folder".to_string()))
+}
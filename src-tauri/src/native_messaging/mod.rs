@@ -0,0 +1,223 @@
+//! Lets a companion browser extension send selected text to FlowPaste and
+//! get results back without going through the clipboard. The browser starts
+//! this executable as a native messaging host (`host`), which relays each
+//! message to the running app over a private localhost endpoint.
+
+mod host;
+mod install;
+mod protocol;
+
+use hyper::body::Incoming;
+use hyper::header::AUTHORIZATION;
+use hyper::{Method, Request, Response, StatusCode};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, Runtime};
+use thiserror::Error;
+
+use crate::api;
+use crate::config::{AppConfig, SERVICE_NAME};
+use crate::hotkey;
+use crate::localhost::{self, Body, Running};
+
+pub use host::run as run_host;
+pub use install::install;
+pub use protocol::{is_host_invocation, Browser};
+
+/// Keyring entry where the app publishes its endpoint for the host process.
+const ENDPOINT_ACCOUNT: &str = "native_messaging";
+
+#[derive(Debug, Error)]
+pub enum NativeMessagingError {
+    #[error("unknown browser '{0}'; use chrome or firefox")]
+    UnknownBrowser(String),
+    #[error("add the browser extension id in settings first")]
+    NoExtensionIds,
+    #[error("HOME is not set")]
+    NoHome,
+    #[error("can't install the native messaging host: {0}")]
+    Install(String),
+    #[error("can't install the native messaging host: {0}")]
+    Io(#[from] io::Error),
+}
+
+/// Where the app listens for the host and the token the host must send.
+#[derive(Deserialize)]
+struct Endpoint {
+    port: u16,
+    token: String,
+}
+
+/// The app side of the bridge. Managed as state; `configure` starts or
+/// stops it to match the settings.
+#[derive(Default)]
+pub struct NativeBridge {
+    running: Mutex<Option<Running>>,
+}
+
+/// Apply `browser_extension`. A new port and token are picked on every
+/// start, so a stale host can't reach a later session.
+pub fn configure<R: Runtime>(app: &AppHandle<R>, config: &AppConfig) {
+    let state = app.state::<NativeBridge>();
+    let mut running = state.running.lock().unwrap_or_else(|e| e.into_inner());
+    if running.is_some() == config.browser_extension {
+        return;
+    }
+
+    if let Some(old) = running.take() {
+        old.stop();
+        if let Err(e) = endpoint_entry().and_then(|entry| entry.delete_credential()) {
+            if !matches!(e, keyring::Error::NoEntry) {
+                log::warn!("Failed to remove browser extension endpoint: {}", e);
+            }
+        }
+        log::info!("Browser extension bridge stopped");
+        return;
+    }
+
+    let token = api::new_token();
+    let handler_app = app.clone();
+    let handler_token = token.clone();
+    let server = match localhost::serve("browser extension bridge", 0, move |req| {
+        handle(handler_app.clone(), handler_token.clone(), req)
+    }) {
+        Ok(server) => server,
+        Err(e) => {
+            log::error!("Failed to start browser extension bridge: {}", e);
+            return;
+        }
+    };
+    let endpoint = json!({ "port": server.port, "token": token }).to_string();
+    match endpoint_entry().and_then(|entry| entry.set_password(&endpoint)) {
+        Ok(()) => {
+            log::info!("Browser extension bridge listening on port {}", server.port);
+            *running = Some(server);
+        }
+        Err(e) => {
+            log::error!("Failed to publish browser extension endpoint: {}", e);
+            server.stop();
+        }
+    }
+}
+
+/// Keyring entry shared with the host process, which can't know the app's
+/// data folder but can reach the same keyring.
+fn endpoint_entry() -> keyring::Result<keyring::Entry> {
+    keyring::Entry::new(SERVICE_NAME, ENDPOINT_ACCOUNT)
+}
+
+/// Register the host for `browser`, letting the comma-separated
+/// `extension_ids` connect.
+pub fn install_host<R: Runtime>(
+    app: &AppHandle<R>,
+    browser: &str,
+    extension_ids: &str,
+) -> Result<PathBuf, NativeMessagingError> {
+    let browser = Browser::from_name(browser)
+        .ok_or_else(|| NativeMessagingError::UnknownBrowser(browser.to_string()))?;
+    check_extension_ids(extension_ids).map_err(NativeMessagingError::Install)?;
+    let ids: Vec<&str> = extension_ids
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .collect();
+    install(app, browser, &ids)
+}
+
+/// Check the extension ids before they are saved.
+pub fn check_extension_ids(ids: &str) -> Result<(), String> {
+    match ids.split(',').map(str::trim).find(|id| {
+        !id.is_empty()
+            && !id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "@._-{}".contains(c))
+    }) {
+        Some(id) => Err(format!("'{}' is not an extension id", id)),
+        None => Ok(()),
+    }
+}
+
+/// A message from the extension: `{"id": 1, "action": "transform",
+/// "params": {"ruleIds": [...], "text": "..."}}`.
+#[derive(Deserialize)]
+struct BridgeMessage {
+    #[serde(default)]
+    id: Value,
+    action: String,
+    #[serde(default)]
+    params: Value,
+}
+
+async fn handle<R: Runtime>(
+    app: AppHandle<R>,
+    token: String,
+    req: Request<Incoming>,
+) -> Response<Body> {
+    let bearer = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "));
+    if !bearer.is_some_and(|given| localhost::constant_time_eq(given.as_bytes(), token.as_bytes()))
+    {
+        return localhost::status(StatusCode::UNAUTHORIZED);
+    }
+    if req.method() != Method::POST {
+        return localhost::status(StatusCode::METHOD_NOT_ALLOWED);
+    }
+    let body = match localhost::read_body(req).await {
+        Ok(body) => body,
+        Err(response) => return response,
+    };
+    let message: BridgeMessage = match serde_json::from_str(&body) {
+        Ok(message) => message,
+        Err(e) => {
+            return localhost::json(
+                StatusCode::OK,
+                &json!({ "id": null, "ok": false, "error": e.to_string() }),
+            )
+        }
+    };
+
+    let reply = match dispatch(&app, &message.action, message.params).await {
+        Ok(result) => json!({ "id": message.id, "ok": true, "result": result }),
+        Err(e) => json!({ "id": message.id, "ok": false, "error": e }),
+    };
+    localhost::json(StatusCode::OK, &reply)
+}
+
+/// The selected text in `params`; the bridge never falls back to the clipboard.
+fn text(params: &Value) -> Result<String, String> {
+    match params.get("text").and_then(Value::as_str) {
+        Some(text) if !text.trim().is_empty() => Ok(text.to_string()),
+        _ => Err("params.text is empty".to_string()),
+    }
+}
+
+async fn dispatch<R: Runtime>(
+    app: &AppHandle<R>,
+    action: &str,
+    params: Value,
+) -> Result<Value, String> {
+    match action {
+        "ping" => Ok(json!({ "version": env!("CARGO_PKG_VERSION") })),
+        // Open the panel on the text, as if it had been copied
+        "open" => {
+            hotkey::show_panel_with(app, "panel:text", text(&params)?);
+            Ok(Value::Null)
+        }
+        "rules" => api::call(app, action, params)
+            .await
+            .map_err(|e| e.to_string()),
+        "transform" | "ai" => {
+            text(&params)?;
+            api::call(app, action, params)
+                .await
+                .map_err(|e| e.to_string())
+        }
+        other => Err(format!("unknown action '{}'", other)),
+    }
+}
@@ -0,0 +1,154 @@
+use serde_json::{json, Value};
+use std::io::{self, Read, Write};
+
+/// Name the browser extension connects to.
+pub const HOST_NAME: &str = "com.flowpaste.app";
+/// Browsers refuse larger messages from a host.
+pub const MAX_OUTGOING_BYTES: usize = 1024 * 1024;
+/// Chrome allows up to 64 MiB towards the host; selections are far smaller.
+pub const MAX_INCOMING_BYTES: usize = 16 * 1024 * 1024;
+/// Passed explicitly to run as the host, e.g. when testing from a shell.
+pub const HOST_FLAG: &str = "--native-messaging";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Browser {
+    /// Chrome, Chromium, Edge and other Chromium-based browsers
+    Chrome,
+    Firefox,
+}
+
+impl Browser {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "chrome" => Some(Browser::Chrome),
+            "firefox" => Some(Browser::Firefox),
+            _ => None,
+        }
+    }
+}
+
+/// Whether the process was started by a browser as a native messaging host.
+/// Chrome passes the caller's origin; Firefox passes the path of the host
+/// manifest followed by the extension id.
+pub fn is_host_invocation(args: &[String]) -> bool {
+    match args {
+        [first, ..] if first == HOST_FLAG || first.starts_with("chrome-extension://") => true,
+        [manifest, _extension_id] => manifest.ends_with(&format!("{}.json", HOST_NAME)),
+        _ => false,
+    }
+}
+
+/// Read one message: a 32-bit native-endian length, then that many bytes of
+/// JSON. `None` when the browser closed the pipe between messages.
+pub fn read_message(reader: &mut impl Read) -> io::Result<Option<Value>> {
+    let mut length = [0u8; 4];
+    match reader.read_exact(&mut length) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let length = u32::from_ne_bytes(length) as usize;
+    if length > MAX_INCOMING_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("message of {} bytes is too large", length),
+        ));
+    }
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(io::Error::from)
+}
+
+/// Write one message in the same framing.
+pub fn write_message(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = serde_json::to_vec(message)?;
+    if body.len() > MAX_OUTGOING_BYTES {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("reply of {} bytes is too large", body.len()),
+        ));
+    }
+    writer.write_all(&(body.len() as u32).to_ne_bytes())?;
+    writer.write_all(&body)?;
+    writer.flush()
+}
+
+/// Host manifest registering `path` for the given extension ids.
+pub fn manifest(browser: Browser, path: &str, extension_ids: &[&str]) -> Value {
+    let mut manifest = json!({
+        "name": HOST_NAME,
+        "description": "FlowPaste",
+        "path": path,
+        "type": "stdio",
+    });
+    match browser {
+        Browser::Chrome => {
+            let origins: Vec<String> = extension_ids
+                .iter()
+                .map(|id| format!("chrome-extension://{}/", id))
+                .collect();
+            manifest["allowed_origins"] = json!(origins);
+        }
+        Browser::Firefox => manifest["allowed_extensions"] = json!(extension_ids),
+    }
+    manifest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_is_host_invocation() {
+        assert!(is_host_invocation(&args(&[
+            "chrome-extension://abcdef/",
+            "--parent-window=0"
+        ])));
+        assert!(is_host_invocation(&args(&[
+            "/home/me/.mozilla/native-messaging-hosts/com.flowpaste.app.json",
+            "ext@flowpaste"
+        ])));
+        assert!(is_host_invocation(&args(&["--native-messaging"])));
+        assert!(!is_host_invocation(&args(&[])));
+        assert!(!is_host_invocation(&args(&["--minimized"])));
+    }
+
+    #[test]
+    fn test_message_framing() {
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, &json!({ "action": "ping", "text": "你好" })).unwrap();
+        write_message(&mut buffer, &json!({ "id": 2 })).unwrap();
+
+        let mut reader = buffer.as_slice();
+        assert_eq!(
+            read_message(&mut reader).unwrap(),
+            Some(json!({ "action": "ping", "text": "你好" }))
+        );
+        assert_eq!(read_message(&mut reader).unwrap(), Some(json!({ "id": 2 })));
+        assert_eq!(read_message(&mut reader).unwrap(), None);
+
+        let too_large = json!({ "text": "x".repeat(MAX_OUTGOING_BYTES) });
+        assert!(write_message(&mut Vec::new(), &too_large).is_err());
+        let mut truncated = 10u32.to_ne_bytes().to_vec();
+        truncated.push(b'{');
+        assert!(read_message(&mut truncated.as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_manifest() {
+        let chrome = manifest(Browser::Chrome, "/opt/flow-paste", &["abc"]);
+        assert_eq!(
+            chrome["allowed_origins"],
+            json!(["chrome-extension://abc/"])
+        );
+        assert_eq!(chrome["type"], "stdio");
+        let firefox = manifest(Browser::Firefox, "/opt/flow-paste", &["ext@flowpaste"]);
+        assert_eq!(firefox["allowed_extensions"], json!(["ext@flowpaste"]));
+    }
+}
@@ -0,0 +1,148 @@
+mod template;
+
+use chrono::{Local, NaiveDateTime};
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager, Runtime};
+use thiserror::Error;
+
+use crate::clipboard::{self, ClipboardError};
+use crate::config::{ConfigError, ConfigManager};
+
+#[derive(Debug, Error)]
+pub enum NotesError {
+    #[error("no notes location set; choose a Markdown file or folder in settings")]
+    NotConfigured,
+    #[error("nothing to save")]
+    Empty,
+    #[error("invalid file name template: {0}")]
+    Template(String),
+    #[error("can't write notes: {0}")]
+    Io(#[from] io::Error),
+    #[error(transparent)]
+    Clipboard(#[from] ClipboardError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+}
+
+/// Whether `location` names a Markdown file rather than a folder.
+fn is_markdown_file(location: &Path) -> bool {
+    location
+        .extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("md") || e.eq_ignore_ascii_case("markdown"))
+}
+
+/// Check a notes location before it is saved; `""` is allowed (not set up).
+pub fn check_location(location: &str) -> Result<(), String> {
+    let location = Path::new(location.trim());
+    if location.as_os_str().is_empty() {
+        return Ok(());
+    }
+    if !location.is_absolute() {
+        return Err("must be an absolute path".to_string());
+    }
+    let folder = if is_markdown_file(location) {
+        location.parent()
+    } else {
+        Some(location)
+    };
+    match folder {
+        Some(folder) if folder.is_dir() => Ok(()),
+        _ => Err(format!(
+            "'{}' is not an existing folder",
+            location.display()
+        )),
+    }
+}
+
+/// Check a file name template before it is saved.
+pub fn check_file_template(file_template: &str) -> Result<(), String> {
+    template::file_path(file_template, Local::now().naive_local()).map(|_| ())
+}
+
+/// File an entry saved at `now` goes to: `location` itself when it is a
+/// Markdown file, otherwise the templated file inside that folder, such as
+/// a daily note in an Obsidian vault.
+fn target(location: &str, file_template: &str, now: NaiveDateTime) -> Result<PathBuf, NotesError> {
+    let location = Path::new(location.trim());
+    if location.as_os_str().is_empty() {
+        return Err(NotesError::NotConfigured);
+    }
+    if is_markdown_file(location) {
+        return Ok(location.to_path_buf());
+    }
+    let name = template::file_path(file_template, now).map_err(NotesError::Template)?;
+    Ok(location.join(name))
+}
+
+/// Last two bytes of `path`, empty when it doesn't exist yet.
+fn tail(path: &Path) -> io::Result<Vec<u8>> {
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e),
+    };
+    let len = file.metadata()?.len();
+    file.seek(SeekFrom::Start(len.saturating_sub(2)))?;
+    let mut tail = Vec::new();
+    file.read_to_end(&mut tail)?;
+    Ok(tail)
+}
+
+/// Append `text` to the configured notes file and return its path.
+pub fn save<R: Runtime>(app: &AppHandle<R>, text: &str) -> Result<PathBuf, NotesError> {
+    if text.trim().is_empty() {
+        return Err(NotesError::Empty);
+    }
+    let config = app.state::<ConfigManager>().effective_config()?;
+    let now = Local::now().naive_local();
+    let path = target(&config.notes_path, &config.notes_file_template, now)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let separator = template::separator(&tail(&path)?);
+    let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+    file.write_all(format!("{}{}", separator, template::entry(text, now)).as_bytes())?;
+    log::info!(
+        "Saved {} chars to notes at {}",
+        text.chars().count(),
+        path.display()
+    );
+    Ok(path)
+}
+
+/// Append the clipboard text to the notes file.
+pub fn save_clipboard<R: Runtime>(app: &AppHandle<R>) -> Result<PathBuf, NotesError> {
+    let text = clipboard::read_clipboard(app)?
+        .text
+        .ok_or(ClipboardError::Empty)?;
+    save(app, &text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_target() {
+        let now = chrono::NaiveDate::from_ymd_opt(2024, 6, 1)
+            .unwrap()
+            .and_hms_opt(9, 0, 0)
+            .unwrap();
+        assert!(matches!(
+            target("", "{date}", now),
+            Err(NotesError::NotConfigured)
+        ));
+        assert_eq!(
+            target("/notes/Inbox.md", "{date}", now).unwrap(),
+            PathBuf::from("/notes/Inbox.md")
+        );
+        assert_eq!(
+            target("/vault", "Daily/{date}", now).unwrap(),
+            PathBuf::from("/vault/Daily/2024-06-01.md")
+        );
+    }
+}
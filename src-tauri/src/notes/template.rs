@@ -0,0 +1,103 @@
+use chrono::{Datelike, NaiveDateTime};
+use std::path::{Component, Path, PathBuf};
+
+/// Placeholders of the file name template and what they become.
+const PLACEHOLDERS: &[(&str, &str)] = &[
+    ("{date}", "%Y-%m-%d"),
+    ("{year}", "%Y"),
+    ("{month}", "%m"),
+    ("{day}", "%d"),
+];
+
+/// Path of the notes file for `now`, relative to the notes folder: `{date}.md`
+/// becomes `2024-06-01.md`. `.md` is added when there is no extension.
+pub fn file_path(template: &str, now: NaiveDateTime) -> Result<PathBuf, String> {
+    let mut name = template.trim().to_string();
+    for (placeholder, format) in PLACEHOLDERS {
+        name = name.replace(placeholder, &now.format(format).to_string());
+    }
+    name = name.replace("{week}", &format!("{:02}", now.iso_week().week()));
+    if name.contains(['{', '}']) {
+        return Err(
+            "unknown placeholder; use {date}, {year}, {month}, {day} or {week}".to_string(),
+        );
+    }
+
+    let mut path = PathBuf::from(&name);
+    if name.is_empty() || name.ends_with(['/', '\\']) {
+        return Err("must name a file".to_string());
+    }
+    if !Path::new(&name)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return Err("must stay inside the notes folder".to_string());
+    }
+    if path.extension().is_none() {
+        path.set_extension("md");
+    }
+    Ok(path)
+}
+
+/// One saved result, headed with when it was saved.
+pub fn entry(text: &str, now: NaiveDateTime) -> String {
+    format!(
+        "## {}\n\n{}\n",
+        now.format("%Y-%m-%d %H:%M"),
+        text.trim_end()
+    )
+}
+
+/// What goes before an entry appended after `tail`, the end of the file, so
+/// the heading starts its own paragraph.
+pub fn separator(tail: &[u8]) -> &'static str {
+    match tail {
+        [] | [.., b'\n', b'\n'] => "",
+        [.., b'\n'] => "\n",
+        _ => "\n\n",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn now() -> NaiveDateTime {
+        chrono::NaiveDate::from_ymd_opt(2024, 6, 1)
+            .unwrap()
+            .and_hms_opt(9, 5, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_file_path() {
+        assert_eq!(
+            file_path("{date}.md", now()).unwrap(),
+            PathBuf::from("2024-06-01.md")
+        );
+        assert_eq!(
+            file_path("Daily/{year}/{year}-W{week}", now()).unwrap(),
+            PathBuf::from("Daily/2024/2024-W22.md")
+        );
+        assert_eq!(
+            file_path("Inbox.markdown", now()).unwrap(),
+            PathBuf::from("Inbox.markdown")
+        );
+        assert!(file_path("{hour}.md", now()).is_err());
+        assert!(file_path("../outside.md", now()).is_err());
+        assert!(file_path("/etc/notes.md", now()).is_err());
+        assert!(file_path("  ", now()).is_err());
+    }
+
+    #[test]
+    fn test_entry() {
+        assert_eq!(
+            entry("Result\n\n", now()),
+            "## 2024-06-01 09:05\n\nResult\n"
+        );
+        assert_eq!(separator(b""), "");
+        assert_eq!(separator(b"text\n\n"), "");
+        assert_eq!(separator(b"text\n"), "\n");
+        assert_eq!(separator(b"text"), "\n\n");
+    }
+}
@@ -0,0 +1,135 @@
+use std::path::Path;
+#[cfg(not(windows))]
+use std::process::Command;
+use tauri::{AppHandle, Runtime};
+
+use super::OcrError;
+
+/// Let the user drag out a screen region and save it as a PNG at `path`.
+/// Fails with `OcrError::Cancelled` when the selection is dismissed.
+pub fn capture_region<R: Runtime>(app: &AppHandle<R>, path: &Path) -> Result<(), OcrError> {
+    platform::capture(app, path)?;
+    match std::fs::metadata(path) {
+        Ok(meta) if meta.len() > 0 => Ok(()),
+        _ => Err(OcrError::Cancelled),
+    }
+}
+
+/// Run a capture tool; `Ok(false)` if it isn't installed.
+#[cfg(not(windows))]
+fn run_tool(program: &str, args: &[&str], path: &Path) -> Result<bool, OcrError> {
+    match Command::new(program).args(args).arg(path).status() {
+        // Most tools exit non-zero when the selection is dismissed
+        Ok(status) if status.success() => Ok(true),
+        Ok(_) => Err(OcrError::Cancelled),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
+        Err(e) => Err(OcrError::Capture(format!("{}: {}", program, e))),
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+
+    pub fn capture<R: Runtime>(_app: &AppHandle<R>, path: &Path) -> Result<(), OcrError> {
+        // Interactive selection, no shutter sound; exits 0 even when cancelled
+        if run_tool("screencapture", &["-i", "-x"], path)? {
+            Ok(())
+        } else {
+            Err(OcrError::NoCaptureTool("screencapture".to_string()))
+        }
+    }
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+mod platform {
+    use super::*;
+
+    /// Region capture tools tried in order, each taking the output path last.
+    const TOOLS: &[(&str, &[&str])] = &[
+        ("gnome-screenshot", &["-a", "-f"]),
+        ("spectacle", &["-r", "-b", "-n", "-o"]),
+        ("maim", &["-s"]),
+        ("scrot", &["-s", "-o"]),
+    ];
+
+    pub fn capture<R: Runtime>(_app: &AppHandle<R>, path: &Path) -> Result<(), OcrError> {
+        if std::env::var_os("WAYLAND_DISPLAY").is_some() && capture_wayland(path)? {
+            return Ok(());
+        }
+        for (program, args) in TOOLS {
+            if run_tool(program, args, path)? {
+                return Ok(());
+            }
+        }
+        let mut names: Vec<&str> = TOOLS.iter().map(|(program, _)| *program).collect();
+        names.push("grim + slurp");
+        Err(OcrError::NoCaptureTool(names.join(", ")))
+    }
+
+    /// `slurp` picks the region, `grim` captures it.
+    fn capture_wayland(path: &Path) -> Result<bool, OcrError> {
+        let region = match Command::new("slurp").output() {
+            Ok(output) if output.status.success() => {
+                String::from_utf8_lossy(&output.stdout).trim().to_string()
+            }
+            Ok(_) => return Err(OcrError::Cancelled),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(OcrError::Capture(format!("slurp: {}", e))),
+        };
+        run_tool("grim", &["-g", &region], path)
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::*;
+    use crate::clipboard;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::{Hash, Hasher};
+    use std::process::Command;
+    use std::time::{Duration, Instant};
+    use tauri_plugin_clipboard_manager::ClipboardExt;
+
+    /// How long the user has to finish the selection.
+    const SELECT_TIMEOUT: Duration = Duration::from_secs(60);
+    const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+    /// Snipping Tool has no output file option: it puts the region on the
+    /// clipboard, so wait for a new image there and save it.
+    pub fn capture<R: Runtime>(app: &AppHandle<R>, path: &Path) -> Result<(), OcrError> {
+        let before = image_fingerprint(app);
+        Command::new("explorer.exe")
+            .arg("ms-screenclip:")
+            .spawn()
+            .map_err(|e| OcrError::NoCaptureTool(format!("Snipping Tool ({})", e)))?;
+
+        let started = Instant::now();
+        while started.elapsed() < SELECT_TIMEOUT {
+            std::thread::sleep(POLL_INTERVAL);
+            let Ok(image) = app.clipboard().read_image() else {
+                continue;
+            };
+            if Some(fingerprint(image.rgba())) == before {
+                continue;
+            }
+            return clipboard::encode_png(image.width(), image.height(), image.rgba())
+                .and_then(|png| std::fs::write(path, png))
+                .map_err(|e| OcrError::Capture(e.to_string()));
+        }
+        Err(OcrError::Cancelled)
+    }
+
+    fn image_fingerprint<R: Runtime>(app: &AppHandle<R>) -> Option<u64> {
+        app.clipboard()
+            .read_image()
+            .ok()
+            .map(|image| fingerprint(image.rgba()))
+    }
+
+    fn fingerprint(rgba: &[u8]) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        rgba.hash(&mut hasher);
+        hasher.finish()
+    }
+}
@@ -0,0 +1,91 @@
+mod capture;
+
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::Command;
+use tauri::{AppHandle, Manager, Runtime};
+use thiserror::Error;
+
+use crate::clipboard::{self, ClipboardError};
+use crate::config::{ConfigError, ConfigManager};
+use crate::text;
+
+#[derive(Debug, Error)]
+pub enum OcrError {
+    #[error("screen capture cancelled")]
+    Cancelled,
+    #[error("no screen capture tool found; install one of: {0}")]
+    NoCaptureTool(String),
+    #[error("screen capture failed: {0}")]
+    Capture(String),
+    #[error("OCR engine '{0}' not found; install Tesseract or set its path in settings")]
+    EngineNotFound(String),
+    #[error("OCR failed: {0}")]
+    Engine(String),
+    #[error("no text found in the captured region")]
+    NoText,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Clipboard(#[from] ClipboardError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+}
+
+/// Read the text in the image at `image` with a Tesseract-compatible engine.
+pub fn recognize(engine: &str, languages: &str, image: &Path) -> Result<String, OcrError> {
+    let output = Command::new(engine)
+        .arg(image)
+        .arg("stdout")
+        .args(["-l", languages])
+        .output()
+        .map_err(|e| match e.kind() {
+            ErrorKind::NotFound => OcrError::EngineNotFound(engine.to_string()),
+            _ => OcrError::Io(e),
+        })?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr
+            .lines()
+            .rev()
+            .find(|l| !l.trim().is_empty())
+            .unwrap_or("unknown error");
+        return Err(OcrError::Engine(reason.trim().to_string()));
+    }
+
+    let text = text::clean_ocr_text(&String::from_utf8_lossy(&output.stdout));
+    if text.is_empty() {
+        return Err(OcrError::NoText);
+    }
+    Ok(text)
+}
+
+/// Let the user select a screen region, read its text and put it on the
+/// clipboard, where the panel picks it up like any copied text. The panel is
+/// hidden while selecting so it doesn't end up in the capture.
+pub fn capture_text<R: Runtime>(app: &AppHandle<R>) -> Result<String, OcrError> {
+    let config = app.state::<ConfigManager>().effective_config()?;
+    if let Some(window) = app.get_webview_window("main") {
+        if let Err(e) = window.hide() {
+            log::warn!("Failed to hide window before capture: {}", e);
+        }
+    }
+
+    let path = std::env::temp_dir().join(format!("flow-paste-ocr-{}.png", std::process::id()));
+    let result = capture::capture_region(app, &path)
+        .and_then(|()| recognize(config.ocr_engine.trim(), &config.ocr_languages, &path));
+    // The capture may hold sensitive content; don't leave it behind
+    if let Err(e) = std::fs::remove_file(&path) {
+        if e.kind() != ErrorKind::NotFound {
+            log::warn!("Failed to remove capture {}: {}", path.display(), e);
+        }
+    }
+
+    let text = result?;
+    clipboard::write_clipboard(app, &text)?;
+    log::info!(
+        "Read {} character(s) from screen capture",
+        text.chars().count()
+    );
+    Ok(text)
+}
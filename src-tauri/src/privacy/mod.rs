@@ -1,6 +0,0 @@
-mod patterns;
-mod scanner;
-mod masker;
-
-pub use scanner::{scan_pii, PIIScanResult};
-pub use masker::{mask_pii, restore_pii, MaskMapping, MaskResult};
@@ -0,0 +1,85 @@
+mod render;
+
+pub use render::render;
+
+use std::io::ErrorKind;
+use std::path::Path;
+use std::process::Command;
+use tauri::{AppHandle, Manager, Runtime};
+use thiserror::Error;
+
+use crate::clipboard::{self, ClipboardError};
+use crate::config::{ConfigError, ConfigManager};
+
+/// `zbarimg` exit code when the image holds no code.
+const NO_CODE_EXIT: i32 = 4;
+
+#[derive(Debug, Error)]
+pub enum QrError {
+    #[error("cannot make a QR code: {0}")]
+    Encode(#[from] qrcode::types::QrError),
+    #[error("QR decoder '{0}' not found; install ZBar or set its path in settings")]
+    DecoderNotFound(String),
+    #[error("QR decoding failed: {0}")]
+    Decode(String),
+    #[error("no QR code found in the image")]
+    NotFound,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Clipboard(#[from] ClipboardError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+}
+
+/// Read the QR codes in the image at `image` with a ZBar-compatible
+/// `decoder`. Several codes come back one per line.
+pub fn decode(decoder: &str, image: &Path) -> Result<String, QrError> {
+    let output = Command::new(decoder)
+        .args(["--quiet", "--raw", "-Sdisable", "-Sqrcode.enable"])
+        .arg(image)
+        .output()
+        .map_err(|e| match e.kind() {
+            ErrorKind::NotFound => QrError::DecoderNotFound(decoder.to_string()),
+            _ => QrError::Io(e),
+        })?;
+    if output.status.code() == Some(NO_CODE_EXIT) {
+        return Err(QrError::NotFound);
+    }
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let reason = stderr
+            .lines()
+            .rev()
+            .find(|l| !l.trim().is_empty())
+            .unwrap_or("unknown error");
+        return Err(QrError::Decode(reason.trim().to_string()));
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout)
+        .trim_end_matches(['\r', '\n'])
+        .to_string();
+    if text.is_empty() {
+        return Err(QrError::NotFound);
+    }
+    Ok(text)
+}
+
+/// Decode the QR code in the clipboard image and replace the image with
+/// its text.
+pub fn decode_clipboard<R: Runtime>(app: &AppHandle<R>) -> Result<String, QrError> {
+    let config = app.state::<ConfigManager>().effective_config()?;
+    let path = std::env::temp_dir().join(format!("flow-paste-qr-{}.png", std::process::id()));
+    let result = clipboard::save_clipboard_image(app, &path)
+        .map_err(QrError::from)
+        .and_then(|()| decode(config.qr_decoder.trim(), &path));
+    if let Err(e) = std::fs::remove_file(&path) {
+        if e.kind() != ErrorKind::NotFound {
+            log::warn!("Failed to remove {}: {}", path.display(), e);
+        }
+    }
+
+    let text = result?;
+    clipboard::write_clipboard(app, &text)?;
+    Ok(text)
+}
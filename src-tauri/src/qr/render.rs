@@ -0,0 +1,69 @@
+use qrcode::types::QrError;
+use qrcode::{Color, QrCode};
+
+/// Pixels per module, enough for phone cameras at normal viewing distance.
+const MODULE_PIXELS: u32 = 8;
+/// Blank modules around the code; scanners need at least four.
+const QUIET_ZONE: u32 = 4;
+
+/// A rendered code as 8-bit RGBA pixels, black on white.
+#[derive(Debug, Clone)]
+pub struct QrImage {
+    pub width: u32,
+    pub height: u32,
+    pub rgba: Vec<u8>,
+}
+
+pub fn render(text: &str) -> Result<QrImage, QrError> {
+    let code = QrCode::new(text.as_bytes())?;
+    let modules = code.width() as u32;
+    let size = (modules + 2 * QUIET_ZONE) * MODULE_PIXELS;
+    let mut rgba = vec![255u8; (size * size * 4) as usize];
+
+    for (i, color) in code.to_colors().into_iter().enumerate() {
+        if color != Color::Dark {
+            continue;
+        }
+        let left = (i as u32 % modules + QUIET_ZONE) * MODULE_PIXELS;
+        let top = (i as u32 / modules + QUIET_ZONE) * MODULE_PIXELS;
+        for y in top..top + MODULE_PIXELS {
+            let start = ((y * size + left) * 4) as usize;
+            let end = start + (MODULE_PIXELS * 4) as usize;
+            for pixel in rgba[start..end].chunks_exact_mut(4) {
+                pixel.copy_from_slice(&[0, 0, 0, 255]);
+            }
+        }
+    }
+    Ok(QrImage {
+        width: size,
+        height: size,
+        rgba,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let image = render("WIFI:T:WPA;S:Home;P:secret;;").unwrap();
+        assert_eq!(image.width, image.height);
+        assert_eq!(image.rgba.len(), (image.width * image.height * 4) as usize);
+
+        let pixel = |x: u32, y: u32| {
+            let i = ((y * image.width + x) * 4) as usize;
+            &image.rgba[i..i + 4]
+        };
+        // Quiet zone, then the finder pattern's dark corner
+        let edge = QUIET_ZONE * MODULE_PIXELS;
+        assert_eq!(pixel(edge - 1, edge - 1), [255, 255, 255, 255]);
+        assert_eq!(pixel(edge, edge), [0, 0, 0, 255]);
+        assert_eq!(
+            pixel(image.width - 1, image.height - 1),
+            [255, 255, 255, 255]
+        );
+
+        assert!(render(&"x".repeat(4000)).is_err());
+    }
+}
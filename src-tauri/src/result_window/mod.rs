@@ -0,0 +1,183 @@
+//! Small always-on-top window that shows one transform or AI result next
+//! to the cursor, to paste with accept or drop with dismiss, without
+//! opening the panel. It is created on first use and hidden in between.
+
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager, Runtime, WebviewUrl, WebviewWindow, WebviewWindowBuilder};
+use thiserror::Error;
+
+use crate::clipboard::ClipboardError;
+use crate::hotkey;
+
+/// Label of the result window; the frontend renders it by this label.
+pub const LABEL: &str = "result";
+const WIDTH: f64 = 360.0;
+const HEIGHT: f64 = 180.0;
+
+#[derive(Debug, Error)]
+pub enum ResultWindowError {
+    #[error("no result is showing")]
+    Empty,
+    #[error("failed to open the result window: {0}")]
+    Window(#[from] tauri::Error),
+    #[error(transparent)]
+    Clipboard(#[from] ClipboardError),
+}
+
+/// What the window shows.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InlineResult {
+    pub text: String,
+    /// Short heading such as the rule or prompt that produced `text`
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Label of the window that asked; it gets `result:closed` afterwards
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+/// Sent to the source window once the result is accepted or dismissed.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResultOutcome {
+    pub accepted: bool,
+    pub text: String,
+}
+
+/// The result on show, kept for a window that loads after `result:show`.
+#[derive(Default)]
+pub struct ResultWindow {
+    current: Mutex<Option<InlineResult>>,
+    /// Set while accepting hides the window to paste, which isn't a dismissal
+    accepting: AtomicBool,
+}
+
+impl ResultWindow {
+    pub fn current(&self) -> Option<InlineResult> {
+        self.current.lock().unwrap().clone()
+    }
+
+    fn replace(&self, result: Option<InlineResult>) -> Option<InlineResult> {
+        std::mem::replace(&mut *self.current.lock().unwrap(), result)
+    }
+
+    /// Clear `result` unless another one has replaced it meanwhile.
+    fn finish(&self, result: &InlineResult) -> bool {
+        let mut current = self.current.lock().unwrap();
+        if current.as_ref() != Some(result) {
+            return false;
+        }
+        *current = None;
+        true
+    }
+}
+
+fn window<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<WebviewWindow<R>> {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        return Ok(window);
+    }
+    WebviewWindowBuilder::new(app, LABEL, WebviewUrl::App("index.html".into()))
+        .title("FlowPaste")
+        .inner_size(WIDTH, HEIGHT)
+        .resizable(false)
+        .decorations(false)
+        .always_on_top(true)
+        .skip_taskbar(true)
+        .visible(false)
+        .build()
+}
+
+/// Show `result` next to the cursor, replacing any result on show, which
+/// counts as dismissed.
+pub fn show<R: Runtime>(app: &AppHandle<R>, result: InlineResult) -> Result<(), ResultWindowError> {
+    let window = window(app)?;
+    if let Some(previous) = app.state::<ResultWindow>().replace(Some(result.clone())) {
+        notify(app, previous, false);
+    }
+    if let Err(e) = hotkey::move_to_cursor(app, &window) {
+        log::warn!("Failed to position result window at cursor: {}", e);
+    }
+    window.show()?;
+    if let Err(e) = window.set_focus() {
+        log::warn!("Failed to focus result window: {}", e);
+    }
+    app.emit_to(LABEL, "result:show", result)?;
+    Ok(())
+}
+
+/// Hide the window and paste the result into the app that had focus. If
+/// that fails the window comes back with the result, to try again.
+pub async fn accept<R: Runtime>(app: &AppHandle<R>) -> Result<(), ResultWindowError> {
+    let state = app.state::<ResultWindow>();
+    let result = state.current().ok_or(ResultWindowError::Empty)?;
+    state.accepting.store(true, Ordering::SeqCst);
+    hide(app);
+    let paste = {
+        let app = app.clone();
+        let text = result.text.clone();
+        tauri::async_runtime::spawn_blocking(move || hotkey::write_and_paste(&app, &text))
+    };
+    let pasted = paste
+        .await
+        .map_err(|e| ClipboardError::Unavailable(e.to_string()))
+        .and_then(|pasted| pasted);
+    state.accepting.store(false, Ordering::SeqCst);
+
+    if let Err(e) = pasted {
+        if let Some(window) = app.get_webview_window(LABEL) {
+            if let Err(e) = window.show().and_then(|_| window.set_focus()) {
+                log::error!("Failed to show result window again: {}", e);
+            }
+        }
+        return Err(e.into());
+    }
+    if state.finish(&result) {
+        notify(app, result, true);
+    }
+    Ok(())
+}
+
+/// The window lost focus: dismiss, unless it was hidden to paste.
+pub fn dismiss_on_blur<R: Runtime>(app: &AppHandle<R>) {
+    if !app.state::<ResultWindow>().accepting.load(Ordering::SeqCst) {
+        dismiss(app);
+    }
+}
+
+/// Hide the window and drop the result. Nothing to do if none is showing.
+pub fn dismiss<R: Runtime>(app: &AppHandle<R>) {
+    hide(app);
+    if let Some(result) = app.state::<ResultWindow>().replace(None) {
+        notify(app, result, false);
+    }
+}
+
+/// Dismiss and destroy the window, freeing its webview until the next result.
+pub fn close<R: Runtime>(app: &AppHandle<R>) -> Result<(), ResultWindowError> {
+    dismiss(app);
+    if let Some(window) = app.get_webview_window(LABEL) {
+        window.destroy()?;
+    }
+    Ok(())
+}
+
+fn hide<R: Runtime>(app: &AppHandle<R>) {
+    if let Some(window) = app.get_webview_window(LABEL) {
+        if let Err(e) = window.hide() {
+            log::error!("Failed to hide result window: {}", e);
+        }
+    }
+}
+
+fn notify<R: Runtime>(app: &AppHandle<R>, result: InlineResult, accepted: bool) {
+    let Some(source) = result.source else {
+        return;
+    };
+    let outcome = ResultOutcome { accepted, text: result.text };
+    if let Err(e) = app.emit_to(source.as_str(), "result:closed", outcome) {
+        log::error!("Failed to emit result:closed to {}: {}", source, e);
+    }
+}
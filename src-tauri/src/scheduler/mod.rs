@@ -0,0 +1,226 @@
+//! Recurring housekeeping run by one background task: clearing personal
+//! data left on the clipboard, purging history each night, refreshing
+//! Ollama's model list and syncing with the sync folder. Each tick reads the
+//! settings, so turning a task on or off takes effect without a restart.
+
+use chrono::{Local, NaiveDate};
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::clipboard::{RecentClipboard, TransformHistory};
+use crate::commands::AIState;
+use crate::config::{AppConfig, ConfigManager};
+use crate::folder_sync::{self, SyncError};
+use crate::privacy;
+
+const TICK: Duration = Duration::from_secs(30);
+const MODEL_REFRESH_INTERVAL: Duration = Duration::from_secs(60 * 60);
+const SYNC_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Clipboard text holding personal data, as a hash rather than the text
+/// itself, and when it was first seen.
+type PiiSeen = Option<(u64, Instant)>;
+
+/// When each task last ran or started waiting.
+#[derive(Default)]
+struct Timers {
+    pii: PiiSeen,
+    /// Day the history was last purged, or the scheduler started
+    purged_on: Option<NaiveDate>,
+    models_refreshed: Option<Instant>,
+    synced: Option<Instant>,
+}
+
+/// True on the first call and then once `interval` has passed since the
+/// last time it was.
+fn every(last: &mut Option<Instant>, now: Instant, interval: Duration) -> bool {
+    let due = match *last {
+        Some(at) => now.duration_since(at) >= interval,
+        None => true,
+    };
+    if due {
+        *last = Some(now);
+    }
+    due
+}
+
+impl Timers {
+    /// True once per day, from the first tick after midnight; never on the
+    /// day the app started.
+    fn purge_due(&mut self, today: NaiveDate) -> bool {
+        let due = self.purged_on.is_some_and(|day| day != today);
+        self.purged_on = Some(today);
+        due
+    }
+
+    fn refresh_due(&mut self, now: Instant) -> bool {
+        every(&mut self.models_refreshed, now, MODEL_REFRESH_INTERVAL)
+    }
+
+    fn sync_due(&mut self, now: Instant) -> bool {
+        every(&mut self.synced, now, SYNC_INTERVAL)
+    }
+}
+
+/// Whether the clipboard has held the same personal data (`hash`, `None`
+/// for none) for `after`. A new copy starts the wait again.
+fn pii_expired(seen: &mut PiiSeen, hash: Option<u64>, now: Instant, after: Duration) -> bool {
+    let Some(hash) = hash else {
+        *seen = None;
+        return false;
+    };
+    match *seen {
+        Some((seen_hash, since)) if seen_hash == hash => now.duration_since(since) >= after,
+        _ => {
+            *seen = Some((hash, now));
+            false
+        }
+    }
+}
+
+/// Start the scheduler; it runs for the life of the app.
+pub fn spawn<R: Runtime>(app: AppHandle<R>) {
+    tauri::async_runtime::spawn(async move {
+        let mut timers = Timers::default();
+        loop {
+            tick(&app, &mut timers).await;
+            tokio::time::sleep(TICK).await;
+        }
+    });
+}
+
+async fn tick<R: Runtime>(app: &AppHandle<R>, timers: &mut Timers) {
+    let config = match app.state::<ConfigManager>().effective_config() {
+        Ok(config) => config,
+        Err(e) => {
+            log::warn!("Scheduler skipped a run, settings unreadable: {}", e);
+            return;
+        }
+    };
+
+    if config.clear_pii_after_minutes > 0 {
+        let after = Duration::from_secs(u64::from(config.clear_pii_after_minutes) * 60);
+        let (app, seen) = (app.clone(), timers.pii.take());
+        timers.pii = tauri::async_runtime::spawn_blocking(move || clear_pii(&app, seen, after))
+            .await
+            .unwrap_or(None);
+    } else {
+        timers.pii = None;
+    }
+
+    if timers.purge_due(Local::now().date_naive()) && config.purge_history_nightly {
+        purge_history(app);
+    }
+
+    if config.refresh_models_hourly && timers.refresh_due(Instant::now()) {
+        refresh_models(app, &config).await;
+    }
+
+    if !config.sync_folder.trim().is_empty() && timers.sync_due(Instant::now()) {
+        sync_folder(app).await;
+    }
+}
+
+/// Clear the clipboard, and forget it in the history, once it has held
+/// personal data for `after`. Returns what to remember for the next tick.
+fn clear_pii<R: Runtime>(app: &AppHandle<R>, mut seen: PiiSeen, after: Duration) -> PiiSeen {
+    let text = app.clipboard().read_text().unwrap_or_default();
+    let hash = privacy::scan_pii(&text).has_pii.then(|| {
+        let mut hasher = DefaultHasher::new();
+        text.hash(&mut hasher);
+        hasher.finish()
+    });
+    if !pii_expired(&mut seen, hash, Instant::now(), after) {
+        return seen;
+    }
+
+    if let Err(e) = app.clipboard().clear() {
+        log::warn!("Failed to clear personal data from the clipboard: {}", e);
+        return seen;
+    }
+    app.state::<RecentClipboard>().remove(&text);
+    crate::tray::refresh(app);
+    log::info!("Cleared personal data left on the clipboard for {} min", after.as_secs() / 60);
+    None
+}
+
+fn purge_history<R: Runtime>(app: &AppHandle<R>) {
+    app.state::<RecentClipboard>().clear();
+    app.state::<TransformHistory>().clear();
+    crate::tray::refresh(app);
+    log::info!("Purged clipboard and transform history");
+}
+
+/// Refresh the cached model list from the configured Ollama server and tell
+/// the settings page with `models:refreshed`.
+async fn refresh_models<R: Runtime>(app: &AppHandle<R>, config: &AppConfig) {
+    let Some(ollama) = config.provider("Ollama") else {
+        return;
+    };
+    match app.state::<Arc<AIState>>().refresh_local_models(&ollama.base_url).await {
+        Ok(models) => {
+            log::debug!("Refreshed {} Ollama model(s)", models.len());
+            if let Err(e) = app.emit("models:refreshed", models) {
+                log::error!("Failed to emit models:refreshed event: {}", e);
+            }
+        }
+        Err(e) => log::debug!("Ollama model refresh failed: {}", e),
+    }
+}
+
+/// Sync with the sync folder and tell the settings page with
+/// `sync:completed`.
+async fn sync_folder<R: Runtime>(app: &AppHandle<R>) {
+    match folder_sync::sync(app).await {
+        Ok(report) => {
+            if let Err(e) = app.emit("sync:completed", report) {
+                log::error!("Failed to emit sync:completed event: {}", e);
+            }
+        }
+        // The settings page is syncing right now
+        Err(SyncError::Busy) => {}
+        Err(e) => log::warn!("Folder sync failed: {}", e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pii_expires_after_wait() {
+        let mut seen = None;
+        let start = Instant::now();
+        let wait = Duration::from_secs(300);
+        assert!(!pii_expired(&mut seen, Some(1), start, wait));
+        assert!(!pii_expired(&mut seen, Some(1), start + Duration::from_secs(100), wait));
+        assert!(pii_expired(&mut seen, Some(1), start + wait, wait));
+
+        // A different copy, or none, starts over
+        assert!(!pii_expired(&mut seen, Some(2), start + wait, wait));
+        assert!(!pii_expired(&mut seen, None, start + wait * 2, wait));
+        assert!(!pii_expired(&mut seen, Some(2), start + wait * 2, wait));
+    }
+
+    #[test]
+    fn test_purge_and_refresh_due() {
+        let mut timers = Timers::default();
+        let day = NaiveDate::from_ymd_opt(2024, 6, 1).unwrap();
+        assert!(!timers.purge_due(day));
+        assert!(!timers.purge_due(day));
+        assert!(timers.purge_due(day.succ_opt().unwrap()));
+        assert!(!timers.purge_due(day.succ_opt().unwrap()));
+
+        let start = Instant::now();
+        assert!(timers.refresh_due(start));
+        assert!(!timers.refresh_due(start + Duration::from_secs(60)));
+        assert!(timers.refresh_due(start + MODEL_REFRESH_INTERVAL));
+        assert!(timers.sync_due(start));
+        assert!(!timers.sync_due(start + MODEL_REFRESH_INTERVAL / 60));
+        assert!(timers.sync_due(start + SYNC_INTERVAL));
+    }
+}
@@ -0,0 +1,142 @@
+use super::SnippetError;
+
+const MIN_CHARS: usize = 2;
+const MAX_CHARS: usize = 32;
+
+/// Check an abbreviation can be typed and told apart from normal text.
+pub fn validate_abbreviation(abbreviation: &str) -> Result<(), SnippetError> {
+    let chars = abbreviation.chars().count();
+    if !(MIN_CHARS..=MAX_CHARS).contains(&chars) {
+        return Err(SnippetError::InvalidAbbreviation(format!(
+            "must be {} to {} characters",
+            MIN_CHARS, MAX_CHARS
+        )));
+    }
+    if abbreviation.chars().any(|c| c.is_whitespace() || c.is_control()) {
+        return Err(SnippetError::InvalidAbbreviation("must not contain spaces".to_string()));
+    }
+    Ok(())
+}
+
+/// An abbreviation and the snippet it expands to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Abbreviation {
+    pub text: String,
+    pub snippet_id: String,
+}
+
+/// What the user did, as far as the matcher cares.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Typed {
+    Text(String),
+    Backspace,
+    /// The caret moved or focus changed: what came before no longer counts
+    Reset,
+}
+
+/// Follows recently typed text and reports when it ends in an abbreviation.
+#[derive(Debug, Default)]
+pub struct AbbreviationMatcher {
+    typed: String,
+}
+
+impl AbbreviationMatcher {
+    /// Feed one input; on a match the typed text is forgotten so the
+    /// replacement doesn't match again.
+    pub fn input(&mut self, typed: Typed, abbreviations: &[Abbreviation]) -> Option<Abbreviation> {
+        match typed {
+            Typed::Text(text) => self.typed.push_str(&text),
+            Typed::Backspace => {
+                self.typed.pop();
+                return None;
+            }
+            Typed::Reset => {
+                self.typed.clear();
+                return None;
+            }
+        }
+
+        // Only the tail can ever match
+        let excess = self.typed.chars().count().saturating_sub(MAX_CHARS + 1);
+        if excess > 0 {
+            let cut = self.typed.char_indices().nth(excess).map_or(0, |(i, _)| i);
+            self.typed.drain(..cut);
+        }
+
+        // Longest wins, so `;sig` beats `sig` when both end the text
+        let found = abbreviations
+            .iter()
+            .filter(|a| self.ends_with_word(&a.text))
+            .max_by_key(|a| a.text.chars().count())
+            .cloned();
+        if found.is_some() {
+            self.typed.clear();
+        }
+        found
+    }
+
+    /// `abbreviation` ends the typed text and doesn't continue a word
+    /// (`btw` must not fire inside `xbtw`).
+    fn ends_with_word(&self, abbreviation: &str) -> bool {
+        let Some(before) = self.typed.strip_suffix(abbreviation) else {
+            return false;
+        };
+        let starts_word = abbreviation.chars().next().is_some_and(char::is_alphanumeric);
+        !starts_word || !before.chars().next_back().is_some_and(char::is_alphanumeric)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn abbreviations() -> Vec<Abbreviation> {
+        [(";sig", "signature"), (";addr", "address"), ("btw", "by-the-way")]
+            .iter()
+            .map(|(text, id)| Abbreviation { text: text.to_string(), snippet_id: id.to_string() })
+            .collect()
+    }
+
+    fn type_text(matcher: &mut AbbreviationMatcher, text: &str) -> Option<String> {
+        let abbreviations = abbreviations();
+        let mut found = None;
+        for c in text.chars() {
+            if let Some(a) = matcher.input(Typed::Text(c.to_string()), &abbreviations) {
+                found = Some(a.snippet_id);
+            }
+        }
+        found
+    }
+
+    #[test]
+    fn test_matches_typed_abbreviation() {
+        let mut matcher = AbbreviationMatcher::default();
+        assert_eq!(type_text(&mut matcher, "thanks;sig").as_deref(), Some("signature"));
+        assert_eq!(type_text(&mut matcher, "sig"), None);
+        assert_eq!(type_text(&mut matcher, "ok btw").as_deref(), Some("by-the-way"));
+        assert_eq!(type_text(&mut matcher, "xbtw"), None);
+    }
+
+    #[test]
+    fn test_backspace_and_reset() {
+        let mut matcher = AbbreviationMatcher::default();
+        type_text(&mut matcher, ";adx");
+        matcher.input(Typed::Backspace, &abbreviations());
+        assert_eq!(type_text(&mut matcher, "dr").as_deref(), Some("address"));
+
+        type_text(&mut matcher, ";ad");
+        matcher.input(Typed::Reset, &abbreviations());
+        assert_eq!(type_text(&mut matcher, "dr"), None);
+
+        let long = "x".repeat(500);
+        assert_eq!(type_text(&mut matcher, &format!("{} ;sig", long)).as_deref(), Some("signature"));
+    }
+
+    #[test]
+    fn test_validate_abbreviation() {
+        assert!(validate_abbreviation(";sig").is_ok());
+        assert!(validate_abbreviation(";").is_err());
+        assert!(validate_abbreviation("; sig").is_err());
+        assert!(validate_abbreviation(&"x".repeat(33)).is_err());
+    }
+}
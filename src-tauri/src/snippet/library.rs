@@ -0,0 +1,171 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+
+use super::Snippet;
+
+/// Folder path with segments trimmed and empty ones dropped: `" Work//Mail "`
+/// becomes `"Work/Mail"`. `""` is the top level.
+pub fn normalize_folder(folder: &str) -> String {
+    folder
+        .split('/')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Tags trimmed, lowercased and without a leading `#`, sorted and deduplicated.
+pub fn normalize_tags(tags: &[String]) -> Vec<String> {
+    let tags: BTreeSet<String> = tags
+        .iter()
+        .map(|t| t.trim().trim_start_matches('#').trim().to_lowercase())
+        .filter(|t| !t.is_empty())
+        .collect();
+    tags.into_iter().collect()
+}
+
+/// Whether `folder` is `parent` or one of its subfolders.
+pub fn in_folder(folder: &str, parent: &str) -> bool {
+    parent.is_empty()
+        || folder == parent
+        || folder.strip_prefix(parent).is_some_and(|rest| rest.starts_with('/'))
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnippetQuery {
+    /// Words that must all appear in the name, description, text, tags or folder
+    #[serde(default)]
+    pub text: String,
+    /// Only this folder and its subfolders
+    #[serde(default)]
+    pub folder: Option<String>,
+    /// Only snippets carrying every one of these tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Snippets matching `query`, those with the words in their name first.
+pub fn search(snippets: Vec<Snippet>, query: &SnippetQuery) -> Vec<Snippet> {
+    let folder = query.folder.as_deref().map(normalize_folder);
+    let tags = normalize_tags(&query.tags);
+    let terms: Vec<String> = query.text.split_whitespace().map(str::to_lowercase).collect();
+
+    let mut matches: Vec<(usize, Snippet)> = snippets
+        .into_iter()
+        .filter(|s| match &folder {
+            Some(folder) => in_folder(&s.folder, folder),
+            None => true,
+        })
+        .filter(|s| tags.iter().all(|t| s.tags.contains(t)))
+        .filter_map(|s| {
+            let name = s.name.to_lowercase();
+            let rest = format!("{}\n{}\n{}\n{}", s.description, s.template, s.tags.join(" "), s.folder).to_lowercase();
+            let mut score = 0;
+            for term in &terms {
+                if name.contains(term.as_str()) {
+                    score += 2;
+                } else if rest.contains(term.as_str()) {
+                    score += 1;
+                } else {
+                    return None;
+                }
+            }
+            Some((score, s))
+        })
+        .collect();
+    // Stable, so equally good matches keep their order
+    matches.sort_by_key(|(score, _)| Reverse(*score));
+    matches.into_iter().map(|(_, s)| s).collect()
+}
+
+/// Every folder in use, including parents of nested ones, sorted.
+pub fn folders(snippets: &[Snippet]) -> Vec<String> {
+    let mut folders = BTreeSet::new();
+    for snippet in snippets {
+        let mut path = String::new();
+        for segment in snippet.folder.split('/').filter(|s| !s.is_empty()) {
+            if !path.is_empty() {
+                path.push('/');
+            }
+            path.push_str(segment);
+            folders.insert(path.clone());
+        }
+    }
+    folders.into_iter().collect()
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: usize,
+}
+
+/// Tags in use with the number of snippets carrying each, sorted by tag.
+pub fn tags(snippets: &[Snippet]) -> Vec<TagCount> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for tag in snippets.iter().flat_map(|s| &s.tags) {
+        *counts.entry(tag).or_default() += 1;
+    }
+    counts
+        .into_iter()
+        .map(|(tag, count)| TagCount { tag: tag.to_string(), count })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snippet(name: &str, folder: &str, tags: &[&str], template: &str) -> Snippet {
+        Snippet {
+            id: name.to_lowercase(),
+            name: name.to_string(),
+            template: template.to_string(),
+            folder: folder.to_string(),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Snippet::default()
+        }
+    }
+
+    fn names(snippets: &[Snippet]) -> Vec<&str> {
+        snippets.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize_folder(" Work//Mail / "), "Work/Mail");
+        assert_eq!(normalize_folder("/"), "");
+        let tags = ["#Work".to_string(), " work ".to_string(), "".to_string(), "Sig".to_string()];
+        assert_eq!(normalize_tags(&tags), ["sig", "work"]);
+        assert!(in_folder("Work/Mail", "Work"));
+        assert!(!in_folder("Workshop", "Work"));
+        assert!(in_folder("Workshop", ""));
+    }
+
+    #[test]
+    fn test_search() {
+        let snippets = vec![
+            snippet("Address", "Personal", &["home"], "221B Baker Street"),
+            snippet("Mail signature", "Work/Mail", &["sig", "work"], "Best regards"),
+            snippet("Standup", "Work", &["work"], "Yesterday: ... mail ..."),
+        ];
+        let query = |text: &str, folder: Option<&str>, tags: &[&str]| SnippetQuery {
+            text: text.to_string(),
+            folder: folder.map(str::to_string),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+        };
+
+        assert_eq!(names(&search(snippets.clone(), &query("", None, &[]))), ["Address", "Mail signature", "Standup"]);
+        assert_eq!(names(&search(snippets.clone(), &query("MAIL", None, &[]))), ["Mail signature", "Standup"]);
+        assert_eq!(names(&search(snippets.clone(), &query("baker street", None, &[]))), ["Address"]);
+        assert_eq!(names(&search(snippets.clone(), &query("", Some("Work"), &[]))), ["Mail signature", "Standup"]);
+        assert_eq!(names(&search(snippets.clone(), &query("", None, &["#Sig"]))), ["Mail signature"]);
+        assert!(search(snippets.clone(), &query("mail", Some("Personal"), &[])).is_empty());
+
+        assert_eq!(folders(&snippets), ["Personal", "Work", "Work/Mail"]);
+        let counts = tags(&snippets);
+        assert_eq!(counts[2], TagCount { tag: "work".to_string(), count: 2 });
+    }
+}
@@ -0,0 +1,261 @@
+use chrono::{DateTime, Local};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::Write;
+use thiserror::Error;
+
+mod abbreviation;
+mod library;
+
+pub use abbreviation::{validate_abbreviation, Abbreviation, AbbreviationMatcher, Typed};
+pub use library::{folders, in_folder, normalize_folder, normalize_tags, search, tags, SnippetQuery, TagCount};
+
+/// A named text template. Placeholders:
+/// - `{date}`, `{time}`, `{datetime}`, or `{date:%d.%m.%Y}` with a chrono format
+/// - `{clipboard}`: current clipboard text
+/// - `{cursor}`: where the caret should end up after pasting
+/// - `{input:name}` / `{input:name=default}`: a value the user fills in
+///
+/// Literal braces are written `{{` and `}}`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Snippet {
+    pub id: String,
+    pub name: String,
+    pub template: String,
+    #[serde(default)]
+    pub description: String,
+    /// `/`-separated path such as `Work/Mail`; empty for the top level
+    #[serde(default)]
+    pub folder: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Typed text that is replaced with the snippet, e.g. `;sig`
+    #[serde(default)]
+    pub abbreviation: Option<String>,
+}
+
+#[derive(Debug, Error)]
+pub enum SnippetError {
+    #[error("snippet not found: {0}")]
+    NotFound(String),
+    #[error("invalid template: {0}")]
+    InvalidTemplate(String),
+    #[error("unknown placeholder: {{{0}}}")]
+    UnknownPlaceholder(String),
+    #[error("missing values for: {}", .0.join(", "))]
+    MissingValues(Vec<String>),
+    #[error("invalid abbreviation: {0}")]
+    InvalidAbbreviation(String),
+}
+
+/// A fill-in the UI should prompt for.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnippetField {
+    pub name: String,
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderedSnippet {
+    pub text: String,
+    /// Character offset of `{cursor}` in `text`
+    pub cursor: Option<usize>,
+    /// Fields without a value or default; rendered as empty text
+    pub missing: Vec<String>,
+}
+
+pub struct RenderContext {
+    pub clipboard: Option<String>,
+    pub values: HashMap<String, String>,
+    pub now: DateTime<Local>,
+}
+
+enum Token<'a> {
+    Text(&'a str),
+    Placeholder(&'a str),
+}
+
+fn tokenize(template: &str) -> Result<Vec<Token<'_>>, SnippetError> {
+    let mut tokens = Vec::new();
+    let mut rest = template;
+
+    while let Some(pos) = rest.find(['{', '}']) {
+        if pos > 0 {
+            tokens.push(Token::Text(&rest[..pos]));
+        }
+        let brace = &rest[pos..pos + 1];
+        rest = &rest[pos + 1..];
+
+        if rest.starts_with(brace) {
+            // Escaped `{{` / `}}`
+            tokens.push(Token::Text(brace));
+            rest = &rest[1..];
+        } else if brace == "}" {
+            return Err(SnippetError::InvalidTemplate("unmatched '}' (use '}}')".to_string()));
+        } else {
+            let end = rest
+                .find('}')
+                .ok_or_else(|| SnippetError::InvalidTemplate("unclosed '{'".to_string()))?;
+            tokens.push(Token::Placeholder(rest[..end].trim()));
+            rest = &rest[end + 1..];
+        }
+    }
+
+    if !rest.is_empty() {
+        tokens.push(Token::Text(rest));
+    }
+    Ok(tokens)
+}
+
+fn parse_field(spec: &str) -> SnippetField {
+    match spec.split_once('=') {
+        Some((name, default)) => SnippetField {
+            name: name.trim().to_string(),
+            default: Some(default.to_string()),
+        },
+        None => SnippetField {
+            name: spec.trim().to_string(),
+            default: None,
+        },
+    }
+}
+
+/// Fill-in fields in template order, without duplicates.
+pub fn snippet_fields(template: &str) -> Result<Vec<SnippetField>, SnippetError> {
+    let mut fields: Vec<SnippetField> = Vec::new();
+    for token in tokenize(template)? {
+        if let Token::Placeholder(p) = token {
+            if let Some(spec) = p.strip_prefix("input:") {
+                let field = parse_field(spec);
+                if !fields.iter().any(|f| f.name == field.name) {
+                    fields.push(field);
+                }
+            }
+        }
+    }
+    Ok(fields)
+}
+
+/// Check the template parses and uses only known placeholders.
+pub fn validate_template(template: &str) -> Result<(), SnippetError> {
+    let ctx = RenderContext {
+        clipboard: None,
+        values: HashMap::new(),
+        now: Local::now(),
+    };
+    render(template, &ctx).map(|_| ())
+}
+
+pub fn render(template: &str, ctx: &RenderContext) -> Result<RenderedSnippet, SnippetError> {
+    let mut text = String::with_capacity(template.len());
+    let mut cursor = None;
+    let mut missing: Vec<String> = Vec::new();
+
+    for token in tokenize(template)? {
+        let placeholder = match token {
+            Token::Text(t) => {
+                text.push_str(t);
+                continue;
+            }
+            Token::Placeholder(p) => p,
+        };
+
+        let (name, arg) = match placeholder.split_once(':') {
+            Some((name, arg)) => (name.trim(), Some(arg)),
+            None => (placeholder, None),
+        };
+
+        match (name, arg) {
+            ("date", None) => push_time(&mut text, &ctx.now, "%Y-%m-%d")?,
+            ("time", None) => push_time(&mut text, &ctx.now, "%H:%M")?,
+            ("datetime", None) => push_time(&mut text, &ctx.now, "%Y-%m-%d %H:%M")?,
+            ("date", Some(fmt)) => push_time(&mut text, &ctx.now, fmt)?,
+            ("clipboard", None) => text.push_str(ctx.clipboard.as_deref().unwrap_or("")),
+            ("cursor", None) => {
+                if cursor.is_some() {
+                    return Err(SnippetError::InvalidTemplate("more than one {cursor}".to_string()));
+                }
+                cursor = Some(text.chars().count());
+            }
+            ("input", Some(spec)) => {
+                let field = parse_field(spec);
+                if field.name.is_empty() {
+                    return Err(SnippetError::InvalidTemplate("input field without a name".to_string()));
+                }
+                match ctx.values.get(&field.name).or(field.default.as_ref()) {
+                    Some(value) => text.push_str(value),
+                    None => {
+                        if !missing.contains(&field.name) {
+                            missing.push(field.name);
+                        }
+                    }
+                }
+            }
+            _ => return Err(SnippetError::UnknownPlaceholder(placeholder.to_string())),
+        }
+    }
+
+    Ok(RenderedSnippet { text, cursor, missing })
+}
+
+fn push_time(out: &mut String, now: &DateTime<Local>, fmt: &str) -> Result<(), SnippetError> {
+    // chrono reports bad format specifiers as a fmt::Error while writing
+    write!(out, "{}", now.format(fmt))
+        .map_err(|_| SnippetError::InvalidTemplate(format!("bad date format '{}'", fmt)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn ctx(values: &[(&str, &str)]) -> RenderContext {
+        RenderContext {
+            clipboard: Some("CLIP".to_string()),
+            values: values.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect(),
+            now: Local.with_ymd_and_hms(2024, 3, 9, 14, 5, 0).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_render_builtin_placeholders() {
+        let out = render("{date} {time} [{clipboard}] {date:%d.%m.%Y}", &ctx(&[])).unwrap();
+        assert_eq!(out.text, "2024-03-09 14:05 [CLIP] 09.03.2024");
+        assert!(out.cursor.is_none());
+    }
+
+    #[test]
+    fn test_render_cursor_and_escapes() {
+        let out = render("fn {{ 你{cursor} }}", &ctx(&[])).unwrap();
+        assert_eq!(out.text, "fn { 你 }");
+        assert_eq!(out.cursor, Some(6));
+    }
+
+    #[test]
+    fn test_render_inputs() {
+        let template = "Hi {input:name}, from {input:sender=Team} {input:name}";
+        let out = render(template, &ctx(&[("name", "Ann")])).unwrap();
+        assert_eq!(out.text, "Hi Ann, from Team Ann");
+        assert!(out.missing.is_empty());
+
+        let out = render(template, &ctx(&[])).unwrap();
+        assert_eq!(out.missing, vec!["name"]);
+
+        let fields = snippet_fields(template).unwrap();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[1].default.as_deref(), Some("Team"));
+    }
+
+    #[test]
+    fn test_invalid_templates() {
+        assert!(matches!(validate_template("{nope}"), Err(SnippetError::UnknownPlaceholder(_))));
+        assert!(validate_template("open {date").is_err());
+        assert!(validate_template("stray }").is_err());
+        assert!(validate_template("{cursor}{cursor}").is_err());
+        assert!(validate_template("{date:%Q}").is_err());
+        assert!(validate_template("plain {{text}}").is_ok());
+    }
+}
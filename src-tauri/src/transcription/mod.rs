@@ -0,0 +1,133 @@
+mod whisper;
+
+use serde::Serialize;
+use std::io::{BufRead, BufReader, ErrorKind, Read};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use thiserror::Error;
+
+use crate::clipboard::{self, ClipboardError};
+use crate::config::{ConfigError, ConfigManager};
+use crate::text;
+
+#[derive(Debug, Error)]
+pub enum TranscriptionError {
+    #[error("not a supported audio file (wav, mp3, flac, ogg)")]
+    NotAudio,
+    #[error("audio file not found: {0}")]
+    FileNotFound(String),
+    #[error("no whisper model configured; set the model file in settings")]
+    NoModel,
+    #[error("whisper.cpp '{0}' not found; install it or set its path in settings")]
+    EngineNotFound(String),
+    #[error("transcription failed: {0}")]
+    Engine(String),
+    #[error("no speech found in the audio")]
+    NoSpeech,
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Clipboard(#[from] ClipboardError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+}
+
+/// Emitted as `transcription:progress` while a file is transcribed.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranscriptionProgress {
+    pub path: String,
+    pub percent: u8,
+}
+
+/// Transcribe the audio file copied as `text` with whisper.cpp and put the
+/// transcript on the clipboard, where the panel treats it like any copied
+/// text. Long files report progress as they go.
+pub fn transcribe<R: Runtime>(
+    app: &AppHandle<R>,
+    text: &str,
+) -> Result<String, TranscriptionError> {
+    let config = app.state::<ConfigManager>().effective_config()?;
+    let model = config.whisper_model.trim();
+    if model.is_empty() {
+        return Err(TranscriptionError::NoModel);
+    }
+    let copied = text::audio_file_path(text).ok_or(TranscriptionError::NotAudio)?;
+    let path = expand_home(app, copied);
+    if !path.is_file() {
+        return Err(TranscriptionError::FileNotFound(copied.to_string()));
+    }
+    let audio = path.to_string_lossy().into_owned();
+
+    let engine = config.whisper_command.trim();
+    let mut child = Command::new(engine)
+        .args(whisper::args(model, config.whisper_language.trim(), &audio))
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| match e.kind() {
+            ErrorKind::NotFound => TranscriptionError::EngineNotFound(engine.to_string()),
+            _ => TranscriptionError::Io(e),
+        })?;
+
+    // Progress and errors arrive on stderr while the transcript builds up on stdout
+    let stderr = child.stderr.take().map(|stderr| {
+        let app = app.clone();
+        let audio = audio.clone();
+        std::thread::spawn(move || {
+            let mut last_line = String::new();
+            for line in BufReader::new(stderr).lines().map_while(Result::ok) {
+                match whisper::parse_progress(&line) {
+                    Some(percent) => emit_progress(&app, &audio, percent),
+                    None if !line.trim().is_empty() => last_line = line,
+                    None => {}
+                }
+            }
+            last_line
+        })
+    });
+    let mut output = String::new();
+    if let Some(mut stdout) = child.stdout.take() {
+        stdout.read_to_string(&mut output)?;
+    }
+    let status = child.wait()?;
+    let last_line = stderr
+        .and_then(|handle| handle.join().ok())
+        .unwrap_or_default();
+
+    if !status.success() {
+        return Err(TranscriptionError::Engine(last_line.trim().to_string()));
+    }
+    let transcript = whisper::clean_transcript(&output);
+    if transcript.is_empty() {
+        return Err(TranscriptionError::NoSpeech);
+    }
+    emit_progress(app, &audio, 100);
+    clipboard::write_clipboard(app, &transcript)?;
+    log::info!(
+        "Transcribed {} to {} character(s)",
+        audio,
+        transcript.chars().count()
+    );
+    Ok(transcript)
+}
+
+fn emit_progress<R: Runtime>(app: &AppHandle<R>, path: &str, percent: u8) {
+    let progress = TranscriptionProgress {
+        path: path.to_string(),
+        percent,
+    };
+    if let Err(e) = app.emit("transcription:progress", progress) {
+        log::error!("Failed to emit transcription:progress event: {}", e);
+    }
+}
+
+/// `~/x` as a real path; child processes don't expand it.
+pub(crate) fn expand_home<R: Runtime>(app: &AppHandle<R>, path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), app.path().home_dir()) {
+        (Some(rest), Ok(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
@@ -0,0 +1,72 @@
+/// Arguments for whisper.cpp's CLI: plain text on stdout, progress on stderr.
+pub fn args(model: &str, language: &str, audio: &str) -> Vec<String> {
+    [
+        "--model",
+        model,
+        "--language",
+        language,
+        "--no-timestamps",
+        "--print-progress",
+        "--file",
+        audio,
+    ]
+    .iter()
+    .map(|a| a.to_string())
+    .collect()
+}
+
+/// Percent done from a progress line like
+/// `whisper_print_progress_callback: progress =  42%`.
+pub fn parse_progress(line: &str) -> Option<u8> {
+    let (_, rest) = line.split_once("progress =")?;
+    let percent = rest.trim().strip_suffix('%')?.trim().parse::<u8>().ok()?;
+    (percent <= 100).then_some(percent)
+}
+
+/// Segments joined into one text, without the markers whisper prints for
+/// silence and noise (`[BLANK_AUDIO]`, `(music)`).
+pub fn clean_transcript(output: &str) -> String {
+    output
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !is_marker(l))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn is_marker(segment: &str) -> bool {
+    let wrapped = |open: char, close: char| segment.starts_with(open) && segment.ends_with(close);
+    wrapped('[', ']') || wrapped('(', ')')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_progress() {
+        assert_eq!(
+            parse_progress("whisper_print_progress_callback: progress =  42%"),
+            Some(42)
+        );
+        assert_eq!(
+            parse_progress("whisper_print_progress_callback: progress = 100%"),
+            Some(100)
+        );
+        assert_eq!(
+            parse_progress("whisper_init_from_file: loading model"),
+            None
+        );
+        assert_eq!(parse_progress("progress = 250%"), None);
+    }
+
+    #[test]
+    fn test_clean_transcript() {
+        let output = " [BLANK_AUDIO]\n 大家好，今天讨论发布计划。\n\n (music)\n Let's start.\n";
+        assert_eq!(
+            clean_transcript(output),
+            "大家好，今天讨论发布计划。\nLet's start."
+        );
+        assert_eq!(clean_transcript(" [BLANK_AUDIO]\n"), "");
+    }
+}
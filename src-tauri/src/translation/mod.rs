@@ -0,0 +1,138 @@
+mod service;
+
+pub use service::SERVICES;
+
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager, Runtime};
+use thiserror::Error;
+
+use crate::ai::intent::translation_target;
+use crate::clipboard::{self, ClipboardError};
+use crate::commands::AIState;
+use crate::config::{ConfigError, ConfigManager, UsageMetric};
+use crate::i18n::Locale;
+use crate::privacy::{self, ShieldAction, ShieldDecision, ShieldPolicy};
+use service::Service;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+#[derive(Debug, Error)]
+pub enum TranslationError {
+    #[error("no translation service configured")]
+    NotConfigured,
+    #[error("no API key saved for {0}")]
+    MissingKey(&'static str),
+    #[error("nothing to translate")]
+    Empty,
+    #[error("request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("{service} answered {status}: {message}")]
+    Status {
+        service: &'static str,
+        status: u16,
+        message: String,
+    },
+    #[error("unexpected response from {0}: {1}")]
+    Parse(&'static str, String),
+    #[error(transparent)]
+    Clipboard(#[from] ClipboardError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TranslationResult {
+    pub text: String,
+    /// `DeepL` or `Google`
+    pub service: String,
+    /// Source language as detected by the service
+    pub detected_language: Option<String>,
+    /// Target language code as sent to the service
+    pub target_language: String,
+}
+
+/// Translate `text` with the configured machine translation service. The
+/// target defaults to the one its translate chip offers. Personal data is
+/// masked under the shield policy; nobody can be asked, so "ask" masks.
+pub async fn translate<R: Runtime>(
+    app: &AppHandle<R>,
+    text: &str,
+    target: Option<Locale>,
+) -> Result<TranslationResult, TranslationError> {
+    if text.trim().is_empty() {
+        return Err(TranslationError::Empty);
+    }
+    let config = app.state::<ConfigManager>();
+    let app_config = config.effective_config()?;
+    let service = Service::from_name(&app_config.translation_service)
+        .ok_or(TranslationError::NotConfigured)?;
+    let key = config
+        .get_api_key(service.name())?
+        .filter(|k| !k.trim().is_empty())
+        .ok_or(TranslationError::MissingKey(service.name()))?;
+    let target = service.target_code(target.unwrap_or_else(|| translation_target(text)));
+
+    let action = privacy::resolve_shield(
+        ShieldPolicy::from_name(&app_config.shield_policy),
+        true,
+        &privacy::scan_pii(text),
+        Some(ShieldDecision::Mask),
+    );
+    let masked = (action == ShieldAction::Mask).then(|| privacy::mask_pii(text));
+    if let Some(m) = &masked {
+        config.track(UsageMetric::PiiMasked, m.mapping.mappings.len() as u64);
+    }
+    let sent = masked.as_ref().map_or(text, |m| m.masked.as_str());
+
+    let client = app.state::<Arc<AIState>>().http_client();
+    let response = service
+        .request(&client, key.trim(), sent, target)
+        .timeout(REQUEST_TIMEOUT)
+        .send()
+        .await?;
+    let status = response.status();
+    let body = response.text().await?;
+    if !status.is_success() {
+        return Err(TranslationError::Status {
+            service: service.name(),
+            status: status.as_u16(),
+            message: service
+                .error_message(&body)
+                .unwrap_or_else(|| body.chars().take(200).collect()),
+        });
+    }
+    let translated = service
+        .parse(&body)
+        .map_err(|e| TranslationError::Parse(service.name(), e))?;
+
+    Ok(TranslationResult {
+        text: match &masked {
+            Some(m) => privacy::restore_pii(&translated.text, &m.mapping),
+            None => translated.text,
+        },
+        service: service.name().to_string(),
+        detected_language: translated.detected_language,
+        target_language: target.to_string(),
+    })
+}
+
+/// Translate the clipboard text and put the translation in its place.
+pub async fn translate_clipboard<R: Runtime>(
+    app: &AppHandle<R>,
+) -> Result<TranslationResult, TranslationError> {
+    let text = clipboard::read_clipboard(app)?
+        .text
+        .ok_or(ClipboardError::Empty)?;
+    let result = translate(app, &text, None).await?;
+    clipboard::write_clipboard(app, &result.text)?;
+    log::info!(
+        "Translated clipboard with {} ({} -> {})",
+        result.service,
+        result.detected_language.as_deref().unwrap_or("?"),
+        result.target_language
+    );
+    Ok(result)
+}
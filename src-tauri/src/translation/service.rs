@@ -0,0 +1,199 @@
+use reqwest::{Client, RequestBuilder};
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::i18n::Locale;
+
+/// Names accepted in `AppConfig::translation_service`; `""` translates with
+/// the AI provider. Each service's API key is stored under its name.
+pub const SERVICES: &[&str] = &["", "DeepL", "Google"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Service {
+    DeepL,
+    Google,
+}
+
+/// Translated text and the source language the service detected.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Translated {
+    pub text: String,
+    pub detected_language: Option<String>,
+}
+
+impl Service {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "DeepL" => Some(Service::DeepL),
+            "Google" => Some(Service::Google),
+            _ => None,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Service::DeepL => "DeepL",
+            Service::Google => "Google",
+        }
+    }
+
+    /// The service's code for `locale` as a target language.
+    pub fn target_code(self, locale: Locale) -> &'static str {
+        match (self, locale) {
+            (Service::DeepL, Locale::En) => "EN-US",
+            (Service::DeepL, Locale::ZhCn) => "ZH-HANS",
+            (Service::DeepL, Locale::Ja) => "JA",
+            (Service::Google, Locale::En) => "en",
+            (Service::Google, Locale::ZhCn) => "zh-CN",
+            (Service::Google, Locale::Ja) => "ja",
+        }
+    }
+
+    /// A request translating `text` to `target`; the source language is
+    /// left for the service to detect.
+    pub fn request(self, client: &Client, key: &str, text: &str, target: &str) -> RequestBuilder {
+        match self {
+            Service::DeepL => {
+                // Free plan keys end in `:fx` and have their own host
+                let host = if key.ends_with(":fx") {
+                    "api-free.deepl.com"
+                } else {
+                    "api.deepl.com"
+                };
+                client
+                    .post(format!("https://{}/v2/translate", host))
+                    .header("Authorization", format!("DeepL-Auth-Key {}", key))
+                    .json(&json!({ "text": [text], "target_lang": target }))
+            }
+            Service::Google => client
+                .post("https://translation.googleapis.com/language/translate/v2")
+                .query(&[("key", key)])
+                .json(&json!({ "q": text, "target": target, "format": "text" })),
+        }
+    }
+
+    /// Read a successful response body.
+    pub fn parse(self, body: &str) -> Result<Translated, String> {
+        let translated = match self {
+            Service::DeepL => {
+                let response: DeepLResponse =
+                    serde_json::from_str(body).map_err(|e| e.to_string())?;
+                response
+                    .translations
+                    .into_iter()
+                    .next()
+                    .map(|t| Translated {
+                        text: t.text,
+                        detected_language: t.detected_source_language.map(|l| l.to_lowercase()),
+                    })
+            }
+            Service::Google => {
+                let response: GoogleResponse =
+                    serde_json::from_str(body).map_err(|e| e.to_string())?;
+                response
+                    .data
+                    .translations
+                    .into_iter()
+                    .next()
+                    .map(|t| Translated {
+                        text: t.translated_text,
+                        detected_language: t.detected_source_language,
+                    })
+            }
+        };
+        translated.ok_or_else(|| "empty response".to_string())
+    }
+
+    /// The service's own explanation in an error response, if it gave one.
+    pub fn error_message(self, body: &str) -> Option<String> {
+        let value: serde_json::Value = serde_json::from_str(body).ok()?;
+        let message = match self {
+            Service::DeepL => value.get("message"),
+            Service::Google => value.get("error").and_then(|e| e.get("message")),
+        };
+        message.and_then(|m| m.as_str()).map(str::to_string)
+    }
+}
+
+#[derive(Deserialize)]
+struct DeepLResponse {
+    translations: Vec<DeepLTranslation>,
+}
+
+#[derive(Deserialize)]
+struct DeepLTranslation {
+    text: String,
+    detected_source_language: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct GoogleResponse {
+    data: GoogleData,
+}
+
+#[derive(Deserialize)]
+struct GoogleData {
+    translations: Vec<GoogleTranslation>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GoogleTranslation {
+    translated_text: String,
+    detected_source_language: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requests() {
+        let client = Client::new();
+        let request = Service::DeepL
+            .request(&client, "abc:fx", "你好", "EN-US")
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.url().as_str(),
+            "https://api-free.deepl.com/v2/translate"
+        );
+        assert_eq!(request.headers()["authorization"], "DeepL-Auth-Key abc:fx");
+        let body = request.body().and_then(|b| b.as_bytes()).unwrap();
+        assert_eq!(
+            serde_json::from_slice::<serde_json::Value>(body).unwrap(),
+            json!({ "text": ["你好"], "target_lang": "EN-US" })
+        );
+
+        let request = Service::Google
+            .request(&client, "k", "hi", "zh-CN")
+            .build()
+            .unwrap();
+        assert_eq!(
+            request.url().as_str(),
+            "https://translation.googleapis.com/language/translate/v2?key=k"
+        );
+    }
+
+    #[test]
+    fn test_parse() {
+        let deepl = r#"{"translations":[{"detected_source_language":"ZH","text":"Hello"}]}"#;
+        assert_eq!(
+            Service::DeepL.parse(deepl).unwrap(),
+            Translated {
+                text: "Hello".to_string(),
+                detected_language: Some("zh".to_string())
+            }
+        );
+        let google = r#"{"data":{"translations":[{"translatedText":"你好","detectedSourceLanguage":"en"}]}}"#;
+        assert_eq!(Service::Google.parse(google).unwrap().text, "你好");
+        assert!(Service::DeepL.parse(r#"{"translations":[]}"#).is_err());
+
+        let error = r#"{"error":{"code":400,"message":"API key not valid."}}"#;
+        assert_eq!(
+            Service::Google.error_message(error).as_deref(),
+            Some("API key not valid.")
+        );
+        assert_eq!(Service::DeepL.error_message("Forbidden"), None);
+    }
+}
@@ -0,0 +1,226 @@
+use tauri::menu::{CheckMenuItem, IsMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{AppHandle, Manager, Runtime};
+
+use crate::clipboard::{self, RecentClipboard};
+use crate::config::{ConfigManager, RuleStat, UsageTarget};
+use crate::hotkey;
+use crate::i18n::tr;
+use crate::regex::{self, Rule};
+
+const TRAY_ID: &str = "main";
+const MAX_RECENT_ITEMS: usize = 10;
+const MAX_QUICK_RULES: usize = 6;
+const RECENT_LABEL_CHARS: usize = 40;
+
+// Menu item ids; recent entries and rules carry their index or rule id after the colon
+const TOGGLE_PANEL: &str = "toggle_panel";
+const PAUSE_CAPTURE: &str = "pause_capture";
+const QUIT: &str = "quit";
+const RECENT_PREFIX: &str = "recent:";
+const RULE_PREFIX: &str = "rule:";
+
+/// Create the tray icon. A left click toggles the panel; the menu holds
+/// everything else.
+pub fn init<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<()> {
+    let mut builder = TrayIconBuilder::with_id(TRAY_ID)
+        .tooltip(status_text(app))
+        .menu(&build_menu(app)?)
+        .show_menu_on_left_click(false)
+        .on_menu_event(|app, event| handle_menu_event(app, event.id().as_ref()))
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click {
+                button: MouseButton::Left,
+                button_state: MouseButtonState::Up,
+                ..
+            } = event
+            {
+                hotkey::toggle_panel(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        builder = builder.icon(icon.clone());
+    }
+    builder.build(app)?;
+    Ok(())
+}
+
+/// Rebuild the menu after the clipboard history, capture state or locale
+/// changed. Does nothing before `init`.
+pub fn refresh<R: Runtime>(app: &AppHandle<R>) {
+    let Some(tray) = app.tray_by_id(TRAY_ID) else {
+        return;
+    };
+    let menu = match build_menu(app) {
+        Ok(menu) => menu,
+        Err(e) => {
+            log::warn!("Failed to rebuild tray menu: {}", e);
+            return;
+        }
+    };
+    if let Err(e) = tray.set_menu(Some(menu)) {
+        log::warn!("Failed to update tray menu: {}", e);
+    }
+    if let Err(e) = tray.set_tooltip(Some(status_text(app))) {
+        log::warn!("Failed to update tray tooltip: {}", e);
+    }
+}
+
+fn status_text<R: Runtime>(app: &AppHandle<R>) -> &'static str {
+    if app.state::<RecentClipboard>().is_paused() {
+        tr("tray.status_paused")
+    } else {
+        tr("tray.status_running")
+    }
+}
+
+fn build_menu<R: Runtime>(app: &AppHandle<R>) -> tauri::Result<Menu<R>> {
+    let status = MenuItem::with_id(app, "status", status_text(app), false, None::<&str>)?;
+    let toggle = MenuItem::with_id(app, TOGGLE_PANEL, tr("tray.toggle_panel"), true, None::<&str>)?;
+
+    let recent_items = app
+        .state::<RecentClipboard>()
+        .items()
+        .iter()
+        .take(MAX_RECENT_ITEMS)
+        .enumerate()
+        .map(|(i, text)| {
+            let one_line = text.split_whitespace().collect::<Vec<_>>().join(" ");
+            let label = clipboard::preview(&one_line, RECENT_LABEL_CHARS);
+            MenuItem::with_id(app, format!("{}{}", RECENT_PREFIX, i), label, true, None::<&str>)
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let recent = submenu(app, tr("tray.recent"), &recent_items)?;
+
+    let rule_items = quick_rules(app)
+        .into_iter()
+        .map(|rule| {
+            let id = format!("{}{}", RULE_PREFIX, rule.id);
+            MenuItem::with_id(app, id, rule.name, true, None::<&str>)
+        })
+        .collect::<tauri::Result<Vec<_>>>()?;
+    let rules = submenu(app, tr("tray.rules"), &rule_items)?;
+
+    let paused = app.state::<RecentClipboard>().is_paused();
+    let pause = CheckMenuItem::with_id(app, PAUSE_CAPTURE, tr("tray.pause"), true, paused, None::<&str>)?;
+    let quit = MenuItem::with_id(app, QUIT, tr("tray.quit"), true, None::<&str>)?;
+
+    Menu::with_items(
+        app,
+        &[
+            &status,
+            &PredefinedMenuItem::separator(app)?,
+            &toggle,
+            &recent,
+            &rules,
+            &PredefinedMenuItem::separator(app)?,
+            &pause,
+            &quit,
+        ],
+    )
+}
+
+/// A submenu of `items`, or a disabled placeholder inside it when there are none.
+fn submenu<R: Runtime>(app: &AppHandle<R>, text: &str, items: &[MenuItem<R>]) -> tauri::Result<Submenu<R>> {
+    if items.is_empty() {
+        let empty = MenuItem::new(app, tr("tray.empty"), false, None::<&str>)?;
+        return Submenu::with_items(app, text, true, &[&empty]);
+    }
+    let items: Vec<&dyn IsMenuItem<R>> = items.iter().map(|i| i as &dyn IsMenuItem<R>).collect();
+    Submenu::with_items(app, text, true, &items)
+}
+
+/// Rules in the user's panel order, most used first.
+fn quick_rules<R: Runtime>(app: &AppHandle<R>) -> Vec<Rule> {
+    let config = app.state::<ConfigManager>();
+    let mut rules = regex::get_builtin_rules();
+    rules.extend(config.get_custom_rules().unwrap_or_else(|e| {
+        log::warn!("Failed to load custom rules for the tray: {}", e);
+        Vec::new()
+    }));
+    let order = config.get_rule_order().unwrap_or_default();
+    let stats = config.get_rule_stats().unwrap_or_default();
+    pick_quick_rules(regex::arrange_rules(rules, &order, None), &stats, MAX_QUICK_RULES)
+}
+
+/// Up to `max` of `rules`: the most used ones (`stats` is sorted by count),
+/// then the first ones in `rules` order.
+fn pick_quick_rules(rules: Vec<Rule>, stats: &[RuleStat], max: usize) -> Vec<Rule> {
+    let mut picked: Vec<Rule> = stats
+        .iter()
+        .filter(|s| s.target == UsageTarget::Rule)
+        .filter_map(|s| rules.iter().find(|r| r.id == s.id).cloned())
+        .take(max)
+        .collect();
+    for rule in rules {
+        if picked.len() >= max {
+            break;
+        }
+        if !picked.iter().any(|r| r.id == rule.id) {
+            picked.push(rule);
+        }
+    }
+    picked
+}
+
+fn handle_menu_event<R: Runtime>(app: &AppHandle<R>, id: &str) {
+    match id {
+        TOGGLE_PANEL => hotkey::toggle_panel(app),
+        PAUSE_CAPTURE => {
+            let recent = app.state::<RecentClipboard>();
+            recent.set_paused(!recent.is_paused());
+            log::info!("Clipboard capture {}", if recent.is_paused() { "paused" } else { "resumed" });
+            refresh(app);
+        }
+        QUIT => app.exit(0),
+        _ => {
+            if let Some(index) = id.strip_prefix(RECENT_PREFIX).and_then(|i| i.parse::<usize>().ok()) {
+                let Some(text) = app.state::<RecentClipboard>().items().into_iter().nth(index) else {
+                    return;
+                };
+                if let Err(e) = clipboard::write_clipboard(app, &text) {
+                    log::error!("Failed to restore clipboard item from tray: {}", e);
+                }
+            } else if let Some(rule_id) = id.strip_prefix(RULE_PREFIX) {
+                let app = app.clone();
+                let rule_ids = vec![rule_id.to_string()];
+                tauri::async_runtime::spawn_blocking(move || {
+                    if let Err(e) = clipboard::transform_clipboard(&app, &rule_ids) {
+                        log::error!("Tray rule {:?} failed: {}", rule_ids, e);
+                    }
+                });
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rule(id: &str) -> Rule {
+        Rule {
+            id: id.to_string(),
+            ..Rule::default()
+        }
+    }
+
+    fn used(id: &str) -> RuleStat {
+        RuleStat {
+            target: UsageTarget::Rule,
+            id: id.to_string(),
+            count: 1,
+            last_used: 0,
+        }
+    }
+
+    #[test]
+    fn test_quick_rules_put_most_used_first() {
+        let rules = vec![rule("a"), rule("b"), rule("c"), rule("d")];
+        let mut chip = used("a");
+        chip.target = UsageTarget::Chip;
+        let stats = [used("c"), chip, used("gone")];
+        let ids: Vec<String> = pick_quick_rules(rules, &stats, 3).into_iter().map(|r| r.id).collect();
+        assert_eq!(ids, ["c", "a", "b"]);
+    }
+}
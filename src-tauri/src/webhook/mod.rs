@@ -0,0 +1,226 @@
+mod template;
+
+use reqwest::header::{HeaderName, HeaderValue};
+use reqwest::Method;
+use serde::Serialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use thiserror::Error;
+
+use crate::clipboard::{self, ClipboardError};
+use crate::commands::AIState;
+use crate::config::{ConfigError, ConfigManager, Webhook, WebhookHeader, WebhookPrivacy};
+use crate::privacy;
+use template::Escape;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Response bodies are cut to this many characters in results.
+const MAX_RESPONSE_CHARS: usize = 2000;
+const METHODS: &[&str] = &["GET", "POST", "PUT", "PATCH", "DELETE"];
+
+#[derive(Debug, Error)]
+pub enum WebhookError {
+    #[error("webhook not found: {0}")]
+    NotFound(String),
+    #[error("invalid webhook: {0}")]
+    Invalid(String),
+    #[error("not sent: the text contains {0} personal data item(s)")]
+    Blocked(usize),
+    #[error("request failed: {0}")]
+    Request(#[from] reqwest::Error),
+    #[error("server answered {0}")]
+    Status(u16),
+    #[error(transparent)]
+    Clipboard(#[from] ClipboardError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+}
+
+/// Where the text to send comes from.
+pub enum Source {
+    Text(String),
+    Clipboard,
+    /// The focused app's selection, falling back to the clipboard
+    Selection,
+}
+
+/// Outcome of one run, emitted as `webhook:result` and returned to callers.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookResult {
+    pub webhook_id: String,
+    pub name: String,
+    pub ok: bool,
+    /// HTTP status, when the server answered
+    pub status: Option<u16>,
+    /// Start of the response body
+    pub response: Option<String>,
+    pub error: Option<String>,
+    /// Personal data items replaced with placeholders before sending
+    pub masked: usize,
+}
+
+/// Check a webhook before it is saved.
+pub fn validate(webhook: &Webhook) -> Result<(), String> {
+    if webhook.id.trim().is_empty() {
+        return Err("id must not be empty".to_string());
+    }
+    if webhook.name.trim().is_empty() {
+        return Err("name must not be empty".to_string());
+    }
+    if !METHODS.contains(&webhook.method.as_str()) {
+        return Err(format!("unsupported method: {}", webhook.method));
+    }
+    let url = template::render(&webhook.url, "x", Escape::Url);
+    let parsed = reqwest::Url::parse(&url).map_err(|e| format!("invalid URL: {}", e))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("URL must start with http:// or https://".to_string());
+    }
+    for header in &webhook.headers {
+        HeaderName::from_bytes(header.name.as_bytes())
+            .map_err(|_| format!("invalid header name: {}", header.name))?;
+        HeaderValue::from_str(&header.value).map_err(|_| format!("invalid value for header {}", header.name))?;
+    }
+    Ok(())
+}
+
+/// Send text to the webhook `id` and emit the result. Failures are reported
+/// in the result, not as errors, so the UI gets one event either way.
+pub async fn run<R: Runtime>(app: &AppHandle<R>, id: &str, source: Source) -> WebhookResult {
+    let mut result = WebhookResult {
+        webhook_id: id.to_string(),
+        name: id.to_string(),
+        ok: false,
+        status: None,
+        response: None,
+        error: None,
+        masked: 0,
+    };
+    if let Err(e) = deliver(app, id, source, &mut result).await {
+        log::warn!("Webhook '{}' failed: {}", id, e);
+        result.error = Some(e.to_string());
+    }
+
+    if let Err(e) = app.emit("webhook:result", &result) {
+        log::error!("Failed to emit webhook:result event: {}", e);
+    }
+    result
+}
+
+async fn deliver<R: Runtime>(
+    app: &AppHandle<R>,
+    id: &str,
+    source: Source,
+    result: &mut WebhookResult,
+) -> Result<(), WebhookError> {
+    let webhook = app
+        .state::<ConfigManager>()
+        .get_webhook(id)?
+        .ok_or_else(|| WebhookError::NotFound(id.to_string()))?;
+    result.name = webhook.name.clone();
+    validate(&webhook).map_err(WebhookError::Invalid)?;
+
+    let text = match source {
+        Source::Text(text) => text,
+        source => {
+            let app = app.clone();
+            tauri::async_runtime::spawn_blocking(move || match source {
+                Source::Selection => clipboard::capture_selection(&app),
+                _ => clipboard::read_clipboard(&app)?.text.ok_or(ClipboardError::Empty),
+            })
+            .await
+            .map_err(|e| ClipboardError::Unavailable(e.to_string()))??
+        }
+    };
+
+    let payload = match webhook.privacy {
+        WebhookPrivacy::Off => text,
+        WebhookPrivacy::Mask => {
+            let masked = privacy::mask_pii(&text);
+            result.masked = masked.scan_result.items.len();
+            masked.masked
+        }
+        WebhookPrivacy::Block => {
+            let scan = privacy::scan_pii(&text);
+            if scan.has_pii {
+                return Err(WebhookError::Blocked(scan.items.len()));
+            }
+            text
+        }
+    };
+
+    let client = app.state::<Arc<AIState>>().http_client();
+    let response = request(&client, &webhook, &payload)?.send().await?;
+    let status = response.status();
+    result.status = Some(status.as_u16());
+    // The body is informational; a failure to read it doesn't fail the run
+    let body = response.text().await.unwrap_or_default();
+    result.response = Some(body.chars().take(MAX_RESPONSE_CHARS).collect());
+    if !status.is_success() {
+        return Err(WebhookError::Status(status.as_u16()));
+    }
+    result.ok = true;
+    Ok(())
+}
+
+fn request(client: &reqwest::Client, webhook: &Webhook, text: &str) -> Result<reqwest::RequestBuilder, WebhookError> {
+    let method = Method::from_bytes(webhook.method.as_bytes()).map_err(|e| WebhookError::Invalid(e.to_string()))?;
+    let url = template::render(&webhook.url, text, Escape::Url);
+    let content_type = webhook
+        .headers
+        .iter()
+        .find(|h| h.name.eq_ignore_ascii_case("content-type"))
+        .map(|h| h.value.as_str());
+
+    let mut request = client.request(method.clone(), url).timeout(REQUEST_TIMEOUT);
+    for WebhookHeader { name, value } in &webhook.headers {
+        request = request.header(name.as_str(), value.as_str());
+    }
+    if method != Method::GET {
+        let escape = Escape::for_content_type(content_type);
+        request = request.body(template::render(&webhook.body_template, text, escape));
+    }
+    Ok(request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn webhook() -> Webhook {
+        Webhook {
+            id: "tickets".to_string(),
+            name: "Tickets".to_string(),
+            url: "https://tickets.example.com/api/issues".to_string(),
+            method: "POST".to_string(),
+            headers: vec![WebhookHeader {
+                name: "Content-Type".to_string(),
+                value: "application/json".to_string(),
+            }],
+            body_template: r#"{"title": "{{clipboard}}"}"#.to_string(),
+            privacy: WebhookPrivacy::Mask,
+            hotkey: None,
+            capture_selection: true,
+        }
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&webhook()).is_ok());
+        assert!(validate(&Webhook { url: "https://x.test/?q={{clipboard}}".to_string(), ..webhook() }).is_ok());
+        assert!(validate(&Webhook { url: "file:///etc/passwd".to_string(), ..webhook() }).is_err());
+        assert!(validate(&Webhook { method: "TRACE".to_string(), ..webhook() }).is_err());
+        let bad_header = vec![WebhookHeader { name: "Bad Name".to_string(), value: "x".to_string() }];
+        assert!(validate(&Webhook { headers: bad_header, ..webhook() }).is_err());
+    }
+
+    #[test]
+    fn test_request_renders_body() {
+        let request = request(&reqwest::Client::new(), &webhook(), "line \"1\"").unwrap().build().unwrap();
+        assert_eq!(request.method(), Method::POST);
+        assert_eq!(request.headers()["content-type"], "application/json");
+        let body = request.body().and_then(|b| b.as_bytes()).unwrap();
+        assert_eq!(body, br#"{"title": "line \"1\""}"#);
+    }
+}
@@ -0,0 +1,81 @@
+/// Placeholder replaced with the text in a webhook's URL and body.
+pub const PLACEHOLDER: &str = "{{clipboard}}";
+
+/// How the text is escaped where the placeholder appears.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Escape {
+    None,
+    /// Inside a JSON string literal: `{"text": "{{clipboard}}"}`
+    Json,
+    /// Percent-encoded, for URLs and form bodies
+    Url,
+}
+
+impl Escape {
+    /// Escaping for a body sent with `content_type`.
+    pub fn for_content_type(content_type: Option<&str>) -> Self {
+        let content_type = content_type.unwrap_or("").to_ascii_lowercase();
+        if content_type.contains("json") {
+            Escape::Json
+        } else if content_type.starts_with("application/x-www-form-urlencoded") {
+            Escape::Url
+        } else {
+            Escape::None
+        }
+    }
+}
+
+pub fn render(template: &str, text: &str, escape: Escape) -> String {
+    if !template.contains(PLACEHOLDER) {
+        return template.to_string();
+    }
+    let value = match escape {
+        Escape::None => text.to_string(),
+        Escape::Json => {
+            let quoted = serde_json::Value::String(text.to_string()).to_string();
+            quoted[1..quoted.len() - 1].to_string()
+        }
+        Escape::Url => percent_encode(text),
+    };
+    template.replace(PLACEHOLDER, &value)
+}
+
+fn percent_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let text = "say \"hi\"\nnow & then";
+        assert_eq!(
+            render(r#"{"text": "{{clipboard}}"}"#, text, Escape::Json),
+            r#"{"text": "say \"hi\"\nnow & then"}"#
+        );
+        assert_eq!(
+            render("https://x.test/?q={{clipboard}}", "a b&c/é", Escape::Url),
+            "https://x.test/?q=a%20b%26c%2F%C3%A9"
+        );
+        assert_eq!(render("{{clipboard}}|{{clipboard}}", text, Escape::None), format!("{}|{}", text, text));
+        assert_eq!(render("static", text, Escape::Json), "static");
+    }
+
+    #[test]
+    fn test_escape_for_content_type() {
+        assert_eq!(Escape::for_content_type(Some("application/json; charset=utf-8")), Escape::Json);
+        assert_eq!(Escape::for_content_type(Some("Application/Vnd.Api+JSON")), Escape::Json);
+        assert_eq!(Escape::for_content_type(Some("application/x-www-form-urlencoded")), Escape::Url);
+        assert_eq!(Escape::for_content_type(Some("text/plain")), Escape::None);
+        assert_eq!(Escape::for_content_type(None), Escape::None);
+    }
+}
@@ -0,0 +1,339 @@
+//! Workflows: steps stored as JSON (`Workflow`) and run in order on one
+//! text, e.g. capture the selection → mask personal data → AI prompt →
+//! rule → paste → notify. Each step reports `workflow:progress`; the run
+//! ends with one `workflow:result`.
+
+mod notify;
+
+use serde::Serialize;
+use tauri::{AppHandle, Emitter, Manager, Runtime};
+use thiserror::Error;
+
+use crate::clipboard::{self, ClipboardError, TransformHistory};
+use crate::config::{ConfigError, ConfigManager, StepAction, UsageMetric, Workflow};
+use crate::hotkey::{self, HeadlessError};
+use crate::privacy::{self, MaskMapping};
+use crate::regex::{self, RegexError};
+use crate::webhook::{self, Source};
+
+const MAX_STEPS: usize = 32;
+/// Characters of the text shown by a notification without its own message
+const NOTIFY_PREVIEW_CHARS: usize = 200;
+
+#[derive(Debug, Error)]
+pub enum WorkflowError {
+    #[error("workflow not found: {0}")]
+    NotFound(String),
+    #[error("invalid workflow: {0}")]
+    Invalid(String),
+    #[error(transparent)]
+    Clipboard(#[from] ClipboardError),
+    #[error(transparent)]
+    Config(#[from] ConfigError),
+    #[error(transparent)]
+    Rule(#[from] RegexError),
+    #[error(transparent)]
+    Ai(#[from] HeadlessError),
+    #[error("webhook failed: {0}")]
+    Webhook(String),
+    #[error("notification failed: {0}")]
+    Notify(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum StepStatus {
+    Running,
+    Done,
+    /// Failed; the run stops unless the step may fail
+    Failed,
+}
+
+/// Payload of `workflow:progress`, sent as each step starts and ends.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowProgress {
+    pub workflow_id: String,
+    /// Index of the step, from 0
+    pub step: usize,
+    pub total: usize,
+    pub status: StepStatus,
+    pub error: Option<String>,
+}
+
+/// Outcome of one run, emitted as `workflow:result` and returned to callers.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkflowResult {
+    pub workflow_id: String,
+    pub name: String,
+    pub ok: bool,
+    /// The step the run stopped at
+    pub failed_step: Option<usize>,
+    pub error: Option<String>,
+    /// The text after the last step, personal data restored
+    pub output: Option<String>,
+}
+
+/// Check a workflow before it is saved. Whether its rules and webhooks
+/// exist is up to the caller.
+pub fn validate(workflow: &Workflow) -> Result<(), String> {
+    if workflow.id.trim().is_empty() {
+        return Err("id must not be empty".to_string());
+    }
+    if workflow.name.trim().is_empty() {
+        return Err("name must not be empty".to_string());
+    }
+    if workflow.steps.is_empty() {
+        return Err("a workflow needs at least one step".to_string());
+    }
+    if workflow.steps.len() > MAX_STEPS {
+        return Err(format!("at most {} steps", MAX_STEPS));
+    }
+    for (i, step) in workflow.steps.iter().enumerate() {
+        let empty = match &step.action {
+            StepAction::AiPrompt { prompt } => prompt.trim().is_empty(),
+            StepAction::ApplyRule { rule_id } => rule_id.trim().is_empty(),
+            StepAction::Webhook { id } => id.trim().is_empty(),
+            _ => false,
+        };
+        if empty {
+            return Err(format!("step {} is missing its prompt, rule or webhook", i + 1));
+        }
+    }
+    Ok(())
+}
+
+/// The text carried from step to step.
+struct Carried {
+    /// As the run started, for the transform history
+    input: String,
+    text: String,
+    /// Set once a step masked personal data
+    mapping: Option<MaskMapping>,
+}
+
+impl Carried {
+    fn restored(&self) -> String {
+        match &self.mapping {
+            Some(mapping) => privacy::restore_pii(&self.text, mapping),
+            None => self.text.clone(),
+        }
+    }
+}
+
+/// Run workflow `id` on `text`, or on the clipboard when the panel didn't
+/// pass any, and emit the result. Failures are reported in the result.
+pub async fn run<R: Runtime>(app: &AppHandle<R>, id: &str, text: Option<String>) -> WorkflowResult {
+    let mut result = WorkflowResult {
+        workflow_id: id.to_string(),
+        name: id.to_string(),
+        ok: false,
+        failed_step: None,
+        error: None,
+        output: None,
+    };
+    match execute(app, id, text, &mut result).await {
+        Ok(output) => {
+            result.ok = true;
+            result.output = Some(output);
+        }
+        Err(e) => {
+            log::warn!("Workflow '{}' failed: {}", id, e);
+            result.error = Some(e.to_string());
+        }
+    }
+
+    if let Err(e) = app.emit("workflow:result", &result) {
+        log::error!("Failed to emit workflow:result event: {}", e);
+    }
+    result
+}
+
+async fn execute<R: Runtime>(
+    app: &AppHandle<R>,
+    id: &str,
+    text: Option<String>,
+    result: &mut WorkflowResult,
+) -> Result<String, WorkflowError> {
+    let workflow = app
+        .state::<ConfigManager>()
+        .get_workflow(id)?
+        .ok_or_else(|| WorkflowError::NotFound(id.to_string()))?;
+    result.name = workflow.name.clone();
+    validate(&workflow).map_err(WorkflowError::Invalid)?;
+
+    // A workflow that starts by reading its own input doesn't need the clipboard
+    let reads_input = matches!(
+        workflow.steps.first().map(|s| &s.action),
+        Some(StepAction::CaptureSelection | StepAction::ReadClipboard)
+    );
+    let input = match text {
+        Some(text) => text,
+        None if reads_input => String::new(),
+        None => read_clipboard(app).await?,
+    };
+    let mut carried = Carried {
+        text: input.clone(),
+        input,
+        mapping: None,
+    };
+
+    let total = workflow.steps.len();
+    for (i, step) in workflow.steps.iter().enumerate() {
+        progress(app, id, i, total, StepStatus::Running, None);
+        match run_step(app, &workflow, &step.action, &mut carried).await {
+            Ok(()) => progress(app, id, i, total, StepStatus::Done, None),
+            Err(e) => {
+                progress(app, id, i, total, StepStatus::Failed, Some(e.to_string()));
+                if !step.continue_on_error {
+                    result.failed_step = Some(i);
+                    return Err(e);
+                }
+                log::warn!("Workflow '{}' step {} failed, continuing: {}", id, i + 1, e);
+            }
+        }
+    }
+    Ok(carried.restored())
+}
+
+fn progress<R: Runtime>(
+    app: &AppHandle<R>,
+    id: &str,
+    step: usize,
+    total: usize,
+    status: StepStatus,
+    error: Option<String>,
+) {
+    let payload = WorkflowProgress {
+        workflow_id: id.to_string(),
+        step,
+        total,
+        status,
+        error,
+    };
+    if let Err(e) = app.emit("workflow:progress", payload) {
+        log::error!("Failed to emit workflow:progress event: {}", e);
+    }
+}
+
+/// Clipboard and notification calls block, so they run off the async runtime.
+async fn blocking<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, WorkflowError> + Send + 'static,
+) -> Result<T, WorkflowError> {
+    tauri::async_runtime::spawn_blocking(f)
+        .await
+        .map_err(|e| ClipboardError::Unavailable(e.to_string()))?
+}
+
+async fn read_clipboard<R: Runtime>(app: &AppHandle<R>) -> Result<String, WorkflowError> {
+    let app = app.clone();
+    blocking(move || Ok(clipboard::read_clipboard(&app)?.text.ok_or(ClipboardError::Empty)?)).await
+}
+
+async fn run_step<R: Runtime>(
+    app: &AppHandle<R>,
+    workflow: &Workflow,
+    action: &StepAction,
+    carried: &mut Carried,
+) -> Result<(), WorkflowError> {
+    let config = app.state::<ConfigManager>();
+    match action {
+        StepAction::CaptureSelection => {
+            let app = app.clone();
+            carried.text = blocking(move || Ok(clipboard::capture_selection(&app)?)).await?;
+        }
+        StepAction::ReadClipboard => carried.text = read_clipboard(app).await?,
+        StepAction::MaskPii => {
+            let mapping = carried.mapping.get_or_insert_with(MaskMapping::default);
+            let before = mapping.mappings.len();
+            carried.text = privacy::mask_pii_with(&carried.text, mapping);
+            config.track(UsageMetric::PiiMasked, (mapping.mappings.len() - before) as u64);
+        }
+        StepAction::AiPrompt { prompt } => {
+            carried.text = hotkey::run_prompt(app, prompt, &carried.text).await?;
+        }
+        StepAction::ApplyRule { rule_id } => {
+            let custom_rules = config.get_custom_rules()?;
+            carried.text = regex::apply_pipeline(&carried.text, std::slice::from_ref(rule_id), &custom_rules)?;
+            config.track(UsageMetric::Transform, 1);
+        }
+        StepAction::Webhook { id } => {
+            let sent = webhook::run(app, id, Source::Text(carried.text.clone())).await;
+            if !sent.ok {
+                return Err(WorkflowError::Webhook(sent.error.unwrap_or_default()));
+            }
+        }
+        StepAction::WriteClipboard | StepAction::Paste => {
+            let app = app.clone();
+            let (input, output) = (carried.input.clone(), carried.restored());
+            let label = workflow.name.clone();
+            let paste = *action == StepAction::Paste;
+            blocking(move || {
+                clipboard::write_clipboard(&app, &output)?;
+                app.state::<TransformHistory>().push(input, output, label);
+                if paste {
+                    clipboard::simulate_paste()?;
+                }
+                Ok(())
+            })
+            .await?;
+        }
+        StepAction::Notify { message } => {
+            let title = workflow.name.clone();
+            let body = match message.as_deref().map(str::trim) {
+                Some(message) if !message.is_empty() => message.to_string(),
+                _ => clipboard::preview(carried.restored().trim(), NOTIFY_PREVIEW_CHARS),
+            };
+            blocking(move || notify::show(&title, &body)).await?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn workflow(json: &str) -> Workflow {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_parse_steps() {
+        let flow = workflow(
+            r#"{"id":"reply","name":"Reply","steps":[
+                {"type":"captureSelection"},
+                {"type":"maskPii"},
+                {"type":"aiPrompt","prompt":"Draft a polite reply"},
+                {"type":"applyRule","ruleId":"trim_whitespace","continueOnError":true},
+                {"type":"paste"},
+                {"type":"notify"}
+            ]}"#,
+        );
+        assert_eq!(flow.steps.len(), 6);
+        assert_eq!(
+            flow.steps[3].action,
+            StepAction::ApplyRule {
+                rule_id: "trim_whitespace".to_string()
+            }
+        );
+        assert!(flow.steps[3].continue_on_error);
+        assert!(!flow.steps[0].continue_on_error);
+        assert_eq!(flow.steps[5].action, StepAction::Notify { message: None });
+        assert!(validate(&flow).is_ok());
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(validate(&workflow(r#"{"id":"a","name":"A","steps":[]}"#)).is_err());
+        assert!(validate(&workflow(
+            r#"{"id":"a","name":"A","steps":[{"type":"aiPrompt","prompt":" "}]}"#
+        ))
+        .is_err());
+        assert!(serde_json::from_str::<Workflow>(
+            r#"{"id":"a","name":"A","steps":[{"type":"launchRockets"}]}"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,67 @@
+use std::process::{Command, Stdio};
+
+use super::WorkflowError;
+
+/// Show a desktop notification with the system's own tool; the text is
+/// passed as arguments or environment, never spliced into a script.
+pub fn show(title: &str, body: &str) -> Result<(), WorkflowError> {
+    let mut command = platform::command(title, body);
+    let program = command.get_program().to_string_lossy().into_owned();
+    let status = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map_err(|e| WorkflowError::Notify(format!("{}: {}", program, e)))?;
+    if !status.success() {
+        return Err(WorkflowError::Notify(format!("{} exited with {}", program, status)));
+    }
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+mod platform {
+    use super::*;
+
+    pub fn command(title: &str, body: &str) -> Command {
+        let mut command = Command::new("osascript");
+        command
+            .args(["-e", "on run argv"])
+            .args(["-e", "display notification (item 2 of argv) with title (item 1 of argv)"])
+            .args(["-e", "end run"])
+            .args([title, body]);
+        command
+    }
+}
+
+#[cfg(windows)]
+mod platform {
+    use super::*;
+
+    /// A balloon tip from a short-lived tray icon, which needs nothing installed.
+    const SCRIPT: &str = "Add-Type -AssemblyName System.Windows.Forms; \
+        $n = New-Object System.Windows.Forms.NotifyIcon; \
+        $n.Icon = [System.Drawing.SystemIcons]::Information; $n.Visible = $true; \
+        $n.ShowBalloonTip(5000, $env:FLOWPASTE_TITLE, $env:FLOWPASTE_BODY, 'Info'); \
+        Start-Sleep -Seconds 6; $n.Dispose()";
+
+    pub fn command(title: &str, body: &str) -> Command {
+        let mut command = Command::new("powershell");
+        command
+            .args(["-NoProfile", "-NonInteractive", "-WindowStyle", "Hidden", "-Command", SCRIPT])
+            .env("FLOWPASTE_TITLE", title)
+            .env("FLOWPASTE_BODY", body);
+        command
+    }
+}
+
+#[cfg(not(any(target_os = "macos", windows)))]
+mod platform {
+    use super::*;
+
+    pub fn command(title: &str, body: &str) -> Command {
+        let mut command = Command::new("notify-send");
+        command.args(["--app-name=FlowPaste", "--", title, body]);
+        command
+    }
+}
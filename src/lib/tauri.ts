@@ -1,5 +1,6 @@
 import { invoke } from '@tauri-apps/api/core'
 import { listen, type UnlistenFn } from '@tauri-apps/api/event'
+import { getCurrentWebviewWindow } from '@tauri-apps/api/webviewWindow'
 import { createModuleLogger } from '@/utils/logger'
 import { IPC_EVENTS } from '@/types'
 import type {
@@ -13,9 +14,41 @@ import type {
   MaskMapping,
   ModelInfo,
   AIConfig,
+  AIRequestStatus,
   AppConfig,
+  LogEntry,
+  LogLevel,
   Rule,
+  ShieldDecision,
+  UsagePeriod,
+  UsageStats,
+  DashboardStats,
+  CustomIntent,
+  IntentResult,
   ActionChip,
+  ContentType,
+  Webhook,
+  WebhookResult,
+  WorkflowProgress,
+  WorkflowResult,
+  TranscriptionProgress,
+  TranslationResult,
+  UrlMetadata,
+  ShareInfo,
+  IngestedFile,
+  PaletteAction,
+  HashAlgorithm,
+  Evaluation,
+  SyncKind,
+  SyncReport,
+  InlineResult,
+  ResultOutcome,
+  Generator,
+  Snippet,
+  SnippetField,
+  SnippetQuery,
+  RenderedSnippet,
+  TagCount,
 } from '@/types'
 
 const log = createModuleLogger('tauri')
@@ -43,6 +76,19 @@ export async function listenToEvent<T>(
   })
 }
 
+/** Like `listenToEvent`, but only for events sent to this window */
+export async function listenToWindowEvent<T>(
+  event: string,
+  handler: (payload: T) => void
+): Promise<UnlistenFn> {
+  const webview = getCurrentWebviewWindow()
+  log.info(`Listening to event: ${event} (${webview.label})`)
+  return webview.listen<T>(event, (e) => {
+    log.debug(`Received event: ${event}`, e.payload)
+    handler(e.payload)
+  })
+}
+
 export const tauriEvents = {
   onPanelToggle: (handler: (payload: PanelTogglePayload) => void) =>
     listenToEvent<PanelTogglePayload>(IPC_EVENTS.PANEL_TOGGLE, handler),
@@ -58,6 +104,30 @@ export const tauriEvents = {
 
   onClipboardChanged: (handler: (payload: ClipboardChangedPayload) => void) =>
     listenToEvent<ClipboardChangedPayload>(IPC_EVENTS.CLIPBOARD_CHANGED, handler),
+
+  onConfigChanged: (handler: (payload: AppConfig) => void) =>
+    listenToEvent<AppConfig>(IPC_EVENTS.CONFIG_CHANGED, handler),
+
+  onWebhookResult: (handler: (payload: WebhookResult) => void) =>
+    listenToEvent<WebhookResult>(IPC_EVENTS.WEBHOOK_RESULT, handler),
+
+  onWorkflowProgress: (handler: (payload: WorkflowProgress) => void) =>
+    listenToEvent<WorkflowProgress>(IPC_EVENTS.WORKFLOW_PROGRESS, handler),
+
+  onTranscriptionProgress: (handler: (payload: TranscriptionProgress) => void) =>
+    listenToEvent<TranscriptionProgress>(IPC_EVENTS.TRANSCRIPTION_PROGRESS, handler),
+
+  onModelsRefreshed: (handler: (payload: ModelInfo[]) => void) =>
+    listenToEvent<ModelInfo[]>(IPC_EVENTS.MODELS_REFRESHED, handler),
+
+  onSyncCompleted: (handler: (payload: SyncReport) => void) =>
+    listenToEvent<SyncReport>(IPC_EVENTS.SYNC_COMPLETED, handler),
+
+  onResultShow: (handler: (payload: InlineResult) => void) =>
+    listenToWindowEvent<InlineResult>(IPC_EVENTS.RESULT_SHOW, handler),
+
+  onResultClosed: (handler: (payload: ResultOutcome) => void) =>
+    listenToWindowEvent<ResultOutcome>(IPC_EVENTS.RESULT_CLOSED, handler),
 }
 
 export interface MaskResult {
@@ -85,20 +155,88 @@ export const commands = {
     invokeCommand<boolean>('check_ollama_health', { baseUrl }),
   sendAiRequest: (
     prompt: string,
+    content: string | null,
     config: AIConfig,
     requestId: string,
-    usePrivacyShield: boolean
+    shieldDecision?: ShieldDecision
   ) =>
-    invokeCommand<void>('send_ai_request', {
+    invokeCommand<AIRequestStatus>('send_ai_request', {
       prompt,
+      content,
       config,
       requestId,
-      usePrivacyShield,
+      shieldDecision,
     }),
   cancelAiRequest: (requestId: string) =>
     invokeCommand<void>('cancel_ai_request', { requestId }),
   detectContentIntent: (text: string) =>
-    invokeCommand<ActionChip[]>('detect_content_intent', { text }),
+    invokeCommand<IntentResult>('detect_content_intent', { text }),
+  getCustomIntents: () => invokeCommand<CustomIntent[]>('get_custom_intents'),
+  saveCustomIntent: (intent: CustomIntent) => invokeCommand<void>('save_custom_intent', { intent }),
+  deleteCustomIntent: (id: string) => invokeCommand<void>('delete_custom_intent', { id }),
+  recordChipUsage: (chip: ActionChip, contentType: ContentType) =>
+    invokeCommand<void>('record_chip_usage', { chip, contentType }),
+  saveDecodedFile: (text: string) => invokeCommand<string>('save_decoded_file', { text }),
+  makeMarkdownLinks: (text: string) => invokeCommand<string>('make_markdown_links', { text }),
+  makeCitation: (text: string) => invokeCommand<string>('make_citation', { text }),
+  fetchUrlMetadata: (url: string) => invokeCommand<UrlMetadata>('fetch_url_metadata', { url }),
+  getWebhooks: () => invokeCommand<Webhook[]>('get_webhooks'),
+  saveWebhook: (webhook: Webhook) => invokeCommand<void>('save_webhook', { webhook }),
+  deleteWebhook: (id: string) => invokeCommand<void>('delete_webhook', { id }),
+  runWebhook: (id: string, text: string) => invokeCommand<WebhookResult>('run_webhook', { id, text }),
+  runWorkflow: (id: string, text?: string) => invokeCommand<WorkflowResult>('run_workflow', { id, text }),
+  /** Select a screen region and copy its text; `null` if cancelled */
+  captureScreenText: () => invokeCommand<string | null>('capture_screen_text'),
+  copyQrCode: (text: string) => invokeCommand<void>('copy_qr_code', { text }),
+  computeHash: (text: string, algorithm: HashAlgorithm) =>
+    invokeCommand<string>('compute_hash', { text, algorithm }),
+  /** A Conventional Commits message for a diff, or for the staged changes of a repository path */
+  generateCommitMessage: (text: string) => invokeCommand<string>('generate_commit_message', { text }),
+  /** Arithmetic, percentages, or a unit or currency conversion such as `$25 in EUR` */
+  evaluate: (expression: string) => invokeCommand<Evaluation>('evaluate', { expression }),
+  /** Sync rules, snippets and prompts with the saved sync folder */
+  syncFolder: () => invokeCommand<SyncReport>('sync_folder'),
+  resolveSyncConflict: (kind: SyncKind, id: string, keep: 'local' | 'remote') =>
+    invokeCommand<void>('resolve_sync_conflict', { kind, id, keep }),
+  /** Show a result near the cursor; this window gets `result:closed` after */
+  showInlineResult: (result: InlineResult) =>
+    invokeCommand<void>('show_inline_result', { result }),
+  getInlineResult: () => invokeCommand<InlineResult | null>('get_inline_result'),
+  acceptInlineResult: () => invokeCommand<void>('accept_inline_result'),
+  dismissInlineResult: () => invokeCommand<void>('dismiss_inline_result'),
+  closeResultWindow: () => invokeCommand<void>('close_result_window'),
+  generateToClipboard: (generator: Generator) =>
+    invokeCommand<string>('generate_to_clipboard', { generator }),
+  /** Saves to Downloads; resolves to the file's path */
+  saveQrCode: (text: string) => invokeCommand<string>('save_qr_code', { text }),
+  /** Reads the clipboard image; its text replaces it on the clipboard */
+  decodeQrCode: () => invokeCommand<string>('decode_qr_code'),
+  /** `text` is the audio file's path; the transcript also replaces the clipboard */
+  transcribeAudio: (text: string) => invokeCommand<string>('transcribe_audio', { text }),
+  translateText: (text: string) => invokeCommand<TranslationResult>('translate_text', { text }),
+  /** Appends to the notes file; resolves to its path */
+  saveToNotes: (text: string) => invokeCommand<string>('save_to_notes', { text }),
+  readDroppedFile: (path: string) => invokeCommand<IngestedFile>('read_dropped_file', { path }),
+  searchActions: (query: string) => invokeCommand<PaletteAction[]>('search_actions', { query }),
+  shareText: (text: string) => invokeCommand<ShareInfo>('share_text', { text }),
+  /** `index` 0 is the latest copy */
+  shareRecentClipboard: (index: number) => invokeCommand<ShareInfo>('share_recent_clipboard', { index }),
+  stopLanShare: () => invokeCommand<void>('stop_lan_share'),
+  /** Registers the browser extension host; resolves to the manifest path */
+  installNativeHost: (browser: 'chrome' | 'firefox', extensionIds: string) =>
+    invokeCommand<string>('install_native_host', { browser, extensionIds }),
+
+  // Snippet commands
+  getSnippets: () => invokeCommand<Snippet[]>('get_snippets'),
+  saveSnippet: (snippet: Snippet) => invokeCommand<void>('save_snippet', { snippet }),
+  deleteSnippet: (id: string) => invokeCommand<void>('delete_snippet', { id }),
+  searchSnippets: (query: SnippetQuery) => invokeCommand<Snippet[]>('search_snippets', { query }),
+  getSnippetFolders: () => invokeCommand<string[]>('get_snippet_folders'),
+  getSnippetTags: () => invokeCommand<TagCount[]>('get_snippet_tags'),
+  renameSnippetFolder: (from: string, to: string) => invokeCommand<void>('rename_snippet_folder', { from, to }),
+  getSnippetFields: (template: string) => invokeCommand<SnippetField[]>('get_snippet_fields', { template }),
+  insertSnippet: (id: string, values: Record<string, string> = {}) =>
+    invokeCommand<RenderedSnippet>('insert_snippet', { id, values }),
 
   // Config commands
   getConfig: () => invokeCommand<AppConfig>('get_config'),
@@ -106,6 +244,18 @@ export const commands = {
   getApiKey: (provider: string) => invokeCommand<string | null>('get_api_key', { provider }),
   setApiKey: (provider: string, key: string) =>
     invokeCommand<void>('set_api_key', { provider, key }),
+  /** Bearer token of the local HTTP API; created on first call */
+  getHttpApiToken: () => invokeCommand<string>('get_http_api_token'),
+  regenerateHttpApiToken: () => invokeCommand<string>('regenerate_http_api_token'),
+  /** Frontend preferences keyed `namespace.name`; `null` deletes */
+  getSetting: <T = unknown>(key: string) => invokeCommand<T | null>('get_setting', { key }),
+  setSetting: (key: string, value: unknown) => invokeCommand<void>('set_setting', { key, value }),
+
+  // Diagnostics
+  getRecentLogs: (lines: number, level?: LogLevel) =>
+    invokeCommand<LogEntry[]>('get_recent_logs', { lines, level }),
+  getUsageStats: (period: UsagePeriod) => invokeCommand<UsageStats>('get_usage_stats', { period }),
+  getDashboardStats: (range: UsagePeriod) => invokeCommand<DashboardStats>('get_dashboard_stats', { range }),
 
   // Regex commands
   getBuiltinRules: () => invokeCommand<Rule[]>('get_builtin_rules'),
@@ -1,14 +1,23 @@
 import { defineStore } from 'pinia'
 import { ref, computed } from 'vue'
 import { commands } from '@/lib/tauri'
+import { DEFAULT_PROVIDERS } from '@/types'
 import type {
   ActionChip,
+  ContentType,
   PrivacyStatus,
   PanelMode,
   MaskMapping,
   AIConfig,
   AppConfig,
   ClipboardContent,
+  PIIScanResult,
+  ShieldDecision,
+  TranscriptionProgress,
+  AIProgress,
+  WorkflowProgress,
+  Generator,
+  ShareInfo,
 } from '@/types'
 
 export const useAppStore = defineStore('app', () => {
@@ -18,6 +27,9 @@ export const useAppStore = defineStore('app', () => {
 
   // Content
   const clipboardContent = ref<ClipboardContent | null>(null)
+  // Set while the panel works on text sent by the browser extension, so
+  // refocusing doesn't swap it for the clipboard
+  const externalText = ref(false)
   const processedContent = ref('')
   const streamingContent = ref('')
 
@@ -25,10 +37,19 @@ export const useAppStore = defineStore('app', () => {
   const actionChips = ref<ActionChip[]>([])
   const selectedChipIndex = ref(0)
   const currentRequestId = ref<string | null>(null)
+  // Language of the clipboard text, for prompt templates
+  const detectedLanguage = ref<string | null>(null)
+  // Picks are recorded against it so chips adapt to what the user uses
+  const detectedContentType = ref<ContentType>('unknown')
 
   // Privacy
   const privacyStatus = ref<PrivacyStatus>({ type: 'local' })
   const maskedMapping = ref<MaskMapping>({ mappings: {} })
+  // Set while the backend waits for a mask/send answer about high-severity PII
+  const pendingShield = ref<{ prompt: string; aiConfig?: Partial<AIConfig>; scan: PIIScanResult } | null>(null)
+
+  // LAN share of the shown text, until fetched or expired
+  const shareInfo = ref<ShareInfo | null>(null)
 
   // Config
   const config = ref<AppConfig | null>(null)
@@ -40,6 +61,9 @@ export const useAppStore = defineStore('app', () => {
   const clipboardText = computed(() => clipboardContent.value?.text ?? '')
   const hasContent = computed(() => !!clipboardContent.value?.text)
   const isProcessing = computed(() => panelMode.value === 'processing')
+  const activeProvider = computed(() =>
+    config.value?.providers.find((p) => p.provider === config.value?.aiProvider) ?? DEFAULT_PROVIDERS[0]
+  )
 
   // Panel Actions
   async function showPanel() {
@@ -57,14 +81,49 @@ export const useAppStore = defineStore('app', () => {
     errorMessage.value = null
   }
 
+  /** Show `text` from the browser extension as if it had been copied. */
+  async function showText(text: string) {
+    isVisible.value = true
+    externalText.value = true
+    clipboardContent.value = { kind: 'text', text }
+    panelMode.value = 'preview'
+    await scanPrivacy(text)
+  }
+
+  /** Show the text of a file dropped onto the panel. */
+  async function openDroppedFile(path: string) {
+    try {
+      const file = await commands.readDroppedFile(path)
+      await showText(file.text)
+      if (file.truncated) {
+        errorMessage.value = `${file.name} 较大，只读取了开头部分`
+      }
+    } catch (e) {
+      setError(`Failed to read file: ${e}`)
+    }
+  }
+
   // Clipboard Actions
   async function refreshClipboard() {
+    externalText.value = false
     try {
       const content = await commands.readClipboard()
       clipboardContent.value = content
       if (content.text) {
         panelMode.value = 'preview'
         await scanPrivacy(content.text)
+      } else if (content.kind === 'image') {
+        // Nothing to detect in an image except a QR code it may hold
+        panelMode.value = 'preview'
+        actionChips.value = [
+          {
+            id: 'image.decode_qr',
+            actionId: 'decode_qr',
+            label: '识别二维码',
+            actionType: 'Command',
+            payload: 'decode_qr_code',
+          },
+        ]
       }
     } catch (e) {
       setError(`Failed to read clipboard: ${e}`)
@@ -81,14 +140,25 @@ export const useAppStore = defineStore('app', () => {
       }
 
       // Generate action chips based on content
-      const chips = await commands.detectContentIntent(text)
-      actionChips.value = chips
+      const intent = await commands.detectContentIntent(text)
+      actionChips.value = intent.chips
+      detectedLanguage.value = intent.language
+      detectedContentType.value = intent.contentType
     } catch (e) {
       privacyStatus.value = { type: 'local' }
       actionChips.value = []
     }
   }
 
+  async function recordChipUsage(chip: ActionChip) {
+    try {
+      await commands.recordChipUsage(chip, detectedContentType.value)
+    } catch (e) {
+      // Ranking is a nicety; a failed write must not block the action
+      console.warn('Failed to record chip usage:', e)
+    }
+  }
+
   // Rule Processing
   async function processWithRule(ruleId: string) {
     if (!clipboardText.value) return
@@ -102,8 +172,189 @@ export const useAppStore = defineStore('app', () => {
     }
   }
 
+  // Backend commands offered as chips. `save_decoded_file` and
+  // `save_qr_code` write to Downloads and their result is the file's path;
+  // the `hash_*` ones also copy the digest
+  const chipCommands: Record<string, (text: string) => Promise<string>> = {
+    save_decoded_file: commands.saveDecodedFile,
+    make_markdown_links: commands.makeMarkdownLinks,
+    make_citation: commands.makeCitation,
+    save_qr_code: commands.saveQrCode,
+    translate_text: async (text) => (await commands.translateText(text)).text,
+    hash_md5: (text) => commands.computeHash(text, 'md5'),
+    hash_sha1: (text) => commands.computeHash(text, 'sha1'),
+    hash_sha256: (text) => commands.computeHash(text, 'sha256'),
+    hash_crc32: (text) => commands.computeHash(text, 'crc32'),
+    generate_commit_message: commands.generateCommitMessage,
+  }
+
+  // Commands that replace the clipboard with something other than text, so
+  // there is no result to preview and paste
+  const clipboardCommands: Record<string, (text: string) => Promise<void>> = {
+    copy_qr_code: commands.copyQrCode,
+  }
+
+  // Commands that read text out of what was copied (an image, an audio
+  // file) and put it on the clipboard, where it goes through the usual
+  // detection. An image has no text, so these get ''
+  const readCommands: Record<string, (text: string) => Promise<string>> = {
+    decode_qr_code: () => commands.decodeQrCode(),
+    transcribe_audio: commands.transcribeAudio,
+  }
+
+  // Resolves to true when the command is done with the panel
+  async function runCommand(name: string): Promise<boolean> {
+    if (name === 'save_to_notes') return saveToNotes()
+    const read = readCommands[name]
+    if (read) {
+      startProcessing()
+      try {
+        await read(clipboardText.value)
+        await refreshClipboard()
+      } catch (e) {
+        setError(`Command failed: ${e}`)
+      }
+      return false
+    }
+    if (!clipboardText.value) return false
+
+    const toClipboard = clipboardCommands[name]
+    if (toClipboard) {
+      try {
+        await toClipboard(clipboardText.value)
+        hidePanel()
+        reset()
+        return true
+      } catch (e) {
+        setError(`Command failed: ${e}`)
+        return false
+      }
+    }
+
+    const command = chipCommands[name]
+    if (!command) {
+      setError(`Unknown command: ${name}`)
+      return false
+    }
+    startProcessing()
+    try {
+      const result = await command(clipboardText.value)
+      finishProcessing(result)
+    } catch (e) {
+      setError(`Command failed: ${e}`)
+    }
+    return false
+  }
+
+  // Saves the result when there is one, otherwise what was copied, and
+  // closes the panel. Resolves to true when saved
+  async function saveToNotes(): Promise<boolean> {
+    const text = panelMode.value === 'result' ? processedContent.value : clipboardText.value
+    if (!text) return false
+    try {
+      await commands.saveToNotes(text)
+      hidePanel()
+      reset()
+      return true
+    } catch (e) {
+      setError(`Save to notes failed: ${e}`)
+      return false
+    }
+  }
+
+  async function shareToLan() {
+    const text = panelMode.value === 'result' ? processedContent.value : clipboardText.value
+    if (!text) return
+    try {
+      shareInfo.value = await commands.shareText(text)
+    } catch (e) {
+      setError(`Share failed: ${e}`)
+    }
+  }
+
+  async function stopShare() {
+    shareInfo.value = null
+    try {
+      await commands.stopLanShare()
+    } catch (e) {
+      setError(`Failed to stop sharing: ${e}`)
+    }
+  }
+
+  // Shown in place of streamed text while a long file is transcribed
+  function handleTranscriptionProgress(progress: TranscriptionProgress) {
+    if (panelMode.value === 'processing') {
+      streamingContent.value = `转写中… ${progress.percent}%`
+    }
+  }
+
+  // Sending doesn't change the text, so the panel goes back to the preview.
+  // Failures come back in the result rather than as a rejection
+  async function runWebhook(id: string) {
+    if (!clipboardText.value) return
+
+    startProcessing()
+    try {
+      const result = await commands.runWebhook(id, clipboardText.value)
+      if (result.ok) {
+        panelMode.value = 'preview'
+      } else {
+        setError(`${result.name}: ${result.error}`)
+      }
+    } catch (e) {
+      setError(`Webhook failed: ${e}`)
+    }
+  }
+
+  // The workflow's last text becomes the result, ready to paste
+  async function runWorkflow(id: string) {
+    if (!clipboardText.value) return
+
+    startProcessing()
+    try {
+      const result = await commands.runWorkflow(id, clipboardText.value)
+      if (result.ok) {
+        finishProcessing(result.output ?? '')
+      } else {
+        const step = result.failedStep === null ? '' : ` (step ${result.failedStep + 1})`
+        setError(`${result.name}${step}: ${result.error}`)
+      }
+    } catch (e) {
+      setError(`Workflow failed: ${e}`)
+    }
+  }
+
+  // The generated value is already on the clipboard; show it as the result
+  async function generate(kind: Generator['kind']) {
+    const generator: Generator =
+      kind === 'password' ? { kind, ...config.value?.passwordPolicy } : { kind }
+    startProcessing()
+    try {
+      finishProcessing(await commands.generateToClipboard(generator))
+    } catch (e) {
+      setError(`Generate failed: ${e}`)
+    }
+  }
+
+  // Steps run in the backend; show which one is running
+  function handleWorkflowProgress(progress: WorkflowProgress) {
+    if (panelMode.value === 'processing' && progress.status === 'running') {
+      streamingContent.value = `工作流运行中… ${progress.step + 1}/${progress.total}`
+    }
+  }
+
+  // The panel hides while the region is selected and reopens (via
+  // `panel:show`) on the recognized text, which is on the clipboard by then
+  async function captureScreenText() {
+    try {
+      await commands.captureScreenText()
+    } catch (e) {
+      setError(`Screenshot OCR failed: ${e}`)
+    }
+  }
+
   // AI Processing
-  async function processWithAI(prompt: string, aiConfig?: Partial<AIConfig>) {
+  async function processWithAI(prompt: string, aiConfig?: Partial<AIConfig>, shieldDecision?: ShieldDecision) {
     if (!clipboardText.value) return
 
     startProcessing()
@@ -111,12 +362,13 @@ export const useAppStore = defineStore('app', () => {
     currentRequestId.value = requestId
 
     try {
+      const provider = activeProvider.value
       const fullConfig: AIConfig = {
-        provider: config.value?.aiProvider ?? 'Ollama',
-        baseUrl: config.value?.aiProvider === 'OpenAI'
-          ? config.value?.openaiBaseUrl ?? 'https://api.openai.com/v1'
-          : config.value?.ollamaBaseUrl ?? 'http://localhost:11434',
-        model: config.value?.modelName ?? 'llama3.2',
+        provider: provider.provider,
+        baseUrl: provider.baseUrl,
+        model: provider.model,
+        timeoutSecs: provider.timeoutSecs,
+        contextTokens: provider.contextTokens,
         maxTokens: 2048,
         temperature: 0.7,
         ...aiConfig,
@@ -130,16 +382,28 @@ export const useAppStore = defineStore('app', () => {
         }
       }
 
-      const usePrivacyShield = privacyStatus.value.type === 'cloud-masked'
-      const fullPrompt = `${prompt}\n\nContent:\n${clipboardText.value}`
-
-      await commands.sendAiRequest(fullPrompt, fullConfig, requestId, usePrivacyShield)
+      // Masking, and chunking of text too long for the model, are decided by the backend
+      const status = await commands.sendAiRequest(prompt, clipboardText.value, fullConfig, requestId, shieldDecision)
+      if (status.status === 'needsConfirmation') {
+        pendingShield.value = { prompt, aiConfig, scan: status.scan }
+        panelMode.value = 'preview'
+        currentRequestId.value = null
+      }
     } catch (e) {
       setError(`AI request failed: ${e}`)
       currentRequestId.value = null
     }
   }
 
+  /** Answer a pending shield question; `null` drops the request. */
+  function resolveShield(decision: ShieldDecision | null) {
+    const pending = pendingShield.value
+    pendingShield.value = null
+    if (pending && decision) {
+      processWithAI(pending.prompt, pending.aiConfig, decision)
+    }
+  }
+
   async function cancelAI() {
     if (currentRequestId.value) {
       try {
@@ -211,12 +475,16 @@ export const useAppStore = defineStore('app', () => {
 
   function reset() {
     clipboardContent.value = null
+    externalText.value = false
     processedContent.value = ''
     streamingContent.value = ''
     actionChips.value = []
+    detectedLanguage.value = null
+    detectedContentType.value = 'unknown'
     selectedChipIndex.value = 0
     privacyStatus.value = { type: 'local' }
     maskedMapping.value = { mappings: {} }
+    pendingShield.value = null
     errorMessage.value = null
     panelMode.value = 'idle'
     currentRequestId.value = null
@@ -233,6 +501,12 @@ export const useAppStore = defineStore('app', () => {
     }
   }
 
+  // Long text is answered in several requests, with no streamed text to show
+  function handleAIProgress(progress: AIProgress) {
+    if (progress.requestId !== currentRequestId.value) return
+    streamingContent.value = `分段处理中… ${progress.done}/${progress.total}`
+  }
+
   function handleAIError(payload: { code: string; message: string; requestId: string }) {
     if (payload.requestId !== currentRequestId.value) return
     setError(`AI Error [${payload.code}]: ${payload.message}`)
@@ -243,28 +517,48 @@ export const useAppStore = defineStore('app', () => {
     isVisible,
     panelMode,
     clipboardContent,
+    externalText,
+    shareInfo,
     clipboardText,
     processedContent,
     streamingContent,
     actionChips,
     selectedChipIndex,
+    detectedLanguage,
+    detectedContentType,
     privacyStatus,
     maskedMapping,
+    pendingShield,
     currentRequestId,
     errorMessage,
     config,
     // Computed
     hasContent,
     isProcessing,
+    activeProvider,
     // Panel Actions
     showPanel,
+    showText,
+    openDroppedFile,
     hidePanel,
     // Clipboard Actions
     refreshClipboard,
     // Processing Actions
     processWithRule,
+    runCommand,
+    runWebhook,
+    runWorkflow,
+    handleWorkflowProgress,
+    generate,
+    saveToNotes,
+    shareToLan,
+    stopShare,
+    handleTranscriptionProgress,
+    captureScreenText,
+    recordChipUsage,
     processWithAI,
     cancelAI,
+    resolveShield,
     confirmPaste,
     // Config Actions
     loadConfig,
@@ -277,6 +571,7 @@ export const useAppStore = defineStore('app', () => {
     reset,
     // Event Handlers
     handleAIChunk,
+    handleAIProgress,
     handleAIError,
   }
 })
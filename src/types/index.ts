@@ -1,16 +1,140 @@
 // ============================================================
 // Action Chip Types
 // ============================================================
-export type ActionType = 'LocalRule' | 'AIPrompt'
+export type ActionType = 'LocalRule' | 'AIPrompt' | 'Command' | 'Webhook' | 'Workflow'
 
 export interface ActionChip {
+  /** The same on every detection, e.g. `json.format_json` */
   id: string
+  /** Stable across detections and UI languages, e.g. `format_json` or `custom:<id>` */
+  actionId: string
+  /** Unique per detection */
+  handle?: string | null
   label: string
   actionType: ActionType
   payload: string
   shortcut?: string
 }
 
+export type ContentType =
+  | 'json'
+  | 'color'
+  | 'timestamp'
+  | 'identifier'
+  | 'math'
+  | 'url'
+  | 'path'
+  | 'base64'
+  | 'sql'
+  | 'diff'
+  | 'email'
+  | 'log'
+  | 'code'
+  | 'table'
+  | 'list'
+  | 'prose'
+  | 'unknown'
+
+export interface TableInfo {
+  format: 'csv' | 'tsv' | 'markdown'
+  hasHeader: boolean
+  columnCount: number
+  /** Header cells when `hasHeader`, otherwise empty */
+  columns: string[]
+  /** Rows below the header */
+  rowCount: number
+}
+
+export type IntentMatcher =
+  | { kind: 'regex'; pattern: string }
+  | { kind: 'contentType'; contentType: ContentType }
+
+export interface ContentCandidate {
+  contentType: ContentType
+  /** 0-1 */
+  confidence: number
+}
+
+export interface IntentResult {
+  /** Most likely type, same as `candidates[0]` */
+  contentType: ContentType
+  /** Every type that matched, most likely first */
+  candidates: ContentCandidate[]
+  /** 'zh', 'en', ... for prose-like text */
+  language: string | null
+  table: TableInfo | null
+  chips: ActionChip[]
+}
+
+/** User-defined chip, offered ahead of the built-in ones when it matches */
+export interface CustomIntent {
+  id: string
+  matcher: IntentMatcher
+  label: string
+  actionType: ActionType
+  /** Rule id for `LocalRule`, prompt for `AIPrompt`, command name for `Command`, webhook id for `Webhook` */
+  payload: string
+  enabled?: boolean
+}
+
+/** `off` sends the text as is, `mask` replaces personal data, `block` refuses to send */
+export type WebhookPrivacy = 'off' | 'mask' | 'block'
+
+/** An HTTP request run from a chip or hotkey; `{{clipboard}}` in the URL or body is the text */
+export interface Webhook {
+  id: string
+  name: string
+  url: string
+  method: 'GET' | 'POST' | 'PUT' | 'PATCH' | 'DELETE'
+  headers: { name: string; value: string }[]
+  bodyTemplate: string
+  privacy: WebhookPrivacy
+  hotkey?: string | null
+  /** Send the focused app's selection when run from the hotkey */
+  captureSelection?: boolean
+}
+
+// ============================================================
+// Snippet Types
+// ============================================================
+/** Reusable text; `{date}`, `{clipboard}`, `{cursor}` and `{input:name=default}` are filled in */
+export interface Snippet {
+  id: string
+  name: string
+  template: string
+  description?: string
+  /** `/`-separated path such as `Work/Mail`; empty for the top level */
+  folder?: string
+  tags?: string[]
+  /** Typed text replaced with the snippet, e.g. `;sig` */
+  abbreviation?: string | null
+}
+
+export interface SnippetField {
+  name: string
+  default: string | null
+}
+
+export interface RenderedSnippet {
+  text: string
+  /** Character offset of `{cursor}` */
+  cursor: number | null
+  missing: string[]
+}
+
+export interface SnippetQuery {
+  text?: string
+  /** This folder and its subfolders */
+  folder?: string | null
+  /** Snippets must carry all of them */
+  tags?: string[]
+}
+
+export interface TagCount {
+  tag: string
+  count: number
+}
+
 // ============================================================
 // Clipboard Types
 // ============================================================
@@ -52,6 +176,13 @@ export interface PIIScanResult {
   items: PIIItem[]
 }
 
+/** Answer to a `needsConfirmation` from `send_ai_request` */
+export type ShieldDecision = 'mask' | 'send'
+
+export type AIRequestStatus =
+  | { status: 'started' }
+  | { status: 'needsConfirmation'; scan: PIIScanResult }
+
 export interface MaskMapping {
   mappings: Record<string, string>
 }
@@ -64,6 +195,17 @@ export type PanelMode = 'idle' | 'preview' | 'processing' | 'result'
 // ============================================================
 // Rule Types
 // ============================================================
+export type RuleKind = 'regex' | 'transform' | 'callback' | 'script' | 'rhai'
+
+export type RuleCategory = 'cleanup' | 'format' | 'extract' | 'dev' | 'cjk' | 'custom'
+
+export interface RuleFlags {
+  caseInsensitive: boolean
+  multiline: boolean
+  dotMatchesNewLine: boolean
+  unicode: boolean
+}
+
 export interface Rule {
   id: string
   name: string
@@ -71,6 +213,12 @@ export interface Rule {
   pattern: string
   replacement: string
   isBuiltin: boolean
+  kind?: RuleKind
+  flags?: RuleFlags
+  category?: RuleCategory
+  tags?: string[]
+  sortOrder?: number
+  shortcut?: string | null
 }
 
 // ============================================================
@@ -85,6 +233,9 @@ export interface AIConfig {
   apiKey?: string
   maxTokens: number
   temperature: number
+  timeoutSecs?: number
+  /** Context window; longer content is processed in chunks */
+  contextTokens?: number
 }
 
 export interface ModelInfo {
@@ -102,8 +253,19 @@ export const IPC_EVENTS = {
   AI_CHUNK: 'ai:chunk',
   AI_DONE: 'ai:done',
   AI_ERROR: 'ai:error',
+  AI_PROGRESS: 'ai:progress',
   AI_CANCEL: 'ai:cancel',
   CLIPBOARD_CHANGED: 'clipboard:changed',
+  HOTKEY_TRIGGERED: 'hotkey:triggered',
+  CONFIG_CHANGED: 'config:changed',
+  WEBHOOK_RESULT: 'webhook:result',
+  WORKFLOW_PROGRESS: 'workflow:progress',
+  WORKFLOW_RESULT: 'workflow:result',
+  TRANSCRIPTION_PROGRESS: 'transcription:progress',
+  MODELS_REFRESHED: 'models:refreshed',
+  SYNC_COMPLETED: 'sync:completed',
+  RESULT_SHOW: 'result:show',
+  RESULT_CLOSED: 'result:closed',
 } as const
 
 export type IPCEventName = typeof IPC_EVENTS[keyof typeof IPC_EVENTS]
@@ -138,6 +300,136 @@ export interface ClipboardChangedPayload {
   hasText: boolean
 }
 
+export interface WebhookResult {
+  webhookId: string
+  name: string
+  ok: boolean
+  /** HTTP status, when the server answered */
+  status: number | null
+  /** Start of the response body */
+  response: string | null
+  error: string | null
+  /** Personal data items masked before sending */
+  masked: number
+}
+
+export interface WorkflowResult {
+  workflowId: string
+  name: string
+  ok: boolean
+  /** Index of the step the run stopped at */
+  failedStep: number | null
+  error: string | null
+  /** The text after the last step */
+  output: string | null
+}
+
+export interface WorkflowProgress {
+  workflowId: string
+  step: number
+  total: number
+  status: 'running' | 'done' | 'failed'
+  error: string | null
+}
+
+export interface TranslationResult {
+  text: string
+  service: string
+  /** Source language as detected by the service */
+  detectedLanguage: string | null
+  targetLanguage: string
+}
+
+/** Text read from a file dropped onto the panel */
+export interface IngestedFile {
+  name: string
+  format: 'text' | 'pdf' | 'docx'
+  text: string
+  /** Detected encoding of a text file */
+  encoding: string | null
+  /** Only the first part of the text was kept */
+  truncated: boolean
+}
+
+/** Length and characters of generated passwords; one of each chosen kind is included */
+export interface PasswordPolicy {
+  /** 4-256 */
+  length: number
+  lowercase: boolean
+  uppercase: boolean
+  digits: boolean
+  symbols: boolean
+  /** Leave out `0 O 1 l I |` */
+  excludeAmbiguous: boolean
+}
+
+/** What `generate_to_clipboard` makes */
+export type Generator =
+  | ({ kind: 'password' } & Partial<PasswordPolicy>)
+  | { kind: 'uuid' }
+  | { kind: 'loremIpsum'; paragraphs?: number }
+  | { kind: 'cardNumber'; brand?: 'visa' | 'mastercard' | 'amex' }
+
+/** Answer from `evaluate` */
+export interface Evaluation {
+  value: number
+  /** Unit or currency code of a conversion's result */
+  unit: string | null
+  /** `value` formatted for display, with its unit */
+  text: string
+}
+
+/** Digest kinds `compute_hash` offers */
+export type HashAlgorithm = 'md5' | 'sha1' | 'sha256' | 'crc32'
+
+/** A command palette entry from `search_actions`, best match first */
+export interface PaletteAction {
+  kind: 'rule' | 'prompt' | 'snippet' | 'generator' | 'history'
+  /** Rule or snippet id, the prompt of a prompt, the generator's `kind`, or the clipboard history position (0 = latest) */
+  target: string
+  title: string
+  subtitle: string
+  score: number
+}
+
+/** Where another device on the network fetches a shared item, once */
+export interface ShareInfo {
+  url: string
+  /** 6 digits the other device must enter */
+  code: string
+  /** RFC 3339 */
+  expiresAt: string
+}
+
+export interface UrlMetadata {
+  /** Address after redirects */
+  url: string
+  title: string | null
+  description: string | null
+  image: string | null
+  siteName: string | null
+}
+
+export interface TranscriptionProgress {
+  /** The audio file being transcribed */
+  path: string
+  percent: number
+}
+
+/** Requests of a chunked AI answer; `total` can grow while merging */
+export interface AIProgress {
+  done: number
+  total: number
+  requestId: string
+}
+
+export interface HotkeyTriggeredPayload {
+  binding: string
+  action: { type: string; [key: string]: unknown }
+  timestamp: number
+  frontmostApp: string | null
+}
+
 // ============================================================
 // Error Types
 // ============================================================
@@ -153,13 +445,212 @@ export interface ErrorResponse {
 // ============================================================
 // Config Types
 // ============================================================
+export interface ProviderConfig {
+  provider: AIProvider
+  baseUrl: string
+  model: string
+  privacyShield: boolean
+  timeoutSecs: number
+  contextTokens: number
+}
+
+export const DEFAULT_PROVIDERS: ProviderConfig[] = [
+  { provider: 'Ollama', baseUrl: 'http://localhost:11434', model: 'llama3.2', privacyShield: true, timeoutSecs: 120, contextTokens: 8192 },
+  { provider: 'OpenAI', baseUrl: 'https://api.openai.com/v1', model: 'gpt-4o-mini', privacyShield: true, timeoutSecs: 120, contextTokens: 8192 },
+]
+
 export interface AppConfig {
   hotkey: string
+  /** Active entry in `providers` */
   aiProvider: AIProvider
-  ollamaBaseUrl: string
-  openaiBaseUrl: string
-  modelName: string
+  providers: ProviderConfig[]
   theme: 'system' | 'light' | 'dark'
+  autoPaste?: boolean
+  panelMode?: 'toggle' | 'hold'
+  showAtCursor?: boolean
+  hotkeyDebounceMs?: number
+  autostart?: boolean
+  proxyUrl?: string
+  noProxy?: string
+  caCertPath?: string
+  locale?: 'zh-CN' | 'en' | 'ja'
+  alwaysOnTop?: boolean
+  visibleOnAllWorkspaces?: boolean
+  /** 0.2 to 1 */
+  windowOpacity?: number
+  hideOnBlur?: boolean
+  logLevel?: LogLevel
+  logToFile?: boolean
+  /** Count feature usage locally; never sent anywhere */
+  usageStats?: boolean
+  /** Privacy shield for cloud providers */
+  shieldPolicy?: 'always' | 'ask' | 'never'
+  chipLayout?: ChipLayout
+  /** Ask the local Ollama model about content the heuristics can't place */
+  aiIntent?: boolean
+  /** Empty uses the Ollama provider's model */
+  aiIntentModel?: string
+  /** 100-5000; heuristic chips are shown if the model takes longer */
+  aiIntentBudgetMs?: number
+  /** Local MCP server exposing clipboard and rule tools */
+  mcpServer?: boolean
+  /** 1024-65535; served at http://127.0.0.1:<port>/mcp */
+  mcpPort?: number
+  /** Token-protected REST/WebSocket API for scripts */
+  httpApi?: boolean
+  /** 1024-65535; served at http://127.0.0.1:<port>/v1 */
+  httpApiPort?: number
+  /** Replace typed snippet abbreviations with the snippet text */
+  textExpansion?: boolean
+  /** Comma-separated apps where abbreviations are left alone */
+  textExpansionExcludedApps?: string
+  /** Tesseract executable used to read text from screenshots */
+  ocrEngine?: string
+  /** Tesseract languages joined with `+`, e.g. `chi_sim+eng` */
+  ocrLanguages?: string
+  /** ZBar `zbarimg` executable used to read QR codes from images */
+  qrDecoder?: string
+  /** Poppler `pdftotext` executable used to read dropped PDFs */
+  pdfExtractor?: string
+  /** whisper.cpp CLI used to transcribe audio files */
+  whisperCommand?: string
+  /** whisper.cpp model file (`ggml-*.bin`); transcription is off until set */
+  whisperModel?: string
+  /** Spoken language code, or `auto` */
+  whisperLanguage?: string
+  /** `DeepL` or `Google` for the translate chips; empty uses the AI provider */
+  translationService?: '' | 'DeepL' | 'Google'
+  /** Markdown file, or folder such as an Obsidian vault, results are saved to */
+  notesPath?: string
+  /** File name inside `notesPath` when it is a folder, e.g. `{date}.md` */
+  notesFileTemplate?: string
+  /** Answer the companion browser extension through native messaging */
+  browserExtension?: boolean
+  /** Comma-separated extension ids allowed to connect */
+  browserExtensionIds?: string
+  passwordPolicy?: PasswordPolicy
+  /** Clear the clipboard once it has held personal data this long; 0 = never, at most 1440 */
+  clearPiiAfterMinutes?: number
+  /** Empty the clipboard and transform history each night */
+  purgeHistoryNightly?: boolean
+  /** Ask Ollama for its model list every hour */
+  refreshModelsHourly?: boolean
+  /** Folder rules, snippets and prompts are synced with; empty for no sync */
+  syncFolder?: string
+  /** Rewrite a copied link without its tracking parameters */
+  cleanCopiedUrls?: boolean
+}
+
+/** What folder sync keeps in step */
+export type SyncKind = 'rule' | 'snippet' | 'prompt'
+
+/** An item changed both here and in the sync folder since the last sync */
+export interface SyncConflict {
+  kind: SyncKind
+  id: string
+  name: string
+  /** `false` when it was deleted here */
+  localExists: boolean
+  /** `false` when its file was deleted */
+  remoteExists: boolean
+}
+
+/** Outcome of a folder sync */
+export interface SyncReport {
+  pushed: number
+  pulled: number
+  deleted: number
+  conflicts: SyncConflict[]
+  /** Files that couldn't be used, with the reason */
+  skipped: string[]
+}
+
+/** A transform or AI result shown in the small window at the cursor */
+export interface InlineResult {
+  text: string
+  title?: string | null
+  /** Label of the window that asked; set by the backend */
+  source?: string | null
+}
+
+/** Sent to the window that showed an inline result once it closes */
+export interface ResultOutcome {
+  accepted: boolean
+  text: string
+}
+
+/** How many action chips to offer, and their order per content type */
+export interface ChipLayout {
+  /** 1-9 */
+  maxChips: number
+  types: ContentTypeChips[]
+}
+
+export interface ContentTypeChips {
+  contentType: ContentType
+  /** Replaces `ChipLayout.maxChips` for this type */
+  maxChips?: number | null
+  /** Built-in chip action ids to offer first, in this order */
+  order?: string[]
+  /** Built-in chip action ids never to offer */
+  hidden?: string[]
+}
+
+export type LogLevel = 'error' | 'warn' | 'info' | 'debug' | 'trace'
+
+export type UsagePeriod = 'day' | 'week' | 'month' | 'all'
+
+export interface UsageCounts {
+  transforms: number
+  aiRequests: number
+  piiMasked: number
+  hotkeyActivations: number
+}
+
+export interface UsageStats {
+  period: UsagePeriod
+  totals: UsageCounts
+  /** Days with any activity, oldest first */
+  days: (UsageCounts & { day: string })[]
+}
+
+export interface DashboardStats {
+  period: UsagePeriod
+  totals: UsageCounts
+  /** Every day of the period, oldest first, including days without activity */
+  days: (UsageCounts & { day: string })[]
+  /** Most used rules, at most 10 */
+  topRules: { id: string; name: string; count: number }[]
+  /** Estimated tokens per provider and model, most first */
+  aiTokens: {
+    provider: 'Ollama' | 'OpenAI'
+    model: string
+    requests: number
+    promptTokens: number
+    completionTokens: number
+  }[]
+}
+
+export interface LogEntry {
+  timestamp: string
+  level: string
+  target: string
+  message: string
+}
+
+export interface Profile {
+  name: string
+  config: AppConfig
+  actionHotkeys: { action: string; hotkey: string }[]
+}
+
+export interface ConfigBackup {
+  id: string
+  /** RFC 3339, UTC */
+  createdAt: string
+  /** What was about to change: 'settings', 'import', 'profile', 'migration', 'restore' */
+  reason: string
+  sizeBytes: number
 }
 
 // ============================================================